@@ -1,19 +1,32 @@
 use tokio::sync::mpsc;
 
 use crate::app_core::{reduce, DomainEvent};
-use crate::domain::{AppSettings, AppState, FlatpakSteamAvailability, Profile, ProfileId, Route};
+use crate::debounce::CheckDebouncer;
+use crate::domain::{
+    AppSettings, AppState, CacheWarmup, FlatpakSteamAvailability, Profile, ProfileId, ProfileTree,
+    RepoAuthKind, Route, ServerStatus, UpdateStatus,
+};
 use crate::launcher::LauncherImpl;
-use crate::orchestrator::PipelineOrchestrator;
+use crate::orchestrator::{profile_engine, PipelineOrchestrator};
 use crate::persistence::FilePersistence;
-use crate::pipeline::{PipelineRunEvent, PipelineRunId, StepStatus};
+use crate::pipeline::{PipelineRunEvent, PipelineRunId, PipelineStep, StepStatus};
 use crate::ports::SyncPipelinePort;
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use fleet_persistence::{DbState, FleetDataStore, RedbFleetDataStore};
+use fleet_scanner::ScanStats;
 
-use fleet_core::repo::Repository;
+use fleet_core::repo::{Repository, Server};
 use fleet_core::SyncPlan;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fs;
 use std::path::Path;
 use std::process::Command;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// How often the dashboard re-queries a profile's game servers.
+pub const SERVER_STATUS_REFRESH: Duration = Duration::from_secs(30);
 
 pub struct FleetApplication {
     pub state: AppState,
@@ -23,6 +36,15 @@ pub struct FleetApplication {
     launcher: LauncherImpl,
     orchestrator: PipelineOrchestrator,
     auto_local_checked: HashSet<ProfileId>,
+    server_queries: HashMap<ProfileId, Instant>,
+    /// Remote checks in flight, so their outcome can be recorded in `update_status`.
+    remote_checks: HashMap<PipelineRunId, ProfileId>,
+    /// Profiles still waiting for their turn in a "check all" run.
+    check_queue: VecDeque<ProfileId>,
+    /// Live-update subscriptions by profile, with the URL each one listens on.
+    live_watches: HashMap<ProfileId, (String, tokio::task::JoinHandle<()>)>,
+    /// Automatic checks waiting out `recheck_quiet_period_secs`.
+    check_debounce: CheckDebouncer,
 
     msg_rx: mpsc::Receiver<DomainEvent>,
     msg_tx: mpsc::Sender<DomainEvent>,
@@ -36,10 +58,9 @@ impl Default for FleetApplication {
 impl FleetApplication {
     pub fn new() -> Self {
         let (msg_tx, msg_rx) = mpsc::channel(100);
-        let client =
-            fleet_infra::net::default_http_client().unwrap_or_else(|_| reqwest::Client::new());
-        let engine = fleet_pipeline::default_engine(client);
-        let engine = std::sync::Arc::new(engine);
+        let engine = std::sync::Arc::new(
+            build_engine(&AppSettings::default()).expect("the default network settings are valid"),
+        );
 
         Self {
             state: AppState::default(),
@@ -47,6 +68,11 @@ impl FleetApplication {
             launcher: LauncherImpl::new(),
             orchestrator: PipelineOrchestrator::new(engine, msg_tx.clone()),
             auto_local_checked: HashSet::new(),
+            server_queries: HashMap::new(),
+            remote_checks: HashMap::new(),
+            check_queue: VecDeque::new(),
+            live_watches: HashMap::new(),
+            check_debounce: CheckDebouncer::default(),
             msg_rx,
             msg_tx,
         }
@@ -58,7 +84,14 @@ impl FleetApplication {
 
         self.state.profiles = profiles;
         self.state.settings = settings;
+        // Saved settings that no longer build a client keep the default engine; the error
+        // stays on screen until the settings are fixed.
+        match build_engine(&self.state.settings) {
+            Ok(engine) => self.orchestrator.set_engine(std::sync::Arc::new(engine)),
+            Err(e) => self.state.pipeline.error = Some(format!("{e:#}")),
+        }
         self.state.flatpak_steam = detect_flatpak_steam_availability();
+        self.sync_live_watches();
         self.state.selected_profile_id = self.state.profiles.first().map(|p| p.id.clone());
         self.state.route = if let Some(ref id) = self.state.selected_profile_id {
             Route::ProfileDashboard(id.clone())
@@ -67,15 +100,40 @@ impl FleetApplication {
         };
 
         if let Some(id) = self.state.selected_profile_id.clone() {
-            self.ensure_local_integrity_checked(&id);
+            // The automatic local check follows once the warm-up reports back.
+            if !(self.state.settings.warm_cache_on_start && self.warm_cache(&id)) {
+                self.ensure_local_integrity_checked(&id);
+            }
         }
         Ok(())
     }
 
+    /// Starts a background warm-up for an initialized profile; returns whether one started.
+    fn warm_cache(&mut self, profile_id: &ProfileId) -> bool {
+        if self.is_pipeline_running() {
+            return false;
+        }
+        let Ok(profile) = self.get_profile(profile_id.clone()).cloned() else {
+            return false;
+        };
+        // Warming a profile without `fleet.redb` would create an empty one and hide the
+        // cold state from the next check.
+        let root = camino::Utf8PathBuf::from(profile.local_path.clone());
+        if !matches!(RedbFleetDataStore.validate(&root), Ok(DbState::Valid)) {
+            return false;
+        }
+        self.orchestrator
+            .start_cache_warmup(profile, self.state.settings.clone())
+            .is_ok()
+    }
+
     // --- Actions ---
 
     pub fn check_for_updates(&mut self, profile_id: ProfileId) -> anyhow::Result<()> {
         let profile = self.get_profile(profile_id)?.clone();
+        let profile_id = profile.id.clone();
+        // This check covers any automatic one still waiting.
+        self.check_debounce.cancel(&profile_id);
         let run_id: PipelineRunId = uuid::Uuid::new_v4();
         self.state.pipeline.run_id = Some(run_id);
         self.state.last_plan = None;
@@ -85,12 +143,93 @@ impl FleetApplication {
             self.state.settings.clone(),
             run_id,
         ) {
-            self.state = reduce(self.state.clone(), DomainEvent::UserError(e.to_string()));
+            self.state
+                .update_status
+                .insert(profile_id, UpdateStatus::Failed(e.to_string()));
+            self.state = reduce(
+                std::mem::take(&mut self.state),
+                DomainEvent::UserError(e.to_string()),
+            );
             return Err(e);
         }
+        self.state
+            .update_status
+            .insert(profile_id.clone(), UpdateStatus::Checking);
+        self.remote_checks.insert(run_id, profile_id);
         Ok(())
     }
 
+    /// Asks for an automatic check of the profile. Requests are coalesced until
+    /// `recheck_quiet_period_secs` pass without a new one, then a single check starts once
+    /// nothing else runs; see [`CheckDebouncer`]. User-initiated checks call
+    /// [`Self::check_for_updates`] instead.
+    pub fn request_check(&mut self, profile_id: ProfileId) {
+        let quiet = self.state.settings.recheck_quiet_period();
+        self.check_debounce
+            .request(profile_id, Instant::now(), quiet);
+    }
+
+    /// How long until an automatic check is due, so the UI can wake up for it. Nothing is
+    /// due while a plan waits for review; applying or dismissing it wakes the UI anyway.
+    pub fn next_scheduled_check(&self) -> Option<Duration> {
+        if self.plan_pending() {
+            return None;
+        }
+        self.check_debounce.next_due(Instant::now())
+    }
+
+    /// Starts the automatic checks whose quiet period is over; the first one runs now and the
+    /// rest wait for it to finish. A check replaces the plan under review, so they also wait
+    /// until that plan is applied or dismissed.
+    fn run_due_checks(&mut self) {
+        if self.is_pipeline_running()
+            || !self.remote_checks.is_empty()
+            || !self.check_queue.is_empty()
+            || self.plan_pending()
+        {
+            return;
+        }
+        let now = Instant::now();
+        let mut due = self
+            .check_debounce
+            .take_due(now, self.state.settings.recheck_quiet_period())
+            .into_iter();
+        if let Some(profile_id) = due.next() {
+            // A check that fails to start is recorded as failed, so this does not repeat.
+            let _ = self.check_for_updates(profile_id);
+        }
+        for profile_id in due {
+            self.check_debounce.request(profile_id, now, Duration::ZERO);
+        }
+    }
+
+    /// Runs a remote check for every profile, one after another; each result lands in
+    /// `state.update_status`. Returns how many profiles were queued.
+    pub fn check_all_profiles(&mut self) -> anyhow::Result<usize> {
+        if self.is_pipeline_running() {
+            anyhow::bail!("Wait for the current operation to finish before checking all profiles");
+        }
+        self.check_queue = self.state.profiles.iter().map(|p| p.id.clone()).collect();
+        for id in &self.check_queue {
+            self.state
+                .update_status
+                .insert(id.clone(), UpdateStatus::Checking);
+        }
+        let queued = self.check_queue.len();
+        self.start_next_queued_check();
+        Ok(queued)
+    }
+
+    /// Starts the next profile of a "check all" run; a profile that fails to start is
+    /// recorded as failed and skipped.
+    fn start_next_queued_check(&mut self) {
+        while let Some(id) = self.check_queue.pop_front() {
+            if self.check_for_updates(id).is_ok() {
+                return;
+            }
+        }
+    }
+
     pub fn local_check(&mut self, profile_id: ProfileId) -> anyhow::Result<()> {
         let profile = self.get_profile(profile_id)?.clone();
         let profile_id = profile.id.clone();
@@ -103,7 +242,10 @@ impl FleetApplication {
             self.state.settings.clone(),
             run_id,
         ) {
-            self.state = reduce(self.state.clone(), DomainEvent::UserError(e.to_string()));
+            self.state = reduce(
+                std::mem::take(&mut self.state),
+                DomainEvent::UserError(e.to_string()),
+            );
             return Err(e);
         }
         self.auto_local_checked.insert(profile_id);
@@ -120,7 +262,10 @@ impl FleetApplication {
             .orchestrator
             .start_repair(profile, self.state.settings.clone(), run_id)
         {
-            self.state = reduce(self.state.clone(), DomainEvent::UserError(e.to_string()));
+            self.state = reduce(
+                std::mem::take(&mut self.state),
+                DomainEvent::UserError(e.to_string()),
+            );
             return Err(e);
         }
         Ok(())
@@ -139,13 +284,21 @@ impl FleetApplication {
     }
 
     pub fn execute_sync(&mut self, profile_id: ProfileId) -> anyhow::Result<()> {
+        if self.state.pipeline.plan_risk == Some(fleet_core::RiskLevel::Destructive) {
+            anyhow::bail!("Plan deletes most local files; confirm before syncing");
+        }
+        self.execute_confirmed_sync(profile_id)
+    }
+
+    /// Starts the reviewed plan even if it was flagged as destructive.
+    pub fn execute_confirmed_sync(&mut self, profile_id: ProfileId) -> anyhow::Result<()> {
         // Do not start sync if no plan is available
         if self.state.last_plan.is_none() {
             return Ok(());
         }
 
         let profile = self.get_profile(profile_id)?.clone();
-        let plan = self.state.last_plan.clone().unwrap();
+        let plan = Arc::unwrap_or_clone(self.state.last_plan.clone().unwrap());
 
         let run_id: PipelineRunId = uuid::Uuid::new_v4();
         self.state.pipeline.run_id = Some(run_id);
@@ -153,13 +306,115 @@ impl FleetApplication {
             self.orchestrator
                 .start_sync(profile, plan, self.state.settings.clone(), run_id)
         {
-            self.state = reduce(self.state.clone(), DomainEvent::UserError(e.to_string()));
+            self.state = reduce(
+                std::mem::take(&mut self.state),
+                DomainEvent::UserError(e.to_string()),
+            );
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Re-downloads only the files the last sync failed on, skipping a fresh check.
+    pub fn retry_failed_downloads(&mut self, profile_id: ProfileId) -> anyhow::Result<()> {
+        if self.state.pipeline.failed_downloads.is_empty() {
+            return Ok(());
+        }
+
+        let profile = self.get_profile(profile_id)?.clone();
+        let plan = fleet_pipeline::retry_plan(&self.state.pipeline.failed_downloads);
+
+        let run_id: PipelineRunId = uuid::Uuid::new_v4();
+        self.state.pipeline.run_id = Some(run_id);
+        if let Err(e) =
+            self.orchestrator
+                .start_sync(profile, plan, self.state.settings.clone(), run_id)
+        {
+            self.state = reduce(
+                std::mem::take(&mut self.state),
+                DomainEvent::UserError(e.to_string()),
+            );
             return Err(e);
         }
         Ok(())
     }
 
+    /// Includes or excludes a mod from `-mod=` on launch and join.
+    pub fn set_mod_loaded(
+        &mut self,
+        profile_id: ProfileId,
+        mod_name: &str,
+        loaded: bool,
+    ) -> anyhow::Result<()> {
+        self.update_profile(&profile_id, |p| p.set_mod_loaded(mod_name, loaded))
+    }
+
+    /// Includes or excludes a mod from future checks and syncs.
+    pub fn set_mod_synced(
+        &mut self,
+        profile_id: ProfileId,
+        mod_name: &str,
+        synced: bool,
+    ) -> anyhow::Result<()> {
+        self.update_profile(&profile_id, |p| p.set_mod_synced(mod_name, synced))
+    }
+
+    fn update_profile(
+        &mut self,
+        profile_id: &str,
+        f: impl FnOnce(&mut Profile),
+    ) -> anyhow::Result<()> {
+        let profile = self
+            .state
+            .profiles
+            .iter_mut()
+            .find(|p| p.id == profile_id)
+            .ok_or_else(|| anyhow::anyhow!("Profile not found"))?;
+        f(profile);
+        self.persistence.save_profiles(&self.state.profiles)
+    }
+
+    /// Holds the profile at the repo.json checksum from the latest fetch.
+    pub fn pin_repo_version(&mut self, profile_id: ProfileId) -> anyhow::Result<()> {
+        let Some(checksum) = self.state.repo_checksums.get(&profile_id).cloned() else {
+            anyhow::bail!("Check for updates first so there is a repository version to pin");
+        };
+        self.update_profile(&profile_id, |p| p.pinned_repo_checksum = Some(checksum))
+    }
+
+    /// Lets the profile follow the repository again.
+    pub fn unpin_repo_version(&mut self, profile_id: ProfileId) -> anyhow::Result<()> {
+        self.update_profile(&profile_id, |p| p.pinned_repo_checksum = None)
+    }
+
+    /// Cancels the running pipeline, unless it is a sync large enough that
+    /// `cancel_confirm_mb` asks for a confirmation first.
+    pub fn request_cancel(&mut self) {
+        if !self.state.pipeline.is_running() {
+            return;
+        }
+        let threshold = self
+            .state
+            .settings
+            .cancel_confirm_mb
+            .saturating_mul(1024 * 1024);
+        let sync_bytes = match &self.state.pipeline.stats.transfer {
+            Some(t) if self.state.pipeline.sync_status == StepStatus::Running => t.total_bytes,
+            _ => 0,
+        };
+        if threshold > 0 && sync_bytes >= threshold && !self.state.cancel_pending {
+            self.state.cancel_pending = true;
+        } else {
+            self.cancel_pipeline();
+        }
+    }
+
+    pub fn dismiss_cancel(&mut self) {
+        self.state.cancel_pending = false;
+    }
+
     pub fn cancel_pipeline(&mut self) {
+        self.state.cancel_pending = false;
         self.orchestrator.cancel();
         let run_id = self
             .state
@@ -192,14 +447,172 @@ impl FleetApplication {
         } else {
             discover_mod_dirs(&profile.local_path)
         };
+        let mut mods = fleet_infra::order_mods(mods, &profile.mod_order);
+        mods.retain(|m| m.file_name().is_none_or(|name| profile.loads_mod(name)));
 
-        let params = self.state.settings.launch_params.trim().to_string();
+        let params = profile
+            .launch_params(&self.state.settings.launch_params)
+            .to_string();
 
         self.launcher
-            .launch("", &params, &self.state.settings.launch_template, &mods)
+            .launch("", &params, &self.state.settings, &mods)
+    }
+
+    /// Saves `password` for the profile's join server in the OS keyring.
+    pub fn set_server_password(
+        &mut self,
+        profile_id: ProfileId,
+        password: &str,
+    ) -> anyhow::Result<()> {
+        let key = self.server_password_key(profile_id.clone())?;
+        fleet_infra::credentials::store_password(&key, password)?;
+        self.state.server_password_saved = Some((profile_id, true));
+        Ok(())
+    }
+
+    /// Forgets the keyring password so joining falls back to repo.json again.
+    pub fn clear_server_password(&mut self, profile_id: ProfileId) -> anyhow::Result<()> {
+        let key = self.server_password_key(profile_id.clone())?;
+        fleet_infra::credentials::delete_password(&key)?;
+        self.state.server_password_saved = Some((profile_id, false));
+        Ok(())
+    }
+
+    /// Whether the keyring holds a join server password for the profile. The keyring is
+    /// asked once per opened editor (see [`AppState::server_password_saved`]).
+    pub fn has_server_password(&mut self, profile_id: ProfileId) -> bool {
+        if let Some((id, saved)) = &self.state.server_password_saved {
+            if *id == profile_id {
+                return *saved;
+            }
+        }
+        let saved = self
+            .server_password_key(profile_id.clone())
+            .ok()
+            .and_then(|key| fleet_infra::credentials::load_password(&key).ok().flatten())
+            .is_some();
+        self.state.server_password_saved = Some((profile_id, saved));
+        saved
+    }
+
+    /// Saves repository credentials for a profile: the password goes to the OS keyring, the
+    /// username to the profile and any open editor draft. A blank username stores `secret`
+    /// as a bearer token.
+    pub fn set_repo_credentials(
+        &mut self,
+        profile_id: ProfileId,
+        username: &str,
+        secret: &str,
+    ) -> anyhow::Result<()> {
+        if profile_id.trim().is_empty() {
+            anyhow::bail!("Set the profile ID before saving repository credentials");
+        }
+        let username = username.trim();
+        let kind = if username.is_empty() {
+            RepoAuthKind::Bearer
+        } else {
+            RepoAuthKind::Basic {
+                username: username.to_string(),
+            }
+        };
+        let key = fleet_infra::credentials::repo_key(&profile_id);
+        fleet_infra::credentials::store_password(&key, secret)?;
+        self.update_repo_auth(&profile_id, Some(kind))
+    }
+
+    /// Forgets a profile's repository credentials so its repositories are fetched anonymously.
+    pub fn clear_repo_credentials(&mut self, profile_id: ProfileId) -> anyhow::Result<()> {
+        let key = fleet_infra::credentials::repo_key(&profile_id);
+        fleet_infra::credentials::delete_password(&key)?;
+        self.update_repo_auth(&profile_id, None)
     }
 
+    fn update_repo_auth(
+        &mut self,
+        profile_id: &str,
+        kind: Option<RepoAuthKind>,
+    ) -> anyhow::Result<()> {
+        if let Some(draft) = self
+            .state
+            .editor_draft
+            .as_mut()
+            .filter(|d| d.id == profile_id)
+        {
+            draft.repo_auth = kind.clone();
+        }
+        if let Some(profile) = self.state.profiles.iter_mut().find(|p| p.id == profile_id) {
+            profile.repo_auth = kind;
+            self.persistence.save_profiles(&self.state.profiles)?;
+        }
+        Ok(())
+    }
+
+    fn server_password_key(&self, profile_id: ProfileId) -> anyhow::Result<String> {
+        let profile = self.get_profile(profile_id)?;
+        let repo = load_local_repo_json(&profile.local_path)
+            .ok_or_else(|| anyhow::anyhow!("No repo.json found in {}", profile.local_path))?;
+        let server = repo
+            .servers
+            .first()
+            .ok_or_else(|| anyhow::anyhow!("No servers configured in repo.json"))?;
+        Ok(fleet_infra::credentials::server_key(
+            &profile.id,
+            &server.address,
+            server.port,
+        ))
+    }
+
+    /// The servers the profile's repo.json declares, in file order; empty without a repo.json.
+    pub fn profile_servers(&self, profile_id: ProfileId) -> anyhow::Result<Vec<Server>> {
+        let profile = self.get_profile(profile_id)?;
+        Ok(repo_servers(&profile.local_path))
+    }
+
+    /// Queries the servers of the open dashboard's repo.json (see [`AppState::repo_servers`])
+    /// in the background, at most once per [`SERVER_STATUS_REFRESH`]; results arrive as
+    /// [`DomainEvent::ServerStatus`].
+    pub fn refresh_server_status(&mut self, profile_id: ProfileId) {
+        if self
+            .server_queries
+            .get(&profile_id)
+            .is_some_and(|at| at.elapsed() < SERVER_STATUS_REFRESH)
+        {
+            return;
+        }
+        let servers = match &self.state.repo_servers {
+            Some((id, servers)) if *id == profile_id => servers.clone(),
+            _ => return,
+        };
+        self.server_queries.insert(profile_id, Instant::now());
+        let Ok(rt) = crate::async_runtime::runtime() else {
+            return;
+        };
+        for server in servers {
+            let tx = self.msg_tx.clone();
+            rt.spawn(async move {
+                use fleet_infra::a2s;
+                let port = a2s::query_port(server.port);
+                // Unreachable, silent and misbehaving servers all show as offline.
+                let status = a2s::query_info(&server.address, port, a2s::DEFAULT_QUERY_TIMEOUT)
+                    .await
+                    .map_or(ServerStatus::Offline, ServerStatus::Online);
+                let address = format!("{}:{}", server.address, server.port);
+                let _ = tx.send(DomainEvent::ServerStatus { address, status }).await;
+            });
+        }
+    }
+
+    /// Joins the first server in repo.json.
     pub fn join_profile(&mut self, profile_id: ProfileId) -> anyhow::Result<()> {
+        self.join_profile_server(profile_id, 0)
+    }
+
+    /// Launches the profile and connects to `repo.json`'s `servers[server_index]`.
+    pub fn join_profile_server(
+        &mut self,
+        profile_id: ProfileId,
+        server_index: usize,
+    ) -> anyhow::Result<()> {
         let profile = self.get_profile(profile_id)?;
 
         let repo = load_local_repo_json(&profile.local_path)
@@ -211,16 +624,33 @@ impl FleetApplication {
         } else {
             discover_mod_dirs(&profile.local_path)
         };
+        let mut mods = fleet_infra::order_mods(mods, &profile.mod_order);
+        mods.retain(|m| m.file_name().is_none_or(|name| profile.loads_mod(name)));
 
-        let server = repo
-            .servers
-            .first()
-            .ok_or_else(|| anyhow::anyhow!("No servers configured in repo.json"))?;
-
-        let mut params = self.state.settings.launch_params.trim().to_string();
+        let server = match repo.servers.get(server_index) {
+            Some(server) => server,
+            None if repo.servers.is_empty() => {
+                anyhow::bail!("No servers configured in repo.json")
+            }
+            None => anyhow::bail!(
+                "repo.json lists {} server(s); there is no server #{}",
+                repo.servers.len(),
+                server_index + 1
+            ),
+        };
+        // A password saved in the OS keyring wins over the (public) one in repo.json.
+        let key = fleet_infra::credentials::server_key(&profile.id, &server.address, server.port);
+        let password = fleet_infra::credentials::load_password(&key)
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| server.password.clone());
+
+        let mut params = profile
+            .launch_params(&self.state.settings.launch_params)
+            .to_string();
         let mut join_args = format!("-connect={} -port={}", server.address, server.port);
-        if !server.password.trim().is_empty() {
-            join_args.push_str(&format!(" -password={}", server.password));
+        if !password.trim().is_empty() {
+            join_args.push_str(&format!(" -password={password}"));
         }
 
         if params.is_empty() {
@@ -230,20 +660,245 @@ impl FleetApplication {
         }
 
         self.launcher
-            .launch("", &params, &self.state.settings.launch_template, &mods)
+            .launch("", &params, &self.state.settings, &mods)
     }
 
     // --- State Management ---
 
-    /// Call this from your UI loop/tick to process async messages
-    pub fn handle_pipeline_events(&mut self) {
+    /// Call this from your UI loop/tick to process async messages.
+    /// Returns whether any event was applied, i.e. whether the UI needs redrawing.
+    pub fn handle_pipeline_events(&mut self) -> bool {
+        // Launch on the call after the reducer asked for it, so the UI has shown it first.
+        if let Some(profile_id) = self.state.auto_launch.take() {
+            if let Err(e) = self.launch_profile(profile_id) {
+                self.state = reduce(
+                    std::mem::take(&mut self.state),
+                    DomainEvent::UserError(format!("Auto-launch failed: {e}")),
+                );
+            }
+        }
+        let mut applied = false;
+        let mut check_finished = false;
+        let mut tree_stale = false;
         while let Ok(ev) = self.msg_rx.try_recv() {
-            if let DomainEvent::PipelineEvent { run_id, .. } = &ev {
+            if let DomainEvent::PipelineEvent { run_id, ev } = &ev {
+                check_finished |= self.track_remote_check(*run_id, ev);
                 if self.state.pipeline.run_id != Some(*run_id) {
                     continue;
                 }
             }
-            self.state = reduce(self.state.clone(), ev);
+            let scan_finished = matches!(
+                &ev,
+                DomainEvent::PipelineEvent {
+                    ev: PipelineRunEvent::StepChanged {
+                        step: PipelineStep::Scan,
+                        status: StepStatus::Succeeded,
+                        ..
+                    },
+                    ..
+                }
+            );
+            let run_recorded = matches!(
+                &ev,
+                DomainEvent::PipelineEvent {
+                    ev: PipelineRunEvent::RepoChecksum { .. }
+                        | PipelineRunEvent::SyncSummary { .. },
+                    ..
+                }
+            );
+            // Plans and finished runs change what the FILES screen shows.
+            tree_stale |= matches!(
+                &ev,
+                DomainEvent::PipelineEvent {
+                    ev: PipelineRunEvent::PlanReady { .. }
+                        | PipelineRunEvent::Completed
+                        | PipelineRunEvent::Failed { .. }
+                        | PipelineRunEvent::Cancelled,
+                    ..
+                }
+            );
+            let changed_upstream = match &ev {
+                DomainEvent::RepoChangedUpstream { profile_id } => Some(profile_id.clone()),
+                _ => None,
+            };
+            let warmed = match &ev {
+                DomainEvent::CacheWarmup { profile_id, status }
+                    if *status != CacheWarmup::Running =>
+                {
+                    Some(profile_id.clone())
+                }
+                _ => None,
+            };
+            self.state = reduce(std::mem::take(&mut self.state), ev);
+            applied = true;
+            if let Some(id) = warmed {
+                if self.state.selected_profile_id.as_ref() == Some(&id) {
+                    self.ensure_local_integrity_checked(&id);
+                }
+            }
+            if let Some(id) = changed_upstream {
+                // A check already under way will pick up the change.
+                if self.state.update_status.get(&id) == Some(&UpdateStatus::ChangedUpstream) {
+                    self.request_check(id);
+                }
+            }
+            if scan_finished || run_recorded {
+                // Scan history, the first checksum pin and the change log are re-recorded on the
+                // next run if lost.
+                let _ = self.persistence.save_profiles(&self.state.profiles);
+            }
+        }
+        if tree_stale {
+            // A run may have moved a corrupt database aside, replaced repo.json or added mods.
+            self.refresh_dashboard_folder();
+            if self.state.profile_tree.is_some() {
+                self.refresh_profile_tree();
+            }
+        }
+        if check_finished {
+            self.start_next_queued_check();
+        }
+        self.run_due_checks();
+        applied
+    }
+
+    /// A plan with changes is waiting for the user to apply it.
+    fn plan_pending(&self) -> bool {
+        self.state.last_plan.as_ref().is_some_and(|p| {
+            !(p.downloads.is_empty() && p.deletes.is_empty() && p.renames.is_empty())
+        })
+    }
+
+    /// Keeps one live-update subscription per profile with a `live_notify_url`, restarting
+    /// those whose URL changed. Offline mode drops them all.
+    fn sync_live_watches(&mut self) {
+        let wanted: HashMap<ProfileId, String> = if self.state.settings.offline {
+            HashMap::new()
+        } else {
+            self.state
+                .profiles
+                .iter()
+                .filter_map(|p| {
+                    let url = p.live_notify_url.as_deref()?.trim();
+                    (!url.is_empty()).then(|| (p.id.clone(), url.to_string()))
+                })
+                .collect()
+        };
+        self.live_watches.retain(|id, (url, handle)| {
+            let keep = wanted.get(id) == Some(url);
+            if !keep {
+                handle.abort();
+            }
+            keep
+        });
+        let Ok(rt) = crate::async_runtime::runtime() else {
+            return;
+        };
+        for (profile_id, url) in wanted {
+            if self.live_watches.contains_key(&profile_id) {
+                continue;
+            }
+            let Some(profile) = self.state.profiles.iter().find(|p| p.id == profile_id) else {
+                continue;
+            };
+            // Without a working client the profile simply falls back to manual checks.
+            let Some(client) = self
+                .state
+                .settings
+                .net_config_for(profile)
+                .ok()
+                .and_then(|config| fleet_infra::net::streaming_http_client(Some(&config)).ok())
+            else {
+                continue;
+            };
+            let tx = self.msg_tx.clone();
+            let id = profile_id.clone();
+            let watch_url = url.clone();
+            let handle = rt.spawn(async move {
+                let (live_tx, mut live_rx) = mpsc::channel(8);
+                let forward = async move {
+                    while live_rx.recv().await.is_some() {
+                        let ev = DomainEvent::RepoChangedUpstream {
+                            profile_id: id.clone(),
+                        };
+                        if tx.send(ev).await.is_err() {
+                            break;
+                        }
+                    }
+                };
+                tokio::join!(
+                    fleet_infra::net::live::watch(client, watch_url, live_tx),
+                    forward
+                );
+            });
+            self.live_watches.insert(profile_id, (url, handle));
+        }
+    }
+
+    /// Records the outcome of a remote check in `update_status`; returns whether one finished.
+    fn track_remote_check(&mut self, run_id: PipelineRunId, ev: &PipelineRunEvent) -> bool {
+        let status = match ev {
+            PipelineRunEvent::Started { .. } => {
+                // Any other run supersedes checks still in flight and ends a "check all".
+                let stale: Vec<PipelineRunId> = self
+                    .remote_checks
+                    .keys()
+                    .filter(|id| **id != run_id)
+                    .copied()
+                    .collect();
+                for id in stale {
+                    if let Some(profile_id) = self.remote_checks.remove(&id) {
+                        self.clear_pending_status(&profile_id);
+                    }
+                }
+                if !self.remote_checks.contains_key(&run_id) {
+                    self.abandon_check_queue();
+                }
+                return false;
+            }
+            PipelineRunEvent::PlanReady { plan, .. } => {
+                if plan.downloads.is_empty() && plan.deletes.is_empty() && plan.renames.is_empty() {
+                    UpdateStatus::UpToDate
+                } else {
+                    UpdateStatus::Updates {
+                        downloads: plan.downloads.len(),
+                        bytes: plan.downloads.iter().map(|d| d.size).sum(),
+                    }
+                }
+            }
+            PipelineRunEvent::Failed { message } => UpdateStatus::Failed(message.clone()),
+            PipelineRunEvent::Cancelled => {
+                if let Some(profile_id) = self.remote_checks.remove(&run_id) {
+                    self.clear_pending_status(&profile_id);
+                    self.abandon_check_queue();
+                }
+                return false;
+            }
+            _ => return false,
+        };
+        match self.remote_checks.remove(&run_id) {
+            Some(profile_id) => {
+                self.check_debounce.record_check(
+                    &profile_id,
+                    status == UpdateStatus::UpToDate,
+                    Instant::now(),
+                );
+                self.state.update_status.insert(profile_id, status);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn abandon_check_queue(&mut self) {
+        for profile_id in std::mem::take(&mut self.check_queue) {
+            self.clear_pending_status(&profile_id);
+        }
+    }
+
+    fn clear_pending_status(&mut self, profile_id: &ProfileId) {
+        if self.state.update_status.get(profile_id) == Some(&UpdateStatus::Checking) {
+            self.state.update_status.remove(profile_id);
         }
     }
 
@@ -257,6 +912,22 @@ impl FleetApplication {
             .ok_or_else(|| anyhow::anyhow!("Profile not found"))
     }
 
+    /// Recorded scans for a profile, most recent first.
+    pub fn scan_history(&self, profile_id: &ProfileId) -> Vec<(DateTime<Utc>, ScanStats)> {
+        self.state
+            .profiles
+            .iter()
+            .find(|p| &p.id == profile_id)
+            .map(|p| {
+                p.scan_history
+                    .iter()
+                    .rev()
+                    .map(|r| (r.at, r.stats.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     pub fn is_pipeline_running(&self) -> bool {
         self.state.pipeline.is_running()
     }
@@ -270,10 +941,47 @@ impl FleetApplication {
             _ => None,
         };
         self.state.route = route;
+        self.state.server_password_saved = None;
+        self.refresh_profile_tree();
+        self.refresh_dashboard_folder();
         if let Some(id) = dashboard_id {
             self.ensure_local_integrity_checked(&id);
         }
     }
+
+    /// Rebuilds the FILES screen's tree, or drops it on any other screen. It reads
+    /// `fleet.redb`, so this runs on navigation and after runs, never per frame.
+    fn refresh_profile_tree(&mut self) {
+        self.state.profile_tree = match &self.state.route {
+            Route::ProfileTree(id) => Some(ProfileTree {
+                profile_id: id.clone(),
+                mods: crate::viewmodel::profile_tree_vm(&self.state, id).map(Arc::new),
+            }),
+            _ => None,
+        };
+    }
+
+    /// Re-reads what the open dashboard shows from its mods folder, or forgets it on any
+    /// other screen.
+    fn refresh_dashboard_folder(&mut self) {
+        let profile = match &self.state.route {
+            Route::ProfileDashboard(id) => self.get_profile(id.clone()).ok().cloned(),
+            _ => None,
+        };
+        let Some(profile) = profile else {
+            self.state.quarantined_dbs = None;
+            self.state.repo_servers = None;
+            self.state.local_mods = None;
+            return;
+        };
+        let root = camino::Utf8Path::new(&profile.local_path);
+        self.state.quarantined_dbs = Some((
+            profile.id.clone(),
+            RedbFleetDataStore::list_quarantined(root).len(),
+        ));
+        self.state.repo_servers = Some((profile.id.clone(), repo_servers(&profile.local_path)));
+        self.state.local_mods = Some((profile.id, local_mod_names(&profile.local_path)));
+    }
     pub fn editor_draft(&self) -> Option<&Profile> {
         self.state.editor_draft.as_ref()
     }
@@ -284,24 +992,45 @@ impl FleetApplication {
     pub fn edit_profile(&mut self, id: ProfileId) {
         if let Ok(p) = self.get_profile(id.clone()) {
             self.state.editor_draft = Some(p.clone());
+            self.state.server_password_saved = None;
             self.state.route = Route::ProfileEditor(id);
         }
     }
     pub fn save_profile(&mut self) -> anyhow::Result<()> {
-        if let Some(draft) = self.state.editor_draft.clone() {
+        if let Some(mut draft) = self.state.editor_draft.clone() {
+            draft.extra_repos = draft.extra_repo_urls();
+            let normalize = |url: &str| {
+                fleet_pipeline::sync::remote::normalize_repo_url(url)
+                    .unwrap_or_else(|_| url.trim().to_string())
+            };
+            draft.repo_url = normalize(&draft.repo_url);
+            draft.extra_repos = draft.extra_repos.iter().map(|u| normalize(u)).collect();
+            draft.launch_params_override = draft
+                .launch_params_override
+                .map(|p| p.trim().to_string())
+                .filter(|p| !p.is_empty());
+            draft.live_notify_url = draft
+                .live_notify_url
+                .map(|u| u.trim().to_string())
+                .filter(|u| !u.is_empty());
             // Optimistically commit and close draft via reducer
             self.state = reduce(
-                self.state.clone(),
+                std::mem::take(&mut self.state),
                 DomainEvent::DraftCommitted(draft.clone()),
             );
             self.state = reduce(
-                self.state.clone(),
+                std::mem::take(&mut self.state),
                 DomainEvent::RouteChanged(Route::ProfileHub),
             );
+            self.sync_live_watches();
 
             let profiles_snapshot = self.state.profiles.clone();
-            let repo_url = draft.repo_url.clone();
+            let settings = self.state.settings.clone();
+            let repo_urls: Vec<String> = std::iter::once(draft.repo_url.clone())
+                .chain(draft.extra_repos.iter().cloned())
+                .collect();
             let tx = self.msg_tx.clone();
+            let draft_for_thread = draft.clone();
             let reopen_draft = draft.clone();
             let reopen_id = draft.id.clone();
             let reopen_draft_for_thread = reopen_draft.clone();
@@ -311,11 +1040,18 @@ impl FleetApplication {
                 .name("fleet-save-profile".into())
                 .spawn(move || {
                     let res: anyhow::Result<()> = (|| {
-                        let client = fleet_infra::net::default_http_client()
-                            .unwrap_or_else(|_| reqwest::Client::new());
-                        let engine = fleet_pipeline::default_engine(client);
-                        crate::async_runtime::runtime()?
-                            .block_on(engine.validate_repo_url(&repo_url))?;
+                        let engine = if draft_for_thread.repo_auth.is_some() {
+                            profile_engine(&settings, &draft_for_thread)?
+                        } else {
+                            build_engine(&settings)?
+                        };
+                        let rt = crate::async_runtime::runtime()?;
+                        // Offline, the URLs were already normalized; reaching them is checked later.
+                        if !settings.offline {
+                            for repo_url in &repo_urls {
+                                rt.block_on(engine.validate_repo_url(repo_url))?;
+                            }
+                        }
 
                         let persistence = FilePersistence::new();
                         persistence.save_profiles(&profiles_snapshot)?;
@@ -347,18 +1083,68 @@ impl FleetApplication {
         self.state.route = Route::ProfileHub;
     }
     pub fn delete_profile(&mut self, id: ProfileId) -> anyhow::Result<()> {
+        if self
+            .state
+            .profiles
+            .iter()
+            .any(|p| p.id == id && p.repo_auth.is_some())
+        {
+            let _ =
+                fleet_infra::credentials::delete_password(&fleet_infra::credentials::repo_key(&id));
+        }
         self.state.profiles.retain(|p| p.id != id);
+        self.sync_live_watches();
         self.persistence.save_profiles(&self.state.profiles)?;
         Ok(())
     }
+    /// Opens the profile's mods folder in the system file manager. A folder that doesn't
+    /// exist yet (nothing synced) is an error instead of an empty file manager window.
+    pub fn open_local_folder(&self, profile_id: ProfileId) -> anyhow::Result<()> {
+        let profile = self.get_profile(profile_id)?;
+        match fleet_infra::open_folder(std::path::Path::new(&profile.local_path)) {
+            Err(fleet_infra::LaunchError::MissingFolder(dir)) => anyhow::bail!(
+                "Mods folder {} does not exist yet; sync the profile first",
+                dir.display()
+            ),
+            other => Ok(other?),
+        }
+    }
+
+    /// Deletes the corrupt databases quarantined in the profile folder; returns how many.
+    pub fn clear_quarantined_dbs(&mut self, profile_id: ProfileId) -> anyhow::Result<usize> {
+        let profile = self.get_profile(profile_id)?;
+        let root = camino::Utf8Path::new(&profile.local_path);
+        let removed = RedbFleetDataStore::clear_quarantined(root);
+        self.refresh_dashboard_folder();
+        Ok(removed?)
+    }
+
+    pub fn open_log_folder(&self) -> anyhow::Result<()> {
+        let dir = self.persistence.log_dir()?;
+        fleet_infra::open_url(&dir.to_string_lossy())?;
+        Ok(())
+    }
+
     pub fn update_settings(&mut self, s: AppSettings) -> anyhow::Result<()> {
         self.state.pipeline.error = None;
+        let engine = build_engine(&s).inspect_err(|e| {
+            self.state.pipeline.error = Some(format!("{e:#}"));
+        })?;
+        self.orchestrator.set_engine(std::sync::Arc::new(engine));
         self.state.settings = s.clone();
+        self.sync_live_watches();
         self.persistence.save_settings(&s)
     }
 }
 
-fn detect_flatpak_steam_availability() -> FlatpakSteamAvailability {
+/// Builds a sync engine whose HTTP client honours the proxy/auth settings.
+fn build_engine(settings: &AppSettings) -> anyhow::Result<fleet_pipeline::DefaultSyncEngine> {
+    let client = fleet_infra::net::default_http_client(Some(&settings.net_config()))
+        .context("Invalid network settings")?;
+    Ok(fleet_pipeline::default_engine(client))
+}
+
+pub(crate) fn detect_flatpak_steam_availability() -> FlatpakSteamAvailability {
     #[cfg(not(target_os = "linux"))]
     {
         return FlatpakSteamAvailability::Unavailable("Flatpak is only supported on Linux".into());
@@ -406,6 +1192,33 @@ fn load_local_repo_json(local_root: &str) -> Option<Repository> {
     serde_json::from_str(&content).ok()
 }
 
+/// `servers` from the repo.json in `local_root`, or none when it is missing or unreadable.
+pub(crate) fn repo_servers(local_root: &str) -> Vec<Server> {
+    load_local_repo_json(local_root)
+        .map(|r| r.servers)
+        .unwrap_or_default()
+}
+
+/// Mod folder names in the order they would load without an explicit `mod_order`:
+/// repo.json order when present, otherwise alphabetical.
+pub fn default_mod_order(local_root: &str) -> Vec<String> {
+    let mods = load_local_repo_json(local_root)
+        .map(|r| enabled_mod_paths(&r, local_root))
+        .filter(|m| !m.is_empty())
+        .unwrap_or_else(|| discover_mod_dirs(local_root));
+    mods.iter()
+        .filter_map(|m| m.file_name().map(str::to_string))
+        .collect()
+}
+
+/// Mod folders currently in the profile's mods folder, alphabetically.
+pub fn local_mod_names(local_root: &str) -> Vec<String> {
+    discover_mod_dirs(local_root)
+        .iter()
+        .filter_map(|m| m.file_name().map(str::to_string))
+        .collect()
+}
+
 fn enabled_mod_paths(repo: &Repository, local_root: &str) -> Vec<camino::Utf8PathBuf> {
     let root = camino::Utf8PathBuf::from(local_root.to_string());
     let mut mods = Vec::new();
@@ -498,4 +1311,441 @@ mod tests {
             .iter()
             .any(|p| p.as_str().ends_with(r"\@optional") || p.as_str().ends_with("/@optional")));
     }
+
+    #[test]
+    fn profile_launch_params_override_falls_back_to_global_when_blank() {
+        let mut profile = Profile::default();
+        assert_eq!(profile.launch_params(" -noSplash "), "-noSplash");
+
+        profile.launch_params_override = Some("   ".into());
+        assert_eq!(profile.launch_params("-noSplash"), "-noSplash");
+
+        profile.launch_params_override = Some(" -filePatching ".into());
+        assert_eq!(profile.launch_params("-noSplash"), "-filePatching");
+    }
+
+    #[test]
+    fn scan_history_keeps_only_the_most_recent_scans() {
+        let mut profile = Profile::default();
+        for i in 0..(crate::domain::SCAN_HISTORY_LEN as u64 + 3) {
+            let stats = ScanStats {
+                total_files: i,
+                ..Default::default()
+            };
+            profile.record_scan(Utc::now(), stats);
+        }
+
+        assert_eq!(profile.scan_history.len(), crate::domain::SCAN_HISTORY_LEN);
+        assert_eq!(profile.scan_history[0].stats.total_files, 3);
+
+        let json = serde_json::to_string(&profile).unwrap();
+        let loaded: Profile = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.scan_history.len(), crate::domain::SCAN_HISTORY_LEN);
+    }
+
+    #[test]
+    fn large_sync_cancel_waits_for_confirmation() {
+        let mut app = FleetApplication::new();
+        app.state.settings.cancel_confirm_mb = 1;
+        app.state.pipeline.sync_status = StepStatus::Running;
+        let transfer = |total_bytes| crate::pipeline::TransferProgressVm {
+            downloaded_files: 0,
+            total_files: 1,
+            downloaded_bytes: 0,
+            total_bytes,
+            speed_bps: 0,
+            failed_count: 0,
+            active_files: Vec::new(),
+            per_mod: Vec::new(),
+        };
+
+        app.state.pipeline.stats.transfer = Some(transfer(2 * 1024 * 1024));
+        app.request_cancel();
+        assert!(app.state.cancel_pending);
+        app.dismiss_cancel();
+        assert!(!app.state.cancel_pending);
+        app.request_cancel();
+        app.request_cancel();
+        assert!(!app.state.cancel_pending);
+
+        app.state.pipeline.stats.transfer = Some(transfer(1024));
+        app.request_cancel();
+        assert!(!app.state.cancel_pending);
+
+        app.state.settings.cancel_confirm_mb = 0;
+        app.state.pipeline.stats.transfer = Some(transfer(u64::MAX));
+        app.request_cancel();
+        assert!(!app.state.cancel_pending);
+    }
+
+    #[test]
+    fn starting_a_run_clears_an_unfinished_cache_warmup() {
+        let warmup = |id: &str, status| DomainEvent::CacheWarmup {
+            profile_id: id.into(),
+            status,
+        };
+        let mut state = reduce(AppState::default(), warmup("a", CacheWarmup::Running));
+        state = reduce(state, warmup("b", CacheWarmup::Ready));
+        state = reduce(
+            state,
+            DomainEvent::PipelineEvent {
+                run_id: uuid::Uuid::new_v4(),
+                ev: PipelineRunEvent::Started {
+                    profile_id: "a".into(),
+                },
+            },
+        );
+
+        assert!(!state.cache_warmup.contains_key("a"));
+        assert_eq!(state.cache_warmup.get("b"), Some(&CacheWarmup::Ready));
+    }
+
+    #[test]
+    fn only_a_completed_sync_requests_an_auto_launch() {
+        let event = |ev| DomainEvent::PipelineEvent {
+            run_id: uuid::Uuid::new_v4(),
+            ev,
+        };
+        let started = || {
+            event(PipelineRunEvent::Started {
+                profile_id: "a".into(),
+            })
+        };
+        let mut state = AppState::default();
+        state.settings.auto_launch_after_sync = true;
+
+        state = reduce(state, started());
+        state = reduce(state, event(PipelineRunEvent::Completed));
+        assert_eq!(state.auto_launch.as_deref(), Some("a"));
+
+        for end in [
+            PipelineRunEvent::Cancelled,
+            PipelineRunEvent::Failed {
+                message: "boom".into(),
+            },
+        ] {
+            state = reduce(state, started());
+            state = reduce(state, event(end));
+            assert_eq!(state.auto_launch, None);
+        }
+
+        state.settings.auto_launch_after_sync = false;
+        state = reduce(state, started());
+        state = reduce(state, event(PipelineRunEvent::Completed));
+        assert_eq!(state.auto_launch, None);
+    }
+
+    #[test]
+    fn failed_downloads_are_kept_until_the_next_run() {
+        let event = |ev| DomainEvent::PipelineEvent {
+            run_id: uuid::Uuid::new_v4(),
+            ev,
+        };
+        let started = || {
+            event(PipelineRunEvent::Started {
+                profile_id: "a".into(),
+            })
+        };
+        let failure = fleet_pipeline::FailedDownload {
+            mod_name: "@a".into(),
+            rel_path: "addons/a.pbo".into(),
+            size: 5,
+            expected_checksum: "ABC".into(),
+            mtime: None,
+            mod_checksum: "M1".into(),
+            reason: fleet_infra::DownloadError::Status(503),
+        };
+
+        let mut state = reduce(AppState::default(), started());
+        state = reduce(
+            state,
+            event(PipelineRunEvent::DownloadsFailed {
+                failures: vec![failure.clone()],
+            }),
+        );
+        state = reduce(
+            state,
+            event(PipelineRunEvent::Failed {
+                message: "1 file failed".into(),
+            }),
+        );
+        assert_eq!(state.pipeline.failed_downloads, vec![failure]);
+        assert_eq!(
+            fleet_pipeline::retry_plan(&state.pipeline.failed_downloads).downloads[0].rel_path,
+            "addons/a.pbo"
+        );
+
+        state = reduce(state, started());
+        assert!(state.pipeline.failed_downloads.is_empty());
+    }
+
+    #[test]
+    fn latest_repo_checksum_is_tracked_per_profile() {
+        let event = |ev| DomainEvent::PipelineEvent {
+            run_id: uuid::Uuid::new_v4(),
+            ev,
+        };
+        let checksum = |c: &str| event(PipelineRunEvent::RepoChecksum { checksum: c.into() });
+        let mut state = AppState {
+            profiles: vec![Profile {
+                id: "a".into(),
+                ..Profile::default()
+            }],
+            ..AppState::default()
+        };
+        state = reduce(
+            state,
+            event(PipelineRunEvent::Started {
+                profile_id: "a".into(),
+            }),
+        );
+
+        state = reduce(state, checksum("AAA"));
+        state = reduce(state, checksum("BBB"));
+        assert_eq!(
+            state.repo_checksums.get("a").map(String::as_str),
+            Some("BBB")
+        );
+    }
+
+    #[test]
+    fn mod_toggles_match_case_insensitively_and_round_trip() {
+        let mut profile = Profile::default();
+        profile.set_mod_loaded("@ACE", false);
+        profile.set_mod_synced("@cba_a3", false);
+        assert!(!profile.loads_mod("@ace"));
+        assert!(profile.syncs_mod("@ace"));
+        assert!(!profile.syncs_mod("@CBA_A3"));
+
+        let loaded: Profile =
+            serde_json::from_str(&serde_json::to_string(&profile).unwrap()).unwrap();
+        assert_eq!(loaded.disabled_mods, vec!["@ACE".to_string()]);
+
+        profile.set_mod_loaded("@ace", true);
+        profile.set_mod_synced("@CBA_A3", true);
+        assert!(profile.disabled_mods.is_empty());
+        assert!(profile.unsynced_mods.is_empty());
+    }
+
+    #[test]
+    fn profile_servers_lists_repo_servers_and_join_rejects_unknown_index() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let repo_json = r#"
+        {
+          "repoName": "pca",
+          "checksum": "abc",
+          "requiredMods": [],
+          "optionalMods": [],
+          "servers": [
+            { "name": "Main", "address": "main.example.com", "port": 2302, "password": "", "battleEye": true },
+            { "name": "Training", "address": "10.0.0.2", "port": 2402, "password": "pw", "battleEye": false }
+          ]
+        }
+        "#;
+        fs::write(dir.path().join("repo.json"), repo_json).expect("write repo.json");
+
+        let mut app = FleetApplication::new();
+        app.state.profiles.push(Profile {
+            id: "p".into(),
+            local_path: dir.path().to_string_lossy().to_string(),
+            ..Profile::default()
+        });
+
+        let servers = app.profile_servers("p".into()).unwrap();
+        let names: Vec<&str> = servers.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["Main", "Training"]);
+        assert_eq!(servers[1].port, 2402);
+
+        let err = app.join_profile_server("p".into(), 2).unwrap_err();
+        assert!(err.to_string().contains("no server #3"), "{err}");
+
+        app.navigate(Route::ProfileDashboard("p".into()));
+        fs::remove_file(dir.path().join("repo.json")).unwrap();
+        let vm = crate::viewmodel::profile_dashboard_vm(&app.state, "p".into()).unwrap();
+        assert_eq!(
+            vm.servers.len(),
+            2,
+            "read on opening the dashboard, not per frame"
+        );
+    }
+
+    #[test]
+    fn remote_check_outcomes_land_in_update_status_and_other_runs_abandon_the_queue() {
+        let mut app = FleetApplication::new();
+        let (checked, queued) = (ProfileId::from("a"), ProfileId::from("b"));
+        let run_id = uuid::Uuid::new_v4();
+        app.remote_checks.insert(run_id, checked.clone());
+        app.check_queue.push_back(queued.clone());
+        for id in [&checked, &queued] {
+            app.state
+                .update_status
+                .insert(id.clone(), UpdateStatus::Checking);
+        }
+
+        let plan = SyncPlan {
+            downloads: vec![fleet_core::DownloadAction {
+                mod_name: "@m".into(),
+                rel_path: "a.pbo".into(),
+                size: 2048,
+                expected_checksum: "X".into(),
+                mtime: None,
+                parts: Vec::new(),
+            }],
+            renames: vec![],
+            checks: vec![],
+            deletes: vec![],
+        };
+        let ready = PipelineRunEvent::PlanReady {
+            plan,
+            diff_stats: (1, 0),
+            risk: fleet_core::RiskLevel::Safe,
+            existing_mods: vec![],
+        };
+        assert!(app.track_remote_check(run_id, &ready));
+        assert_eq!(
+            app.state.update_status.get(&checked),
+            Some(&UpdateStatus::Updates {
+                downloads: 1,
+                bytes: 2048
+            })
+        );
+
+        let started = PipelineRunEvent::Started {
+            profile_id: "c".into(),
+        };
+        assert!(!app.track_remote_check(uuid::Uuid::new_v4(), &started));
+        assert!(app.check_queue.is_empty());
+        assert!(!app.state.update_status.contains_key(&queued));
+    }
+
+    #[test]
+    fn upstream_change_is_debounced_and_waits_while_a_plan_is_under_review() {
+        let mut app = FleetApplication::new();
+        app.state.settings.recheck_quiet_period_secs = 0;
+        app.state.profiles.push(Profile {
+            id: "a".into(),
+            ..Profile::default()
+        });
+        app.state.last_plan = Some(Arc::new(SyncPlan {
+            downloads: vec![fleet_core::DownloadAction {
+                mod_name: "@m".into(),
+                rel_path: "a.pbo".into(),
+                size: 1,
+                expected_checksum: "X".into(),
+                mtime: None,
+                parts: Vec::new(),
+            }],
+            renames: vec![],
+            checks: vec![],
+            deletes: vec![],
+        }));
+
+        for _ in 0..3 {
+            app.msg_tx
+                .try_send(DomainEvent::RepoChangedUpstream {
+                    profile_id: "a".into(),
+                })
+                .unwrap();
+        }
+        app.handle_pipeline_events();
+
+        assert!(app.remote_checks.is_empty());
+        assert!(app.state.last_plan.is_some());
+        assert_eq!(app.next_scheduled_check(), None);
+        assert_eq!(
+            app.state.update_status.get("a"),
+            Some(&UpdateStatus::ChangedUpstream)
+        );
+
+        app.state.last_plan = None;
+        assert_eq!(app.next_scheduled_check(), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn the_file_tree_is_built_on_navigation_and_dropped_on_leaving() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let mut app = FleetApplication::new();
+        app.state.profiles.push(Profile {
+            id: "a".into(),
+            local_path: dir.path().to_string_lossy().into_owned(),
+            ..Profile::default()
+        });
+
+        app.navigate(Route::ProfileTree("a".into()));
+        let tree = app.state.profile_tree.as_ref().expect("tree built");
+        assert_eq!(tree.profile_id, "a");
+        assert!(tree.mods.as_ref().is_some_and(|m| m.is_empty()));
+
+        app.navigate(Route::ProfileHub);
+        assert!(app.state.profile_tree.is_none());
+    }
+
+    #[test]
+    fn invalid_network_settings_are_reported_instead_of_replaced() {
+        let mut app = FleetApplication::new();
+        let settings = AppSettings {
+            proxy: "not a url".into(),
+            ..AppSettings::default()
+        };
+
+        assert!(app.update_settings(settings).is_err());
+        let error = app.state.pipeline.error.as_deref().unwrap();
+        assert!(error.contains("Invalid network settings"), "{error}");
+        assert!(error.contains("not a url"), "{error}");
+        assert!(app.state.settings.proxy.is_empty());
+    }
+
+    #[test]
+    fn the_dashboard_reads_its_folder_on_opening_it() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::write(dir.path().join("fleet.redb.corrupt.1"), b"bad").unwrap();
+        let mut app = FleetApplication::new();
+        app.state.profiles.push(Profile {
+            id: "a".into(),
+            local_path: dir.path().to_string_lossy().into_owned(),
+            ..Profile::default()
+        });
+
+        app.navigate(Route::ProfileDashboard("a".into()));
+        assert_eq!(app.state.quarantined_dbs, Some(("a".into(), 1)));
+
+        std::fs::create_dir(dir.path().join("@late")).unwrap();
+        let vm = crate::viewmodel::profile_dashboard_vm(&app.state, "a".into()).unwrap();
+        assert!(
+            vm.mods.is_empty(),
+            "mods are listed on opening, not per frame"
+        );
+        app.navigate(Route::ProfileDashboard("a".into()));
+        let vm = crate::viewmodel::profile_dashboard_vm(&app.state, "a".into()).unwrap();
+        assert_eq!(vm.mods.len(), 1);
+
+        assert_eq!(app.clear_quarantined_dbs("a".into()).unwrap(), 1);
+        assert_eq!(app.state.quarantined_dbs, Some(("a".into(), 0)));
+
+        app.navigate(Route::ProfileHub);
+        assert!(app.state.quarantined_dbs.is_none());
+    }
+
+    #[test]
+    fn upstream_change_badges_the_profile_unless_a_check_is_running() {
+        let changed = |id: &str| DomainEvent::RepoChangedUpstream {
+            profile_id: id.into(),
+        };
+        let mut state = AppState::default();
+        state
+            .update_status
+            .insert("busy".into(), UpdateStatus::Checking);
+
+        state = reduce(state, changed("a"));
+        state = reduce(state, changed("busy"));
+
+        assert_eq!(
+            state.update_status.get("a"),
+            Some(&UpdateStatus::ChangedUpstream)
+        );
+        assert_eq!(
+            state.update_status.get("busy"),
+            Some(&UpdateStatus::Checking)
+        );
+    }
 }
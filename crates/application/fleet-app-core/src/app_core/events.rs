@@ -1,4 +1,4 @@
-use crate::domain::{AppSettings, Profile, Route};
+use crate::domain::{AppSettings, CacheWarmup, Profile, ProfileId, Route, ServerStatus};
 use crate::pipeline::{PipelineRunEvent, PipelineRunId};
 
 #[derive(Debug, Clone)]
@@ -27,6 +27,23 @@ pub enum DomainEvent {
         ev: PipelineRunEvent,
     },
 
+    // Background cache warm-up
+    CacheWarmup {
+        profile_id: ProfileId,
+        status: CacheWarmup,
+    },
+
+    // Game server queries
+    ServerStatus {
+        address: String,
+        status: ServerStatus,
+    },
+
+    // Live repo-change notifications
+    RepoChangedUpstream {
+        profile_id: ProfileId,
+    },
+
     // User-visible errors
     UserError(String),
 }
@@ -1,6 +1,7 @@
-use crate::domain::{AppState, BootState, Route};
+use crate::domain::{AppState, BootState, CacheWarmup, Route, UpdateStatus, LAST_CHANGES_SAMPLE};
 use crate::pipeline::{PipelineRunEvent, PipelineStep, StepStatus};
 use chrono::Utc;
+use std::sync::Arc;
 
 use super::events::DomainEvent;
 
@@ -41,6 +42,23 @@ pub fn reduce(mut state: AppState, ev: DomainEvent) -> AppState {
 
         DomainEvent::PipelineEvent { run_id: _, ev } => apply_pipeline_event(&mut state, ev),
 
+        DomainEvent::CacheWarmup { profile_id, status } => {
+            state.cache_warmup.insert(profile_id, status);
+        }
+
+        DomainEvent::ServerStatus { address, status } => {
+            state.server_status.insert(address, status);
+        }
+
+        DomainEvent::RepoChangedUpstream { profile_id } => {
+            // A check already under way will pick up the change.
+            if state.update_status.get(&profile_id) != Some(&UpdateStatus::Checking) {
+                state
+                    .update_status
+                    .insert(profile_id, UpdateStatus::ChangedUpstream);
+            }
+        }
+
         DomainEvent::UserError(msg) => {
             state.pipeline.error = Some(msg);
         }
@@ -51,6 +69,10 @@ pub fn reduce(mut state: AppState, ev: DomainEvent) -> AppState {
 fn apply_pipeline_event(state: &mut AppState, ev: PipelineRunEvent) {
     match ev {
         PipelineRunEvent::Started { profile_id } => {
+            // Starting a run cancels any warm-up still in progress.
+            state.cache_warmup.retain(|_, s| *s != CacheWarmup::Running);
+            state.cancel_pending = false;
+            state.auto_launch = None;
             state.pipeline.error = None;
             state.last_plan = None;
             state.pipeline = crate::pipeline::PipelineState::starting(profile_id)
@@ -64,6 +86,16 @@ fn apply_pipeline_event(state: &mut AppState, ev: PipelineRunEvent) {
         } => {
             state.pipeline.set_step_status(step, status);
             state.pipeline.details.insert(step, detail);
+
+            if step == PipelineStep::Scan && status == StepStatus::Succeeded {
+                let stats = state.pipeline.stats.scan.clone();
+                let active = state.pipeline.active_profile_id.as_ref();
+                if let (Some(stats), Some(active_id)) = (stats, active) {
+                    if let Some(profile) = state.profiles.iter_mut().find(|p| &p.id == active_id) {
+                        profile.record_scan(Utc::now(), stats);
+                    }
+                }
+            }
         }
 
         PipelineRunEvent::ScanStats { stats } => {
@@ -92,6 +124,18 @@ fn apply_pipeline_event(state: &mut AppState, ev: PipelineRunEvent) {
                         rel_path: f.rel_path,
                         bytes_downloaded: f.bytes_downloaded,
                         total_bytes: f.total_bytes,
+                        verifying: f.verifying,
+                    })
+                    .collect(),
+                per_mod: snapshot
+                    .per_mod
+                    .into_iter()
+                    .map(|m| crate::pipeline::ModTransferVm {
+                        mod_name: m.mod_name,
+                        downloaded_files: m.downloaded_files,
+                        total_files: m.total_files,
+                        downloaded_bytes: m.downloaded_bytes,
+                        total_bytes: m.total_bytes,
                     })
                     .collect(),
             });
@@ -100,9 +144,11 @@ fn apply_pipeline_event(state: &mut AppState, ev: PipelineRunEvent) {
         PipelineRunEvent::PlanReady {
             plan,
             diff_stats,
+            risk,
             existing_mods,
         } => {
-            state.last_plan = Some(plan);
+            state.last_plan = Some(Arc::new(plan));
+            state.pipeline.plan_risk = Some(risk);
             state.pipeline.stats.diff = Some(diff_stats);
             state.pipeline.plan_existing_mods = Some(existing_mods);
             state
@@ -110,6 +156,48 @@ fn apply_pipeline_event(state: &mut AppState, ev: PipelineRunEvent) {
                 .set_step_status(PipelineStep::Diff, StepStatus::Succeeded);
         }
 
+        PipelineRunEvent::SignatureWarnings { warnings } => {
+            if let Some(active_id) = &state.pipeline.active_profile_id {
+                state.signature_warnings.insert(active_id.clone(), warnings);
+            }
+        }
+
+        PipelineRunEvent::RepoChecksum { checksum } => {
+            if let Some(active_id) = &state.pipeline.active_profile_id {
+                state.repo_checksums.insert(active_id.clone(), checksum);
+            }
+        }
+
+        PipelineRunEvent::DownloadsFailed { failures } => {
+            state.pipeline.failed_downloads = failures;
+        }
+
+        PipelineRunEvent::StorageWarnings { warnings } => {
+            for warning in warnings {
+                if !state.pipeline.storage_warnings.contains(&warning) {
+                    state.pipeline.storage_warnings.push(warning);
+                }
+            }
+        }
+
+        PipelineRunEvent::VerifyMismatches { mismatches } => {
+            state.pipeline.verify_mismatches = mismatches;
+        }
+
+        PipelineRunEvent::SyncSummary {
+            changes,
+            throughput_bps,
+        } => {
+            if let Some(active_id) = &state.pipeline.active_profile_id {
+                if let Some(profile) = state.profiles.iter_mut().find(|p| &p.id == active_id) {
+                    profile.last_changes = Some(changes.condensed(LAST_CHANGES_SAMPLE));
+                    if let Some(bps) = throughput_bps {
+                        profile.record_throughput(bps);
+                    }
+                }
+            }
+        }
+
         PipelineRunEvent::Completed => {
             state
                 .pipeline
@@ -120,9 +208,15 @@ fn apply_pipeline_event(state: &mut AppState, ev: PipelineRunEvent) {
                     profile.last_synced = Some(Utc::now());
                 }
             }
+            // Files that just failed verification are worth a look before playing.
+            if state.settings.auto_launch_after_sync && state.pipeline.verify_mismatches.is_empty()
+            {
+                state.auto_launch = state.pipeline.active_profile_id.clone();
+            }
         }
 
         PipelineRunEvent::Failed { message } => {
+            state.auto_launch = None;
             state.pipeline.error = Some(message);
             for step in [
                 PipelineStep::Fetch,
@@ -138,6 +232,7 @@ fn apply_pipeline_event(state: &mut AppState, ev: PipelineRunEvent) {
         }
 
         PipelineRunEvent::Cancelled => {
+            state.auto_launch = None;
             state.pipeline.error = Some("Operation cancelled by user".into());
             for step in [
                 PipelineStep::Fetch,
@@ -22,7 +22,7 @@ impl AppStore {
 
     pub fn apply(&self, ev: DomainEvent) {
         let mut guard = self.inner.lock().unwrap();
-        let next = reduce(guard.clone(), ev);
+        let next = reduce(std::mem::take(&mut *guard), ev);
         *guard = next;
     }
 
@@ -0,0 +1,117 @@
+//! Quiet-period scheduling for automatic checks, so a burst of triggers (file edits, repeated
+//! notifications) runs one check instead of one per trigger.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::domain::ProfileId;
+
+/// Pending automatic checks per profile. A check runs once no new request has arrived for
+/// the quiet period, and is dropped if a check of the same profile found nothing to do
+/// within the quiet period before that.
+#[derive(Debug, Default)]
+pub struct CheckDebouncer {
+    /// When each pending check becomes due.
+    pending: HashMap<ProfileId, Instant>,
+    /// When each profile's latest check finished with nothing to do.
+    clean: HashMap<ProfileId, Instant>,
+}
+
+impl CheckDebouncer {
+    /// Requests a check; a request already pending is pushed back to `now + quiet`.
+    pub fn request(&mut self, profile_id: ProfileId, now: Instant, quiet: Duration) {
+        self.pending.insert(profile_id, now + quiet);
+    }
+
+    /// Drops a pending request, e.g. because the user started a check by hand.
+    pub fn cancel(&mut self, profile_id: &ProfileId) {
+        self.pending.remove(profile_id);
+    }
+
+    /// Records the outcome of a finished check of `profile_id`.
+    pub fn record_check(&mut self, profile_id: &ProfileId, up_to_date: bool, now: Instant) {
+        if up_to_date {
+            self.clean.insert(profile_id.clone(), now);
+        } else {
+            self.clean.remove(profile_id);
+        }
+    }
+
+    /// Removes and returns the requests whose quiet period is over, minus those a recent
+    /// clean check makes redundant.
+    pub fn take_due(&mut self, now: Instant, quiet: Duration) -> Vec<ProfileId> {
+        let mut due: Vec<ProfileId> = self
+            .pending
+            .iter()
+            .filter(|(_, at)| **at <= now)
+            .map(|(id, _)| id.clone())
+            .collect();
+        due.sort();
+        for id in &due {
+            self.pending.remove(id);
+        }
+        due.retain(|id| {
+            self.clean
+                .get(id)
+                .is_none_or(|at| now.saturating_duration_since(*at) >= quiet)
+        });
+        due
+    }
+
+    /// Time until the next pending request is due; `None` when nothing is pending.
+    pub fn next_due(&self, now: Instant) -> Option<Duration> {
+        self.pending
+            .values()
+            .map(|at| at.saturating_duration_since(now))
+            .min()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const QUIET: Duration = Duration::from_secs(10);
+
+    #[test]
+    fn requests_within_the_quiet_period_coalesce_into_one_check() {
+        let mut d = CheckDebouncer::default();
+        let t0 = Instant::now();
+        d.request("a".into(), t0, QUIET);
+        d.request("a".into(), t0 + Duration::from_secs(6), QUIET);
+
+        assert!(d.take_due(t0 + QUIET, QUIET).is_empty());
+        assert_eq!(d.next_due(t0 + QUIET), Some(Duration::from_secs(6)));
+        assert_eq!(
+            d.take_due(t0 + Duration::from_secs(16), QUIET),
+            vec!["a".to_string()]
+        );
+        assert_eq!(d.next_due(t0 + Duration::from_secs(16)), None);
+    }
+
+    #[test]
+    fn a_recent_clean_check_suppresses_the_recheck() {
+        let mut d = CheckDebouncer::default();
+        let t0 = Instant::now();
+        d.record_check(&"a".into(), true, t0);
+        d.request("a".into(), t0, Duration::from_secs(5));
+        assert!(d.take_due(t0 + Duration::from_secs(5), QUIET).is_empty());
+
+        // Pending changes, or a clean check longer ago than the quiet period, do not.
+        d.record_check(&"a".into(), false, t0);
+        d.request("a".into(), t0, Duration::ZERO);
+        assert_eq!(d.take_due(t0, QUIET), vec!["a".to_string()]);
+        d.record_check(&"b".into(), true, t0);
+        d.request("b".into(), t0 + QUIET, Duration::ZERO);
+        assert_eq!(d.take_due(t0 + QUIET, QUIET), vec!["b".to_string()]);
+    }
+
+    #[test]
+    fn cancel_drops_the_pending_request() {
+        let mut d = CheckDebouncer::default();
+        let t0 = Instant::now();
+        d.request("a".into(), t0, QUIET);
+        d.cancel(&"a".into());
+        assert!(d.take_due(t0 + QUIET, QUIET).is_empty());
+    }
+}
@@ -0,0 +1,278 @@
+//! Self-test behind `fleet doctor`: checks the pieces a sync depends on and reports each
+//! one instead of stopping at the first failure.
+
+use camino::{Utf8Path, Utf8PathBuf};
+use fleet_infra::NetConfig;
+use fleet_persistence::{DbState, RedbFleetDataStore};
+use std::fmt;
+
+use crate::domain::FlatpakSteamAvailability;
+
+/// Below this much free space the disk check warns.
+const LOW_DISK_SPACE: u64 = 1_000_000_000;
+
+/// Content and expected checksum of the file the hashing check writes.
+const HASH_PROBE: &[u8] = b"hello";
+const HASH_PROBE_CHECKSUM: &str = "F872A18EB88181EB00816510E762FEE6";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+    /// Not applicable here, e.g. no repository was given.
+    Skipped,
+}
+
+impl fmt::Display for CheckStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Pass => "PASS",
+            Self::Warn => "WARN",
+            Self::Fail => "FAIL",
+            Self::Skipped => "SKIP",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DoctorCheck {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl DoctorCheck {
+    fn new(name: &'static str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DoctorReport {
+    pub checks: Vec<DoctorCheck>,
+}
+
+impl DoctorReport {
+    /// True when no check failed; warnings and skipped checks still pass.
+    pub fn passed(&self) -> bool {
+        self.failures() == 0
+    }
+
+    pub fn failures(&self) -> usize {
+        self.checks
+            .iter()
+            .filter(|c| c.status == CheckStatus::Fail)
+            .count()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&DoctorCheck> {
+        self.checks.iter().find(|c| c.name == name)
+    }
+}
+
+/// Runs every check. `repo_url` and `local_path` are optional; the checks that need them are
+/// skipped when they are missing.
+pub async fn run_doctor(
+    repo_url: Option<&str>,
+    local_path: Option<&Utf8Path>,
+    net: &NetConfig,
+) -> DoctorReport {
+    let mut checks = Vec::new();
+
+    let client = fleet_infra::net::default_http_client(Some(net));
+    checks.push(match &client {
+        Ok(_) => DoctorCheck::new("http client", CheckStatus::Pass, "built"),
+        Err(e) => DoctorCheck::new("http client", CheckStatus::Fail, e.to_string()),
+    });
+    checks.push(check_repository(client.ok().as_ref(), repo_url, net).await);
+
+    match local_path {
+        Some(path) => {
+            checks.push(check_writable(path));
+            checks.push(check_disk_space(path));
+            checks.push(check_database(path));
+        }
+        None => {
+            for name in ["local path", "disk space", "database"] {
+                checks.push(DoctorCheck::new(
+                    name,
+                    CheckStatus::Skipped,
+                    "no path given",
+                ));
+            }
+        }
+    }
+
+    checks.push(check_flatpak());
+    checks.push(check_hashing());
+
+    DoctorReport { checks }
+}
+
+async fn check_repository(
+    client: Option<&reqwest::Client>,
+    repo_url: Option<&str>,
+    net: &NetConfig,
+) -> DoctorCheck {
+    const NAME: &str = "repository";
+    let Some(repo_url) = repo_url else {
+        return DoctorCheck::new(NAME, CheckStatus::Skipped, "no repository given");
+    };
+    if net.offline {
+        return DoctorCheck::new(NAME, CheckStatus::Skipped, "offline mode");
+    }
+    let url = match fleet_pipeline::sync::remote::normalize_repo_url(repo_url)
+        .map_err(|e| e.to_string())
+        .and_then(|base| {
+            reqwest::Url::parse(&base)
+                .and_then(|u| u.join("repo.json"))
+                .map_err(|e| e.to_string())
+        }) {
+        Ok(url) => url,
+        Err(e) => return DoctorCheck::new(NAME, CheckStatus::Fail, e),
+    };
+
+    if url.scheme() == "file" {
+        return match url.to_file_path() {
+            Ok(path) if path.is_file() => DoctorCheck::new(
+                NAME,
+                CheckStatus::Pass,
+                format!("{} exists", path.display()),
+            ),
+            _ => DoctorCheck::new(NAME, CheckStatus::Fail, format!("{url} not found")),
+        };
+    }
+
+    let Some(client) = client else {
+        return DoctorCheck::new(NAME, CheckStatus::Skipped, "no HTTP client");
+    };
+    match client.head(url.clone()).send().await {
+        Ok(resp) if resp.status().is_success() => DoctorCheck::new(
+            NAME,
+            CheckStatus::Pass,
+            format!("HEAD {url}: {}", resp.status()),
+        ),
+        Ok(resp) => DoctorCheck::new(
+            NAME,
+            CheckStatus::Fail,
+            format!("HEAD {url}: {}", resp.status()),
+        ),
+        Err(e) => DoctorCheck::new(NAME, CheckStatus::Fail, format!("HEAD {url}: {e}")),
+    }
+}
+
+fn check_writable(path: &Utf8Path) -> DoctorCheck {
+    const NAME: &str = "local path";
+    if !path.exists() {
+        return DoctorCheck::new(
+            NAME,
+            CheckStatus::Warn,
+            format!("{path} does not exist yet; sync creates it"),
+        );
+    }
+    let probe = path.join(format!(".fleet-doctor-{}", std::process::id()));
+    let result = std::fs::write(&probe, b"fleet");
+    let _ = std::fs::remove_file(&probe);
+    match result {
+        Ok(()) => DoctorCheck::new(NAME, CheckStatus::Pass, format!("{path} is writable")),
+        Err(e) => DoctorCheck::new(NAME, CheckStatus::Fail, format!("{path}: {e}")),
+    }
+}
+
+fn check_disk_space(path: &Utf8Path) -> DoctorCheck {
+    const NAME: &str = "disk space";
+    let Some(existing) = path.ancestors().find(|p| p.exists()) else {
+        return DoctorCheck::new(NAME, CheckStatus::Skipped, format!("{path} not found"));
+    };
+    match fs2::available_space(existing.as_std_path()) {
+        Ok(bytes) => {
+            let detail = format!("{:.1} GB free on {existing}", bytes as f64 / 1e9);
+            let status = if bytes < LOW_DISK_SPACE {
+                CheckStatus::Warn
+            } else {
+                CheckStatus::Pass
+            };
+            DoctorCheck::new(NAME, status, detail)
+        }
+        Err(e) => DoctorCheck::new(NAME, CheckStatus::Fail, format!("{existing}: {e}")),
+    }
+}
+
+fn check_database(path: &Utf8Path) -> DoctorCheck {
+    const NAME: &str = "database";
+    // Only reads: the doctor reports problems, it does not fix or move anything.
+    let (status, detail) = match RedbFleetDataStore.probe(path) {
+        Ok(DbState::Valid) => (CheckStatus::Pass, "fleet.redb opens".to_string()),
+        Ok(DbState::Missing) => (
+            CheckStatus::Warn,
+            "no fleet.redb yet; sync or repair creates it".to_string(),
+        ),
+        Ok(DbState::Busy) => (
+            CheckStatus::Warn,
+            "fleet.redb is open in another Fleet instance".to_string(),
+        ),
+        Ok(DbState::Corrupt) => (
+            CheckStatus::Fail,
+            "fleet.redb is corrupt; run repair".to_string(),
+        ),
+        Ok(DbState::NewerSchema { found, supported }) => (
+            CheckStatus::Fail,
+            format!("fleet.redb is from a newer Fleet (schema {found}, supported {supported})"),
+        ),
+        Err(e) => (CheckStatus::Fail, e.to_string()),
+    };
+    DoctorCheck::new(NAME, status, detail)
+}
+
+fn check_flatpak() -> DoctorCheck {
+    const NAME: &str = "flatpak steam";
+    if !cfg!(target_os = "linux") {
+        return DoctorCheck::new(NAME, CheckStatus::Skipped, "Flatpak is only used on Linux");
+    }
+    match crate::app::detect_flatpak_steam_availability() {
+        FlatpakSteamAvailability::Available => DoctorCheck::new(
+            NAME,
+            CheckStatus::Pass,
+            "com.valvesoftware.Steam is installed",
+        ),
+        // Only needed for Flatpak launches, so a missing install is not a failure.
+        FlatpakSteamAvailability::Unavailable(reason) => {
+            DoctorCheck::new(NAME, CheckStatus::Warn, reason)
+        }
+        FlatpakSteamAvailability::Unknown => {
+            DoctorCheck::new(NAME, CheckStatus::Skipped, "not checked")
+        }
+    }
+}
+
+fn check_hashing() -> DoctorCheck {
+    const NAME: &str = "hashing";
+    let dir = std::env::temp_dir();
+    let Ok(dir) = Utf8PathBuf::from_path_buf(dir) else {
+        return DoctorCheck::new(NAME, CheckStatus::Skipped, "temp dir is not UTF-8");
+    };
+    let probe = dir.join(format!("fleet-doctor-{}.txt", std::process::id()));
+    let result = std::fs::write(&probe, HASH_PROBE)
+        .map_err(|e| e.to_string())
+        .and_then(|()| {
+            fleet_infra::hashing::compute_file_checksum(&probe, Utf8Path::new("doctor.txt"))
+                .map_err(|e| e.to_string())
+        });
+    let _ = std::fs::remove_file(&probe);
+    match result {
+        Ok(sum) if sum == HASH_PROBE_CHECKSUM => {
+            DoctorCheck::new(NAME, CheckStatus::Pass, "checksums match")
+        }
+        Ok(sum) => DoctorCheck::new(
+            NAME,
+            CheckStatus::Fail,
+            format!("expected {HASH_PROBE_CHECKSUM}, got {sum}"),
+        ),
+        Err(e) => DoctorCheck::new(NAME, CheckStatus::Fail, e),
+    }
+}
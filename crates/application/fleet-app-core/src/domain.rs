@@ -1,7 +1,11 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
 
 use crate::pipeline::PipelineState;
+pub use fleet_pipeline::DownloadOrder;
 use fleet_scanner::ScanStats;
 
 pub type ProfileId = String;
@@ -24,14 +28,205 @@ fn default_launch_template() -> String {
     STEAM_LAUNCH_TEMPLATE.to_string()
 }
 
+/// Number of completed scans kept per profile.
+pub const SCAN_HISTORY_LEN: usize = 20;
+
+/// Number of recent sync download rates kept per profile.
+pub const THROUGHPUT_HISTORY_LEN: usize = 5;
+/// Changed paths kept per mod in [`Profile::last_changes`].
+pub const LAST_CHANGES_SAMPLE: usize = 10;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanRecord {
+    pub at: DateTime<Utc>,
+    pub stats: ScanStats,
+}
+
+/// How a profile authenticates to its repositories. The password or token itself is kept in
+/// the OS keyring, never in profiles.json.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RepoAuthKind {
+    Basic { username: String },
+    Bearer,
+}
+
+/// Download settings a profile uses instead of the global ones; `None` inherits
+/// [`AppSettings`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkOverrides {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_threads: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speed_limit_enabled: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_speed_bytes: Option<u64>,
+}
+
+impl NetworkOverrides {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Profile {
     pub id: ProfileId,
     pub name: String,
     pub repo_url: String,
+    /// Additional repositories merged with `repo_url` (e.g. a mission/server repo).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_repos: Vec<String>,
+    /// Credentials sent to every repository of this profile; `None` for public repositories.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo_auth: Option<RepoAuthKind>,
     pub local_path: String,
     pub last_synced: Option<DateTime<Utc>>,
     pub last_scan: Option<ScanStats>,
+    /// Completed scans, oldest first, capped at [`SCAN_HISTORY_LEN`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scan_history: Vec<ScanRecord>,
+    /// Average download rates (bytes/s) of recent syncs, oldest first, capped at
+    /// [`THROUGHPUT_HISTORY_LEN`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub throughput_history: Vec<u64>,
+    /// Launch parameters used instead of `AppSettings.launch_params` for this profile.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub launch_params_override: Option<String>,
+    /// Explicit `-mod=` load order (folder names); unlisted mods load after these.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mod_order: Vec<String>,
+    /// Mods kept on disk but left out of `-mod=` when launching or joining.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disabled_mods: Vec<String>,
+    /// Mods a sync never downloads, verifies or deletes; they may still be loaded.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unsynced_mods: Vec<String>,
+    /// Glob patterns (e.g. `@mod/userconfig/**`) of files a sync never deletes or overwrites.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub excluded_paths: Vec<String>,
+    /// repo.json checksum the profile is held at; syncs refuse any other until it is unpinned.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pinned_repo_checksum: Option<String>,
+    /// What the latest completed sync changed on disk: counts and a few paths per mod (see
+    /// [`LAST_CHANGES_SAMPLE`]), so a large sync does not bloat profiles.json.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_changes: Option<fleet_pipeline::ChangeSummary>,
+    /// SSE endpoint the repository publishes change notifications on; each one triggers a
+    /// remote check. `None` leaves checking manual.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub live_notify_url: Option<String>,
+    /// Threads and speed limit used instead of the global settings for this profile.
+    #[serde(default, skip_serializing_if = "NetworkOverrides::is_empty")]
+    pub network: NetworkOverrides,
+}
+
+impl Profile {
+    /// Extra repositories with blank entries dropped.
+    pub fn extra_repo_urls(&self) -> Vec<String> {
+        self.extra_repos
+            .iter()
+            .map(|r| r.trim())
+            .filter(|r| !r.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Exclusion globs with blank entries dropped.
+    pub fn exclude_patterns(&self) -> Vec<String> {
+        self.excluded_paths
+            .iter()
+            .map(|p| p.trim())
+            .filter(|p| !p.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// The profile's launch parameters, falling back to `global` when no override is set.
+    pub fn launch_params<'a>(&'a self, global: &'a str) -> &'a str {
+        match self.launch_params_override.as_deref().map(str::trim) {
+            Some(params) if !params.is_empty() => params,
+            _ => global.trim(),
+        }
+    }
+
+    /// Reads the repository credentials from the OS keyring; `None` when none are configured.
+    pub fn load_repo_auth(&self) -> anyhow::Result<Option<fleet_infra::RepoAuth>> {
+        let Some(kind) = &self.repo_auth else {
+            return Ok(None);
+        };
+        let key = fleet_infra::credentials::repo_key(&self.id);
+        let secret = fleet_infra::credentials::load_password(&key)?.ok_or_else(|| {
+            anyhow::anyhow!(
+                "Repository credentials for '{}' are missing from the keyring",
+                self.name
+            )
+        })?;
+        Ok(Some(match kind {
+            RepoAuthKind::Basic { username } => fleet_infra::RepoAuth::Basic {
+                username: username.clone(),
+                password: secret,
+            },
+            RepoAuthKind::Bearer => fleet_infra::RepoAuth::Bearer(secret),
+        }))
+    }
+
+    /// Whether `mod_name` is passed to the game on launch.
+    pub fn loads_mod(&self, mod_name: &str) -> bool {
+        !contains_mod(&self.disabled_mods, mod_name)
+    }
+
+    /// Whether syncs manage `mod_name`.
+    pub fn syncs_mod(&self, mod_name: &str) -> bool {
+        !contains_mod(&self.unsynced_mods, mod_name)
+    }
+
+    pub fn set_mod_loaded(&mut self, mod_name: &str, loaded: bool) {
+        toggle_mod(&mut self.disabled_mods, mod_name, !loaded);
+    }
+
+    pub fn set_mod_synced(&mut self, mod_name: &str, synced: bool) {
+        toggle_mod(&mut self.unsynced_mods, mod_name, !synced);
+    }
+
+    /// Appends a completed scan, dropping the oldest entries beyond [`SCAN_HISTORY_LEN`].
+    pub fn record_scan(&mut self, at: DateTime<Utc>, stats: ScanStats) {
+        self.scan_history.push(ScanRecord { at, stats });
+        let excess = self.scan_history.len().saturating_sub(SCAN_HISTORY_LEN);
+        self.scan_history.drain(..excess);
+    }
+
+    /// Appends a sync's average download rate, dropping the oldest beyond
+    /// [`THROUGHPUT_HISTORY_LEN`].
+    pub fn record_throughput(&mut self, bps: u64) {
+        if bps == 0 {
+            return;
+        }
+        self.throughput_history.push(bps);
+        let excess = self
+            .throughput_history
+            .len()
+            .saturating_sub(THROUGHPUT_HISTORY_LEN);
+        self.throughput_history.drain(..excess);
+    }
+
+    /// Mean of the recorded sync download rates; `None` before the first sync.
+    pub fn average_throughput(&self) -> Option<u64> {
+        let count = self.throughput_history.len() as u64;
+        (count > 0).then(|| self.throughput_history.iter().sum::<u64>() / count)
+    }
+}
+
+// Mod folders are matched case-insensitively, like the diff does.
+fn contains_mod(list: &[String], mod_name: &str) -> bool {
+    list.iter().any(|m| m.eq_ignore_ascii_case(mod_name))
+}
+
+fn toggle_mod(list: &mut Vec<String>, mod_name: &str, present: bool) {
+    list.retain(|m| !m.eq_ignore_ascii_case(mod_name));
+    if present {
+        list.push(mod_name.to_string());
+    }
 }
 
 impl Default for Profile {
@@ -40,9 +235,22 @@ impl Default for Profile {
             id: String::new(),
             name: "New Profile".to_string(),
             repo_url: String::new(),
+            extra_repos: Vec::new(),
+            repo_auth: None,
             local_path: String::new(),
             last_synced: None,
             last_scan: None,
+            scan_history: Vec::new(),
+            throughput_history: Vec::new(),
+            launch_params_override: None,
+            mod_order: Vec::new(),
+            disabled_mods: Vec::new(),
+            unsynced_mods: Vec::new(),
+            excluded_paths: Vec::new(),
+            pinned_repo_checksum: None,
+            last_changes: None,
+            live_notify_url: None,
+            network: NetworkOverrides::default(),
         }
     }
 }
@@ -55,6 +263,174 @@ pub struct AppSettings {
     pub launch_params: String,
     #[serde(default = "default_launch_template")]
     pub launch_template: String,
+    /// Proxy URL for all repository traffic; empty means a direct connection.
+    #[serde(default)]
+    pub proxy: String,
+    /// Bearer token sent as `Authorization` header; empty means no auth.
+    #[serde(default)]
+    pub auth_token: String,
+    /// Sent as `User-Agent` instead of `fleet/<version>`; also stops sending the version
+    /// header. Empty keeps the default.
+    #[serde(default)]
+    pub user_agent: String,
+    #[serde(default = "default_connect_timeout_secs")]
+    pub connect_timeout_secs: u64,
+    /// Abort a request after this many seconds without receiving data.
+    #[serde(default = "default_read_timeout_secs")]
+    pub read_timeout_secs: u64,
+    /// Retry a download after this many seconds without progress; 0 disables the check.
+    #[serde(default = "default_stall_timeout_secs")]
+    pub stall_timeout_secs: u64,
+    /// Downloads open to one host at a time, for hosts that cap connections per IP; 0 only
+    /// applies `max_threads`.
+    #[serde(default)]
+    pub max_connections_per_host: usize,
+    /// Give up on a repository host after this many consecutive failed requests instead of
+    /// retrying every file; 0 never gives up.
+    #[serde(default = "default_max_host_failures")]
+    pub max_host_failures: usize,
+    /// Which files are downloaded first.
+    #[serde(default)]
+    pub download_order: DownloadOrder,
+    /// Parallel Range requests per large file; 0 or 1 downloads every file in one stream.
+    #[serde(default)]
+    pub download_segments: usize,
+    /// Files from this size (MB) on are split when `download_segments` is above 1.
+    #[serde(default = "default_segment_min_size_mb")]
+    pub segment_min_size_mb: u64,
+    /// Work from local state only: checks skip the repository and syncs refuse to download.
+    #[serde(default)]
+    pub offline: bool,
+    /// Never delete files or mods that are not in the repository; syncs run with
+    /// [`fleet_pipeline::SyncOptions::skip_deletes`], as `--no-delete` does on the CLI.
+    #[serde(default)]
+    pub preserve_unmanaged: bool,
+    /// On start, pre-read the selected profile's files and scan cache in the background so
+    /// the first check is fast.
+    #[serde(default)]
+    pub warm_cache_on_start: bool,
+    /// Hashing threads for local scans; 0 uses all cores but one.
+    #[serde(default)]
+    pub scan_threads: usize,
+    /// Scan one file at a time in path order, for spinning disks and network drives where
+    /// parallel reads are slower. Overrides `scan_threads`.
+    #[serde(default)]
+    pub sequential_scan: bool,
+    /// Minimum level written to the console and log file (`error` .. `trace`).
+    #[serde(default = "default_log_level")]
+    pub log_level: String,
+    /// Extra environment variables for the launched game (e.g. `STEAM_COMPAT_DATA_PATH`).
+    #[serde(default)]
+    pub launch_env: Vec<(String, String)>,
+    /// Working directory for the launched process; empty derives it from the template.
+    #[serde(default)]
+    pub launch_working_dir: String,
+    /// Folder where downloads are staged before being moved into the mods folder; empty
+    /// writes them next to their targets.
+    #[serde(default)]
+    pub download_temp_dir: String,
+    /// Another install to copy matching files from before downloading; empty disables it.
+    #[serde(default)]
+    pub local_source_dir: String,
+    /// Syncs downloading at least this many MB ask before cancelling; 0 cancels instantly.
+    #[serde(default = "default_cancel_confirm_mb")]
+    pub cancel_confirm_mb: u64,
+    /// Launch the profile's game as soon as a sync completes successfully.
+    #[serde(default)]
+    pub auto_launch_after_sync: bool,
+    /// Re-hash every downloaded file once a sync finishes and warn about any mismatch.
+    #[serde(default)]
+    pub verify_after_sync: bool,
+    /// Checksum each file as it downloads; off trusts sizes alone (trusted LAN mirrors only).
+    #[serde(default = "default_verify_downloads")]
+    pub verify_downloads: bool,
+    /// Read the local database back after each sync and fail the sync if it did not save.
+    #[serde(default = "default_verify_baseline")]
+    pub verify_baseline: bool,
+    /// Stamp downloads with the modification time the repository lists, when it lists one.
+    #[serde(default = "default_preserve_mtimes")]
+    pub preserve_mtimes: bool,
+    /// Check and sync the rest of a repository when some of its mods fail to load.
+    #[serde(default)]
+    pub skip_unreachable_mods: bool,
+    /// Speed limits by time of day, first match wins; outside every window the fixed limit
+    /// above applies.
+    #[serde(default)]
+    pub bandwidth_schedule: Vec<BandwidthWindow>,
+    /// Automatic rechecks wait until no new request has come in for this many seconds, and
+    /// are skipped if a check found nothing to do this recently. Manual checks ignore it.
+    #[serde(default = "default_recheck_quiet_period_secs")]
+    pub recheck_quiet_period_secs: u64,
+}
+
+/// A download speed limit for part of the day, in local time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BandwidthWindow {
+    /// `HH:MM`.
+    pub start: String,
+    /// `HH:MM`; a window that ends before it starts runs past midnight.
+    pub end: String,
+    /// Bytes per second; 0 is unlimited.
+    pub max_speed_bytes: u64,
+}
+
+impl BandwidthWindow {
+    /// Whether `time` falls in `[start, end)`; windows with unparsable times never match.
+    pub fn contains(&self, time: chrono::NaiveTime) -> bool {
+        let parse = |s: &str| chrono::NaiveTime::parse_from_str(s.trim(), "%H:%M").ok();
+        let (Some(start), Some(end)) = (parse(&self.start), parse(&self.end)) else {
+            return false;
+        };
+        if start <= end {
+            start <= time && time < end
+        } else {
+            time >= start || time < end
+        }
+    }
+}
+
+fn default_preserve_mtimes() -> bool {
+    true
+}
+
+fn default_verify_downloads() -> bool {
+    true
+}
+
+fn default_verify_baseline() -> bool {
+    true
+}
+
+fn default_cancel_confirm_mb() -> u64 {
+    1024
+}
+
+fn default_log_level() -> String {
+    "info".to_string()
+}
+
+fn default_connect_timeout_secs() -> u64 {
+    fleet_infra::net::DEFAULT_CONNECT_TIMEOUT.as_secs()
+}
+
+fn default_read_timeout_secs() -> u64 {
+    fleet_infra::net::DEFAULT_READ_TIMEOUT.as_secs()
+}
+
+fn default_stall_timeout_secs() -> u64 {
+    fleet_infra::net::DEFAULT_STALL_TIMEOUT.as_secs()
+}
+
+fn default_max_host_failures() -> usize {
+    fleet_infra::net::DEFAULT_MAX_HOST_FAILURES
+}
+
+fn default_recheck_quiet_period_secs() -> u64 {
+    10
+}
+
+fn default_segment_min_size_mb() -> u64 {
+    fleet_infra::net::DEFAULT_SEGMENT_MIN_SIZE / (1024 * 1024)
 }
 
 impl Default for AppSettings {
@@ -65,6 +441,166 @@ impl Default for AppSettings {
             max_speed_bytes: fleet_config::DEFAULT_SPEED_LIMIT_BYTES,
             launch_params: "-noPause -noSplash -skipIntro -noLauncher".to_string(),
             launch_template: default_launch_template(),
+            proxy: String::new(),
+            auth_token: String::new(),
+            user_agent: String::new(),
+            connect_timeout_secs: default_connect_timeout_secs(),
+            read_timeout_secs: default_read_timeout_secs(),
+            stall_timeout_secs: default_stall_timeout_secs(),
+            max_connections_per_host: 0,
+            max_host_failures: default_max_host_failures(),
+            download_order: DownloadOrder::AsPlanned,
+            download_segments: 0,
+            segment_min_size_mb: default_segment_min_size_mb(),
+            offline: false,
+            preserve_unmanaged: false,
+            warm_cache_on_start: false,
+            scan_threads: 0,
+            sequential_scan: false,
+            log_level: default_log_level(),
+            launch_env: Vec::new(),
+            launch_working_dir: String::new(),
+            download_temp_dir: String::new(),
+            local_source_dir: String::new(),
+            cancel_confirm_mb: default_cancel_confirm_mb(),
+            auto_launch_after_sync: false,
+            verify_after_sync: false,
+            verify_downloads: default_verify_downloads(),
+            verify_baseline: default_verify_baseline(),
+            preserve_mtimes: default_preserve_mtimes(),
+            skip_unreachable_mods: false,
+            bandwidth_schedule: Vec::new(),
+            recheck_quiet_period_secs: default_recheck_quiet_period_secs(),
+        }
+    }
+}
+
+impl AppSettings {
+    /// `scan_threads` as a scanner limit (`None` for the default).
+    pub fn scan_thread_limit(&self) -> Option<usize> {
+        (self.scan_threads > 0).then_some(self.scan_threads)
+    }
+
+    /// Download limit at `time` of day: the first scheduled window containing it, else the
+    /// fixed limit. `None` is unlimited.
+    pub fn rate_limit_at(&self, time: chrono::NaiveTime) -> Option<u64> {
+        match self.bandwidth_schedule.iter().find(|w| w.contains(time)) {
+            Some(window) => (window.max_speed_bytes > 0).then_some(window.max_speed_bytes),
+            None => self.speed_limit_enabled.then_some(self.max_speed_bytes),
+        }
+    }
+
+    /// These settings with `profile`'s network overrides applied. Bandwidth schedule windows
+    /// still take precedence over the speed limit.
+    pub fn for_profile(&self, profile: &Profile) -> AppSettings {
+        let overrides = &profile.network;
+        AppSettings {
+            max_threads: overrides.max_threads.unwrap_or(self.max_threads),
+            speed_limit_enabled: overrides
+                .speed_limit_enabled
+                .unwrap_or(self.speed_limit_enabled),
+            max_speed_bytes: overrides.max_speed_bytes.unwrap_or(self.max_speed_bytes),
+            ..self.clone()
+        }
+    }
+
+    pub fn recheck_quiet_period(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.recheck_quiet_period_secs)
+    }
+
+    /// `download_temp_dir` as a staging folder (`None` when blank).
+    pub fn download_temp_dir(&self) -> Option<camino::Utf8PathBuf> {
+        let dir = self.download_temp_dir.trim();
+        (!dir.is_empty()).then(|| camino::Utf8PathBuf::from(dir))
+    }
+
+    /// `local_source_dir` as a copy source (`None` when blank).
+    pub fn local_source_dir(&self) -> Option<camino::Utf8PathBuf> {
+        let dir = self.local_source_dir.trim();
+        (!dir.is_empty()).then(|| camino::Utf8PathBuf::from(dir))
+    }
+
+    /// `max_connections_per_host` as a downloader cap (`None` when 0).
+    pub fn per_host_limit(&self) -> Option<usize> {
+        (self.max_connections_per_host > 0).then_some(self.max_connections_per_host)
+    }
+
+    /// `download_segments` and `segment_min_size_mb` as a downloader setting (`None` when
+    /// files are not split).
+    pub fn segmented_downloads(&self) -> Option<fleet_infra::SegmentedDownloads> {
+        (self.download_segments > 1).then(|| fleet_infra::SegmentedDownloads {
+            min_size: self.segment_min_size_mb * 1024 * 1024,
+            segments: self.download_segments,
+        })
+    }
+
+    /// `max_host_failures` as a breaker threshold (`None` when 0).
+    pub fn max_host_failures(&self) -> Option<usize> {
+        (self.max_host_failures > 0).then_some(self.max_host_failures)
+    }
+
+    /// `stall_timeout_secs` as a duration (`None` when 0).
+    pub fn stall_timeout(&self) -> Option<Duration> {
+        (self.stall_timeout_secs > 0).then(|| Duration::from_secs(self.stall_timeout_secs))
+    }
+
+    /// Sync options for `profile` under these settings. Rate limits are left unset; syncs
+    /// that download fill them in from [`Self::rate_limit_at`].
+    pub fn sync_options(&self, profile: &Profile) -> fleet_pipeline::SyncOptions {
+        fleet_pipeline::SyncOptions {
+            max_threads: self.max_threads,
+            max_connections_per_host: self.per_host_limit(),
+            download_order: self.download_order,
+            segmented_downloads: self.segmented_downloads(),
+            rate_limit_bytes: None,
+            rate_limit: None,
+            cache_root: None,
+            skip_deletes: self.preserve_unmanaged,
+            verify_checks: false,
+            verify_downloads: self.verify_downloads,
+            verify_baseline: self.verify_baseline,
+            delete_threshold: Some(fleet_core::DeleteThreshold::default()),
+            offline: self.offline,
+            scan_threads: self.scan_thread_limit(),
+            sequential_scan: self.sequential_scan,
+            temp_dir: self.download_temp_dir(),
+            local_source: self.local_source_dir(),
+            preserve_mtimes: self.preserve_mtimes,
+            stall_timeout: self.stall_timeout(),
+            skip_mods: profile.unsynced_mods.clone(),
+            exclude: profile.exclude_patterns(),
+            pinned_repo_checksum: profile.pinned_repo_checksum.clone(),
+            skip_unreachable_mods: self.skip_unreachable_mods,
+            max_host_failures: self.max_host_failures(),
+        }
+    }
+
+    /// [`Self::net_config`] with `profile`'s repository credentials in place of the global
+    /// auth token.
+    pub fn net_config_for(&self, profile: &Profile) -> anyhow::Result<fleet_infra::NetConfig> {
+        let config = self.net_config();
+        Ok(match profile.load_repo_auth()? {
+            Some(auth) => config.with_repo_auth(&auth),
+            None => config,
+        })
+    }
+
+    pub fn net_config(&self) -> fleet_infra::NetConfig {
+        let proxy = self.proxy.trim();
+        let user_agent = self.user_agent.trim();
+        let config = fleet_infra::NetConfig {
+            proxy: (!proxy.is_empty()).then(|| proxy.to_string()),
+            user_agent: (!user_agent.is_empty()).then(|| user_agent.to_string()),
+            connect_timeout: Some(Duration::from_secs(self.connect_timeout_secs.max(1))),
+            read_timeout: Some(Duration::from_secs(self.read_timeout_secs.max(1))),
+            offline: self.offline,
+            ..Default::default()
+        };
+        let token = self.auth_token.trim();
+        if token.is_empty() {
+            config
+        } else {
+            config.with_bearer_token(token)
         }
     }
 }
@@ -74,6 +610,8 @@ pub enum Route {
     ProfileHub,
     ProfileDashboard(ProfileId),
     ProfileEditor(ProfileId),
+    /// Every mod and file of the profile with its sync status.
+    ProfileTree(ProfileId),
     Settings,
 }
 
@@ -84,6 +622,42 @@ pub enum BootState {
     Failed(String),
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CacheWarmup {
+    Running,
+    Ready,
+    Failed(String),
+}
+
+/// Outcome of a profile's latest remote check, shown as a badge in the profile list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UpdateStatus {
+    /// The live-update endpoint reported a change; a check starts once the pipeline is idle.
+    ChangedUpstream,
+    Checking,
+    UpToDate,
+    Updates {
+        downloads: usize,
+        bytes: u64,
+    },
+    Failed(String),
+}
+
+/// The FILES screen's tree for one profile; `mods` is `None` when its database could not be
+/// read.
+#[derive(Debug, Clone)]
+pub struct ProfileTree {
+    pub profile_id: ProfileId,
+    pub mods: Option<Arc<Vec<crate::viewmodel::ModNode>>>,
+}
+
+/// Latest A2S answer from a repo.json server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServerStatus {
+    Online(fleet_infra::a2s::ServerInfo),
+    Offline,
+}
+
 #[derive(Debug, Clone)]
 pub struct AppState {
     pub boot: BootState,
@@ -98,7 +672,37 @@ pub struct AppState {
     pub editor_draft: Option<Profile>,
 
     pub pipeline: PipelineState,
-    pub last_plan: Option<fleet_core::SyncPlan>,
+    /// Shared so that view models and state snapshots don't copy large plans.
+    pub last_plan: Option<Arc<fleet_core::SyncPlan>>,
+    /// Latest signature warnings per profile, refreshed after each local scan.
+    pub signature_warnings: HashMap<ProfileId, Vec<String>>,
+    /// repo.json checksum seen by the latest fetch per profile.
+    pub repo_checksums: HashMap<ProfileId, String>,
+    /// Background cache warm-up per profile; cleared when a check or sync starts.
+    pub cache_warmup: HashMap<ProfileId, CacheWarmup>,
+    /// A cancel of a large sync is waiting for the user to confirm it.
+    pub cancel_pending: bool,
+    /// A finished sync is about to launch this profile's game (`auto_launch_after_sync`).
+    pub auto_launch: Option<ProfileId>,
+    /// Latest query result per server, keyed by `host:port` (the game port).
+    pub server_status: HashMap<String, ServerStatus>,
+    /// Result of the latest remote check per profile, from CHECK or "check all".
+    pub update_status: HashMap<ProfileId, UpdateStatus>,
+    /// Built when the FILES screen opens and after runs that change its baseline or plan,
+    /// rather than every frame.
+    pub profile_tree: Option<ProfileTree>,
+    /// Whether the edited profile has a join server password in the keyring; looked up when
+    /// the editor opens and updated when it is saved or forgotten, not asked every frame.
+    pub server_password_saved: Option<(ProfileId, bool)>,
+    /// Corrupt `fleet.redb` copies in the open dashboard's mods folder; counted when the
+    /// dashboard opens, after runs and after a delete rather than every frame.
+    pub quarantined_dbs: Option<(ProfileId, usize)>,
+    /// `servers` from the open dashboard's repo.json, read when the dashboard opens and after
+    /// runs (which may replace the file) rather than every frame.
+    pub repo_servers: Option<(ProfileId, Vec<fleet_core::repo::Server>)>,
+    /// Mod folders in the open dashboard's mods folder, for its load/sync toggles; listed
+    /// when the dashboard opens and after runs rather than every frame.
+    pub local_mods: Option<(ProfileId, Vec<String>)>,
 }
 
 impl Default for AppState {
@@ -114,6 +718,18 @@ impl Default for AppState {
             editor_draft: None,
             pipeline: PipelineState::idle(),
             last_plan: None,
+            signature_warnings: HashMap::new(),
+            repo_checksums: HashMap::new(),
+            cache_warmup: HashMap::new(),
+            cancel_pending: false,
+            auto_launch: None,
+            server_status: HashMap::new(),
+            update_status: HashMap::new(),
+            profile_tree: None,
+            server_password_saved: None,
+            quarantined_dbs: None,
+            repo_servers: None,
+            local_mods: None,
         }
     }
 }
@@ -147,7 +147,9 @@ where
                         }
                     }
 
-                    let validate_res = self.sync.validate_repo_url_blocking(&draft.repo_url);
+                    let validate_res = std::iter::once(draft.repo_url.clone())
+                        .chain(draft.extra_repo_urls())
+                        .try_for_each(|url| self.sync.validate_repo_url_blocking(&url));
                     if let Err(e) = validate_res {
                         self.store.apply(DomainEvent::UserError(format!(
                             "Repository URL validation failed: {e}"
@@ -234,7 +236,7 @@ where
                 if let Some(profile) = snapshot.profiles.iter().find(|p| p.id == profile_id) {
                     let _ = self.launcher.launch(
                         "",
-                        &snapshot.settings.launch_params,
+                        profile.launch_params(&snapshot.settings.launch_params),
                         &snapshot.settings.launch_template,
                         &[],
                     );
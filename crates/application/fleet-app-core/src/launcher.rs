@@ -1,3 +1,4 @@
+use crate::domain::AppSettings;
 use camino::Utf8PathBuf;
 use fleet_infra::launcher::Launcher;
 
@@ -18,14 +19,23 @@ impl LauncherImpl {
         &self,
         exe_path: &str,
         params: &str,
-        template: &str,
+        settings: &AppSettings,
         mods: &[Utf8PathBuf],
     ) -> anyhow::Result<()> {
+        let env = settings
+            .launch_env
+            .iter()
+            .filter(|(key, _)| !key.trim().is_empty())
+            .map(|(key, value)| (key.trim().to_string(), value.clone()))
+            .collect();
+        let working_dir = settings.launch_working_dir.trim();
         let launcher = Launcher::new(
             exe_path.to_string(),
             params.to_string(),
-            template.to_string(),
-        );
+            settings.launch_template.clone(),
+        )
+        .with_env(env)
+        .with_working_dir((!working_dir.is_empty()).then(|| working_dir.into()));
         launcher.launch(mods.to_vec())?;
         Ok(())
     }
@@ -1,6 +1,8 @@
 pub mod app;
 pub mod app_core;
 mod async_runtime;
+pub mod debounce;
+pub mod doctor;
 pub mod domain;
 pub mod kernel;
 pub mod launcher;
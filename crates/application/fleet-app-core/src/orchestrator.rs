@@ -7,20 +7,32 @@ use tokio::time::interval;
 use tokio_util::sync::CancellationToken;
 
 use crate::app_core::DomainEvent;
-use crate::domain::{AppSettings, Profile};
+use crate::domain::{AppSettings, CacheWarmup, Profile};
 use crate::pipeline::{PipelineRunEvent, PipelineRunId, PipelineStep, StepStatus};
 use crate::ports::SyncPipelinePort;
 
 use fleet_core::SyncPlan;
 use fleet_persistence::{DbState, FleetDataStore, RedbFleetDataStore};
 use fleet_pipeline::{
-    DefaultSyncEngine, ProgressTracker, SyncMode, SyncOptions, SyncRequest, TransferSnapshot,
+    DefaultSyncEngine, FailedCheck, ProgressTracker, SyncError, SyncMode, SyncOptions, SyncRequest,
+    TransferSnapshot,
 };
 
+/// Runs started within this window of each other coalesce into the last one.
+const RESTART_DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// Cadence of transfer progress events while a sync runs; UIs can poll at the same rate.
+pub const PROGRESS_TICK: Duration = Duration::from_millis(100);
+
+/// How often a running sync re-reads the bandwidth schedule.
+pub const SCHEDULE_TICK: Duration = Duration::from_secs(30);
+
 pub struct PipelineOrchestrator {
     engine: Arc<DefaultSyncEngine>,
     tx: mpsc::Sender<DomainEvent>,
     cancel: Option<CancellationToken>,
+    /// Held by the running worker for its whole run.
+    run_lock: Arc<tokio::sync::Mutex<()>>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -36,9 +48,27 @@ impl PipelineOrchestrator {
             engine,
             tx,
             cancel: None,
+            run_lock: Arc::new(tokio::sync::Mutex::new(())),
         }
     }
 
+    /// Swap the engine used by future runs (e.g. after network settings change).
+    pub fn set_engine(&mut self, engine: Arc<DefaultSyncEngine>) {
+        self.engine = engine;
+    }
+
+    /// The shared engine, or a dedicated one when `profile` has repository credentials.
+    fn engine_for(
+        &self,
+        profile: &Profile,
+        settings: &AppSettings,
+    ) -> anyhow::Result<Arc<DefaultSyncEngine>> {
+        if profile.repo_auth.is_none() {
+            return Ok(self.engine.clone());
+        }
+        Ok(Arc::new(profile_engine(settings, profile)?))
+    }
+
     pub fn cancel(&mut self) {
         if let Some(token) = self.cancel.take() {
             token.cancel();
@@ -79,12 +109,23 @@ impl PipelineOrchestrator {
         run_id: PipelineRunId,
         kind: CheckKind,
     ) -> anyhow::Result<()> {
+        let settings = settings.for_profile(&profile);
+        // Offline there is nothing to compare against but the last synced state.
+        let kind = match kind {
+            CheckKind::RemoteUpdate if settings.offline => CheckKind::LocalIntegrity,
+            other => other,
+        };
+        // A local check never talks to the repository, so it needs no credentials.
+        let engine = match kind {
+            CheckKind::LocalIntegrity => self.engine.clone(),
+            _ => self.engine_for(&profile, &settings)?,
+        };
         self.cancel();
         let token = CancellationToken::new();
         self.cancel = Some(token.clone());
+        let run_lock = self.run_lock.clone();
 
         let tx = self.tx.clone();
-        let engine = self.engine.clone();
 
         let thread_name = match kind {
             CheckKind::LocalIntegrity => "fleet-check-local",
@@ -109,6 +150,9 @@ impl PipelineOrchestrator {
                 };
 
                 rt.block_on(async move {
+                    let Some(_run) = supersede(run_lock, &token).await else {
+                        return;
+                    };
                     let _ = tx
                         .send(DomainEvent::PipelineEvent {
                             run_id,
@@ -187,14 +231,11 @@ impl PipelineOrchestrator {
                         CheckKind::Repair => SyncMode::SmartVerify,
                     };
 
-                    let options = SyncOptions {
-                        max_threads: settings.max_threads,
-                        rate_limit_bytes: None,
-                        cache_root: None,
-                    };
+                    let options = settings.sync_options(&profile);
 
                     let req = SyncRequest {
                         repo_url: profile.repo_url.clone(),
+                        extra_repo_urls: profile.extra_repo_urls(),
                         local_root,
                         mode,
                         options,
@@ -225,7 +266,7 @@ impl PipelineOrchestrator {
                             let _ = tx.send(DomainEvent::PipelineEvent { run_id, ev: PipelineRunEvent::Cancelled }).await;
                             return;
                         }
-                        res = engine.scan_local_state(&req, Some(on_progress)) => res
+                        res = engine.scan_local_state(&req, Some(on_progress), Some(&token)) => res
                     };
 
                     let local_state = match local_res {
@@ -240,6 +281,23 @@ impl PipelineOrchestrator {
                                     },
                                 })
                                 .await;
+                            let warnings = signature_warnings(req.local_root.clone()).await;
+                            let _ = tx
+                                .send(DomainEvent::PipelineEvent {
+                                    run_id,
+                                    ev: PipelineRunEvent::SignatureWarnings { warnings },
+                                })
+                                .await;
+                            if !s.warnings.is_empty() {
+                                let _ = tx
+                                    .send(DomainEvent::PipelineEvent {
+                                        run_id,
+                                        ev: PipelineRunEvent::StorageWarnings {
+                                            warnings: s.warnings.clone(),
+                                        },
+                                    })
+                                    .await;
+                            }
                             s
                         }
                         Err(e) => {
@@ -304,12 +362,14 @@ impl PipelineOrchestrator {
                             match plan_res {
                                 Ok(plan) => {
                                     let diff_stats = (plan.downloads.len(), plan.deletes.len());
+                                    let risk = engine.assess_plan(&plan, &local_state, &req);
                                     let _ = tx
                                         .send(DomainEvent::PipelineEvent {
                                             run_id,
                                             ev: PipelineRunEvent::PlanReady {
                                                 plan,
                                                 diff_stats,
+                                                risk,
                                                 existing_mods: existing_mods(),
                                             },
                                         })
@@ -358,10 +418,27 @@ impl PipelineOrchestrator {
                                     ev: PipelineRunEvent::StepChanged {
                                         step: PipelineStep::Fetch,
                                         status: StepStatus::Succeeded,
-                                        detail: "Manifest fetched".into(),
+                                        detail: if r.unreachable_mods.is_empty() {
+                                            "Manifest fetched".into()
+                                        } else {
+                                            format!(
+                                                "Manifest fetched; unreachable mods left as is: {}",
+                                                r.unreachable_mod_names().join(", ")
+                                            )
+                                        },
                                     },
                                 })
                                 .await;
+                            if !r.repo_checksum.is_empty() {
+                                let _ = tx
+                                    .send(DomainEvent::PipelineEvent {
+                                        run_id,
+                                        ev: PipelineRunEvent::RepoChecksum {
+                                            checksum: r.repo_checksum.clone(),
+                                        },
+                                    })
+                                    .await;
+                            }
                             r
                         }
                         Err(e) => {
@@ -414,16 +491,18 @@ impl PipelineOrchestrator {
                         }
                     }
 
-                    let plan_res = engine.compute_plan(&fetch_res.manifest, &local_state, &req);
+                    let plan_res = engine.compute_plan(&fetch_res, &local_state, &req);
                     match plan_res {
                         Ok(plan) => {
                             let diff_stats = (plan.downloads.len(), plan.deletes.len());
+                            let risk = engine.assess_plan(&plan, &local_state, &req);
                             let _ = tx
                                 .send(DomainEvent::PipelineEvent {
                                     run_id,
                                     ev: PipelineRunEvent::PlanReady {
                                         plan,
                                         diff_stats,
+                                        risk,
                                         existing_mods: existing_mods(),
                                     },
                                 })
@@ -447,6 +526,74 @@ impl PipelineOrchestrator {
         Ok(())
     }
 
+    /// Reads the profile's file metadata and scan cache in the background (`MetadataOnly`)
+    /// so the next check hits warm caches. Shares the cancellation token with checks and
+    /// syncs, so starting either one stops the warm-up.
+    pub fn start_cache_warmup(
+        &mut self,
+        profile: Profile,
+        settings: AppSettings,
+    ) -> anyhow::Result<()> {
+        self.cancel();
+        let token = CancellationToken::new();
+        self.cancel = Some(token.clone());
+        let run_lock = self.run_lock.clone();
+
+        let tx = self.tx.clone();
+        let engine = self.engine.clone();
+
+        std::thread::Builder::new()
+            .name("fleet-warmup".into())
+            .spawn(move || {
+                let profile_id = profile.id.clone();
+                let status = |status| DomainEvent::CacheWarmup {
+                    profile_id: profile_id.clone(),
+                    status,
+                };
+                let rt = match crate::async_runtime::runtime() {
+                    Ok(rt) => rt,
+                    Err(e) => {
+                        let _ = tx.blocking_send(status(CacheWarmup::Failed(format!(
+                            "Failed to start async runtime: {e}"
+                        ))));
+                        return;
+                    }
+                };
+
+                rt.block_on(async move {
+                    let Some(_run) = supersede(run_lock, &token).await else {
+                        return;
+                    };
+                    let _ = tx.send(status(CacheWarmup::Running)).await;
+
+                    let req = SyncRequest {
+                        repo_url: profile.repo_url.clone(),
+                        extra_repo_urls: profile.extra_repo_urls(),
+                        local_root: camino::Utf8PathBuf::from(profile.local_path.clone()),
+                        mode: SyncMode::MetadataOnly,
+                        options: SyncOptions {
+                            max_threads: 1,
+                            offline: settings.offline,
+                            ..SyncOptions::default()
+                        },
+                        profile_id: Some(profile.id.clone()),
+                    };
+
+                    // The blocking walk checks the token per entry, so it stops too.
+                    let res = engine.scan_local_state(&req, None, Some(&token)).await;
+                    let done = match res {
+                        Err(SyncError::Cancelled) => return,
+                        Ok(_) => CacheWarmup::Ready,
+                        Err(e) => CacheWarmup::Failed(e.to_string()),
+                    };
+                    let _ = tx.send(status(done)).await;
+                });
+            })
+            .context("Failed to spawn cache warm-up worker thread")?;
+
+        Ok(())
+    }
+
     pub fn start_sync(
         &mut self,
         profile: Profile,
@@ -454,12 +601,14 @@ impl PipelineOrchestrator {
         settings: AppSettings,
         run_id: PipelineRunId,
     ) -> anyhow::Result<()> {
+        let settings = settings.for_profile(&profile);
+        let engine = self.engine_for(&profile, &settings)?;
         self.cancel();
         let token = CancellationToken::new();
         self.cancel = Some(token.clone());
+        let run_lock = self.run_lock.clone();
 
         let tx = self.tx.clone();
-        let engine = self.engine.clone();
 
         std::thread::Builder::new()
             .name("fleet-sync".into())
@@ -478,6 +627,9 @@ impl PipelineOrchestrator {
                 };
 
                 rt.block_on(async move {
+                    let Some(_run) = supersede(run_lock, &token).await else {
+                        return;
+                    };
                     let _ = tx
                         .send(DomainEvent::PipelineEvent {
                             run_id,
@@ -487,18 +639,17 @@ impl PipelineOrchestrator {
                         })
                         .await;
 
+                    let rate_limit =
+                        fleet_infra::RateLimit::new(settings.rate_limit_at(chrono::Local::now().time()));
                     let req = SyncRequest {
                         repo_url: profile.repo_url.clone(),
+                        extra_repo_urls: profile.extra_repo_urls(),
                         local_root: camino::Utf8PathBuf::from(profile.local_path.clone()),
                         mode: SyncMode::CacheOnly,
                         options: SyncOptions {
-                            max_threads: settings.max_threads,
-                            rate_limit_bytes: if settings.speed_limit_enabled {
-                                Some(settings.max_speed_bytes)
-                            } else {
-                                None
-                            },
-                            cache_root: None,
+                            rate_limit_bytes: rate_limit.get(),
+                            rate_limit: Some(rate_limit.clone()),
+                            ..settings.sync_options(&profile)
                         },
                         profile_id: Some(profile.id.clone()),
                     };
@@ -517,27 +668,72 @@ impl PipelineOrchestrator {
                     let (prog_tx, mut prog_rx) = mpsc::channel(100);
                     let mut tracker = ProgressTracker::new(&plan);
                     let mut latest: Option<TransferSnapshot> = None;
-                    let mut ticker = interval(Duration::from_millis(100));
-
-                    let work_fut = engine.execute_with_plan(&req, plan.clone(), Some(prog_tx));
+                    let mut ticker = interval(PROGRESS_TICK);
+                    let mut schedule_ticker = interval(SCHEDULE_TICK);
+                    let scheduled = !settings.bandwidth_schedule.is_empty();
+
+                    // Cancellation is handled by the engine so files that already finished are
+                    // committed to the baseline before it returns `SyncError::Cancelled`.
+                    let work_fut = engine.execute_with_plan_cancellable(
+                        &req,
+                        plan.clone(),
+                        Some(prog_tx),
+                        &token,
+                    );
 
                     tokio::pin!(work_fut);
+                    let mut cancel_reported = false;
 
                     loop {
                         tokio::select! {
-                            _ = token.cancelled() => {
+                            // Reported at once; the engine still commits finished files before
+                            // `work_fut` returns.
+                            _ = token.cancelled(), if !cancel_reported => {
+                                cancel_reported = true;
                                 let _ = tx.send(DomainEvent::PipelineEvent { run_id, ev: PipelineRunEvent::Cancelled }).await;
-                                return;
                             }
                             res = &mut work_fut => {
+                                if cancel_reported {
+                                    return;
+                                }
                                 if let Some(snap) = latest.take() {
                                     let _ = tx.send(DomainEvent::PipelineEvent { run_id, ev: PipelineRunEvent::TransferProgress { snapshot: snap } }).await;
                                 }
                                 match res {
-                                    Ok(_r) => {
+                                    Ok(r) => {
+                                        // Network transfers only; see `average_speed`.
+                                        let throughput_bps = tracker.average_speed();
+                                        if !r.warnings.is_empty() {
+                                            let _ = tx.send(DomainEvent::PipelineEvent { run_id, ev: PipelineRunEvent::StorageWarnings { warnings: r.warnings } }).await;
+                                        }
+                                        if settings.verify_after_sync {
+                                            let _ = tx.send(DomainEvent::PipelineEvent { run_id, ev: PipelineRunEvent::StepChanged { step: PipelineStep::Execute, status: StepStatus::Running, detail: "Verifying downloaded files...".into() } }).await;
+                                            let verified = tokio::select! {
+                                                _ = token.cancelled() => {
+                                                    let _ = tx.send(DomainEvent::PipelineEvent { run_id, ev: PipelineRunEvent::Cancelled }).await;
+                                                    return;
+                                                }
+                                                v = fleet_pipeline::verify_downloads(&req.local_root, &plan) => v,
+                                            };
+                                            let mismatches = match verified {
+                                                Ok(m) => m,
+                                                Err(e) => vec![FailedCheck { path: req.local_root.to_string(), reason: format!("verification failed: {e}") }],
+                                            };
+                                            if !mismatches.is_empty() {
+                                                let _ = tx.send(DomainEvent::PipelineEvent { run_id, ev: PipelineRunEvent::VerifyMismatches { mismatches } }).await;
+                                            }
+                                        }
+                                        let _ = tx.send(DomainEvent::PipelineEvent { run_id, ev: PipelineRunEvent::SyncSummary { changes: r.stats.changes, throughput_bps } }).await;
                                         let _ = tx.send(DomainEvent::PipelineEvent { run_id, ev: PipelineRunEvent::Completed }).await;
                                     }
+                                    Err(SyncError::Cancelled) => {
+                                        let _ = tx.send(DomainEvent::PipelineEvent { run_id, ev: PipelineRunEvent::Cancelled }).await;
+                                        return;
+                                    }
                                     Err(e) => {
+                                        if let SyncError::Downloads(failures) = &e {
+                                            let _ = tx.send(DomainEvent::PipelineEvent { run_id, ev: PipelineRunEvent::DownloadsFailed { failures: failures.clone() } }).await;
+                                        }
                                         let _ = tx.send(DomainEvent::PipelineEvent { run_id, ev: PipelineRunEvent::Failed { message: e.to_string() } }).await;
                                     }
                                 }
@@ -549,23 +745,23 @@ impl PipelineOrchestrator {
                                     latest = Some(tracker.get_snapshot());
                                 }
                             }
-                            _ = ticker.tick() => {
+                            _ = ticker.tick(), if !cancel_reported => {
                                 if let Some(snap) = latest.clone() {
                                     let _ = tx.try_send(DomainEvent::PipelineEvent { run_id, ev: PipelineRunEvent::TransferProgress { snapshot: snap } });
                                 }
                             }
+                            _ = schedule_ticker.tick(), if scheduled => {
+                                rate_limit.set(settings.rate_limit_at(chrono::Local::now().time()));
+                            }
                         }
                     }
 
                     let scan_req = SyncRequest {
                         repo_url: profile.repo_url.clone(),
+                        extra_repo_urls: profile.extra_repo_urls(),
                         local_root: camino::Utf8PathBuf::from(profile.local_path.clone()),
                         mode: SyncMode::SmartVerify,
-                        options: SyncOptions {
-                            max_threads: settings.max_threads,
-                            rate_limit_bytes: None,
-                            cache_root: None,
-                        },
+                        options: settings.sync_options(&profile),
                         profile_id: Some(profile.id.clone()),
                     };
 
@@ -578,7 +774,7 @@ impl PipelineOrchestrator {
                         }
                     });
 
-                    let _ = engine.scan_local_state(&scan_req, Some(on_progress)).await;
+                    let _ = engine.scan_local_state(&scan_req, Some(on_progress), None).await;
                     let stats_opt = latest_stats.lock().ok().and_then(|guard| guard.clone());
                     if let Some(stats) = stats_opt {
                         let _ = tx
@@ -588,6 +784,14 @@ impl PipelineOrchestrator {
                             })
                             .await;
                     }
+
+                    let warnings = signature_warnings(scan_req.local_root.clone()).await;
+                    let _ = tx
+                        .send(DomainEvent::PipelineEvent {
+                            run_id,
+                            ev: PipelineRunEvent::SignatureWarnings { warnings },
+                        })
+                        .await;
                 });
             })
             .context("Failed to spawn background sync worker thread")?;
@@ -596,6 +800,64 @@ impl PipelineOrchestrator {
     }
 }
 
+/// Builds an engine whose client sends `profile`'s repository credentials on every request.
+pub(crate) fn profile_engine(
+    settings: &AppSettings,
+    profile: &Profile,
+) -> anyhow::Result<DefaultSyncEngine> {
+    let config = settings.net_config_for(profile)?;
+    let client = fleet_infra::net::default_http_client(Some(&config))
+        .context("Failed to build HTTP client for repository credentials")?;
+    Ok(fleet_pipeline::default_engine(client))
+}
+
+/// Waits out [`RESTART_DEBOUNCE`] and then for the worker being replaced to exit, so a new
+/// run starts emitting only after the old one has observed its cancellation. `None` when
+/// this run was itself superseded meanwhile; it then exits without emitting anything.
+async fn supersede(
+    run_lock: Arc<tokio::sync::Mutex<()>>,
+    token: &CancellationToken,
+) -> Option<tokio::sync::OwnedMutexGuard<()>> {
+    tokio::select! {
+        _ = token.cancelled() => return None,
+        _ = tokio::time::sleep(RESTART_DEBOUNCE) => {}
+    }
+    tokio::select! {
+        _ = token.cancelled() => None,
+        guard = run_lock.lock_owned() => Some(guard),
+    }
+}
+
+/// One "N unsigned PBOs in @mod" line for every mod under `root` with unsigned PBOs.
+async fn signature_warnings(root: camino::Utf8PathBuf) -> Vec<String> {
+    tokio::task::spawn_blocking(move || {
+        let mut mod_dirs: Vec<camino::Utf8PathBuf> = std::fs::read_dir(&root)
+            .ok()
+            .into_iter()
+            .flatten()
+            .filter_map(|res| res.ok())
+            .filter_map(|entry| camino::Utf8PathBuf::from_path_buf(entry.path()).ok())
+            .filter(|path| path.is_dir() && path.file_name().is_some_and(|n| n.starts_with('@')))
+            .collect();
+        mod_dirs.sort();
+
+        mod_dirs
+            .iter()
+            .filter_map(|dir| {
+                let report = fleet_infra::check_mod_signatures(dir, false).ok()?;
+                let count = report.unsigned.len();
+                match count {
+                    0 => None,
+                    1 => Some(format!("1 unsigned PBO in {}", dir.file_name()?)),
+                    n => Some(format!("{n} unsigned PBOs in {}", dir.file_name()?)),
+                }
+            })
+            .collect()
+    })
+    .await
+    .unwrap_or_default()
+}
+
 impl SyncPipelinePort for PipelineOrchestrator {
     fn validate_repo_url_blocking(&self, repo_url: &str) -> anyhow::Result<()> {
         if let Ok(handle) = tokio::runtime::Handle::try_current() {
@@ -32,6 +32,16 @@ impl FilePersistence {
         Ok(config_dir.to_path_buf())
     }
 
+    /// Per-user directory for the GUI's log files, created on first use.
+    pub fn log_dir(&self) -> Result<std::path::PathBuf> {
+        let proj_dirs = ProjectDirs::from(QUALIFIER, ORG, APP)
+            .ok_or_else(|| anyhow::anyhow!("Could not determine data directory"))?;
+
+        let log_dir = proj_dirs.data_local_dir().join("logs");
+        fs::create_dir_all(&log_dir)?;
+        Ok(log_dir)
+    }
+
     fn profiles_path(&self) -> Result<std::path::PathBuf> {
         Ok(self.config_dir()?.join("profiles.json"))
     }
@@ -24,6 +24,7 @@ pub struct TransferProgressVm {
     pub speed_bps: u64,
     pub failed_count: u64,
     pub active_files: Vec<ActiveTransferFileVm>,
+    pub per_mod: Vec<ModTransferVm>,
 }
 
 #[derive(Debug, Clone)]
@@ -32,6 +33,16 @@ pub struct ActiveTransferFileVm {
     pub rel_path: String,
     pub bytes_downloaded: u64,
     pub total_bytes: u64,
+    pub verifying: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ModTransferVm {
+    pub mod_name: String,
+    pub downloaded_files: u64,
+    pub total_files: u64,
+    pub downloaded_bytes: u64,
+    pub total_bytes: u64,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -72,8 +83,36 @@ pub enum PipelineRunEvent {
     PlanReady {
         plan: fleet_core::SyncPlan,
         diff_stats: (usize, usize),
+        /// Whether the plan deletes enough local content to need confirmation.
+        risk: fleet_core::RiskLevel,
         existing_mods: Vec<String>,
     },
+    /// Mods with PBOs a signature-checking server would reject.
+    SignatureWarnings {
+        warnings: Vec<String>,
+    },
+    /// Checksum advertised by the primary repo.json in this run's fetch.
+    RepoChecksum {
+        checksum: String,
+    },
+    /// Files the sync could not download; they can be retried without re-planning.
+    DownloadsFailed {
+        failures: Vec<fleet_pipeline::FailedDownload>,
+    },
+    /// A corrupt local database or scan cache was reset during the run.
+    StorageWarnings {
+        warnings: Vec<String>,
+    },
+    /// Downloaded files whose re-hash after the sync no longer matched (`verify_after_sync`).
+    VerifyMismatches {
+        mismatches: Vec<fleet_pipeline::FailedCheck>,
+    },
+    /// What a finished sync changed on disk; becomes the profile's last change log.
+    SyncSummary {
+        changes: fleet_pipeline::ChangeLog,
+        /// Average download rate of the sync, added to the profile's throughput history.
+        throughput_bps: Option<u64>,
+    },
     Completed,
     Failed {
         message: String,
@@ -94,6 +133,13 @@ pub struct PipelineState {
     pub stats: PipelineStats,
     pub details: HashMap<PipelineStep, String>,
     pub plan_existing_mods: Option<Vec<String>>,
+    pub plan_risk: Option<fleet_core::RiskLevel>,
+    /// Local database or scan cache recoveries that happened during this run.
+    pub storage_warnings: Vec<String>,
+    /// Downloads that failed in the last sync, offered for a targeted retry.
+    pub failed_downloads: Vec<fleet_pipeline::FailedDownload>,
+    /// Downloads that failed the post-sync re-hash of this run.
+    pub verify_mismatches: Vec<fleet_pipeline::FailedCheck>,
     pub error: Option<String>,
 }
 
@@ -109,6 +155,10 @@ impl PipelineState {
             stats: PipelineStats::default(),
             details: HashMap::new(),
             plan_existing_mods: None,
+            plan_risk: None,
+            storage_warnings: Vec::new(),
+            failed_downloads: Vec::new(),
+            verify_mismatches: Vec::new(),
             error: None,
         }
     }
@@ -131,6 +181,10 @@ impl PipelineState {
             stats: PipelineStats::default(),
             details: HashMap::new(),
             plan_existing_mods: None,
+            plan_risk: None,
+            storage_warnings: Vec::new(),
+            failed_downloads: Vec::new(),
+            verify_mismatches: Vec::new(),
             error: None,
         }
     }
@@ -1,9 +1,13 @@
 use crate::app::FleetApplication;
-use crate::domain::{AppSettings, AppState, Profile, ProfileId};
+use crate::domain::{
+    AppSettings, AppState, CacheWarmup, Profile, ProfileId, ServerStatus, UpdateStatus,
+};
 use crate::pipeline::{PipelineState, StepStatus};
 use chrono::{DateTime, Utc};
 use fleet_persistence::{DbState, FleetDataStore, RedbFleetDataStore};
 use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
 
 fn format_last_synced(ts: Option<DateTime<Utc>>) -> Option<String> {
     ts.map(|t| t.to_rfc3339())
@@ -30,16 +34,18 @@ fn format_rate(bps: u64) -> Option<String> {
     })
 }
 
-fn format_eta(total_bytes: u64, downloaded_bytes: u64, bps: u64) -> Option<String> {
+/// Whole seconds, rounded up, to transfer what is left of `total_bytes` at `bps`.
+fn remaining_secs(total_bytes: u64, downloaded_bytes: u64, bps: u64) -> Option<u64> {
     if bps == 0 || total_bytes == 0 || downloaded_bytes >= total_bytes {
         return None;
     }
 
     let remaining = total_bytes.saturating_sub(downloaded_bytes);
-    let mut secs = remaining / bps;
-    if !remaining.is_multiple_of(bps) {
-        secs = secs.saturating_add(1);
-    }
+    Some(remaining.div_ceil(bps))
+}
+
+fn format_eta(total_bytes: u64, downloaded_bytes: u64, bps: u64) -> Option<String> {
+    let mut secs = remaining_secs(total_bytes, downloaded_bytes, bps)?;
 
     let hours = secs / 3600;
     secs %= 3600;
@@ -53,6 +59,36 @@ fn format_eta(total_bytes: u64, downloaded_bytes: u64, bps: u64) -> Option<Strin
     })
 }
 
+/// How long `plan`'s downloads should take at `bps`; `None` when nothing is downloaded or the
+/// speed is unknown.
+pub fn estimate_sync_duration(plan: &fleet_core::SyncPlan, bps: u64) -> Option<Duration> {
+    let total: u64 = plan.downloads.iter().map(|d| d.size).sum();
+    remaining_secs(total, 0, bps).map(Duration::from_secs)
+}
+
+/// Download speed to expect from `profile`'s next sync: its recent average, capped by the
+/// speed limit in effect now. Falls back to the limit before the first sync.
+pub fn expected_sync_speed(settings: &AppSettings, profile: &Profile) -> Option<u64> {
+    let limit = settings
+        .for_profile(profile)
+        .rate_limit_at(chrono::Local::now().time());
+    match (profile.average_throughput(), limit) {
+        (Some(average), Some(limit)) => Some(average.min(limit)),
+        (average, limit) => average.or(limit),
+    }
+}
+
+/// e.g. "~4 min at 5.0 MB/s".
+fn format_sync_estimate(plan: &fleet_core::SyncPlan, bps: u64) -> Option<String> {
+    let minutes = estimate_sync_duration(plan, bps)?.as_secs().div_ceil(60);
+    let duration = if minutes >= 60 {
+        format!("~{}h {:02}min", minutes / 60, minutes % 60)
+    } else {
+        format!("~{minutes} min")
+    };
+    Some(format!("{duration} at {}", format_rate(bps)?))
+}
+
 #[derive(Debug, Clone)]
 pub struct ProfileStatsVm {
     pub file_count: String,
@@ -60,6 +96,25 @@ pub struct ProfileStatsVm {
     pub cache_ratio: String,
 }
 
+/// Recent scans shown on the dashboard.
+const DASHBOARD_SCAN_HISTORY: usize = 5;
+
+#[derive(Debug, Clone)]
+pub struct ScanHistoryVm {
+    pub when: String,
+    pub file_count: String,
+    pub cache_ratio: String,
+}
+
+fn format_cache_ratio(s: &fleet_scanner::ScanStats) -> String {
+    let ratio = if s.total_files > 0 {
+        (s.files_cached as f64 / s.total_files as f64) * 100.0
+    } else {
+        0.0
+    };
+    format!("{:.1}%", ratio)
+}
+
 #[derive(Debug, Clone)]
 pub struct ProfileSummaryVm {
     pub id: ProfileId,
@@ -68,6 +123,8 @@ pub struct ProfileSummaryVm {
     pub local_path: String,
     pub last_synced_human: Option<String>,
     pub status_label: String,
+    /// Result of the latest remote check, e.g. "12 file(s), 340 MB to download".
+    pub status_detail: Option<String>,
 }
 
 impl From<&Profile> for ProfileSummaryVm {
@@ -83,23 +140,90 @@ impl From<&Profile> for ProfileSummaryVm {
             } else {
                 "Unknown".into()
             },
+            status_detail: None,
         }
     }
 }
 
+/// "340 MB" or "1.25 GB".
+fn format_size(bytes: u64) -> String {
+    let size_mb = bytes as f64 / 1024.0 / 1024.0;
+    if size_mb > 1024.0 {
+        format!("{:.2} GB", size_mb / 1024.0)
+    } else {
+        format!("{:.0} MB", size_mb)
+    }
+}
+
 // --- Pipeline VMs ---
 
 #[derive(Debug, Clone)]
 pub struct ProfileHubVm {
     pub profiles: Vec<ProfileSummaryVm>,
     pub can_create_profile: bool,
+    pub can_check_all: bool,
+    /// e.g. "3 profiles have updates", once any profile has been checked.
+    pub update_summary: Option<String>,
 }
 
 pub fn profile_hub_vm(state: &AppState) -> ProfileHubVm {
+    let profiles = state
+        .profiles
+        .iter()
+        .map(|p| {
+            let mut vm = ProfileSummaryVm::from(p);
+            if let Some(status) = state.update_status.get(&p.id) {
+                let (label, detail) = match status {
+                    UpdateStatus::ChangedUpstream => (
+                        "Repo Changed",
+                        Some("The repository published an update; checking soon".to_string()),
+                    ),
+                    UpdateStatus::Checking => ("Checking", None),
+                    UpdateStatus::UpToDate => ("Ready", Some("Up to date".to_string())),
+                    UpdateStatus::Updates { downloads, bytes } => (
+                        "Update Available",
+                        Some(format!(
+                            "{downloads} file(s), {} to download",
+                            format_size(*bytes)
+                        )),
+                    ),
+                    UpdateStatus::Failed(message) => ("Check Failed", Some(message.clone())),
+                };
+                vm.status_label = label.into();
+                vm.status_detail = detail;
+            }
+            vm
+        })
+        .collect();
     ProfileHubVm {
-        profiles: state.profiles.iter().map(ProfileSummaryVm::from).collect(),
+        profiles,
         can_create_profile: !state.pipeline.is_running(),
+        can_check_all: !state.profiles.is_empty() && !state.pipeline.is_running(),
+        update_summary: update_summary(state),
+    }
+}
+
+fn update_summary(state: &AppState) -> Option<String> {
+    if state.update_status.is_empty() {
+        return None;
+    }
+    let count =
+        |f: fn(&UpdateStatus) -> bool| state.update_status.values().filter(|s| f(s)).count();
+    let checking = count(|s| matches!(s, UpdateStatus::Checking | UpdateStatus::ChangedUpstream));
+    if checking > 0 {
+        return Some(format!("Checking {checking} profile(s)…"));
     }
+    let updates = count(|s| matches!(s, UpdateStatus::Updates { .. }));
+    let failed = count(|s| matches!(s, UpdateStatus::Failed(_)));
+    let mut summary = match updates {
+        0 => "No updates".to_string(),
+        1 => "1 profile has updates".to_string(),
+        n => format!("{n} profiles have updates"),
+    };
+    if failed > 0 {
+        summary.push_str(&format!(", {failed} check(s) failed"));
+    }
+    Some(summary)
 }
 
 #[derive(Debug, Clone)]
@@ -223,16 +347,26 @@ pub enum DashboardState {
         detail: String,
         progress: Option<(f32, String)>, // 0.0..1.0, Label
         can_cancel: bool,
+        /// Cancelling a large sync is waiting for confirmation.
+        confirm_cancel: bool,
     },
     /// Check finished, changes detected.
     Review {
         changes_summary: String, // e.g., "15 files to download"
         can_launch: bool,        // Allow launch even if dirty (with warning)
+        /// The plan deletes most local files; SYNC needs explicit confirmation.
+        destructive: bool,
+        /// Expected download time, e.g. "~4 min at 5.0 MB/s"; `None` without a speed estimate.
+        eta: Option<String>,
     },
     /// Success state (briefly shown after sync).
     Synced { msg: String, can_launch: bool },
     /// Error state.
-    Error { msg: String },
+    Error {
+        msg: String,
+        /// Files a failed sync can retry without re-planning.
+        retry_files: usize,
+    },
     /// Local folder has no baseline/cache information yet.
     Unknown { msg: String },
 }
@@ -255,7 +389,7 @@ pub struct VisualizerVm {
     pub phase: VisualizerPhase,
     pub scan: Option<fleet_scanner::ScanStats>,
     pub transfer: Option<crate::pipeline::TransferProgressVm>,
-    pub plan: Option<fleet_core::SyncPlan>,
+    pub plan: Option<Arc<fleet_core::SyncPlan>>,
     pub existing_mods: Vec<String>,
 }
 
@@ -263,8 +397,108 @@ pub struct VisualizerVm {
 pub struct ProfileDashboardVm {
     pub profile: ProfileSummaryVm,
     pub stats: Option<ProfileStatsVm>,
+    /// Most recent scans first; a dropping cache ratio hints at something touching mtimes.
+    pub scan_history: Vec<ScanHistoryVm>,
     pub state: DashboardState,
     pub visualizer: VisualizerVm,
+    /// e.g. "3 unsigned PBOs in @mymod".
+    pub signature_warnings: Vec<String>,
+    /// Offline mode is on: checks only compare against the last synced state.
+    pub offline: bool,
+    /// Status of the background cache warm-up, if one ran for this profile.
+    pub cache_warmup: Option<String>,
+    /// Summary of the last sync's changes, e.g. "Updated @ace (12 files), removed @oldmod".
+    pub last_changes: Option<String>,
+    /// The mods folder is on disk, so it can be opened in the file manager.
+    pub local_folder_exists: bool,
+    /// Corrupt `fleet.redb` copies moved aside in the profile folder.
+    pub quarantined_dbs: usize,
+    /// Corrupt database or scan cache recoveries from the last run.
+    pub storage_warnings: Vec<String>,
+    /// Downloads whose post-sync re-hash did not match, e.g. "@mod/a.pbo: checksum mismatch".
+    pub verify_mismatches: Vec<String>,
+    /// Set while the profile is held at a repository version, e.g. "Pinned — upstream has
+    /// moved on".
+    pub pin_status: Option<String>,
+    /// A fetched repository version is known and the profile is not pinned yet.
+    pub can_pin: bool,
+    /// Mods in the folder, in the pending plan or toggled off, with their toggles.
+    pub mods: Vec<ModToggleVm>,
+    /// Servers from repo.json, in order; JOIN connects to one of them by index.
+    pub servers: Vec<ServerVm>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerVm {
+    pub name: String,
+    /// `host:port` as passed to `-connect`/`-port`.
+    pub address: String,
+    pub battle_eye: bool,
+    /// repo.json carries a password for it.
+    pub has_password: bool,
+    /// "12/64 players · Altis" or "offline"; `None` until the first query answers.
+    pub status: Option<String>,
+}
+
+impl From<&fleet_core::repo::Server> for ServerVm {
+    fn from(s: &fleet_core::repo::Server) -> Self {
+        Self {
+            name: s.name.clone(),
+            address: format!("{}:{}", s.address, s.port),
+            battle_eye: s.battle_eye,
+            has_password: !s.password.trim().is_empty(),
+            status: None,
+        }
+    }
+}
+
+fn server_status_label(status: &ServerStatus) -> String {
+    match status {
+        ServerStatus::Online(info) if info.map.is_empty() => {
+            format!("{}/{} players", info.players, info.max_players)
+        }
+        ServerStatus::Online(info) => {
+            format!(
+                "{}/{} players · {}",
+                info.players, info.max_players, info.map
+            )
+        }
+        ServerStatus::Offline => "offline".into(),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModToggleVm {
+    pub name: String,
+    /// Passed to the game via `-mod=`.
+    pub loaded: bool,
+    /// Managed by checks and syncs.
+    pub synced: bool,
+}
+
+fn mod_toggles(state: &AppState, profile: &Profile) -> Vec<ModToggleVm> {
+    let mut names = match &state.local_mods {
+        Some((id, names)) if *id == profile.id => names.clone(),
+        _ => Vec::new(),
+    };
+    if state.pipeline.active_profile_id.as_ref() == Some(&profile.id) {
+        if let Some(plan) = &state.last_plan {
+            names.extend(plan.downloads.iter().map(|d| d.mod_name.clone()));
+        }
+    }
+    names.extend(profile.disabled_mods.iter().cloned());
+    names.extend(profile.unsynced_mods.iter().cloned());
+    names.sort_by_key(|n| n.to_lowercase());
+    names.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+
+    names
+        .into_iter()
+        .map(|name| ModToggleVm {
+            loaded: profile.loads_mod(&name),
+            synced: profile.syncs_mod(&name),
+            name,
+        })
+        .collect()
 }
 
 pub fn profile_dashboard_vm(state: &AppState, profile_id: ProfileId) -> Option<ProfileDashboardVm> {
@@ -273,39 +507,52 @@ pub fn profile_dashboard_vm(state: &AppState, profile_id: ProfileId) -> Option<P
     let local_root = Path::new(&profile.local_path);
 
     let store = RedbFleetDataStore;
-    let (db_state, db_error) = match camino::Utf8PathBuf::from_path_buf(local_root.to_path_buf()) {
-        Ok(p) => match store.validate(&p) {
+    let utf8_root = camino::Utf8PathBuf::from_path_buf(local_root.to_path_buf());
+    let (db_state, db_error) = match &utf8_root {
+        Ok(p) => match store.validate(p) {
             Ok(s) => (s, None),
             Err(e) => (DbState::Missing, Some(e.to_string())),
         },
         Err(_) => (DbState::Missing, Some("Non-UTF local path".into())),
     };
+    let quarantined_dbs = match &state.quarantined_dbs {
+        Some((id, count)) if *id == profile_id => *count,
+        _ => 0,
+    };
 
     // Stats Logic
-    let stats_vm = profile.last_scan.as_ref().map(|s| {
-        let size_mb = s.total_bytes as f64 / 1024.0 / 1024.0;
-        let size_str = if size_mb > 1024.0 {
-            format!("{:.2} GB", size_mb / 1024.0)
-        } else {
-            format!("{:.0} MB", size_mb)
-        };
-
-        let ratio = if s.total_files > 0 {
-            (s.files_cached as f64 / s.total_files as f64) * 100.0
-        } else {
-            0.0
-        };
-
-        ProfileStatsVm {
-            file_count: format!("{}", s.total_files),
-            total_size: size_str,
-            cache_ratio: format!("{:.1}%", ratio),
-        }
+    let stats_vm = profile.last_scan.as_ref().map(|s| ProfileStatsVm {
+        file_count: format!("{}", s.total_files),
+        total_size: format_size(s.total_bytes),
+        cache_ratio: format_cache_ratio(s),
     });
 
+    let scan_history = profile
+        .scan_history
+        .iter()
+        .rev()
+        .take(DASHBOARD_SCAN_HISTORY)
+        .map(|r| ScanHistoryVm {
+            when: r.at.format("%Y-%m-%d %H:%M").to_string(),
+            file_count: format!("{}", r.stats.total_files),
+            cache_ratio: format_cache_ratio(&r.stats),
+        })
+        .collect();
+
     // 1. Determine High-Level State
     let dashboard_state = if let Some(err) = &pl.error {
-        DashboardState::Error { msg: err.clone() }
+        DashboardState::Error {
+            msg: err.clone(),
+            retry_files: pl.failed_downloads.len(),
+        }
+    } else if state.auto_launch.as_ref() == Some(&profile.id) {
+        DashboardState::Busy {
+            task_name: "Launching Game".to_string(),
+            detail: "Sync complete; starting the game (auto-launch is on).".to_string(),
+            progress: None,
+            can_cancel: false,
+            confirm_cancel: false,
+        }
     } else if pl.is_running() {
         // Map pipeline steps to a simple "Busy" view
         let (task, detail, prog) = if pl.sync_status == StepStatus::Running {
@@ -331,11 +578,17 @@ pub fn profile_dashboard_vm(state: &AppState, profile_id: ProfileId) -> Option<P
             } else {
                 (0.0, "".into())
             };
-            (
-                "Syncing Content".to_string(),
-                "Downloading files...".to_string(),
-                Some((p, l)),
-            )
+            // Once only checksums are outstanding, say so rather than looking stalled at 100%.
+            let verifying = pl.stats.transfer.as_ref().and_then(|t| {
+                let all_verifying =
+                    !t.active_files.is_empty() && t.active_files.iter().all(|f| f.verifying);
+                all_verifying.then(|| &t.active_files[0])
+            });
+            let detail = match verifying {
+                Some(f) => format!("Verifying {}/{}…", f.mod_name, f.rel_path),
+                None => "Downloading files...".to_string(),
+            };
+            ("Syncing Content".to_string(), detail, Some((p, l)))
         } else if pl.diff_status == StepStatus::Running {
             (
                 "Checking Updates".to_string(),
@@ -362,11 +615,13 @@ pub fn profile_dashboard_vm(state: &AppState, profile_id: ProfileId) -> Option<P
             detail,
             progress: prog,
             can_cancel: true,
+            confirm_cancel: state.cancel_pending,
         }
     } else if local_root.is_dir() {
         if let Some(msg) = db_error {
             DashboardState::Error {
                 msg: format!("Failed to open local database: {msg}"),
+                retry_files: 0,
             }
         } else {
             match db_state {
@@ -383,11 +638,13 @@ pub fn profile_dashboard_vm(state: &AppState, profile_id: ProfileId) -> Option<P
             },
             DbState::Busy => DashboardState::Error {
                 msg: "Local database is busy (another Fleet instance may be running). Close it and try again.".into(),
+                retry_files: 0,
             },
             DbState::NewerSchema { found, supported } => DashboardState::Error {
                 msg: format!(
                     "Local database is from a newer Fleet (schema_version={found}, supported={supported}). Update Fleet and try again."
                 ),
+                retry_files: 0,
             },
             }
         }
@@ -402,6 +659,9 @@ pub fn profile_dashboard_vm(state: &AppState, profile_id: ProfileId) -> Option<P
                     plan.deletes.len()
                 ),
                 can_launch: true,
+                destructive: state.pipeline.plan_risk == Some(fleet_core::RiskLevel::Destructive),
+                eta: expected_sync_speed(&state.settings, profile)
+                    .and_then(|bps| format_sync_estimate(plan, bps)),
             }
         } else {
             // Plan exists but empty -> We are synced.
@@ -451,6 +711,7 @@ pub fn profile_dashboard_vm(state: &AppState, profile_id: ProfileId) -> Option<P
     Some(ProfileDashboardVm {
         profile: ProfileSummaryVm::from(profile),
         stats: stats_vm,
+        scan_history,
         state: dashboard_state,
         visualizer: VisualizerVm {
             phase,
@@ -459,9 +720,194 @@ pub fn profile_dashboard_vm(state: &AppState, profile_id: ProfileId) -> Option<P
             plan: state.last_plan.clone(),
             existing_mods: pl.plan_existing_mods.clone().unwrap_or_default(),
         },
+        signature_warnings: state
+            .signature_warnings
+            .get(&profile.id)
+            .cloned()
+            .unwrap_or_default(),
+        offline: state.settings.offline,
+        last_changes: profile.last_changes.as_ref().map(|c| c.summary()),
+        cache_warmup: state.cache_warmup.get(&profile.id).map(|s| match s {
+            CacheWarmup::Running => "Warming scan cache...".to_string(),
+            CacheWarmup::Ready => "Scan cache warm".to_string(),
+            CacheWarmup::Failed(e) => format!("Cache warm-up failed: {e}"),
+        }),
+        local_folder_exists: local_root.is_dir(),
+        quarantined_dbs,
+        storage_warnings: pl.storage_warnings.clone(),
+        verify_mismatches: pl
+            .verify_mismatches
+            .iter()
+            .map(ToString::to_string)
+            .collect(),
+        pin_status: profile.pinned_repo_checksum.as_ref().map(|pinned| {
+            match state.repo_checksums.get(&profile.id) {
+                Some(live) if !live.eq_ignore_ascii_case(pinned) => {
+                    "Pinned \u{2014} upstream has moved on".to_string()
+                }
+                _ => format!("Pinned at repository version {pinned}"),
+            }
+        }),
+        can_pin: profile.pinned_repo_checksum.is_none()
+            && state.repo_checksums.contains_key(&profile.id),
+        mods: mod_toggles(state, profile),
+        servers: match &state.repo_servers {
+            Some((id, servers)) if *id == profile.id => servers.as_slice(),
+            _ => &[],
+        }
+        .iter()
+        .map(|server| {
+            let mut vm = ServerVm::from(server);
+            vm.status = state
+                .server_status
+                .get(&vm.address)
+                .map(server_status_label);
+            vm
+        })
+        .collect(),
     })
 }
 
+/// What the last plan does to a file in the mod tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileSyncStatus {
+    /// In the baseline and untouched by the plan.
+    Installed,
+    /// In the baseline, but the plan downloads a different version.
+    Outdated,
+    /// Only in the plan: the sync downloads it for the first time.
+    Missing,
+    /// The plan deletes it.
+    Extra,
+}
+
+impl FileSyncStatus {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Installed => "installed",
+            Self::Outdated => "update",
+            Self::Missing => "new",
+            Self::Extra => "delete",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct FileNode {
+    pub path: String,
+    pub size: u64,
+    pub status: FileSyncStatus,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct ModNode {
+    pub name: String,
+    /// Baseline checksum; empty for a mod the plan installs for the first time.
+    pub checksum: String,
+    pub files: Vec<FileNode>,
+}
+
+/// Every mod and file of `baseline`, plus the files `plan` adds, each with what the plan
+/// does to it. Mods and files are sorted case-insensitively.
+pub fn mod_tree(
+    baseline: &fleet_core::Manifest,
+    plan: Option<&fleet_core::SyncPlan>,
+) -> Vec<ModNode> {
+    let key = |mod_name: &str, path: &str| format!("{}/{}", mod_name, path).to_lowercase();
+    let mut downloads = std::collections::HashMap::new();
+    let mut deleted_mods = std::collections::HashSet::new();
+    let mut deleted_files = std::collections::HashSet::new();
+    if let Some(plan) = plan {
+        for d in &plan.downloads {
+            downloads.insert(key(&d.mod_name, &d.rel_path), d);
+        }
+        for d in &plan.deletes {
+            match d.path.split_once('/') {
+                Some(_) => deleted_files.insert(d.path.to_lowercase()),
+                None => deleted_mods.insert(d.path.to_lowercase()),
+            };
+        }
+    }
+
+    let mut nodes: Vec<ModNode> = baseline
+        .mods
+        .iter()
+        .map(|m| {
+            let mod_deleted = deleted_mods.contains(&m.name.to_lowercase());
+            let files = m
+                .files
+                .iter()
+                .map(|f| {
+                    let k = key(&m.name, &f.path);
+                    let (size, status) = if mod_deleted || deleted_files.contains(&k) {
+                        (f.length, FileSyncStatus::Extra)
+                    } else if let Some(d) = downloads.remove(&k) {
+                        (d.size, FileSyncStatus::Outdated)
+                    } else {
+                        (f.length, FileSyncStatus::Installed)
+                    };
+                    FileNode {
+                        path: f.path.clone(),
+                        size,
+                        status,
+                    }
+                })
+                .collect();
+            ModNode {
+                name: m.name.clone(),
+                checksum: m.checksum.clone(),
+                files,
+            }
+        })
+        .collect();
+
+    // Whatever is left is new: attach it to its mod, creating the mod if the baseline lacks it.
+    let mut added: Vec<_> = downloads.into_values().collect();
+    added.sort_by(|a, b| (&a.mod_name, &a.rel_path).cmp(&(&b.mod_name, &b.rel_path)));
+    for d in added {
+        let file = FileNode {
+            path: d.rel_path.clone(),
+            size: d.size,
+            status: FileSyncStatus::Missing,
+        };
+        match nodes
+            .iter_mut()
+            .find(|n| n.name.eq_ignore_ascii_case(&d.mod_name))
+        {
+            Some(node) => node.files.push(file),
+            None => nodes.push(ModNode {
+                name: d.mod_name.clone(),
+                checksum: String::new(),
+                files: vec![file],
+            }),
+        }
+    }
+
+    nodes.sort_by_key(|n| n.name.to_lowercase());
+    for node in &mut nodes {
+        node.files.sort_by_key(|f| f.path.to_lowercase());
+    }
+    nodes
+}
+
+/// [`mod_tree`] for a profile: its baseline from `fleet.redb`, plus the pending plan when it
+/// belongs to this profile. `None` for an unknown profile or an unreadable database.
+pub fn profile_tree_vm(state: &AppState, profile_id: &str) -> Option<Vec<ModNode>> {
+    let profile = state.profiles.iter().find(|p| p.id == profile_id)?;
+    let root = camino::Utf8Path::new(&profile.local_path);
+    // A folder that has never been synced has an empty baseline rather than none.
+    let baseline = match RedbFleetDataStore.validate(root).ok()? {
+        DbState::Missing => fleet_core::Manifest::new(Vec::new()),
+        _ => RedbFleetDataStore.load_baseline_manifest(root).ok()?,
+    };
+    let plan = state
+        .last_plan
+        .as_deref()
+        .filter(|_| state.pipeline.active_profile_id.as_deref() == Some(profile_id));
+    Some(mod_tree(&baseline, plan))
+}
+
 #[derive(Debug, Clone)]
 pub struct ProfileEditorVm {
     pub draft: Profile,
@@ -501,6 +947,12 @@ pub fn profile_editor_vm(app: &FleetApplication) -> Option<ProfileEditorVm> {
     }
     if draft.repo_url.trim().is_empty() {
         repo_url_error = Some("Repository URL is required".into());
+    } else if let Err(e) = std::iter::once(draft.repo_url.as_str())
+        .chain(draft.extra_repos.iter().map(String::as_str))
+        .filter(|u| !u.trim().is_empty())
+        .try_for_each(|u| fleet_pipeline::sync::remote::normalize_repo_url(u).map(|_| ()))
+    {
+        repo_url_error = Some(e.to_string());
     }
     if draft.local_path.trim().is_empty() {
         path_error = Some("Local path is required".into());
@@ -0,0 +1,62 @@
+use chrono::NaiveTime;
+use fleet_app_core::domain::{AppSettings, BandwidthWindow};
+
+fn at(hh: u32, mm: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(hh, mm, 0).unwrap()
+}
+
+fn window(start: &str, end: &str, max_speed_bytes: u64) -> BandwidthWindow {
+    BandwidthWindow {
+        start: start.into(),
+        end: end.into(),
+        max_speed_bytes,
+    }
+}
+
+#[test]
+fn no_schedule_keeps_the_fixed_limit() {
+    let mut settings = AppSettings::default();
+    assert_eq!(settings.rate_limit_at(at(12, 0)), None);
+    settings.speed_limit_enabled = true;
+    assert_eq!(
+        settings.rate_limit_at(at(12, 0)),
+        Some(settings.max_speed_bytes)
+    );
+}
+
+#[test]
+fn windows_apply_by_time_of_day_and_wrap_past_midnight() {
+    let settings = AppSettings {
+        speed_limit_enabled: true,
+        max_speed_bytes: 1000,
+        bandwidth_schedule: vec![
+            window("08:00", "18:00", 200),
+            window("23:00", "06:00", 0),
+            window("bogus", "07:00", 5),
+        ],
+        ..AppSettings::default()
+    };
+
+    assert_eq!(settings.rate_limit_at(at(8, 0)), Some(200));
+    assert_eq!(settings.rate_limit_at(at(17, 59)), Some(200));
+    assert_eq!(settings.rate_limit_at(at(18, 0)), Some(1000));
+    assert_eq!(settings.rate_limit_at(at(23, 30)), None, "0 is unlimited");
+    assert_eq!(settings.rate_limit_at(at(3, 0)), None);
+    assert_eq!(settings.rate_limit_at(at(6, 30)), Some(1000));
+}
+
+#[test]
+fn schedules_survive_a_settings_round_trip_and_default_to_empty() {
+    let settings = AppSettings {
+        bandwidth_schedule: vec![window("08:00", "18:00", 200)],
+        ..AppSettings::default()
+    };
+    let json = serde_json::to_string(&settings).unwrap();
+    let back: AppSettings = serde_json::from_str(&json).unwrap();
+    assert_eq!(back.bandwidth_schedule, settings.bandwidth_schedule);
+
+    let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    value.as_object_mut().unwrap().remove("bandwidth_schedule");
+    let old: AppSettings = serde_json::from_value(value).unwrap();
+    assert!(old.bandwidth_schedule.is_empty());
+}
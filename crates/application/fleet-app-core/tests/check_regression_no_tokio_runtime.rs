@@ -11,9 +11,22 @@ fn check_does_not_panic_without_tokio_runtime() {
         id: "p1".to_string(),
         name: "Test Profile".to_string(),
         repo_url: "not-a-url".to_string(),
+        extra_repos: Vec::new(),
+        repo_auth: None,
         local_path: dir.path().to_string_lossy().to_string(),
         last_synced: None,
         last_scan: None,
+        scan_history: Vec::new(),
+        throughput_history: Vec::new(),
+        launch_params_override: None,
+        mod_order: Vec::new(),
+        disabled_mods: Vec::new(),
+        unsynced_mods: Vec::new(),
+        excluded_paths: Vec::new(),
+        pinned_repo_checksum: None,
+        last_changes: None,
+        live_notify_url: None,
+        network: Default::default(),
     };
 
     app.state.selected_profile_id = Some(profile.id.clone());
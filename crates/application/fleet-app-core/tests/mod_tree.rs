@@ -0,0 +1,90 @@
+use fleet_app_core::viewmodel::{mod_tree, FileSyncStatus};
+use fleet_core::{DeleteAction, DownloadAction, File, FileType, Manifest, Mod, SyncPlan};
+
+fn file(path: &str, length: u64) -> File {
+    File {
+        path: path.into(),
+        length,
+        checksum: "AAA".into(),
+        file_type: FileType::File,
+        parts: Vec::new(),
+        mtime: None,
+    }
+}
+
+fn download(mod_name: &str, rel_path: &str, size: u64) -> DownloadAction {
+    DownloadAction {
+        mod_name: mod_name.into(),
+        rel_path: rel_path.into(),
+        size,
+        expected_checksum: "BBB".into(),
+        mtime: None,
+        parts: Vec::new(),
+    }
+}
+
+#[test]
+fn tree_marks_each_file_with_what_the_plan_does_to_it() {
+    let baseline = Manifest {
+        version: "1".into(),
+        mods: vec![
+            Mod {
+                name: "@ace".into(),
+                checksum: "M1".into(),
+                files: vec![file("b.pbo", 2), file("a.pbo", 1), file("old.pbo", 3)],
+            },
+            Mod {
+                name: "@gone".into(),
+                checksum: "M2".into(),
+                files: vec![file("x.pbo", 4)],
+            },
+        ],
+    };
+    let plan = SyncPlan {
+        renames: Vec::new(),
+        checks: Vec::new(),
+        downloads: vec![
+            download("@ACE", "A.pbo", 10),
+            download("@ace", "new.pbo", 5),
+            download("@cba", "c.pbo", 7),
+        ],
+        deletes: vec![
+            DeleteAction {
+                path: "@ace/old.pbo".into(),
+            },
+            DeleteAction {
+                path: "@gone".into(),
+            },
+        ],
+    };
+
+    let tree = mod_tree(&baseline, Some(&plan));
+    let names: Vec<&str> = tree.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(names, ["@ace", "@cba", "@gone"]);
+    let files = |i: usize| -> Vec<(&str, u64, FileSyncStatus)> {
+        tree[i]
+            .files
+            .iter()
+            .map(|f| (f.path.as_str(), f.size, f.status))
+            .collect()
+    };
+    assert_eq!(
+        files(0),
+        [
+            ("a.pbo", 10, FileSyncStatus::Outdated),
+            ("b.pbo", 2, FileSyncStatus::Installed),
+            ("new.pbo", 5, FileSyncStatus::Missing),
+            ("old.pbo", 3, FileSyncStatus::Extra),
+        ]
+    );
+    assert_eq!(files(1), [("c.pbo", 7, FileSyncStatus::Missing)]);
+    assert_eq!(files(2), [("x.pbo", 4, FileSyncStatus::Extra)]);
+    assert_eq!(tree[0].checksum, "M1");
+    assert!(tree[1].checksum.is_empty());
+
+    // Without a plan everything in the baseline is simply installed.
+    assert!(mod_tree(&baseline, None)
+        .iter()
+        .flat_map(|m| &m.files)
+        .all(|f| f.status == FileSyncStatus::Installed));
+}
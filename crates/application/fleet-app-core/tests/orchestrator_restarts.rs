@@ -0,0 +1,55 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use fleet_app_core::app_core::DomainEvent;
+use fleet_app_core::orchestrator::PipelineOrchestrator;
+use fleet_app_core::{AppSettings, PipelineRunEvent, PipelineRunId, Profile};
+
+#[test]
+fn rapid_restarts_only_emit_the_last_run() {
+    let dir = tempfile::tempdir().expect("create temp dir");
+    let profile = Profile {
+        id: "p1".to_string(),
+        local_path: dir.path().to_string_lossy().to_string(),
+        ..Profile::default()
+    };
+
+    let (tx, mut rx) = tokio::sync::mpsc::channel(100);
+    let engine = Arc::new(fleet_pipeline::default_engine(reqwest::Client::new()));
+    let mut orchestrator = PipelineOrchestrator::new(engine, tx);
+
+    let run_ids: Vec<PipelineRunId> = (0..5).map(|_| uuid::Uuid::new_v4()).collect();
+    for run_id in &run_ids {
+        orchestrator
+            .start_local_integrity_check(profile.clone(), AppSettings::default(), *run_id)
+            .expect("start check");
+    }
+    let last = *run_ids.last().unwrap();
+
+    // The folder has no `fleet.redb`, so the surviving run fails right after starting.
+    let mut seen = Vec::new();
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while Instant::now() < deadline {
+        match rx.try_recv() {
+            Ok(DomainEvent::PipelineEvent { run_id, ev }) => {
+                let failed = matches!(ev, PipelineRunEvent::Failed { .. });
+                seen.push(run_id);
+                if failed {
+                    break;
+                }
+            }
+            Ok(_) => {}
+            Err(_) => std::thread::sleep(Duration::from_millis(10)),
+        }
+    }
+    // Superseded runs must stay silent even after the surviving one finished.
+    std::thread::sleep(Duration::from_millis(300));
+    while let Ok(ev) = rx.try_recv() {
+        if let DomainEvent::PipelineEvent { run_id, .. } = ev {
+            seen.push(run_id);
+        }
+    }
+
+    assert!(seen.len() >= 2, "expected Started and Failed, got {seen:?}");
+    assert!(seen.iter().all(|id| *id == last), "got {seen:?}");
+}
@@ -0,0 +1,53 @@
+use fleet_app_core::domain::{AppSettings, NetworkOverrides, Profile};
+
+#[test]
+fn unset_overrides_inherit_the_global_settings() {
+    let global = AppSettings {
+        max_threads: 8,
+        speed_limit_enabled: true,
+        max_speed_bytes: 1_000,
+        ..AppSettings::default()
+    };
+    let merged = global.for_profile(&Profile::default());
+    assert_eq!(merged.max_threads, 8);
+    assert!(merged.speed_limit_enabled);
+    assert_eq!(merged.max_speed_bytes, 1_000);
+}
+
+#[test]
+fn set_overrides_replace_the_global_settings() {
+    let global = AppSettings {
+        max_threads: 8,
+        speed_limit_enabled: true,
+        max_speed_bytes: 1_000,
+        ..AppSettings::default()
+    };
+    let profile = Profile {
+        network: NetworkOverrides {
+            max_threads: Some(2),
+            speed_limit_enabled: Some(false),
+            max_speed_bytes: None,
+        },
+        ..Profile::default()
+    };
+    let merged = global.for_profile(&profile);
+    assert_eq!(merged.max_threads, 2);
+    assert_eq!(merged.rate_limit_at(chrono::NaiveTime::MIN), None);
+    assert_eq!(merged.max_speed_bytes, 1_000);
+}
+
+#[test]
+fn profiles_without_overrides_round_trip_unchanged() {
+    let json = serde_json::to_value(Profile::default()).unwrap();
+    assert!(json.get("network").is_none());
+
+    let profile = Profile {
+        network: NetworkOverrides {
+            max_threads: Some(16),
+            ..NetworkOverrides::default()
+        },
+        ..Profile::default()
+    };
+    let back: Profile = serde_json::from_value(serde_json::to_value(&profile).unwrap()).unwrap();
+    assert_eq!(back.network, profile.network);
+}
@@ -0,0 +1,73 @@
+use fleet_app_core::domain::{AppSettings, Profile, THROUGHPUT_HISTORY_LEN};
+use fleet_app_core::viewmodel::{estimate_sync_duration, expected_sync_speed};
+use fleet_core::{DownloadAction, SyncPlan};
+use std::time::Duration;
+
+fn plan(sizes: &[u64]) -> SyncPlan {
+    SyncPlan {
+        renames: vec![],
+        checks: vec![],
+        deletes: vec![],
+        downloads: sizes
+            .iter()
+            .enumerate()
+            .map(|(i, &size)| DownloadAction {
+                mod_name: "@mod".into(),
+                rel_path: format!("{i}.txt"),
+                size,
+                expected_checksum: String::new(),
+                mtime: None,
+                parts: vec![],
+            })
+            .collect(),
+    }
+}
+
+#[test]
+fn planned_bytes_are_divided_by_the_speed_and_rounded_up() {
+    let plan = plan(&[600_000_000, 600_000_001]);
+    assert_eq!(
+        estimate_sync_duration(&plan, 5_000_000),
+        Some(Duration::from_secs(241))
+    );
+    assert_eq!(estimate_sync_duration(&plan, 0), None);
+    assert_eq!(estimate_sync_duration(&self::plan(&[]), 5_000_000), None);
+}
+
+#[test]
+fn throughput_history_keeps_the_most_recent_syncs() {
+    let mut profile = Profile::default();
+    assert_eq!(profile.average_throughput(), None);
+
+    profile.record_throughput(0);
+    assert!(profile.throughput_history.is_empty());
+
+    for bps in 1..=THROUGHPUT_HISTORY_LEN as u64 + 2 {
+        profile.record_throughput(bps * 1_000);
+    }
+    assert_eq!(profile.throughput_history.len(), THROUGHPUT_HISTORY_LEN);
+    assert_eq!(profile.throughput_history[0], 3_000);
+    assert_eq!(profile.average_throughput(), Some(5_000));
+}
+
+#[test]
+fn expected_speed_is_the_average_capped_by_the_limit() {
+    let limited = AppSettings {
+        speed_limit_enabled: true,
+        max_speed_bytes: 4_000,
+        ..AppSettings::default()
+    };
+    let unlimited = AppSettings {
+        speed_limit_enabled: false,
+        ..AppSettings::default()
+    };
+    let mut profile = Profile::default();
+    assert_eq!(expected_sync_speed(&unlimited, &profile), None);
+    assert_eq!(expected_sync_speed(&limited, &profile), Some(4_000));
+
+    profile.record_throughput(3_000);
+    assert_eq!(expected_sync_speed(&limited, &profile), Some(3_000));
+    profile.record_throughput(9_000);
+    assert_eq!(expected_sync_speed(&limited, &profile), Some(4_000));
+    assert_eq!(expected_sync_speed(&unlimited, &profile), Some(6_000));
+}
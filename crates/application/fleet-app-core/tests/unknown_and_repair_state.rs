@@ -10,9 +10,22 @@ fn dashboard_state_is_unknown_when_fleet_redb_is_missing() {
         id: "p1".to_string(),
         name: "Test".to_string(),
         repo_url: "http://example.invalid/repo.json".to_string(),
+        extra_repos: Vec::new(),
+        repo_auth: None,
         local_path: dir.path().to_string_lossy().to_string(),
         last_synced: None,
         last_scan: None,
+        scan_history: Vec::new(),
+        throughput_history: Vec::new(),
+        launch_params_override: None,
+        mod_order: Vec::new(),
+        disabled_mods: Vec::new(),
+        unsynced_mods: Vec::new(),
+        excluded_paths: Vec::new(),
+        pinned_repo_checksum: None,
+        last_changes: None,
+        live_notify_url: None,
+        network: Default::default(),
     };
 
     let state = AppState {
@@ -47,9 +60,22 @@ fn dashboard_state_is_not_unknown_when_fleet_redb_exists() {
         id: "p1".to_string(),
         name: "Test".to_string(),
         repo_url: "http://example.invalid/repo.json".to_string(),
+        extra_repos: Vec::new(),
+        repo_auth: None,
         local_path: dir.path().to_string_lossy().to_string(),
         last_synced: None,
         last_scan: None,
+        scan_history: Vec::new(),
+        throughput_history: Vec::new(),
+        launch_params_override: None,
+        mod_order: Vec::new(),
+        disabled_mods: Vec::new(),
+        unsynced_mods: Vec::new(),
+        excluded_paths: Vec::new(),
+        pinned_repo_checksum: None,
+        last_changes: None,
+        live_notify_url: None,
+        network: Default::default(),
     };
 
     let state = AppState {
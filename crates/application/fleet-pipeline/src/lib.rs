@@ -1,13 +1,15 @@
-mod io_utils;
+pub mod publish;
 pub mod sync;
 pub mod tracker;
 
 // Re-export core engine components
 pub use sync::{
-    default_engine, DefaultSyncEngine, FetchResult, FetchStats, SyncError, SyncMode, SyncOptions,
-    SyncRequest, SyncResult, SyncStats,
+    default_engine, restore_plan, retry_plan, verify_downloads, ChangeLog, ChangeSummary,
+    DefaultSyncEngine, DownloadOrder, FailedCheck, FailedDownload, FetchResult, FetchStats,
+    ModChangeCounts, ModChanges, QuickCheck, RepoSizeInfo, SyncError, SyncMode, SyncOptions,
+    SyncRequest, SyncResult, SyncStats, UnreachableMod,
 };
-pub use tracker::{ProgressTracker, TransferSnapshot};
+pub use tracker::{ModProgress, ProgressTracker, TransferSnapshot};
 
 // Re-export scanner types often needed by consumers
 pub use fleet_scanner::ScanStats;
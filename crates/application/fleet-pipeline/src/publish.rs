@@ -0,0 +1,295 @@
+//! Repository authoring: turns a scanned mods folder into the `repo.json` + `mod.srf`
+//! layout that [`crate::sync::remote::HttpRemoteStateProvider`] consumes.
+
+use crate::sync::remote::{HttpRemoteStateProvider, RemoteStateProvider};
+use crate::sync::SyncError;
+use camino::Utf8Path;
+use fleet_core::formats::repo::RepoModExternal;
+use fleet_core::formats::RepositoryExternal;
+use fleet_core::path_utils::FleetPath;
+use fleet_core::{Manifest, Mod};
+use fleet_scanner::MOD_SRF;
+use std::collections::{HashMap, HashSet};
+
+/// What a publish wrote; mods are listed by name.
+#[derive(Debug, Clone)]
+pub struct PublishReport {
+    pub repo: RepositoryExternal,
+    /// Mods whose `mod.srf` was (re)written.
+    pub rewritten: Vec<String>,
+    /// Mods whose checksum matched the previous `repo.json`; their `mod.srf` was left alone.
+    pub unchanged: Vec<String>,
+    /// Whether `repo.json` itself was written.
+    pub repo_json_written: bool,
+}
+
+/// What an upload has to change on the remote so it serves the published repository.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UploadPlan {
+    /// Files to `PUT`, in order: content, then each `mod.srf`, then `repo.json`, so a
+    /// remote never advertises files it doesn't have yet.
+    pub puts: Vec<String>,
+    /// Files the remote still has but the repository no longer lists; removed once the new
+    /// `repo.json` is up.
+    pub deletes: Vec<String>,
+}
+
+/// Diffs the published `local` manifest against what the remote currently serves (an empty
+/// manifest for a fresh remote). Only files whose checksum differs are uploaded, and
+/// everything the remote has beyond `local` is deleted.
+pub fn plan_upload(local: &Manifest, remote: &Manifest) -> UploadPlan {
+    let remote_mods: HashMap<&str, &Mod> =
+        remote.mods.iter().map(|m| (m.name.as_str(), m)).collect();
+    let local_names: HashSet<&str> = local.mods.iter().map(|m| m.name.as_str()).collect();
+
+    let (mut puts, mut srfs, mut deletes) = (Vec::new(), Vec::new(), Vec::new());
+    for m in &local.mods {
+        let remote_files: HashMap<String, &str> = remote_mods
+            .get(m.name.as_str())
+            .map(|r| {
+                r.files
+                    .iter()
+                    .map(|f| (FleetPath::canonicalize(&f.path), f.checksum.as_str()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let local_files: HashSet<String> = m
+            .files
+            .iter()
+            .map(|f| FleetPath::canonicalize(&f.path))
+            .collect();
+        puts.extend(
+            m.files
+                .iter()
+                .filter(|f| {
+                    remote_files.get(&FleetPath::canonicalize(&f.path))
+                        != Some(&f.checksum.as_str())
+                })
+                .map(|f| format!("{}/{}", m.name, f.path)),
+        );
+        if remote_mods
+            .get(m.name.as_str())
+            .map(|r| r.checksum.as_str())
+            != Some(m.checksum.as_str())
+        {
+            srfs.push(format!("{}/{MOD_SRF}", m.name));
+        }
+        if let Some(r) = remote_mods.get(m.name.as_str()) {
+            deletes.extend(
+                r.files
+                    .iter()
+                    .filter(|f| !local_files.contains(&FleetPath::canonicalize(&f.path)))
+                    .map(|f| format!("{}/{}", m.name, f.path)),
+            );
+        }
+    }
+    for r in remote
+        .mods
+        .iter()
+        .filter(|r| !local_names.contains(r.name.as_str()))
+    {
+        deletes.extend(r.files.iter().map(|f| format!("{}/{}", r.name, f.path)));
+        deletes.push(format!("{}/{MOD_SRF}", r.name));
+    }
+
+    let repo_changed = !puts.is_empty() || !srfs.is_empty() || !deletes.is_empty();
+    puts.extend(srfs);
+    if repo_changed {
+        puts.push("repo.json".to_string());
+    }
+    UploadPlan { puts, deletes }
+}
+
+/// The manifest a remote repository currently serves, or an empty one when it has no
+/// `repo.json` yet.
+pub async fn fetch_published(client: reqwest::Client, url: &str) -> Result<Manifest, SyncError> {
+    let provider = HttpRemoteStateProvider::new(client);
+    match provider.fetch_remote(url).await {
+        Ok(state) => Ok(state.manifest),
+        Err(SyncError::NotFound(what)) if what == "repo.json" => Ok(Manifest::new(Vec::new())),
+        Err(e) => Err(e),
+    }
+}
+
+/// Every file of a published repository, in the same order as [`UploadPlan::puts`]; for
+/// seeding an empty remote.
+pub fn repository_paths(manifest: &Manifest) -> Vec<String> {
+    let mut paths: Vec<String> = manifest
+        .mods
+        .iter()
+        .flat_map(|m| {
+            m.files
+                .iter()
+                .map(move |f| format!("{}/{}", m.name, f.path))
+        })
+        .collect();
+    paths.extend(
+        manifest
+            .mods
+            .iter()
+            .map(|m| format!("{}/{MOD_SRF}", m.name)),
+    );
+    paths.push("repo.json".to_string());
+    paths
+}
+
+/// Destination for a published repository.
+#[async_trait::async_trait]
+pub trait RepoPublisher: Send + Sync {
+    /// Stores the local file `source` at `rel_path` (`/`-separated) below the repository base.
+    async fn put(&self, rel_path: &str, source: &Utf8Path) -> Result<(), SyncError>;
+    /// Removes `rel_path` below the repository base; a file that is already gone is fine.
+    async fn delete(&self, rel_path: &str) -> Result<(), SyncError>;
+}
+
+/// Uploads with HTTP `PUT`, which WebDAV servers and S3-compatible stores accept.
+pub struct HttpPutPublisher {
+    base: reqwest::Url,
+    uploader: fleet_infra::Uploader,
+}
+
+impl HttpPutPublisher {
+    pub fn new(
+        client: reqwest::Client,
+        base_url: &str,
+        rate_limit_bytes: Option<u64>,
+    ) -> Result<Self, SyncError> {
+        let base = crate::sync::remote::normalize_repo_base(base_url)?;
+        if base.scheme() == "file" {
+            return Err(SyncError::InvalidUrl(format!(
+                "{base_url}: uploads need an http:// or https:// URL"
+            )));
+        }
+        Ok(Self {
+            base,
+            uploader: fleet_infra::Uploader::new(client, rate_limit_bytes),
+        })
+    }
+}
+
+impl HttpPutPublisher {
+    fn url(&self, rel_path: &str) -> Result<String, SyncError> {
+        let mut url = self.base.clone();
+        {
+            let mut segs = url
+                .path_segments_mut()
+                .map_err(|_| SyncError::InvalidUrl(self.base.to_string()))?;
+            segs.pop_if_empty();
+            segs.extend(rel_path.split('/').filter(|s| !s.is_empty()));
+        }
+        Ok(url.to_string())
+    }
+}
+
+#[async_trait::async_trait]
+impl RepoPublisher for HttpPutPublisher {
+    async fn put(&self, rel_path: &str, source: &Utf8Path) -> Result<(), SyncError> {
+        let req = fleet_infra::UploadRequest {
+            url: self.url(rel_path)?,
+            source_path: source.to_path_buf(),
+        };
+        self.uploader
+            .upload(&req)
+            .await
+            .map_err(|e| SyncError::Execution(format!("upload of {rel_path} failed: {e}")))
+    }
+
+    async fn delete(&self, rel_path: &str) -> Result<(), SyncError> {
+        self.uploader
+            .delete(&self.url(rel_path)?)
+            .await
+            .map_err(|e| SyncError::Execution(format!("delete of {rel_path} failed: {e}")))
+    }
+}
+
+/// Carries out `plan` against `publisher`, reading uploads from `root`. Steps run one by one
+/// in order and stop at the first failure, so `repo.json` is never pushed ahead of the files
+/// it lists and nothing is deleted before the remote stops advertising it.
+pub async fn upload_changes(
+    root: &Utf8Path,
+    plan: &UploadPlan,
+    publisher: &dyn RepoPublisher,
+) -> Result<(), SyncError> {
+    for rel_path in &plan.puts {
+        publisher.put(rel_path, &root.join(rel_path)).await?;
+    }
+    for rel_path in &plan.deletes {
+        publisher.delete(rel_path).await?;
+    }
+    Ok(())
+}
+
+/// The `repo.json` describing `mods`, all required and enabled, in the given order.
+pub fn repository_json(repo_name: &str, mods: &[Mod]) -> RepositoryExternal {
+    RepositoryExternal {
+        repo_name: repo_name.to_string(),
+        checksum: fleet_infra::compute_repo_checksum(mods),
+        required_mods: mods
+            .iter()
+            .map(|m| RepoModExternal {
+                mod_name: m.name.clone(),
+                checksum: m.checksum.clone(),
+                enabled: true,
+            })
+            .collect(),
+        optional_mods: Vec::new(),
+    }
+}
+
+/// Writes `<mod>/mod.srf` for every mod in `manifest` and then `repo.json` under `root`,
+/// all mods required and enabled, with each file's mtime as scanned so clients can keep it.
+/// `repo.json` goes last so a client never sees it point at metadata that isn't there yet.
+///
+/// Re-publishing is incremental: a mod whose checksum matches the existing `repo.json`
+/// keeps its `mod.srf`, and nothing is written at all when no mod changed, so file mtimes
+/// (and CDN caches) only move for what actually changed.
+pub fn write_repository(
+    root: &Utf8Path,
+    repo_name: &str,
+    manifest: &Manifest,
+) -> Result<PublishReport, SyncError> {
+    let io_err = |what: &str, e: &dyn std::fmt::Display| SyncError::Local(format!("{what}: {e}"));
+
+    let previous_repo: Option<RepositoryExternal> = std::fs::read(root.join("repo.json"))
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok());
+    let previous: HashMap<&str, &str> = previous_repo
+        .iter()
+        .flat_map(|repo| repo.required_mods.iter().chain(&repo.optional_mods))
+        .map(|m| (m.mod_name.as_str(), m.checksum.as_str()))
+        .collect();
+
+    let mut mods = manifest.mods.clone();
+    mods.sort_by_key(|m| m.name.to_lowercase());
+    let (mut rewritten, mut unchanged) = (Vec::new(), Vec::new());
+    for m in &mods {
+        let path = root.join(&m.name).join(MOD_SRF);
+        if previous.get(m.name.as_str()) == Some(&m.checksum.as_str()) && path.is_file() {
+            unchanged.push(m.name.clone());
+            continue;
+        }
+        let srf = serde_json::to_vec_pretty(m).map_err(|e| io_err(&m.name, &e))?;
+        std::fs::write(&path, srf).map_err(|e| io_err(path.as_str(), &e))?;
+        rewritten.push(m.name.clone());
+    }
+
+    let repo = repository_json(repo_name, &mods);
+    let same_repo = rewritten.is_empty()
+        && previous_repo.as_ref().is_some_and(|old| {
+            old.repo_name == repo.repo_name
+                && old.checksum == repo.checksum
+                && old.required_mods.len() == mods.len()
+                && old.optional_mods.is_empty()
+        });
+    if !same_repo {
+        let json = serde_json::to_vec_pretty(&repo).map_err(|e| io_err("repo.json", &e))?;
+        let path = root.join("repo.json");
+        std::fs::write(&path, json).map_err(|e| io_err(path.as_str(), &e))?;
+    }
+    Ok(PublishReport {
+        repo,
+        rewritten,
+        unchanged,
+        repo_json_written: !same_repo,
+    })
+}
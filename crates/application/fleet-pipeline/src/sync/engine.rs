@@ -1,24 +1,36 @@
 use camino::Utf8Path;
-use fleet_core::diff::diff as diff_manifests;
-use fleet_core::SyncPlan;
+use fleet_core::diff::{diff as diff_manifests, diff_report, DiffReport};
+use fleet_core::{RiskLevel, SyncPlan};
 use fleet_infra::net::DownloadEvent;
 use futures::StreamExt;
 use tokio::sync::mpsc::Sender;
 
-use crate::sync::execute::{DefaultPlanExecutor, PlanExecutor};
-use crate::sync::local::{DefaultLocalStateProvider, LocalState, LocalStateProvider};
-use crate::sync::remote::{HttpRemoteStateProvider, RemoteStateProvider};
+use crate::sync::execute::{DefaultPlanExecutor, PlanExecutor, SyncArtifact};
+use crate::sync::local::{
+    DefaultLocalStateProvider, LocalState, LocalStateProvider, LocalTrustLevel,
+};
+use crate::sync::plan_file::SavedPlan;
+use crate::sync::remote::{
+    CacheValidators, Conditional, HttpRemoteStateProvider, RemoteStateProvider,
+};
 use crate::sync::storage::{
-    FileRepoSummaryStore, LocalFileSummary, LocalManifestSummary, RepoSummary, RepoSummaryStore,
+    CachedModSrf, FileRepoSummaryStore, LocalFileSummary, LocalManifestSummary, RepoSummaryStore,
+    MISSING_SRF_TTL,
+};
+use crate::sync::{
+    retry_plan, FailedDownload, SyncError, SyncMode, SyncOptions, SyncRequest, SyncResult,
+    SyncStats,
 };
-use crate::sync::{SyncError, SyncMode, SyncOptions, SyncRequest, SyncResult, SyncStats};
-use fleet_core::path_utils::FleetPath;
+use fleet_core::path_utils::{is_fleet_artifact, FleetPath};
 use fleet_persistence::{
-    CacheDeleteRecord, CacheRenameRecord, CacheUpsertRecord, FleetDataStore, RedbFleetDataStore,
+    CacheDeleteRecord, CacheRenameRecord, CacheUpsert, CacheUpsertRecord, FleetDataStore,
+    RedbFleetDataStore,
 };
-use fleet_scanner::Scanner;
-use std::collections::HashMap;
+use fleet_scanner::{ScanOptions, ScanStrategy, Scanner};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::time::Instant;
+use tokio_util::sync::CancellationToken;
 
 pub struct DefaultSyncEngine {
     remote: Box<dyn RemoteStateProvider>,
@@ -62,56 +74,175 @@ impl DefaultSyncEngine {
 
     /// Step 1: Network only. Fetch repo.json and mod.srf files.
     /// This is the Phase 1: Network Discovery step.
+    ///
+    /// With extra repositories, each one is fetched and merged into a single manifest;
+    /// a mod name provided by more than one repository is an error.
+    #[tracing::instrument(name = "fetch", skip_all, fields(repo = %req.repo_url, root = %req.local_root))]
     pub async fn fetch_remote_state(
         &self,
         req: &SyncRequest,
     ) -> Result<crate::sync::FetchResult, SyncError> {
-        let cached_repo_summary = if let Some(pid) = req.profile_id.as_deref() {
+        if req.options.offline {
+            return self.cached_remote_state(req);
+        }
+        let started = Instant::now();
+        let mut merged: Option<crate::sync::FetchResult> = None;
+
+        for (repo_url, summary_key) in req.repo_urls().zip(repo_summary_keys(req)) {
+            let res = self
+                .fetch_repo_state(req, repo_url, summary_key.as_deref())
+                .await?;
+
+            let Some(acc) = merged.as_mut() else {
+                merged = Some(res);
+                continue;
+            };
+            for m in res.manifest.mods {
+                if let Some(existing) = acc.mod_sources.get(&m.name) {
+                    return Err(SyncError::Remote(format!(
+                        "mod {} is provided by both {existing} and {repo_url}",
+                        m.name
+                    )));
+                }
+                acc.mod_sources.insert(m.name.clone(), repo_url.to_string());
+                acc.manifest.mods.push(m);
+            }
+            acc.stats.mods_total += res.stats.mods_total;
+            acc.stats.mods_fetched += res.stats.mods_fetched;
+            acc.stats.mods_cached += res.stats.mods_cached;
+            acc.unreachable_mods.extend(res.unreachable_mods);
+        }
+
+        let mut merged =
+            merged.ok_or_else(|| SyncError::Remote("no repository configured".into()))?;
+        merged.case_collisions =
+            fleet_core::path_utils::resolve_case_collisions(&mut merged.manifest);
+        for c in &merged.case_collisions {
+            tracing::warn!(
+                mod_name = %c.mod_name,
+                kept = %c.kept,
+                dropped = ?c.dropped,
+                "manifest paths differ only by case; keeping the first"
+            );
+        }
+        tracing::info!(
+            mods = merged.stats.mods_total,
+            fetched = merged.stats.mods_fetched,
+            cached = merged.stats.mods_cached,
+            unreachable = merged.unreachable_mods.len(),
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "fetched remote state"
+        );
+        Ok(merged)
+    }
+
+    /// Offline stand-in for [`Self::fetch_remote_state`]: the manifest recorded by the last
+    /// successful sync.
+    fn cached_remote_state(
+        &self,
+        req: &SyncRequest,
+    ) -> Result<crate::sync::FetchResult, SyncError> {
+        let manifest = self
+            .fleet_data
+            .load_baseline_manifest(&req.local_root)
+            .map_err(|e| {
+                SyncError::Remote(format!(
+                    "offline and no cached manifest for {} ({e}); sync once while online",
+                    req.local_root
+                ))
+            })?;
+        let mods = manifest.mods.len();
+        let mod_sources = manifest
+            .mods
+            .iter()
+            .map(|m| (m.name.clone(), req.repo_url.clone()))
+            .collect();
+        tracing::info!(mods, "offline: using cached manifest");
+        Ok(crate::sync::FetchResult {
+            manifest,
+            stats: crate::sync::FetchStats {
+                mods_total: mods,
+                mods_fetched: 0,
+                mods_cached: mods,
+            },
+            mod_sources,
+            repo_checksum: String::new(),
+            case_collisions: Vec::new(),
+            unreachable_mods: Vec::new(),
+        })
+    }
+
+    #[tracing::instrument(name = "fetch_repo", skip_all, fields(repo = %repo_url))]
+    async fn fetch_repo_state(
+        &self,
+        req: &SyncRequest,
+        repo_url: &str,
+        summary_key: Option<&str>,
+    ) -> Result<crate::sync::FetchResult, SyncError> {
+        let mut summary = if let Some(pid) = summary_key {
             self.repo_summary_store
                 .load_repo_summary(pid)
                 .map_err(|e| SyncError::Remote(format!("load repo summary failed: {e}")))?
         } else {
             None
+        }
+        .unwrap_or_default();
+        let mut summary_dirty = false;
+
+        // Only offer validators when the cached copy is usable, so a 304 always has a fallback.
+        let cached_repo =
+            serde_json::from_str::<fleet_core::formats::RepositoryExternal>(&summary.repo_json)
+                .ok();
+        let repo_validators = if cached_repo.is_some() {
+            summary.validators()
+        } else {
+            CacheValidators::default()
         };
 
-        let remote_mtime = self
+        let repo_external = match self
             .remote
-            .head_repo_json_mtime(&req.repo_url)
-            .await
-            .unwrap_or(None);
-
-        let mut repo_external: Option<fleet_core::formats::RepositoryExternal> = None;
-
-        if let (Some(cached), Some(ref mtime)) = (&cached_repo_summary, &remote_mtime) {
-            if cached.last_modified.as_ref() == Some(mtime) {
-                if let Ok(repo_ext) = serde_json::from_str::<fleet_core::formats::RepositoryExternal>(
-                    &cached.repo_json,
-                ) {
-                    repo_external = Some(repo_ext);
+            .fetch_repo_json_conditional(repo_url, &repo_validators)
+            .await?
+        {
+            Conditional::NotModified => cached_repo.ok_or_else(|| {
+                SyncError::Remote("repo.json: 304 Not Modified without a cached copy".into())
+            })?,
+            Conditional::Fetched { value, validators } => {
+                let repo_json = serde_json::to_string(&value)
+                    .map_err(|e| SyncError::Remote(format!("serialize repo.json: {e}")))?;
+                // A changed repo.json may mean the repository was fixed; retry every srf.
+                if repo_json != summary.repo_json
+                    || validators.etag != summary.etag
+                    || validators.last_modified != summary.last_modified
+                {
+                    summary.missing_srfs.clear();
                 }
+                summary.etag = validators.etag;
+                summary.last_modified = validators.last_modified;
+                summary.repo_json = repo_json;
+                summary_dirty = true;
+                value
             }
-        }
-
-        if repo_external.is_none() {
-            let fetched = self.remote.fetch_repo_json(&req.repo_url).await?;
-
-            if let Some(pid) = req.profile_id.as_deref() {
-                let summary = RepoSummary {
-                    last_modified: remote_mtime.clone(),
-                    repo_json: serde_json::to_string(&fetched)
-                        .map_err(|e| SyncError::Remote(format!("serialize repo.json: {e}")))?,
-                };
-                let _ = self.repo_summary_store.save_repo_summary(pid, &summary);
-            }
+        };
 
-            repo_external = Some(fetched);
-        }
+        let repository: fleet_core::repo::Repository = repo_external.into();
+        verify_repo_checksum(&repository)?;
+        let repo_checksum = repository.checksum.clone();
+        let base = crate::sync::remote::normalize_repo_base(repo_url)?;
 
-        let repository: fleet_core::repo::Repository = repo_external
-            .clone()
-            .ok_or_else(|| SyncError::Remote("repository unavailable".into()))?
-            .into();
-        let base = crate::sync::remote::normalize_repo_base(&req.repo_url)?;
+        let required: HashSet<String> = repository
+            .required_mods
+            .iter()
+            .map(|m| m.mod_name.clone())
+            .collect();
+        let before = summary.mod_srfs.len() + summary.missing_srfs.len();
+        summary.mod_srfs.retain(|name, _| required.contains(name));
+        let now = unix_now();
+        let ttl = MISSING_SRF_TTL.as_secs();
+        summary
+            .missing_srfs
+            .retain(|name, at| required.contains(name) && now.saturating_sub(*at) < ttl);
+        summary_dirty |= summary.mod_srfs.len() + summary.missing_srfs.len() != before;
 
         let mut mods = Vec::new();
         // Differential Analysis: reuse local manifest entries when checksum matches
@@ -144,68 +275,358 @@ impl DefaultSyncEngine {
             }
         }
 
-        let mods_to_fetch_count = mods_to_fetch.len();
+        // A recent 404 is reported again without asking the server.
+        let mut results: Vec<(String, Result<Conditional<fleet_core::Mod>, SyncError>)> =
+            Vec::new();
+        mods_to_fetch.retain(|rmod| {
+            if !summary.missing_srfs.contains_key(&rmod.mod_name) {
+                return true;
+            }
+            results.push((
+                rmod.mod_name.clone(),
+                Err(SyncError::NotFound(format!(
+                    "{}/mod.srf (cached; retried after {} min or when repo.json changes)",
+                    rmod.mod_name,
+                    MISSING_SRF_TTL.as_secs() / 60
+                ))),
+            ));
+            false
+        });
 
-        // Fetch only what changed, concurrently
+        // Fetch only what changed, concurrently; mods with a cached srf are revalidated.
+        // Once the host has failed often enough in a row the rest are not attempted.
+        let breaker = req.options.circuit_breaker();
         let remote_ref = &*self.remote;
-        let fetch_stream = futures::stream::iter(mods_to_fetch.into_iter())
-            .map(move |rmod| {
+        let cached_srfs = &summary.mod_srfs;
+        let fetch_stream = futures::stream::iter(mods_to_fetch)
+            .map(|rmod| {
                 let base = base.clone();
                 let remote = remote_ref;
-                async move { remote.fetch_mod_srf(&base, &rmod.mod_name).await }
+                let breaker = breaker.as_deref();
+                let validators = cached_srfs
+                    .get(&rmod.mod_name)
+                    .map(|c| c.validators.clone())
+                    .unwrap_or_default();
+                async move {
+                    if let Some(e) = breaker.and_then(repo_unavailable) {
+                        return (rmod.mod_name, Err(e));
+                    }
+                    let res = remote
+                        .fetch_mod_srf_conditional(&base, &rmod.mod_name, &validators)
+                        .await;
+                    if let Some(breaker) = breaker {
+                        // A 404 or a malformed srf is an answer; unreachable hosts and server
+                        // errors are not.
+                        match &res {
+                            Err(SyncError::HostFailure(_)) => breaker.record_failure(base.as_str()),
+                            _ => breaker.record_success(base.as_str()),
+                        }
+                    }
+                    (rmod.mod_name, res)
+                }
             })
-            .buffer_unordered(20);
+            .buffer_unordered(req.options.metadata_concurrency());
 
-        let results: Vec<Result<fleet_core::Mod, SyncError>> = fetch_stream.collect().await;
+        let fetched: Vec<(String, Result<Conditional<fleet_core::Mod>, SyncError>)> =
+            fetch_stream.collect().await;
+        for (mod_name, res) in &fetched {
+            if summary_key.is_some() && matches!(res, Err(SyncError::NotFound(_))) {
+                summary.missing_srfs.insert(mod_name.clone(), now);
+                summary_dirty = true;
+            }
+        }
+        results.extend(fetched);
+
+        let mut mods_fetched = 0;
+        let mut unreachable_mods = Vec::new();
+        let mut failure = None;
+        for (mod_name, res) in results {
+            let res = match res {
+                Ok(res) => res,
+                Err(e) if req.options.skip_unreachable_mods => {
+                    tracing::warn!(mod_name = %mod_name, error = %e, "mod.srf unreachable; leaving the mod as is");
+                    // Keep the last synced entry so the saved baseline still describes it.
+                    if let Some(known) = last_known_manifest
+                        .as_ref()
+                        .and_then(|m| m.mods.iter().find(|m| m.name == mod_name))
+                    {
+                        mods.push(known.clone());
+                    }
+                    unreachable_mods.push(crate::sync::UnreachableMod {
+                        mod_name,
+                        repo_url: repo_url.to_string(),
+                        reason: e.to_string(),
+                    });
+                    continue;
+                }
+                Err(e) => {
+                    // Saved below first, so the next check remembers this run's 404s.
+                    failure.get_or_insert(e);
+                    continue;
+                }
+            };
+            match res {
+                Conditional::NotModified => {
+                    let cached = summary.mod_srfs.get(&mod_name).ok_or_else(|| {
+                        SyncError::Remote(format!(
+                            "srf for {mod_name}: 304 Not Modified without a cached copy"
+                        ))
+                    })?;
+                    mods.push(cached.srf.clone());
+                }
+                Conditional::Fetched { value, validators } => {
+                    mods_fetched += 1;
+                    if summary_key.is_some() {
+                        summary.mod_srfs.insert(
+                            mod_name,
+                            CachedModSrf {
+                                validators,
+                                srf: value.clone(),
+                            },
+                        );
+                        summary_dirty = true;
+                    }
+                    mods.push(value);
+                }
+            }
+        }
 
-        for res in results {
-            mods.push(res?);
+        if let Some(pid) = summary_key {
+            if summary_dirty {
+                let _ = self.repo_summary_store.save_repo_summary(pid, &summary);
+            }
+        }
+        if let Some(e) = failure {
+            return Err(breaker.as_deref().and_then(repo_unavailable).unwrap_or(e));
+        }
+        // Leaving every mod as is would report a dead repository as up to date.
+        if total_mods > 0 && unreachable_mods.len() == total_mods {
+            return Err(breaker
+                .as_deref()
+                .and_then(repo_unavailable)
+                .unwrap_or_else(|| {
+                    SyncError::Remote(format!(
+                        "none of the {total_mods} mods in {repo_url} could be fetched: {}",
+                        unreachable_mods[0].reason
+                    ))
+                }));
         }
 
         let stats = crate::sync::FetchStats {
             mods_total: total_mods,
-            mods_fetched: mods_to_fetch_count,
-            mods_cached: total_mods.saturating_sub(mods_to_fetch_count),
+            mods_fetched,
+            mods_cached: total_mods.saturating_sub(mods_fetched + unreachable_mods.len()),
         };
 
+        let mod_sources = mods
+            .iter()
+            .map(|m| (m.name.clone(), repo_url.to_string()))
+            .collect();
+
         Ok(crate::sync::FetchResult {
-            manifest: fleet_core::Manifest {
-                version: "1.0".to_string(),
-                mods,
-            },
+            manifest: fleet_core::Manifest::new(mods),
             stats,
+            mod_sources,
+            repo_checksum,
+            case_collisions: Vec::new(),
+            unreachable_mods,
         })
     }
 
     /// Validate that the repository URL is reachable and returns a parsable repo.json.
     pub async fn validate_repo_url(&self, repo_url: &str) -> Result<(), SyncError> {
-        let _ = self.remote.fetch_repo_json(repo_url).await?;
+        let repo_url = crate::sync::remote::normalize_repo_url(repo_url)?;
+        let _ = self.remote.fetch_repo_json(&repo_url).await?;
         Ok(())
     }
 
+    /// Sum the file sizes of a repository's required mods (and optional ones if asked)
+    /// without touching any local state.
+    pub async fn repo_size(
+        &self,
+        repo_url: &str,
+        include_optional: bool,
+    ) -> Result<crate::sync::RepoSizeInfo, SyncError> {
+        let repo_url = crate::sync::remote::normalize_repo_url(repo_url)?;
+        let repository: fleet_core::repo::Repository =
+            self.remote.fetch_repo_json(&repo_url).await?.into();
+        let base = crate::sync::remote::normalize_repo_base(&repo_url)?;
+
+        let mut names: Vec<String> = repository
+            .required_mods
+            .into_iter()
+            .map(|m| m.mod_name)
+            .collect();
+        if include_optional {
+            names.extend(repository.optional_mods.into_iter().map(|m| m.mod_name));
+        }
+
+        let remote = &*self.remote;
+        let mods: Vec<Result<fleet_core::Mod, SyncError>> = futures::stream::iter(names)
+            .map(|name| {
+                let base = base.clone();
+                async move { remote.fetch_mod_srf(&base, &name).await }
+            })
+            .buffer_unordered(crate::sync::remote::DEFAULT_METADATA_CONCURRENCY)
+            .collect()
+            .await;
+
+        let mut info = crate::sync::RepoSizeInfo {
+            repo_name: repository.repo_name,
+            ..Default::default()
+        };
+        for m in mods {
+            let m = m?;
+            info.mods += 1;
+            info.files += m.files.len();
+            info.total_bytes += m.files.iter().map(|f| f.length).sum::<u64>();
+        }
+        Ok(info)
+    }
+
+    /// Compares the per-mod checksums in each repository's repo.json with the stored baseline
+    /// manifest: one request per repository and no filesystem scan. Optional mods count only
+    /// where the baseline has them, and names match case-insensitively. This trusts the
+    /// baseline, so files changed on disk since the last sync go unnoticed.
+    pub async fn quick_check(
+        &self,
+        req: &SyncRequest,
+    ) -> Result<crate::sync::QuickCheck, SyncError> {
+        if req.options.offline {
+            return Err(SyncError::Remote(
+                "a quick check needs the network; disable offline mode".into(),
+            ));
+        }
+        let baseline = self
+            .fleet_data
+            .load_baseline_manifest(&req.local_root)
+            .map_err(|e| {
+                SyncError::Local(format!(
+                    "no baseline to compare against ({e}); run a full check or sync first"
+                ))
+            })?;
+        let skipped = |name: &str| {
+            req.options
+                .skip_mods
+                .iter()
+                .any(|m| m.eq_ignore_ascii_case(name))
+        };
+
+        // (name, checksum, required); optional mods are compared only where they are installed.
+        let mut remote: Vec<(String, String, bool)> = Vec::new();
+        for repo_url in req.repo_urls() {
+            let repo_url = crate::sync::remote::normalize_repo_url(repo_url)?;
+            let repository: fleet_core::repo::Repository =
+                self.remote.fetch_repo_json(&repo_url).await?.into();
+            let required = repository.required_mods.into_iter().map(|m| (m, true));
+            let optional = repository.optional_mods.into_iter().map(|m| (m, false));
+            for (m, is_required) in required.chain(optional) {
+                match remote
+                    .iter_mut()
+                    .find(|(name, ..)| name.eq_ignore_ascii_case(&m.mod_name))
+                {
+                    Some(entry) => *entry = (m.mod_name, m.checksum, entry.2 || is_required),
+                    None => remote.push((m.mod_name, m.checksum, is_required)),
+                }
+            }
+        }
+
+        let mut check = crate::sync::QuickCheck::default();
+        for (name, checksum, required) in remote.iter().filter(|(name, ..)| !skipped(name)) {
+            match baseline
+                .mods
+                .iter()
+                .find(|m| m.name.eq_ignore_ascii_case(name))
+            {
+                Some(local) if local.checksum == *checksum => {}
+                Some(_) => check.changed.push(name.clone()),
+                None if *required => check.added.push(name.clone()),
+                None => {}
+            }
+        }
+        check.removed = baseline
+            .mods
+            .iter()
+            .filter(|m| {
+                !skipped(&m.name)
+                    && !remote
+                        .iter()
+                        .any(|(name, ..)| name.eq_ignore_ascii_case(&m.name))
+            })
+            .map(|m| m.name.clone())
+            .collect();
+        check.changed.sort();
+        check.added.sort();
+        check.removed.sort();
+        Ok(check)
+    }
+
     /// Step 2: Disk only. Hash/stat local files with optional progress callbacks.
+    #[tracing::instrument(name = "scan", skip_all, fields(root = %req.local_root, mode = ?req.mode))]
     pub async fn scan_local_state(
         &self,
         req: &SyncRequest,
         on_progress: Option<Box<dyn Fn(fleet_scanner::ScanStats) + Send + Sync>>,
+        cancel: Option<&CancellationToken>,
     ) -> Result<LocalState, SyncError> {
-        self.local
-            .local_state(&req.local_root, req.mode, on_progress)
-            .await
+        let started = Instant::now();
+        let local = self
+            .local
+            .local_state(
+                &req.local_root,
+                req.mode,
+                req.options.scan_threads,
+                req.options.sequential_scan,
+                on_progress,
+                cancel,
+            )
+            .await?;
+        tracing::info!(
+            mods = local.manifest.mods.len(),
+            files = local.manifest.mods.iter().map(|m| m.files.len()).sum::<usize>(),
+            trust = ?local.trust,
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "scanned local state"
+        );
+        Ok(local)
     }
 
-    /// Step 3: CPU only. Diff remote + local into a SyncPlan.
+    /// Step 3: CPU only. Diff remote + local into a SyncPlan. Unreachable mods are left out
+    /// like skipped ones.
+    #[tracing::instrument(name = "diff", skip_all, fields(root = %req.local_root))]
     pub fn compute_plan(
         &self,
-        remote: &fleet_core::Manifest,
+        remote: &crate::sync::FetchResult,
         local: &LocalState,
         req: &SyncRequest,
     ) -> Result<SyncPlan, SyncError> {
-        Ok(diff_manifests(remote, &local.manifest))
+        // An offline fetch has no checksum to compare and never downloads anyway.
+        if let Some(pinned) = &req.options.pinned_repo_checksum {
+            if !remote.repo_checksum.is_empty()
+                && !remote.repo_checksum.eq_ignore_ascii_case(pinned)
+            {
+                return Err(SyncError::PinnedVersion {
+                    pinned: pinned.clone(),
+                    live: remote.repo_checksum.clone(),
+                });
+            }
+        }
+        let started = Instant::now();
+        let mut plan = diff_manifests(&remote.manifest, &local.manifest);
+        if req.options.skip_deletes {
+            plan.deletes.clear();
+        }
+        exclude_mods(&mut plan, &req.options.skip_mods);
+        exclude_mods(&mut plan, &remote.unreachable_mod_names());
+        exclude_paths(&mut plan, &req.options.exclude)?;
+        keep_fleet_artifacts(&mut plan);
+        log_plan(&plan, started);
+        Ok(plan)
     }
 
     /// Builds a plan without any network I/O by comparing current local state against the last
     /// persisted local summary stored in `fleet.redb` (captured at the end of a successful sync).
+    #[tracing::instrument(name = "integrity_diff", skip_all, fields(root = %req.local_root))]
     pub fn compute_local_integrity_plan(
         &self,
         req: &SyncRequest,
@@ -235,14 +656,102 @@ impl DefaultSyncEngine {
             .clone()
             .ok_or_else(|| SyncError::Local("Local scan did not produce a summary".into()))?;
 
-        Ok(build_fast_plan(&expected, &current))
+        let started = Instant::now();
+        let mut plan = build_fast_plan(&expected, &current, req.options.skip_deletes);
+        exclude_mods(&mut plan, &req.options.skip_mods);
+        exclude_paths(&mut plan, &req.options.exclude)?;
+        keep_fleet_artifacts(&mut plan);
+        log_plan(&plan, started);
+        Ok(plan)
+    }
+
+    /// Rates `plan` against the local state it was diffed from using the request's
+    /// delete threshold. Plans are always [`RiskLevel::Safe`] when the threshold is disabled.
+    pub fn assess_plan(&self, plan: &SyncPlan, local: &LocalState, req: &SyncRequest) -> RiskLevel {
+        match &req.options.delete_threshold {
+            Some(threshold) => plan.delete_risk_against(&local.manifest, threshold),
+            None => RiskLevel::Safe,
+        }
     }
 
     /// Pure planning step - fetch remote, scan local, diff.
     pub async fn plan(&self, req: &SyncRequest) -> Result<SyncPlan, SyncError> {
         let fetch_res = self.fetch_remote_state(req).await?;
-        let local = self.scan_local_state(req, None).await?;
-        self.compute_plan(&fetch_res.manifest, &local, req)
+        let local = self.scan_local_state(req, None, None).await?;
+        self.compute_plan(&fetch_res, &local, req)
+    }
+
+    /// Like [`Self::plan`], also returning how every file compares (unaffected by skipped
+    /// mods and exclusions).
+    pub async fn plan_with_report(
+        &self,
+        req: &SyncRequest,
+    ) -> Result<(SyncPlan, DiffReport), SyncError> {
+        self.plan_with_remote(req)
+            .await
+            .map(|(plan, report, _)| (plan, report))
+    }
+
+    /// Like [`Self::plan_with_report`], also returning the remote state the plan was computed
+    /// against, e.g. for [`Self::save_plan`].
+    pub async fn plan_with_remote(
+        &self,
+        req: &SyncRequest,
+    ) -> Result<(SyncPlan, DiffReport, crate::sync::FetchResult), SyncError> {
+        let fetch_res = self.fetch_remote_state(req).await?;
+        let local = self.scan_local_state(req, None, None).await?;
+        let plan = self.compute_plan(&fetch_res, &local, req)?;
+        let report = diff_report(&fetch_res.manifest, &local.manifest);
+        Ok((plan, report, fetch_res))
+    }
+
+    /// Read-only audit of the folder against the repository: fetches, hashes every local file
+    /// and diffs, returning what a sync would do. Unlike [`Self::plan`] nothing is written:
+    /// no downloads or deletes, and neither `fleet.redb`, its scan cache nor the repo summary
+    /// is created or updated. `req.mode` is ignored: an audit always hashes every file, as
+    /// [`SyncMode::FullRehash`] would.
+    #[tracing::instrument(name = "audit", skip_all, fields(repo = %req.repo_url, root = %req.local_root))]
+    pub async fn audit(&self, req: &SyncRequest) -> Result<(SyncPlan, DiffReport), SyncError> {
+        if req.options.offline {
+            return Err(SyncError::Remote(
+                "an audit needs the network; disable offline mode".into(),
+            ));
+        }
+        // Without a profile id the fetch neither reads nor saves a repo summary.
+        let fetch_req = SyncRequest {
+            profile_id: None,
+            ..req.clone()
+        };
+        let remote = self.fetch_remote_state(&fetch_req).await?;
+
+        let root = req.local_root.clone();
+        let options = ScanOptions {
+            threads: req.options.scan_threads,
+            sequential: req.options.sequential_scan,
+            ..ScanOptions::default()
+        };
+        // No cache store: every file is hashed and nothing is recorded.
+        let manifest = tokio::task::spawn_blocking(move || {
+            Scanner::scan_directory_with_options(
+                &root,
+                ScanStrategy::ForceRehash,
+                options,
+                None,
+                None,
+                None,
+            )
+        })
+        .await
+        .map_err(|e| SyncError::Local(format!("scan join failed: {e}")))?
+        .map_err(|e| SyncError::Local(format!("scan failed: {e}")))?;
+        let local = LocalState {
+            manifest,
+            summary: None,
+            trust: LocalTrustLevel::VerifiedFull,
+            warnings: Vec::new(),
+        };
+        let plan = self.compute_plan(&remote, &local, req)?;
+        Ok((plan, diff_report(&remote.manifest, &local.manifest)))
     }
 
     /// Plan + execute.
@@ -252,10 +761,65 @@ impl DefaultSyncEngine {
         progress_tx: Option<Sender<DownloadEvent>>,
     ) -> Result<SyncResult, SyncError> {
         let fetch_res = self.fetch_remote_state(req).await?;
-        let manifest = fetch_res.manifest;
-        let local = self.scan_local_state(req, None).await?;
-        let plan = self.compute_plan(&manifest, &local, req)?;
-        self.execute_with_plan_internal(req, plan, Some(manifest), progress_tx)
+        let local = self.scan_local_state(req, None, None).await?;
+        let plan = self.compute_plan(&fetch_res, &local, req)?;
+        if self.assess_plan(&plan, &local, req) == RiskLevel::Destructive {
+            return Err(SyncError::Destructive {
+                deleted: plan.deleted_file_count(&local.manifest),
+                existing: local.manifest.mods.iter().map(|m| m.files.len()).sum(),
+            });
+        }
+        let unreachable: Vec<String> = fetch_res
+            .unreachable_mods
+            .iter()
+            .map(|m| m.to_string())
+            .collect();
+        let mut result = self
+            .execute_with_plan_internal(req, plan, Some(fetch_res), progress_tx, None)
+            .await?;
+        result
+            .warnings
+            .splice(0..0, local.warnings.into_iter().chain(unreachable));
+        Ok(result)
+    }
+
+    /// Write `plan` as JSON, tagged with the repositories and root it was computed for and
+    /// the remote state it was computed against.
+    pub fn save_plan(
+        &self,
+        req: &SyncRequest,
+        plan: &SyncPlan,
+        remote: &crate::sync::FetchResult,
+        path: &Utf8Path,
+    ) -> Result<(), SyncError> {
+        let saved = SavedPlan::new(req, plan.clone(), remote);
+        let json = serde_json::to_string_pretty(&saved)
+            .map_err(|e| SyncError::Execution(format!("serialize plan: {e}")))?;
+        std::fs::write(path.as_std_path(), json)
+            .map_err(|e| SyncError::Execution(format!("write plan {path}: {e}")))
+    }
+
+    /// Load a plan written by [`Self::save_plan`] and check it still targets `req`.
+    /// The result can be passed straight to [`Self::execute_saved_plan`].
+    pub fn load_plan(&self, req: &SyncRequest, path: &Utf8Path) -> Result<SavedPlan, SyncError> {
+        let data = std::fs::read_to_string(path.as_std_path())
+            .map_err(|e| SyncError::Execution(format!("read plan {path}: {e}")))?;
+        let saved: SavedPlan = serde_json::from_str(&data)
+            .map_err(|e| SyncError::Execution(format!("parse plan {path}: {e}")))?;
+        saved.validate_for(req)?;
+        Ok(saved)
+    }
+
+    /// Executes a plan loaded by [`Self::load_plan`] and records the manifest it was
+    /// computed against as the baseline, without fetching the repository again.
+    pub async fn execute_saved_plan(
+        &self,
+        req: &SyncRequest,
+        saved: SavedPlan,
+        progress_tx: Option<Sender<DownloadEvent>>,
+    ) -> Result<SyncResult, SyncError> {
+        let remote = saved.remote_state();
+        self.execute_with_plan_internal(req, saved.plan, Some(remote), progress_tx, None)
             .await
     }
 
@@ -265,44 +829,215 @@ impl DefaultSyncEngine {
         plan: SyncPlan,
         progress_tx: Option<Sender<DownloadEvent>>,
     ) -> Result<SyncResult, SyncError> {
-        self.execute_with_plan_internal(req, plan, None, progress_tx)
+        self.execute_with_plan_internal(req, plan, None, progress_tx, None)
+            .await
+    }
+
+    /// Like [`Self::execute_with_plan`], stopping when `cancel` fires. Files that finished
+    /// downloading are still committed to the baseline before [`SyncError::Cancelled`] is
+    /// returned, so the next check neither fetches nor deletes them again.
+    pub async fn execute_with_plan_cancellable(
+        &self,
+        req: &SyncRequest,
+        plan: SyncPlan,
+        progress_tx: Option<Sender<DownloadEvent>>,
+        cancel: &CancellationToken,
+    ) -> Result<SyncResult, SyncError> {
+        self.execute_with_plan_internal(req, plan, None, progress_tx, Some(cancel))
             .await
     }
 
+    /// Downloads only the files a previous sync reported in [`SyncError::Downloads`], without
+    /// re-planning; everything else is assumed to be in place. Only the retried mods move to
+    /// the repository's current state in the baseline; the others keep the entry the failed
+    /// sync left, since their files were not looked at. A retried mod that has changed on the
+    /// repository since the failed sync (or whose planned checksum is unknown) refuses the
+    /// retry, as its other files would be recorded with checksums they do not have.
+    pub async fn retry_failures(
+        &self,
+        req: &SyncRequest,
+        failures: &[FailedDownload],
+        progress_tx: Option<Sender<DownloadEvent>>,
+    ) -> Result<SyncResult, SyncError> {
+        let mut remote = self.fetch_remote_state(req).await?;
+        for f in failures {
+            let live = remote
+                .manifest
+                .mods
+                .iter()
+                .find(|m| m.name == f.mod_name)
+                .map(|m| m.checksum.as_str());
+            if f.mod_checksum.is_empty() || live != Some(f.mod_checksum.as_str()) {
+                return Err(SyncError::Remote(format!(
+                    "{} has changed on the repository since the failed sync; run a new check",
+                    f.mod_name
+                )));
+            }
+        }
+        match self.fleet_data.load_baseline_manifest(&req.local_root) {
+            Ok(baseline) => {
+                let retried: HashSet<&str> = failures.iter().map(|f| f.mod_name.as_str()).collect();
+                let mut mods: Vec<fleet_core::Mod> = baseline
+                    .mods
+                    .into_iter()
+                    .filter(|m| !retried.contains(m.name.as_str()))
+                    .collect();
+                mods.extend(
+                    remote
+                        .manifest
+                        .mods
+                        .iter()
+                        .filter(|m| retried.contains(m.name.as_str()))
+                        .cloned(),
+                );
+                remote.manifest = fleet_core::Manifest::new(mods);
+            }
+            // Nothing was recorded yet, so the fetched state is all there is.
+            Err(e) if e.kind() == fleet_persistence::StorageErrorKind::Missing => {}
+            Err(e) => {
+                return Err(SyncError::Local(format!(
+                    "failed to load the baseline to retry against: {e}"
+                )))
+            }
+        }
+        self.execute_with_plan_internal(req, retry_plan(failures), Some(remote), progress_tx, None)
+            .await
+    }
+
+    /// Downloads just `files` (`(mod_name, rel_path)`) with the checksums and sizes the last
+    /// sync recorded, without a check; for restoring a few files deleted from a current mod.
+    /// The baseline is kept as it was, so a repository that has moved on since is only picked
+    /// up by the next check.
+    pub async fn download_files(
+        &self,
+        req: &SyncRequest,
+        files: Vec<(String, String)>,
+        progress_tx: Option<Sender<DownloadEvent>>,
+    ) -> Result<SyncResult, SyncError> {
+        let baseline = self
+            .fleet_data
+            .load_baseline_manifest(&req.local_root)
+            .map_err(|e| {
+                SyncError::Local(format!(
+                    "no baseline to restore from ({e}); run a sync first"
+                ))
+            })?;
+        let plan = crate::sync::restore_plan(&baseline, &files)?;
+        // Merged repositories need the fetched state only to route each file to its repository.
+        let remote = if req.extra_repo_urls.is_empty() {
+            self.cached_remote_state(req)?
+        } else {
+            crate::sync::FetchResult {
+                manifest: baseline,
+                ..self.fetch_remote_state(req).await?
+            }
+        };
+        self.execute_with_plan_internal(req, plan, Some(remote), progress_tx, None)
+            .await
+    }
+
+    #[tracing::instrument(name = "execute", skip_all, fields(repo = %req.repo_url, root = %req.local_root))]
     async fn execute_with_plan_internal(
         &self,
         req: &SyncRequest,
         plan: SyncPlan,
-        remote_manifest: Option<fleet_core::Manifest>,
+        remote: Option<crate::sync::FetchResult>,
         progress_tx: Option<Sender<DownloadEvent>>,
+        cancel: Option<&CancellationToken>,
     ) -> Result<SyncResult, SyncError> {
-        if plan.deletes.is_empty() && plan.renames.is_empty() && plan.downloads.is_empty() {
+        // Plans computed elsewhere (e.g. loaded from disk) may still carry deletes.
+        let mut plan = plan;
+        if req.options.skip_deletes {
+            plan.deletes.clear();
+        }
+        keep_fleet_artifacts(&mut plan);
+        let verify = req.options.verify_checks && !plan.checks.is_empty();
+        if plan.deletes.is_empty()
+            && plan.renames.is_empty()
+            && plan.downloads.is_empty()
+            && !verify
+        {
             return Ok(SyncResult {
                 plan,
                 executed: false,
                 stats: SyncStats::default(),
+                warnings: Vec::new(),
             });
         }
+        if req.options.offline && !plan.downloads.is_empty() {
+            return Err(SyncError::Execution(format!(
+                "offline: {} file(s) need downloading",
+                plan.downloads.len()
+            )));
+        }
 
+        // Multi-repository plans need the remote state up front to route each mod to the
+        // repository it came from.
+        let remote = match remote {
+            None if !req.extra_repo_urls.is_empty() => Some(self.fetch_remote_state(req).await?),
+            other => other,
+        };
+        let mod_sources = remote
+            .as_ref()
+            .map(|r| r.mod_sources.clone())
+            .unwrap_or_default();
+
+        // The diff lists every unchanged file as a check; only re-hash them when asked to.
+        let mut to_execute = plan.clone();
+        if !verify {
+            to_execute.checks.clear();
+        }
+
+        // Each mod whose downloads all landed is committed right away, so a sync killed
+        // mid-batch resumes with those mods already up to date. Plans executed without a
+        // fetched remote state only learn the manifest afterwards and commit at the end.
+        let checkpoint = |mod_name: &str, mod_artifacts: &[SyncArtifact]| {
+            let Some(remote_mod) = remote
+                .as_ref()
+                .and_then(|r| r.manifest.mods.iter().find(|m| m.name == mod_name))
+            else {
+                return;
+            };
+            if let Err(e) = self.commit_mod(&req.local_root, remote_mod, mod_artifacts) {
+                // The final snapshot still records the mod; only crash recovery is lost.
+                tracing::warn!("checkpoint for {mod_name} failed: {e}");
+            }
+        };
+
+        let started = Instant::now();
         let (artifacts, stats) = self
             .executor
             .execute(
                 &req.local_root,
                 &req.repo_url,
-                plan.clone(),
+                &mod_sources,
+                to_execute,
                 &req.options,
                 progress_tx,
+                Some(&checkpoint),
+                cancel,
             )
-            .await?;
+            .await
+            .map_err(|e| match (e, remote.as_ref()) {
+                (SyncError::Downloads(mut failed), Some(r)) => {
+                    for f in &mut failed {
+                        if let Some(m) = r.manifest.mods.iter().find(|m| m.name == f.mod_name) {
+                            f.mod_checksum = m.checksum.clone();
+                        }
+                    }
+                    SyncError::Downloads(failed)
+                }
+                (e, _) => e,
+            })?;
 
-        let manifest_to_save = if let Some(m) = remote_manifest {
-            m
-        } else {
-            self.remote
-                .fetch_remote(&req.repo_url)
-                .await
-                .map_err(|e| SyncError::Remote(format!("{e}")))?
-                .manifest
+        if stats.cancelled {
+            self.commit_partial_progress(req, &plan, remote.as_ref(), &artifacts);
+            return Err(SyncError::Cancelled);
+        }
+
+        let manifest_to_save = match remote {
+            Some(r) => r.manifest,
+            None => self.fetch_remote_state(req).await?.manifest,
         };
 
         let summary = compute_summary_from_manifest(&req.local_root, &manifest_to_save);
@@ -342,8 +1077,8 @@ impl DefaultSyncEngine {
             })
             .collect::<Vec<_>>();
 
-        self.fleet_data
-            .commit_sync_snapshot(
+        let commit = || {
+            self.fleet_data.commit_sync_snapshot(
                 &req.local_root,
                 &manifest_to_save,
                 &summary,
@@ -351,15 +1086,143 @@ impl DefaultSyncEngine {
                 &cache_deletes,
                 &cache_renames,
             )
-            .map_err(|e| SyncError::Local(format!("fleet.redb commit failed: {e}")))?;
+        };
+        let mut warnings = Vec::new();
+        let committed = match commit() {
+            // The store has moved the corrupt file aside. The snapshot is a complete baseline,
+            // so committing it into a fresh database loses nothing but scan cache entries.
+            Err(e) if e.kind() == fleet_persistence::StorageErrorKind::Corrupt => {
+                tracing::warn!("fleet.redb was corrupt at commit; recreating it");
+                warnings.push(
+                    "Local database was corrupt and has been recreated; unchanged files will be rehashed on the next check"
+                        .to_string(),
+                );
+                commit()
+            }
+            other => other,
+        };
+        committed.map_err(|e| SyncError::Local(format!("fleet.redb commit failed: {e}")))?;
+        if req.options.verify_baseline {
+            self.fleet_data
+                .verify_baseline(&req.local_root, &manifest_to_save, &summary)
+                .map_err(|e| {
+                    SyncError::Local(format!("baseline read-back mismatch after commit: {e}"))
+                })?;
+        }
 
+        tracing::info!(
+            downloaded = stats.files_planned_download,
+            bytes = stats.bytes_planned_download,
+            deleted = stats.files_deleted,
+            mods_deleted = stats.mods_deleted,
+            renamed = stats.renames,
+            verified = stats.files_verified,
+            deduplicated = stats.files_deduplicated,
+            from_local_source = stats.files_from_local_source,
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "executed plan"
+        );
         Ok(SyncResult {
             plan,
             executed: true,
             stats,
+            warnings,
         })
     }
 
+    /// Commits one mod's baseline entry and the scan cache entries of its downloaded files.
+    fn commit_mod(
+        &self,
+        root: &Utf8Path,
+        remote_mod: &fleet_core::Mod,
+        mod_artifacts: &[SyncArtifact],
+    ) -> Result<(), fleet_persistence::StorageError> {
+        let summary = compute_summary_from_manifest(
+            root,
+            &fleet_core::Manifest::new(vec![remote_mod.clone()]),
+        );
+        let cache_updates: Vec<CacheUpsert> = mod_artifacts
+            .iter()
+            .map(|a| CacheUpsert {
+                rel_path: a.rel_path.clone(),
+                mtime: a.final_mtime,
+                size: a.size,
+                checksum: a.checksum.clone(),
+            })
+            .collect();
+        self.fleet_data
+            .commit_mod_progress(root, remote_mod, &summary[0], &cache_updates)
+    }
+
+    /// Records what a cancelled execution downloaded. Each touched mod is committed as the
+    /// remote mod minus the downloads that never landed, so those are fetched next time while
+    /// the finished ones are neither fetched again nor treated as extra files. Plans executed
+    /// without a fetched remote state use the `mod.srf`s cached by the check that made them.
+    fn commit_partial_progress(
+        &self,
+        req: &SyncRequest,
+        plan: &SyncPlan,
+        remote: Option<&crate::sync::FetchResult>,
+        artifacts: &[SyncArtifact],
+    ) {
+        let cached;
+        let remote_mods = match remote {
+            Some(r) => &r.manifest.mods,
+            None => {
+                cached = self.cached_remote_mods(req);
+                &cached
+            }
+        };
+        let landed: HashSet<(&str, String)> = artifacts
+            .iter()
+            .map(|a| (a.mod_name.as_str(), FleetPath::canonicalize(&a.rel_path)))
+            .collect();
+
+        let mut by_mod: HashMap<&str, Vec<SyncArtifact>> = HashMap::new();
+        for a in artifacts {
+            by_mod.entry(&a.mod_name).or_default().push(a.clone());
+        }
+        for (mod_name, mod_artifacts) in by_mod {
+            let Some(remote_mod) = remote_mods.iter().find(|m| m.name == mod_name) else {
+                tracing::warn!(
+                    "no remote state for {mod_name}; its partial download is not recorded"
+                );
+                continue;
+            };
+            let pending: HashSet<String> = plan
+                .downloads
+                .iter()
+                .filter(|d| d.mod_name == mod_name)
+                .map(|d| FleetPath::canonicalize(&d.rel_path))
+                .filter(|key| !landed.contains(&(mod_name, key.clone())))
+                .collect();
+            let mut partial = remote_mod.clone();
+            if !pending.is_empty() {
+                partial
+                    .files
+                    .retain(|f| !pending.contains(&FleetPath::canonicalize(&f.path)));
+                // No longer the repository's mod; a matching checksum must never skip it.
+                partial.checksum.clear();
+            }
+            if let Err(e) = self.commit_mod(&req.local_root, &partial, &mod_artifacts) {
+                tracing::warn!("recording partial download of {mod_name} failed: {e}");
+            }
+        }
+    }
+
+    /// Mods from the `mod.srf`s cached for `req`'s profile by its last fetch.
+    fn cached_remote_mods(&self, req: &SyncRequest) -> Vec<fleet_core::Mod> {
+        let keys = repo_summary_keys(req).into_iter().flatten();
+        keys.filter_map(|key| {
+            self.repo_summary_store
+                .load_repo_summary(&key)
+                .ok()
+                .flatten()
+        })
+        .flat_map(|summary| summary.mod_srfs.into_values().map(|cached| cached.srf))
+        .collect()
+    }
+
     /// Persist the given manifest as the local baseline and write a matching summary file.
     /// This is used by "repair" to bootstrap `fleet.redb` without executing a sync.
     pub fn persist_remote_snapshot(
@@ -376,6 +1239,118 @@ impl DefaultSyncEngine {
     }
 }
 
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+fn log_plan(plan: &SyncPlan, started: Instant) {
+    tracing::info!(
+        downloads = plan.downloads.len(),
+        deletes = plan.deletes.len(),
+        renames = plan.renames.len(),
+        checks = plan.checks.len(),
+        elapsed_ms = started.elapsed().as_millis() as u64,
+        "computed plan"
+    );
+}
+
+/// Drops every action that touches one of `mods`.
+fn exclude_mods(plan: &mut SyncPlan, mods: &[String]) {
+    if mods.is_empty() {
+        return;
+    }
+    let skipped = |mod_name: &str| mods.iter().any(|m| m.eq_ignore_ascii_case(mod_name));
+    let skipped_path = |path: &str| {
+        let mod_name = path
+            .trim_end_matches('/')
+            .split('/')
+            .next()
+            .unwrap_or_default();
+        skipped(mod_name)
+    };
+    plan.downloads.retain(|d| !skipped(&d.mod_name));
+    plan.checks.retain(|c| !skipped_path(&c.path));
+    plan.deletes.retain(|d| !skipped_path(&d.path));
+    plan.renames
+        .retain(|r| !skipped_path(&r.old_path) && !skipped_path(&r.new_path));
+}
+
+/// The repo summary key of each of `req`'s repositories, in [`SyncRequest::repo_urls`] order;
+/// `None` without a profile. The primary keeps the bare profile id and the others are
+/// numbered in sorted URL order, so reordering them keeps each one's cached metadata.
+fn repo_summary_keys(req: &SyncRequest) -> Vec<Option<String>> {
+    let Some(pid) = req.profile_id.as_deref() else {
+        return vec![None; req.extra_repo_urls.len() + 1];
+    };
+    let mut sorted: Vec<&String> = req.extra_repo_urls.iter().collect();
+    sorted.sort();
+    let extra = req.extra_repo_urls.iter().map(|url| {
+        let i = sorted.iter().position(|u| *u == url).unwrap_or_default() + 1;
+        Some(format!("{pid}-repo{i}"))
+    });
+    std::iter::once(Some(pid.to_string()))
+        .chain(extra)
+        .collect()
+}
+
+/// [`SyncError::RepoUnavailable`] once `breaker` has given up on a host.
+fn repo_unavailable(breaker: &fleet_infra::CircuitBreaker) -> Option<SyncError> {
+    breaker
+        .open_host()
+        .map(|(host, failures)| SyncError::RepoUnavailable { host, failures })
+}
+
+/// Rejects a `repo.json` whose advertised checksum does not match its required mods. A repo
+/// that advertises no checksum has nothing to check.
+fn verify_repo_checksum(repository: &fleet_core::repo::Repository) -> Result<(), SyncError> {
+    if repository.checksum.is_empty() {
+        return Ok(());
+    }
+    let computed = fleet_infra::compute_listed_repo_checksum(&repository.required_mods);
+    if !computed.eq_ignore_ascii_case(&repository.checksum) {
+        return Err(SyncError::Remote(format!(
+            "repo.json checksum mismatch: advertised {}, computed {computed}",
+            repository.checksum
+        )));
+    }
+    Ok(())
+}
+
+/// Drops deletes of Fleet's own files, whatever planned them.
+fn keep_fleet_artifacts(plan: &mut SyncPlan) {
+    plan.deletes.retain(|d| {
+        let name = d.path.trim_end_matches('/').rsplit('/').next();
+        !name.is_some_and(is_fleet_artifact)
+    });
+}
+
+/// Drops downloads, checks and deletes of files matching one of the `patterns` globs.
+fn exclude_paths(plan: &mut SyncPlan, patterns: &[String]) -> Result<(), SyncError> {
+    if patterns.is_empty() {
+        return Ok(());
+    }
+    let mut builder = globset::GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = globset::GlobBuilder::new(&FleetPath::normalize(pattern))
+            .case_insensitive(true)
+            .literal_separator(true)
+            .build()
+            .map_err(|e| SyncError::Local(format!("invalid exclude pattern {pattern:?}: {e}")))?;
+        builder.add(glob);
+    }
+    let set = builder
+        .build()
+        .map_err(|e| SyncError::Local(format!("invalid exclude patterns: {e}")))?;
+
+    plan.downloads
+        .retain(|d| !set.is_match(format!("{}/{}", d.mod_name, d.rel_path)));
+    plan.checks.retain(|c| !set.is_match(&c.path));
+    plan.deletes.retain(|d| !set.is_match(&d.path));
+    Ok(())
+}
+
 fn split_mod_rel(path: &str) -> Option<(String, Option<String>)> {
     let cleaned = path.trim_end_matches('/');
     if let Some((mod_name, rel)) = cleaned.split_once('/') {
@@ -391,30 +1366,23 @@ fn compute_summary_from_manifest(
     root: &Utf8Path,
     manifest: &fleet_core::Manifest,
 ) -> Vec<LocalManifestSummary> {
-    let mut summaries = Vec::new();
+    let mut summaries = Vec::with_capacity(manifest.mods.len());
     for m in &manifest.mods {
         let mod_root = root.join(&m.name);
-        let mut files = Vec::new();
+        let mut files = Vec::with_capacity(m.files.len());
         for f in &m.files {
             // Normalize remote path so it matches Scanner's normalized output
-            let normalized = FleetPath::normalize(&f.path);
-            let fs_path = mod_root.join(&normalized);
-            if let Ok(meta) = std::fs::metadata(&fs_path) {
-                let mtime = Scanner::mtime(&meta);
-                files.push(LocalFileSummary {
-                    rel_path: normalized.clone(),
-                    mtime,
-                    size: meta.len(),
-                    checksum: f.checksum.clone(),
-                });
-            } else {
-                files.push(LocalFileSummary {
-                    rel_path: normalized.clone(),
-                    mtime: 0,
-                    size: f.length,
-                    checksum: f.checksum.clone(),
-                });
-            }
+            let rel_path = FleetPath::normalize(&f.path);
+            let (mtime, size) = match std::fs::metadata(mod_root.join(&rel_path)) {
+                Ok(meta) => (Scanner::mtime(&meta), meta.len()),
+                Err(_) => (0, f.length),
+            };
+            files.push(LocalFileSummary {
+                rel_path,
+                mtime,
+                size,
+                checksum: f.checksum.clone(),
+            });
         }
         summaries.push(LocalManifestSummary {
             mod_name: m.name.clone(),
@@ -424,80 +1392,79 @@ fn compute_summary_from_manifest(
     summaries
 }
 
+/// Files of one mod that differ, borrowed from the summaries being compared.
 #[derive(Debug, Default)]
-struct SummaryDiff {
-    changed_files: Vec<String>,
-    missing_files: Vec<String>,
-    extra_files: Vec<String>,
+struct SummaryDiff<'a> {
+    changed_files: Vec<&'a LocalFileSummary>,
+    missing_files: Vec<&'a LocalFileSummary>,
+    extra_files: Vec<&'a str>,
 }
 
-fn diff_summary(repo: &LocalManifestSummary, local: &LocalManifestSummary) -> SummaryDiff {
-    let repo_map: HashMap<_, _> = repo
+fn diff_summary<'a>(
+    repo: &'a LocalManifestSummary,
+    local: &'a LocalManifestSummary,
+) -> SummaryDiff<'a> {
+    let local_map: HashMap<&str, &LocalFileSummary> = local
         .files
         .iter()
-        .map(|f| (f.rel_path.clone(), (f.mtime, f.size)))
-        .collect();
-    let local_map: HashMap<_, _> = local
-        .files
-        .iter()
-        .map(|f| (f.rel_path.clone(), (f.mtime, f.size)))
+        .map(|f| (f.rel_path.as_str(), f))
         .collect();
 
-    let mut changed_files = Vec::new();
-    let mut missing_files = Vec::new();
-    let mut extra_files = Vec::new();
-
-    for (rel, (mtime, size)) in &repo_map {
-        match local_map.get(rel) {
-            Some((l_mtime, l_size)) => {
-                if l_mtime != mtime || l_size != size {
-                    changed_files.push(rel.clone());
+    let mut diff = SummaryDiff::default();
+    for f in &repo.files {
+        match local_map.get(f.rel_path.as_str()) {
+            Some(l) => {
+                if l.mtime != f.mtime || l.size != f.size {
+                    diff.changed_files.push(f);
                 }
             }
-            None => missing_files.push(rel.clone()),
-        }
-    }
-
-    for rel in local_map.keys() {
-        if !repo_map.contains_key(rel) {
-            extra_files.push(rel.clone());
+            None => diff.missing_files.push(f),
         }
     }
 
-    SummaryDiff {
-        changed_files,
-        missing_files,
-        extra_files,
-    }
+    let repo_paths: HashSet<&str> = repo.files.iter().map(|f| f.rel_path.as_str()).collect();
+    diff.extra_files = local
+        .files
+        .iter()
+        .map(|f| f.rel_path.as_str())
+        .filter(|rel| !repo_paths.contains(rel))
+        .collect();
+    diff
 }
 
+/// Builds a plan from summary differences. With `skip_deletes`, extra local files and mods
+/// are left in place and only missing/changed files are scheduled for download.
 fn build_fast_plan(
     expected: &[LocalManifestSummary],
     current: &[LocalManifestSummary],
+    skip_deletes: bool,
 ) -> SyncPlan {
     let mut downloads = Vec::new();
     let mut deletes = Vec::new();
 
-    let current_map: HashMap<_, _> = current.iter().map(|m| (m.mod_name.clone(), m)).collect();
+    let current_map: HashMap<&str, &LocalManifestSummary> =
+        current.iter().map(|m| (m.mod_name.as_str(), m)).collect();
 
     for repo_mod in expected {
-        if let Some(local_mod) = current_map.get(&repo_mod.mod_name) {
+        if let Some(local_mod) = current_map.get(repo_mod.mod_name.as_str()) {
             let diff = diff_summary(repo_mod, local_mod);
-            for rel in diff.changed_files.iter().chain(diff.missing_files.iter()) {
-                if let Some(file) = repo_mod.files.iter().find(|f| &f.rel_path == rel) {
-                    downloads.push(fleet_core::DownloadAction {
-                        mod_name: repo_mod.mod_name.clone(),
-                        rel_path: file.rel_path.clone(),
-                        size: file.size,
-                        expected_checksum: file.checksum.clone(),
+            for file in diff.changed_files.into_iter().chain(diff.missing_files) {
+                downloads.push(fleet_core::DownloadAction {
+                    mod_name: repo_mod.mod_name.clone(),
+                    rel_path: file.rel_path.clone(),
+                    size: file.size,
+                    expected_checksum: file.checksum.clone(),
+                    mtime: None,
+                    parts: Vec::new(),
+                });
+            }
+            if !skip_deletes {
+                for rel in diff.extra_files {
+                    deletes.push(fleet_core::DeleteAction {
+                        path: format!("{}/{}", repo_mod.mod_name, rel),
                     });
                 }
             }
-            for rel in diff.extra_files {
-                deletes.push(fleet_core::DeleteAction {
-                    path: format!("{}/{}", repo_mod.mod_name, rel),
-                });
-            }
         } else {
             for file in &repo_mod.files {
                 downloads.push(fleet_core::DownloadAction {
@@ -505,16 +1472,21 @@ fn build_fast_plan(
                     rel_path: file.rel_path.clone(),
                     size: file.size,
                     expected_checksum: file.checksum.clone(),
+                    mtime: None,
+                    parts: Vec::new(),
                 });
             }
         }
     }
 
-    for local_mod in current {
-        if !expected.iter().any(|m| m.mod_name == local_mod.mod_name) {
-            deletes.push(fleet_core::DeleteAction {
-                path: local_mod.mod_name.clone(),
-            });
+    if !skip_deletes {
+        let expected_mods: HashSet<&str> = expected.iter().map(|m| m.mod_name.as_str()).collect();
+        for local_mod in current {
+            if !expected_mods.contains(local_mod.mod_name.as_str()) {
+                deletes.push(fleet_core::DeleteAction {
+                    path: local_mod.mod_name.clone(),
+                });
+            }
         }
     }
 
@@ -528,8 +1500,43 @@ fn build_fast_plan(
 
 #[cfg(test)]
 mod tests {
-    use super::build_fast_plan;
+    use super::{build_fast_plan, repo_summary_keys};
     use crate::sync::storage::{LocalFileSummary, LocalManifestSummary};
+    use crate::sync::{SyncMode, SyncOptions, SyncRequest};
+
+    #[test]
+    fn repo_summary_keys_do_not_depend_on_the_order_of_extra_repos() {
+        let request = |extra: &[&str], profile_id: Option<&str>| SyncRequest {
+            repo_url: "https://main".into(),
+            extra_repo_urls: extra.iter().map(|u| u.to_string()).collect(),
+            local_root: "/mods".into(),
+            mode: SyncMode::SmartVerify,
+            options: SyncOptions::default(),
+            profile_id: profile_id.map(str::to_string),
+        };
+        let keys = |extra: &[&str]| repo_summary_keys(&request(extra, Some("p")));
+
+        assert_eq!(
+            keys(&["https://b", "https://a"]),
+            vec![
+                Some("p".to_string()),
+                Some("p-repo2".to_string()),
+                Some("p-repo1".to_string()),
+            ]
+        );
+        assert_eq!(
+            keys(&["https://a", "https://b"]),
+            vec![
+                Some("p".to_string()),
+                Some("p-repo1".to_string()),
+                Some("p-repo2".to_string()),
+            ]
+        );
+        assert_eq!(
+            repo_summary_keys(&request(&["https://a"], None)),
+            vec![None, None]
+        );
+    }
 
     #[test]
     fn fast_plan_detects_changes() {
@@ -568,7 +1575,7 @@ mod tests {
             ],
         }];
 
-        let plan = build_fast_plan(&expected, &current);
+        let plan = build_fast_plan(&expected, &current, false);
         assert_eq!(plan.downloads.len(), 2);
         assert_eq!(plan.deletes.len(), 1);
         assert!(plan
@@ -576,5 +1583,9 @@ mod tests {
             .iter()
             .any(|d| d.mod_name == "@m" && d.rel_path == "a.txt"));
         assert!(plan.deletes.iter().any(|d| d.path == "@m/c.txt"));
+
+        let plan = build_fast_plan(&expected, &current, true);
+        assert_eq!(plan.downloads.len(), 2);
+        assert!(plan.deletes.is_empty());
     }
 }
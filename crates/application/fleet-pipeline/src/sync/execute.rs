@@ -1,14 +1,18 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 
 use camino::Utf8Path;
 use fleet_core::path_utils::FleetPath;
 use fleet_core::SyncPlan;
-use fleet_infra::net::{DownloadEvent, DownloadRequest, Downloader};
+use fleet_infra::fs::{link_or_copy, robust_rename};
+use fleet_infra::net::{DownloadError, DownloadEvent, DownloadRequest, Downloader};
+use futures::StreamExt;
 use tokio::sync::mpsc::Sender;
+use tokio_util::sync::CancellationToken;
 
-use crate::io_utils::robust_rename;
-use crate::sync::{SyncError, SyncOptions, SyncStats};
+use crate::sync::{
+    ChangeLog, DownloadOrder, FailedCheck, FailedDownload, SyncError, SyncOptions, SyncStats,
+};
 use fleet_scanner::Scanner;
 
 fn validate_relative_path(path: &str) -> Result<(), SyncError> {
@@ -37,15 +41,27 @@ pub struct SyncArtifact {
     pub final_mtime: u64,
 }
 
+/// Called with a mod's artifacts as soon as every planned download for that mod succeeded,
+/// so progress survives a sync that is killed before it finishes.
+pub type ModCheckpoint<'a> = &'a (dyn Fn(&str, &[SyncArtifact]) + Send + Sync);
+
+/// Downloads are issued in batches of this many files; finished mods are checkpointed
+/// between batches.
+const CHECKPOINT_BATCH_FILES: usize = 256;
+
 #[async_trait::async_trait]
 pub trait PlanExecutor: Send + Sync {
+    #[allow(clippy::too_many_arguments)]
     async fn execute(
         &self,
         root: &Utf8Path,
         repo_url: &str,
+        mod_sources: &HashMap<String, String>,
         plan: SyncPlan,
         opts: &SyncOptions,
         progress_tx: Option<Sender<DownloadEvent>>,
+        checkpoint: Option<ModCheckpoint<'_>>,
+        cancel: Option<&CancellationToken>,
     ) -> Result<(Vec<SyncArtifact>, SyncStats), SyncError>;
 }
 
@@ -65,9 +81,12 @@ impl PlanExecutor for DefaultPlanExecutor {
         &self,
         root: &Utf8Path,
         repo_url: &str,
+        mod_sources: &HashMap<String, String>,
         plan: SyncPlan,
         opts: &SyncOptions,
         progress_tx: Option<Sender<DownloadEvent>>,
+        checkpoint: Option<ModCheckpoint<'_>>,
+        cancel: Option<&CancellationToken>,
     ) -> Result<(Vec<SyncArtifact>, SyncStats), SyncError> {
         let mut stats = SyncStats::default();
         let root_std = root.as_std_path();
@@ -82,11 +101,30 @@ impl PlanExecutor for DefaultPlanExecutor {
                 )));
             }
             if path.exists() {
+                let (mod_name, rel_path) = match del.path.split_once('/') {
+                    Some((mod_name, rel_path)) => (mod_name, Some(rel_path)),
+                    None => (del.path.as_str(), None),
+                };
                 if path.is_dir() {
-                    let _ = tokio::fs::remove_dir_all(path.as_std_path()).await;
+                    if tokio::fs::remove_dir_all(path.as_std_path()).await.is_ok() {
+                        match rel_path {
+                            Some(rel_path) => stats
+                                .changes
+                                .mod_entry(mod_name)
+                                .deleted
+                                .push(rel_path.to_string()),
+                            None => stats.changes.mod_entry(mod_name).removed = true,
+                        }
+                    }
                     stats.mods_deleted += 1;
                 } else {
-                    let _ = tokio::fs::remove_file(path.as_std_path()).await;
+                    if tokio::fs::remove_file(path.as_std_path()).await.is_ok() {
+                        stats
+                            .changes
+                            .mod_entry(mod_name)
+                            .deleted
+                            .push(rel_path.unwrap_or_default().to_string());
+                    }
                     stats.files_deleted += 1;
                 }
             }
@@ -107,7 +145,13 @@ impl PlanExecutor for DefaultPlanExecutor {
                 )));
             }
             if old.exists() {
-                let _ = robust_rename(old.as_std_path(), new.as_std_path()).await;
+                if robust_rename(old.as_std_path(), new.as_std_path())
+                    .await
+                    .is_ok()
+                {
+                    stats.changes.mod_entry(&ren.new_path).renamed_from =
+                        Some(ren.old_path.clone());
+                }
                 stats.renames += 1;
             }
         }
@@ -120,8 +164,31 @@ impl PlanExecutor for DefaultPlanExecutor {
             rel_path: String,
             checksum: String,
             size: u64,
+            mtime: Option<u64>,
+        }
+        impl DlCtx {
+            fn failed(&self, reason: DownloadError) -> FailedDownload {
+                FailedDownload {
+                    mod_name: self.mod_name.clone(),
+                    rel_path: self.rel_path.clone(),
+                    size: self.size,
+                    expected_checksum: self.checksum.clone(),
+                    mtime: self.mtime,
+                    mod_checksum: String::new(),
+                    reason,
+                }
+            }
         }
         let mut ctx_map = HashMap::new();
+        // Content shared across mods (same checksum and size) is downloaded once; the other
+        // mods' targets are linked to that download afterwards.
+        let mut first_by_content: HashMap<(String, u64), (u64, String)> = HashMap::new();
+        let mut duplicates: HashMap<u64, Vec<u64>> = HashMap::new();
+        let mut remaining: HashMap<String, usize> = HashMap::new();
+        // Targets that differ only by case are one file on Windows/macOS; download it once.
+        let mut targets = HashSet::new();
+        // Downloads replacing a file on disk are updates in the change log; the rest are new.
+        let mut replaced = HashSet::new();
 
         for (i, action) in plan.downloads.iter().enumerate() {
             // SECURITY CHECK
@@ -129,7 +196,12 @@ impl PlanExecutor for DefaultPlanExecutor {
             validate_relative_path(&action.rel_path)?;
 
             let id = i as u64;
-            let url = build_file_url(repo_url, &action.mod_name, &action.rel_path)
+            // Mods from extra repositories are downloaded from where they were listed.
+            let source = mod_sources
+                .get(&action.mod_name)
+                .map(String::as_str)
+                .unwrap_or(repo_url);
+            let url = build_file_url(source, &action.mod_name, &action.rel_path)
                 .map_err(SyncError::Execution)?;
             // Normalize relative path so on-disk layout is consistent
             let normalized_rel = FleetPath::normalize(&action.rel_path);
@@ -143,78 +215,399 @@ impl PlanExecutor for DefaultPlanExecutor {
                     target
                 )));
             }
-            requests.push(DownloadRequest {
-                id,
-                url,
-                target_path: target,
-                expected_size: action.size,
-                expected_checksum: Some(action.expected_checksum.clone()),
-            });
+            if !targets.insert(FleetPath::canonicalize(target.as_str())) {
+                tracing::warn!(
+                    mod_name = %action.mod_name,
+                    rel_path = %normalized_rel,
+                    "skipping download whose path differs only by case from another"
+                );
+                continue;
+            }
+            if target.is_file() {
+                replaced.insert((action.mod_name.clone(), normalized_rel.clone()));
+            }
+            stats.files_planned_download += 1;
+            stats.bytes_planned_download += action.size;
+            *remaining.entry(action.mod_name.clone()).or_default() += 1;
             ctx_map.insert(
                 id,
                 DlCtx {
                     mod_name: action.mod_name.clone(),
-                    rel_path: normalized_rel,
+                    rel_path: normalized_rel.clone(),
                     checksum: action.expected_checksum.clone(),
                     size: action.size,
+                    mtime: action.mtime,
                 },
             );
 
-            stats.files_planned_download += 1;
-            stats.bytes_planned_download += action.size;
+            if !action.expected_checksum.is_empty() {
+                let key = (action.expected_checksum.to_ascii_uppercase(), action.size);
+                match first_by_content.get(&key) {
+                    Some((first, first_mod)) if *first_mod != action.mod_name => {
+                        duplicates.entry(*first).or_default().push(id);
+                        continue;
+                    }
+                    Some(_) => {}
+                    None => {
+                        first_by_content.insert(key, (id, action.mod_name.clone()));
+                    }
+                }
+            }
+
+            requests.push(DownloadRequest {
+                id,
+                mod_name: action.mod_name.clone(),
+                rel_path: normalized_rel.clone(),
+                url,
+                target_path: target,
+                expected_size: action.size,
+                expected_checksum: opts
+                    .verify_downloads
+                    .then(|| action.expected_checksum.clone()),
+                parts: action.parts.clone(),
+            });
+        }
+
+        // Files the local source already has with the right checksum are copied from there.
+        // Candidates are hashed here, in parallel, which is the copy's verification.
+        let mut from_local_source = HashSet::new();
+        if let Some(source) = &opts.local_source {
+            let threads = opts
+                .scan_threads
+                .unwrap_or_else(fleet_scanner::default_scan_threads);
+            let candidates: Vec<(DownloadRequest, String)> = requests
+                .iter()
+                .map(|req| {
+                    let checksum = ctx_map
+                        .get(&req.id)
+                        .map(|ctx| ctx.checksum.clone())
+                        .unwrap_or_default();
+                    (req.clone(), checksum)
+                })
+                .collect();
+            let found: Vec<Option<String>> = futures::stream::iter(candidates)
+                .map(|(req, checksum)| {
+                    let source = source.clone();
+                    async move { local_source_url(&source, &req, &checksum).await }
+                })
+                .buffered(threads.max(1))
+                .collect()
+                .await;
+            for (req, local) in requests.iter_mut().zip(found) {
+                if let Some(local) = local {
+                    req.url = local;
+                    from_local_source.insert(req.id);
+                }
+            }
         }
 
-        let downloader =
-            Downloader::new(self.client.clone(), opts.max_threads, opts.rate_limit_bytes);
-        let results = downloader.download_batch(requests, progress_tx).await;
-
-        let mut artifacts = Vec::new();
-        let mut failed = 0;
-        for res in results {
-            if res.success {
-                if let Some(ctx) = ctx_map.get(&res.id) {
-                    let abs_path = root.join(&ctx.mod_name).join(&ctx.rel_path);
-                    let now = std::time::SystemTime::now();
-                    let _ = filetime::set_file_mtime(
-                        abs_path.as_std_path(),
-                        filetime::FileTime::from_system_time(now),
-                    );
-
-                    // Read back exactly what the OS recorded.
-                    // Do not trust 'now' because some filesystems coarsen or adjust timestamps.
-                    match fs::metadata(abs_path.as_std_path()) {
-                        Ok(meta) => {
-                            let mtime = Scanner::mtime(&meta);
-                            let size = meta.len();
+        // Stable sorts keep plan order among files of equal size.
+        match opts.download_order {
+            DownloadOrder::AsPlanned => {}
+            DownloadOrder::SmallestFirst => requests.sort_by_key(|r| r.expected_size),
+            DownloadOrder::LargestFirst => {
+                requests.sort_by_key(|r| std::cmp::Reverse(r.expected_size))
+            }
+        }
+
+        let breaker = opts.circuit_breaker();
+        let mut downloader =
+            Downloader::new(self.client.clone(), opts.max_threads, opts.rate_limit_bytes)
+                .with_temp_dir(opts.temp_dir.clone())
+                .with_stall_timeout(opts.stall_timeout)
+                .with_per_host_limit(opts.max_connections_per_host)
+                .with_segmented_downloads(opts.segmented_downloads)
+                .with_circuit_breaker(breaker.clone());
+        if let Some(limit) = &opts.rate_limit {
+            downloader = downloader.with_rate_limit(limit.clone());
+        }
+
+        let mut artifacts: Vec<SyncArtifact> = Vec::new();
+        let mut failed: Vec<FailedDownload> = Vec::new();
+        for batch in requests.chunks(CHECKPOINT_BATCH_FILES) {
+            let (artifacts_before, failed_before) = (artifacts.len(), failed.len());
+            // On cancellation stop taking results; downloads that already landed are kept
+            // and returned so the caller can record them.
+            let cancelled = async {
+                match cancel {
+                    Some(token) => token.cancelled().await,
+                    None => std::future::pending().await,
+                }
+            };
+            let results = downloader
+                .download_stream(batch.to_vec(), progress_tx.clone())
+                .take_until(cancelled);
+            tokio::pin!(results);
+            while let Some(res) = results.next().await {
+                let Some(ctx) = ctx_map.get(&res.id) else {
+                    continue;
+                };
+                let copies = duplicates.remove(&res.id).unwrap_or_default();
+                if !res.success {
+                    let reason = res
+                        .error
+                        .unwrap_or_else(|| DownloadError::Network("unknown failure".into()));
+                    for copy in copies.iter().filter_map(|id| ctx_map.get(id)) {
+                        failed.push(copy.failed(reason.clone()));
+                    }
+                    failed.push(ctx.failed(reason));
+                    continue;
+                }
+
+                if from_local_source.contains(&res.id) {
+                    stats.files_from_local_source += 1;
+                }
+                let abs_path = root.join(&ctx.mod_name).join(&ctx.rel_path);
+                stamp_mtime(&abs_path, ctx.mtime.filter(|_| opts.preserve_mtimes));
+                match stat_artifact(&abs_path) {
+                    Ok((size, final_mtime)) => artifacts.push(SyncArtifact {
+                        mod_name: ctx.mod_name.clone(),
+                        rel_path: ctx.rel_path.clone(),
+                        checksum: ctx.checksum.clone(),
+                        size,
+                        final_mtime,
+                    }),
+                    Err(e) => failed.push(ctx.failed(e)),
+                }
+
+                for id in copies {
+                    let Some(copy) = ctx_map.get(&id) else {
+                        continue;
+                    };
+                    let copy_path = root.join(&copy.mod_name).join(&copy.rel_path);
+                    // A hard link shares the stamped mtime already; a copy needs its own.
+                    match link_or_copy(abs_path.as_std_path(), copy_path.as_std_path()).await {
+                        Ok(copied) => {
+                            if copied {
+                                stamp_mtime(
+                                    &copy_path,
+                                    copy.mtime.filter(|_| opts.preserve_mtimes),
+                                );
+                            }
+                        }
+                        Err(e) => {
+                            failed.push(copy.failed(DownloadError::Io(e.to_string())));
+                            continue;
+                        }
+                    }
+                    match stat_artifact(&copy_path) {
+                        Ok((size, final_mtime)) => {
+                            stats.files_deduplicated += 1;
                             artifacts.push(SyncArtifact {
-                                mod_name: ctx.mod_name.clone(),
-                                rel_path: ctx.rel_path.clone(),
-                                checksum: ctx.checksum.clone(),
+                                mod_name: copy.mod_name.clone(),
+                                rel_path: copy.rel_path.clone(),
+                                checksum: copy.checksum.clone(),
                                 size,
-                                final_mtime: mtime,
+                                final_mtime,
                             });
                         }
                         Err(e) => {
-                            tracing::error!("Failed to stat downloaded file {}: {}", abs_path, e);
-                            failed += 1;
+                            failed.push(copy.failed(e));
+                            continue;
                         }
                     }
-                } else {
-                    failed += 1;
+                    if let Some(tx) = &progress_tx {
+                        // Report linked files like downloads so progress reaches its total.
+                        let _ = tx
+                            .send(DownloadEvent::Started {
+                                id,
+                                mod_name: copy.mod_name.clone(),
+                                rel_path: copy.rel_path.clone(),
+                                total_bytes: copy.size,
+                                local: true,
+                            })
+                            .await;
+                        let _ = tx
+                            .send(DownloadEvent::Progress {
+                                id,
+                                bytes_delta: copy.size,
+                            })
+                            .await;
+                        let _ = tx
+                            .send(DownloadEvent::Completed { id, success: true })
+                            .await;
+                    }
+                }
+            }
+
+            for f in &failed[failed_before..] {
+                // A mod with any failure is never checkpointed.
+                remaining.remove(&f.mod_name);
+            }
+            let mut finished = Vec::new();
+            for a in &artifacts[artifacts_before..] {
+                if let Some(left) = remaining.get_mut(&a.mod_name) {
+                    *left -= 1;
+                    if *left == 0 {
+                        remaining.remove(&a.mod_name);
+                        finished.push(a.mod_name.clone());
+                    }
+                }
+            }
+            if let Some(checkpoint) = checkpoint {
+                for mod_name in finished {
+                    let mod_artifacts: Vec<SyncArtifact> = artifacts
+                        .iter()
+                        .filter(|a| a.mod_name == mod_name)
+                        .cloned()
+                        .collect();
+                    checkpoint(&mod_name, &mod_artifacts);
                 }
-            } else {
-                failed += 1;
             }
+            if cancel.is_some_and(CancellationToken::is_cancelled) {
+                stats.cancelled = true;
+                record_downloads(&mut stats.changes, &artifacts, &replaced);
+                return Ok((artifacts, stats));
+            }
+        }
+        record_downloads(&mut stats.changes, &artifacts, &replaced);
+
+        if !failed.is_empty() {
+            if let Some((host, failures)) = breaker.as_ref().and_then(|b| b.open_host()) {
+                return Err(SyncError::RepoUnavailable { host, failures });
+            }
+            return Err(SyncError::Downloads(failed));
+        }
+
+        // Verification: re-hash files the plan trusted without downloading.
+        let mut mismatched = Vec::new();
+        for check in &plan.checks {
+            let failed = rehash(root, &check.path, &check.expected_checksum).await?;
+            stats.files_verified += 1;
+            mismatched.extend(failed);
         }
 
-        if failed > 0 {
-            return Err(SyncError::Execution(format!("Failed downloads: {failed}")));
+        if !mismatched.is_empty() {
+            return Err(SyncError::Verification(mismatched));
         }
 
         Ok((artifacts, stats))
     }
 }
 
+/// Re-hashes every file `plan` downloaded, returning those that no longer match the
+/// repository checksum. Catches corruption between the download's own check and now.
+pub async fn verify_downloads(
+    root: &Utf8Path,
+    plan: &SyncPlan,
+) -> Result<Vec<FailedCheck>, SyncError> {
+    let mut mismatched = Vec::new();
+    for action in &plan.downloads {
+        let path = format!(
+            "{}/{}",
+            action.mod_name,
+            FleetPath::normalize(&action.rel_path)
+        );
+        mismatched.extend(rehash(root, &path, &action.expected_checksum).await?);
+    }
+    Ok(mismatched)
+}
+
+/// A `file://` URL for the file `req` downloads, under `source` instead of the mods folder,
+/// when it has the expected size and `checksum`; `None` sends the download to the repository
+/// as usual.
+async fn local_source_url(
+    source: &Utf8Path,
+    req: &DownloadRequest,
+    checksum: &str,
+) -> Option<String> {
+    if checksum.is_empty() {
+        return None;
+    }
+    let candidate = std::path::absolute(source.join(&req.mod_name).join(&req.rel_path)).ok()?;
+    let candidate = camino::Utf8PathBuf::from_path_buf(candidate).ok()?;
+    if fs::metadata(&candidate).ok()?.len() != req.expected_size || !candidate.is_file() {
+        return None;
+    }
+
+    let logical = camino::Utf8PathBuf::from(req.rel_path.as_str());
+    let path = candidate.clone();
+    let hashed =
+        tokio::task::spawn_blocking(move || fleet_infra::compute_file_checksum(&path, &logical))
+            .await
+            .ok()?
+            .ok()?;
+    if !hashed.eq_ignore_ascii_case(checksum) {
+        tracing::debug!(path = %candidate, "local source copy differs; downloading instead");
+        return None;
+    }
+    reqwest::Url::from_file_path(candidate.as_std_path())
+        .ok()
+        .map(String::from)
+}
+
+/// Hashes `rel_path` under `root`; `Some` when it is unreadable or differs from `expected`.
+async fn rehash(
+    root: &Utf8Path,
+    rel_path: &str,
+    expected: &str,
+) -> Result<Option<FailedCheck>, SyncError> {
+    validate_relative_path(rel_path)?;
+    let path = root.join(FleetPath::normalize(rel_path));
+    if !path.as_std_path().starts_with(root.as_std_path()) {
+        return Err(SyncError::Execution(format!(
+            "Security: Check path escapes root: {path}"
+        )));
+    }
+
+    let logical = camino::Utf8PathBuf::from(rel_path);
+    let hashed =
+        tokio::task::spawn_blocking(move || fleet_infra::compute_file_checksum(&path, &logical))
+            .await
+            .map_err(|e| SyncError::Execution(format!("verification task failed: {e}")))?;
+
+    let reason = match hashed {
+        Ok(actual) if actual.eq_ignore_ascii_case(expected) => return Ok(None),
+        Ok(actual) => format!("checksum mismatch (expected {expected}, got {actual})"),
+        Err(e) => e.to_string(),
+    };
+    Ok(Some(FailedCheck {
+        path: rel_path.to_string(),
+        reason,
+    }))
+}
+
+fn record_downloads(
+    changes: &mut ChangeLog,
+    artifacts: &[SyncArtifact],
+    replaced: &HashSet<(String, String)>,
+) {
+    for a in artifacts {
+        let entry = changes.mod_entry(&a.mod_name);
+        if replaced.contains(&(a.mod_name.clone(), a.rel_path.clone())) {
+            entry.updated.push(a.rel_path.clone());
+        } else {
+            entry.added.push(a.rel_path.clone());
+        }
+    }
+    // Downloads finish in any order; keep reports stable.
+    for m in &mut changes.mods {
+        m.added.sort();
+        m.updated.sort();
+    }
+}
+
+/// Stamps `upstream` (Unix seconds) when the repository published one, else the current time.
+fn stamp_mtime(path: &Utf8Path, upstream: Option<u64>) {
+    let time = match upstream {
+        Some(secs) => filetime::FileTime::from_unix_time(secs as i64, 0),
+        None => filetime::FileTime::from_system_time(std::time::SystemTime::now()),
+    };
+    let _ = filetime::set_file_mtime(path.as_std_path(), time);
+}
+
+/// Size and mtime exactly as the OS recorded them; some filesystems coarsen or adjust
+/// timestamps, so the stamped time can't be trusted.
+fn stat_artifact(path: &Utf8Path) -> Result<(u64, u64), DownloadError> {
+    match fs::metadata(path.as_std_path()) {
+        Ok(meta) => Ok((meta.len(), Scanner::mtime(&meta))),
+        Err(e) => {
+            tracing::error!("Failed to stat downloaded file {}: {}", path, e);
+            Err(DownloadError::Io(e.to_string()))
+        }
+    }
+}
+
 fn build_file_url(repo_url: &str, mod_name: &str, rel_path: &str) -> Result<String, String> {
     let base = crate::sync::remote::normalize_repo_base(repo_url)
         .map_err(|e| format!("invalid repo url {repo_url}: {e}"))?;
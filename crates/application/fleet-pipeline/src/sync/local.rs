@@ -2,15 +2,17 @@ use camino::{Utf8Path, Utf8PathBuf};
 use fleet_core::path_utils::FleetPath;
 use fleet_core::{File, FileType, Manifest, Mod};
 use fleet_scanner::cache::ScanCache;
-use fleet_scanner::{ScanCacheStore, ScanStrategy, Scanner};
+use fleet_scanner::{ScanCacheStore, ScanOptions, ScanStrategy, Scanner};
 use rayon::prelude::*;
-use walkdir::WalkDir;
 
 use crate::sync::storage::{LocalFileSummary, LocalManifestSummary};
 use crate::sync::{SyncError, SyncMode};
 use fleet_infra::hashing::compute_file_checksum;
-use fleet_persistence::{CacheUpsert, FleetDataStore};
-use std::sync::Arc;
+use fleet_persistence::{CacheUpsert, FileCacheEntry, FleetDataStore, StorageErrorKind};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio_util::sync::CancellationToken;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LocalTrustLevel {
@@ -26,25 +28,43 @@ pub struct LocalState {
     pub manifest: Manifest,
     pub summary: Option<Vec<crate::sync::storage::LocalManifestSummary>>,
     pub trust: LocalTrustLevel,
+    /// Storage problems recovered from while scanning (e.g. a corrupt scan cache was reset).
+    pub warnings: Vec<String>,
 }
 
 #[async_trait::async_trait]
 pub trait LocalStateProvider: Send + Sync {
+    /// Scans `root` as `mode` asks. Once `cancel` fires the walk stops at the next file and
+    /// the scan fails with [`SyncError::Cancelled`].
     async fn local_state(
         &self,
         root: &Utf8Path,
         mode: SyncMode,
+        scan_threads: Option<usize>,
+        sequential: bool,
         on_progress: Option<Box<dyn Fn(fleet_scanner::ScanStats) + Send + Sync>>,
+        cancel: Option<&CancellationToken>,
     ) -> Result<LocalState, SyncError>;
 }
 
 pub struct DefaultLocalStateProvider {
     pub fleet_data: Arc<dyn FleetDataStore>,
+    /// Follow symlinks and junctions inside mod folders, for both metadata and hashing scans;
+    /// on by default so linked Workshop content is seen the way the game sees it.
+    pub follow_symlinks: bool,
 }
 
 impl DefaultLocalStateProvider {
     pub fn new(fleet_data: Arc<dyn FleetDataStore>) -> Self {
-        Self { fleet_data }
+        Self {
+            fleet_data,
+            follow_symlinks: true,
+        }
+    }
+
+    pub fn with_follow_symlinks(mut self, follow: bool) -> Self {
+        self.follow_symlinks = follow;
+        self
     }
 
     async fn cache_only(&self, root: &Utf8Path) -> Result<LocalState, SyncError> {
@@ -58,6 +78,7 @@ impl DefaultLocalStateProvider {
             manifest,
             summary,
             trust: LocalTrustLevel::CacheOnly,
+            warnings: Vec::new(),
         })
     }
 
@@ -65,9 +86,14 @@ impl DefaultLocalStateProvider {
         &self,
         root: &Utf8Path,
         on_progress: Option<Box<dyn Fn(fleet_scanner::ScanStats) + Send + Sync>>,
+        cancel: Option<&CancellationToken>,
     ) -> Result<LocalState, SyncError> {
         let fleet_data = self.fleet_data.clone();
+        let follow_symlinks = self.follow_symlinks;
         let root = root.to_owned();
+        let warnings = ScanWarnings::default();
+        let task_warnings = warnings.clone();
+        let task_cancel = cancel.cloned();
         let (manifest, summaries) = tokio::task::spawn_blocking(move || {
             let mut mods = Vec::new();
             let mut summaries = Vec::new();
@@ -87,22 +113,14 @@ impl DefaultLocalStateProvider {
                     continue;
                 }
                 let mod_name = utf.file_name().unwrap().to_string();
-                let cache = match fleet_data.scan_cache_load_mod(&root, &mod_name) {
-                    Ok(c) => c,
-                    Err(e) => {
-                        tracing::warn!("Cache unavailable for {mod_name}: {e}");
-                        Default::default()
-                    }
-                };
+                let cache = load_scan_cache(&*fleet_data, &root, &mod_name, &task_warnings);
 
                 let mut files = Vec::new();
                 let mut summary_files = Vec::new();
-                for walk in WalkDir::new(&utf) {
-                    let walk = walk.map_err(|e| e.to_string())?;
-                    if !walk.file_type().is_file() {
-                        continue;
+                for walk in fleet_scanner::walk_mod_files(&utf, follow_symlinks) {
+                    if is_cancelled(task_cancel.as_ref()) {
+                        return Err("cancelled".to_string());
                     }
-
                     let fs_path = Utf8PathBuf::from_path_buf(walk.into_path())
                         .map_err(|_| "non-utf path".to_string())?;
                     let rel = FleetPath::normalize(
@@ -133,6 +151,7 @@ impl DefaultLocalStateProvider {
                         checksum: checksum.clone(),
                         file_type: FileType::File,
                         parts: Vec::new(),
+                        mtime: Some(mtime),
                     });
 
                     summary_files.push(LocalFileSummary {
@@ -165,35 +184,35 @@ impl DefaultLocalStateProvider {
                 });
             }
 
-            Ok::<(Manifest, Vec<LocalManifestSummary>), String>((
-                Manifest {
-                    version: "1.0".to_string(),
-                    mods,
-                },
-                summaries,
-            ))
+            Ok::<(Manifest, Vec<LocalManifestSummary>), String>((Manifest::new(mods), summaries))
         })
         .await
         .map_err(|e| SyncError::Local(format!("metadata scan join failed: {e}")))?
-        .map_err(SyncError::Local)?;
+        .map_err(|e| cancelled_or(cancel, SyncError::Local(e)))?;
 
         Ok(LocalState {
             manifest,
             summary: Some(summaries),
             trust: LocalTrustLevel::MetadataOnly,
+            warnings: warnings.take(),
         })
     }
 
     async fn smart_verify(
         &self,
         root: &Utf8Path,
+        scan_threads: Option<usize>,
+        sequential: bool,
         on_progress: Option<Box<dyn Fn(fleet_scanner::ScanStats) + Send + Sync>>,
+        cancel: Option<&CancellationToken>,
     ) -> Result<LocalState, SyncError> {
         self.scan_with_strategy(
             root,
             ScanStrategy::SmartCache,
-            LocalTrustLevel::VerifiedSmart,
+            scan_threads,
+            sequential,
             on_progress,
+            cancel,
         )
         .await
     }
@@ -201,13 +220,18 @@ impl DefaultLocalStateProvider {
     async fn full_rehash(
         &self,
         root: &Utf8Path,
+        scan_threads: Option<usize>,
+        sequential: bool,
         on_progress: Option<Box<dyn Fn(fleet_scanner::ScanStats) + Send + Sync>>,
+        cancel: Option<&CancellationToken>,
     ) -> Result<LocalState, SyncError> {
         self.scan_with_strategy(
             root,
             ScanStrategy::ForceRehash,
-            LocalTrustLevel::VerifiedFull,
+            scan_threads,
+            sequential,
             on_progress,
+            cancel,
         )
         .await
     }
@@ -215,7 +239,10 @@ impl DefaultLocalStateProvider {
     async fn fast_check(
         &self,
         root: &Utf8Path,
+        scan_threads: Option<usize>,
+        sequential: bool,
         on_progress: Option<Box<dyn Fn(fleet_scanner::ScanStats) + Send + Sync>>,
+        cancel: Option<&CancellationToken>,
     ) -> Result<LocalState, SyncError> {
         let fleet_data = self.fleet_data.clone();
 
@@ -224,128 +251,138 @@ impl DefaultLocalStateProvider {
         // metadata-only scan so callers still see the on-disk files.
         let contract = match fleet_data.load_baseline_manifest(root) {
             Ok(m) if !m.mods.is_empty() => m,
-            _ => return self.metadata_only(root, on_progress).await,
+            _ => return self.metadata_only(root, on_progress, cancel).await,
         };
 
         let root = root.to_owned();
+        let warnings = ScanWarnings::default();
+        let task_warnings = warnings.clone();
+        let task_cancel = cancel.cloned();
         let (manifest, summary) = tokio::task::spawn_blocking(move || {
             // Process mods in parallel for performance.
-            let results: Result<Vec<_>, String> = contract
-                .mods
-                .par_iter()
-                .map(|contract_mod| -> Result<_, String> {
-                    let mod_path = root.join(&contract_mod.name);
-                    let mut expected_files: u64 = 0;
-                    let mut expected_bytes: u64 = 0;
-                    let mut cached_files: u64 = 0;
-
-                    for f in &contract_mod.files {
-                        expected_files += 1;
-                        expected_bytes += f.length;
-                    }
+            let results: Result<Vec<_>, String> = fleet_scanner::run_in_scan_pool(
+                if sequential { Some(1) } else { scan_threads },
+                || {
+                    contract
+                        .mods
+                        .par_iter()
+                        .map(|contract_mod| -> Result<_, String> {
+                            if is_cancelled(task_cancel.as_ref()) {
+                                return Err("cancelled".to_string());
+                            }
+                            let mod_path = root.join(&contract_mod.name);
+                            let mut expected_files: u64 = 0;
+                            let mut expected_bytes: u64 = 0;
+                            let mut cached_files: u64 = 0;
+
+                            for f in &contract_mod.files {
+                                expected_files += 1;
+                                expected_bytes += f.length;
+                            }
 
-                    if !mod_path.exists() {
-                        // If the directory is gone, the whole mod is missing.
-                        return Ok((
-                            // Manifest Mod Entry (marked dirty/empty)
-                            Mod {
-                                name: contract_mod.name.clone(),
-                                checksum: contract_mod.checksum.clone(),
-                                files: Vec::new(), // Empty files list triggers re-download
-                            },
-                            // Local Summary (empty)
-                            LocalManifestSummary {
-                                mod_name: contract_mod.name.clone(),
-                                files: Vec::new(),
-                            },
-                            (expected_files, expected_bytes, cached_files),
-                        ));
-                    }
+                            if !mod_path.exists() {
+                                // If the directory is gone, the whole mod is missing.
+                                return Ok((
+                                    // Manifest Mod Entry (marked dirty/empty)
+                                    Mod {
+                                        name: contract_mod.name.clone(),
+                                        checksum: contract_mod.checksum.clone(),
+                                        files: Vec::new(), // Empty files list triggers re-download
+                                    },
+                                    // Local Summary (empty)
+                                    LocalManifestSummary {
+                                        mod_name: contract_mod.name.clone(),
+                                        files: Vec::new(),
+                                    },
+                                    (expected_files, expected_bytes, cached_files),
+                                ));
+                            }
 
-                    let cache = match fleet_data.scan_cache_load_mod(&root, &contract_mod.name) {
-                        Ok(c) => c,
-                        Err(e) => {
-                            tracing::warn!("Cache unavailable for {}: {e}", contract_mod.name);
-                            Default::default()
-                        }
-                    };
-
-                    let mut valid_files = Vec::new();
-                    let mut summary_files = Vec::new();
-
-                    for contract_file in &contract_mod.files {
-                        let fs_path = mod_path.join(&contract_file.path);
-
-                        let mut is_valid = false;
-                        let mut current_mtime = 0;
-                        let mut current_size = 0;
-                        let mut current_checksum = String::new();
-
-                        // 1. Check Filesystem Reality
-                        if let Ok(meta) = std::fs::metadata(&fs_path) {
-                            current_mtime = Scanner::mtime(&meta);
-                            current_size = meta.len();
-
-                            // 2. Validate Cache Integrity
-                            // We strictly compare FS vs Cache first.
-                            // If FS matches Cache, we assume Cache's checksum is the file's checksum.
-                            if let Some(cached_entry) = cache.get(&contract_file.path) {
-                                if current_size == cached_entry.size
-                                    && current_mtime == cached_entry.mtime
-                                {
-                                    current_checksum = cached_entry.checksum.clone();
+                            let cache = load_scan_cache(
+                                &*fleet_data,
+                                &root,
+                                &contract_mod.name,
+                                &task_warnings,
+                            );
+
+                            let mut valid_files = Vec::new();
+                            let mut summary_files = Vec::new();
+
+                            for contract_file in &contract_mod.files {
+                                let fs_path = mod_path.join(&contract_file.path);
+
+                                let mut is_valid = false;
+                                let mut current_mtime = 0;
+                                let mut current_size = 0;
+                                let mut current_checksum = String::new();
+
+                                // 1. Check Filesystem Reality
+                                if let Ok(meta) = std::fs::metadata(&fs_path) {
+                                    current_mtime = Scanner::mtime(&meta);
+                                    current_size = meta.len();
+
+                                    // 2. Validate Cache Integrity
+                                    // We strictly compare FS vs Cache first.
+                                    // If FS matches Cache, we assume Cache's checksum is the file's checksum.
+                                    if let Some(cached_entry) = cache.get(&contract_file.path) {
+                                        if current_size == cached_entry.size
+                                            && current_mtime == cached_entry.mtime
+                                        {
+                                            current_checksum = cached_entry.checksum.clone();
+                                        }
+                                    }
+
+                                    // 3. Validate Contract Requirement
+                                    // If the derived checksum matches the contract, the file is healthy.
+                                    if !current_checksum.is_empty()
+                                        && current_checksum == contract_file.checksum
+                                    {
+                                        is_valid = true;
+                                    }
                                 }
-                            }
 
-                            // 3. Validate Contract Requirement
-                            // If the derived checksum matches the contract, the file is healthy.
-                            if !current_checksum.is_empty()
-                                && current_checksum == contract_file.checksum
-                            {
-                                is_valid = true;
+                                if is_valid {
+                                    cached_files += 1;
+                                    valid_files.push(contract_file.clone());
+                                    summary_files.push(LocalFileSummary {
+                                        rel_path: contract_file.path.clone(),
+                                        mtime: current_mtime,
+                                        size: current_size,
+                                        checksum: contract_file.checksum.clone(),
+                                    });
+                                } else if fs_path.exists() {
+                                    // Exists but invalid (size/mtime mismatch OR hash mismatch)
+                                    valid_files.push(File {
+                                        checksum: String::new(), // Mark dirty
+                                        ..contract_file.clone()
+                                    });
+                                    summary_files.push(LocalFileSummary {
+                                        rel_path: contract_file.path.clone(),
+                                        mtime: current_mtime,
+                                        size: current_size,
+                                        checksum: current_checksum, // Might be empty if cache missed
+                                    });
+                                } else {
+                                    // File missing entirely - omit from valid_files so diff sees it as missing
+                                }
                             }
-                        }
-
-                        if is_valid {
-                            cached_files += 1;
-                            valid_files.push(contract_file.clone());
-                            summary_files.push(LocalFileSummary {
-                                rel_path: contract_file.path.clone(),
-                                mtime: current_mtime,
-                                size: current_size,
-                                checksum: contract_file.checksum.clone(),
-                            });
-                        } else if fs_path.exists() {
-                            // Exists but invalid (size/mtime mismatch OR hash mismatch)
-                            valid_files.push(File {
-                                checksum: String::new(), // Mark dirty
-                                ..contract_file.clone()
-                            });
-                            summary_files.push(LocalFileSummary {
-                                rel_path: contract_file.path.clone(),
-                                mtime: current_mtime,
-                                size: current_size,
-                                checksum: current_checksum, // Might be empty if cache missed
-                            });
-                        } else {
-                            // File missing entirely - omit from valid_files so diff sees it as missing
-                        }
-                    }
 
-                    Ok((
-                        Mod {
-                            name: contract_mod.name.clone(),
-                            checksum: contract_mod.checksum.clone(),
-                            files: valid_files,
-                        },
-                        LocalManifestSummary {
-                            mod_name: contract_mod.name.clone(),
-                            files: summary_files,
-                        },
-                        (expected_files, expected_bytes, cached_files),
-                    ))
-                })
-                .collect();
+                            Ok((
+                                Mod {
+                                    name: contract_mod.name.clone(),
+                                    checksum: contract_mod.checksum.clone(),
+                                    files: valid_files,
+                                },
+                                LocalManifestSummary {
+                                    mod_name: contract_mod.name.clone(),
+                                    files: summary_files,
+                                },
+                                (expected_files, expected_bytes, cached_files),
+                            ))
+                        })
+                        .collect()
+                },
+            );
 
             // Unzip the parallel results
             let results = results?;
@@ -383,12 +420,13 @@ impl DefaultLocalStateProvider {
         })
         .await
         .map_err(|e| SyncError::Local(format!("fast check join failed: {e}")))?
-        .map_err(SyncError::Local)?;
+        .map_err(|e| cancelled_or(cancel, SyncError::Local(e)))?;
 
         Ok(LocalState {
             manifest,
             summary: Some(summary),
             trust: LocalTrustLevel::MetadataLite,
+            warnings: warnings.take(),
         })
     }
 
@@ -396,48 +434,154 @@ impl DefaultLocalStateProvider {
         &self,
         root: &Utf8Path,
         strategy: ScanStrategy,
-        trust: LocalTrustLevel,
+        scan_threads: Option<usize>,
+        sequential: bool,
         on_progress: Option<Box<dyn Fn(fleet_scanner::ScanStats) + Send + Sync>>,
+        cancel: Option<&CancellationToken>,
     ) -> Result<LocalState, SyncError> {
         let root_path = root.to_owned();
+        let warnings = ScanWarnings::default();
         let cache_store: Arc<dyn ScanCacheStore> = Arc::new(FleetDataScanCacheStore {
             root: root_path.clone(),
             fleet_data: self.fleet_data.clone(),
+            warnings: warnings.clone(),
         });
 
+        let follow_symlinks = self.follow_symlinks;
+        // The scanner polls a flag; raise it when the token fires.
+        let stop = Arc::new(AtomicBool::new(is_cancelled(cancel)));
+        let relay = cancel.cloned().map(|token| {
+            let stop = stop.clone();
+            tokio::spawn(async move {
+                token.cancelled().await;
+                stop.store(true, Ordering::Relaxed);
+            })
+        });
+        let task_stop = stop.clone();
         let manifest = tokio::task::spawn_blocking(move || {
-            Scanner::scan_directory(&root_path, strategy, on_progress, Some(cache_store), None)
+            let options = ScanOptions {
+                threads: scan_threads,
+                sequential,
+                follow_symlinks,
+                ..ScanOptions::default()
+            };
+            Scanner::scan_directory_with_options(
+                &root_path,
+                strategy,
+                options,
+                on_progress,
+                Some(cache_store),
+                Some(task_stop),
+            )
         })
-        .await
-        .map_err(|e| SyncError::Local(format!("scan join failed: {e}")))?
-        .map_err(|e| SyncError::Local(format!("scan failed: {e}")))?;
+        .await;
+        if let Some(relay) = relay {
+            relay.abort();
+        }
+        let manifest = manifest
+            .map_err(|e| SyncError::Local(format!("scan join failed: {e}")))?
+            .map_err(|e| match e {
+                fleet_scanner::ScannerError::Cancelled => SyncError::Cancelled,
+                e => SyncError::Local(format!("scan failed: {e}")),
+            })?;
 
         let summary = build_summary_from_manifest(root, &manifest).ok();
 
         Ok(LocalState {
             manifest,
             summary,
-            trust,
+            trust: match strategy {
+                ScanStrategy::SmartCache => LocalTrustLevel::VerifiedSmart,
+                ScanStrategy::ForceRehash => LocalTrustLevel::VerifiedFull,
+            },
+            warnings: warnings.take(),
         })
     }
 }
 
+/// Recovered storage problems collected across scan threads, reported once each.
+#[derive(Clone, Default)]
+struct ScanWarnings(Arc<Mutex<Vec<String>>>);
+
+impl ScanWarnings {
+    fn push(&self, message: String) {
+        let mut warnings = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        if !warnings.contains(&message) {
+            warnings.push(message);
+        }
+    }
+
+    fn take(&self) -> Vec<String> {
+        std::mem::take(&mut *self.0.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+/// Loads a mod's scan cache. The cache only saves rehashing, so when it is corrupt its
+/// entries are dropped (the mod is rehashed from scratch) and the reset is reported.
+fn is_cancelled(cancel: Option<&CancellationToken>) -> bool {
+    cancel.is_some_and(CancellationToken::is_cancelled)
+}
+
+/// `err`, unless the scan stopped because `cancel` fired.
+fn cancelled_or(cancel: Option<&CancellationToken>, err: SyncError) -> SyncError {
+    if is_cancelled(cancel) {
+        SyncError::Cancelled
+    } else {
+        err
+    }
+}
+
+fn load_scan_cache(
+    fleet_data: &dyn FleetDataStore,
+    root: &Utf8Path,
+    mod_name: &str,
+    warnings: &ScanWarnings,
+) -> HashMap<String, FileCacheEntry> {
+    match fleet_data.scan_cache_load_mod(root, mod_name) {
+        Ok(cache) => cache,
+        Err(e) if e.kind() == StorageErrorKind::CacheCorrupt => {
+            tracing::warn!("{e}; resetting it");
+            match fleet_data.scan_cache_delete_mod(root, mod_name) {
+                Ok(()) => warnings.push(format!(
+                    "Scan cache for {mod_name} was corrupt and has been reset; its files were rehashed"
+                )),
+                Err(e) => warnings.push(format!(
+                    "Scan cache for {mod_name} is corrupt and could not be reset: {e}"
+                )),
+            }
+            HashMap::new()
+        }
+        Err(e) if e.kind() == StorageErrorKind::Corrupt => {
+            // The store has already moved the file aside; the next write recreates it.
+            tracing::warn!("Cache unavailable for {mod_name}: {e}");
+            warnings.push(
+                "Local database was corrupt and has been moved aside; it will be recreated"
+                    .to_string(),
+            );
+            HashMap::new()
+        }
+        Err(e) => {
+            tracing::warn!("Cache unavailable for {mod_name}: {e}");
+            HashMap::new()
+        }
+    }
+}
+
 struct FleetDataScanCacheStore {
     root: Utf8PathBuf,
     fleet_data: Arc<dyn FleetDataStore>,
+    warnings: ScanWarnings,
 }
 
 impl ScanCacheStore for FleetDataScanCacheStore {
     fn load_mod_cache(&self, mod_name: &str) -> Result<ScanCache, fleet_scanner::ScannerError> {
-        let entries = match self.fleet_data.scan_cache_load_mod(&self.root, mod_name) {
-            Ok(e) => e,
-            Err(e) => {
-                tracing::warn!("Cache load failed for {mod_name}: {e}");
-                return Ok(ScanCache::default());
-            }
-        };
+        let entries = load_scan_cache(&*self.fleet_data, &self.root, mod_name, &self.warnings);
         let mut cache = ScanCache::default();
         for (rel_path, e) in entries {
+            if let Some(dir) = ScanCache::parse_dir_row(&rel_path) {
+                cache.dirs.insert(dir.to_string(), e.mtime);
+                continue;
+            }
             cache.entries.insert(
                 rel_path,
                 fleet_scanner::cache::FileCacheEntry {
@@ -455,7 +599,7 @@ impl ScanCacheStore for FleetDataScanCacheStore {
         mod_name: &str,
         cache: &ScanCache,
     ) -> Result<(), fleet_scanner::ScannerError> {
-        let mut upserts = Vec::with_capacity(cache.entries.len());
+        let mut upserts = Vec::with_capacity(cache.entries.len() + cache.dirs.len());
         for (rel_path, e) in &cache.entries {
             upserts.push(CacheUpsert {
                 rel_path: rel_path.clone(),
@@ -464,6 +608,14 @@ impl ScanCacheStore for FleetDataScanCacheStore {
                 checksum: e.checksum.clone(),
             });
         }
+        for (dir, &mtime) in &cache.dirs {
+            upserts.push(CacheUpsert {
+                rel_path: ScanCache::dir_row(dir),
+                mtime,
+                size: 0,
+                checksum: String::new(),
+            });
+        }
         if let Err(e) = self
             .fleet_data
             .scan_cache_upsert_batch(&self.root, mod_name, &upserts)
@@ -472,6 +624,20 @@ impl ScanCacheStore for FleetDataScanCacheStore {
         }
         Ok(())
     }
+
+    fn prune_mod(
+        &self,
+        mod_name: &str,
+        stale_rel_paths: &[String],
+    ) -> Result<(), fleet_scanner::ScannerError> {
+        if let Err(e) =
+            self.fleet_data
+                .scan_cache_delete_files(&self.root, mod_name, stale_rel_paths)
+        {
+            tracing::warn!("Cache prune failed for {mod_name}: {e}");
+        }
+        Ok(())
+    }
 }
 
 fn build_summary_from_manifest(
@@ -514,14 +680,26 @@ impl LocalStateProvider for DefaultLocalStateProvider {
         &self,
         root: &Utf8Path,
         mode: SyncMode,
+        scan_threads: Option<usize>,
+        sequential: bool,
         on_progress: Option<Box<dyn Fn(fleet_scanner::ScanStats) + Send + Sync>>,
+        cancel: Option<&CancellationToken>,
     ) -> Result<LocalState, SyncError> {
         match mode {
             SyncMode::CacheOnly => self.cache_only(root).await,
-            SyncMode::MetadataOnly => self.metadata_only(root, on_progress).await,
-            SyncMode::SmartVerify => self.smart_verify(root, on_progress).await,
-            SyncMode::FullRehash => self.full_rehash(root, on_progress).await,
-            SyncMode::FastCheck => self.fast_check(root, on_progress).await,
+            SyncMode::MetadataOnly => self.metadata_only(root, on_progress, cancel).await,
+            SyncMode::SmartVerify => {
+                self.smart_verify(root, scan_threads, sequential, on_progress, cancel)
+                    .await
+            }
+            SyncMode::FullRehash => {
+                self.full_rehash(root, scan_threads, sequential, on_progress, cancel)
+                    .await
+            }
+            SyncMode::FastCheck => {
+                self.fast_check(root, scan_threads, sequential, on_progress, cancel)
+                    .await
+            }
         }
     }
 }
@@ -564,7 +742,7 @@ mod tests {
             .unwrap();
 
         let provider = DefaultLocalStateProvider::new(fleet_data);
-        let state = provider.metadata_only(&root, None).await.unwrap();
+        let state = provider.metadata_only(&root, None, None).await.unwrap();
 
         assert_eq!(state.trust, LocalTrustLevel::MetadataOnly);
         let f = state.manifest.mods[0]
@@ -575,6 +753,25 @@ mod tests {
         assert_eq!(f.checksum, checksum);
     }
 
+    #[tokio::test]
+    async fn a_cancelled_scan_stops_walking() {
+        let dir = tempdir().unwrap();
+        let root = Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+        std::fs::create_dir_all(root.join("@m")).unwrap();
+        std::fs::write(root.join("@m/file.txt"), b"hello").unwrap();
+        let provider = DefaultLocalStateProvider::new(Arc::new(RedbFleetDataStore));
+        let token = CancellationToken::new();
+        token.cancel();
+
+        for mode in [SyncMode::MetadataOnly, SyncMode::FullRehash] {
+            let err = provider
+                .local_state(&root, mode, None, false, None, Some(&token))
+                .await
+                .unwrap_err();
+            assert!(matches!(err, SyncError::Cancelled), "{mode:?}: {err:?}");
+        }
+    }
+
     #[tokio::test]
     async fn metadata_only_marks_dirty_when_metadata_changed() {
         let dir = tempdir().unwrap();
@@ -606,7 +803,7 @@ mod tests {
         std::fs::write(&file_path, b"hello world").unwrap();
 
         let provider = DefaultLocalStateProvider::new(fleet_data);
-        let state = provider.metadata_only(&root, None).await.unwrap();
+        let state = provider.metadata_only(&root, None, None).await.unwrap();
         let f = state.manifest.mods[0]
             .files
             .iter()
@@ -1,10 +1,12 @@
 use camino::Utf8PathBuf;
-use fleet_core::SyncPlan;
+use fleet_core::{DeleteThreshold, DownloadAction, SyncPlan};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 pub mod engine;
 pub mod execute;
 pub mod local;
+pub mod plan_file;
 pub mod remote;
 pub mod storage;
 
@@ -19,6 +21,75 @@ pub struct FetchStats {
 pub struct FetchResult {
     pub manifest: fleet_core::Manifest,
     pub stats: FetchStats,
+    /// Repository URL each mod in `manifest` was fetched from.
+    pub mod_sources: HashMap<String, String>,
+    /// Checksum advertised by the primary repo.json; empty when offline.
+    pub repo_checksum: String,
+    /// Paths that differed only by case; `manifest` keeps only the first casing of each.
+    pub case_collisions: Vec<fleet_core::path_utils::CaseCollision>,
+    /// Mods whose `mod.srf` could not be fetched (only with
+    /// [`SyncOptions::skip_unreachable_mods`]). Plans leave them untouched; `manifest` keeps
+    /// their last synced entry, if any.
+    pub unreachable_mods: Vec<UnreachableMod>,
+}
+
+impl FetchResult {
+    /// Names of [`Self::unreachable_mods`], for excluding them from a plan.
+    pub fn unreachable_mod_names(&self) -> Vec<String> {
+        self.unreachable_mods
+            .iter()
+            .map(|m| m.mod_name.clone())
+            .collect()
+    }
+}
+
+/// A mod listed in repo.json whose `mod.srf` failed to load.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnreachableMod {
+    pub mod_name: String,
+    pub repo_url: String,
+    pub reason: String,
+}
+
+impl std::fmt::Display for UnreachableMod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} is unreachable ({}); it was left as is",
+            self.mod_name, self.reason
+        )
+    }
+}
+
+/// Size of a repository's mods as advertised by its `mod.srf` files.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RepoSizeInfo {
+    pub repo_name: String,
+    pub mods: usize,
+    pub files: usize,
+    pub total_bytes: u64,
+}
+
+/// Outcome of [`DefaultSyncEngine::quick_check`]: the repositories' per-mod checksums
+/// against the stored baseline manifest. Mod names are sorted.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuickCheck {
+    /// Mods whose checksum differs from the baseline.
+    pub changed: Vec<String>,
+    /// Mods the repositories list that the baseline lacks.
+    pub added: Vec<String>,
+    /// Baseline mods no repository lists any more.
+    pub removed: Vec<String>,
+}
+
+impl QuickCheck {
+    pub fn is_up_to_date(&self) -> bool {
+        self.mods_changed() == 0
+    }
+
+    pub fn mods_changed(&self) -> usize {
+        self.changed.len() + self.added.len() + self.removed.len()
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -35,32 +106,141 @@ pub enum SyncMode {
     FastCheck,
 }
 
+/// Order files are handed to the downloader in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DownloadOrder {
+    /// Plan order: mod by mod, files in manifest order.
+    #[default]
+    AsPlanned,
+    /// Many small files first, so the file count climbs quickly.
+    SmallestFirst,
+    /// Big files first, so the end of the sync isn't a trickle of small requests.
+    LargestFirst,
+}
+
 #[derive(Debug, Clone)]
 pub struct SyncOptions {
     pub max_threads: usize,
+    /// Downloads open to any one host at a time, within `max_threads`; `None` is no cap.
+    pub max_connections_per_host: Option<usize>,
+    pub download_order: DownloadOrder,
+    /// Split large files into parallel Range requests; `None` downloads every file in one
+    /// stream.
+    pub segmented_downloads: Option<fleet_infra::SegmentedDownloads>,
+    /// Refuse to plan against a repo.json whose checksum differs from this one.
+    pub pinned_repo_checksum: Option<String>,
     pub rate_limit_bytes: Option<u64>,
+    /// A limit the caller can change while downloads run (e.g. on a bandwidth schedule);
+    /// replaces `rate_limit_bytes` when set.
+    pub rate_limit: Option<fleet_infra::RateLimit>,
     pub cache_root: Option<Utf8PathBuf>,
+    /// Restore missing/changed files only; never delete extra local files or mods.
+    pub skip_deletes: bool,
+    /// Re-hash the files listed in `SyncPlan.checks` after downloading and fail on mismatch.
+    pub verify_checks: bool,
+    /// Checksum every file as it is downloaded. Turning this off trusts the size alone and
+    /// halves disk reads; only do that for a trusted mirror (e.g. on the LAN).
+    pub verify_downloads: bool,
+    /// Re-read the baseline after the final commit and fail the sync if it did not land intact.
+    pub verify_baseline: bool,
+    /// Refuse plans whose deletes exceed this threshold; `None` allows any plan.
+    pub delete_threshold: Option<DeleteThreshold>,
+    /// Never touch the network: use the cached manifest and refuse plans that need downloads.
+    pub offline: bool,
+    /// Hashing threads for local scans; `None` leaves one core free.
+    pub scan_threads: Option<usize>,
+    /// Scan one file at a time in path order (see [`fleet_scanner::ScanOptions::sequential`]).
+    /// Overrides `scan_threads`.
+    pub sequential_scan: bool,
+    /// Stage downloads here and move them into place; `None` writes `.part` files in place.
+    pub temp_dir: Option<Utf8PathBuf>,
+    /// Another install laid out like the mods folder (a second drive, a USB copy). A file
+    /// there with the expected checksum is copied in instead of downloaded.
+    pub local_source: Option<Utf8PathBuf>,
+    /// Give downloaded files the modification time the repository lists for them, where it
+    /// lists one, instead of the download time.
+    pub preserve_mtimes: bool,
+    /// Retry a download that receives no bytes for this long; `None` relies on the client's
+    /// read timeout.
+    pub stall_timeout: Option<std::time::Duration>,
+    /// Mod folders left alone: nothing in them is downloaded, verified, renamed or deleted.
+    pub skip_mods: Vec<String>,
+    /// Glob patterns over `@mod/path` (e.g. `@mod/userconfig/**`) of files that are never
+    /// deleted or downloaded over; matching is case-insensitive.
+    pub exclude: Vec<String>,
+    /// Carry on without mods whose `mod.srf` fails to load, leaving them untouched, instead
+    /// of failing the whole fetch.
+    pub skip_unreachable_mods: bool,
+    /// Give up on a host after this many consecutive failed requests (srf fetches and
+    /// downloads) and fail with [`SyncError::RepoUnavailable`]; `None` retries everything.
+    pub max_host_failures: Option<usize>,
 }
 
 impl Default for SyncOptions {
     fn default() -> Self {
         Self {
             max_threads: 4,
+            max_connections_per_host: None,
+            download_order: DownloadOrder::AsPlanned,
+            segmented_downloads: None,
+            pinned_repo_checksum: None,
             rate_limit_bytes: None,
+            rate_limit: None,
             cache_root: None,
+            skip_deletes: false,
+            verify_checks: false,
+            verify_downloads: true,
+            verify_baseline: true,
+            delete_threshold: Some(DeleteThreshold::default()),
+            offline: false,
+            scan_threads: None,
+            sequential_scan: false,
+            temp_dir: None,
+            local_source: None,
+            preserve_mtimes: true,
+            stall_timeout: Some(fleet_infra::net::DEFAULT_STALL_TIMEOUT),
+            skip_mods: Vec::new(),
+            exclude: Vec::new(),
+            skip_unreachable_mods: false,
+            max_host_failures: Some(fleet_infra::net::DEFAULT_MAX_HOST_FAILURES),
         }
     }
 }
 
+impl SyncOptions {
+    /// How many `mod.srf` requests are in flight at once; follows `max_threads` so hosts that
+    /// rate-limit metadata requests can be treated politely.
+    pub fn metadata_concurrency(&self) -> usize {
+        self.max_threads.max(1)
+    }
+
+    /// A fresh breaker for one sync phase, when [`Self::max_host_failures`] is set.
+    pub(crate) fn circuit_breaker(&self) -> Option<std::sync::Arc<fleet_infra::CircuitBreaker>> {
+        self.max_host_failures
+            .filter(|&n| n > 0)
+            .map(|n| std::sync::Arc::new(fleet_infra::CircuitBreaker::new(n)))
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SyncRequest {
     pub repo_url: String,
+    /// Additional repositories merged with `repo_url` into one manifest.
+    pub extra_repo_urls: Vec<String>,
     pub local_root: Utf8PathBuf,
     pub mode: SyncMode,
     pub options: SyncOptions,
     pub profile_id: Option<String>,
 }
 
+impl SyncRequest {
+    /// The primary repository followed by any extra ones.
+    pub fn repo_urls(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.repo_url.as_str())
+            .chain(self.extra_repo_urls.iter().map(String::as_str))
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct SyncStats {
     pub files_planned_download: u64,
@@ -68,6 +248,166 @@ pub struct SyncStats {
     pub files_deleted: u64,
     pub mods_deleted: u64,
     pub renames: u64,
+    pub files_verified: u64,
+    /// Planned downloads satisfied by linking or copying an identical file from this sync.
+    pub files_deduplicated: u64,
+    /// Planned downloads copied from [`SyncOptions::local_source`] instead.
+    pub files_from_local_source: u64,
+    /// Execution stopped at a cancellation; only the returned artifacts were downloaded.
+    pub cancelled: bool,
+    /// What was actually changed on disk, per mod.
+    pub changes: ChangeLog,
+}
+
+/// What a sync changed on disk, grouped by mod folder in name order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangeLog {
+    pub mods: Vec<ModChanges>,
+}
+
+/// One mod's changes; file paths are relative to the mod folder.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModChanges {
+    pub mod_name: String,
+    /// The whole mod folder was deleted.
+    #[serde(default)]
+    pub removed: bool,
+    /// The folder was renamed from this name (a casing fix) before syncing.
+    #[serde(default)]
+    pub renamed_from: Option<String>,
+    #[serde(default)]
+    pub added: Vec<String>,
+    #[serde(default)]
+    pub updated: Vec<String>,
+    #[serde(default)]
+    pub deleted: Vec<String>,
+}
+
+impl ModChanges {
+    pub fn file_count(&self) -> usize {
+        self.added.len() + self.updated.len() + self.deleted.len()
+    }
+}
+
+impl ChangeLog {
+    pub fn is_empty(&self) -> bool {
+        self.mods.is_empty()
+    }
+
+    pub(crate) fn mod_entry(&mut self, mod_name: &str) -> &mut ModChanges {
+        let index = match self
+            .mods
+            .binary_search_by(|m| m.mod_name.as_str().cmp(mod_name))
+        {
+            Ok(index) => index,
+            Err(index) => {
+                self.mods.insert(
+                    index,
+                    ModChanges {
+                        mod_name: mod_name.to_string(),
+                        ..ModChanges::default()
+                    },
+                );
+                index
+            }
+        };
+        &mut self.mods[index]
+    }
+
+    /// One line for people, e.g. "Updated @ace (12 files), removed @oldmod".
+    pub fn summary(&self) -> String {
+        self.condensed(0).summary()
+    }
+
+    /// Counts per mod plus up to `sample_paths` of its file paths, for keeping around
+    /// without growing with the size of the sync.
+    pub fn condensed(&self, sample_paths: usize) -> ChangeSummary {
+        ChangeSummary {
+            mods: self
+                .mods
+                .iter()
+                .map(|m| ModChangeCounts {
+                    mod_name: m.mod_name.clone(),
+                    removed: m.removed,
+                    renamed_from: m.renamed_from.clone(),
+                    files_added: m.added.len(),
+                    files_updated: m.updated.len(),
+                    files_deleted: m.deleted.len(),
+                    sample: m
+                        .added
+                        .iter()
+                        .chain(&m.updated)
+                        .chain(&m.deleted)
+                        .take(sample_paths)
+                        .cloned()
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A [`ChangeLog`] reduced to counts and a few example paths per mod.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangeSummary {
+    pub mods: Vec<ModChangeCounts>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModChangeCounts {
+    pub mod_name: String,
+    #[serde(default)]
+    pub removed: bool,
+    #[serde(default)]
+    pub renamed_from: Option<String>,
+    #[serde(default)]
+    pub files_added: usize,
+    #[serde(default)]
+    pub files_updated: usize,
+    #[serde(default)]
+    pub files_deleted: usize,
+    /// The first few changed paths (added, then updated, then deleted).
+    #[serde(default)]
+    pub sample: Vec<String>,
+}
+
+impl ChangeSummary {
+    /// See [`ChangeLog::summary`].
+    pub fn summary(&self) -> String {
+        let parts: Vec<String> = self
+            .mods
+            .iter()
+            .map(|m| {
+                let files = |n: usize| {
+                    if n == 1 {
+                        "1 file".to_string()
+                    } else {
+                        format!("{n} files")
+                    }
+                };
+                let changed = m.files_added + m.files_updated + m.files_deleted;
+                if m.removed {
+                    format!("removed {}", m.mod_name)
+                } else if m.files_updated == 0 && m.files_deleted == 0 && m.renamed_from.is_none() {
+                    format!("added {} ({})", m.mod_name, files(m.files_added))
+                } else if changed == 0 {
+                    format!(
+                        "renamed {} to {}",
+                        m.renamed_from.as_deref().unwrap_or_default(),
+                        m.mod_name
+                    )
+                } else {
+                    format!("updated {} ({})", m.mod_name, files(changed))
+                }
+            })
+            .collect();
+        let summary = parts.join(", ");
+        let mut chars = summary.chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => "No changes".into(),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -75,6 +415,129 @@ pub struct SyncResult {
     pub plan: SyncPlan,
     pub executed: bool,
     pub stats: SyncStats,
+    /// Storage problems the sync recovered from (e.g. a corrupt database was recreated).
+    pub warnings: Vec<String>,
+}
+
+/// A file that could not be downloaded, with the reason from the last attempt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailedDownload {
+    pub mod_name: String,
+    pub rel_path: String,
+    pub size: u64,
+    pub expected_checksum: String,
+    pub mtime: Option<u64>,
+    /// Checksum of the mod entry the download was planned against; empty when not known.
+    pub mod_checksum: String,
+    pub reason: fleet_infra::DownloadError,
+}
+
+impl FailedDownload {
+    /// The planned download that failed, ready to be retried.
+    pub fn action(&self) -> DownloadAction {
+        DownloadAction {
+            mod_name: self.mod_name.clone(),
+            rel_path: self.rel_path.clone(),
+            size: self.size,
+            expected_checksum: self.expected_checksum.clone(),
+            mtime: self.mtime,
+            parts: Vec::new(),
+        }
+    }
+}
+
+/// A plan that downloads only `failures`, for resuming a partly failed sync.
+pub fn retry_plan(failures: &[FailedDownload]) -> SyncPlan {
+    SyncPlan {
+        renames: Vec::new(),
+        checks: Vec::new(),
+        downloads: failures.iter().map(FailedDownload::action).collect(),
+        deletes: Vec::new(),
+    }
+}
+
+/// A plan that downloads `files` (`(mod_name, rel_path)`) as `baseline` lists them, for
+/// restoring a few files of an otherwise current install. Names match case-insensitively;
+/// a file the baseline does not list is [`SyncError::NotFound`].
+pub fn restore_plan(
+    baseline: &fleet_core::Manifest,
+    files: &[(String, String)],
+) -> Result<SyncPlan, SyncError> {
+    let mut downloads: Vec<DownloadAction> = Vec::new();
+    for (mod_name, rel_path) in files {
+        let key = fleet_core::path_utils::FleetPath::canonicalize(rel_path);
+        let found = baseline
+            .mods
+            .iter()
+            .filter(|m| m.name.eq_ignore_ascii_case(mod_name))
+            .find_map(|m| {
+                m.files
+                    .iter()
+                    .find(|f| fleet_core::path_utils::FleetPath::canonicalize(&f.path) == key)
+                    .map(|f| fleet_core::diff::download_action(&m.name, f))
+            })
+            .ok_or_else(|| {
+                SyncError::NotFound(format!(
+                    "{mod_name}/{rel_path} is not in the last synced manifest"
+                ))
+            })?;
+        if !downloads
+            .iter()
+            .any(|d| d.mod_name == found.mod_name && d.rel_path == found.rel_path)
+        {
+            downloads.push(found);
+        }
+    }
+    Ok(SyncPlan {
+        renames: Vec::new(),
+        checks: Vec::new(),
+        downloads,
+        deletes: Vec::new(),
+    })
+}
+
+impl std::fmt::Display for FailedDownload {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}: {}", self.mod_name, self.rel_path, self.reason)
+    }
+}
+
+/// A file listed in `SyncPlan.checks` that no longer matches its expected checksum.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailedCheck {
+    pub path: String,
+    pub reason: String,
+}
+
+impl std::fmt::Display for FailedCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path, self.reason)
+    }
+}
+
+fn describe_failed_downloads(failed: &[FailedDownload]) -> String {
+    describe_failures(
+        format!("{} file(s) failed to download", failed.len()),
+        failed,
+    )
+}
+
+fn describe_failed_checks(failed: &[FailedCheck]) -> String {
+    describe_failures(
+        format!("{} file(s) failed verification", failed.len()),
+        failed,
+    )
+}
+
+fn describe_failures<T: std::fmt::Display>(mut out: String, failed: &[T]) -> String {
+    const SHOWN: usize = 10;
+    for f in failed.iter().take(SHOWN) {
+        out.push_str(&format!("\n  {f}"));
+    }
+    if failed.len() > SHOWN {
+        out.push_str(&format!("\n  ... and {} more", failed.len() - SHOWN));
+    }
+    out
 }
 
 /// High-level error type for sync operations.
@@ -82,15 +545,42 @@ pub struct SyncResult {
 pub enum SyncError {
     #[error("Remote fetch error: {0}")]
     Remote(String),
+    /// The repository host could not be reached or answered with a server error (5xx);
+    /// these count towards [`SyncOptions::max_host_failures`].
+    #[error("Repository host error: {0}")]
+    HostFailure(String),
     #[error("Local state error: {0}")]
     Local(String),
     #[error("Diff error: {0}")]
     Diff(String),
     #[error("Execution error: {0}")]
     Execution(String),
+    #[error("{}", describe_failed_downloads(.0))]
+    Downloads(Vec<FailedDownload>),
+    #[error("{}", describe_failed_checks(.0))]
+    Verification(Vec<FailedCheck>),
+    #[error("Invalid repository URL: {0}")]
+    InvalidUrl(String),
+    #[error("Held at repository checksum {pinned}, but the repository now advertises {live}; unpin the profile to update")]
+    PinnedVersion { pinned: String, live: String },
+    /// The repository host failed this many times in a row, so the remaining requests to it
+    /// were not attempted.
+    #[error(
+        "Repository host {host} is unavailable ({failures} consecutive failures); try again later"
+    )]
+    RepoUnavailable { host: String, failures: usize },
+    /// The repository answered 404 for a file it lists.
+    #[error("Not found on the repository: {0}")]
+    NotFound(String),
+    #[error("Plan would delete {deleted} of {existing} local files; confirm to continue")]
+    Destructive { deleted: usize, existing: usize },
+    /// The sync was cancelled; files that finished downloading are recorded in the baseline.
+    #[error("Sync cancelled")]
+    Cancelled,
 }
 
 pub use engine::DefaultSyncEngine;
+pub use execute::verify_downloads;
 pub use local::{LocalState, LocalStateProvider, LocalTrustLevel};
 
 /// Convenience constructor for the default engine.
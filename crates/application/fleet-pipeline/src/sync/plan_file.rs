@@ -0,0 +1,155 @@
+use camino::Utf8Path;
+use fleet_core::SyncPlan;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::sync::{FetchResult, FetchStats, SyncError, SyncRequest};
+
+/// Bumped whenever the on-disk layout of [`SavedPlan`] changes incompatibly.
+pub const PLAN_FORMAT_VERSION: u32 = 2;
+
+/// A plan written to disk together with the target it was computed for, so it can be
+/// reviewed and applied later (possibly on another machine with the same layout).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SavedPlan {
+    pub format_version: u32,
+    pub repo_url: String,
+    #[serde(default)]
+    pub extra_repo_urls: Vec<String>,
+    pub local_root: String,
+    pub plan: SyncPlan,
+    /// The remote manifest the plan was computed against; applying the plan records it as
+    /// the baseline, whatever the repository has moved on to since.
+    pub manifest: fleet_core::Manifest,
+    /// Repository URL each mod in `manifest` came from.
+    pub mod_sources: HashMap<String, String>,
+}
+
+impl SavedPlan {
+    pub fn new(req: &SyncRequest, plan: SyncPlan, remote: &FetchResult) -> Self {
+        Self {
+            format_version: PLAN_FORMAT_VERSION,
+            repo_url: req.repo_url.clone(),
+            extra_repo_urls: req.extra_repo_urls.clone(),
+            local_root: req.local_root.to_string(),
+            plan,
+            manifest: remote.manifest.clone(),
+            mod_sources: remote.mod_sources.clone(),
+        }
+    }
+
+    /// The remote state as it was when the plan was saved.
+    pub fn remote_state(&self) -> FetchResult {
+        FetchResult {
+            manifest: self.manifest.clone(),
+            stats: FetchStats::default(),
+            mod_sources: self.mod_sources.clone(),
+            repo_checksum: String::new(),
+            case_collisions: Vec::new(),
+            unreachable_mods: Vec::new(),
+        }
+    }
+
+    /// Checks that the plan was computed for the same repositories and root as `req`.
+    pub fn validate_for(&self, req: &SyncRequest) -> Result<(), SyncError> {
+        if self.format_version != PLAN_FORMAT_VERSION {
+            return Err(SyncError::Execution(format!(
+                "Unsupported plan format version {} (expected {PLAN_FORMAT_VERSION})",
+                self.format_version
+            )));
+        }
+        let saved_repos: Vec<&str> = std::iter::once(self.repo_url.as_str())
+            .chain(self.extra_repo_urls.iter().map(String::as_str))
+            .collect();
+        let req_repos: Vec<&str> = req.repo_urls().collect();
+        if saved_repos != req_repos {
+            return Err(SyncError::Execution(format!(
+                "Plan was computed for repository {}, not {}",
+                saved_repos.join(", "),
+                req_repos.join(", ")
+            )));
+        }
+        if Utf8Path::new(&self.local_root) != req.local_root {
+            return Err(SyncError::Execution(format!(
+                "Plan was computed for {}, not {}",
+                self.local_root, req.local_root
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{SyncMode, SyncOptions};
+    use fleet_core::{DeleteAction, DownloadAction};
+
+    fn request(repo_url: &str, root: &str) -> SyncRequest {
+        SyncRequest {
+            repo_url: repo_url.into(),
+            extra_repo_urls: vec![],
+            local_root: root.into(),
+            mode: SyncMode::FastCheck,
+            options: SyncOptions::default(),
+            profile_id: None,
+        }
+    }
+
+    fn sample_remote() -> FetchResult {
+        FetchResult {
+            manifest: fleet_core::Manifest::new(Vec::new()),
+            stats: FetchStats::default(),
+            mod_sources: HashMap::from([("@mod".into(), "https://example.com/repo".into())]),
+            repo_checksum: String::new(),
+            case_collisions: Vec::new(),
+            unreachable_mods: Vec::new(),
+        }
+    }
+
+    fn sample_plan() -> SyncPlan {
+        SyncPlan {
+            renames: vec![],
+            checks: vec![],
+            downloads: vec![DownloadAction {
+                mod_name: "@mod".into(),
+                rel_path: "addons/a.pbo".into(),
+                size: 10,
+                expected_checksum: "ABC".into(),
+                mtime: None,
+                parts: Vec::new(),
+            }],
+            deletes: vec![DeleteAction {
+                path: "@mod/old.pbo".into(),
+            }],
+        }
+    }
+
+    #[test]
+    fn saved_plan_round_trips_through_json() {
+        let req = request("https://example.com/repo", "/mods");
+        let saved = SavedPlan::new(&req, sample_plan(), &sample_remote());
+
+        let json = serde_json::to_string(&saved).unwrap();
+        let loaded: SavedPlan = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(loaded, saved);
+        assert!(loaded.validate_for(&req).is_ok());
+    }
+
+    #[test]
+    fn saved_plan_rejects_other_repo_or_root() {
+        let saved = SavedPlan::new(
+            &request("https://example.com/repo", "/mods"),
+            sample_plan(),
+            &sample_remote(),
+        );
+
+        assert!(saved
+            .validate_for(&request("https://other.example.com/repo", "/mods"))
+            .is_err());
+        assert!(saved
+            .validate_for(&request("https://example.com/repo", "/elsewhere"))
+            .is_err());
+    }
+}
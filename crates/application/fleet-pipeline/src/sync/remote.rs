@@ -4,16 +4,50 @@ use fleet_core::path_utils::FleetPath;
 use fleet_core::repo::Repository;
 use fleet_core::Manifest;
 use futures::StreamExt;
-use reqwest::Client;
+use reqwest::header::{HeaderMap, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone)]
 pub struct RemoteState {
     pub manifest: Manifest,
 }
 
+/// Validators from a previous response, replayed as `If-None-Match` / `If-Modified-Since`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheValidators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl CacheValidators {
+    fn from_headers(headers: &HeaderMap) -> Self {
+        let get = |name| {
+            headers
+                .get(name)
+                .and_then(|h| h.to_str().ok())
+                .map(|s| s.to_string())
+        };
+        Self {
+            etag: get(ETAG),
+            last_modified: get(LAST_MODIFIED),
+        }
+    }
+}
+
+/// Outcome of a conditional GET.
+#[derive(Debug, Clone)]
+pub enum Conditional<T> {
+    /// `304 Not Modified`: the caller's cached copy is still current.
+    NotModified,
+    Fetched {
+        value: T,
+        validators: CacheValidators,
+    },
+}
+
 #[async_trait::async_trait]
 pub trait RemoteStateProvider: Send + Sync {
-    async fn head_repo_json_mtime(&self, repo_url: &str) -> Result<Option<String>, SyncError>;
     async fn fetch_repo_json(&self, repo_url: &str) -> Result<RepositoryExternal, SyncError>;
     async fn fetch_mod_srf(
         &self,
@@ -21,26 +55,85 @@ pub trait RemoteStateProvider: Send + Sync {
         mod_name: &str,
     ) -> Result<fleet_core::Mod, SyncError>;
     async fn fetch_remote(&self, repo_url: &str) -> Result<RemoteState, SyncError>;
+    async fn fetch_repo_json_conditional(
+        &self,
+        repo_url: &str,
+        cached: &CacheValidators,
+    ) -> Result<Conditional<RepositoryExternal>, SyncError>;
+    async fn fetch_mod_srf_conditional(
+        &self,
+        base: &reqwest::Url,
+        mod_name: &str,
+        cached: &CacheValidators,
+    ) -> Result<Conditional<fleet_core::Mod>, SyncError>;
 }
 
-/// HTTP-based remote provider that fetches repo.json and per-mod SRFs.
+/// `mod.srf` requests in flight at once when no [`super::SyncOptions`] apply.
+pub(crate) const DEFAULT_METADATA_CONCURRENCY: usize = 4;
+
+/// Remote provider that fetches repo.json and per-mod SRFs over HTTP, or reads them
+/// straight from disk for `file://` repositories (local mirrors, NAS mounts).
 pub struct HttpRemoteStateProvider {
     client: Client,
 }
 
+/// Turns user input into a canonical repository base URL: `https://` is assumed when no
+/// scheme is given, a bare local path becomes a `file://` URL, a pasted `.../repo.json` is
+/// reduced to its directory, and the result always ends with `/`.
+pub fn normalize_repo_url(input: &str) -> Result<String, SyncError> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err(SyncError::InvalidUrl("URL is empty".into()));
+    }
+    let with_scheme = if input.contains("://") || is_local_path(input) {
+        input.to_string()
+    } else {
+        format!("https://{input}")
+    };
+    Ok(normalize_repo_base(&with_scheme)?.to_string())
+}
+
+/// Absolute local paths: `/srv/mirror`, `C:\mirror` or a UNC share like `\\nas\mods`.
+fn is_local_path(input: &str) -> bool {
+    let bytes = input.as_bytes();
+    input.starts_with('/')
+        || input.starts_with("\\\\")
+        || (bytes.len() >= 3
+            && bytes[0].is_ascii_alphabetic()
+            && bytes[1] == b':'
+            && matches!(bytes[2], b'\\' | b'/'))
+}
+
+/// Parses a repo URL, accepting a bare local path as its `file://` equivalent.
+fn parse_repo_input(repo_url: &str) -> Result<reqwest::Url, SyncError> {
+    let input = repo_url.trim();
+    if is_local_path(input) {
+        return reqwest::Url::from_file_path(input)
+            .map_err(|_| SyncError::InvalidUrl(format!("{repo_url}: not an absolute path")));
+    }
+    reqwest::Url::parse(input).map_err(|e| SyncError::InvalidUrl(format!("{repo_url}: {e}")))
+}
+
 /// Normalize a repository URL so it can be used as a base for repo.json and mod files.
 /// Supports inputs ending with or without `repo.json`.
 pub(crate) fn normalize_repo_base(repo_url: &str) -> Result<reqwest::Url, SyncError> {
-    let mut url = reqwest::Url::parse(repo_url)
-        .map_err(|e| SyncError::Remote(format!("invalid repo url {repo_url}: {e}")))?;
+    let mut url = parse_repo_input(repo_url)?;
+    if !matches!(url.scheme(), "http" | "https" | "file") {
+        return Err(SyncError::InvalidUrl(format!(
+            "{repo_url}: URL must start with http:// or https:// (or file:// for a local mirror)"
+        )));
+    }
+    if url.scheme() != "file" && url.host_str().is_none_or(str::is_empty) {
+        return Err(SyncError::InvalidUrl(format!("{repo_url}: missing host")));
+    }
 
     if let Some(last) = url
         .path_segments()
         .and_then(|mut s| s.next_back().map(|p| p.to_string()))
     {
-        if last == "repo.json" {
+        if last.eq_ignore_ascii_case("repo.json") {
             url.path_segments_mut()
-                .map_err(|_| SyncError::Remote("invalid repo url".into()))?
+                .map_err(|_| SyncError::InvalidUrl(repo_url.to_string()))?
                 .pop();
         }
     }
@@ -65,8 +158,7 @@ impl HttpRemoteStateProvider {
 
     async fn manifest_url(&self, repo_url: &str) -> Result<reqwest::Url, SyncError> {
         // If caller already provided repo.json, honor it. Otherwise append it.
-        let parsed = reqwest::Url::parse(repo_url)
-            .map_err(|e| SyncError::Remote(format!("invalid repo url {repo_url}: {e}")))?;
+        let parsed = parse_repo_input(repo_url)?;
 
         if parsed
             .path_segments()
@@ -81,31 +173,79 @@ impl HttpRemoteStateProvider {
             .map_err(|e| SyncError::Remote(format!("bad repo.json url from {base}: {e}")))
     }
 
+    /// GET `url`, sending any cached validators. Returns `None` on `304 Not Modified`.
+    async fn get_conditional(
+        &self,
+        url: reqwest::Url,
+        cached: &CacheValidators,
+    ) -> Result<Option<reqwest::Response>, reqwest::Error> {
+        let mut request = self.client.get(url);
+        if let Some(etag) = &cached.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &cached.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+        let resp = request.send().await?;
+        if resp.status() == StatusCode::NOT_MODIFIED {
+            return Ok(None);
+        }
+        Ok(Some(resp))
+    }
+
+    /// Reads a `file://` resource. Local files carry no validators, so they are always
+    /// re-read; metadata is small enough that this costs next to nothing.
+    async fn read_local(url: &reqwest::Url) -> Result<Vec<u8>, String> {
+        let path = url
+            .to_file_path()
+            .map_err(|_| format!("{url} is not a local path"))?;
+        tokio::fs::read(&path)
+            .await
+            .map_err(|e| format!("{}: {e}", path.display()))
+    }
+
     async fn fetch_repo_json_internal(
         &self,
         repo_url: &str,
-    ) -> Result<RepositoryExternal, SyncError> {
+        cached: &CacheValidators,
+    ) -> Result<Conditional<RepositoryExternal>, SyncError> {
         let manifest_url = self.manifest_url(repo_url).await?;
 
-        let bytes = self
-            .client
-            .get(manifest_url)
-            .send()
-            .await
-            .map_err(|e| SyncError::Remote(format!("repo.json request failed: {e}")))?
-            .bytes()
-            .await
-            .map_err(|e| SyncError::Remote(format!("repo.json bytes failed: {e}")))?;
+        let (bytes, validators) = if manifest_url.scheme() == "file" {
+            let bytes = Self::read_local(&manifest_url)
+                .await
+                .map_err(|e| SyncError::Remote(format!("repo.json read failed: {e}")))?;
+            (bytes, CacheValidators::default())
+        } else {
+            let Some(resp) = self
+                .get_conditional(manifest_url, cached)
+                .await
+                .map_err(|e| SyncError::Remote(format!("repo.json request failed: {e}")))?
+            else {
+                return Ok(Conditional::NotModified);
+            };
+            if resp.status() == StatusCode::NOT_FOUND {
+                return Err(SyncError::NotFound("repo.json".into()));
+            }
+            let validators = CacheValidators::from_headers(resp.headers());
+            let bytes = resp
+                .bytes()
+                .await
+                .map_err(|e| SyncError::Remote(format!("repo.json bytes failed: {e}")))?;
+            (bytes.into(), validators)
+        };
 
-        serde_json::from_slice(&bytes)
-            .map_err(|e| SyncError::Remote(format!("repo.json parse failed: {e}")))
+        let value = serde_json::from_slice(&bytes)
+            .map_err(|e| SyncError::Remote(format!("repo.json parse failed: {e}")))?;
+        Ok(Conditional::Fetched { value, validators })
     }
 
     async fn fetch_mod_srf_internal(
         &self,
         base: &reqwest::Url,
         mod_name: &str,
-    ) -> Result<fleet_core::Mod, SyncError> {
+        cached: &CacheValidators,
+    ) -> Result<Conditional<fleet_core::Mod>, SyncError> {
         let mut url = base.clone();
         url.path_segments_mut()
             .map_err(|_| SyncError::Remote("invalid base url".into()))?
@@ -118,15 +258,34 @@ impl HttpRemoteStateProvider {
             segs.push("mod.srf");
         }
 
-        let bytes = self
-            .client
-            .get(url.clone())
-            .send()
-            .await
-            .map_err(|e| SyncError::Remote(format!("srf request for {mod_name} failed: {e}")))?
-            .bytes()
-            .await
-            .map_err(|e| SyncError::Remote(format!("srf bytes for {mod_name} failed: {e}")))?;
+        let (bytes, validators) = if url.scheme() == "file" {
+            let bytes = Self::read_local(&url)
+                .await
+                .map_err(|e| SyncError::Remote(format!("srf read for {mod_name} failed: {e}")))?;
+            (bytes, CacheValidators::default())
+        } else {
+            let Some(resp) = self.get_conditional(url, cached).await.map_err(|e| {
+                SyncError::HostFailure(format!("srf request for {mod_name} failed: {e}"))
+            })?
+            else {
+                return Ok(Conditional::NotModified);
+            };
+            if resp.status() == StatusCode::NOT_FOUND {
+                return Err(SyncError::NotFound(format!("{mod_name}/mod.srf")));
+            }
+            if resp.status().is_server_error() {
+                return Err(SyncError::HostFailure(format!(
+                    "srf request for {mod_name} failed: HTTP {}",
+                    resp.status()
+                )));
+            }
+            let validators = CacheValidators::from_headers(resp.headers());
+            let bytes = resp
+                .bytes()
+                .await
+                .map_err(|e| SyncError::Remote(format!("srf bytes for {mod_name} failed: {e}")))?;
+            (bytes.into(), validators)
+        };
 
         let mut mod_data = fleet_core::formats::parse_srf(&bytes)
             .map_err(|e| SyncError::Remote(format!("srf parse for {mod_name} failed: {e}")))?;
@@ -141,30 +300,30 @@ impl HttpRemoteStateProvider {
             }
         }
 
-        Ok(mod_data)
+        Ok(Conditional::Fetched {
+            value: mod_data,
+            validators,
+        })
     }
 }
 
-#[async_trait::async_trait]
-impl RemoteStateProvider for HttpRemoteStateProvider {
-    async fn head_repo_json_mtime(&self, repo_url: &str) -> Result<Option<String>, SyncError> {
-        let manifest_url = self.manifest_url(repo_url).await?;
-        let resp = self
-            .client
-            .head(manifest_url)
-            .send()
-            .await
-            .map_err(|e| SyncError::Remote(format!("repo.json HEAD failed: {e}")))?;
-        let last_modified = resp
-            .headers()
-            .get("Last-Modified")
-            .and_then(|h| h.to_str().ok())
-            .map(|s| s.to_string());
-        Ok(last_modified)
+/// Unwraps an unconditional fetch; without validators the server has nothing to compare.
+fn expect_fetched<T>(res: Conditional<T>, what: &str) -> Result<T, SyncError> {
+    match res {
+        Conditional::Fetched { value, .. } => Ok(value),
+        Conditional::NotModified => Err(SyncError::Remote(format!(
+            "{what}: unexpected 304 for unconditional request"
+        ))),
     }
+}
 
+#[async_trait::async_trait]
+impl RemoteStateProvider for HttpRemoteStateProvider {
     async fn fetch_repo_json(&self, repo_url: &str) -> Result<RepositoryExternal, SyncError> {
-        self.fetch_repo_json_internal(repo_url).await
+        let res = self
+            .fetch_repo_json_internal(repo_url, &CacheValidators::default())
+            .await?;
+        expect_fetched(res, "repo.json")
     }
 
     async fn fetch_mod_srf(
@@ -172,11 +331,31 @@ impl RemoteStateProvider for HttpRemoteStateProvider {
         base: &reqwest::Url,
         mod_name: &str,
     ) -> Result<fleet_core::Mod, SyncError> {
-        self.fetch_mod_srf_internal(base, mod_name).await
+        let res = self
+            .fetch_mod_srf_internal(base, mod_name, &CacheValidators::default())
+            .await?;
+        expect_fetched(res, mod_name)
+    }
+
+    async fn fetch_repo_json_conditional(
+        &self,
+        repo_url: &str,
+        cached: &CacheValidators,
+    ) -> Result<Conditional<RepositoryExternal>, SyncError> {
+        self.fetch_repo_json_internal(repo_url, cached).await
+    }
+
+    async fn fetch_mod_srf_conditional(
+        &self,
+        base: &reqwest::Url,
+        mod_name: &str,
+        cached: &CacheValidators,
+    ) -> Result<Conditional<fleet_core::Mod>, SyncError> {
+        self.fetch_mod_srf_internal(base, mod_name, cached).await
     }
 
     async fn fetch_remote(&self, repo_url: &str) -> Result<RemoteState, SyncError> {
-        let repo_external = self.fetch_repo_json_internal(repo_url).await?;
+        let repo_external = self.fetch_repo_json(repo_url).await?;
         let repository: Repository = repo_external.clone().into();
 
         let base = normalize_repo_base(repo_url)?;
@@ -186,9 +365,9 @@ impl RemoteStateProvider for HttpRemoteStateProvider {
             .map(|rmod| {
                 let base = base.clone();
                 let this = &*self;
-                async move { this.fetch_mod_srf_internal(&base, &rmod.mod_name).await }
+                async move { this.fetch_mod_srf(&base, &rmod.mod_name).await }
             })
-            .buffer_unordered(20);
+            .buffer_unordered(DEFAULT_METADATA_CONCURRENCY);
 
         let results: Vec<Result<fleet_core::Mod, SyncError>> = fetch_stream.collect().await;
 
@@ -197,11 +376,64 @@ impl RemoteStateProvider for HttpRemoteStateProvider {
             mods.push(res?);
         }
 
-        let manifest = Manifest {
-            version: "1.0".to_string(),
-            mods,
-        };
+        let manifest = Manifest::new(mods);
 
         Ok(RemoteState { manifest })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repo_urls_are_normalized_to_a_directory_base() {
+        let cases = [
+            ("example.com/repo", "https://example.com/repo/"),
+            ("http://example.com/repo/", "http://example.com/repo/"),
+            (
+                "https://example.com/repo/repo.json",
+                "https://example.com/repo/",
+            ),
+            ("  https://example.com  ", "https://example.com/"),
+            ("file:///srv/mirror/repo.json", "file:///srv/mirror/"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(
+                normalize_repo_url(input).unwrap(),
+                expected,
+                "input: {input}"
+            );
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn bare_local_paths_become_file_urls() {
+        assert_eq!(
+            normalize_repo_url("/srv/mirror").unwrap(),
+            "file:///srv/mirror/"
+        );
+        assert_eq!(
+            normalize_repo_url("/srv/mirror/repo.json").unwrap(),
+            "file:///srv/mirror/"
+        );
+    }
+
+    #[test]
+    fn unsupported_or_malformed_repo_urls_are_rejected() {
+        for input in [
+            "",
+            "ftp://example.com/repo",
+            "https://",
+            "http://exa mple.com",
+        ] {
+            match normalize_repo_url(input) {
+                Err(SyncError::InvalidUrl(_)) => {}
+                other => panic!("expected InvalidUrl for {input:?}, got {other:?}"),
+            }
+        }
+        let err = normalize_repo_url("ftp://example.com/repo").unwrap_err();
+        assert!(err.to_string().contains("http:// or https://"));
+    }
+}
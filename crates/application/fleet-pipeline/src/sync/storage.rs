@@ -1,13 +1,43 @@
 use camino::Utf8Path;
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::sync::remote::CacheValidators;
 
 pub use fleet_persistence::{LocalFileSummary, LocalManifestSummary};
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
 pub struct RepoSummary {
     pub last_modified: Option<String>,
+    #[serde(default)]
+    pub etag: Option<String>,
     pub repo_json: String,
+    /// Last fetched `mod.srf` per mod, revalidated with a conditional GET.
+    #[serde(default)]
+    pub mod_srfs: HashMap<String, CachedModSrf>,
+    /// Mods whose `mod.srf` answered 404, with when (Unix seconds). They are not requested
+    /// again for [`MISSING_SRF_TTL`] unless repo.json changes.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub missing_srfs: HashMap<String, u64>,
+}
+
+/// How long a `mod.srf` 404 is remembered before the file is requested again.
+pub const MISSING_SRF_TTL: std::time::Duration = std::time::Duration::from_secs(15 * 60);
+
+impl RepoSummary {
+    pub fn validators(&self) -> CacheValidators {
+        CacheValidators {
+            etag: self.etag.clone(),
+            last_modified: self.last_modified.clone(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CachedModSrf {
+    pub validators: CacheValidators,
+    pub srf: fleet_core::Mod,
 }
 
 pub trait RepoSummaryStore: Send + Sync {
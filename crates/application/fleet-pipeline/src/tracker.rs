@@ -1,7 +1,14 @@
 use fleet_core::SyncPlan;
 use fleet_infra::net::DownloadEvent;
-use std::collections::{HashMap, VecDeque};
-use std::time::Instant;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Window for the smoothed speed; long enough to keep the ETA steady.
+const SPEED_WINDOW: Duration = Duration::from_secs(5);
+/// Window for the instantaneous speed.
+const INSTANT_WINDOW: Duration = Duration::from_secs(1);
+/// Transfers shorter than this are dominated by latency and give no useful average.
+const MIN_AVERAGE_SPAN: Duration = Duration::from_secs(1);
 
 #[derive(Debug, Clone)]
 pub struct ActiveDownload {
@@ -11,6 +18,19 @@ pub struct ActiveDownload {
     pub rel_path: String,
     pub bytes_downloaded: u64,
     pub total_bytes: u64,
+    /// Fully downloaded and waiting on its checksum.
+    pub verifying: bool,
+}
+
+/// Download progress for the files of one mod.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModProgress {
+    pub mod_name: String,
+    pub total_files: u64,
+    pub downloaded_files: u64,
+    pub failed_files: u64,
+    pub total_bytes: u64,
+    pub downloaded_bytes: u64,
 }
 
 #[derive(Debug, Clone)]
@@ -19,115 +39,466 @@ pub struct TransferSnapshot {
     pub downloaded_files: u64,
     pub total_bytes: u64,
     pub downloaded_bytes: u64,
+    /// Moving average over the last few seconds; use this for ETAs.
     pub speed_bps: u64,
+    /// Rate over roughly the last second; reacts quickly but jitters.
+    pub instant_speed_bps: u64,
     pub failed_count: u64,
     pub in_flight: Vec<ActiveDownload>,
+    /// One entry per mod in the plan, in plan order.
+    pub per_mod: Vec<ModProgress>,
 }
 
 pub struct ProgressTracker {
-    id_map: HashMap<u64, (String, String)>, // ID -> (ModName, RelPath)
+    id_mod: HashMap<u64, usize>, // ID -> index into per_mod
+    per_mod: Vec<ModProgress>,
     in_flight: HashMap<u64, ActiveDownload>,
     downloaded_files: u64,
     failed_count: u64,
     current_downloaded_bytes: u64,
     total_files: u64,
     total_bytes: u64,
-    last_tick: Instant,
-    bytes_since_last_tick: u64,
-    speed_bps: u64,
-    history: VecDeque<u64>,
+    started: Instant,
+    samples: VecDeque<(Instant, u64)>, // (time, bytes) of recent progress events
+    /// Transfers copied from disk, left out of [`Self::average_speed`].
+    local_ids: HashSet<u64>,
+    /// Bytes of network transfers that were kept.
+    network_bytes: u64,
+    /// First network transfer started / last one finished.
+    network_started: Option<Instant>,
+    network_finished: Option<Instant>,
 }
 
 impl ProgressTracker {
     pub fn new(plan: &SyncPlan) -> Self {
-        let mut id_map = HashMap::new();
+        let mut id_mod = HashMap::new();
+        let mut per_mod: Vec<ModProgress> = Vec::new();
+        let mut mod_index = HashMap::new();
         let mut total_bytes = 0;
 
         for (idx, action) in plan.downloads.iter().enumerate() {
             let id = idx as u64;
-            id_map.insert(id, (action.mod_name.clone(), action.rel_path.clone()));
             total_bytes += action.size;
+
+            let mod_ix = *mod_index
+                .entry(action.mod_name.as_str())
+                .or_insert_with(|| {
+                    per_mod.push(ModProgress {
+                        mod_name: action.mod_name.clone(),
+                        ..Default::default()
+                    });
+                    per_mod.len() - 1
+                });
+            per_mod[mod_ix].total_files += 1;
+            per_mod[mod_ix].total_bytes += action.size;
+            id_mod.insert(id, mod_ix);
         }
 
         Self {
-            id_map,
+            id_mod,
+            per_mod,
             in_flight: HashMap::new(),
             downloaded_files: 0,
             failed_count: 0,
             current_downloaded_bytes: 0,
             total_files: plan.downloads.len() as u64,
             total_bytes,
-            last_tick: Instant::now(),
-            bytes_since_last_tick: 0,
-            speed_bps: 0,
-            history: VecDeque::new(),
+            started: Instant::now(),
+            samples: VecDeque::new(),
+            local_ids: HashSet::new(),
+            network_bytes: 0,
+            network_started: None,
+            network_finished: None,
         }
     }
 
     pub fn update(&mut self, event: DownloadEvent) {
+        self.update_at(event, Instant::now());
+    }
+
+    fn update_at(&mut self, event: DownloadEvent, now: Instant) {
         match event {
-            DownloadEvent::Started { id, total_bytes } => {
-                if let Some((mod_name, rel_path)) = self.id_map.get(&id) {
-                    let file_name = std::path::Path::new(rel_path)
-                        .file_name()
-                        .unwrap_or_default()
-                        .to_string_lossy()
-                        .to_string();
-                    self.in_flight.insert(
-                        id,
-                        ActiveDownload {
-                            id,
-                            mod_name: mod_name.clone(),
-                            file_name,
-                            rel_path: rel_path.clone(),
-                            bytes_downloaded: 0,
-                            total_bytes,
-                        },
-                    );
+            DownloadEvent::Started {
+                id,
+                mod_name,
+                rel_path,
+                total_bytes,
+                local,
+            } => {
+                if local {
+                    self.local_ids.insert(id);
+                } else {
+                    self.network_started.get_or_insert(now);
                 }
+                // The event names its file, so per-mod totals follow it even if ids and plan
+                // order ever diverge.
+                if let Some(ix) = self.per_mod.iter().position(|m| m.mod_name == mod_name) {
+                    self.id_mod.insert(id, ix);
+                }
+                let file_name = std::path::Path::new(&rel_path)
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string();
+                self.in_flight.insert(
+                    id,
+                    ActiveDownload {
+                        id,
+                        mod_name,
+                        file_name,
+                        rel_path,
+                        bytes_downloaded: 0,
+                        total_bytes,
+                        verifying: false,
+                    },
+                );
             }
             DownloadEvent::Progress { id, bytes_delta } => {
-                self.bytes_since_last_tick += bytes_delta;
+                self.samples.push_back((now, bytes_delta));
+                self.prune_samples(now);
                 self.current_downloaded_bytes += bytes_delta;
+                if !self.local_ids.contains(&id) {
+                    self.network_bytes += bytes_delta;
+                }
                 if let Some(entry) = self.in_flight.get_mut(&id) {
                     entry.bytes_downloaded += bytes_delta;
                 }
+                if let Some(m) = self.mod_progress_mut(id) {
+                    m.downloaded_bytes += bytes_delta;
+                }
+            }
+            DownloadEvent::Verifying { id } => {
+                if let Some(entry) = self.in_flight.get_mut(&id) {
+                    entry.verifying = true;
+                }
+            }
+            DownloadEvent::Discarded { id, bytes } => {
+                self.current_downloaded_bytes = self.current_downloaded_bytes.saturating_sub(bytes);
+                if !self.local_ids.contains(&id) {
+                    self.network_bytes = self.network_bytes.saturating_sub(bytes);
+                }
+                if let Some(entry) = self.in_flight.get_mut(&id) {
+                    entry.bytes_downloaded = entry.bytes_downloaded.saturating_sub(bytes);
+                }
+                if let Some(m) = self.mod_progress_mut(id) {
+                    m.downloaded_bytes = m.downloaded_bytes.saturating_sub(bytes);
+                }
             }
             DownloadEvent::Completed { id, success } => {
                 self.in_flight.remove(&id);
+                if success && !self.local_ids.contains(&id) {
+                    self.network_finished = Some(now);
+                }
                 if success {
                     self.downloaded_files += 1;
                 } else {
                     self.failed_count += 1;
                 }
+                if let Some(m) = self.mod_progress_mut(id) {
+                    if success {
+                        m.downloaded_files += 1;
+                    } else {
+                        m.failed_files += 1;
+                    }
+                }
             }
         }
     }
 
+    fn mod_progress_mut(&mut self, id: u64) -> Option<&mut ModProgress> {
+        let ix = *self.id_mod.get(&id)?;
+        self.per_mod.get_mut(ix)
+    }
+
     pub fn get_snapshot(&mut self) -> TransferSnapshot {
-        let now = Instant::now();
-        let elapsed = now.duration_since(self.last_tick).as_secs_f64();
-
-        if elapsed >= 0.5 {
-            let current_bps = (self.bytes_since_last_tick as f64 / elapsed) as u64;
-            self.history.push_back(current_bps);
-            if self.history.len() > 5 {
-                self.history.pop_front();
+        self.snapshot_at(Instant::now())
+    }
+
+    fn prune_samples(&mut self, now: Instant) {
+        while let Some(&(at, _)) = self.samples.front() {
+            if now.saturating_duration_since(at) <= SPEED_WINDOW {
+                break;
             }
-            self.speed_bps =
-                (self.history.iter().sum::<u64>() as f64 / self.history.len() as f64) as u64;
-            self.last_tick = now;
-            self.bytes_since_last_tick = 0;
+            self.samples.pop_front();
+        }
+    }
+
+    /// Average rate over the last `window`, or since the transfer started if that is shorter.
+    fn rate_over(&self, now: Instant, window: Duration) -> u64 {
+        let bytes: u64 = self
+            .samples
+            .iter()
+            .rev()
+            .take_while(|(at, _)| now.saturating_duration_since(*at) <= window)
+            .map(|(_, b)| b)
+            .sum();
+        let span = now
+            .saturating_duration_since(self.started)
+            .min(window)
+            .max(Duration::from_millis(500));
+        (bytes as f64 / span.as_secs_f64()) as u64
+    }
+
+    /// Average network download rate, from the first download starting to the last one
+    /// finishing; copies from disk (`file://` mirrors, the local source, duplicates) are left
+    /// out. `None` when nothing was downloaded or the transfer was too short to tell.
+    pub fn average_speed(&self) -> Option<u64> {
+        let span = self
+            .network_finished?
+            .saturating_duration_since(self.network_started?);
+        if self.network_bytes == 0 || span < MIN_AVERAGE_SPAN {
+            return None;
         }
+        Some((self.network_bytes as f64 / span.as_secs_f64()) as u64)
+    }
+
+    fn snapshot_at(&mut self, now: Instant) -> TransferSnapshot {
+        self.prune_samples(now);
 
         TransferSnapshot {
             total_files: self.total_files,
             downloaded_files: self.downloaded_files,
             total_bytes: self.total_bytes,
             downloaded_bytes: self.current_downloaded_bytes,
-            speed_bps: self.speed_bps,
+            speed_bps: self.rate_over(now, SPEED_WINDOW),
+            instant_speed_bps: self.rate_over(now, INSTANT_WINDOW),
             failed_count: self.failed_count,
             in_flight: self.in_flight.values().cloned().collect(),
+            per_mod: self.per_mod.clone(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fleet_core::DownloadAction;
+
+    fn action(mod_name: &str, rel_path: &str, size: u64) -> DownloadAction {
+        DownloadAction {
+            mod_name: mod_name.into(),
+            rel_path: rel_path.into(),
+            size,
+            expected_checksum: String::new(),
+            mtime: None,
+            parts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn snapshot_breaks_progress_down_per_mod() {
+        let plan = SyncPlan {
+            renames: vec![],
+            checks: vec![],
+            deletes: vec![],
+            downloads: vec![
+                action("@b", "one.pbo", 10),
+                action("@a", "two.pbo", 20),
+                action("@b", "three.pbo", 30),
+            ],
+        };
+        let mut tracker = ProgressTracker::new(&plan);
+
+        tracker.update(DownloadEvent::Started {
+            id: 0,
+            mod_name: "@b".into(),
+            rel_path: "one.pbo".into(),
+            total_bytes: 10,
+            local: false,
+        });
+        tracker.update(DownloadEvent::Progress {
+            id: 0,
+            bytes_delta: 10,
+        });
+        tracker.update(DownloadEvent::Completed {
+            id: 0,
+            success: true,
+        });
+        tracker.update(DownloadEvent::Completed {
+            id: 1,
+            success: false,
+        });
+        tracker.update(DownloadEvent::Progress {
+            id: 2,
+            bytes_delta: 5,
+        });
+
+        let snapshot = tracker.get_snapshot();
+        let names: Vec<_> = snapshot
+            .per_mod
+            .iter()
+            .map(|m| m.mod_name.as_str())
+            .collect();
+        assert_eq!(names, ["@b", "@a"]);
+
+        let b = &snapshot.per_mod[0];
+        assert_eq!((b.total_files, b.downloaded_files), (2, 1));
+        assert_eq!((b.total_bytes, b.downloaded_bytes), (40, 15));
+        let a = &snapshot.per_mod[1];
+        assert_eq!(
+            (a.total_files, a.failed_files, a.downloaded_bytes),
+            (1, 1, 0)
+        );
+    }
+
+    #[test]
+    fn smoothed_speed_decays_after_a_burst() {
+        let plan = SyncPlan {
+            renames: vec![],
+            checks: vec![],
+            deletes: vec![],
+            downloads: vec![action("@a", "big.pbo", 100_000_000)],
+        };
+        let mut tracker = ProgressTracker::new(&plan);
+        let t0 = tracker.started;
+
+        // One second of 1 MB every 100 ms, then silence.
+        for i in 1..=10 {
+            tracker.update_at(
+                DownloadEvent::Progress {
+                    id: 0,
+                    bytes_delta: 1_000_000,
+                },
+                t0 + Duration::from_millis(100 * i),
+            );
+        }
+        let burst = tracker.snapshot_at(t0 + Duration::from_secs(1));
+        let idle_2s = tracker.snapshot_at(t0 + Duration::from_secs(3));
+        let idle_3s = tracker.snapshot_at(t0 + Duration::from_secs(4));
+
+        assert!(burst.speed_bps >= 9_000_000, "got {}", burst.speed_bps);
+        assert_eq!(idle_2s.instant_speed_bps, 0);
+        assert!(idle_2s.speed_bps > 0, "smoothed rate snapped to zero");
+        assert!(idle_3s.speed_bps < idle_2s.speed_bps);
+        assert!(idle_2s.speed_bps < burst.speed_bps);
+    }
+
+    #[test]
+    fn average_speed_spans_network_transfers_only() {
+        let plan = SyncPlan {
+            renames: vec![],
+            checks: vec![],
+            deletes: vec![],
+            downloads: vec![
+                action("@a", "big.pbo", 8_000_000),
+                action("@a", "copy.pbo", 50_000_000),
+            ],
+        };
+        let mut tracker = ProgressTracker::new(&plan);
+        let t0 = tracker.started;
+        let started = |id, local| DownloadEvent::Started {
+            id,
+            mod_name: "@a".into(),
+            rel_path: format!("{id}.pbo"),
+            total_bytes: 0,
+            local,
+        };
+
+        // Planning time before the first download does not count, nor does the local copy.
+        let at = |secs| t0 + Duration::from_secs(secs);
+        tracker.update_at(started(0, false), at(10));
+        tracker.update_at(started(1, true), at(10));
+        tracker.update_at(
+            DownloadEvent::Progress {
+                id: 1,
+                bytes_delta: 50_000_000,
+            },
+            at(10),
+        );
+        tracker.update_at(
+            DownloadEvent::Completed {
+                id: 1,
+                success: true,
+            },
+            at(10),
+        );
+        assert_eq!(tracker.average_speed(), None);
+
+        tracker.update_at(
+            DownloadEvent::Progress {
+                id: 0,
+                bytes_delta: 8_000_000,
+            },
+            at(13),
+        );
+        tracker.update_at(
+            DownloadEvent::Completed {
+                id: 0,
+                success: true,
+            },
+            at(14),
+        );
+        assert_eq!(tracker.average_speed(), Some(2_000_000));
+    }
+
+    #[test]
+    fn active_files_are_named_by_the_started_event() {
+        let plan = SyncPlan {
+            renames: vec![],
+            checks: vec![],
+            deletes: vec![],
+            downloads: vec![action("@a", "one.pbo", 10), action("@b", "two.pbo", 20)],
+        };
+        let mut tracker = ProgressTracker::new(&plan);
+
+        // Id 0 is planned as @a, but the downloader reports it as @b's file.
+        tracker.update(DownloadEvent::Started {
+            id: 0,
+            mod_name: "@b".into(),
+            rel_path: "addons/two.pbo".into(),
+            total_bytes: 20,
+            local: false,
+        });
+        tracker.update(DownloadEvent::Progress {
+            id: 0,
+            bytes_delta: 5,
+        });
+
+        let snapshot = tracker.get_snapshot();
+        let active = &snapshot.in_flight[0];
+        assert_eq!(
+            (active.mod_name.as_str(), active.rel_path.as_str()),
+            ("@b", "addons/two.pbo")
+        );
+        assert_eq!(active.file_name, "two.pbo");
+        assert_eq!(snapshot.per_mod[0].downloaded_bytes, 0);
+        assert_eq!(snapshot.per_mod[1].downloaded_bytes, 5);
+    }
+
+    #[test]
+    fn verifying_files_stay_in_flight_until_completed() {
+        let plan = SyncPlan {
+            renames: vec![],
+            checks: vec![],
+            deletes: vec![],
+            downloads: vec![action("@a", "big.pbo", 10)],
+        };
+        let mut tracker = ProgressTracker::new(&plan);
+
+        tracker.update(DownloadEvent::Started {
+            id: 0,
+            mod_name: "@a".into(),
+            rel_path: "big.pbo".into(),
+            total_bytes: 10,
+            local: false,
+        });
+        tracker.update(DownloadEvent::Progress {
+            id: 0,
+            bytes_delta: 10,
+        });
+        tracker.update(DownloadEvent::Verifying { id: 0 });
+
+        let snapshot = tracker.get_snapshot();
+        assert_eq!(snapshot.downloaded_files, 0);
+        assert_eq!(snapshot.in_flight.len(), 1);
+        assert!(snapshot.in_flight[0].verifying);
+
+        tracker.update(DownloadEvent::Completed {
+            id: 0,
+            success: true,
+        });
+        let snapshot = tracker.get_snapshot();
+        assert_eq!(snapshot.downloaded_files, 1);
+        assert!(snapshot.in_flight.is_empty());
+    }
+}
@@ -0,0 +1,53 @@
+use axum::{routing::get, Router};
+use fleet_core::diff::FileStatus;
+use fleet_pipeline::sync::{default_engine, SyncMode, SyncOptions, SyncRequest};
+use tempfile::tempdir;
+
+const FILE_CHECKSUM: &str = "F872A18EB88181EB00816510E762FEE6";
+const PART_CHECKSUM: &str = "5D41402ABC4B2A76B9719D911017C592";
+
+#[tokio::test]
+async fn audit_reports_drift_without_touching_the_folder() {
+    let repo_json = r#"{"repoName":"r","checksum":"","requiredMods":[{"modName":"@m","checksum":"AAA","enabled":true}],"optionalMods":[]}"#;
+    let srf = format!(
+        r#"{{"Name":"@m","Checksum":"AAA","Files":[{{"Path":"data.txt","Length":5,"Checksum":"{FILE_CHECKSUM}","Type":"SwiftyFile","Parts":[{{"Path":"data.txt_5","Length":5,"Start":0,"Checksum":"{PART_CHECKSUM}"}}]}}]}}"#
+    );
+    let app = Router::new()
+        .route("/repo.json", get(move || async move { repo_json }))
+        .route("/@m/mod.srf", get(move || async move { srf }))
+        .route("/@m/data.txt", get(|| async { b"hello".to_vec() }));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    std::fs::create_dir_all(root.join("@m")).unwrap();
+    std::fs::write(root.join("@m/data.txt"), b"HELLO").unwrap();
+    std::fs::write(root.join("@m/extra.txt"), b"mine").unwrap();
+
+    let engine = default_engine(reqwest::Client::new());
+    let req = SyncRequest {
+        repo_url: format!("http://{addr}"),
+        extra_repo_urls: vec![],
+        local_root: root.clone(),
+        mode: SyncMode::SmartVerify,
+        options: SyncOptions::default(),
+        profile_id: None,
+    };
+    let (plan, report) = engine.audit(&req).await.unwrap();
+    assert_eq!(plan.downloads.len(), 1);
+    assert_eq!(plan.deletes.len(), 1);
+    assert_eq!(report.count(FileStatus::Changed), 1);
+
+    assert_eq!(std::fs::read(root.join("@m/data.txt")).unwrap(), b"HELLO");
+    assert!(root.join("@m/extra.txt").exists());
+    assert!(
+        !root.join("fleet.redb").exists(),
+        "audit created fleet.redb"
+    );
+
+    server.abort();
+}
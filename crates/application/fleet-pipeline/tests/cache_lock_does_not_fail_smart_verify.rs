@@ -22,7 +22,7 @@ async fn smart_verify_does_not_fail_when_cache_db_is_locked() {
 
     let provider = DefaultLocalStateProvider::new(std::sync::Arc::new(RedbFleetDataStore));
     let state = provider
-        .local_state(&root, SyncMode::SmartVerify, None)
+        .local_state(&root, SyncMode::SmartVerify, None, false, None, None)
         .await
         .unwrap();
 
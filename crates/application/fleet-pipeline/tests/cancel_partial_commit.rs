@@ -0,0 +1,181 @@
+use axum::body::Body;
+use axum::{routing::get, Router};
+use fleet_persistence::{FleetDataStore, RedbFleetDataStore};
+use fleet_pipeline::sync::{default_engine, SyncError, SyncMode, SyncOptions, SyncRequest};
+use futures::{stream, StreamExt};
+use std::time::Duration;
+use tempfile::tempdir;
+use tokio_util::sync::CancellationToken;
+
+const HELLO_PART: &str = "5D41402ABC4B2A76B9719D911017C592";
+const HELLO_FILE: &str = "F872A18EB88181EB00816510E762FEE6";
+const WORLD_PART: &str = "7D793037A0760186574B0282F2F435E7";
+const WORLD_FILE: &str = "1373DF2F299DE0030C8449FE9D013BB8";
+
+fn srf_file(path: &str, file: &str, part: &str) -> String {
+    format!(
+        r#"{{"Path":"{path}","Length":5,"Checksum":"{file}","Type":"SwiftyFile","Parts":[{{"Path":"{path}_5","Length":5,"Start":0,"Checksum":"{part}"}}]}}"#
+    )
+}
+
+async fn start_server() -> String {
+    let repo = r#"{"repoName":"r","checksum":"","requiredMods":[{"modName":"@m","checksum":"MOD","enabled":true}],"optionalMods":[]}"#;
+    let srf = format!(
+        r#"{{"Name":"@m","Checksum":"MOD","Files":[{},{}]}}"#,
+        srf_file("fast.bin", HELLO_FILE, HELLO_PART),
+        srf_file("slow.bin", WORLD_FILE, WORLD_PART)
+    );
+    let app = Router::new()
+        .route("/repo.json", get(move || async move { repo }))
+        .route("/@m/mod.srf", get(move || async move { srf }))
+        .route("/@m/fast.bin", get(|| async { "hello" }))
+        .route(
+            "/@m/slow.bin",
+            get(|| async {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+                "world"
+            }),
+        );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{addr}")
+}
+
+/// Serves `@m/stuck.bin` as a response that sends its first bytes and then hangs.
+async fn start_stuck_server() -> String {
+    let repo = r#"{"repoName":"r","checksum":"","requiredMods":[{"modName":"@m","checksum":"MOD","enabled":true}],"optionalMods":[]}"#;
+    let srf = format!(
+        r#"{{"Name":"@m","Checksum":"MOD","Files":[{}]}}"#,
+        srf_file("stuck.bin", WORLD_FILE, WORLD_PART)
+    );
+    let app = Router::new()
+        .route("/repo.json", get(move || async move { repo }))
+        .route("/@m/mod.srf", get(move || async move { srf }))
+        .route(
+            "/@m/stuck.bin",
+            get(|| async {
+                let first = stream::once(async { Ok::<_, std::io::Error>("wo") });
+                Body::from_stream(first.chain(stream::pending()))
+            }),
+        );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{addr}")
+}
+
+fn part_files(dir: &std::path::Path) -> Vec<std::path::PathBuf> {
+    walkdir::WalkDir::new(dir)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "part"))
+        .map(|e| e.into_path())
+        .collect()
+}
+
+#[tokio::test]
+async fn cancelling_mid_download_removes_part_files() {
+    let base = start_stuck_server().await;
+    let staging = tempdir().unwrap();
+    for temp_dir in [None, Some(staging.path())] {
+        let dir = tempdir().unwrap();
+        let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+        let engine = default_engine(reqwest::Client::new());
+        let req = SyncRequest {
+            repo_url: base.clone(),
+            extra_repo_urls: vec![],
+            local_root: root.clone(),
+            mode: SyncMode::FullRehash,
+            options: SyncOptions {
+                temp_dir: temp_dir
+                    .map(|p| camino::Utf8PathBuf::from_path_buf(p.to_path_buf()).unwrap()),
+                ..SyncOptions::default()
+            },
+            profile_id: Some(format!("cancel-part-files-{}", std::process::id())),
+        };
+        let plan = engine.plan(&req).await.unwrap();
+
+        let token = CancellationToken::new();
+        let canceller = token.clone();
+        let watched = temp_dir.unwrap_or(dir.path()).to_path_buf();
+        tokio::spawn(async move {
+            // Cancel only once the download is demonstrably mid-transfer.
+            while part_files(&watched).is_empty() {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+            canceller.cancel();
+        });
+
+        let res = tokio::time::timeout(
+            Duration::from_secs(10),
+            engine.execute_with_plan_cancellable(&req, plan, None, &token),
+        )
+        .await
+        .expect("cancellation should stop the sync promptly");
+        assert!(matches!(res, Err(SyncError::Cancelled)), "{res:?}");
+
+        assert!(token.is_cancelled());
+        assert_eq!(part_files(dir.path()), Vec::<std::path::PathBuf>::new());
+        assert_eq!(part_files(staging.path()), Vec::<std::path::PathBuf>::new());
+        assert!(!root.join("@m/stuck.bin").exists());
+    }
+}
+
+#[tokio::test]
+async fn cancelled_sync_records_finished_downloads() {
+    let base = start_server().await;
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let engine = default_engine(reqwest::Client::new());
+    let req = SyncRequest {
+        repo_url: base,
+        extra_repo_urls: vec![],
+        local_root: root.clone(),
+        mode: SyncMode::FullRehash,
+        options: SyncOptions::default(),
+        profile_id: Some(format!("cancel-partial-commit-{}", std::process::id())),
+    };
+
+    // The plan comes from a separate check, as in the app; the sync only has cached srfs.
+    let plan = engine.plan(&req).await.unwrap();
+    assert_eq!(plan.downloads.len(), 2);
+
+    let token = CancellationToken::new();
+    let canceller = token.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        canceller.cancel();
+    });
+
+    let res = tokio::time::timeout(
+        Duration::from_secs(10),
+        engine.execute_with_plan_cancellable(&req, plan, None, &token),
+    )
+    .await
+    .expect("cancellation should stop the sync promptly");
+    assert!(matches!(res, Err(SyncError::Cancelled)), "{res:?}");
+
+    assert!(root.join("@m/fast.bin").exists());
+    assert!(!root.join("@m/slow.bin").exists());
+
+    let store = RedbFleetDataStore;
+    let summary = store.load_baseline_summary(&root).unwrap();
+    let files: Vec<_> = summary
+        .iter()
+        .filter(|s| s.mod_name == "@m")
+        .flat_map(|s| s.files.iter().map(|f| f.rel_path.as_str()))
+        .collect();
+    assert_eq!(files, vec!["fast.bin"]);
+
+    let manifest = store.load_baseline_manifest(&root).unwrap();
+    let m = manifest.mods.iter().find(|m| m.name == "@m").unwrap();
+    assert!(
+        m.checksum.is_empty(),
+        "a partial mod must not carry the repo checksum"
+    );
+}
@@ -0,0 +1,104 @@
+use axum::{routing::get, Router};
+use fleet_core::{DownloadAction, SyncPlan};
+use fleet_pipeline::sync::{default_engine, SyncMode, SyncOptions, SyncRequest};
+use tempfile::tempdir;
+
+const HELLO_PART: &str = "5D41402ABC4B2A76B9719D911017C592";
+const HELLO_FILE: &str = "F872A18EB88181EB00816510E762FEE6";
+
+fn mod_srf() -> String {
+    let file = |path: &str| {
+        format!(
+            r#"{{"Path":"{path}","Length":5,"Checksum":"{HELLO_FILE}","Type":"SwiftyFile","Parts":[{{"Path":"{path}_5","Length":5,"Start":0,"Checksum":"{HELLO_PART}"}}]}}"#
+        )
+    };
+    format!(
+        r#"{{"Name":"@m","Checksum":"MOD","Files":[{},{}]}}"#,
+        file("Addons/x.bin"),
+        file("addons/x.bin")
+    )
+}
+
+async fn start_server() -> String {
+    let repo = r#"{"repoName":"r","checksum":"","requiredMods":[{"modName":"@m","checksum":"MOD","enabled":true}],"optionalMods":[]}"#;
+    let app = Router::new()
+        .route("/repo.json", get(move || async move { repo }))
+        .route("/@m/mod.srf", get(|| async { mod_srf() }))
+        .route("/@m/Addons/x.bin", get(|| async { "hello" }))
+        .route("/@m/addons/x.bin", get(|| async { "hello" }));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{addr}")
+}
+
+fn request(repo_url: String, root: camino::Utf8PathBuf) -> SyncRequest {
+    SyncRequest {
+        repo_url,
+        extra_repo_urls: vec![],
+        local_root: root,
+        mode: SyncMode::FullRehash,
+        options: SyncOptions::default(),
+        profile_id: None,
+    }
+}
+
+#[tokio::test]
+async fn fetch_keeps_first_casing_and_reports_collision() {
+    let base = start_server().await;
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let engine = default_engine(reqwest::Client::new());
+
+    let fetch = engine
+        .fetch_remote_state(&request(base, root))
+        .await
+        .unwrap();
+
+    let files: Vec<_> = fetch.manifest.mods[0]
+        .files
+        .iter()
+        .map(|f| f.path.as_str())
+        .collect();
+    assert_eq!(files, vec!["Addons/x.bin"]);
+    assert_eq!(fetch.case_collisions.len(), 1);
+    assert_eq!(fetch.case_collisions[0].mod_name, "@m");
+    assert_eq!(fetch.case_collisions[0].kept, "Addons/x.bin");
+    assert_eq!(fetch.case_collisions[0].dropped, vec!["addons/x.bin"]);
+}
+
+#[tokio::test]
+async fn executor_downloads_case_colliding_targets_once() {
+    let base = start_server().await;
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let engine = default_engine(reqwest::Client::new());
+
+    let download = |rel_path: &str| DownloadAction {
+        mod_name: "@m".into(),
+        rel_path: rel_path.into(),
+        size: 5,
+        expected_checksum: HELLO_FILE.into(),
+        mtime: None,
+        parts: Vec::new(),
+    };
+    let plan = SyncPlan {
+        renames: vec![],
+        checks: vec![],
+        downloads: vec![download("Addons/x.bin"), download("addons/x.bin")],
+        deletes: vec![],
+    };
+
+    let result = engine
+        .execute_with_plan(&request(base, root.clone()), plan, None)
+        .await
+        .unwrap();
+
+    assert_eq!(result.stats.files_planned_download, 1);
+    assert_eq!(
+        std::fs::read(root.join("@m/Addons/x.bin")).unwrap(),
+        b"hello"
+    );
+}
@@ -0,0 +1,132 @@
+use axum::{routing::get, Router};
+use fleet_core::{DeleteAction, DownloadAction, SyncPlan};
+use fleet_pipeline::sync::{default_engine, SyncMode, SyncOptions, SyncRequest};
+use fleet_pipeline::{ChangeLog, ModChanges};
+use tempfile::tempdir;
+
+const HELLO_FILE: &str = "F872A18EB88181EB00816510E762FEE6";
+
+const HELLO_PART: &str = "5D41402ABC4B2A76B9719D911017C592";
+
+async fn start_server() -> String {
+    let repo = r#"{"repoName":"r","checksum":"","requiredMods":[{"modName":"@m","checksum":"MOD","enabled":true}],"optionalMods":[]}"#;
+    let file = |name: &str| {
+        format!(
+            r#"{{"Path":"{name}","Length":5,"Checksum":"{HELLO_FILE}","Type":"SwiftyFile","Parts":[{{"Path":"{name}_5","Length":5,"Start":0,"Checksum":"{HELLO_PART}"}}]}}"#
+        )
+    };
+    let srf = format!(
+        r#"{{"Name":"@m","Checksum":"MOD","Files":[{},{}]}}"#,
+        file("a.bin"),
+        file("b.bin")
+    );
+    let app = Router::new()
+        .route("/repo.json", get(move || async move { repo }))
+        .route("/@m/mod.srf", get(move || async move { srf }))
+        .route("/@m/a.bin", get(|| async { "hello" }))
+        .route("/@m/b.bin", get(|| async { "hello" }));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{addr}")
+}
+
+fn download(rel_path: &str) -> DownloadAction {
+    DownloadAction {
+        mod_name: "@m".into(),
+        rel_path: rel_path.into(),
+        size: 5,
+        expected_checksum: HELLO_FILE.into(),
+        mtime: None,
+        parts: Vec::new(),
+    }
+}
+
+#[tokio::test]
+async fn executed_plan_is_reported_per_mod() {
+    let base = start_server().await;
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    std::fs::create_dir_all(root.join("@m")).unwrap();
+    std::fs::write(root.join("@m/a.bin"), "stale").unwrap();
+    std::fs::write(root.join("@m/gone.bin"), "gone").unwrap();
+    std::fs::create_dir_all(root.join("@oldmod")).unwrap();
+    std::fs::write(root.join("@oldmod/x.bin"), "x").unwrap();
+
+    let plan = SyncPlan {
+        renames: vec![],
+        checks: vec![],
+        downloads: vec![download("a.bin"), download("b.bin")],
+        deletes: vec![
+            DeleteAction {
+                path: "@m/gone.bin".into(),
+            },
+            DeleteAction {
+                path: "@oldmod".into(),
+            },
+        ],
+    };
+    let req = SyncRequest {
+        repo_url: base,
+        extra_repo_urls: vec![],
+        local_root: root,
+        mode: SyncMode::FullRehash,
+        options: SyncOptions::default(),
+        profile_id: None,
+    };
+
+    let result = default_engine(reqwest::Client::new())
+        .execute_with_plan(&req, plan, None)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        result.stats.changes,
+        ChangeLog {
+            mods: vec![
+                ModChanges {
+                    mod_name: "@m".into(),
+                    added: vec!["b.bin".into()],
+                    updated: vec!["a.bin".into()],
+                    deleted: vec!["gone.bin".into()],
+                    ..ModChanges::default()
+                },
+                ModChanges {
+                    mod_name: "@oldmod".into(),
+                    removed: true,
+                    ..ModChanges::default()
+                },
+            ],
+        }
+    );
+    assert_eq!(
+        result.stats.changes.summary(),
+        "Updated @m (3 files), removed @oldmod"
+    );
+}
+
+#[test]
+fn condensed_logs_keep_counts_and_a_capped_sample() {
+    let log = ChangeLog {
+        mods: vec![ModChanges {
+            mod_name: "@m".into(),
+            added: (0..50).map(|i| format!("new{i}.bin")).collect(),
+            updated: vec!["a.bin".into()],
+            deleted: vec!["gone.bin".into()],
+            ..ModChanges::default()
+        }],
+    };
+
+    let condensed = log.condensed(3);
+
+    let m = &condensed.mods[0];
+    assert_eq!(
+        (m.files_added, m.files_updated, m.files_deleted),
+        (50, 1, 1)
+    );
+    assert_eq!(m.sample, vec!["new0.bin", "new1.bin", "new2.bin"]);
+    assert_eq!(condensed.summary(), log.summary());
+    assert_eq!(condensed.summary(), "Updated @m (52 files)");
+}
@@ -0,0 +1,68 @@
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::IntoResponse;
+use axum::{routing::get, Router};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use fleet_pipeline::sync::remote::{HttpRemoteStateProvider, RemoteStateProvider};
+use std::io::Write;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+const HELLO_FILE: &str = "F872A18EB88181EB00816510E762FEE6";
+
+const HELLO_PART: &str = "5D41402ABC4B2A76B9719D911017C592";
+
+/// Answers only clients that accept gzip, with a gzip-encoded body.
+fn gzipped(headers: &HeaderMap, body: &str, served: &AtomicUsize) -> axum::response::Response {
+    let accepts_gzip = headers
+        .get(header::ACCEPT_ENCODING)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.contains("gzip"));
+    if !accepts_gzip {
+        return StatusCode::NOT_ACCEPTABLE.into_response();
+    }
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(body.as_bytes()).unwrap();
+    served.fetch_add(1, Ordering::SeqCst);
+    (
+        [(header::CONTENT_ENCODING, "gzip")],
+        encoder.finish().unwrap(),
+    )
+        .into_response()
+}
+
+#[tokio::test]
+async fn gzip_encoded_repo_json_and_srf_are_decoded() {
+    let repo = r#"{"repoName":"r","checksum":"","requiredMods":[{"modName":"@m","checksum":"MOD","enabled":true}],"optionalMods":[]}"#;
+    let srf = format!(
+        r#"{{"Name":"@m","Checksum":"MOD","Files":[{{"Path":"addons\\a.bin","Length":5,"Checksum":"{HELLO_FILE}","Type":"SwiftyFile","Parts":[{{"Path":"a.bin_5","Length":5,"Start":0,"Checksum":"{HELLO_PART}"}}]}}]}}"#
+    );
+    let served = Arc::new(AtomicUsize::new(0));
+    let (repo_served, srf_served) = (served.clone(), served.clone());
+    let app = Router::new()
+        .route(
+            "/repo.json",
+            get(move |headers: HeaderMap| async move { gzipped(&headers, repo, &repo_served) }),
+        )
+        .route(
+            "/@m/mod.srf",
+            get(move |headers: HeaderMap| async move { gzipped(&headers, &srf, &srf_served) }),
+        );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let client = fleet_infra::net::default_http_client(None).unwrap();
+    let remote = HttpRemoteStateProvider::new(client)
+        .fetch_remote(&format!("http://{addr}/repo.json"))
+        .await
+        .unwrap();
+
+    assert_eq!(served.load(Ordering::SeqCst), 2);
+    let m = &remote.manifest.mods[0];
+    assert_eq!(m.name, "@m");
+    assert_eq!(m.files[0].path, "addons/a.bin");
+    assert_eq!(m.files[0].checksum, HELLO_FILE);
+}
@@ -0,0 +1,230 @@
+use axum::extract::Path;
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::IntoResponse;
+use axum::{routing::get, Router};
+use camino::Utf8PathBuf;
+use fleet_persistence::{FleetDataStore, RedbFleetDataStore};
+use fleet_pipeline::sync::execute::DefaultPlanExecutor;
+use fleet_pipeline::sync::local::DefaultLocalStateProvider;
+use fleet_pipeline::sync::remote::HttpRemoteStateProvider;
+use fleet_pipeline::sync::storage::{RepoSummary, RepoSummaryStore};
+use fleet_pipeline::sync::{DefaultSyncEngine, SyncMode, SyncOptions, SyncRequest};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tempfile::tempdir;
+
+#[derive(Default)]
+struct MemorySummaryStore {
+    summaries: Mutex<HashMap<String, RepoSummary>>,
+}
+
+impl RepoSummaryStore for MemorySummaryStore {
+    fn load_repo_summary(&self, profile_id: &str) -> Result<Option<RepoSummary>, String> {
+        Ok(self.summaries.lock().unwrap().get(profile_id).cloned())
+    }
+
+    fn save_repo_summary(&self, profile_id: &str, summary: &RepoSummary) -> Result<(), String> {
+        self.summaries
+            .lock()
+            .unwrap()
+            .insert(profile_id.to_string(), summary.clone());
+        Ok(())
+    }
+}
+
+/// Serves `body` with a fixed ETag, answering 304 when the client already has it.
+fn etag_response(
+    headers: &HeaderMap,
+    etag: &str,
+    body: String,
+    full: &Mutex<u32>,
+) -> impl IntoResponse {
+    let matches = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        == Some(etag);
+    if matches {
+        return (
+            StatusCode::NOT_MODIFIED,
+            [(header::ETAG, etag.to_string())],
+            String::new(),
+        );
+    }
+    *full.lock().unwrap() += 1;
+    (StatusCode::OK, [(header::ETAG, etag.to_string())], body)
+}
+
+#[tokio::test]
+async fn unchanged_repo_and_srfs_are_served_from_cache_on_304() {
+    let full_bodies = Arc::new(Mutex::new(0u32));
+    let repo_json = r#"{
+        "repoName": "test",
+        "checksum": "",
+        "requiredMods": [
+            {"modName": "@a", "checksum": "hash_A", "enabled": true},
+            {"modName": "@b", "checksum": "hash_B", "enabled": true}
+        ],
+        "optionalMods": []
+    }"#;
+
+    let repo_counter = full_bodies.clone();
+    let srf_counter = full_bodies.clone();
+    let app =
+        Router::new()
+            .route(
+                "/repo.json",
+                get(move |headers: HeaderMap| {
+                    let counter = repo_counter.clone();
+                    async move {
+                        etag_response(&headers, "\"repo-v1\"", repo_json.to_string(), &counter)
+                    }
+                }),
+            )
+            .route(
+                "/:mod_name/mod.srf",
+                get(move |Path(mod_name): Path<String>, headers: HeaderMap| {
+                    let counter = srf_counter.clone();
+                    async move {
+                        let srf =
+                            format!(r#"{{"Name":"{mod_name}","Checksum":"hash","Files":[]}}"#);
+                        let etag = format!("\"{mod_name}-v1\"");
+                        etag_response(&headers, &etag, srf, &counter)
+                    }
+                }),
+            );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let work_dir = tempdir().unwrap();
+    let local_root = Utf8PathBuf::from_path_buf(work_dir.path().to_path_buf()).unwrap();
+
+    let client = reqwest::Client::new();
+    let fleet_data: Arc<dyn FleetDataStore> = Arc::new(RedbFleetDataStore);
+    let engine = DefaultSyncEngine::with_components(
+        Box::new(HttpRemoteStateProvider::new(client.clone())),
+        Box::new(DefaultLocalStateProvider::new(fleet_data.clone())),
+        Box::new(DefaultPlanExecutor::new(client)),
+        fleet_data,
+        Arc::new(MemorySummaryStore::default()),
+    );
+    let req = SyncRequest {
+        repo_url: format!("http://{addr}/repo.json"),
+        extra_repo_urls: vec![],
+        local_root,
+        mode: SyncMode::FastCheck,
+        options: SyncOptions::default(),
+        profile_id: Some("conditional".into()),
+    };
+
+    let first = engine.fetch_remote_state(&req).await.unwrap();
+    assert_eq!(*full_bodies.lock().unwrap(), 3);
+    assert_eq!(first.stats.mods_fetched, 2);
+
+    let second = engine.fetch_remote_state(&req).await.unwrap();
+    assert_eq!(
+        *full_bodies.lock().unwrap(),
+        3,
+        "second check should be answered entirely by 304s"
+    );
+    assert_eq!(second.stats.mods_fetched, 0);
+    assert_eq!(second.stats.mods_cached, 2);
+
+    let mut first_names: Vec<_> = first.manifest.mods.iter().map(|m| m.name.clone()).collect();
+    let mut second_names: Vec<_> = second
+        .manifest
+        .mods
+        .iter()
+        .map(|m| m.name.clone())
+        .collect();
+    first_names.sort();
+    second_names.sort();
+    assert_eq!(first_names, second_names);
+
+    server.abort();
+}
+
+#[tokio::test]
+async fn a_missing_srf_is_not_requested_again_until_repo_json_changes() {
+    let missing_requests = Arc::new(Mutex::new(0u32));
+    let repo_etag = Arc::new(Mutex::new("\"repo-v1\"".to_string()));
+    let repo_json = r#"{
+        "repoName": "test",
+        "checksum": "",
+        "requiredMods": [
+            {"modName": "@ok", "checksum": "hash_OK", "enabled": true},
+            {"modName": "@broken", "checksum": "hash_BROKEN", "enabled": true}
+        ],
+        "optionalMods": []
+    }"#;
+
+    let etag = repo_etag.clone();
+    let counter = missing_requests.clone();
+    let app = Router::new()
+        .route(
+            "/repo.json",
+            get(move || {
+                let etag = etag.lock().unwrap().clone();
+                async move { ([(header::ETAG, etag)], repo_json) }
+            }),
+        )
+        .route(
+            "/@ok/mod.srf",
+            get(|| async { r#"{"Name":"@ok","Checksum":"hash_OK","Files":[]}"# }),
+        )
+        .route(
+            "/@broken/mod.srf",
+            get(move || {
+                *counter.lock().unwrap() += 1;
+                async { StatusCode::NOT_FOUND }
+            }),
+        );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let work_dir = tempdir().unwrap();
+    let local_root = Utf8PathBuf::from_path_buf(work_dir.path().to_path_buf()).unwrap();
+    let client = reqwest::Client::new();
+    let fleet_data: Arc<dyn FleetDataStore> = Arc::new(RedbFleetDataStore);
+    let engine = DefaultSyncEngine::with_components(
+        Box::new(HttpRemoteStateProvider::new(client.clone())),
+        Box::new(DefaultLocalStateProvider::new(fleet_data.clone())),
+        Box::new(DefaultPlanExecutor::new(client)),
+        fleet_data,
+        Arc::new(MemorySummaryStore::default()),
+    );
+    let mut req = SyncRequest {
+        repo_url: format!("http://{addr}/repo.json"),
+        extra_repo_urls: vec![],
+        local_root,
+        mode: SyncMode::FastCheck,
+        options: SyncOptions {
+            skip_unreachable_mods: true,
+            ..SyncOptions::default()
+        },
+        profile_id: Some("missing-srf".into()),
+    };
+
+    let first = engine.fetch_remote_state(&req).await.unwrap();
+    assert_eq!(first.unreachable_mod_names(), vec!["@broken".to_string()]);
+    assert_eq!(*missing_requests.lock().unwrap(), 1);
+
+    // Still reported, but answered from the cache.
+    let second = engine.fetch_remote_state(&req).await.unwrap();
+    assert_eq!(second.unreachable_mod_names(), vec!["@broken".to_string()]);
+    assert_eq!(*missing_requests.lock().unwrap(), 1);
+    req.options.skip_unreachable_mods = false;
+    assert!(engine.fetch_remote_state(&req).await.is_err());
+    assert_eq!(*missing_requests.lock().unwrap(), 1);
+
+    *repo_etag.lock().unwrap() = "\"repo-v2\"".into();
+    assert!(engine.fetch_remote_state(&req).await.is_err());
+    assert_eq!(*missing_requests.lock().unwrap(), 2);
+
+    server.abort();
+}
@@ -0,0 +1,101 @@
+use axum::{routing::get, Router};
+use fleet_persistence::{FleetDataStore, RedbFleetDataStore};
+use fleet_pipeline::sync::{default_engine, SyncMode, SyncOptions, SyncRequest};
+use redb::{Database, ReadableTable, TableDefinition};
+use tempfile::tempdir;
+
+const FILE_CHECKSUM: &str = "F872A18EB88181EB00816510E762FEE6";
+const PART_CHECKSUM: &str = "5D41402ABC4B2A76B9719D911017C592";
+const SCAN_CACHE: TableDefinition<&[u8], &[u8]> = TableDefinition::new("scan_cache");
+
+/// Copies a synced tree to a fresh root; the store keeps the original database open.
+fn copy_tree(from: &camino::Utf8Path, to: &camino::Utf8Path) {
+    for entry in walkdir::WalkDir::new(from) {
+        let entry = entry.unwrap();
+        let rel = entry.path().strip_prefix(from).unwrap();
+        let target = to.as_std_path().join(rel);
+        if entry.file_type().is_dir() {
+            std::fs::create_dir_all(&target).unwrap();
+        } else {
+            std::fs::copy(entry.path(), &target).unwrap();
+        }
+    }
+}
+
+/// Overwrites every scan cache row with bytes that do not decode.
+fn corrupt_scan_cache(root: &camino::Utf8Path) -> usize {
+    let db = Database::open(root.join("fleet.redb").as_std_path()).unwrap();
+    let write_tx = db.begin_write().unwrap();
+    let corrupted = {
+        let mut table = write_tx.open_table(SCAN_CACHE).unwrap();
+        let keys: Vec<Vec<u8>> = table
+            .iter()
+            .unwrap()
+            .map(|row| row.unwrap().0.value().to_vec())
+            .collect();
+        for key in &keys {
+            table
+                .insert(key.as_slice(), b"not json".as_slice())
+                .unwrap();
+        }
+        keys.len()
+    };
+    write_tx.commit().unwrap();
+    corrupted
+}
+
+#[tokio::test]
+async fn corrupt_scan_cache_is_reset_and_reported() {
+    let repo_json = r#"{"repoName":"cached","checksum":"","requiredMods":[{"modName":"@cached","checksum":"AAA","enabled":true}],"optionalMods":[]}"#;
+    let srf = format!(
+        r#"{{"Name":"@cached","Checksum":"AAA","Files":[{{"Path":"file.txt","Length":5,"Checksum":"{FILE_CHECKSUM}","Type":"SwiftyFile","Parts":[{{"Path":"file.txt_5","Length":5,"Start":0,"Checksum":"{PART_CHECKSUM}"}}]}}]}}"#
+    );
+    let app = Router::new()
+        .route("/repo.json", get(move || async move { repo_json }))
+        .route("/@cached/mod.srf", get(move || async move { srf }))
+        .route("/@cached/file.txt", get(|| async { b"hello".to_vec() }));
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let synced = tempdir().unwrap();
+    let synced_root = camino::Utf8PathBuf::from_path_buf(synced.path().to_path_buf()).unwrap();
+    let engine = default_engine(reqwest::Client::new());
+    let mut req = SyncRequest {
+        repo_url: format!("http://{addr}"),
+        extra_repo_urls: vec![],
+        local_root: synced_root.clone(),
+        mode: SyncMode::MetadataOnly,
+        options: SyncOptions::default(),
+        profile_id: None,
+    };
+
+    let first = engine.plan_and_execute(&req, None).await.unwrap();
+    assert!(first.warnings.is_empty(), "got: {:?}", first.warnings);
+
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    copy_tree(&synced_root, &root);
+    assert!(corrupt_scan_cache(&root) > 0);
+    req.local_root = root.clone();
+    req.mode = SyncMode::SmartVerify;
+    assert!(RedbFleetDataStore
+        .scan_cache_load_mod(&root, "@cached")
+        .is_err());
+
+    let second = engine.plan_and_execute(&req, None).await.unwrap();
+    assert_eq!(second.stats.files_planned_download, 0);
+    assert!(
+        second.warnings.iter().any(|w| w.contains("@cached")),
+        "got: {:?}",
+        second.warnings
+    );
+    assert!(RedbFleetDataStore
+        .scan_cache_load_mod(&root, "@cached")
+        .is_ok());
+
+    server.abort();
+}
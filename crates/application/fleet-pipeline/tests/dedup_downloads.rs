@@ -0,0 +1,76 @@
+use axum::{routing::get, Router};
+use fleet_pipeline::sync::{default_engine, SyncMode, SyncOptions, SyncRequest};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tempfile::tempdir;
+
+const FILE_CHECKSUM: &str = "F872A18EB88181EB00816510E762FEE6";
+const PART_CHECKSUM: &str = "5D41402ABC4B2A76B9719D911017C592";
+
+#[tokio::test]
+async fn identical_files_are_downloaded_once() {
+    let repo_json = r#"{"repoName":"shared","checksum":"","requiredMods":[{"modName":"@a","checksum":"AAA","enabled":true},{"modName":"@b","checksum":"AAA","enabled":true}],"optionalMods":[]}"#;
+    let srf = |mod_name: &str| {
+        format!(
+            r#"{{"Name":"{mod_name}","Checksum":"AAA","Files":[{{"Path":"shared.txt","Length":5,"Checksum":"{FILE_CHECKSUM}","Type":"SwiftyFile","Parts":[{{"Path":"shared.txt_5","Length":5,"Start":0,"Checksum":"{PART_CHECKSUM}"}}]}}]}}"#
+        )
+    };
+    let (srf_a, srf_b) = (srf("@a"), srf("@b"));
+
+    let requests = Arc::new(AtomicUsize::new(0));
+    let (a_ref, b_ref) = (requests.clone(), requests.clone());
+    let app = Router::new()
+        .route("/repo.json", get(move || async move { repo_json }))
+        .route("/@a/mod.srf", get(move || async move { srf_a }))
+        .route("/@b/mod.srf", get(move || async move { srf_b }))
+        .route(
+            "/@a/shared.txt",
+            get(move || async move {
+                a_ref.fetch_add(1, Ordering::SeqCst);
+                b"hello".to_vec()
+            }),
+        )
+        .route(
+            "/@b/shared.txt",
+            get(move || async move {
+                b_ref.fetch_add(1, Ordering::SeqCst);
+                b"hello".to_vec()
+            }),
+        );
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let engine = default_engine(reqwest::Client::new());
+    let mut req = SyncRequest {
+        repo_url: format!("http://{addr}"),
+        extra_repo_urls: vec![],
+        local_root: root.clone(),
+        mode: SyncMode::MetadataOnly,
+        options: SyncOptions::default(),
+        profile_id: None,
+    };
+
+    let first = engine.plan_and_execute(&req, None).await.unwrap();
+    assert_eq!(first.stats.files_planned_download, 2);
+    assert_eq!(first.stats.files_deduplicated, 1);
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+    for mod_name in ["@a", "@b"] {
+        assert_eq!(
+            std::fs::read(root.join(mod_name).join("shared.txt")).unwrap(),
+            b"hello"
+        );
+    }
+
+    req.mode = SyncMode::SmartVerify;
+    let second = engine.plan_and_execute(&req, None).await.unwrap();
+    assert_eq!(second.stats.files_planned_download, 0);
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+    server.abort();
+}
@@ -0,0 +1,59 @@
+use axum::{routing::get, Router};
+use fleet_pipeline::sync::{default_engine, SyncError, SyncMode, SyncOptions, SyncRequest};
+use std::fs;
+use tempfile::tempdir;
+
+const FILE_CHECKSUM: &str = "F872A18EB88181EB00816510E762FEE6";
+const PART_CHECKSUM: &str = "5D41402ABC4B2A76B9719D911017C592";
+
+#[tokio::test]
+async fn plans_deleting_most_local_files_need_confirmation() {
+    let repo_json = r#"{"repoName":"test","checksum":"","requiredMods":[{"modName":"@base","checksum":"AAA","enabled":true}],"optionalMods":[]}"#;
+    let srf = format!(
+        r#"{{"Name":"@base","Checksum":"AAA","Files":[{{"Path":"file.txt","Length":5,"Checksum":"{FILE_CHECKSUM}","Type":"SwiftyFile","Parts":[{{"Path":"file.txt_5","Length":5,"Start":0,"Checksum":"{PART_CHECKSUM}"}}]}}]}}"#
+    );
+    let app = Router::new()
+        .route("/repo.json", get(move || async move { repo_json }))
+        .route("/@base/mod.srf", get(move || async move { srf }))
+        .route("/@base/file.txt", get(|| async { b"hello".to_vec() }));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    // A folder full of unrelated mods, as if the wrong path had been selected.
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    fs::create_dir_all(root.join("@other/addons")).unwrap();
+    for i in 0..20 {
+        fs::write(root.join(format!("@other/addons/{i}.pbo")), b"data").unwrap();
+    }
+
+    let engine = default_engine(reqwest::Client::new());
+    let mut req = SyncRequest {
+        repo_url: format!("http://{addr}"),
+        extra_repo_urls: vec![],
+        local_root: root.clone(),
+        mode: SyncMode::MetadataOnly,
+        options: SyncOptions::default(),
+        profile_id: None,
+    };
+
+    match engine.plan_and_execute(&req, None).await {
+        Err(SyncError::Destructive { deleted, existing }) => {
+            assert_eq!(deleted, 20);
+            assert_eq!(existing, 20);
+        }
+        other => panic!("expected destructive plan to be refused, got {other:?}"),
+    }
+    assert!(root.join("@other/addons/0.pbo").exists());
+
+    req.options.delete_threshold = None;
+    let result = engine.plan_and_execute(&req, None).await.unwrap();
+    assert!(result.executed);
+    assert!(!root.join("@other").exists());
+    assert!(root.join("@base/file.txt").exists());
+
+    server.abort();
+}
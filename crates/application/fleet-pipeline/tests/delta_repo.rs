@@ -7,7 +7,7 @@ use tempfile::tempdir;
 fn tiny_repo_json() -> String {
     r#"{
         "repoName": "tiny",
-        "checksum": "AAA",
+        "checksum": "",
         "requiredMods": [{"modName": "@tiny", "checksum": "AAA", "enabled": true}],
         "optionalMods": []
     }"#
@@ -89,6 +89,7 @@ async fn plan_against_local_repo_is_hermetic() {
 
     let req = SyncRequest {
         repo_url,
+        extra_repo_urls: vec![],
         local_root: root,
         mode: SyncMode::MetadataOnly,
         options: SyncOptions::default(),
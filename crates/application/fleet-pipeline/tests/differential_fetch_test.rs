@@ -62,7 +62,7 @@ async fn differential_fetch_skips_unchanged_mods() {
     let calls = Arc::new(Mutex::new(Vec::new()));
     let repo_json = r#"{
         "repoName": "test",
-        "checksum": "abc",
+        "checksum": "",
         "requiredMods": [
             {"modName": "@mod_unchanged", "checksum": "hash_A", "enabled": true},
             {"modName": "@mod_changed", "checksum": "hash_NEW", "enabled": true},
@@ -102,6 +102,7 @@ async fn differential_fetch_skips_unchanged_mods() {
     let engine = DefaultSyncEngine::new(reqwest::Client::new());
     let req = SyncRequest {
         repo_url,
+        extra_repo_urls: vec![],
         local_root,
         mode: SyncMode::FastCheck,
         options: SyncOptions::default(),
@@ -0,0 +1,154 @@
+use axum::extract::Path;
+use axum::{routing::get, Router};
+use fleet_persistence::{FleetDataStore, RedbFleetDataStore};
+use fleet_pipeline::sync::{default_engine, SyncError, SyncMode, SyncOptions, SyncRequest};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use tempfile::tempdir;
+
+const FILE_CHECKSUM: &str = "F872A18EB88181EB00816510E762FEE6";
+const PART_CHECKSUM: &str = "5D41402ABC4B2A76B9719D911017C592";
+
+#[tokio::test]
+async fn only_the_named_files_are_downloaded() {
+    let repo_json = r#"{"repoName":"r","checksum":"","requiredMods":[{"modName":"@mod","checksum":"AAA","enabled":true}],"optionalMods":[]}"#;
+    let file = |name: &str| {
+        format!(
+            r#"{{"Path":"{name}","Length":5,"Checksum":"{FILE_CHECKSUM}","Type":"SwiftyFile","Parts":[{{"Path":"{name}_5","Length":5,"Start":0,"Checksum":"{PART_CHECKSUM}"}}]}}"#
+        )
+    };
+    let srf = format!(
+        r#"{{"Name":"@mod","Checksum":"AAA","Files":[{},{}]}}"#,
+        file("addons/a.txt"),
+        file("b.txt")
+    );
+
+    let requests = Arc::new(AtomicUsize::new(0));
+    let requests_ref = requests.clone();
+    let app = Router::new()
+        .route("/repo.json", get(move || async move { repo_json }))
+        .route("/@mod/mod.srf", get(move || async move { srf }))
+        .route(
+            "/@mod/*path",
+            get(move |Path(_): Path<String>| async move {
+                requests_ref.fetch_add(1, Ordering::SeqCst);
+                "hello"
+            }),
+        );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let engine = default_engine(reqwest::Client::new());
+    let req = SyncRequest {
+        repo_url: format!("http://{addr}"),
+        extra_repo_urls: vec![],
+        local_root: root.clone(),
+        mode: SyncMode::FullRehash,
+        options: SyncOptions::default(),
+        profile_id: None,
+    };
+    engine.plan_and_execute(&req, None).await.unwrap();
+
+    std::fs::remove_file(root.join("@mod/addons/a.txt")).unwrap();
+    let before = requests.load(Ordering::SeqCst);
+    let result = engine
+        .download_files(
+            &req,
+            vec![("@MOD".to_string(), "Addons\\A.txt".to_string())],
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.plan.downloads.len(), 1);
+    assert_eq!(result.plan.downloads[0].rel_path, "addons/a.txt");
+    assert_eq!(requests.load(Ordering::SeqCst), before + 1);
+    assert_eq!(
+        std::fs::read(root.join("@mod/addons/a.txt")).unwrap(),
+        b"hello"
+    );
+
+    let err = engine
+        .download_files(&req, vec![("@mod".into(), "missing.txt".into())], None)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, SyncError::NotFound(_)), "got {err:?}");
+
+    server.abort();
+}
+
+/// Serves `mod_name` with one `file.txt`; its checksum becomes `BBB` once `moved_on` is set.
+async fn start_repo(mod_name: &'static str, moved_on: Arc<AtomicBool>) -> String {
+    let checksum = |flag: &AtomicBool| {
+        if flag.load(Ordering::SeqCst) {
+            "BBB"
+        } else {
+            "AAA"
+        }
+    };
+    let srf_flag = moved_on.clone();
+    let repo_json = move || {
+        let json = format!(
+            r#"{{"repoName":"r","checksum":"","requiredMods":[{{"modName":"{mod_name}","checksum":"{}","enabled":true}}],"optionalMods":[]}}"#,
+            checksum(&moved_on)
+        );
+        async move { json }
+    };
+    let srf = move || {
+        let srf = format!(
+            r#"{{"Name":"{mod_name}","Checksum":"{}","Files":[{{"Path":"file.txt","Length":5,"Checksum":"{FILE_CHECKSUM}","Type":"SwiftyFile","Parts":[{{"Path":"file.txt_5","Length":5,"Start":0,"Checksum":"{PART_CHECKSUM}"}}]}}]}}"#,
+            checksum(&srf_flag)
+        );
+        async move { srf }
+    };
+    let app = Router::new()
+        .route("/repo.json", get(repo_json))
+        .route(&format!("/{mod_name}/mod.srf"), get(srf))
+        .route(&format!("/{mod_name}/file.txt"), get(|| async { "hello" }));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{addr}")
+}
+
+#[tokio::test]
+async fn restoring_from_merged_repositories_keeps_the_baseline() {
+    let moved_on = Arc::new(AtomicBool::new(false));
+    let base = start_repo("@base", moved_on.clone()).await;
+    let extra = start_repo("@extra", Arc::new(AtomicBool::new(false))).await;
+
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let engine = default_engine(reqwest::Client::new());
+    let req = SyncRequest {
+        repo_url: base,
+        extra_repo_urls: vec![extra],
+        local_root: root.clone(),
+        mode: SyncMode::FullRehash,
+        options: SyncOptions::default(),
+        profile_id: None,
+    };
+    engine.plan_and_execute(&req, None).await.unwrap();
+
+    // `@base` moves on upstream; restoring a file of `@extra` must not record that.
+    moved_on.store(true, Ordering::SeqCst);
+    std::fs::remove_file(root.join("@extra/file.txt")).unwrap();
+    engine
+        .download_files(&req, vec![("@extra".into(), "file.txt".into())], None)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        std::fs::read(root.join("@extra/file.txt")).unwrap(),
+        b"hello"
+    );
+    let baseline = RedbFleetDataStore.load_baseline_manifest(&root).unwrap();
+    let base_mod = baseline.mods.iter().find(|m| m.name == "@base").unwrap();
+    assert_eq!(base_mod.checksum, "AAA");
+}
@@ -0,0 +1,93 @@
+use axum::{routing::get, Router};
+use fleet_core::{DownloadAction, SyncPlan};
+use fleet_infra::net::DownloadEvent;
+use fleet_pipeline::sync::{default_engine, DownloadOrder, SyncMode, SyncOptions, SyncRequest};
+use tempfile::tempdir;
+
+async fn start_server() -> String {
+    let repo = r#"{"repoName":"r","checksum":"","requiredMods":[{"modName":"@m","checksum":"MOD","enabled":true}],"optionalMods":[]}"#;
+    let srf = r#"{"Name":"@m","Checksum":"MOD","Files":[]}"#;
+    let app = Router::new()
+        .route("/repo.json", get(move || async move { repo }))
+        .route("/@m/mod.srf", get(move || async move { srf }))
+        .route("/@m/one.bin", get(|| async { "a" }))
+        .route("/@m/three.bin", get(|| async { "abc" }))
+        .route("/@m/two.bin", get(|| async { "ab" }));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{addr}")
+}
+
+fn download(rel_path: &str, size: u64) -> DownloadAction {
+    DownloadAction {
+        mod_name: "@m".into(),
+        rel_path: rel_path.into(),
+        size,
+        expected_checksum: format!("SUM{size}"),
+        mtime: None,
+        parts: Vec::new(),
+    }
+}
+
+/// Paths in the order their downloads started, one at a time.
+async fn started_order(order: DownloadOrder) -> Vec<String> {
+    let base = start_server().await;
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let engine = default_engine(reqwest::Client::new());
+    let req = SyncRequest {
+        repo_url: base,
+        extra_repo_urls: vec![],
+        local_root: root,
+        mode: SyncMode::FullRehash,
+        options: SyncOptions {
+            max_threads: 1,
+            download_order: order,
+            verify_downloads: false,
+            ..SyncOptions::default()
+        },
+        profile_id: None,
+    };
+    let plan = SyncPlan {
+        renames: vec![],
+        checks: vec![],
+        deletes: vec![],
+        downloads: vec![
+            download("two.bin", 2),
+            download("one.bin", 1),
+            download("three.bin", 3),
+        ],
+    };
+
+    let (tx, mut rx) = tokio::sync::mpsc::channel(64);
+    let mut started = Vec::new();
+    let collect = async {
+        while let Some(ev) = rx.recv().await {
+            if let DownloadEvent::Started { rel_path, .. } = ev {
+                started.push(rel_path);
+            }
+        }
+    };
+    let (result, ()) = tokio::join!(engine.execute_with_plan(&req, plan, Some(tx)), collect);
+    result.unwrap();
+    started
+}
+
+#[tokio::test]
+async fn downloads_follow_the_requested_order() {
+    assert_eq!(
+        started_order(DownloadOrder::AsPlanned).await,
+        ["two.bin", "one.bin", "three.bin"]
+    );
+    assert_eq!(
+        started_order(DownloadOrder::SmallestFirst).await,
+        ["one.bin", "two.bin", "three.bin"]
+    );
+    assert_eq!(
+        started_order(DownloadOrder::LargestFirst).await,
+        ["three.bin", "two.bin", "one.bin"]
+    );
+}
@@ -0,0 +1,75 @@
+use axum::{routing::get, Router};
+use fleet_pipeline::sync::{default_engine, SyncError, SyncMode, SyncOptions, SyncRequest};
+use tempfile::tempdir;
+
+const FILE_CHECKSUM: &str = "F872A18EB88181EB00816510E762FEE6";
+const PART_CHECKSUM: &str = "5D41402ABC4B2A76B9719D911017C592";
+
+#[tokio::test]
+async fn excluded_files_are_neither_overwritten_nor_deleted() {
+    let repo_json = r#"{"repoName":"cfg","checksum":"","requiredMods":[{"modName":"@m","checksum":"AAA","enabled":true}],"optionalMods":[]}"#;
+    let file = |path: &str| {
+        format!(
+            r#"{{"Path":"{path}","Length":5,"Checksum":"{FILE_CHECKSUM}","Type":"SwiftyFile","Parts":[{{"Path":"{path}_5","Length":5,"Start":0,"Checksum":"{PART_CHECKSUM}"}}]}}"#
+        )
+    };
+    let srf = format!(
+        r#"{{"Name":"@m","Checksum":"AAA","Files":[{},{}]}}"#,
+        file("addons/data.txt"),
+        file("userconfig/cfg.hpp")
+    );
+    let app = Router::new()
+        .route("/repo.json", get(move || async move { repo_json }))
+        .route("/@m/mod.srf", get(move || async move { srf }))
+        .route("/@m/addons/data.txt", get(|| async { b"hello".to_vec() }))
+        .route(
+            "/@m/userconfig/cfg.hpp",
+            get(|| async { b"hello".to_vec() }),
+        );
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    std::fs::create_dir_all(root.join("@m/userconfig")).unwrap();
+    std::fs::write(root.join("@m/userconfig/cfg.hpp"), b"customized").unwrap();
+    std::fs::write(root.join("@m/userconfig/extra.hpp"), b"mine").unwrap();
+
+    let engine = default_engine(reqwest::Client::new());
+    let mut req = SyncRequest {
+        repo_url: format!("http://{addr}"),
+        extra_repo_urls: vec![],
+        local_root: root.clone(),
+        mode: SyncMode::SmartVerify,
+        options: SyncOptions {
+            exclude: vec!["@M/userconfig/**".into()],
+            ..SyncOptions::default()
+        },
+        profile_id: None,
+    };
+
+    let result = engine.plan_and_execute(&req, None).await.unwrap();
+    assert_eq!(result.stats.files_planned_download, 1);
+    assert!(result.plan.deletes.is_empty());
+    assert_eq!(
+        std::fs::read(root.join("@m/addons/data.txt")).unwrap(),
+        b"hello"
+    );
+    assert_eq!(
+        std::fs::read(root.join("@m/userconfig/cfg.hpp")).unwrap(),
+        b"customized"
+    );
+    assert!(root.join("@m/userconfig/extra.hpp").exists());
+
+    req.options.exclude = vec!["@m/[".into()];
+    match engine.plan_and_execute(&req, None).await {
+        Err(SyncError::Local(msg)) => assert!(msg.contains("exclude"), "got: {msg}"),
+        other => panic!("expected an invalid pattern error, got {other:?}"),
+    }
+
+    server.abort();
+}
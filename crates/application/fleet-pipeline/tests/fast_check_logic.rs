@@ -31,6 +31,7 @@ async fn fast_check_detects_mtime_change() {
                 checksum: "checksum_orig".into(),
                 file_type: fleet_core::FileType::File,
                 parts: vec![],
+                mtime: None,
             }],
         }],
     };
@@ -52,7 +53,7 @@ async fn fast_check_detects_mtime_change() {
     let provider = DefaultLocalStateProvider::new(store);
 
     let clean_state = provider
-        .local_state(&root, SyncMode::FastCheck, None)
+        .local_state(&root, SyncMode::FastCheck, None, false, None, None)
         .await
         .unwrap();
     assert_eq!(
@@ -64,7 +65,7 @@ async fn fast_check_detects_mtime_change() {
     filetime::set_file_mtime(&file_path, filetime::FileTime::now()).unwrap();
 
     let dirty_state = provider
-        .local_state(&root, SyncMode::FastCheck, None)
+        .local_state(&root, SyncMode::FastCheck, None, false, None, None)
         .await
         .unwrap();
     let dirty_file = &dirty_state.manifest.mods[0].files[0];
@@ -99,6 +100,7 @@ async fn fast_check_detects_size_change() {
                 checksum: "checksum_orig".into(),
                 file_type: fleet_core::FileType::File,
                 parts: vec![],
+                mtime: None,
             }],
         }],
     };
@@ -123,7 +125,7 @@ async fn fast_check_detects_size_change() {
     fs::write(&file_path, "original_modified").unwrap();
 
     let dirty_state = provider
-        .local_state(&root, SyncMode::FastCheck, None)
+        .local_state(&root, SyncMode::FastCheck, None, false, None, None)
         .await
         .unwrap();
     let dirty_file = &dirty_state.manifest.mods[0].files[0];
@@ -157,6 +159,7 @@ async fn fast_check_handles_missing_file() {
                 checksum: "checksum_orig".into(),
                 file_type: fleet_core::FileType::File,
                 parts: vec![],
+                mtime: None,
             }],
         }],
     };
@@ -181,7 +184,7 @@ async fn fast_check_handles_missing_file() {
     fs::remove_file(&file_path).unwrap();
 
     let state = provider
-        .local_state(&root, SyncMode::FastCheck, None)
+        .local_state(&root, SyncMode::FastCheck, None, false, None, None)
         .await
         .unwrap();
     // File should be missing from manifest
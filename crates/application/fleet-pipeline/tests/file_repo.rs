@@ -0,0 +1,46 @@
+use fleet_pipeline::sync::{default_engine, SyncMode, SyncOptions, SyncRequest};
+use tempfile::tempdir;
+
+const FILE_CHECKSUM: &str = "F872A18EB88181EB00816510E762FEE6";
+const PART_CHECKSUM: &str = "5D41402ABC4B2A76B9719D911017C592";
+
+#[tokio::test]
+async fn syncs_from_a_local_mirror() {
+    let mirror = tempdir().unwrap();
+    let repo_json = r#"{"repoName":"mirror","checksum":"","requiredMods":[{"modName":"@local","checksum":"AAA","enabled":true}],"optionalMods":[]}"#;
+    let srf = format!(
+        r#"{{"Name":"@local","Checksum":"AAA","Files":[{{"Path":"addons/a b.txt","Length":5,"Checksum":"{FILE_CHECKSUM}","Type":"SwiftyFile","Parts":[{{"Path":"addons/a b.txt_5","Length":5,"Start":0,"Checksum":"{PART_CHECKSUM}"}}]}}]}}"#
+    );
+    std::fs::write(mirror.path().join("repo.json"), repo_json).unwrap();
+    std::fs::create_dir_all(mirror.path().join("@local/addons")).unwrap();
+    std::fs::write(mirror.path().join("@local/mod.srf"), srf).unwrap();
+    std::fs::write(mirror.path().join("@local/addons/a b.txt"), b"hello").unwrap();
+
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let engine = default_engine(reqwest::Client::new());
+    let mut req = SyncRequest {
+        repo_url: reqwest::Url::from_directory_path(mirror.path())
+            .unwrap()
+            .to_string(),
+        extra_repo_urls: vec![],
+        local_root: root.clone(),
+        mode: SyncMode::MetadataOnly,
+        options: SyncOptions::default(),
+        profile_id: None,
+    };
+
+    engine.validate_repo_url(&req.repo_url).await.unwrap();
+    let result = engine.plan_and_execute(&req, None).await.unwrap();
+    assert_eq!(result.stats.files_planned_download, 1);
+    assert_eq!(
+        std::fs::read(root.join("@local/addons/a b.txt")).unwrap(),
+        b"hello"
+    );
+
+    // A bare path to the mirror resolves to the same repository.
+    req.repo_url = mirror.path().to_string_lossy().to_string();
+    req.mode = SyncMode::SmartVerify;
+    let clean = engine.plan(&req).await.unwrap();
+    assert!(clean.downloads.is_empty() && clean.deletes.is_empty());
+}
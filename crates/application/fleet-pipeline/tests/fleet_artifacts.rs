@@ -0,0 +1,79 @@
+use axum::{routing::get, Router};
+use fleet_core::{DeleteAction, SyncPlan};
+use fleet_pipeline::sync::{default_engine, SyncMode, SyncOptions, SyncRequest};
+use fleet_scanner::{ScanStrategy, Scanner};
+use tempfile::tempdir;
+
+const FILE_CHECKSUM: &str = "F872A18EB88181EB00816510E762FEE6";
+const PART_CHECKSUM: &str = "5D41402ABC4B2A76B9719D911017C592";
+
+const ARTIFACTS: [&str; 3] = [
+    ".fleet.redb",
+    "fleet.redb.corrupt.20240101T000000.1.0",
+    ".fleet-cache.json",
+];
+
+#[tokio::test]
+async fn fleet_files_are_neither_scanned_nor_deleted() {
+    let repo_json = r#"{"repoName":"r","checksum":"","requiredMods":[{"modName":"@m","checksum":"AAA","enabled":true}],"optionalMods":[]}"#;
+    let srf = format!(
+        r#"{{"Name":"@m","Checksum":"AAA","Files":[{{"Path":"data.txt","Length":5,"Checksum":"{FILE_CHECKSUM}","Type":"SwiftyFile","Parts":[{{"Path":"data.txt_5","Length":5,"Start":0,"Checksum":"{PART_CHECKSUM}"}}]}}]}}"#
+    );
+    let app = Router::new()
+        .route("/repo.json", get(move || async move { repo_json }))
+        .route("/@m/mod.srf", get(move || async move { srf }))
+        .route("/@m/data.txt", get(|| async { b"hello".to_vec() }));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    std::fs::create_dir_all(root.join("@m")).unwrap();
+    std::fs::write(root.join("@m/data.txt"), b"hello").unwrap();
+    for name in ARTIFACTS {
+        std::fs::write(root.join("@m").join(name), b"state").unwrap();
+    }
+
+    let scanned =
+        Scanner::scan_directory(&root, ScanStrategy::ForceRehash, None, None, None).unwrap();
+    let paths: Vec<&str> = scanned.mods[0]
+        .files
+        .iter()
+        .map(|f| f.path.as_str())
+        .collect();
+    assert_eq!(paths, ["data.txt"]);
+
+    let engine = default_engine(reqwest::Client::new());
+    let req = SyncRequest {
+        repo_url: format!("http://{addr}"),
+        extra_repo_urls: vec![],
+        local_root: root.clone(),
+        mode: SyncMode::SmartVerify,
+        options: SyncOptions::default(),
+        profile_id: None,
+    };
+    let plan = engine.plan(&req).await.unwrap();
+    assert!(plan.deletes.is_empty(), "{:?}", plan.deletes);
+
+    // A plan from elsewhere that lists them still leaves them alone.
+    let foreign = SyncPlan {
+        renames: vec![],
+        checks: vec![],
+        downloads: vec![],
+        deletes: ARTIFACTS
+            .iter()
+            .map(|name| DeleteAction {
+                path: format!("@m/{name}"),
+            })
+            .collect(),
+    };
+    engine.execute_with_plan(&req, foreign, None).await.unwrap();
+    for name in ARTIFACTS {
+        assert!(root.join("@m").join(name).exists(), "{name} was deleted");
+    }
+
+    server.abort();
+}
@@ -0,0 +1,156 @@
+use axum::extract::Path;
+use axum::http::StatusCode;
+use axum::{routing::get, Router};
+use fleet_pipeline::sync::{default_engine, SyncError, SyncMode, SyncOptions, SyncRequest};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tempfile::tempdir;
+
+const MODS: usize = 6;
+
+/// Serves a repo of `MODS` mods with one file each, counting srf and file requests. Files
+/// always answer 500, srfs too when `srfs_fail` is set. Every file has its own checksum so
+/// none are deduplicated; none is ever served, so they need not match any content.
+async fn start_server(srfs_fail: bool) -> (String, Arc<AtomicUsize>, Arc<AtomicUsize>) {
+    let mods: Vec<String> = (0..MODS)
+        .map(|i| format!(r#"{{"modName":"@m{i}","checksum":"AAA","enabled":true}}"#))
+        .collect();
+    let repo_json = format!(
+        r#"{{"repoName":"down","checksum":"","requiredMods":[{}],"optionalMods":[]}}"#,
+        mods.join(",")
+    );
+
+    let srf_requests = Arc::new(AtomicUsize::new(0));
+    let file_requests = Arc::new(AtomicUsize::new(0));
+    let (srf_ref, file_ref) = (srf_requests.clone(), file_requests.clone());
+    let app = Router::new()
+        .route("/repo.json", get(move || async move { repo_json }))
+        .route(
+            "/:mod_name/mod.srf",
+            get(move |Path(mod_name): Path<String>| async move {
+                srf_ref.fetch_add(1, Ordering::SeqCst);
+                if srfs_fail {
+                    return Err(StatusCode::INTERNAL_SERVER_ERROR);
+                }
+                let checksum = format!("{:0>32}", mod_name.trim_start_matches("@m"));
+                Ok(format!(
+                    r#"{{"Name":"{mod_name}","Checksum":"AAA","Files":[{{"Path":"a.txt","Length":5,"Checksum":"{checksum}","Type":"SwiftyFile","Parts":[{{"Path":"a.txt_5","Length":5,"Start":0,"Checksum":"{checksum}"}}]}}]}}"#
+                ))
+            }),
+        )
+        .route(
+            "/:mod_name/a.txt",
+            get(move || async move {
+                file_ref.fetch_add(1, Ordering::SeqCst);
+                StatusCode::INTERNAL_SERVER_ERROR
+            }),
+        );
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    (format!("http://{addr}"), srf_requests, file_requests)
+}
+
+fn request(
+    repo_url: String,
+    root: camino::Utf8PathBuf,
+    max_host_failures: Option<usize>,
+) -> SyncRequest {
+    SyncRequest {
+        repo_url,
+        extra_repo_urls: vec![],
+        local_root: root,
+        mode: SyncMode::FullRehash,
+        options: SyncOptions {
+            max_threads: 1,
+            max_host_failures,
+            ..SyncOptions::default()
+        },
+        profile_id: None,
+    }
+}
+
+#[tokio::test]
+async fn failing_srf_fetches_stop_at_the_threshold() {
+    let (base, srf_requests, _) = start_server(true).await;
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let engine = default_engine(reqwest::Client::new());
+
+    let err = engine
+        .fetch_remote_state(&request(base, root, Some(2)))
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, SyncError::RepoUnavailable { failures: 2, .. }),
+        "got {err:?}"
+    );
+    assert_eq!(srf_requests.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn failing_downloads_stop_at_the_threshold() {
+    let (base, _, file_requests) = start_server(false).await;
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let engine = default_engine(reqwest::Client::new());
+
+    let err = engine
+        .plan_and_execute(&request(base, root, Some(2)), None)
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, SyncError::RepoUnavailable { failures: 2, .. }),
+        "got {err:?}"
+    );
+    // Each file counts once after all its attempts; the second trips the breaker and no
+    // other file is requested.
+    assert_eq!(file_requests.load(Ordering::SeqCst), 2 * 3);
+}
+
+#[tokio::test]
+async fn without_a_threshold_every_file_is_retried() {
+    let (base, _, file_requests) = start_server(false).await;
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let engine = default_engine(reqwest::Client::new());
+
+    let err = engine
+        .plan_and_execute(&request(base, root, None), None)
+        .await
+        .unwrap_err();
+    match err {
+        SyncError::Downloads(failed) => assert_eq!(failed.len(), MODS),
+        other => panic!("expected download failures, got {other:?}"),
+    }
+    assert_eq!(file_requests.load(Ordering::SeqCst), MODS * 3);
+}
+
+#[tokio::test]
+async fn malformed_srfs_do_not_count_as_host_failures() {
+    let app = Router::new()
+        .route(
+            "/repo.json",
+            get(|| async {
+                r#"{"repoName":"bad","checksum":"","requiredMods":[{"modName":"@a","checksum":"AAA","enabled":true},{"modName":"@b","checksum":"AAA","enabled":true}],"optionalMods":[]}"#
+            }),
+        )
+        .route("/:mod_name/mod.srf", get(|| async { "not json" }));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let engine = default_engine(reqwest::Client::new());
+
+    let err = engine
+        .fetch_remote_state(&request(format!("http://{addr}"), root, Some(1)))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, SyncError::Remote(_)), "got {err:?}");
+}
@@ -14,17 +14,18 @@ async fn local_integrity_errors_when_db_is_busy() {
     let db_path = root.join("fleet.redb");
     let _external_lock = redb::Database::create(db_path.as_std_path()).unwrap();
 
-    let client = fleet_infra::net::default_http_client().unwrap();
+    let client = fleet_infra::net::default_http_client(None).unwrap();
     let engine = default_engine(client);
     let req = SyncRequest {
         repo_url: String::new(),
+        extra_repo_urls: vec![],
         local_root: root.clone(),
         mode: SyncMode::MetadataOnly,
         options: SyncOptions::default(),
         profile_id: None,
     };
 
-    let local_state = engine.scan_local_state(&req, None).await.unwrap();
+    let local_state = engine.scan_local_state(&req, None, None).await.unwrap();
     let err = engine
         .compute_local_integrity_plan(&req, &local_state)
         .unwrap_err();
@@ -0,0 +1,82 @@
+use axum::{routing::get, Router};
+use fleet_pipeline::sync::{default_engine, SyncMode, SyncOptions, SyncRequest};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tempfile::tempdir;
+
+const HELLO_FILE: &str = "F872A18EB88181EB00816510E762FEE6";
+const HELLO_PART: &str = "5D41402ABC4B2A76B9719D911017C592";
+
+fn srf_file(path: &str) -> String {
+    format!(
+        r#"{{"Path":"{path}","Length":5,"Checksum":"{HELLO_FILE}","Type":"SwiftyFile","Parts":[{{"Path":"{path}_5","Length":5,"Start":0,"Checksum":"{HELLO_PART}"}}]}}"#
+    )
+}
+
+#[tokio::test]
+async fn matching_files_are_copied_from_the_local_source_and_the_rest_downloaded() {
+    let repo = r#"{"repoName":"r","checksum":"","requiredMods":[{"modName":"@m","checksum":"MOD","enabled":true}],"optionalMods":[]}"#;
+    let srf = format!(
+        r#"{{"Name":"@m","Checksum":"MOD","Files":[{},{},{}]}}"#,
+        srf_file("good.bin"),
+        srf_file("stale.bin"),
+        srf_file("missing.bin")
+    );
+    let downloads = Arc::new(AtomicUsize::new(0));
+    let counter = downloads.clone();
+    let app = Router::new()
+        .route("/repo.json", get(move || async move { repo }))
+        .route("/@m/mod.srf", get(move || async move { srf }))
+        .route(
+            "/@m/:file",
+            get(move || async move {
+                counter.fetch_add(1, Ordering::SeqCst);
+                "hello"
+            }),
+        );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    // The other install has one good copy and one of the right size but wrong content.
+    let source = tempdir().unwrap();
+    std::fs::create_dir_all(source.path().join("@m")).unwrap();
+    std::fs::write(source.path().join("@m/good.bin"), "hello").unwrap();
+    std::fs::write(source.path().join("@m/stale.bin"), "jello").unwrap();
+
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let engine = default_engine(reqwest::Client::new());
+    let req = SyncRequest {
+        repo_url: format!("http://{addr}"),
+        extra_repo_urls: vec![],
+        local_root: root.clone(),
+        mode: SyncMode::FullRehash,
+        options: SyncOptions {
+            local_source: Some(
+                camino::Utf8PathBuf::from_path_buf(source.path().to_path_buf()).unwrap(),
+            ),
+            verify_downloads: false,
+            ..SyncOptions::default()
+        },
+        profile_id: None,
+    };
+
+    let res = engine.plan_and_execute(&req, None).await.unwrap();
+    assert_eq!(res.stats.files_planned_download, 3);
+    assert_eq!(res.stats.files_from_local_source, 1);
+    assert_eq!(downloads.load(Ordering::SeqCst), 2);
+    for name in ["good.bin", "stale.bin", "missing.bin"] {
+        assert_eq!(
+            std::fs::read_to_string(root.join("@m").join(name)).unwrap(),
+            "hello"
+        );
+    }
+    assert_eq!(
+        std::fs::read_to_string(source.path().join("@m/stale.bin")).unwrap(),
+        "jello",
+        "the source is only read from"
+    );
+}
@@ -0,0 +1,90 @@
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::{routing::get, Router};
+use fleet_persistence::{FleetDataStore, RedbFleetDataStore};
+use fleet_pipeline::sync::{default_engine, SyncError, SyncMode, SyncOptions, SyncRequest};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use tempfile::tempdir;
+
+const FILE_CHECKSUM: &str = "F872A18EB88181EB00816510E762FEE6";
+const PART_CHECKSUM: &str = "5D41402ABC4B2A76B9719D911017C592";
+// "world", so the two mods don't share content and both are downloaded.
+const WORLD_CHECKSUM: &str = "1373DF2F299DE0030C8449FE9D013BB8";
+const WORLD_PART_CHECKSUM: &str = "7D793037A0760186574B0282F2F435E7";
+
+#[tokio::test]
+async fn finished_mods_survive_an_interrupted_sync() {
+    let repo_json = r#"{"repoName":"split","checksum":"","requiredMods":[{"modName":"@done","checksum":"AAA","enabled":true},{"modName":"@broken","checksum":"AAA","enabled":true}],"optionalMods":[]}"#;
+    let srf = |mod_name: &str, file: &str, checksum: &str, part: &str| {
+        format!(
+            r#"{{"Name":"{mod_name}","Checksum":"AAA","Files":[{{"Path":"{file}","Length":5,"Checksum":"{checksum}","Type":"SwiftyFile","Parts":[{{"Path":"{file}_5","Length":5,"Start":0,"Checksum":"{part}"}}]}}]}}"#
+        )
+    };
+    let srf_done = srf("@done", "a.txt", FILE_CHECKSUM, PART_CHECKSUM);
+    let srf_broken = srf("@broken", "b.txt", WORLD_CHECKSUM, WORLD_PART_CHECKSUM);
+
+    let broken_available = Arc::new(AtomicBool::new(false));
+    let done_requests = Arc::new(AtomicUsize::new(0));
+    let (broken_ref, done_ref) = (broken_available.clone(), done_requests.clone());
+    let app = Router::new()
+        .route("/repo.json", get(move || async move { repo_json }))
+        .route("/@done/mod.srf", get(move || async move { srf_done }))
+        .route("/@broken/mod.srf", get(move || async move { srf_broken }))
+        .route(
+            "/@done/a.txt",
+            get(move || async move {
+                done_ref.fetch_add(1, Ordering::SeqCst);
+                b"hello".to_vec()
+            }),
+        )
+        .route(
+            "/@broken/b.txt",
+            get(move || async move {
+                if broken_ref.load(Ordering::SeqCst) {
+                    b"world".to_vec().into_response()
+                } else {
+                    StatusCode::SERVICE_UNAVAILABLE.into_response()
+                }
+            }),
+        );
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let engine = default_engine(reqwest::Client::new());
+    let mut req = SyncRequest {
+        repo_url: format!("http://{addr}"),
+        extra_repo_urls: vec![],
+        local_root: root.clone(),
+        mode: SyncMode::MetadataOnly,
+        options: SyncOptions::default(),
+        profile_id: None,
+    };
+
+    // The sync never reaches its final commit.
+    match engine.plan_and_execute(&req, None).await {
+        Err(SyncError::Downloads(failures)) => assert_eq!(failures.len(), 1),
+        other => panic!("expected a download failure, got {other:?}"),
+    }
+    let baseline = RedbFleetDataStore.load_baseline_manifest(&root).unwrap();
+    let names: Vec<&str> = baseline.mods.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(names, vec!["@done"]);
+
+    broken_available.store(true, Ordering::SeqCst);
+    req.mode = SyncMode::FastCheck;
+    let resumed = engine.plan_and_execute(&req, None).await.unwrap();
+    assert_eq!(resumed.stats.files_planned_download, 1);
+    assert_eq!(resumed.plan.downloads[0].mod_name, "@broken");
+    assert_eq!(done_requests.load(Ordering::SeqCst), 1);
+
+    let clean = engine.plan(&req).await.unwrap();
+    assert!(clean.downloads.is_empty() && clean.deletes.is_empty());
+
+    server.abort();
+}
@@ -0,0 +1,95 @@
+use axum::{routing::get, Router};
+use fleet_pipeline::sync::{default_engine, SyncError, SyncMode, SyncOptions, SyncRequest};
+use std::net::SocketAddr;
+use tempfile::tempdir;
+
+const FILE_CHECKSUM: &str = "F872A18EB88181EB00816510E762FEE6";
+const PART_CHECKSUM: &str = "5D41402ABC4B2A76B9719D911017C592";
+
+/// Serves a repository containing a single mod with one `file.txt` ("hello").
+async fn start_repo(mod_name: &'static str) -> (SocketAddr, tokio::task::JoinHandle<()>) {
+    let repo_json = format!(
+        r#"{{"repoName":"{mod_name}","checksum":"","requiredMods":[{{"modName":"{mod_name}","checksum":"AAA","enabled":true}}],"optionalMods":[]}}"#
+    );
+    let srf = format!(
+        r#"{{"Name":"{mod_name}","Checksum":"AAA","Files":[{{"Path":"file.txt","Length":5,"Checksum":"{FILE_CHECKSUM}","Type":"SwiftyFile","Parts":[{{"Path":"file.txt_5","Length":5,"Start":0,"Checksum":"{PART_CHECKSUM}"}}]}}]}}"#
+    );
+
+    let app = Router::new()
+        .route("/repo.json", get(move || async move { repo_json }))
+        .route(
+            &format!("/{mod_name}/mod.srf"),
+            get(move || async move { srf }),
+        )
+        .route(
+            &format!("/{mod_name}/file.txt"),
+            get(|| async { b"hello".to_vec() }),
+        );
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let handle = tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    (addr, handle)
+}
+
+#[tokio::test]
+async fn mods_from_each_repo_are_downloaded_from_their_origin() {
+    let (base_addr, base_server) = start_repo("@base").await;
+    let (mission_addr, mission_server) = start_repo("@missions").await;
+
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let engine = default_engine(reqwest::Client::new());
+
+    let req = SyncRequest {
+        repo_url: format!("http://{base_addr}"),
+        extra_repo_urls: vec![format!("http://{mission_addr}/repo.json")],
+        local_root: root.clone(),
+        mode: SyncMode::MetadataOnly,
+        options: SyncOptions::default(),
+        profile_id: None,
+    };
+
+    let fetched = engine.fetch_remote_state(&req).await.unwrap();
+    assert_eq!(fetched.stats.mods_total, 2);
+
+    let result = engine.plan_and_execute(&req, None).await.unwrap();
+    assert!(result.executed);
+    assert_eq!(result.stats.files_planned_download, 2);
+    for mod_name in ["@base", "@missions"] {
+        let contents = std::fs::read(root.join(mod_name).join("file.txt")).unwrap();
+        assert_eq!(contents, b"hello");
+    }
+
+    base_server.abort();
+    mission_server.abort();
+}
+
+#[tokio::test]
+async fn mod_name_collision_across_repos_is_rejected() {
+    let (first_addr, first_server) = start_repo("@shared").await;
+    let (second_addr, second_server) = start_repo("@shared").await;
+
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let engine = default_engine(reqwest::Client::new());
+
+    let req = SyncRequest {
+        repo_url: format!("http://{first_addr}"),
+        extra_repo_urls: vec![format!("http://{second_addr}")],
+        local_root: root,
+        mode: SyncMode::MetadataOnly,
+        options: SyncOptions::default(),
+        profile_id: None,
+    };
+
+    match engine.fetch_remote_state(&req).await {
+        Err(SyncError::Remote(msg)) => assert!(msg.contains("@shared"), "got: {msg}"),
+        other => panic!("expected collision error, got {other:?}"),
+    }
+
+    first_server.abort();
+    second_server.abort();
+}
@@ -0,0 +1,61 @@
+use axum::{routing::get, Router};
+use fleet_pipeline::sync::{default_engine, SyncError, SyncMode, SyncOptions, SyncRequest};
+use std::fs;
+use tempfile::tempdir;
+
+const FILE_CHECKSUM: &str = "F872A18EB88181EB00816510E762FEE6";
+const PART_CHECKSUM: &str = "5D41402ABC4B2A76B9719D911017C592";
+
+#[tokio::test]
+async fn offline_plans_use_the_cached_manifest() {
+    let repo_json = r#"{"repoName":"test","checksum":"","requiredMods":[{"modName":"@base","checksum":"AAA","enabled":true}],"optionalMods":[]}"#;
+    let srf = format!(
+        r#"{{"Name":"@base","Checksum":"AAA","Files":[{{"Path":"file.txt","Length":5,"Checksum":"{FILE_CHECKSUM}","Type":"SwiftyFile","Parts":[{{"Path":"file.txt_5","Length":5,"Start":0,"Checksum":"{PART_CHECKSUM}"}}]}}]}}"#
+    );
+    let app = Router::new()
+        .route("/repo.json", get(move || async move { repo_json }))
+        .route("/@base/mod.srf", get(move || async move { srf }))
+        .route("/@base/file.txt", get(|| async { b"hello".to_vec() }));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let engine = default_engine(reqwest::Client::new());
+    let mut req = SyncRequest {
+        repo_url: format!("http://{addr}"),
+        extra_repo_urls: vec![],
+        local_root: root.clone(),
+        mode: SyncMode::MetadataOnly,
+        options: SyncOptions::default(),
+        profile_id: None,
+    };
+
+    // Offline before any sync: nothing to fall back on.
+    req.options.offline = true;
+    match engine.fetch_remote_state(&req).await {
+        Err(SyncError::Remote(msg)) => assert!(msg.contains("offline"), "got: {msg}"),
+        other => panic!("expected missing cache error, got {other:?}"),
+    }
+
+    req.options.offline = false;
+    engine.plan_and_execute(&req, None).await.unwrap();
+    server.abort();
+
+    req.options.offline = true;
+    req.mode = SyncMode::SmartVerify;
+    let fetched = engine.fetch_remote_state(&req).await.unwrap();
+    assert_eq!(fetched.stats.mods_total, 1);
+    assert_eq!(fetched.stats.mods_fetched, 0);
+    let plan = engine.plan(&req).await.unwrap();
+    assert!(plan.downloads.is_empty());
+
+    fs::remove_file(root.join("@base/file.txt")).unwrap();
+    match engine.plan_and_execute(&req, None).await {
+        Err(SyncError::Execution(msg)) => assert!(msg.contains("offline"), "got: {msg}"),
+        other => panic!("expected offline download refusal, got {other:?}"),
+    }
+}
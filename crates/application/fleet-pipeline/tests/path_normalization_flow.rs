@@ -18,7 +18,7 @@ fn windows_style_mod_srf(file_checksum: &str) -> String {
 fn repo_json() -> String {
     r#"{
         "repoName": "windows_test",
-        "checksum": "REPO_HASH",
+        "checksum": "",
         "requiredMods": [{"modName": "@win_mod", "checksum": "MOD_HASH", "enabled": true}],
         "optionalMods": []
     }"#
@@ -104,6 +104,7 @@ async fn sync_normalizes_paths_preventing_check_loop() {
     println!("--- Step 1: Execute Sync ---");
     let sync_req = SyncRequest {
         repo_url: base_url.clone(),
+        extra_repo_urls: vec![],
         local_root: root.clone(),
         mode: SyncMode::FullRehash, // Force full download/verify
         options: SyncOptions::default(),
@@ -134,6 +135,7 @@ async fn sync_normalizes_paths_preventing_check_loop() {
     println!("--- Step 2: Fast Check ---");
     let check_req = SyncRequest {
         repo_url: base_url,
+        extra_repo_urls: vec![],
         local_root: root.clone(),
         mode: SyncMode::FastCheck,
         options: SyncOptions::default(),
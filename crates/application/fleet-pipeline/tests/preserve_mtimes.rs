@@ -0,0 +1,113 @@
+use axum::{routing::get, Router};
+use fleet_core::{DownloadAction, SyncPlan};
+use fleet_persistence::{FleetDataStore, RedbFleetDataStore};
+use fleet_pipeline::sync::{default_engine, SyncMode, SyncOptions, SyncRequest};
+use tempfile::tempdir;
+
+const HELLO_FILE: &str = "F872A18EB88181EB00816510E762FEE6";
+
+const HELLO_PART: &str = "5D41402ABC4B2A76B9719D911017C592";
+
+const UPSTREAM_MTIME: u64 = 1_600_000_000;
+
+async fn start_server() -> String {
+    let repo = r#"{"repoName":"r","checksum":"","requiredMods":[{"modName":"@m","checksum":"MOD","enabled":true}],"optionalMods":[]}"#;
+    let srf = format!(
+        r#"{{"Name":"@m","Checksum":"MOD","Files":[{{"Path":"stamped.txt","Length":5,"Checksum":"{HELLO_FILE}","Type":"SwiftyFile","Parts":[{{"Path":"stamped.txt_5","Length":5,"Start":0,"Checksum":"{HELLO_PART}"}}],"Mtime":{UPSTREAM_MTIME}}},{{"Path":"unstamped.txt","Length":5,"Checksum":"{HELLO_FILE}","Type":"SwiftyFile","Parts":[{{"Path":"unstamped.txt_5","Length":5,"Start":0,"Checksum":"{HELLO_PART}"}}]}}]}}"#
+    );
+    let app = Router::new()
+        .route("/repo.json", get(move || async move { repo }))
+        .route("/@m/mod.srf", get(move || async move { srf }))
+        .route("/@m/stamped.txt", get(|| async { "hello" }))
+        .route("/@m/unstamped.txt", get(|| async { "hello" }));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{addr}")
+}
+
+fn download(rel_path: &str, mtime: Option<u64>) -> DownloadAction {
+    DownloadAction {
+        mod_name: "@m".into(),
+        rel_path: rel_path.into(),
+        size: 5,
+        expected_checksum: HELLO_FILE.into(),
+        mtime,
+        parts: Vec::new(),
+    }
+}
+
+fn request(repo_url: String, root: camino::Utf8PathBuf, preserve_mtimes: bool) -> SyncRequest {
+    SyncRequest {
+        repo_url,
+        extra_repo_urls: vec![],
+        local_root: root,
+        mode: SyncMode::FullRehash,
+        options: SyncOptions {
+            preserve_mtimes,
+            ..SyncOptions::default()
+        },
+        profile_id: None,
+    }
+}
+
+fn mtime_of(path: camino::Utf8PathBuf) -> u64 {
+    let meta = std::fs::metadata(path).unwrap();
+    filetime::FileTime::from_last_modification_time(&meta).unix_seconds() as u64
+}
+
+#[tokio::test]
+async fn upstream_mtimes_are_stamped_and_recorded() {
+    let base = start_server().await;
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let engine = default_engine(reqwest::Client::new());
+    let plan = SyncPlan {
+        renames: vec![],
+        checks: vec![],
+        deletes: vec![],
+        downloads: vec![
+            download("stamped.txt", Some(UPSTREAM_MTIME)),
+            download("unstamped.txt", None),
+        ],
+    };
+
+    engine
+        .execute_with_plan(&request(base, root.clone(), true), plan, None)
+        .await
+        .unwrap();
+
+    assert_eq!(mtime_of(root.join("@m/stamped.txt")), UPSTREAM_MTIME);
+    assert!(mtime_of(root.join("@m/unstamped.txt")) > UPSTREAM_MTIME);
+
+    let summary = RedbFleetDataStore.load_baseline_summary(&root).unwrap();
+    let recorded = summary
+        .iter()
+        .flat_map(|m| &m.files)
+        .find(|f| f.rel_path == "stamped.txt")
+        .unwrap();
+    assert_eq!(recorded.mtime, UPSTREAM_MTIME);
+}
+
+#[tokio::test]
+async fn download_time_is_used_when_preservation_is_off() {
+    let base = start_server().await;
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let engine = default_engine(reqwest::Client::new());
+    let plan = SyncPlan {
+        renames: vec![],
+        checks: vec![],
+        deletes: vec![],
+        downloads: vec![download("stamped.txt", Some(UPSTREAM_MTIME))],
+    };
+
+    engine
+        .execute_with_plan(&request(base, root.clone(), false), plan, None)
+        .await
+        .unwrap();
+
+    assert!(mtime_of(root.join("@m/stamped.txt")) > UPSTREAM_MTIME);
+}
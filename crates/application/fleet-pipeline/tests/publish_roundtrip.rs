@@ -0,0 +1,97 @@
+use fleet_pipeline::publish::write_repository;
+use fleet_pipeline::sync::{default_engine, SyncMode, SyncOptions, SyncRequest};
+use fleet_scanner::{ScanStrategy, Scanner};
+use tempfile::tempdir;
+
+#[tokio::test]
+async fn published_repository_syncs_without_diffs() {
+    let source = tempdir().unwrap();
+    let source_root = camino::Utf8PathBuf::from_path_buf(source.path().to_path_buf()).unwrap();
+    for (rel, content) in [
+        ("@alpha/addons/a.txt", "hello".repeat(3)),
+        ("@alpha/readme.md", "alpha".to_string()),
+        ("@beta/keys/beta.bikey", "key".to_string()),
+    ] {
+        let path = source_root.join(rel);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    let manifest =
+        Scanner::scan_directory(&source_root, ScanStrategy::ForceRehash, None, None, None).unwrap();
+    let first = write_repository(&source_root, "roundtrip", &manifest).unwrap();
+    let repo = first.repo;
+    assert_eq!(repo.required_mods.len(), 2);
+    assert_eq!(first.rewritten, vec!["@alpha", "@beta"]);
+    assert!(source_root.join("@alpha/mod.srf").is_file());
+    let srf: fleet_core::Mod =
+        serde_json::from_slice(&std::fs::read(source_root.join("@alpha/mod.srf")).unwrap())
+            .unwrap();
+    let readme_mtime =
+        Scanner::mtime(&std::fs::metadata(source_root.join("@alpha/readme.md")).unwrap());
+    let readme = srf.files.iter().find(|f| f.path == "readme.md").unwrap();
+    assert_eq!(readme.mtime, Some(readme_mtime));
+
+    // The written metadata is not picked up as mod content by a later publish.
+    let rescanned =
+        Scanner::scan_directory(&source_root, ScanStrategy::ForceRehash, None, None, None).unwrap();
+    let republished = write_repository(&source_root, "roundtrip", &rescanned).unwrap();
+    assert_eq!(republished.repo.checksum, repo.checksum);
+    assert!(republished.rewritten.is_empty());
+
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let engine = default_engine(reqwest::Client::new());
+    let mut req = SyncRequest {
+        repo_url: reqwest::Url::from_directory_path(source.path())
+            .unwrap()
+            .to_string(),
+        extra_repo_urls: vec![],
+        local_root: root.clone(),
+        mode: SyncMode::MetadataOnly,
+        options: SyncOptions::default(),
+        profile_id: None,
+    };
+
+    let fetched = engine.fetch_remote_state(&req).await.unwrap();
+    assert_eq!(fetched.repo_checksum, repo.checksum);
+    let result = engine.plan_and_execute(&req, None).await.unwrap();
+    assert_eq!(result.stats.files_planned_download, 3);
+    let synced = std::fs::metadata(root.join("@alpha/readme.md")).unwrap();
+    assert_eq!(Scanner::mtime(&synced), readme_mtime);
+
+    req.mode = SyncMode::FullRehash;
+    let clean = engine.plan(&req).await.unwrap();
+    assert!(clean.downloads.is_empty() && clean.deletes.is_empty());
+}
+
+#[test]
+fn republish_rewrites_only_changed_mods() {
+    let source = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(source.path().to_path_buf()).unwrap();
+    for rel in ["@alpha/a.txt", "@beta/b.txt"] {
+        std::fs::create_dir_all(root.join(rel).parent().unwrap()).unwrap();
+        std::fs::write(root.join(rel), "v1").unwrap();
+    }
+    let scan = || Scanner::scan_directory(&root, ScanStrategy::ForceRehash, None, None, None);
+    let first = write_repository(&root, "inc", &scan().unwrap()).unwrap();
+
+    std::fs::write(root.join("@alpha/a.txt"), "v2").unwrap();
+    // Mark the untouched mod's metadata so a rewrite would be noticed.
+    let beta_srf = root.join("@beta/mod.srf");
+    let beta_before = std::fs::read(&beta_srf).unwrap();
+    std::fs::write(&beta_srf, [beta_before.as_slice(), b"\n"].concat()).unwrap();
+
+    let second = write_repository(&root, "inc", &scan().unwrap()).unwrap();
+    assert_eq!(second.rewritten, vec!["@alpha"]);
+    assert_eq!(second.unchanged, vec!["@beta"]);
+    assert_ne!(second.repo.checksum, first.repo.checksum);
+    assert_eq!(
+        std::fs::read(&beta_srf).unwrap(),
+        [beta_before.as_slice(), b"\n"].concat()
+    );
+    let srf = std::fs::read(root.join("@alpha/mod.srf")).unwrap();
+    let alpha = fleet_core::formats::parse_srf(&srf).unwrap();
+    let repo_alpha = &second.repo.required_mods[0];
+    assert_eq!(alpha.checksum, repo_alpha.checksum);
+}
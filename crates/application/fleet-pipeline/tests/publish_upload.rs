@@ -0,0 +1,132 @@
+use axum::body::Bytes;
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, Method, StatusCode};
+use axum::{routing::any, Router};
+use fleet_pipeline::publish::{
+    fetch_published, plan_upload, upload_changes, write_repository, HttpPutPublisher,
+};
+use fleet_scanner::{ScanStrategy, Scanner};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tempfile::tempdir;
+
+#[derive(Default)]
+struct Remote {
+    files: HashMap<String, Vec<u8>>,
+    /// `PUT path` / `DELETE path` in arrival order.
+    log: Vec<String>,
+    auth: Vec<Option<String>>,
+}
+
+type Shared = Arc<Mutex<Remote>>;
+
+/// A WebDAV-ish host: serves what was PUT, forgets what was DELETEd.
+async fn dav(
+    State(remote): State<Shared>,
+    method: Method,
+    Path(path): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> (StatusCode, Vec<u8>) {
+    let mut remote = remote.lock().unwrap();
+    if method == Method::GET {
+        return match remote.files.get(&path) {
+            Some(bytes) => (StatusCode::OK, bytes.clone()),
+            None => (StatusCode::NOT_FOUND, Vec::new()),
+        };
+    }
+    let auth = headers
+        .get("authorization")
+        .and_then(|h| h.to_str().ok())
+        .map(str::to_string);
+    remote.auth.push(auth);
+    remote.log.push(format!("{method} {path}"));
+    if method == Method::PUT {
+        remote.files.insert(path, body.to_vec());
+    } else if method == Method::DELETE {
+        remote.files.remove(&path);
+    }
+    (StatusCode::OK, Vec::new())
+}
+
+#[tokio::test]
+async fn uploads_diff_against_the_remote_and_delete_what_it_no_longer_lists() {
+    let remote: Shared = Arc::default();
+    let app = Router::new()
+        .route("/repo/*path", any(dav))
+        .with_state(remote.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    let url = format!("http://{addr}/repo");
+
+    let source = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(source.path().to_path_buf()).unwrap();
+    for rel in ["@alpha/a.txt", "@alpha/same.txt", "@beta/b.txt"] {
+        std::fs::create_dir_all(root.join(rel).parent().unwrap()).unwrap();
+        std::fs::write(root.join(rel), "v1").unwrap();
+    }
+    let scan = || Scanner::scan_directory(&root, ScanStrategy::ForceRehash, None, None, None);
+    let config = fleet_infra::NetConfig::default().with_bearer_token("secret");
+    let client = fleet_infra::net::default_http_client(Some(&config)).unwrap();
+    let publisher = HttpPutPublisher::new(client.clone(), &url, None).unwrap();
+
+    // An empty remote gets everything.
+    let manifest = scan().unwrap();
+    write_repository(&root, "up", &manifest).unwrap();
+    let published = fetch_published(client.clone(), &url).await.unwrap();
+    assert!(published.mods.is_empty());
+    let plan = plan_upload(&manifest, &published);
+    assert_eq!(plan.puts.len(), 6);
+    assert!(plan.deletes.is_empty());
+    upload_changes(&root, &plan, &publisher).await.unwrap();
+
+    // The remote is the reference, so a stale local repo.json cannot hide a change.
+    std::fs::write(root.join("@alpha/a.txt"), "v2").unwrap();
+    std::fs::remove_dir_all(root.join("@beta")).unwrap();
+    std::fs::remove_file(root.join("repo.json")).unwrap();
+    remote.lock().unwrap().log.clear();
+    let manifest = scan().unwrap();
+    write_repository(&root, "up", &manifest).unwrap();
+    let published = fetch_published(client.clone(), &url).await.unwrap();
+    let plan = plan_upload(&manifest, &published);
+    upload_changes(&root, &plan, &publisher).await.unwrap();
+
+    let remote = remote.lock().unwrap();
+    assert_eq!(
+        remote.log,
+        vec![
+            "PUT @alpha/a.txt",
+            "PUT @alpha/mod.srf",
+            "PUT repo.json",
+            "DELETE @beta/b.txt",
+            "DELETE @beta/mod.srf",
+        ]
+    );
+    assert_eq!(remote.files["@alpha/a.txt"], b"v2");
+    assert_eq!(
+        remote.files["repo.json"],
+        std::fs::read(root.join("repo.json")).unwrap()
+    );
+    assert!(!remote.files.contains_key("@beta/b.txt"));
+    assert!(remote
+        .auth
+        .iter()
+        .all(|auth| auth.as_deref() == Some("Bearer secret")));
+
+    server.abort();
+}
+
+#[test]
+fn nothing_is_uploaded_when_the_remote_already_matches() {
+    let source = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(source.path().to_path_buf()).unwrap();
+    std::fs::create_dir_all(root.join("@alpha")).unwrap();
+    std::fs::write(root.join("@alpha/a.txt"), "v1").unwrap();
+    let manifest =
+        Scanner::scan_directory(&root, ScanStrategy::ForceRehash, None, None, None).unwrap();
+
+    assert_eq!(plan_upload(&manifest, &manifest), Default::default());
+}
@@ -0,0 +1,55 @@
+use axum::{routing::get, Router};
+use fleet_persistence::{FleetDataStore, RedbFleetDataStore};
+use fleet_pipeline::sync::{default_engine, SyncMode, SyncOptions, SyncRequest};
+use tempfile::tempdir;
+
+async fn start_server() -> String {
+    let repo = r#"{"repoName":"r","checksum":"","requiredMods":[{"modName":"@Core","checksum":"C1","enabled":true}],"optionalMods":[{"modName":"@extra","checksum":"E2","enabled":true},{"modName":"@unused","checksum":"U1","enabled":true}]}"#;
+    let app = Router::new().route("/repo.json", get(move || async move { repo }));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{addr}")
+}
+
+fn installed(name: &str, checksum: &str) -> fleet_core::Mod {
+    fleet_core::Mod {
+        name: name.into(),
+        checksum: checksum.into(),
+        files: Vec::new(),
+    }
+}
+
+#[tokio::test]
+async fn installed_optional_mods_are_compared_and_names_ignore_case() {
+    let base = start_server().await;
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let baseline = fleet_core::Manifest::new(vec![
+        installed("@core", "C1"),
+        installed("@extra", "E1"),
+        installed("@gone", "G1"),
+    ]);
+    RedbFleetDataStore
+        .commit_sync_snapshot(&root, &baseline, &[], &[], &[], &[])
+        .unwrap();
+
+    let req = SyncRequest {
+        repo_url: base,
+        extra_repo_urls: vec![],
+        local_root: root,
+        mode: SyncMode::CacheOnly,
+        options: SyncOptions::default(),
+        profile_id: None,
+    };
+    let check = default_engine(reqwest::Client::new())
+        .quick_check(&req)
+        .await
+        .unwrap();
+
+    assert_eq!(check.changed, vec!["@extra"]);
+    assert!(check.added.is_empty(), "{check:?}");
+    assert_eq!(check.removed, vec!["@gone"]);
+}
@@ -0,0 +1,91 @@
+use axum::extract::Path;
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::IntoResponse;
+use axum::{routing::get, Router};
+use fleet_infra::net::default_http_client;
+use fleet_infra::{NetConfig, RepoAuth};
+use fleet_pipeline::sync::{default_engine, SyncError, SyncMode, SyncOptions, SyncRequest};
+use tempfile::tempdir;
+
+const FILE_CHECKSUM: &str = "F872A18EB88181EB00816510E762FEE6";
+const PART_CHECKSUM: &str = "5D41402ABC4B2A76B9719D911017C592";
+// base64("user:pass")
+const EXPECTED_AUTH: &str = "Basic dXNlcjpwYXNz";
+
+/// Serves `body` only to requests carrying the expected basic auth header.
+fn guarded(headers: &HeaderMap, body: Vec<u8>) -> impl IntoResponse {
+    let authorized = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        == Some(EXPECTED_AUTH);
+    if authorized {
+        (StatusCode::OK, body)
+    } else {
+        (StatusCode::UNAUTHORIZED, Vec::new())
+    }
+}
+
+#[tokio::test]
+async fn credentials_are_sent_to_metadata_and_file_requests() {
+    let repo_json = r#"{"repoName":"private","checksum":"","requiredMods":[{"modName":"@private","checksum":"AAA","enabled":true}],"optionalMods":[]}"#;
+    let srf = format!(
+        r#"{{"Name":"@private","Checksum":"AAA","Files":[{{"Path":"file.txt","Length":5,"Checksum":"{FILE_CHECKSUM}","Type":"SwiftyFile","Parts":[{{"Path":"file.txt_5","Length":5,"Start":0,"Checksum":"{PART_CHECKSUM}"}}]}}]}}"#
+    );
+
+    let app = Router::new()
+        .route(
+            "/repo.json",
+            get(move |headers: HeaderMap| async move {
+                guarded(&headers, repo_json.as_bytes().to_vec())
+            }),
+        )
+        .route(
+            "/:mod_name/:file",
+            get(
+                move |Path((_, file)): Path<(String, String)>, headers: HeaderMap| async move {
+                    match file.as_str() {
+                        "mod.srf" => guarded(&headers, srf.clone().into_bytes()).into_response(),
+                        "file.txt" => guarded(&headers, b"hello".to_vec()).into_response(),
+                        _ => StatusCode::NOT_FOUND.into_response(),
+                    }
+                },
+            ),
+        );
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let req = SyncRequest {
+        repo_url: format!("http://{addr}"),
+        extra_repo_urls: vec![],
+        local_root: root.clone(),
+        mode: SyncMode::MetadataOnly,
+        options: SyncOptions::default(),
+        profile_id: None,
+    };
+
+    let anonymous = default_engine(default_http_client(None).unwrap());
+    assert!(matches!(
+        anonymous.fetch_remote_state(&req).await,
+        Err(SyncError::Remote(_))
+    ));
+
+    let config = NetConfig::default().with_repo_auth(&RepoAuth::Basic {
+        username: "user".into(),
+        password: "pass".into(),
+    });
+    let engine = default_engine(default_http_client(Some(&config)).unwrap());
+    let result = engine.plan_and_execute(&req, None).await.unwrap();
+    assert!(result.executed);
+    assert_eq!(
+        std::fs::read(root.join("@private").join("file.txt")).unwrap(),
+        b"hello"
+    );
+
+    server.abort();
+}
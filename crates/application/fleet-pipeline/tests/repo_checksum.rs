@@ -0,0 +1,108 @@
+use axum::{routing::get, Router};
+use fleet_core::repo::RepoMod;
+use fleet_pipeline::sync::{default_engine, SyncError, SyncMode, SyncOptions, SyncRequest};
+use tempfile::tempdir;
+
+/// Checksum of a repo whose only required mod is `@m` at `AAA`.
+fn repo_checksum() -> String {
+    fleet_infra::compute_listed_repo_checksum(&[RepoMod {
+        mod_name: "@m".into(),
+        checksum: "AAA".into(),
+        enabled: true,
+    }])
+}
+
+fn repo_json(checksum: &str) -> String {
+    format!(
+        r#"{{"repoName":"r","checksum":"{checksum}","requiredMods":[{{"modName":"@m","checksum":"AAA","enabled":true}}],"optionalMods":[]}}"#
+    )
+}
+
+async fn serve(repo_json: String) -> (String, tokio::task::JoinHandle<()>) {
+    let app = Router::new()
+        .route("/repo.json", get(move || async move { repo_json.clone() }))
+        .route(
+            "/@m/mod.srf",
+            get(|| async { r#"{"Name":"@m","Checksum":"AAA","Files":[]}"# }),
+        );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    (format!("http://{addr}"), server)
+}
+
+fn request(repo_url: String, root: camino::Utf8PathBuf) -> SyncRequest {
+    SyncRequest {
+        repo_url,
+        extra_repo_urls: vec![],
+        local_root: root,
+        mode: SyncMode::MetadataOnly,
+        options: SyncOptions::default(),
+        profile_id: None,
+    }
+}
+
+#[tokio::test]
+async fn repo_checksum_is_reported() {
+    let (url, server) = serve(repo_json(&repo_checksum())).await;
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let engine = default_engine(reqwest::Client::new());
+
+    let fetched = engine
+        .fetch_remote_state(&request(url, root))
+        .await
+        .unwrap();
+    assert_eq!(fetched.repo_checksum, repo_checksum());
+
+    server.abort();
+}
+
+#[tokio::test]
+async fn a_pinned_profile_refuses_to_plan_once_the_checksum_moves() {
+    let (url, server) = serve(repo_json(&repo_checksum())).await;
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let engine = default_engine(reqwest::Client::new());
+
+    let mut req = request(url, root);
+    req.options.pinned_repo_checksum = Some(repo_checksum().to_lowercase());
+    engine
+        .plan(&req)
+        .await
+        .expect("the pinned version still plans");
+
+    req.options.pinned_repo_checksum = Some("BEEF".into());
+    let err = engine.plan(&req).await.unwrap_err();
+    assert!(
+        matches!(
+            &err,
+            SyncError::PinnedVersion { pinned, live }
+                if pinned == "BEEF" && *live == repo_checksum()
+        ),
+        "{err:?}"
+    );
+
+    server.abort();
+}
+
+#[tokio::test]
+async fn a_repo_json_not_matching_its_own_checksum_is_rejected() {
+    let (url, server) = serve(repo_json("C0FFEE")).await;
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let engine = default_engine(reqwest::Client::new());
+
+    let err = engine
+        .fetch_remote_state(&request(url, root))
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(&err, SyncError::Remote(msg) if msg.contains("checksum mismatch")),
+        "{err:?}"
+    );
+
+    server.abort();
+}
@@ -0,0 +1,50 @@
+use axum::{routing::get, Router};
+use fleet_pipeline::sync::default_engine;
+
+fn srf(name: &str, lengths: &[u64]) -> String {
+    let files: Vec<String> = lengths
+        .iter()
+        .enumerate()
+        .map(|(i, len)| {
+            format!(
+                r#"{{"Path":"f{i}.bin","Length":{len},"Checksum":"AAA","Type":"SwiftyFile","Parts":[]}}"#
+            )
+        })
+        .collect();
+    format!(
+        r#"{{"Name":"{name}","Checksum":"AAA","Files":[{}]}}"#,
+        files.join(",")
+    )
+}
+
+#[tokio::test]
+async fn repo_size_sums_required_and_optionally_optional_mods() {
+    let repo_json = r#"{"repoName":"sized","checksum":"","requiredMods":[{"modName":"@req","checksum":"AAA","enabled":true}],"optionalMods":[{"modName":"@opt","checksum":"AAA","enabled":false}]}"#;
+    let req_srf = srf("@req", &[100, 23]);
+    let opt_srf = srf("@opt", &[1000]);
+    let app = Router::new()
+        .route("/repo.json", get(move || async move { repo_json }))
+        .route("/@req/mod.srf", get(move || async move { req_srf }))
+        .route("/@opt/mod.srf", get(move || async move { opt_srf }));
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let engine = default_engine(reqwest::Client::new());
+    let url = format!("http://{addr}");
+
+    let required = engine.repo_size(&url, false).await.unwrap();
+    assert_eq!(required.repo_name, "sized");
+    assert_eq!(
+        (required.mods, required.files, required.total_bytes),
+        (1, 2, 123)
+    );
+
+    let all = engine.repo_size(&url, true).await.unwrap();
+    assert_eq!((all.mods, all.files, all.total_bytes), (2, 3, 1123));
+
+    server.abort();
+}
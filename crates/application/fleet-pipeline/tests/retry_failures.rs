@@ -0,0 +1,135 @@
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::{routing::get, Router};
+use fleet_persistence::{FleetDataStore, RedbFleetDataStore};
+use fleet_pipeline::sync::{default_engine, SyncError, SyncMode, SyncOptions, SyncRequest};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use tempfile::tempdir;
+
+const FILE_CHECKSUM: &str = "F872A18EB88181EB00816510E762FEE6";
+const PART_CHECKSUM: &str = "5D41402ABC4B2A76B9719D911017C592";
+
+#[tokio::test]
+async fn only_failed_downloads_are_retried() {
+    let file = |name: &str| {
+        format!(
+            r#"{{"Path":"{name}","Length":5,"Checksum":"{FILE_CHECKSUM}","Type":"SwiftyFile","Parts":[{{"Path":"{name}_5","Length":5,"Start":0,"Checksum":"{PART_CHECKSUM}"}}]}}"#
+        )
+    };
+    let srf = format!(
+        r#"{{"Name":"@flaky","Checksum":"AAA","Files":[{},{}]}}"#,
+        file("good.txt"),
+        file("bad.txt")
+    );
+
+    let bad_available = Arc::new(AtomicBool::new(false));
+    let good_requests = Arc::new(AtomicUsize::new(0));
+    let (bad_ref, good_ref) = (bad_available.clone(), good_requests.clone());
+    // `@steady` syncs fine, then moves on upstream before the retry.
+    let steady = |moved_on: &AtomicBool| {
+        if moved_on.load(Ordering::SeqCst) {
+            "SSS2"
+        } else {
+            "SSS"
+        }
+    };
+    let (repo_ref, steady_ref) = (bad_available.clone(), bad_available.clone());
+    let repo_json = move || {
+        let json = format!(
+            r#"{{"repoName":"flaky","checksum":"","requiredMods":[{{"modName":"@flaky","checksum":"AAA","enabled":true}},{{"modName":"@steady","checksum":"{}","enabled":true}}],"optionalMods":[]}}"#,
+            steady(&repo_ref)
+        );
+        async move { json }
+    };
+    let steady_srf = move || {
+        let srf = format!(
+            r#"{{"Name":"@steady","Checksum":"{}","Files":[{}]}}"#,
+            steady(&steady_ref),
+            file("s.txt")
+        );
+        async move { srf }
+    };
+    let app = Router::new()
+        .route("/repo.json", get(repo_json))
+        .route("/@flaky/mod.srf", get(move || async move { srf }))
+        .route("/@steady/mod.srf", get(steady_srf))
+        .route("/@steady/s.txt", get(|| async { b"hello".to_vec() }))
+        .route(
+            "/@flaky/good.txt",
+            get(move || async move {
+                good_ref.fetch_add(1, Ordering::SeqCst);
+                b"hello".to_vec()
+            }),
+        )
+        .route(
+            "/@flaky/bad.txt",
+            get(move || async move {
+                if bad_ref.load(Ordering::SeqCst) {
+                    b"hello".to_vec().into_response()
+                } else {
+                    StatusCode::SERVICE_UNAVAILABLE.into_response()
+                }
+            }),
+        );
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let engine = default_engine(reqwest::Client::new());
+    let req = SyncRequest {
+        repo_url: format!("http://{addr}"),
+        extra_repo_urls: vec![],
+        local_root: root.clone(),
+        mode: SyncMode::MetadataOnly,
+        options: SyncOptions::default(),
+        profile_id: None,
+    };
+
+    let failures = match engine.plan_and_execute(&req, None).await {
+        Err(SyncError::Downloads(failures)) => failures,
+        other => panic!("expected a download failure, got {other:?}"),
+    };
+    assert_eq!(failures.len(), 1);
+    let action = failures[0].action();
+    assert_eq!(
+        (action.mod_name.as_str(), action.rel_path.as_str()),
+        ("@flaky", "bad.txt")
+    );
+    assert_eq!(action.size, 5);
+    assert_eq!(action.expected_checksum, FILE_CHECKSUM);
+    assert_eq!(failures[0].mod_checksum, "AAA");
+    assert_eq!(good_requests.load(Ordering::SeqCst), 1);
+
+    bad_available.store(true, Ordering::SeqCst);
+    // A mod that moved on since the failed sync is not retried file by file.
+    let mut stale = failures.clone();
+    stale[0].mod_checksum = "OLD".into();
+    match engine.retry_failures(&req, &stale, None).await {
+        Err(SyncError::Remote(msg)) => assert!(msg.contains("@flaky"), "{msg}"),
+        other => panic!("expected the retry to be refused, got {other:?}"),
+    }
+    let result = engine.retry_failures(&req, &failures, None).await.unwrap();
+    assert!(result.executed);
+    assert_eq!(result.stats.files_planned_download, 1);
+    assert_eq!(good_requests.load(Ordering::SeqCst), 1);
+    assert_eq!(
+        std::fs::read(root.join("@flaky/bad.txt")).unwrap(),
+        b"hello"
+    );
+    // Only the retried mod takes the repository's current state; `@steady` was not touched.
+    let baseline = RedbFleetDataStore.load_baseline_manifest(&root).unwrap();
+    let checksum = |name: &str| {
+        let m = baseline.mods.iter().find(|m| m.name == name).unwrap();
+        m.checksum.clone()
+    };
+    assert_eq!(checksum("@steady"), "SSS");
+    assert_eq!(checksum("@flaky"), "AAA");
+
+    server.abort();
+}
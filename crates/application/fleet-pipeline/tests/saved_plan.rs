@@ -0,0 +1,76 @@
+use axum::{routing::get, Router};
+use fleet_persistence::{FleetDataStore, RedbFleetDataStore};
+use fleet_pipeline::sync::{default_engine, SyncMode, SyncOptions, SyncRequest};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tempfile::tempdir;
+
+const FILE_CHECKSUM: &str = "F872A18EB88181EB00816510E762FEE6";
+const PART_CHECKSUM: &str = "5D41402ABC4B2A76B9719D911017C592";
+
+/// Serves `@m` with mod checksum `M1`, or `M2` once `moved_on` is set.
+async fn start_server(moved_on: Arc<AtomicBool>) -> String {
+    let checksum = move |flag: &AtomicBool| {
+        if flag.load(Ordering::SeqCst) {
+            "M2"
+        } else {
+            "M1"
+        }
+    };
+    let (repo_flag, srf_flag) = (moved_on.clone(), moved_on);
+    let repo = move || {
+        let json = format!(
+            r#"{{"repoName":"r","checksum":"","requiredMods":[{{"modName":"@m","checksum":"{}","enabled":true}}],"optionalMods":[]}}"#,
+            checksum(&repo_flag)
+        );
+        async move { json }
+    };
+    let srf = move || {
+        let srf = format!(
+            r#"{{"Name":"@m","Checksum":"{}","Files":[{{"Path":"a.txt","Length":5,"Checksum":"{FILE_CHECKSUM}","Type":"SwiftyFile","Parts":[{{"Path":"a.txt_5","Length":5,"Start":0,"Checksum":"{PART_CHECKSUM}"}}]}}]}}"#,
+            checksum(&srf_flag)
+        );
+        async move { srf }
+    };
+    let app = Router::new()
+        .route("/repo.json", get(repo))
+        .route("/@m/mod.srf", get(srf))
+        .route("/@m/a.txt", get(|| async { "hello" }));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{addr}")
+}
+
+#[tokio::test]
+async fn applying_a_saved_plan_records_the_manifest_it_was_computed_against() {
+    let moved_on = Arc::new(AtomicBool::new(false));
+    let base = start_server(moved_on.clone()).await;
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let engine = default_engine(reqwest::Client::new());
+    let req = SyncRequest {
+        repo_url: base,
+        extra_repo_urls: vec![],
+        local_root: root.clone(),
+        mode: SyncMode::FullRehash,
+        options: SyncOptions::default(),
+        profile_id: None,
+    };
+
+    let (plan, _, remote) = engine.plan_with_remote(&req).await.unwrap();
+    let plan_path = root.join("plan.json");
+    engine.save_plan(&req, &plan, &remote, &plan_path).unwrap();
+
+    // The repository moves on between saving and applying the plan.
+    moved_on.store(true, Ordering::SeqCst);
+    let saved = engine.load_plan(&req, &plan_path).unwrap();
+    let result = engine.execute_saved_plan(&req, saved, None).await.unwrap();
+
+    assert!(result.executed);
+    assert_eq!(std::fs::read(root.join("@m/a.txt")).unwrap(), b"hello");
+    let baseline = RedbFleetDataStore.load_baseline_manifest(&root).unwrap();
+    assert_eq!(baseline.mods[0].checksum, "M1");
+}
@@ -13,6 +13,7 @@ async fn execute_blocks_directory_traversal() {
 
     let req = SyncRequest {
         repo_url: "http://localhost".into(),
+        extra_repo_urls: vec![],
         local_root: root.clone(),
         mode: SyncMode::CacheOnly,
         options: SyncOptions::default(),
@@ -26,6 +27,8 @@ async fn execute_blocks_directory_traversal() {
             rel_path: "../../../etc/passwd".into(),
             size: 123,
             expected_checksum: "abc".into(),
+            mtime: None,
+            parts: Vec::new(),
         }],
         deletes: vec![],
         renames: vec![],
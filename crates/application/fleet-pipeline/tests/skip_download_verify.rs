@@ -0,0 +1,121 @@
+use axum::{routing::get, Router};
+use fleet_core::{DownloadAction, SyncPlan};
+use fleet_infra::DownloadError;
+use fleet_pipeline::sync::{default_engine, SyncError, SyncMode, SyncOptions, SyncRequest};
+use tempfile::tempdir;
+
+const HELLO_FILE: &str = "F872A18EB88181EB00816510E762FEE6";
+
+const HELLO_PART: &str = "5D41402ABC4B2A76B9719D911017C592";
+
+async fn start_server() -> String {
+    let repo = r#"{"repoName":"r","checksum":"","requiredMods":[{"modName":"@m","checksum":"MOD","enabled":true}],"optionalMods":[]}"#;
+    let srf = format!(
+        r#"{{"Name":"@m","Checksum":"MOD","Files":[{{"Path":"same_size.bin","Length":5,"Checksum":"{HELLO_FILE}","Type":"SwiftyFile","Parts":[{{"Path":"same_size.bin_5","Length":5,"Start":0,"Checksum":"{HELLO_PART}"}}]}}]}}"#
+    );
+    // Same size as "hello" but different bytes, and one that is too long.
+    let app = Router::new()
+        .route("/repo.json", get(move || async move { repo }))
+        .route("/@m/mod.srf", get(move || async move { srf }))
+        .route("/@m/same_size.bin", get(|| async { "HELLO" }))
+        .route("/@m/too_long.bin", get(|| async { "hello!" }));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{addr}")
+}
+
+fn plan_for(rel_path: &str) -> SyncPlan {
+    SyncPlan {
+        renames: vec![],
+        checks: vec![],
+        downloads: vec![DownloadAction {
+            mod_name: "@m".into(),
+            rel_path: rel_path.into(),
+            size: 5,
+            expected_checksum: HELLO_FILE.into(),
+            mtime: None,
+            parts: Vec::new(),
+        }],
+        deletes: vec![],
+    }
+}
+
+fn request(repo_url: String, root: camino::Utf8PathBuf, verify_downloads: bool) -> SyncRequest {
+    SyncRequest {
+        repo_url,
+        extra_repo_urls: vec![],
+        local_root: root,
+        mode: SyncMode::FullRehash,
+        options: SyncOptions {
+            verify_downloads,
+            ..SyncOptions::default()
+        },
+        profile_id: None,
+    }
+}
+
+fn failure_reason(err: SyncError) -> DownloadError {
+    let SyncError::Downloads(failed) = err else {
+        panic!("expected SyncError::Downloads, got {err:?}");
+    };
+    failed[0].reason.clone()
+}
+
+#[tokio::test]
+async fn checksums_are_verified_by_default() {
+    let base = start_server().await;
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let engine = default_engine(reqwest::Client::new());
+
+    let err = engine
+        .execute_with_plan(&request(base, root, true), plan_for("same_size.bin"), None)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(
+        failure_reason(err),
+        DownloadError::ChecksumMismatch { .. }
+    ));
+}
+
+#[tokio::test]
+async fn without_verification_only_the_size_is_checked() {
+    let base = start_server().await;
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let engine = default_engine(reqwest::Client::new());
+
+    engine
+        .execute_with_plan(
+            &request(base.clone(), root.clone(), false),
+            plan_for("same_size.bin"),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        std::fs::read(root.join("@m/same_size.bin")).unwrap(),
+        b"HELLO"
+    );
+
+    let err = engine
+        .execute_with_plan(
+            &request(base, root.clone(), false),
+            plan_for("too_long.bin"),
+            None,
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        failure_reason(err),
+        DownloadError::SizeMismatch {
+            expected: 5,
+            actual: 6
+        }
+    );
+    assert!(!root.join("@m/too_long.bin").exists());
+}
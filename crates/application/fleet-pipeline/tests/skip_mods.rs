@@ -0,0 +1,59 @@
+use axum::{routing::get, Router};
+use fleet_pipeline::sync::{default_engine, SyncMode, SyncOptions, SyncRequest};
+use tempfile::tempdir;
+
+const FILE_CHECKSUM: &str = "F872A18EB88181EB00816510E762FEE6";
+const PART_CHECKSUM: &str = "5D41402ABC4B2A76B9719D911017C592";
+
+fn srf(name: &str) -> String {
+    format!(
+        r#"{{"Name":"{name}","Checksum":"AAA","Files":[{{"Path":"file.txt","Length":5,"Checksum":"{FILE_CHECKSUM}","Type":"SwiftyFile","Parts":[{{"Path":"file.txt_5","Length":5,"Start":0,"Checksum":"{PART_CHECKSUM}"}}]}}]}}"#
+    )
+}
+
+#[tokio::test]
+async fn skipped_mods_are_neither_downloaded_nor_cleaned() {
+    let repo_json = r#"{"repoName":"two","checksum":"","requiredMods":[{"modName":"@keep","checksum":"AAA","enabled":true},{"modName":"@skip","checksum":"AAA","enabled":true}],"optionalMods":[]}"#;
+    let (keep_srf, skip_srf) = (srf("@keep"), srf("@skip"));
+    let app = Router::new()
+        .route("/repo.json", get(move || async move { repo_json }))
+        .route("/@keep/mod.srf", get(move || async move { keep_srf }))
+        .route("/@skip/mod.srf", get(move || async move { skip_srf }))
+        .route("/@keep/file.txt", get(|| async { b"hello".to_vec() }))
+        .route("/@skip/file.txt", get(|| async { b"hello".to_vec() }));
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    // A local edit inside the skipped mod that a normal sync would delete.
+    std::fs::create_dir_all(root.join("@skip")).unwrap();
+    std::fs::write(root.join("@skip/local.cfg"), b"mine").unwrap();
+
+    let engine = default_engine(reqwest::Client::new());
+    let req = SyncRequest {
+        repo_url: format!("http://{addr}"),
+        extra_repo_urls: vec![],
+        local_root: root.clone(),
+        mode: SyncMode::SmartVerify,
+        options: SyncOptions {
+            skip_mods: vec!["@SKIP".into()],
+            ..SyncOptions::default()
+        },
+        profile_id: None,
+    };
+
+    let result = engine.plan_and_execute(&req, None).await.unwrap();
+    assert_eq!(result.stats.files_planned_download, 1);
+    assert!(result.plan.downloads.iter().all(|d| d.mod_name == "@keep"));
+    assert!(result.plan.deletes.is_empty());
+    assert!(root.join("@keep/file.txt").exists());
+    assert!(!root.join("@skip/file.txt").exists());
+    assert!(root.join("@skip/local.cfg").exists());
+
+    server.abort();
+}
@@ -0,0 +1,67 @@
+use axum::extract::Path;
+use axum::{routing::get, Router};
+use fleet_pipeline::sync::{default_engine, SyncMode, SyncOptions, SyncRequest};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::tempdir;
+
+const MODS: usize = 12;
+
+#[tokio::test]
+async fn srf_fetches_respect_max_threads() {
+    let mods: Vec<String> = (0..MODS)
+        .map(|i| format!(r#"{{"modName":"@m{i}","checksum":"AAA","enabled":true}}"#))
+        .collect();
+    let repo_json = format!(
+        r#"{{"repoName":"many","checksum":"","requiredMods":[{}],"optionalMods":[]}}"#,
+        mods.join(",")
+    );
+
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+    let (in_flight_ref, peak_ref) = (in_flight.clone(), peak.clone());
+    let app = Router::new()
+        .route("/repo.json", get(move || async move { repo_json }))
+        .route(
+            "/:mod_name/mod.srf",
+            get(move |Path(mod_name): Path<String>| {
+                let (in_flight, peak) = (in_flight_ref.clone(), peak_ref.clone());
+                async move {
+                    let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                    format!(r#"{{"Name":"{mod_name}","Checksum":"AAA","Files":[]}}"#)
+                }
+            }),
+        );
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let engine = default_engine(reqwest::Client::new());
+    let req = SyncRequest {
+        repo_url: format!("http://{addr}"),
+        extra_repo_urls: vec![],
+        local_root: root,
+        mode: SyncMode::MetadataOnly,
+        options: SyncOptions {
+            max_threads: 2,
+            ..SyncOptions::default()
+        },
+        profile_id: None,
+    };
+
+    let fetched = engine.fetch_remote_state(&req).await.unwrap();
+    assert_eq!(fetched.stats.mods_total, MODS);
+    let peak = peak.load(Ordering::SeqCst);
+    assert!((1..=2).contains(&peak), "peak concurrency was {peak}");
+
+    server.abort();
+}
@@ -7,7 +7,7 @@ use tempfile::tempdir;
 fn tiny_repo_json() -> String {
     r#"{
         "repoName": "tiny",
-        "checksum": "AAA",
+        "checksum": "",
         "requiredMods": [{"modName": "@tiny", "checksum": "AAA", "enabled": true}],
         "optionalMods": []
     }"#
@@ -86,6 +86,7 @@ async fn metadata_sync_then_cache_only_is_noop() {
 
     let req = SyncRequest {
         repo_url: base_url.clone(),
+        extra_repo_urls: vec![],
         local_root: root.clone(),
         mode: SyncMode::MetadataOnly,
         options: SyncOptions::default(),
@@ -102,6 +103,7 @@ async fn metadata_sync_then_cache_only_is_noop() {
 
     let cache_req = SyncRequest {
         repo_url: base_url,
+        extra_repo_urls: vec![],
         local_root: root.clone(),
         mode: SyncMode::CacheOnly,
         options: SyncOptions::default(),
@@ -135,6 +137,7 @@ async fn full_rehash_sync_then_fast_check_is_noop() {
     // Full rehash + execute (equivalent to a full check followed by sync).
     let full_req = SyncRequest {
         repo_url: base_url.clone(),
+        extra_repo_urls: vec![],
         local_root: root.clone(),
         mode: SyncMode::FullRehash,
         options: SyncOptions::default(),
@@ -152,6 +155,7 @@ async fn full_rehash_sync_then_fast_check_is_noop() {
     // Fast check should now see a clean state and plan no work.
     let fast_req = SyncRequest {
         repo_url: base_url,
+        extra_repo_urls: vec![],
         local_root: root.clone(),
         mode: SyncMode::FastCheck,
         options: SyncOptions::default(),
@@ -1,8 +1,9 @@
 use axum::response::IntoResponse;
 use axum::{body::Body, routing::get, Router};
-use fleet_core::{DownloadAction, SyncPlan};
+use fleet_core::{DeleteAction, DownloadAction, SyncPlan, VerificationAction};
+use fleet_infra::DownloadError;
 use fleet_persistence::{FleetDataStore, RedbFleetDataStore};
-use fleet_pipeline::sync::{DefaultSyncEngine, SyncMode, SyncOptions, SyncRequest};
+use fleet_pipeline::sync::{DefaultSyncEngine, SyncError, SyncMode, SyncOptions, SyncRequest};
 use std::fs;
 use tempfile::tempdir;
 
@@ -52,6 +53,8 @@ async fn execute_sync_then_fast_check_is_clean() {
             rel_path: "file.txt".into(),
             size: 7, // "content".len()
             expected_checksum: expected_checksum.clone(),
+            mtime: None,
+            parts: Vec::new(),
         }],
         deletes: vec![],
         renames: vec![],
@@ -60,6 +63,7 @@ async fn execute_sync_then_fast_check_is_clean() {
 
     let req = SyncRequest {
         repo_url: repo_url.clone(),
+        extra_repo_urls: vec![],
         local_root: root.clone(),
         mode: SyncMode::FullRehash,
         options: SyncOptions::default(),
@@ -91,12 +95,16 @@ async fn execute_sync_then_fast_check_is_clean() {
 
     let req_fast = SyncRequest {
         repo_url: repo_url.clone(),
+        extra_repo_urls: vec![],
         local_root: root.clone(),
         mode: SyncMode::FastCheck,
         options: SyncOptions::default(),
         profile_id: None,
     };
-    let local_state = engine.scan_local_state(&req_fast, None).await.unwrap();
+    let local_state = engine
+        .scan_local_state(&req_fast, None, None)
+        .await
+        .unwrap();
 
     let mod_state = local_state.manifest.mods.first().unwrap();
     let file_state = mod_state.files.first().unwrap();
@@ -110,3 +118,167 @@ async fn execute_sync_then_fast_check_is_clean() {
         expected_checksum.to_ascii_uppercase()
     );
 }
+
+#[tokio::test]
+async fn failed_downloads_report_file_and_reason() {
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().into()).unwrap();
+
+    let app = Router::new()
+        .route(
+            "/@mod/missing.txt",
+            get(|| async { axum::http::StatusCode::NOT_FOUND }),
+        )
+        .route("/*path", get(|| async { Body::from("content") }));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let _server = tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let engine = DefaultSyncEngine::new(reqwest::Client::new());
+    let plan = SyncPlan {
+        downloads: vec![
+            DownloadAction {
+                mod_name: "@mod".into(),
+                rel_path: "missing.txt".into(),
+                size: 7,
+                expected_checksum: "00".into(),
+                mtime: None,
+                parts: Vec::new(),
+            },
+            DownloadAction {
+                mod_name: "@mod".into(),
+                rel_path: "corrupt.txt".into(),
+                size: 7,
+                expected_checksum: "00".into(),
+                mtime: None,
+                parts: Vec::new(),
+            },
+        ],
+        deletes: vec![],
+        renames: vec![],
+        checks: vec![],
+    };
+    let req = SyncRequest {
+        repo_url: format!("http://{addr}"),
+        extra_repo_urls: vec![],
+        local_root: root.clone(),
+        mode: SyncMode::FullRehash,
+        options: SyncOptions::default(),
+        profile_id: None,
+    };
+
+    let err = engine
+        .execute_with_plan(&req, plan, None)
+        .await
+        .expect_err("downloads should fail");
+    let SyncError::Downloads(mut failed) = err else {
+        panic!("expected SyncError::Downloads, got {err:?}");
+    };
+    failed.sort_by(|a, b| a.rel_path.cmp(&b.rel_path));
+
+    assert_eq!(failed.len(), 2);
+    assert_eq!(failed[0].rel_path, "corrupt.txt");
+    assert!(matches!(
+        failed[0].reason,
+        DownloadError::ChecksumMismatch { .. }
+    ));
+    assert_eq!(failed[1].rel_path, "missing.txt");
+    assert_eq!(failed[1].reason, DownloadError::Status(404));
+    assert!(!root.join("@mod").join("corrupt.txt").exists());
+}
+
+#[tokio::test]
+async fn verification_checks_rehash_listed_files() {
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().into()).unwrap();
+    let (addr, _server) = start_file_server(b"content".to_vec()).await;
+
+    fs::create_dir_all(root.join("@mod").as_std_path()).unwrap();
+    fs::write(root.join("@mod/ok.txt").as_std_path(), b"content").unwrap();
+    fs::write(root.join("@mod/bad.txt").as_std_path(), b"tampered").unwrap();
+    let expected_checksum = fleet_infra::hashing::compute_file_checksum(
+        &root.join("@mod/ok.txt"),
+        camino::Utf8Path::new("ok.txt"),
+    )
+    .unwrap();
+
+    let engine = DefaultSyncEngine::new(reqwest::Client::new());
+    let req = SyncRequest {
+        repo_url: format!("http://{addr}"),
+        extra_repo_urls: vec![],
+        local_root: root.clone(),
+        mode: SyncMode::FullRehash,
+        options: SyncOptions {
+            verify_checks: true,
+            ..SyncOptions::default()
+        },
+        profile_id: None,
+    };
+    let plan_checking = |paths: &[&str]| SyncPlan {
+        downloads: vec![],
+        deletes: vec![],
+        renames: vec![],
+        checks: paths
+            .iter()
+            .map(|p| VerificationAction {
+                path: p.to_string(),
+                expected_checksum: expected_checksum.clone(),
+            })
+            .collect(),
+    };
+
+    let result = engine
+        .execute_with_plan(&req, plan_checking(&["@mod/ok.txt"]), None)
+        .await
+        .unwrap();
+    assert!(result.executed);
+    assert_eq!(result.stats.files_verified, 1);
+
+    match engine
+        .execute_with_plan(&req, plan_checking(&["@mod/ok.txt", "@mod/bad.txt"]), None)
+        .await
+    {
+        Err(SyncError::Verification(failed)) => {
+            assert_eq!(failed.len(), 1);
+            assert_eq!(failed[0].path, "@mod/bad.txt");
+        }
+        other => panic!("expected verification failure, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn skip_deletes_preserves_files_even_if_the_plan_deletes_them() {
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().into()).unwrap();
+    let (addr, _server) = start_file_server(b"content".to_vec()).await;
+
+    fs::create_dir_all(root.join("@mod").as_std_path()).unwrap();
+    fs::write(root.join("@mod/mine.sqm").as_std_path(), b"personal").unwrap();
+
+    let engine = DefaultSyncEngine::new(reqwest::Client::new());
+    let req = SyncRequest {
+        repo_url: format!("http://{addr}"),
+        extra_repo_urls: vec![],
+        local_root: root.clone(),
+        mode: SyncMode::FullRehash,
+        options: SyncOptions {
+            skip_deletes: true,
+            ..SyncOptions::default()
+        },
+        profile_id: None,
+    };
+    let plan = SyncPlan {
+        downloads: vec![],
+        deletes: vec![DeleteAction {
+            path: "@mod/mine.sqm".into(),
+        }],
+        renames: vec![],
+        checks: vec![],
+    };
+
+    let result = engine.execute_with_plan(&req, plan, None).await.unwrap();
+    assert!(!result.executed);
+    assert!(root.join("@mod/mine.sqm").exists());
+}
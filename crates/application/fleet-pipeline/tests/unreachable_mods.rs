@@ -0,0 +1,100 @@
+use axum::{routing::get, Router};
+use fleet_pipeline::sync::{default_engine, SyncMode, SyncOptions, SyncRequest};
+use tempfile::tempdir;
+
+const HELLO_FILE: &str = "F872A18EB88181EB00816510E762FEE6";
+
+const HELLO_PART: &str = "5D41402ABC4B2A76B9719D911017C592";
+
+/// Lists `@ok` and `@broken`; only `@ok` has a `mod.srf`.
+async fn start_server() -> String {
+    let repo = r#"{"repoName":"r","checksum":"","requiredMods":[{"modName":"@ok","checksum":"OK","enabled":true},{"modName":"@broken","checksum":"BROKEN","enabled":true}],"optionalMods":[]}"#;
+    let srf = format!(
+        r#"{{"Name":"@ok","Checksum":"OK","Files":[{{"Path":"a.txt","Length":5,"Checksum":"{HELLO_FILE}","Type":"SwiftyFile","Parts":[{{"Path":"a.txt_5","Length":5,"Start":0,"Checksum":"{HELLO_PART}"}}]}}]}}"#
+    );
+    let app = Router::new()
+        .route("/repo.json", get(move || async move { repo }))
+        .route("/@ok/mod.srf", get(move || async move { srf }))
+        .route("/@ok/a.txt", get(|| async { "hello" }));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{addr}")
+}
+
+fn request(
+    repo_url: String,
+    root: camino::Utf8PathBuf,
+    skip_unreachable_mods: bool,
+) -> SyncRequest {
+    SyncRequest {
+        repo_url,
+        extra_repo_urls: vec![],
+        local_root: root,
+        mode: SyncMode::FullRehash,
+        options: SyncOptions {
+            skip_unreachable_mods,
+            ..SyncOptions::default()
+        },
+        profile_id: None,
+    }
+}
+
+#[tokio::test]
+async fn a_missing_srf_fails_the_fetch_by_default() {
+    let base = start_server().await;
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let engine = default_engine(reqwest::Client::new());
+
+    assert!(engine
+        .fetch_remote_state(&request(base, root, false))
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn unreachable_mods_are_reported_and_left_alone() {
+    let base = start_server().await;
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    std::fs::create_dir_all(root.join("@broken")).unwrap();
+    std::fs::write(root.join("@broken/keep.txt"), b"local").unwrap();
+    let engine = default_engine(reqwest::Client::new());
+    let req = request(base, root.clone(), true);
+
+    let fetched = engine.fetch_remote_state(&req).await.unwrap();
+    assert_eq!(fetched.unreachable_mod_names(), vec!["@broken".to_string()]);
+    assert_eq!(fetched.manifest.mods.len(), 1);
+
+    let result = engine.plan_and_execute(&req, None).await.unwrap();
+    assert_eq!(std::fs::read(root.join("@ok/a.txt")).unwrap(), b"hello");
+    assert_eq!(
+        std::fs::read(root.join("@broken/keep.txt")).unwrap(),
+        b"local"
+    );
+    assert!(result.plan.deletes.is_empty());
+    assert!(result.warnings.iter().any(|w| w.contains("@broken")));
+}
+
+#[tokio::test]
+async fn a_repository_with_every_mod_unreachable_still_fails() {
+    let repo = r#"{"repoName":"r","checksum":"","requiredMods":[{"modName":"@broken","checksum":"BROKEN","enabled":true}],"optionalMods":[]}"#;
+    let app = Router::new().route("/repo.json", get(move || async move { repo }));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let engine = default_engine(reqwest::Client::new());
+
+    let err = engine
+        .fetch_remote_state(&request(format!("http://{addr}"), root, true))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("none of the 1 mods"), "{err}");
+}
@@ -7,7 +7,7 @@ use tempfile::tempdir;
 fn tiny_repo_json() -> String {
     r#"{
         "repoName": "tiny",
-        "checksum": "AAA",
+        "checksum": "",
         "requiredMods": [{"modName": "@tiny", "checksum": "AAA", "enabled": true}],
         "optionalMods": []
     }"#
@@ -91,6 +91,7 @@ async fn sync_accepts_repo_base_without_trailing_slash_in_subpath() {
 
     let req = SyncRequest {
         repo_url,
+        extra_repo_urls: vec![],
         local_root: root.clone(),
         mode: SyncMode::MetadataOnly,
         options: SyncOptions::default(),
@@ -129,6 +130,7 @@ async fn sync_accepts_explicit_repo_json_url() {
 
     let req = SyncRequest {
         repo_url,
+        extra_repo_urls: vec![],
         local_root: root.clone(),
         mode: SyncMode::MetadataOnly,
         options: SyncOptions::default(),
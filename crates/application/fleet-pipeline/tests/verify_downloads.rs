@@ -0,0 +1,41 @@
+use fleet_core::{DownloadAction, SyncPlan};
+use fleet_pipeline::verify_downloads;
+use tempfile::tempdir;
+
+const FILE_CHECKSUM: &str = "F872A18EB88181EB00816510E762FEE6";
+
+#[tokio::test]
+async fn corrupted_downloads_are_reported() {
+    let dir = tempdir().unwrap();
+    let root = camino::Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    std::fs::create_dir_all(root.join("@mod/addons")).unwrap();
+    std::fs::write(root.join("@mod/addons/good.txt"), b"hello").unwrap();
+    std::fs::write(root.join("@mod/addons/bad.txt"), b"hellO").unwrap();
+
+    let download = |rel_path: &str| DownloadAction {
+        mod_name: "@mod".into(),
+        rel_path: rel_path.into(),
+        size: 5,
+        expected_checksum: FILE_CHECKSUM.into(),
+        mtime: None,
+        parts: Vec::new(),
+    };
+    let plan = SyncPlan {
+        renames: vec![],
+        checks: vec![],
+        deletes: vec![],
+        downloads: vec![
+            download("addons/good.txt"),
+            download("addons\\bad.txt"),
+            download("addons/missing.txt"),
+        ],
+    };
+
+    let mismatches = verify_downloads(&root, &plan).await.unwrap();
+    let paths: Vec<&str> = mismatches.iter().map(|m| m.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        vec!["@mod/addons/bad.txt", "@mod/addons/missing.txt"]
+    );
+    assert!(mismatches[0].reason.contains("checksum mismatch"));
+}
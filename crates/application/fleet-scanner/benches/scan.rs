@@ -0,0 +1,66 @@
+//! Full rehash of a generated repository with the default, a bounded and a sequential scan.
+//! Point `FLEET_BENCH_DIR` at a folder on the disk to measure (e.g. a spinning disk); the
+//! default temp dir is usually an SSD or memory, where parallel reads win.
+use camino::Utf8PathBuf;
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use fleet_scanner::{ScanOptions, ScanStrategy, Scanner};
+
+const MODS: usize = 20;
+const FILES_PER_MOD: usize = 100;
+const FILE_SIZE: usize = 64 * 1024;
+
+fn bench_scan(c: &mut Criterion) {
+    let dir = match std::env::var_os("FLEET_BENCH_DIR") {
+        Some(base) => tempfile::tempdir_in(base),
+        None => tempfile::tempdir(),
+    }
+    .unwrap();
+    let root = Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    for m in 0..MODS {
+        let addons = root.join(format!("@mod{m}/addons"));
+        std::fs::create_dir_all(&addons).unwrap();
+        for f in 0..FILES_PER_MOD {
+            let data: Vec<u8> = (0..FILE_SIZE).map(|i| ((i + m * f) % 251) as u8).collect();
+            std::fs::write(addons.join(format!("file_{f}.bin")), data).unwrap();
+        }
+    }
+
+    let mut group = c.benchmark_group("scan");
+    group.sample_size(10);
+    group.throughput(Throughput::Bytes((MODS * FILES_PER_MOD * FILE_SIZE) as u64));
+    for (name, options) in [
+        ("default", ScanOptions::default()),
+        (
+            "2 threads",
+            ScanOptions {
+                threads: Some(2),
+                ..ScanOptions::default()
+            },
+        ),
+        (
+            "sequential",
+            ScanOptions {
+                sequential: true,
+                ..ScanOptions::default()
+            },
+        ),
+    ] {
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                Scanner::scan_directory_with_options(
+                    &root,
+                    ScanStrategy::ForceRehash,
+                    options,
+                    None,
+                    None,
+                    None,
+                )
+                .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_scan);
+criterion_main!(benches);
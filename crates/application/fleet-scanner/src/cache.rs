@@ -1,6 +1,6 @@
 use camino::Utf8Path;
 use fleet_core::path_utils::FleetPath;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Clone)]
 pub struct FileCacheEntry {
@@ -13,6 +13,9 @@ pub struct FileCacheEntry {
 pub struct ScanCache {
     /// Map relative_path (Unix style) -> Entry
     pub entries: HashMap<String, FileCacheEntry>,
+    /// Map relative folder path (`""` for the mod root) -> mtime in nanoseconds, as of the
+    /// last walk. Stores keep these as rows named by [`ScanCache::dir_row`].
+    pub dirs: HashMap<String, u64>,
     dirty: bool,
 }
 
@@ -33,6 +36,41 @@ impl ScanCache {
         self.entries.get(&FleetPath::normalize(rel_path))
     }
 
+    /// Row name under which a store keeps the mtime of folder `rel_dir`: its path with a
+    /// trailing `/`, or `""` for the mod root. No file row can look like either.
+    pub fn dir_row(rel_dir: &str) -> String {
+        if rel_dir.is_empty() {
+            String::new()
+        } else {
+            format!("{rel_dir}/")
+        }
+    }
+
+    /// The folder a row written by [`ScanCache::dir_row`] stands for, or `None` for a file.
+    pub fn parse_dir_row(row: &str) -> Option<&str> {
+        if row.is_empty() {
+            Some(row)
+        } else {
+            row.strip_suffix('/')
+        }
+    }
+
+    /// Replace the recorded folder mtimes with those of the last walk and return the rows
+    /// ([`ScanCache::dir_row`]) of folders that are gone, so a store can drop them too.
+    pub fn replace_dirs(&mut self, dirs: HashMap<String, u64>) -> Vec<String> {
+        let stale = self
+            .dirs
+            .keys()
+            .filter(|dir| !dirs.contains_key(*dir))
+            .map(|dir| Self::dir_row(dir))
+            .collect();
+        if dirs != self.dirs {
+            self.dirs = dirs;
+            self.dirty = true;
+        }
+        stale
+    }
+
     /// Remove an entry (e.g., after file deletion)
     pub fn remove(&mut self, rel_path: &str) {
         if self
@@ -54,4 +92,25 @@ impl ScanCache {
             self.dirty = true;
         }
     }
+
+    /// Remove entries whose path is not in `present` (the rel paths found by the last walk)
+    /// and return the removed paths so a backing store can drop the same rows.
+    ///
+    /// Unlike `prune_ghosts`, this does not stat the filesystem, so entries that differ only
+    /// by case from a real file are still pruned on case-insensitive filesystems.
+    pub fn prune_missing(&mut self, present: &HashSet<String>) -> Vec<String> {
+        let stale: Vec<String> = self
+            .entries
+            .keys()
+            .filter(|rel_path| !present.contains(rel_path.as_str()))
+            .cloned()
+            .collect();
+        for rel_path in &stale {
+            self.entries.remove(rel_path);
+        }
+        if !stale.is_empty() {
+            self.dirty = true;
+        }
+        stale
+    }
 }
@@ -1,7 +1,9 @@
 use camino::{Utf8Path, Utf8PathBuf};
-use fleet_core::path_utils::FleetPath;
+use fleet_core::path_utils::{is_fleet_artifact, FleetPath};
 use fleet_core::{File, FileType, Manifest, Mod};
+use fleet_infra::hashing::HashOptions;
 use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, UNIX_EPOCH};
@@ -24,6 +26,9 @@ pub enum ScannerError {
     Cache(String),
 }
 
+/// Per-mod metadata file of a Swifty repository; never part of the mod's own content.
+pub const MOD_SRF: &str = "mod.srf";
+
 #[derive(Debug, Clone, Copy)]
 pub enum ScanStrategy {
     /// Use cache if mtime/size matches
@@ -44,6 +49,9 @@ pub struct ScanStats {
 struct ScanContext {
     stats: Arc<Mutex<ScanStats>>,
     cancel: Option<Arc<AtomicBool>>,
+    hash: HashOptions,
+    sequential: bool,
+    follow_symlinks: bool,
 }
 
 type ProgressCb = std::sync::Arc<Box<dyn Fn(ScanStats) + Send + Sync>>;
@@ -51,6 +59,103 @@ type ProgressCb = std::sync::Arc<Box<dyn Fn(ScanStats) + Send + Sync>>;
 pub trait ScanCacheStore: Send + Sync {
     fn load_mod_cache(&self, mod_name: &str) -> Result<ScanCache, ScannerError>;
     fn save_mod_cache(&self, mod_name: &str, cache: &ScanCache) -> Result<(), ScannerError>;
+    /// Delete cache rows for files that no longer exist under the mod.
+    fn prune_mod(&self, mod_name: &str, stale_rel_paths: &[String]) -> Result<(), ScannerError>;
+}
+
+/// Options for [`Scanner::scan_directory_with_options`].
+#[derive(Debug, Clone, Copy)]
+pub struct ScanOptions {
+    pub hash: HashOptions,
+    /// Hashing threads; `None` uses [`default_scan_threads`].
+    pub threads: Option<usize>,
+    /// Scan mods and their files one at a time in path order, which keeps seeks down on
+    /// spinning disks and network drives where parallel reads are slower. Overrides `threads`.
+    pub sequential: bool,
+    /// Follow symlinks and junctions inside mod folders, as the game does (see
+    /// [`walk_mod_files`]).
+    pub follow_symlinks: bool,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self {
+            hash: HashOptions::default(),
+            threads: None,
+            sequential: false,
+            follow_symlinks: true,
+        }
+    }
+}
+
+/// Every file under `mod_root`, which itself is always followed if it is a link. With
+/// `follow_symlinks`, linked files and directories inside it (e.g. Workshop content linked
+/// into the mods folder) are included too; a directory reached a second time, through a
+/// cycle or another link, is skipped. Without it, links are left out. Fleet's own files
+/// ([`is_fleet_artifact`]) are never listed. Entries that cannot be read, such as broken
+/// links, are logged and skipped rather than failing the walk.
+pub fn walk_mod_files(
+    mod_root: &Utf8Path,
+    follow_symlinks: bool,
+) -> impl Iterator<Item = walkdir::DirEntry> {
+    walk_mod_tree(mod_root, follow_symlinks).filter(|entry| {
+        entry.file_type().is_file() && !entry.file_name().to_str().is_some_and(is_fleet_artifact)
+    })
+}
+
+/// [`walk_mod_files`] with the directories left in, `mod_root` first.
+fn walk_mod_tree(
+    mod_root: &Utf8Path,
+    follow_symlinks: bool,
+) -> impl Iterator<Item = walkdir::DirEntry> {
+    let mut visited = HashSet::new();
+    WalkDir::new(mod_root)
+        .follow_links(follow_symlinks)
+        .into_iter()
+        .filter_entry(move |entry| {
+            if !follow_symlinks || !entry.file_type().is_dir() {
+                return true;
+            }
+            // Canonical paths identify a directory however it was reached.
+            fs::canonicalize(entry.path()).map_or(true, |dir| visited.insert(dir))
+        })
+        .filter_map(|entry| match entry {
+            Ok(entry) => Some(entry),
+            Err(e) if e.loop_ancestor().is_some() => {
+                warn!("Skipping symlink cycle: {e}");
+                None
+            }
+            Err(e) => {
+                warn!("Skipping unreadable entry: {e}");
+                None
+            }
+        })
+}
+
+/// All cores but one, so a full rehash leaves the machine usable.
+pub fn default_scan_threads() -> usize {
+    thread::available_parallelism()
+        .map(|n| n.get().saturating_sub(1))
+        .unwrap_or(1)
+        .max(1)
+}
+
+/// Runs `f` on a dedicated rayon pool with `threads` workers (see [`ScanOptions::threads`]),
+/// so nested `par_iter` calls are bounded too. Falls back to the global pool if the
+/// dedicated one cannot be built.
+pub fn run_in_scan_pool<R: Send>(threads: Option<usize>, f: impl FnOnce() -> R + Send) -> R {
+    let threads = threads.unwrap_or_else(default_scan_threads).max(1);
+    match rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .thread_name(|i| format!("fleet-scan-{i}"))
+        .build()
+    {
+        Ok(pool) => pool.install(f),
+        Err(e) => {
+            warn!("Falling back to the global thread pool: {e}");
+            f()
+        }
+    }
 }
 
 pub struct Scanner;
@@ -64,25 +169,59 @@ impl Scanner {
             .unwrap_or(0)
     }
 
+    /// Directory mtimes are kept to the nanosecond: a file added right after a scan must
+    /// still move its folder's mtime on.
+    fn dir_mtime(meta: &fs::Metadata) -> u64 {
+        meta.modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |d| d.as_nanos() as u64)
+    }
+
     pub fn scan_directory(
         root: &Utf8Path,
         strategy: ScanStrategy,
         on_progress: Option<Box<dyn Fn(ScanStats) + Send + Sync>>,
         cache_store: Option<Arc<dyn ScanCacheStore>>,
         cancel: Option<Arc<AtomicBool>>,
+    ) -> Result<Manifest, ScannerError> {
+        Self::scan_directory_with_options(
+            root,
+            strategy,
+            ScanOptions::default(),
+            on_progress,
+            cache_store,
+            cancel,
+        )
+    }
+
+    /// Like [`Self::scan_directory`], but with explicit [`ScanOptions`].
+    /// Cached checksums are only valid for the hash options they were computed with, so pair
+    /// a non-default chunk size with `ForceRehash` or no cache store.
+    pub fn scan_directory_with_options(
+        root: &Utf8Path,
+        strategy: ScanStrategy,
+        options: ScanOptions,
+        on_progress: Option<Box<dyn Fn(ScanStats) + Send + Sync>>,
+        cache_store: Option<Arc<dyn ScanCacheStore>>,
+        cancel: Option<Arc<AtomicBool>>,
     ) -> Result<Manifest, ScannerError> {
         info!("Scanning {} ({:?})", root, strategy);
 
-        let mod_dirs: Vec<Utf8PathBuf> = fs::read_dir(root)?
+        let mut mod_dirs: Vec<Utf8PathBuf> = fs::read_dir(root)?
             .filter_map(|e| e.ok())
             .filter(|e| e.path().is_dir())
             .map(|e| Utf8PathBuf::from_path_buf(e.path().to_path_buf()).unwrap())
             .filter(|p| p.file_name().map(|n| n.starts_with('@')).unwrap_or(false))
             .collect();
+        mod_dirs.sort();
 
         let ctx = Arc::new(ScanContext {
             stats: Arc::new(Mutex::new(ScanStats::default())),
             cancel: cancel.clone(),
+            hash: options.hash,
+            sequential: options.sequential,
+            follow_symlinks: options.follow_symlinks,
         });
 
         // Background progress monitor
@@ -118,18 +257,20 @@ impl Scanner {
             None
         };
 
-        // Parallel Scan
-        let results: Vec<Result<Mod, ScannerError>> = mod_dirs
-            .par_iter()
-            .map(|mod_dir| {
-                if let Some(c) = &ctx.cancel {
-                    if c.load(Ordering::Relaxed) {
-                        return Err(ScannerError::Cancelled);
-                    }
+        let scan = |mod_dir: &Utf8PathBuf| {
+            if let Some(c) = &ctx.cancel {
+                if c.load(Ordering::Relaxed) {
+                    return Err(ScannerError::Cancelled);
                 }
-                Self::scan_mod(mod_dir, strategy, &ctx, cache_store.as_deref())
-            })
-            .collect();
+            }
+            Self::scan_mod(mod_dir, strategy, &ctx, cache_store.as_deref())
+        };
+        // A one-thread pool keeps the part hashing of large files sequential too.
+        let results: Vec<Result<Mod, ScannerError>> = if ctx.sequential {
+            run_in_scan_pool(Some(1), || mod_dirs.iter().map(scan).collect())
+        } else {
+            run_in_scan_pool(options.threads, || mod_dirs.par_iter().map(scan).collect())
+        };
 
         if let Some(done) = monitor {
             done.store(true, Ordering::Relaxed);
@@ -148,10 +289,7 @@ impl Scanner {
             mods.push(res?);
         }
 
-        Ok(Manifest {
-            version: "1.0".to_string(),
-            mods,
-        })
+        Ok(Manifest::new(mods))
     }
 
     fn scan_mod(
@@ -161,106 +299,185 @@ impl Scanner {
         cache_store: Option<&dyn ScanCacheStore>,
     ) -> Result<Mod, ScannerError> {
         let mod_name = mod_root.file_name().unwrap_or("unknown").to_string();
-        let mut cache = if matches!(strategy, ScanStrategy::ForceRehash) {
-            ScanCache::default()
-        } else if let Some(store) = cache_store {
+        // The stored cache is loaded even for ForceRehash so stale rows can be pruned below.
+        let mut cache = if let Some(store) = cache_store {
             store.load_mod_cache(&mod_name)?
         } else {
             ScanCache::default()
         };
+        let use_cache = !matches!(strategy, ScanStrategy::ForceRehash);
 
-        // Collect files
-        let files: Vec<Utf8PathBuf> = WalkDir::new(mod_root)
-            .into_iter()
-            .filter_map(|e| e.ok())
-            .filter(|e| e.file_type().is_file())
-            .map(|e| Utf8PathBuf::from_path_buf(e.path().to_path_buf()).unwrap())
-            .filter(|p| !p.as_str().contains(".git") && !p.file_name().unwrap().ends_with(".json"))
-            .collect();
+        // Nothing in the folder changed since it was cached: reuse every entry as-is.
+        if let Some(cached_files) = use_cache
+            .then(|| Self::reuse_unchanged(mod_root, &cache))
+            .flatten()
+        {
+            debug!("{} unchanged since the last scan", mod_name);
+            let count = cached_files.len() as u64;
+            let bytes: u64 = cached_files.iter().map(|f| f.length).sum();
+            {
+                let mut s = ctx.stats.lock().unwrap();
+                s.total_files += count;
+                s.total_bytes += bytes;
+                s.files_scanned += count;
+                s.files_cached += count;
+                s.bytes_processed += bytes;
+            }
+            return Ok(Self::finish_mod(mod_name, cached_files));
+        }
 
-        // Pre-calculate totals
+        // Walk once, keeping each file's mtime and size and each folder's mtime.
+        let mut dirs = HashMap::new();
+        let mut files: Vec<(Utf8PathBuf, u64, u64)> = Vec::new();
+        for entry in walk_mod_tree(mod_root, ctx.follow_symlinks) {
+            let path = Utf8PathBuf::from_path_buf(entry.path().to_path_buf()).unwrap();
+            let meta = entry.metadata().map_err(std::io::Error::from)?;
+            if entry.file_type().is_dir() {
+                let rel_path = FleetPath::normalize(path.strip_prefix(mod_root).unwrap().as_str());
+                dirs.insert(rel_path, Self::dir_mtime(&meta));
+                continue;
+            }
+            let name = path.file_name().unwrap();
+            let is_mod_file = entry.file_type().is_file()
+                && !is_fleet_artifact(name)
+                && !path.as_str().contains(".git")
+                && !name.ends_with(".json")
+                // A published repository keeps each mod's own metadata next to its files.
+                && (path.parent() != Some(mod_root) || !name.eq_ignore_ascii_case(MOD_SRF));
+            if is_mod_file {
+                files.push((path, Self::mtime(&meta), meta.len()));
+            }
+        }
+        files.sort();
         {
             let mut stats = ctx.stats.lock().unwrap();
             stats.total_files += files.len() as u64;
-            stats.total_bytes += files
-                .iter()
-                .filter_map(|p| fs::metadata(p).ok().map(|m| m.len()))
-                .sum::<u64>();
+            stats.total_bytes += files.iter().map(|(_, _, len)| len).sum::<u64>();
         }
 
-        let scanned_files: Result<Vec<File>, ScannerError> = files
-            .par_iter()
-            .map(|fs_path| {
-                if let Some(c) = &ctx.cancel {
-                    if c.load(Ordering::Relaxed) {
-                        return Err(ScannerError::Cancelled);
-                    }
+        let scan_file = |(fs_path, mtime, len): &(Utf8PathBuf, u64, u64)| {
+            let (mtime, len) = (*mtime, *len);
+            if let Some(c) = &ctx.cancel {
+                if c.load(Ordering::Relaxed) {
+                    return Err(ScannerError::Cancelled);
                 }
+            }
 
-                let meta = fs::metadata(fs_path)?;
-                let len = meta.len();
-                let mtime = Self::mtime(&meta);
-
-                let rel_path =
-                    FleetPath::normalize(fs_path.strip_prefix(mod_root).unwrap().as_str());
+            let rel_path = FleetPath::normalize(fs_path.strip_prefix(mod_root).unwrap().as_str());
 
-                if let Some(entry) = cache.get(&rel_path) {
-                    if entry.mtime == mtime && entry.size == len {
-                        {
-                            let mut s = ctx.stats.lock().unwrap();
-                            s.files_scanned += 1;
-                            s.files_cached += 1;
-                            s.bytes_processed += len;
-                        }
-                        return Ok(File {
-                            path: rel_path,
-                            length: len,
-                            checksum: entry.checksum.clone(),
-                            file_type: FileType::File,
-                            parts: vec![],
-                        });
+            if let Some(entry) = cache.get(&rel_path).filter(|_| use_cache) {
+                if entry.mtime == mtime && entry.size == len {
+                    {
+                        let mut s = ctx.stats.lock().unwrap();
+                        s.files_scanned += 1;
+                        s.files_cached += 1;
+                        s.bytes_processed += len;
                     }
+                    return Ok(File {
+                        path: rel_path,
+                        length: len,
+                        checksum: entry.checksum.clone(),
+                        file_type: FileType::File,
+                        parts: vec![],
+                        mtime: Some(mtime),
+                    });
                 }
+            }
 
-                let file_obj = fleet_infra::hashing::scan_file(fs_path, Utf8Path::new(&rel_path))?;
-
-                {
-                    let mut s = ctx.stats.lock().unwrap();
-                    s.files_scanned += 1;
-                    s.bytes_processed += len;
-                }
+            let mut file_obj = fleet_infra::hashing::scan_file_with_options(
+                fs_path,
+                Utf8Path::new(&rel_path),
+                &ctx.hash,
+            )?;
+            file_obj.mtime = Some(mtime);
+
+            {
+                let mut s = ctx.stats.lock().unwrap();
+                s.files_scanned += 1;
+                s.bytes_processed += len;
+            }
 
-                Ok(file_obj)
-            })
-            .collect();
+            Ok(file_obj)
+        };
+        let scanned_files: Result<Vec<File>, ScannerError> = if ctx.sequential {
+            files.iter().map(scan_file).collect()
+        } else {
+            files.par_iter().map(scan_file).collect()
+        };
 
         let scanned_files = scanned_files?;
 
-        for f in &scanned_files {
-            if let Ok(meta) = fs::metadata(mod_root.join(&f.path)) {
-                cache.update(&f.path, Self::mtime(&meta), f.length, f.checksum.clone());
-            }
+        // Stamped with the mtime seen before hashing, so a file written meanwhile is rehashed
+        // next time.
+        for (f, (_, mtime, _)) in scanned_files.iter().zip(&files) {
+            cache.update(&f.path, *mtime, f.length, f.checksum.clone());
         }
-        cache.prune_ghosts(mod_root);
+        let present: HashSet<String> = scanned_files.iter().map(|f| f.path.clone()).collect();
+        let mut stale = cache.prune_missing(&present);
+        stale.extend(cache.replace_dirs(dirs));
         if let Some(store) = cache_store {
             store.save_mod_cache(&mod_name, &cache)?;
+            if !stale.is_empty() {
+                debug!(
+                    "Pruning {} stale cache entries for {}",
+                    stale.len(),
+                    mod_name
+                );
+                store.prune_mod(&mod_name, &stale)?;
+            }
         }
 
+        Ok(Self::finish_mod(mod_name, scanned_files))
+    }
+
+    /// Rebuilds an untouched mod from its cache by re-statting only the folders and files it
+    /// recorded, without listing any folder: adding, removing or renaming a file moves its
+    /// folder's mtime, and editing one moves its own. `None` if anything differs, or if no
+    /// folders were recorded yet.
+    fn reuse_unchanged(mod_root: &Utf8Path, cache: &ScanCache) -> Option<Vec<File>> {
+        if cache.dirs.is_empty() {
+            return None;
+        }
+        for (dir, &mtime) in &cache.dirs {
+            let meta = fs::metadata(mod_root.join(dir)).ok()?;
+            if !meta.is_dir() || Self::dir_mtime(&meta) != mtime {
+                return None;
+            }
+        }
+        cache
+            .entries
+            .iter()
+            .map(|(rel_path, entry)| {
+                let meta = fs::metadata(mod_root.join(rel_path)).ok()?;
+                (meta.is_file() && Self::mtime(&meta) == entry.mtime && meta.len() == entry.size)
+                    .then(|| File {
+                        path: rel_path.clone(),
+                        length: entry.size,
+                        checksum: entry.checksum.clone(),
+                        file_type: FileType::File,
+                        parts: vec![],
+                        mtime: Some(entry.mtime),
+                    })
+            })
+            .collect()
+    }
+
+    /// Sorts `files` and computes the mod checksum over them.
+    fn finish_mod(mod_name: String, mut files: Vec<File>) -> Mod {
         let mut hasher = md5::Context::new();
-        let mut sorted_files = scanned_files.clone();
-        sorted_files.sort_by(|a, b| {
+        files.sort_by(|a, b| {
             FleetPath::canonicalize(&a.path).cmp(&FleetPath::canonicalize(&b.path))
         });
 
-        for file in &sorted_files {
+        for file in &files {
             hasher.consume(file.checksum.as_bytes());
             hasher.consume(FleetPath::canonicalize(&file.path).as_bytes());
         }
 
-        Ok(Mod {
+        Mod {
             name: mod_name,
             checksum: format!("{:X}", hasher.finalize()),
-            files: sorted_files,
-        })
+            files,
+        }
     }
 }
@@ -116,6 +116,30 @@ impl ScanCacheStore for RedbScanCacheStore {
         tx.commit()
             .map_err(|e| fleet_scanner::ScannerError::Cache(format!("redb commit: {e}")))
     }
+
+    fn prune_mod(
+        &self,
+        mod_name: &str,
+        stale_rel_paths: &[String],
+    ) -> Result<(), fleet_scanner::ScannerError> {
+        let tx = self
+            .db
+            .begin_write()
+            .map_err(|e| fleet_scanner::ScannerError::Cache(format!("redb begin_write: {e}")))?;
+        {
+            let mut table = tx
+                .open_table(Self::TABLE)
+                .map_err(|e| fleet_scanner::ScannerError::Cache(format!("redb open_table: {e}")))?;
+            for rel_path in stale_rel_paths {
+                let key = Self::cache_key(mod_name, rel_path);
+                table
+                    .remove(key.as_slice())
+                    .map_err(|e| fleet_scanner::ScannerError::Cache(format!("redb remove: {e}")))?;
+            }
+        }
+        tx.commit()
+            .map_err(|e| fleet_scanner::ScannerError::Cache(format!("redb commit: {e}")))
+    }
 }
 
 #[test]
@@ -214,3 +238,50 @@ fn test_cache_hit_and_miss_behavior() {
         "Should have 1 cache hit (file2)"
     );
 }
+
+#[test]
+fn deleted_files_are_pruned_from_cache_store() {
+    let temp = tempfile::tempdir().unwrap();
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).unwrap();
+
+    let mod_dir = root.join("@TestMod");
+    fs::create_dir_all(&mod_dir).unwrap();
+    fs::write(mod_dir.join("keep.txt"), "keep").unwrap();
+    fs::write(mod_dir.join("gone.txt"), "gone").unwrap();
+
+    let cache_store = Arc::new(RedbScanCacheStore::new(
+        &temp.path().join("scan_cache.redb"),
+    ));
+
+    Scanner::scan_directory(
+        &root,
+        ScanStrategy::SmartCache,
+        None,
+        Some(cache_store.clone()),
+        None,
+    )
+    .expect("Initial scan failed");
+    assert_eq!(
+        cache_store
+            .load_mod_cache("@TestMod")
+            .unwrap()
+            .entries
+            .len(),
+        2
+    );
+
+    fs::remove_file(mod_dir.join("gone.txt")).unwrap();
+
+    Scanner::scan_directory(
+        &root,
+        ScanStrategy::ForceRehash,
+        None,
+        Some(cache_store.clone()),
+        None,
+    )
+    .expect("Rescan failed");
+
+    let cache = cache_store.load_mod_cache("@TestMod").unwrap();
+    assert_eq!(cache.entries.len(), 1);
+    assert!(cache.entries.contains_key("keep.txt"));
+}
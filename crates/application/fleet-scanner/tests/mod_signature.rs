@@ -0,0 +1,84 @@
+use camino::Utf8PathBuf;
+use fleet_scanner::cache::ScanCache;
+use fleet_scanner::{ScanCacheStore, ScanStrategy, Scanner, ScannerError};
+use std::collections::HashMap;
+use std::fs;
+use std::sync::{Arc, Mutex};
+
+/// Keeps caches in memory and counts how often a mod's cache is written back.
+#[derive(Default)]
+struct CountingStore {
+    caches: Mutex<HashMap<String, ScanCache>>,
+    saves: Mutex<usize>,
+}
+
+impl ScanCacheStore for CountingStore {
+    fn load_mod_cache(&self, mod_name: &str) -> Result<ScanCache, ScannerError> {
+        Ok(self
+            .caches
+            .lock()
+            .unwrap()
+            .get(mod_name)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    fn save_mod_cache(&self, mod_name: &str, cache: &ScanCache) -> Result<(), ScannerError> {
+        *self.saves.lock().unwrap() += 1;
+        self.caches
+            .lock()
+            .unwrap()
+            .insert(mod_name.to_string(), cache.clone());
+        Ok(())
+    }
+
+    fn prune_mod(&self, _mod_name: &str, _stale: &[String]) -> Result<(), ScannerError> {
+        Ok(())
+    }
+}
+
+#[test]
+fn unchanged_mod_is_reused_and_added_or_removed_files_invalidate_it() {
+    let temp = tempfile::tempdir().unwrap();
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).unwrap();
+    let mod_dir = root.join("@m/addons");
+    fs::create_dir_all(&mod_dir).unwrap();
+    fs::write(mod_dir.join("a.bin"), "a").unwrap();
+
+    let store = Arc::new(CountingStore::default());
+    let scan = || {
+        let store: Arc<dyn ScanCacheStore> = store.clone();
+        Scanner::scan_directory(&root, ScanStrategy::SmartCache, None, Some(store), None)
+            .expect("scan failed")
+            .mods
+            .remove(0)
+    };
+    let saves = || *store.saves.lock().unwrap();
+
+    let first = scan();
+    assert_eq!(saves(), 1);
+    let dirs = store.caches.lock().unwrap()["@m"].dirs.clone();
+    assert!(dirs.contains_key("") && dirs.contains_key("addons"));
+
+    let again = scan();
+    assert_eq!(
+        saves(),
+        1,
+        "an unchanged mod is not re-checked file by file"
+    );
+    // Cached entries carry no part list, exactly like per-file cache hits.
+    assert_eq!(again.checksum, first.checksum);
+    assert_eq!(again.files[0].checksum, first.files[0].checksum);
+
+    fs::write(mod_dir.join("b.bin"), "b").unwrap();
+    let added = scan();
+    assert_eq!(saves(), 2);
+    assert_eq!(added.files.len(), 2);
+    assert_ne!(added.checksum, first.checksum);
+
+    fs::remove_file(mod_dir.join("a.bin")).unwrap();
+    let removed = scan();
+    assert_eq!(saves(), 3);
+    let paths: Vec<&str> = removed.files.iter().map(|f| f.path.as_str()).collect();
+    assert_eq!(paths, ["addons/b.bin"]);
+}
@@ -0,0 +1,62 @@
+#![cfg(unix)]
+
+use camino::Utf8PathBuf;
+use fleet_scanner::{walk_mod_files, ScanOptions, ScanStrategy, Scanner};
+use std::fs;
+use std::os::unix::fs::symlink;
+
+fn scanned_paths(root: &camino::Utf8Path, follow_symlinks: bool) -> Vec<String> {
+    let manifest = Scanner::scan_directory_with_options(
+        root,
+        ScanStrategy::ForceRehash,
+        ScanOptions {
+            follow_symlinks,
+            ..ScanOptions::default()
+        },
+        None,
+        None,
+        None,
+    )
+    .expect("scan failed");
+    manifest.mods[0]
+        .files
+        .iter()
+        .map(|f| f.path.clone())
+        .collect()
+}
+
+#[test]
+fn linked_subtrees_are_scanned_and_cycles_skipped() {
+    let temp = tempfile::tempdir().unwrap();
+    let base = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).unwrap();
+    let workshop = base.join("workshop/addons");
+    fs::create_dir_all(&workshop).unwrap();
+    fs::write(workshop.join("linked.bin"), "linked").unwrap();
+
+    let root = base.join("mods");
+    let mod_dir = root.join("@m");
+    fs::create_dir_all(&mod_dir).unwrap();
+    fs::write(mod_dir.join("own.bin"), "own").unwrap();
+    symlink(&workshop, mod_dir.join("addons")).unwrap();
+    // Points back at the mod itself; following it naively would never end.
+    symlink(&mod_dir, mod_dir.join("loop")).unwrap();
+
+    assert_eq!(scanned_paths(&root, true), ["addons/linked.bin", "own.bin"]);
+    assert_eq!(scanned_paths(&root, false), ["own.bin"]);
+}
+
+#[test]
+fn broken_links_are_skipped() {
+    let temp = tempfile::tempdir().unwrap();
+    let base = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).unwrap();
+    let mod_dir = base.join("mods/@m");
+    fs::create_dir_all(&mod_dir).unwrap();
+    fs::write(mod_dir.join("own.bin"), "own").unwrap();
+    symlink(base.join("gone"), mod_dir.join("dangling.bin")).unwrap();
+
+    let walked: Vec<String> = walk_mod_files(&mod_dir, true)
+        .map(|e| e.file_name().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(walked, ["own.bin"]);
+    assert_eq!(scanned_paths(&base.join("mods"), true), ["own.bin"]);
+}
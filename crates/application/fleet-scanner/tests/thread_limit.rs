@@ -0,0 +1,72 @@
+use camino::Utf8PathBuf;
+use fleet_scanner::{run_in_scan_pool, ScanOptions, ScanStrategy, Scanner};
+use std::fs;
+
+#[test]
+fn scan_pool_honours_thread_limit() {
+    assert_eq!(run_in_scan_pool(Some(2), rayon::current_num_threads), 2);
+    assert_eq!(run_in_scan_pool(Some(1), rayon::current_num_threads), 1);
+}
+
+#[test]
+fn limited_scan_matches_default_scan() {
+    let temp = tempfile::tempdir().unwrap();
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).unwrap();
+    for mod_name in ["@a", "@b", "@c"] {
+        let mod_dir = root.join(mod_name);
+        fs::create_dir_all(&mod_dir).unwrap();
+        fs::write(mod_dir.join("file.txt"), mod_name).unwrap();
+    }
+
+    let default = Scanner::scan_directory(&root, ScanStrategy::ForceRehash, None, None, None)
+        .expect("default scan failed");
+    let limited = Scanner::scan_directory_with_options(
+        &root,
+        ScanStrategy::ForceRehash,
+        ScanOptions {
+            threads: Some(1),
+            ..ScanOptions::default()
+        },
+        None,
+        None,
+        None,
+    )
+    .expect("limited scan failed");
+
+    assert_eq!(limited.mods, default.mods);
+}
+
+#[test]
+fn sequential_scan_visits_mods_and_files_in_path_order() {
+    let temp = tempfile::tempdir().unwrap();
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).unwrap();
+    for mod_name in ["@c", "@a", "@b"] {
+        let addons = root.join(mod_name).join("addons");
+        fs::create_dir_all(&addons).unwrap();
+        for file in ["z.bin", "m.bin", "a.bin"] {
+            fs::write(addons.join(file), format!("{mod_name}/{file}")).unwrap();
+        }
+    }
+
+    let manifest = Scanner::scan_directory_with_options(
+        &root,
+        ScanStrategy::ForceRehash,
+        ScanOptions {
+            sequential: true,
+            ..ScanOptions::default()
+        },
+        None,
+        None,
+        None,
+    )
+    .expect("sequential scan failed");
+
+    let names: Vec<&str> = manifest.mods.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(names, ["@a", "@b", "@c"]);
+    let files: Vec<&str> = manifest.mods[0]
+        .files
+        .iter()
+        .map(|f| f.path.as_str())
+        .collect();
+    assert_eq!(files, ["addons/a.bin", "addons/m.bin", "addons/z.bin"]);
+}
@@ -0,0 +1,44 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use fleet_core::diff::diff;
+use fleet_core::{File, FileType, Manifest, Mod};
+
+const MODS: usize = 50;
+const FILES_PER_MOD: usize = 1_000;
+
+/// 50 mods x 1000 files; every tenth file has a different checksum locally.
+fn manifests() -> (Manifest, Manifest) {
+    let build = |stale: bool| Manifest {
+        version: "1.0".into(),
+        mods: (0..MODS)
+            .map(|m| Mod {
+                name: format!("@mod{m}"),
+                checksum: "AAA".into(),
+                files: (0..FILES_PER_MOD)
+                    .map(|f| File {
+                        path: format!("addons/file_{f}.pbo"),
+                        length: 1024,
+                        checksum: if stale && f % 10 == 0 {
+                            format!("OLD{f}")
+                        } else {
+                            format!("SUM{f}")
+                        },
+                        file_type: FileType::File,
+                        parts: vec![],
+                        mtime: None,
+                    })
+                    .collect(),
+            })
+            .collect(),
+    };
+    (build(false), build(true))
+}
+
+fn bench_diff(c: &mut Criterion) {
+    let (remote, local) = manifests();
+    c.bench_function("diff 50k files", |b| {
+        b.iter(|| diff(black_box(&remote), black_box(&local)))
+    });
+}
+
+criterion_group!(benches, bench_diff);
+criterion_main!(benches);
@@ -1,9 +1,69 @@
 use crate::path_utils::FleetPath;
 use crate::{
-    DeleteAction, DownloadAction, File, Manifest, Mod, RenameAction, SyncPlan, VerificationAction,
+    DeleteAction, DownloadAction, File, FilePart, Manifest, Mod, RenameAction, SyncPlan,
+    VerificationAction,
 };
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 
+/// How a file compares between the local and remote manifests, regardless of the action
+/// planned for it.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum FileStatus {
+    Unchanged,
+    Changed,
+    Added,
+    Removed,
+    /// Same content under a path or mod folder that differs only in case.
+    Renamed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FileDiff {
+    /// Remote mod name, or the local one for removed files.
+    pub mod_name: String,
+    pub path: String,
+    pub status: FileStatus,
+    /// Local `@mod/path` when it is spelled differently from the remote one.
+    pub renamed_from: Option<String>,
+    pub old_size: Option<u64>,
+    pub new_size: Option<u64>,
+    pub old_checksum: Option<String>,
+    pub new_checksum: Option<String>,
+}
+
+/// Every file of both manifests with its [`FileStatus`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct DiffReport {
+    pub files: Vec<FileDiff>,
+}
+
+impl DiffReport {
+    pub fn count(&self, status: FileStatus) -> usize {
+        self.files.iter().filter(|f| f.status == status).count()
+    }
+
+    fn push(&mut self, mod_name: &str, status: FileStatus, old: Option<&File>, new: Option<&File>) {
+        let path = new.or(old).map(|f| f.path.clone()).unwrap_or_default();
+        self.files.push(FileDiff {
+            mod_name: mod_name.to_string(),
+            path,
+            status,
+            renamed_from: None,
+            old_size: old.map(|f| f.length),
+            new_size: new.map(|f| f.length),
+            old_checksum: old.map(|f| f.checksum.clone()),
+            new_checksum: new.map(|f| f.checksum.clone()),
+        });
+    }
+
+    fn removed_mod(&mut self, m: &Mod) {
+        for f in &m.files {
+            self.push(&m.name, FileStatus::Removed, Some(f), None);
+        }
+    }
+}
+
 pub fn diff(remote: &Manifest, local: &Manifest) -> SyncPlan {
     let mut renames = Vec::new();
     let mut downloads = Vec::new();
@@ -58,12 +118,7 @@ pub fn diff(remote: &Manifest, local: &Manifest) -> SyncPlan {
             }
         } else {
             for file in &remote_mod.files {
-                downloads.push(DownloadAction {
-                    mod_name: remote_mod.name.clone(),
-                    rel_path: file.path.clone(),
-                    size: file.length,
-                    expected_checksum: file.checksum.clone(),
-                });
+                downloads.push(download_action(&remote_mod.name, file));
             }
         }
     }
@@ -84,6 +139,107 @@ pub fn diff(remote: &Manifest, local: &Manifest) -> SyncPlan {
     }
 }
 
+/// Categorizes every file of `remote` and `local`, matching mods and paths the same
+/// case-insensitive way [`diff`] does.
+pub fn diff_report(remote: &Manifest, local: &Manifest) -> DiffReport {
+    let mut report = DiffReport::default();
+
+    let mut local_groups: HashMap<String, Vec<&Mod>> = HashMap::new();
+    for m in &local.mods {
+        local_groups
+            .entry(m.name.to_lowercase())
+            .or_default()
+            .push(m);
+    }
+    let mut claimed: HashSet<&str> = HashSet::new();
+
+    for remote_mod in &remote.mods {
+        let candidates = local_groups
+            .get(&remote_mod.name.to_lowercase())
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let survivor = candidates
+            .iter()
+            .find(|m| m.name == remote_mod.name)
+            .or_else(|| candidates.first());
+        let Some(local_mod) = survivor else {
+            for f in &remote_mod.files {
+                report.push(&remote_mod.name, FileStatus::Added, None, Some(f));
+            }
+            continue;
+        };
+        for m in candidates {
+            claimed.insert(&m.name);
+            if m.name != local_mod.name {
+                report.removed_mod(m);
+            }
+        }
+
+        let local_files: HashMap<String, &File> = local_mod
+            .files
+            .iter()
+            .map(|f| (FleetPath::canonicalize(&f.path), f))
+            .collect();
+        let mut visited: HashSet<String> = HashSet::new();
+        for remote_file in &remote_mod.files {
+            let key = FleetPath::canonicalize(&remote_file.path);
+            let Some(local_file) = local_files.get(&key) else {
+                report.push(&remote_mod.name, FileStatus::Added, None, Some(remote_file));
+                continue;
+            };
+            let renamed = local_mod.name != remote_mod.name || local_file.path != remote_file.path;
+            let status = if local_file.checksum != remote_file.checksum {
+                FileStatus::Changed
+            } else if renamed {
+                FileStatus::Renamed
+            } else {
+                FileStatus::Unchanged
+            };
+            report.push(
+                &remote_mod.name,
+                status,
+                Some(local_file),
+                Some(remote_file),
+            );
+            if renamed {
+                if let Some(last) = report.files.last_mut() {
+                    last.renamed_from = Some(format!("{}/{}", local_mod.name, local_file.path));
+                }
+            }
+            visited.insert(key);
+        }
+        for f in &local_mod.files {
+            if !visited.contains(&FleetPath::canonicalize(&f.path)) {
+                report.push(&local_mod.name, FileStatus::Removed, Some(f), None);
+            }
+        }
+    }
+
+    for m in &local.mods {
+        if !claimed.contains(m.name.as_str()) {
+            report.removed_mod(m);
+        }
+    }
+    report
+}
+
+/// Downloads `file` of `mod_name` as listed; its parts are only kept when there is more
+/// than one to split along.
+pub fn download_action(mod_name: &str, file: &File) -> DownloadAction {
+    DownloadAction {
+        mod_name: mod_name.to_string(),
+        rel_path: file.path.clone(),
+        size: file.length,
+        expected_checksum: file.checksum.clone(),
+        mtime: file.mtime,
+        parts: if file.parts.len() > 1 {
+            file.parts.clone()
+        } else {
+            Vec::new()
+        },
+    }
+}
+
 /// Helper to diff files within a specific matched mod
 fn diff_files(
     remote_mod: &Mod,
@@ -92,28 +248,28 @@ fn diff_files(
     deletes: &mut Vec<DeleteAction>,
     checks: &mut Vec<VerificationAction>,
 ) {
-    // Map Local Files: normalized_path -> File
-    let local_files: HashMap<String, &File> = local_mod
+    // Canonical keys are computed once per local file and borrowed from here on.
+    let local_keys: Vec<String> = local_mod
         .files
         .iter()
-        .map(|f| (FleetPath::canonicalize(&f.path), f))
+        .map(|f| FleetPath::canonicalize(&f.path))
+        .collect();
+    let local_files: HashMap<&str, &File> = local_keys
+        .iter()
+        .map(String::as_str)
+        .zip(&local_mod.files)
         .collect();
 
-    let mut visited_files = HashSet::new();
+    let mut visited_files: HashSet<&str> = HashSet::with_capacity(local_keys.len());
 
     for remote_file in &remote_mod.files {
         let key = FleetPath::canonicalize(&remote_file.path);
-        visited_files.insert(key.clone());
 
-        match local_files.get(&key) {
-            Some(local_file) => {
+        match local_files.get_key_value(key.as_str()) {
+            Some((&local_key, local_file)) => {
+                visited_files.insert(local_key);
                 if local_file.checksum != remote_file.checksum {
-                    downloads.push(DownloadAction {
-                        mod_name: remote_mod.name.clone(),
-                        rel_path: remote_file.path.clone(),
-                        size: remote_file.length,
-                        expected_checksum: remote_file.checksum.clone(),
-                    });
+                    downloads.push(download_action(&remote_mod.name, remote_file));
                 } else {
                     checks.push(VerificationAction {
                         path: format!("{}/{}", local_mod.name, local_file.path),
@@ -122,20 +278,14 @@ fn diff_files(
                 }
             }
             None => {
-                downloads.push(DownloadAction {
-                    mod_name: remote_mod.name.clone(),
-                    rel_path: remote_file.path.clone(),
-                    size: remote_file.length,
-                    expected_checksum: remote_file.checksum.clone(),
-                });
+                downloads.push(download_action(&remote_mod.name, remote_file));
             }
         }
     }
 
     // Identify local files that don't exist in remote (Deletes)
-    for local_file in &local_mod.files {
-        let key = FleetPath::canonicalize(&local_file.path);
-        if !visited_files.contains(&key) {
+    for (local_file, key) in local_mod.files.iter().zip(&local_keys) {
+        if !visited_files.contains(key.as_str()) {
             deletes.push(DeleteAction {
                 // Delete path includes mod name to be absolute relative to root
                 path: format!("{}/{}", local_mod.name, local_file.path),
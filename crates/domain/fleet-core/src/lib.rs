@@ -1,3 +1,4 @@
+use path_utils::FleetPath;
 use serde::{Deserialize, Serialize};
 
 pub mod diff;
@@ -7,6 +8,10 @@ pub mod repo;
 
 pub type Md5Digest = String;
 
+/// `MAJOR.MINOR` format version written into every [`Manifest`]. Additive fields bump the
+/// minor; anything an older client would misread bumps the major.
+pub const MANIFEST_VERSION: &str = "1.0";
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "PascalCase")]
 pub struct Manifest {
@@ -14,6 +19,80 @@ pub struct Manifest {
     pub mods: Vec<Mod>,
 }
 
+/// Why a stored manifest cannot be used by this version of Fleet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ManifestVersionError {
+    /// Written by a newer Fleet with an incompatible layout.
+    Unsupported { found: String, supported: String },
+    /// Not a `MAJOR.MINOR` version at all.
+    Invalid(String),
+}
+
+impl std::fmt::Display for ManifestVersionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ManifestVersionError::Unsupported { found, supported } => write!(
+                f,
+                "manifest version {found} is from a newer Fleet (supported: {supported}.x)"
+            ),
+            ManifestVersionError::Invalid(v) => write!(f, "invalid manifest version '{v}'"),
+        }
+    }
+}
+
+impl std::error::Error for ManifestVersionError {}
+
+impl Manifest {
+    /// A manifest of the current [`MANIFEST_VERSION`].
+    pub fn new(mods: Vec<Mod>) -> Self {
+        Self {
+            version: MANIFEST_VERSION.to_string(),
+            mods,
+        }
+    }
+
+    /// Checks a loaded manifest's version and brings it up to [`MANIFEST_VERSION`]. Newer
+    /// minors of the same major load as they are, since their additions are ignored; a newer
+    /// major is refused rather than misread.
+    pub fn migrate(mut self) -> Result<Self, ManifestVersionError> {
+        // Blank: written before versions were checked. Those manifests could still carry
+        // Windows separators and decomposed (NFD) names in their paths, which every versioned
+        // layout stores normalized.
+        if self.version.trim().is_empty() {
+            for file in self.mods.iter_mut().flat_map(|m| m.files.iter_mut()) {
+                file.path = FleetPath::normalize(&file.path);
+                for part in &mut file.parts {
+                    part.path = FleetPath::normalize(&part.path);
+                }
+            }
+            self.version = MANIFEST_VERSION.to_string();
+        }
+        let found = parse_manifest_version(&self.version)
+            .filter(|&(major, _)| major > 0)
+            .ok_or_else(|| ManifestVersionError::Invalid(self.version.clone()))?;
+        let current = parse_manifest_version(MANIFEST_VERSION).unwrap_or((1, 0));
+        if found.0 > current.0 {
+            return Err(ManifestVersionError::Unsupported {
+                found: self.version,
+                supported: current.0.to_string(),
+            });
+        }
+        // Older layouts are upgraded here, oldest first. 1.0 is the first versioned layout,
+        // so all that is left is to spell an older or bare version (`1`) as the current one.
+        if found <= current {
+            self.version = MANIFEST_VERSION.to_string();
+        }
+        Ok(self)
+    }
+}
+
+/// `MAJOR.MINOR` (or a bare `MAJOR`) as numbers.
+fn parse_manifest_version(version: &str) -> Option<(u32, u32)> {
+    let version = version.trim();
+    let (major, minor) = version.split_once('.').unwrap_or((version, "0"));
+    Some((major.parse().ok()?, minor.parse().ok()?))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "PascalCase")]
 pub struct Mod {
@@ -40,6 +119,9 @@ pub struct File {
     #[serde(rename = "Type")]
     pub file_type: FileType,
     pub parts: Vec<FilePart>,
+    /// Upstream modification time (Unix seconds), when the repository publishes one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mtime: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -51,7 +133,7 @@ pub struct FilePart {
     pub checksum: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct SyncPlan {
     pub renames: Vec<RenameAction>,
     pub checks: Vec<VerificationAction>,
@@ -59,27 +141,110 @@ pub struct SyncPlan {
     pub deletes: Vec<DeleteAction>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct RenameAction {
     pub old_path: String,
     pub new_path: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct DownloadAction {
     pub mod_name: String,
     pub rel_path: String,
     pub size: u64,
     pub expected_checksum: String,
+    /// Stamped on the downloaded file instead of the download time when set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mtime: Option<u64>,
+    /// Part boundaries and checksums of a file the repository splits into more than one
+    /// part; segmented downloads fetch and verify along them. Empty otherwise.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parts: Vec<FilePart>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct DeleteAction {
     pub path: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct VerificationAction {
     pub path: String,
     pub expected_checksum: String,
 }
+
+/// How dangerous a plan's deletes are relative to what already exists locally.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum RiskLevel {
+    Safe,
+    /// Deletes enough content that the user should confirm before it runs.
+    Destructive,
+}
+
+/// Limits beyond which a plan's deletes are considered destructive.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct DeleteThreshold {
+    /// Deleting more files than this is always destructive.
+    pub max_deletes: usize,
+    /// Deleting more than this fraction of existing files is destructive...
+    pub max_fraction: f64,
+    /// ...once at least this many files go, so tiny mods can still be cleaned up.
+    pub min_deletes: usize,
+}
+
+impl Default for DeleteThreshold {
+    fn default() -> Self {
+        Self {
+            max_deletes: 100,
+            max_fraction: 0.5,
+            min_deletes: 10,
+        }
+    }
+}
+
+impl DeleteThreshold {
+    pub fn assess(&self, deletes: usize, existing_count: usize) -> RiskLevel {
+        if deletes > self.max_deletes {
+            return RiskLevel::Destructive;
+        }
+        if deletes >= self.min_deletes && deletes as f64 > existing_count as f64 * self.max_fraction
+        {
+            return RiskLevel::Destructive;
+        }
+        RiskLevel::Safe
+    }
+}
+
+impl SyncPlan {
+    /// Rates the plan's deletes against `existing_count` local files using the default
+    /// [`DeleteThreshold`]. Each delete action counts once.
+    pub fn delete_risk(&self, existing_count: usize) -> RiskLevel {
+        DeleteThreshold::default().assess(self.deletes.len(), existing_count)
+    }
+
+    /// Number of local files the deletes remove; deleting a whole mod counts all of its
+    /// files in `local`.
+    pub fn deleted_file_count(&self, local: &Manifest) -> usize {
+        self.deletes
+            .iter()
+            .map(|d| {
+                if d.path.contains('/') {
+                    1
+                } else {
+                    local
+                        .mods
+                        .iter()
+                        .find(|m| m.name.eq_ignore_ascii_case(&d.path))
+                        .map_or(1, |m| m.files.len().max(1))
+                }
+            })
+            .sum()
+    }
+
+    /// Rates the plan against the local manifest it was diffed from, weighting whole-mod
+    /// deletes by their size.
+    pub fn delete_risk_against(&self, local: &Manifest, threshold: &DeleteThreshold) -> RiskLevel {
+        let existing: usize = local.mods.iter().map(|m| m.files.len()).sum();
+        threshold.assess(self.deleted_file_count(local), existing)
+    }
+}
@@ -1,18 +1,53 @@
 use std::borrow::Cow;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use unicode_normalization::{is_nfc, UnicodeNormalization};
+
+use crate::Manifest;
+
+/// Name of the per-folder database that holds Fleet's baseline and scan cache.
+pub const FLEET_REDB_FILENAME: &str = "fleet.redb";
+
+/// Whether `file_name` is one of Fleet's own files: the database (also as a dotfile), copies
+/// of it quarantined as corrupt, and the `.fleet-*.json` state of pre-database versions.
+/// Scans never list them and plans never delete them.
+pub fn is_fleet_artifact(file_name: &str) -> bool {
+    let name = file_name.to_ascii_lowercase();
+    let db = name.strip_prefix('.').unwrap_or(&name);
+    db == FLEET_REDB_FILENAME
+        || db.starts_with(&format!("{FLEET_REDB_FILENAME}.corrupt."))
+        || (name.starts_with(".fleet-") && name.ends_with(".json"))
+}
 
 pub struct FleetPath;
 
 impl FleetPath {
-    /// Standardize directory separators to forward slashes.
+    /// Standardize directory separators to forward slashes and compose Unicode to NFC.
     /// This is the "Wire Format" for cache keys and Manifest paths.
+    ///
+    /// macOS hands back decomposed (NFD) filenames while repos are published in NFC, so
+    /// without composing an accented name would never match its own download.
     pub fn normalize(path: &str) -> String {
-        path.replace('\\', "/")
+        let slashed = path.replace('\\', "/");
+        if is_nfc(&slashed) {
+            slashed
+        } else {
+            slashed.nfc().collect()
+        }
     }
 
     /// For comparisons (finding duplicates/diffing), use a canonical key.
     /// This resolves the "Addons" vs "addons" infinite sync loop.
     pub fn canonicalize(path: &str) -> String {
-        Self::normalize(path).to_lowercase()
+        // Lowercasing never produces a backslash, so only pay for normalizing when needed.
+        let lower = path.to_lowercase();
+        if lower.contains('\\') || !is_nfc(&lower) {
+            Self::normalize(&lower)
+        } else {
+            lower
+        }
     }
 
     /// Sanitize a path to prevent directory traversal attacks from a malicious repo.
@@ -25,3 +60,49 @@ impl FleetPath {
                 .any(|c| matches!(c, std::path::Component::ParentDir))
     }
 }
+
+/// Manifest entries in one mod whose paths differ only by case. On a case-insensitive
+/// filesystem they land on the same file and keep overwriting each other.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CaseCollision {
+    pub mod_name: String,
+    /// The casing that is kept: the first one the manifest lists.
+    pub kept: String,
+    pub dropped: Vec<String>,
+}
+
+/// Lists the case-insensitive path collisions within each mod of `manifest`.
+pub fn find_case_collisions(manifest: &Manifest) -> Vec<CaseCollision> {
+    let mut collisions = Vec::new();
+    for m in &manifest.mods {
+        let mut by_key: HashMap<String, usize> = HashMap::new();
+        let mut groups: Vec<CaseCollision> = Vec::new();
+        for f in &m.files {
+            match by_key.entry(FleetPath::canonicalize(&f.path)) {
+                Entry::Occupied(o) => groups[*o.get()].dropped.push(f.path.clone()),
+                Entry::Vacant(v) => {
+                    v.insert(groups.len());
+                    groups.push(CaseCollision {
+                        mod_name: m.name.clone(),
+                        kept: f.path.clone(),
+                        dropped: Vec::new(),
+                    });
+                }
+            }
+        }
+        collisions.extend(groups.into_iter().filter(|g| !g.dropped.is_empty()));
+    }
+    collisions
+}
+
+/// Drops every colliding entry except the first listed casing and returns what was dropped,
+/// so one file is downloaded per on-disk path.
+pub fn resolve_case_collisions(manifest: &mut Manifest) -> Vec<CaseCollision> {
+    let collisions = find_case_collisions(manifest);
+    for m in &mut manifest.mods {
+        let mut seen = HashSet::new();
+        m.files
+            .retain(|f| seen.insert(FleetPath::canonicalize(&f.path)));
+    }
+    collisions
+}
@@ -0,0 +1,62 @@
+use fleet_core::path_utils::{find_case_collisions, resolve_case_collisions, CaseCollision};
+use fleet_core::{File, FileType, Manifest, Mod};
+
+fn make_file(path: &str) -> File {
+    File {
+        path: path.to_string(),
+        length: 1,
+        checksum: "C".to_string(),
+        file_type: FileType::File,
+        parts: vec![],
+        mtime: None,
+    }
+}
+
+fn make_manifest(files: &[&str]) -> Manifest {
+    Manifest {
+        version: "1.0".to_string(),
+        mods: vec![Mod {
+            name: "@m".to_string(),
+            checksum: "M".to_string(),
+            files: files.iter().map(|p| make_file(p)).collect(),
+        }],
+    }
+}
+
+#[test]
+fn distinct_paths_do_not_collide() {
+    let manifest = make_manifest(&["addons/a.pbo", "addons/b.pbo", "mod.cpp"]);
+    assert!(find_case_collisions(&manifest).is_empty());
+}
+
+#[test]
+fn paths_differing_only_by_case_collide() {
+    let manifest = make_manifest(&[
+        "Addons/x.pbo",
+        "addons/y.pbo",
+        "addons/x.pbo",
+        "ADDONS\\X.PBO",
+    ]);
+    assert_eq!(
+        find_case_collisions(&manifest),
+        vec![CaseCollision {
+            mod_name: "@m".to_string(),
+            kept: "Addons/x.pbo".to_string(),
+            dropped: vec!["addons/x.pbo".to_string(), "ADDONS\\X.PBO".to_string()],
+        }]
+    );
+}
+
+#[test]
+fn resolving_keeps_the_first_casing() {
+    let mut manifest = make_manifest(&["addons/y.pbo", "Addons/x.pbo", "addons/x.pbo"]);
+    let collisions = resolve_case_collisions(&mut manifest);
+
+    assert_eq!(collisions.len(), 1);
+    let paths: Vec<_> = manifest.mods[0]
+        .files
+        .iter()
+        .map(|f| f.path.as_str())
+        .collect();
+    assert_eq!(paths, vec!["addons/y.pbo", "Addons/x.pbo"]);
+}
@@ -0,0 +1,69 @@
+use fleet_core::{
+    DeleteAction, DeleteThreshold, File, FileType, Manifest, Mod, RiskLevel, SyncPlan,
+};
+
+fn make_mod(name: &str, file_count: usize) -> Mod {
+    Mod {
+        name: name.to_string(),
+        checksum: "mod_hash".to_string(),
+        files: (0..file_count)
+            .map(|i| File {
+                path: format!("addons/{i}.pbo"),
+                length: 100,
+                checksum: "AAA".to_string(),
+                file_type: FileType::Pbo,
+                parts: vec![],
+                mtime: None,
+            })
+            .collect(),
+    }
+}
+
+fn plan_deleting(paths: &[String]) -> SyncPlan {
+    SyncPlan {
+        renames: vec![],
+        checks: vec![],
+        downloads: vec![],
+        deletes: paths
+            .iter()
+            .map(|p| DeleteAction { path: p.clone() })
+            .collect(),
+    }
+}
+
+#[test]
+fn small_or_proportionate_deletes_are_safe() {
+    let few: Vec<String> = (0..3).map(|i| format!("@a/addons/{i}.pbo")).collect();
+    assert_eq!(plan_deleting(&few).delete_risk(4), RiskLevel::Safe);
+
+    let some: Vec<String> = (0..20).map(|i| format!("@a/addons/{i}.pbo")).collect();
+    assert_eq!(plan_deleting(&some).delete_risk(1000), RiskLevel::Safe);
+}
+
+#[test]
+fn deleting_most_files_or_too_many_is_destructive() {
+    let half: Vec<String> = (0..30).map(|i| format!("@a/addons/{i}.pbo")).collect();
+    assert_eq!(plan_deleting(&half).delete_risk(40), RiskLevel::Destructive);
+
+    let many: Vec<String> = (0..150).map(|i| format!("@a/addons/{i}.pbo")).collect();
+    assert_eq!(
+        plan_deleting(&many).delete_risk(100_000),
+        RiskLevel::Destructive
+    );
+}
+
+#[test]
+fn whole_mod_deletes_are_weighted_by_local_file_count() {
+    let local = Manifest {
+        version: "1.0".to_string(),
+        mods: vec![make_mod("@big", 80), make_mod("@small", 20)],
+    };
+    let plan = plan_deleting(&["@big".to_string()]);
+
+    assert_eq!(plan.delete_risk(100), RiskLevel::Safe);
+    assert_eq!(plan.deleted_file_count(&local), 80);
+    assert_eq!(
+        plan.delete_risk_against(&local, &DeleteThreshold::default()),
+        RiskLevel::Destructive
+    );
+}
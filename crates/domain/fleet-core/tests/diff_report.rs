@@ -0,0 +1,92 @@
+use fleet_core::diff::{diff_report, FileStatus};
+use fleet_core::{File, FileType, Manifest, Mod};
+
+fn make_file(path: &str, checksum: &str, length: u64) -> File {
+    File {
+        path: path.to_string(),
+        length,
+        checksum: checksum.to_string(),
+        file_type: FileType::File,
+        parts: vec![],
+        mtime: None,
+    }
+}
+
+fn make_mod(name: &str, files: Vec<File>) -> Mod {
+    Mod {
+        name: name.to_string(),
+        checksum: "mod_hash".to_string(),
+        files,
+    }
+}
+
+fn make_manifest(mods: Vec<Mod>) -> Manifest {
+    Manifest {
+        version: "1.0".to_string(),
+        mods,
+    }
+}
+
+#[test]
+fn every_file_is_categorized() {
+    let local = make_manifest(vec![
+        make_mod(
+            "@a",
+            vec![
+                make_file("same.pbo", "h1", 10),
+                make_file("edited.pbo", "old", 10),
+                make_file("Addons/case.pbo", "h3", 10),
+                make_file("gone.pbo", "h4", 10),
+            ],
+        ),
+        make_mod("@dropped", vec![make_file("x.pbo", "h5", 7)]),
+    ]);
+    let remote = make_manifest(vec![
+        make_mod(
+            "@a",
+            vec![
+                make_file("same.pbo", "h1", 10),
+                make_file("edited.pbo", "new", 12),
+                make_file("addons/case.pbo", "h3", 10),
+                make_file("fresh.pbo", "h6", 3),
+            ],
+        ),
+        make_mod("@new", vec![make_file("y.pbo", "h7", 4)]),
+    ]);
+
+    let report = diff_report(&remote, &local);
+    let status = |mod_name: &str, path: &str| {
+        report
+            .files
+            .iter()
+            .find(|f| f.mod_name == mod_name && f.path == path)
+            .unwrap_or_else(|| panic!("{mod_name}/{path} missing from report"))
+    };
+
+    assert_eq!(status("@a", "same.pbo").status, FileStatus::Unchanged);
+    let edited = status("@a", "edited.pbo");
+    assert_eq!(edited.status, FileStatus::Changed);
+    assert_eq!((edited.old_size, edited.new_size), (Some(10), Some(12)));
+    assert_eq!(edited.old_checksum.as_deref(), Some("old"));
+    assert_eq!(edited.new_checksum.as_deref(), Some("new"));
+    let renamed = status("@a", "addons/case.pbo");
+    assert_eq!(renamed.status, FileStatus::Renamed);
+    assert_eq!(renamed.renamed_from.as_deref(), Some("@a/Addons/case.pbo"));
+    assert_eq!(status("@a", "gone.pbo").status, FileStatus::Removed);
+    assert_eq!(status("@a", "fresh.pbo").status, FileStatus::Added);
+    assert_eq!(status("@dropped", "x.pbo").status, FileStatus::Removed);
+    assert_eq!(status("@new", "y.pbo").status, FileStatus::Added);
+    assert_eq!(report.files.len(), 7);
+    assert_eq!(report.count(FileStatus::Removed), 2);
+}
+
+#[test]
+fn mod_case_rename_marks_its_files_renamed() {
+    let local = make_manifest(vec![make_mod("@ACE", vec![make_file("a.pbo", "h", 1)])]);
+    let remote = make_manifest(vec![make_mod("@ace", vec![make_file("a.pbo", "h", 1)])]);
+
+    let report = diff_report(&remote, &local);
+    assert_eq!(report.files.len(), 1);
+    assert_eq!(report.files[0].status, FileStatus::Renamed);
+    assert_eq!(report.files[0].renamed_from.as_deref(), Some("@ACE/a.pbo"));
+}
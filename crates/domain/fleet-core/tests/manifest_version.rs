@@ -0,0 +1,63 @@
+use fleet_core::{File, FileType, Manifest, ManifestVersionError, Mod, MANIFEST_VERSION};
+
+fn manifest(version: &str) -> Manifest {
+    Manifest {
+        version: version.to_string(),
+        mods: Vec::new(),
+    }
+}
+
+#[test]
+fn unversioned_manifests_are_upgraded_to_the_current_version() {
+    assert_eq!(manifest("").migrate().unwrap().version, MANIFEST_VERSION);
+    assert_eq!(manifest("1.0").migrate().unwrap().version, "1.0");
+    assert_eq!(manifest("1").migrate().unwrap().version, "1.0");
+}
+
+#[test]
+fn unversioned_paths_are_normalized() {
+    let mut old = manifest("");
+    old.mods.push(Mod {
+        name: "@m".into(),
+        checksum: "C".into(),
+        files: vec![File {
+            path: "addons\\a.pbo".into(),
+            length: 1,
+            checksum: "F".into(),
+            file_type: FileType::File,
+            parts: vec![],
+            mtime: None,
+        }],
+    });
+    let mut versioned = old.clone();
+    versioned.version = "1.0".into();
+
+    assert_eq!(old.migrate().unwrap().mods[0].files[0].path, "addons/a.pbo");
+    // A versioned manifest was written normalized already and is left as it is.
+    assert_eq!(
+        versioned.migrate().unwrap().mods[0].files[0].path,
+        "addons\\a.pbo"
+    );
+}
+
+#[test]
+fn newer_minors_load_but_newer_majors_are_refused() {
+    assert_eq!(manifest("1.7").migrate().unwrap().version, "1.7");
+    assert_eq!(
+        manifest("2.0").migrate().unwrap_err(),
+        ManifestVersionError::Unsupported {
+            found: "2.0".into(),
+            supported: "1".into()
+        }
+    );
+}
+
+#[test]
+fn unparseable_versions_are_invalid() {
+    for version in ["v1", "1.x", "0.9"] {
+        assert_eq!(
+            manifest(version).migrate().unwrap_err(),
+            ManifestVersionError::Invalid(version.into())
+        );
+    }
+}
@@ -0,0 +1,53 @@
+use fleet_core::diff::diff;
+use fleet_core::path_utils::FleetPath;
+use fleet_core::{File, FileType, Manifest, Mod};
+
+// "café": precomposed é (U+00E9) versus e + combining acute (U+0301), as macOS reports it.
+const NFC: &str = "addons/caf\u{e9}.pbo";
+const NFD: &str = "addons/cafe\u{301}.pbo";
+
+fn manifest_with(path: &str) -> Manifest {
+    Manifest {
+        version: "1.0".to_string(),
+        mods: vec![Mod {
+            name: "@m".to_string(),
+            checksum: "M".to_string(),
+            files: vec![File {
+                path: path.to_string(),
+                length: 1,
+                checksum: "C".to_string(),
+                file_type: FileType::File,
+                parts: vec![],
+                mtime: None,
+            }],
+        }],
+    }
+}
+
+#[test]
+fn normalize_composes_decomposed_paths() {
+    assert_ne!(NFC, NFD);
+    assert_eq!(FleetPath::normalize(NFD), NFC);
+    assert_eq!(FleetPath::normalize(NFC), NFC);
+    assert_eq!(
+        FleetPath::normalize("addons\\cafe\u{301}.pbo"),
+        "addons/caf\u{e9}.pbo"
+    );
+}
+
+#[test]
+fn canonicalize_matches_across_normal_forms() {
+    assert_eq!(
+        FleetPath::canonicalize(NFD),
+        FleetPath::canonicalize(&NFC.to_uppercase())
+    );
+}
+
+#[test]
+fn decomposed_local_file_is_not_redownloaded() {
+    let plan = diff(&manifest_with(NFC), &manifest_with(NFD));
+
+    assert!(plan.downloads.is_empty(), "{:?}", plan.downloads);
+    assert!(plan.deletes.is_empty(), "{:?}", plan.deletes);
+    assert_eq!(plan.checks.len(), 1);
+}
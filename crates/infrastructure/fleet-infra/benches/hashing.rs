@@ -0,0 +1,31 @@
+//! Hashing a file above the memory-map threshold. Run it as is and again with
+//! `--no-default-features --features keyring` to compare the mapped and buffered reads.
+use camino::Utf8PathBuf;
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use fleet_infra::hashing::scan_file;
+use std::io::Write;
+
+const FILE_SIZE: usize = 256 * 1024 * 1024;
+
+fn bench_large_file(c: &mut Criterion) {
+    let dir = tempfile::tempdir().unwrap();
+    let root = Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let path = root.join("large.bin");
+    let mut file = std::fs::File::create(&path).unwrap();
+    let block: Vec<u8> = (0..1024 * 1024).map(|i| (i % 251) as u8).collect();
+    for _ in 0..FILE_SIZE / block.len() {
+        file.write_all(&block).unwrap();
+    }
+    drop(file);
+
+    let mut group = c.benchmark_group("hashing");
+    group.sample_size(10);
+    group.throughput(Throughput::Bytes(FILE_SIZE as u64));
+    group.bench_function("256 MiB raw file", |b| {
+        b.iter(|| scan_file(black_box(&path), "large.bin".into()).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_large_file);
+criterion_main!(benches);
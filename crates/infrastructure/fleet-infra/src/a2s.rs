@@ -0,0 +1,126 @@
+//! Steam server queries (A2S_INFO) for showing whether a game server is up and how full it is.
+//!
+//! Arma 3 answers on its Steam query port, which is the game port plus one.
+
+use std::time::Duration;
+use thiserror::Error;
+use tokio::net::UdpSocket;
+
+/// How long to wait for each reply before reporting the server as unreachable.
+pub const DEFAULT_QUERY_TIMEOUT: Duration = Duration::from_secs(2);
+
+const SINGLE_PACKET: [u8; 4] = [0xFF; 4];
+const INFO_REQUEST: &[u8] = b"\x54Source Engine Query\0";
+const INFO_RESPONSE: u8 = 0x49;
+const CHALLENGE_RESPONSE: u8 = 0x41;
+
+#[derive(Error, Debug)]
+pub enum A2sError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Server did not answer within {0:?}")]
+    Timeout(Duration),
+    #[error("Malformed A2S reply: {0}")]
+    Malformed(&'static str),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerInfo {
+    pub name: String,
+    pub map: String,
+    pub players: u8,
+    pub max_players: u8,
+}
+
+/// The Steam query port Arma 3 uses for a server listening on `game_port`.
+pub fn query_port(game_port: u16) -> u16 {
+    game_port.wrapping_add(1)
+}
+
+/// Sends A2S_INFO to `host:port` (the query port), answering a challenge if the server asks
+/// for one.
+pub async fn query_info(host: &str, port: u16, timeout: Duration) -> Result<ServerInfo, A2sError> {
+    let addr = tokio::net::lookup_host((host, port))
+        .await?
+        .next()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "no address"))?;
+    let local: std::net::SocketAddr = if addr.is_ipv4() {
+        ([0, 0, 0, 0], 0).into()
+    } else {
+        ([0u16; 8], 0).into()
+    };
+    let socket = UdpSocket::bind(local).await?;
+    socket.connect(addr).await?;
+
+    let mut request = [&SINGLE_PACKET[..], INFO_REQUEST].concat();
+    let mut buf = vec![0u8; 1400];
+    // Servers may reply with a challenge first; the retry must echo it back.
+    for _ in 0..2 {
+        socket.send(&request).await?;
+        let len = tokio::time::timeout(timeout, socket.recv(&mut buf))
+            .await
+            .map_err(|_| A2sError::Timeout(timeout))??;
+        let payload = buf[..len]
+            .strip_prefix(&SINGLE_PACKET)
+            .ok_or(A2sError::Malformed("unsupported packet header"))?;
+        match payload.split_first() {
+            Some((&CHALLENGE_RESPONSE, challenge)) if challenge.len() >= 4 => {
+                request.truncate(SINGLE_PACKET.len() + INFO_REQUEST.len());
+                request.extend_from_slice(&challenge[..4]);
+            }
+            Some((&INFO_RESPONSE, _)) => return parse_info(payload),
+            _ => return Err(A2sError::Malformed("unexpected reply type")),
+        }
+    }
+    Err(A2sError::Malformed("server kept sending challenges"))
+}
+
+/// Parses an A2S_INFO reply without the leading `FF FF FF FF`.
+pub fn parse_info(payload: &[u8]) -> Result<ServerInfo, A2sError> {
+    let mut reader = Reader(payload);
+    if reader.byte()? != INFO_RESPONSE {
+        return Err(A2sError::Malformed("not an A2S_INFO reply"));
+    }
+    let _protocol = reader.byte()?;
+    let name = reader.string()?;
+    let map = reader.string()?;
+    let _folder = reader.string()?;
+    let _game = reader.string()?;
+    let _app_id = reader.take(2)?;
+    let players = reader.byte()?;
+    let max_players = reader.byte()?;
+    Ok(ServerInfo {
+        name,
+        map,
+        players,
+        max_players,
+    })
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], A2sError> {
+        if self.0.len() < n {
+            return Err(A2sError::Malformed("reply is truncated"));
+        }
+        let (head, rest) = self.0.split_at(n);
+        self.0 = rest;
+        Ok(head)
+    }
+
+    fn byte(&mut self) -> Result<u8, A2sError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn string(&mut self) -> Result<String, A2sError> {
+        let end = self
+            .0
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or(A2sError::Malformed("unterminated string"))?;
+        let s = String::from_utf8_lossy(&self.0[..end]).into_owned();
+        self.0 = &self.0[end + 1..];
+        Ok(s)
+    }
+}
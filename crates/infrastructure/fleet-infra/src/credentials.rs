@@ -0,0 +1,72 @@
+use thiserror::Error;
+
+/// Service name for entries in the OS credential store (Keychain, Credential Manager or the
+/// Secret Service).
+const SERVICE: &str = "fleet-manager";
+
+#[derive(Error, Debug)]
+pub enum CredentialError {
+    #[error("Credential store error: {0}")]
+    Store(String),
+    #[error("Fleet was built without credential store support")]
+    Unsupported,
+}
+
+/// Key under which a server's password is stored: one entry per profile and server address.
+pub fn server_key(profile_id: &str, address: &str, port: u16) -> String {
+    format!("{profile_id}/{address}:{port}")
+}
+
+/// Key under which a profile's repository password or token is stored.
+pub fn repo_key(profile_id: &str) -> String {
+    format!("{profile_id}/repo")
+}
+
+#[cfg(feature = "keyring")]
+fn entry(key: &str) -> Result<keyring::Entry, CredentialError> {
+    keyring::Entry::new(SERVICE, key).map_err(|e| CredentialError::Store(e.to_string()))
+}
+
+/// Returns the stored password, or `None` if nothing was saved for `key`.
+pub fn load_password(key: &str) -> Result<Option<String>, CredentialError> {
+    #[cfg(feature = "keyring")]
+    {
+        match entry(key)?.get_password() {
+            Ok(password) => Ok(Some(password)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(CredentialError::Store(e.to_string())),
+        }
+    }
+    #[cfg(not(feature = "keyring"))]
+    {
+        Ok(None)
+    }
+}
+
+pub fn store_password(key: &str, password: &str) -> Result<(), CredentialError> {
+    #[cfg(feature = "keyring")]
+    {
+        entry(key)?
+            .set_password(password)
+            .map_err(|e| CredentialError::Store(e.to_string()))
+    }
+    #[cfg(not(feature = "keyring"))]
+    {
+        Err(CredentialError::Unsupported)
+    }
+}
+
+/// Removes the stored password; succeeds if there was none.
+pub fn delete_password(key: &str) -> Result<(), CredentialError> {
+    #[cfg(feature = "keyring")]
+    {
+        match entry(key)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(CredentialError::Store(e.to_string())),
+        }
+    }
+    #[cfg(not(feature = "keyring"))]
+    {
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+use std::ffi::OsString;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Renames `from` onto `to`, retrying transient failures (e.g. a file briefly locked by a
+/// virus scanner). Paths on different filesystems are copied and then deleted instead.
+pub async fn robust_rename<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> io::Result<()> {
+    let mut attempt = 0u32;
+    let max_attempts = 8u32;
+    let mut backoff = Duration::from_millis(50);
+
+    loop {
+        match tokio::fs::rename(&from, &to).await {
+            Ok(()) => return Ok(()),
+            Err(e) if e.kind() == io::ErrorKind::CrossesDevices => {
+                return move_across_devices(from.as_ref(), to.as_ref()).await;
+            }
+            Err(e) => {
+                attempt += 1;
+                if attempt >= max_attempts {
+                    return Err(e);
+                }
+                // Sleep with exponential backoff
+                tokio::time::sleep(backoff).await;
+                backoff = std::cmp::min(backoff * 2, Duration::from_millis(2000));
+            }
+        }
+    }
+}
+
+/// Copies `from` to a sibling of `to` and renames it into place, so `to` is never seen
+/// half-written, then removes `from`.
+async fn move_across_devices(from: &Path, to: &Path) -> io::Result<()> {
+    let mut staged = OsString::from(to.as_os_str());
+    staged.push(".moving");
+    let staged = PathBuf::from(staged);
+
+    let copied = match tokio::fs::copy(from, &staged).await {
+        Ok(_) => tokio::fs::rename(&staged, to).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = copied {
+        let _ = tokio::fs::remove_file(&staged).await;
+        return Err(e);
+    }
+    tokio::fs::remove_file(from).await
+}
+
+/// Makes `to` another name for the content of `from`: a hard link where the filesystem
+/// allows it, a copy otherwise (e.g. across devices). `to` is replaced atomically.
+/// Returns whether the content was copied.
+pub async fn link_or_copy(from: &Path, to: &Path) -> io::Result<bool> {
+    if let Some(parent) = to.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let mut staged = OsString::from(to.as_os_str());
+    staged.push(".linking");
+    let staged = PathBuf::from(staged);
+    let _ = tokio::fs::remove_file(&staged).await;
+
+    let copied = match tokio::fs::hard_link(from, &staged).await {
+        Ok(()) => false,
+        Err(_) => {
+            tokio::fs::copy(from, &staged).await?;
+            true
+        }
+    };
+    if let Err(e) = tokio::fs::rename(&staged, to).await {
+        let _ = tokio::fs::remove_file(&staged).await;
+        return Err(e);
+    }
+    Ok(copied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn move_across_devices_replaces_target_and_removes_source() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let from = dir.join("staged.part");
+        let to = dir.join("file.pbo");
+        std::fs::write(&from, b"new").unwrap();
+        std::fs::write(&to, b"old").unwrap();
+
+        move_across_devices(&from, &to).await.unwrap();
+
+        assert_eq!(std::fs::read(&to).unwrap(), b"new");
+        assert!(!from.exists());
+        assert!(!dir.join("file.pbo.moving").exists());
+    }
+
+    #[tokio::test]
+    async fn link_or_copy_replaces_target_and_keeps_source() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let from = dir.join("a.pbo");
+        let to = dir.join("other/b.pbo");
+        std::fs::write(&from, b"shared").unwrap();
+        std::fs::create_dir_all(dir.join("other")).unwrap();
+        std::fs::write(&to, b"stale").unwrap();
+
+        link_or_copy(&from, &to).await.unwrap();
+
+        assert_eq!(std::fs::read(&to).unwrap(), b"shared");
+        assert_eq!(std::fs::read(&from).unwrap(), b"shared");
+        assert!(!dir.join("other/b.pbo.linking").exists());
+    }
+}
@@ -1,12 +1,26 @@
 use byteorder::{LittleEndian, ReadBytesExt};
 use camino::Utf8Path;
+use fleet_core::path_utils::FleetPath;
 use fleet_core::{FilePart, FileType};
 use md5::Context;
+use rayon::prelude::*;
 use std::collections::HashMap;
 use std::fs::File;
 use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
 
 const MAX_PBO_STRING_LEN: usize = 1024;
+/// Upper bound on header entries, far above any real PBO; stops a bogus header from
+/// allocating entries until memory runs out.
+const MAX_PBO_ENTRIES: usize = 1 << 20;
+const MAX_PBO_EXTENSIONS: usize = 1024;
+/// Smallest possible entry header: an empty name's terminator plus five `u32` fields.
+const MIN_PBO_ENTRY_LEN: u64 = 21;
+
+/// Files at least this large are hashed from a memory map instead of 8KB buffered reads.
+#[cfg(feature = "mmap")]
+const MMAP_THRESHOLD: u64 = 64 * 1024 * 1024;
+#[cfg(not(feature = "mmap"))]
+const MMAP_THRESHOLD: u64 = u64::MAX;
 
 #[derive(Debug, thiserror::Error)]
 pub enum ScanError {
@@ -18,6 +32,26 @@ pub enum ScanError {
     Utf8,
 }
 
+/// Nimble/Swifty split raw files into parts of this many bytes.
+pub const DEFAULT_CHUNK_SIZE: u64 = 5_000_000;
+
+/// Tunables for hashing that affect the resulting checksums.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HashOptions {
+    /// Part size used for non-PBO files. Changing this changes every raw file's parts and
+    /// therefore its checksum; only use a non-default value to match a repository that was
+    /// built with a different chunk size. PBO checksums are unaffected.
+    pub chunk_size: u64,
+}
+
+impl Default for HashOptions {
+    fn default() -> Self {
+        Self {
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        }
+    }
+}
+
 /// Compute the checksum string for a file using Swifty/Nimble logic.
 pub fn compute_file_checksum(
     fs_path: &Utf8Path,
@@ -27,45 +61,160 @@ pub fn compute_file_checksum(
     Ok(file.checksum)
 }
 
+/// Checksum of a whole repository: MD5 over each mod's name and checksum in name order, so
+/// it changes whenever any mod does.
+pub fn compute_repo_checksum(mods: &[fleet_core::Mod]) -> String {
+    repo_checksum(mods.iter().map(|m| (m.name.as_str(), m.checksum.as_str())))
+}
+
+/// [`compute_repo_checksum`] over the `requiredMods` entries of a fetched `repo.json`.
+pub fn compute_listed_repo_checksum(mods: &[fleet_core::repo::RepoMod]) -> String {
+    repo_checksum(
+        mods.iter()
+            .map(|m| (m.mod_name.as_str(), m.checksum.as_str())),
+    )
+}
+
+fn repo_checksum<'a>(mods: impl Iterator<Item = (&'a str, &'a str)>) -> String {
+    let mut sorted: Vec<(&str, &str)> = mods.collect();
+    sorted.sort_by_key(|(name, _)| name.to_lowercase());
+    let mut hasher = Context::new();
+    for (name, checksum) in sorted {
+        hasher.consume(name.as_bytes());
+        hasher.consume(checksum.as_bytes());
+    }
+    format!("{:X}", hasher.finalize())
+}
+
 /// Scans a single file (PBO or Raw) and returns a fleet_core::File.
 pub fn scan_file(
     fs_path: &Utf8Path,
     logical_path: &Utf8Path,
+) -> Result<fleet_core::File, ScanError> {
+    scan_file_with_options(fs_path, logical_path, &HashOptions::default())
+}
+
+/// Like [`scan_file`], but with explicit [`HashOptions`].
+pub fn scan_file_with_options(
+    fs_path: &Utf8Path,
+    logical_path: &Utf8Path,
+    options: &HashOptions,
+) -> Result<fleet_core::File, ScanError> {
+    scan_file_impl(fs_path, logical_path, options, MMAP_THRESHOLD)
+}
+
+fn scan_file_impl(
+    fs_path: &Utf8Path,
+    logical_path: &Utf8Path,
+    options: &HashOptions,
+    mmap_threshold: u64,
 ) -> Result<fleet_core::File, ScanError> {
     let extension = logical_path.extension().unwrap_or("").to_lowercase();
 
     if extension == "pbo" {
-        scan_pbo(fs_path, logical_path)
+        scan_pbo(fs_path, logical_path, mmap_threshold)
     } else {
-        scan_raw_file(fs_path, logical_path)
+        scan_raw_file(fs_path, logical_path, options.chunk_size, mmap_threshold)
+    }
+}
+
+// --- Memory-mapped reads ---
+
+/// Maps `fs_path` read-only when it is at least `threshold` bytes. Returns `None` (and the
+/// caller falls back to buffered reads) for small files or when mapping fails, e.g. on some
+/// network filesystems.
+#[cfg(feature = "mmap")]
+fn map_large_file(fs_path: &Utf8Path, len: u64, threshold: u64) -> Option<memmap2::Mmap> {
+    if len < threshold || len == 0 {
+        return None;
+    }
+    let file = File::open(fs_path).ok()?;
+    // SAFETY: the map is read-only and only lives for the duration of the hash. Another
+    // process truncating the file mid-scan can fault the read, the same class of race the
+    // scanner already accepts for files changing underneath it.
+    match unsafe { memmap2::Mmap::map(&file) } {
+        Ok(map) => Some(map),
+        Err(e) => {
+            tracing::debug!("mmap of {} failed, using buffered reads: {}", fs_path, e);
+            None
+        }
     }
 }
 
+#[cfg(not(feature = "mmap"))]
+fn map_large_file(_fs_path: &Utf8Path, _len: u64, _threshold: u64) -> Option<Vec<u8>> {
+    None
+}
+
+/// MD5 of `data[start..start + len]`, clamped to the data like a short read would be.
+fn hash_slice(data: &[u8], start: u64, len: u64) -> String {
+    let begin = usize::try_from(start).unwrap_or(usize::MAX).min(data.len());
+    let end = usize::try_from(start.saturating_add(len))
+        .unwrap_or(usize::MAX)
+        .min(data.len());
+    format!("{:X}", md5::compute(&data[begin..end]))
+}
+
 // --- Raw File Logic ---
 
 fn scan_raw_file(
     fs_path: &Utf8Path,
     logical_path: &Utf8Path,
+    chunk_size: u64,
+    mmap_threshold: u64,
 ) -> Result<fleet_core::File, ScanError> {
+    // A zero-sized chunk would never make progress.
+    let chunk_size = chunk_size.max(1);
+
+    // We can't easily predict file size if we just read stream,
+    // but for the final struct we need total length.
+    let total_len = fs_path.metadata()?.len();
+
+    // Nimble Naming Convention: "{filename}_{end_pos}"
+    let file_name = logical_path.file_name().unwrap_or("unknown");
+
+    let parts = if let Some(map) = map_large_file(fs_path, total_len, mmap_threshold) {
+        map.par_chunks(usize::try_from(chunk_size).unwrap_or(usize::MAX))
+            .enumerate()
+            .map(|(i, chunk)| {
+                let start = i as u64 * chunk_size;
+                raw_part(file_name, start, chunk.len() as u64, md5::compute(chunk))
+            })
+            .collect()
+    } else if total_len > chunk_size {
+        raw_parts_parallel(fs_path, file_name, total_len, chunk_size)?
+    } else {
+        raw_parts_sequential(fs_path, file_name, chunk_size)?
+    };
+
+    Ok(raw_file(logical_path, total_len, parts))
+}
+
+fn raw_part(file_name: &str, start: u64, length: u64, digest: md5::Digest) -> FilePart {
+    FilePart {
+        path: format!("{}_{}", file_name, start + length),
+        length,
+        start,
+        checksum: format!("{:X}", digest),
+    }
+}
+
+/// Streams the file once, hashing `chunk_size` blocks into MD5 parts until EOF.
+fn raw_parts_sequential(
+    fs_path: &Utf8Path,
+    file_name: &str,
+    chunk_size: u64,
+) -> Result<Vec<FilePart>, ScanError> {
     let file = File::open(fs_path)?;
     let mut reader = BufReader::new(file);
 
     let mut parts = Vec::new();
     let mut pos: u64 = 0;
 
-    // Nimble uses 5,000,000 byte chunks
-    const CHUNK_SIZE: u64 = 5_000_000;
-
-    // We can't easily predict file size if we just read stream,
-    // but for the final struct we need total length.
-    let total_len = fs_path.metadata()?.len();
-
-    // Loop until EOF, hashing CHUNK_SIZE blocks into MD5 parts
     loop {
         let mut hasher = Context::new();
-        let mut stream = reader.by_ref().take(CHUNK_SIZE);
+        let mut stream = reader.by_ref().take(chunk_size);
 
-        let pre_copy_pos = pos;
         let mut buf = [0u8; 8192];
         let mut copied = 0u64;
         loop {
@@ -76,38 +225,81 @@ fn scan_raw_file(
             hasher.consume(&buf[..n]);
             copied += n as u64;
         }
-        pos += copied;
 
         if copied == 0 {
             break;
         }
 
-        let hash = format!("{:X}", hasher.finalize());
+        parts.push(raw_part(file_name, pos, copied, hasher.finalize()));
+        pos += copied;
+    }
+
+    Ok(parts)
+}
 
-        // Nimble Naming Convention: "{filename}_{end_pos}"
-        let file_name = logical_path.file_name().unwrap_or("unknown");
+/// Part boundaries only depend on the file and chunk size, so each part is hashed on its
+/// own file handle in parallel. Results come back in part order, which the rollup hash
+/// depends on.
+fn raw_parts_parallel(
+    fs_path: &Utf8Path,
+    file_name: &str,
+    total_len: u64,
+    chunk_size: u64,
+) -> Result<Vec<FilePart>, ScanError> {
+    let part_count = total_len.div_ceil(chunk_size);
+    (0..part_count)
+        .into_par_iter()
+        .map(|i| {
+            let start = i * chunk_size;
+            let length = chunk_size.min(total_len - start);
+            let digest = hash_file_range(fs_path, start, length)?;
+            Ok(raw_part(file_name, start, length, digest))
+        })
+        .collect()
+}
 
-        parts.push(FilePart {
-            path: format!("{}_{}", file_name, pos),
-            length: copied,
-            start: pre_copy_pos,
-            checksum: hash,
-        });
+fn hash_file_range(fs_path: &Utf8Path, start: u64, length: u64) -> Result<md5::Digest, ScanError> {
+    let mut file = File::open(fs_path)?;
+    file.seek(SeekFrom::Start(start))?;
+    let mut stream = BufReader::new(file).take(length);
+
+    let mut hasher = Context::new();
+    let mut buf = [0u8; 8192];
+    let mut copied = 0u64;
+    loop {
+        let n = stream.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.consume(&buf[..n]);
+        copied += n as u64;
     }
 
+    if copied != length {
+        // The file shrank since its size was read; the part layout no longer applies.
+        return Err(ScanError::Io(std::io::Error::new(
+            std::io::ErrorKind::UnexpectedEof,
+            format!("{fs_path} changed while hashing"),
+        )));
+    }
+    Ok(hasher.finalize())
+}
+
+fn raw_file(logical_path: &Utf8Path, total_len: u64, parts: Vec<FilePart>) -> fleet_core::File {
     // Swifty hashes the Uppercase MD5 strings of the parts to get the final hash
     let mut hasher = Context::new();
     for part in &parts {
         hasher.consume(part.checksum.as_bytes());
     }
 
-    Ok(fleet_core::File {
-        path: logical_path.as_str().replace('\\', "/"),
+    fleet_core::File {
+        path: FleetPath::normalize(logical_path.as_str()),
         length: total_len,
         checksum: format!("{:X}", hasher.finalize()),
         file_type: FileType::File,
         parts,
-    })
+        mtime: None,
+    }
 }
 
 // --- PBO Logic ---
@@ -122,17 +314,29 @@ struct PboEntry {
 
 /// Reads the PBO header to determine header length and entry list.
 /// This mimics `nimble/src/pbo.rs` logic exactly.
-fn parse_pbo_metadata<R: BufRead + Seek>(input: &mut R) -> Result<(u64, Vec<PboEntry>), ScanError> {
+///
+/// `total_len` is the file size; a header that is truncated, claims more entries than the
+/// file could hold, or runs past the end of the file is rejected with [`ScanError::PboParse`].
+fn parse_pbo_metadata<R: BufRead + Seek>(
+    input: &mut R,
+    total_len: u64,
+) -> Result<(u64, Vec<PboEntry>), ScanError> {
+    let max_entries = usize::try_from(total_len / MIN_PBO_ENTRY_LEN)
+        .unwrap_or(usize::MAX)
+        .min(MAX_PBO_ENTRIES);
     let mut entries = Vec::new();
 
     loop {
+        if entries.len() >= max_entries {
+            return Err(ScanError::PboParse);
+        }
         let filename = read_null_terminated_string(input)?;
 
-        let type_id = input.read_u32::<LittleEndian>()?;
-        let _original_size = input.read_u32::<LittleEndian>()?;
-        let _offset = input.read_u32::<LittleEndian>()?;
-        let _timestamp = input.read_u32::<LittleEndian>()?;
-        let data_size = input.read_u32::<LittleEndian>()?;
+        let type_id = read_header_u32(input)?;
+        let _original_size = read_header_u32(input)?;
+        let _offset = read_header_u32(input)?;
+        let _timestamp = read_header_u32(input)?;
+        let data_size = read_header_u32(input)?;
 
         if type_id == 0x56657273 {
             read_extensions(input)?;
@@ -151,12 +355,28 @@ fn parse_pbo_metadata<R: BufRead + Seek>(input: &mut R) -> Result<(u64, Vec<PboE
     }
 
     let header_len = input.stream_position()?;
+    if header_len > total_len {
+        return Err(ScanError::PboParse);
+    }
     Ok((header_len, entries))
 }
 
+/// A header that ends mid-field is malformed rather than an I/O failure.
+fn read_header_u32<R: Read>(input: &mut R) -> Result<u32, ScanError> {
+    input
+        .read_u32::<LittleEndian>()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::UnexpectedEof => ScanError::PboParse,
+            _ => ScanError::Io(e),
+        })
+}
+
 fn read_extensions<R: BufRead>(input: &mut R) -> Result<HashMap<String, String>, ScanError> {
     let mut map = HashMap::new();
     loop {
+        if map.len() >= MAX_PBO_EXTENSIONS {
+            return Err(ScanError::PboParse);
+        }
         let key = read_null_terminated_string(input)?;
         if key.is_empty() {
             break;
@@ -186,11 +406,49 @@ fn read_null_terminated_string<R: BufRead>(input: &mut R) -> Result<String, Scan
     Ok(String::from_utf8_lossy(&buf).to_string())
 }
 
-fn scan_pbo(fs_path: &Utf8Path, logical_path: &Utf8Path) -> Result<fleet_core::File, ScanError> {
+fn scan_pbo(
+    fs_path: &Utf8Path,
+    logical_path: &Utf8Path,
+    mmap_threshold: u64,
+) -> Result<fleet_core::File, ScanError> {
+    let total_len = fs_path.metadata()?.len();
+    if let Some(map) = map_large_file(fs_path, total_len, mmap_threshold) {
+        let (header_len, entries) =
+            parse_pbo_metadata(&mut std::io::Cursor::new(&map[..]), total_len)?;
+
+        let mut parts = vec![FilePart {
+            path: "$$HEADER$$".to_string(),
+            length: header_len,
+            start: 0,
+            checksum: hash_slice(&map, 0, header_len),
+        }];
+        let mut current_offset = header_len;
+        for entry in &entries {
+            let size = entry.data_size as u64;
+            parts.push(FilePart {
+                path: entry.filename.clone(),
+                length: size,
+                start: current_offset,
+                checksum: hash_slice(&map, current_offset, size),
+            });
+            current_offset += size;
+        }
+        let remaining = total_len.saturating_sub(current_offset);
+        if remaining > 0 {
+            parts.push(FilePart {
+                path: "$$END$$".to_string(),
+                length: remaining,
+                start: current_offset,
+                checksum: hash_slice(&map, current_offset, remaining),
+            });
+        }
+        return Ok(pbo_file(logical_path, total_len, parts));
+    }
+
     let file = File::open(fs_path)?;
     let mut reader = BufReader::new(file);
 
-    let (header_len, entries) = parse_pbo_metadata(&mut reader)?;
+    let (header_len, entries) = parse_pbo_metadata(&mut reader, total_len)?;
 
     let mut parts = Vec::new();
     let mut current_offset: u64 = 0;
@@ -243,7 +501,6 @@ fn scan_pbo(fs_path: &Utf8Path, logical_path: &Utf8Path) -> Result<fleet_core::F
         current_offset += size;
     }
 
-    let total_len = fs_path.metadata()?.len();
     let remaining = total_len.saturating_sub(current_offset);
 
     if remaining > 0 {
@@ -265,16 +522,138 @@ fn scan_pbo(fs_path: &Utf8Path, logical_path: &Utf8Path) -> Result<fleet_core::F
         });
     }
 
+    Ok(pbo_file(logical_path, total_len, parts))
+}
+
+fn pbo_file(logical_path: &Utf8Path, total_len: u64, parts: Vec<FilePart>) -> fleet_core::File {
     let mut hasher = Context::new();
     for part in &parts {
         hasher.consume(part.checksum.as_bytes());
     }
 
-    Ok(fleet_core::File {
-        path: logical_path.as_str().replace('\\', "/"),
+    fleet_core::File {
+        path: FleetPath::normalize(logical_path.as_str()),
         length: total_len,
         checksum: format!("{:X}", hasher.finalize()),
         file_type: FileType::Pbo,
         parts,
-    })
+        mtime: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn raw_files_are_split_at_the_configured_chunk_size() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let path = camino::Utf8PathBuf::from_path_buf(dir.join("data.bin")).unwrap();
+        std::fs::write(&path, b"0123456789AB").unwrap();
+        let logical = Utf8Path::new("addons/data.bin");
+
+        let default = scan_file(&path, logical).unwrap();
+        let small = scan_file_with_options(&path, logical, &HashOptions { chunk_size: 5 }).unwrap();
+
+        assert_eq!(default.parts.len(), 1);
+        let names: Vec<_> = small.parts.iter().map(|p| p.path.as_str()).collect();
+        assert_eq!(names, ["data.bin_5", "data.bin_10", "data.bin_12"]);
+        assert_eq!(small.length, 12);
+        assert_ne!(small.checksum, default.checksum);
+    }
+
+    #[test]
+    fn mapped_and_buffered_reads_produce_identical_files() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let raw = camino::Utf8PathBuf::from_path_buf(dir.join("data.bin")).unwrap();
+        let data: Vec<u8> = (0..12_345u32).map(|i| (i % 251) as u8).collect();
+        std::fs::write(&raw, &data).unwrap();
+
+        let pbo = camino::Utf8PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../../../test_files/@ace/addons/ace_advanced_ballistics.pbo");
+        let options = HashOptions { chunk_size: 1000 };
+
+        for (path, logical) in [(&raw, "data.bin"), (&pbo, "addons/ace.pbo")] {
+            let logical = Utf8Path::new(logical);
+            let buffered = scan_file_impl(path, logical, &options, u64::MAX).unwrap();
+            let mapped = scan_file_impl(path, logical, &options, 0).unwrap();
+            assert_eq!(mapped, buffered, "{path}");
+        }
+    }
+
+    #[test]
+    fn parallel_part_hashing_matches_sequential() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let path = camino::Utf8PathBuf::from_path_buf(dir.join("data.bin")).unwrap();
+        let data: Vec<u8> = (0..10_007u32).map(|i| (i * 7 % 256) as u8).collect();
+        std::fs::write(&path, &data).unwrap();
+
+        let sequential = raw_parts_sequential(&path, "data.bin", 1000).unwrap();
+        let parallel = raw_parts_parallel(&path, "data.bin", data.len() as u64, 1000).unwrap();
+        let scanned = scan_file_with_options(
+            &path,
+            Utf8Path::new("data.bin"),
+            &HashOptions { chunk_size: 1000 },
+        )
+        .unwrap();
+
+        assert_eq!(sequential.len(), 11);
+        assert_eq!(parallel, sequential);
+        assert_eq!(
+            scanned.checksum,
+            raw_file(Utf8Path::new("data.bin"), data.len() as u64, sequential).checksum
+        );
+    }
+
+    fn pbo_entry(name: &str, type_id: u32, data_size: u32) -> Vec<u8> {
+        let mut bytes = name.as_bytes().to_vec();
+        bytes.push(0);
+        for field in [type_id, 0, 0, 0, data_size] {
+            bytes.extend_from_slice(&field.to_le_bytes());
+        }
+        bytes
+    }
+
+    #[test]
+    fn malformed_pbo_headers_fail_cleanly() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let path = camino::Utf8PathBuf::from_path_buf(dir.join("bad.pbo")).unwrap();
+
+        // Header cut off in the middle of an entry.
+        let mut truncated = pbo_entry("config.bin", 0, 4);
+        truncated.truncate(truncated.len() - 3);
+        std::fs::write(&path, &truncated).unwrap();
+        let res = scan_file(&path, Utf8Path::new("addons/bad.pbo"));
+
+        // Garbage without a string terminator.
+        std::fs::write(&path, vec![0xAB; 4096]).unwrap();
+        let garbage = scan_file(&path, Utf8Path::new("addons/bad.pbo"));
+
+        assert!(matches!(res, Err(ScanError::PboParse)), "{res:?}");
+        assert!(matches!(garbage, Err(ScanError::PboParse)), "{garbage:?}");
+    }
+
+    #[test]
+    fn pbo_headers_larger_than_the_file_are_rejected() {
+        let mut header = Vec::new();
+        for i in 0..10 {
+            header.extend(pbo_entry(&format!("file{i}.sqf"), 0, 1));
+        }
+        header.extend(pbo_entry("", 0, 0));
+        let len = header.len() as u64;
+
+        let parse = |total_len| parse_pbo_metadata(&mut std::io::Cursor::new(&header), total_len);
+        assert_eq!(parse(len).unwrap().1.len(), 10);
+        // More entries than a file this size could hold.
+        assert!(matches!(
+            parse(5 * MIN_PBO_ENTRY_LEN),
+            Err(ScanError::PboParse)
+        ));
+        // Header runs past the end of the file.
+        assert!(matches!(parse(len - 1), Err(ScanError::PboParse)));
+    }
 }
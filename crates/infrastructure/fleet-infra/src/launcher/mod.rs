@@ -14,12 +14,16 @@ pub enum LaunchError {
     Io(#[from] std::io::Error),
     #[error("Command parsing error")]
     ParseError,
+    #[error("Folder does not exist: {0}")]
+    MissingFolder(std::path::PathBuf),
 }
 
 pub struct Launcher {
     exe_path: String,
     base_args: String,
     template: String,
+    env: Vec<(String, String)>,
+    working_dir: Option<std::path::PathBuf>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -27,13 +31,54 @@ pub(crate) struct ResolvedLaunchCommand {
     pub program: String,
     pub args: Vec<String>,
     pub working_dir: std::path::PathBuf,
+    pub env: Vec<(String, String)>,
 }
 
 const MODS_PLACEHOLDER: &str = "__FLEET_MODS__";
 const FLATPAK_STEAM_APP_ID: &str = "com.valvesoftware.Steam";
 const ARMA3_APP_ID: &str = "107410";
 
-fn open_url(url: &str) -> Result<(), LaunchError> {
+/// Puts the mods named in `order` first, in that order, followed by the remaining mods in
+/// their original order. Names match the mod folder name case-insensitively; entries in
+/// `order` without a matching mod are ignored.
+pub fn order_mods(mods: Vec<Utf8PathBuf>, order: &[String]) -> Vec<Utf8PathBuf> {
+    let mut rest = mods;
+    let mut ordered = Vec::with_capacity(rest.len());
+    for name in order {
+        let name = name.trim();
+        if let Some(pos) = rest
+            .iter()
+            .position(|m| m.file_name().is_some_and(|f| f.eq_ignore_ascii_case(name)))
+        {
+            ordered.push(rest.remove(pos));
+        }
+    }
+    ordered.extend(rest);
+    ordered
+}
+
+/// Shows the folder `dir` in the platform's file manager. Fails up front when it doesn't
+/// exist, since the opener would otherwise pop up its own error (or nothing at all).
+pub fn open_folder(dir: &std::path::Path) -> Result<(), LaunchError> {
+    if !dir.is_dir() {
+        return Err(LaunchError::MissingFolder(dir.to_path_buf()));
+    }
+    #[cfg(target_os = "windows")]
+    {
+        std::process::Command::new("explorer")
+            .arg(dir)
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::inherit())
+            .spawn()?;
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    open_url(&dir.to_string_lossy())
+}
+
+/// Opens a URL or local path with the platform's default handler.
+pub fn open_url(url: &str) -> Result<(), LaunchError> {
     #[cfg(target_os = "windows")]
     {
         std::process::Command::new("cmd")
@@ -71,20 +116,27 @@ fn steam_run_url_from_flatpak_cmd(cmd: &ResolvedLaunchCommand) -> Option<String>
     }
 
     // flatpak run com.valvesoftware.Steam -applaunch 107410 <args...>
-    if cmd.args.len() < 4 {
+    // A steam:// URL cannot carry environment variables, so `--env=` options are dropped.
+    let args: Vec<&str> = cmd
+        .args
+        .iter()
+        .map(String::as_str)
+        .filter(|a| !a.starts_with("--env="))
+        .collect();
+    if args.len() < 4 {
         return None;
     }
-    if cmd.args[0] != "run" {
+    if args[0] != "run" {
         return None;
     }
-    if cmd.args[1] != FLATPAK_STEAM_APP_ID {
+    if args[1] != FLATPAK_STEAM_APP_ID {
         return None;
     }
-    if cmd.args[2] != "-applaunch" || cmd.args[3] != ARMA3_APP_ID {
+    if args[2] != "-applaunch" || args[3] != ARMA3_APP_ID {
         return None;
     }
 
-    let extra_args = &cmd.args[4..];
+    let extra_args = &args[4..];
 
     if extra_args.is_empty() {
         // No arguments to forward; just launch the app.
@@ -137,9 +189,24 @@ impl Launcher {
             exe_path,
             base_args,
             template,
+            env: Vec::new(),
+            working_dir: None,
         }
     }
 
+    /// Extra environment variables for the spawned process. Flatpak launches forward them
+    /// into the sandbox with `--env=`.
+    pub fn with_env(mut self, env: Vec<(String, String)>) -> Self {
+        self.env = env;
+        self
+    }
+
+    /// Runs the command from `dir` instead of the directory derived from the template.
+    pub fn with_working_dir(mut self, dir: Option<std::path::PathBuf>) -> Self {
+        self.working_dir = dir;
+        self
+    }
+
     pub(crate) fn resolve_command(
         &self,
         mods: Vec<Utf8PathBuf>,
@@ -188,9 +255,18 @@ impl Launcher {
         }
 
         let program = parts[0].clone();
-        let args = parts[1..].to_vec();
+        let mut args = parts[1..].to_vec();
+
+        // The sandbox does not inherit our environment; pass variables as `run --env=K=V`.
+        if program == "flatpak" && args.first().map(String::as_str) == Some("run") {
+            for (i, (key, value)) in self.env.iter().enumerate() {
+                args.insert(1 + i, format!("--env={key}={value}"));
+            }
+        }
 
-        let working_dir = if self.template.contains("$GAME") {
+        let working_dir = if let Some(dir) = &self.working_dir {
+            dir.clone()
+        } else if self.template.contains("$GAME") {
             std::path::PathBuf::from(&exe_path)
                 .parent()
                 .map(|p| p.to_path_buf())
@@ -203,21 +279,27 @@ impl Launcher {
             program,
             args,
             working_dir,
+            env: self.env.clone(),
         })
     }
 
     pub fn launch(&self, mods: Vec<Utf8PathBuf>) -> Result<(), LaunchError> {
         let cmd = self.resolve_command(mods)?;
 
-        // Log the resolved command so it can be inspected when debugging launch issues.
-        eprintln!(
-            "[fleet] Launching program: {:?}, args: {:?}, cwd: {:?}",
-            cmd.program, cmd.args, cmd.working_dir
+        // Log the resolved command so it can be inspected when debugging launch issues,
+        // without the server password or environment values.
+        tracing::debug!(
+            program = %cmd.program,
+            args = ?redacted_args(&cmd.args),
+            cwd = %cmd.working_dir.display(),
+            env = ?cmd.env.iter().map(|(k, _)| k).collect::<Vec<_>>(),
+            "launching"
         );
 
         std::process::Command::new(&cmd.program)
             .args(&cmd.args)
             .current_dir(&cmd.working_dir)
+            .envs(cmd.env.iter().map(|(k, v)| (k, v)))
             .stdout(Stdio::inherit())
             .stderr(Stdio::inherit())
             .spawn()?;
@@ -225,10 +307,36 @@ impl Launcher {
     }
 }
 
+/// `args` for logging: `-password=` values and flatpak `--env=` values are masked.
+fn redacted_args(args: &[String]) -> Vec<String> {
+    args.iter()
+        .map(|arg| {
+            if let Some(rest) = arg.strip_prefix("--env=") {
+                let key = rest.split_once('=').map_or(rest, |(k, _)| k);
+                format!("--env={key}=<redacted>")
+            } else if arg.to_ascii_lowercase().starts_with("-password=") {
+                "-password=<redacted>".to_string()
+            } else {
+                arg.clone()
+            }
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn opening_a_missing_folder_fails_without_spawning() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("no-such-folder");
+        assert!(matches!(
+            open_folder(&dir),
+            Err(LaunchError::MissingFolder(missing)) if missing == dir
+        ));
+    }
+
     #[test]
     #[cfg(target_os = "windows")]
     fn windows_mod_paths_survive_template_splitting_without_quotes() {
@@ -311,6 +419,7 @@ mod tests {
                 "-mod=C:\\mods\\@ace;".to_string(),
             ],
             working_dir: std::path::PathBuf::from("."),
+            env: vec![],
         };
 
         let url = steam_run_url_from_flatpak_cmd(&cmd).expect("expected url");
@@ -319,4 +428,95 @@ mod tests {
             "steam://run/107410//%2DnoSplash%20%2DnoLauncher%20%2Dmod%3DC%3A%5Cmods%5C%40ace%3B/"
         );
     }
+
+    #[test]
+    fn env_and_working_dir_overrides_are_applied() {
+        let launcher = Launcher::new(
+            "/games/arma3/arma3_x64.exe".to_string(),
+            "-noSplash".to_string(),
+            "$GAME $ARGS $MODS".to_string(),
+        )
+        .with_env(vec![(
+            "STEAM_COMPAT_DATA_PATH".to_string(),
+            "/data/pfx".to_string(),
+        )])
+        .with_working_dir(Some(std::path::PathBuf::from("/work")));
+
+        let cmd = launcher.resolve_command(vec![]).expect("expected command");
+
+        assert_eq!(cmd.working_dir, std::path::PathBuf::from("/work"));
+        assert_eq!(
+            cmd.env,
+            vec![(
+                "STEAM_COMPAT_DATA_PATH".to_string(),
+                "/data/pfx".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn flatpak_launch_forwards_env_into_the_sandbox() {
+        let launcher = Launcher::new(
+            "".to_string(),
+            "-noSplash".to_string(),
+            "flatpak run com.valvesoftware.Steam -applaunch 107410 $ARGS".to_string(),
+        )
+        .with_env(vec![("DXVK_HUD".to_string(), "fps".to_string())]);
+
+        let cmd = launcher.resolve_command(vec![]).expect("expected command");
+
+        assert_eq!(cmd.args[0], "run");
+        assert_eq!(cmd.args[1], "--env=DXVK_HUD=fps");
+        assert_eq!(cmd.args[2], FLATPAK_STEAM_APP_ID);
+
+        let url = steam_run_url_from_flatpak_cmd(&cmd).expect("expected url");
+        assert_eq!(url, "steam://run/107410//%2DnoSplash/");
+    }
+
+    #[test]
+    fn explicit_mod_order_comes_first_and_keeps_the_rest() {
+        let mods = vec![
+            Utf8PathBuf::from("/mods/@ace"),
+            Utf8PathBuf::from("/mods/@cba_a3"),
+            Utf8PathBuf::from("/mods/@rhs"),
+            Utf8PathBuf::from("/mods/@tfar"),
+        ];
+        let order = vec![
+            "@CBA_A3".to_string(),
+            "@missing".to_string(),
+            "@rhs".to_string(),
+        ];
+
+        let ordered = order_mods(mods, &order);
+
+        assert_eq!(
+            ordered,
+            vec![
+                Utf8PathBuf::from("/mods/@cba_a3"),
+                Utf8PathBuf::from("/mods/@rhs"),
+                Utf8PathBuf::from("/mods/@ace"),
+                Utf8PathBuf::from("/mods/@tfar"),
+            ]
+        );
+    }
+
+    #[test]
+    fn logged_args_hide_the_password_and_env_values() {
+        let args = vec![
+            "run".to_string(),
+            "--env=API_TOKEN=abc".to_string(),
+            "-connect=1.2.3.4".to_string(),
+            "-password=hunter2".to_string(),
+        ];
+
+        assert_eq!(
+            redacted_args(&args),
+            vec![
+                "run",
+                "--env=API_TOKEN=<redacted>",
+                "-connect=1.2.3.4",
+                "-password=<redacted>",
+            ]
+        );
+    }
 }
@@ -1,8 +1,19 @@
+pub mod a2s;
+pub mod credentials;
+pub mod fs;
 pub mod hashing;
 pub mod launcher;
 pub mod net;
+pub mod signatures;
 
 // Re-exports for convenience
-pub use hashing::{compute_file_checksum, scan_file, ScanError};
-pub use launcher::{LaunchError, Launcher};
-pub use net::{DownloadEvent, DownloadRequest, DownloadResult, Downloader};
+pub use hashing::{
+    compute_file_checksum, compute_listed_repo_checksum, compute_repo_checksum, scan_file,
+    scan_file_with_options, HashOptions, ScanError, DEFAULT_CHUNK_SIZE,
+};
+pub use launcher::{open_folder, open_url, order_mods, LaunchError, Launcher};
+pub use net::{
+    CircuitBreaker, DownloadError, DownloadEvent, DownloadRequest, DownloadResult, Downloader,
+    HttpClientError, NetConfig, RateLimit, RepoAuth, SegmentedDownloads, UploadRequest, Uploader,
+};
+pub use signatures::{check_mod_signatures, SignatureReport};
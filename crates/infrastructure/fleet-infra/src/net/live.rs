@@ -0,0 +1,256 @@
+//! Live repo-change notifications over Server-Sent Events.
+//!
+//! A repository may advertise an SSE endpoint that emits an event whenever it publishes an
+//! update. Every dispatched event counts as a change; `:` comment lines only keep the
+//! connection alive. WebSocket endpoints are not supported.
+
+use super::{DownloadError, StreamExt};
+use reqwest::Client;
+use std::time::Duration;
+use tokio::sync::mpsc::Sender;
+use tracing::warn;
+
+/// First reconnect delay after a dropped or refused connection.
+pub const RECONNECT_MIN: Duration = Duration::from_secs(1);
+
+/// Reconnect delays double up to this cap while the endpoint stays unreachable.
+pub const RECONNECT_MAX: Duration = Duration::from_secs(60);
+
+/// One dispatched SSE event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiveEvent {
+    /// The `event:` field; `message` when the server sent none.
+    pub event: String,
+    pub data: String,
+}
+
+/// Subscribes to `url` and forwards its events to `tx`, reconnecting with exponential
+/// backoff whenever the stream ends or fails. Returns once `tx` is closed, or right away
+/// for URLs that are not http(s). `client` should have no read timeout (see
+/// [`super::streaming_http_client`]), or an idle stream is dropped and reconnected.
+pub async fn watch(client: Client, url: String, tx: Sender<LiveEvent>) {
+    watch_with_backoff(client, url, tx, RECONNECT_MIN, RECONNECT_MAX).await
+}
+
+async fn watch_with_backoff(
+    client: Client,
+    url: String,
+    tx: Sender<LiveEvent>,
+    min: Duration,
+    max: Duration,
+) {
+    if !(url.starts_with("http://") || url.starts_with("https://")) {
+        warn!("Live updates need an http(s) SSE endpoint, got {url}");
+        return;
+    }
+    let mut backoff = Backoff::new(min, max);
+    loop {
+        tokio::select! {
+            _ = tx.closed() => return,
+            res = listen(&client, &url, &tx, &mut backoff) => {
+                if let Err(e) = res {
+                    warn!("Live update stream {url} failed: {e}");
+                }
+            }
+        }
+        let delay = backoff.next_delay();
+        tokio::select! {
+            _ = tx.closed() => return,
+            _ = tokio::time::sleep(delay) => {}
+        }
+    }
+}
+
+/// Reads one connection until it ends; a successful connect resets `backoff`.
+async fn listen(
+    client: &Client,
+    url: &str,
+    tx: &Sender<LiveEvent>,
+    backoff: &mut Backoff,
+) -> Result<(), DownloadError> {
+    let resp = client
+        .get(url)
+        .header(reqwest::header::ACCEPT, "text/event-stream")
+        .send()
+        .await
+        .map_err(|e| DownloadError::Network(e.to_string()))?;
+    if !resp.status().is_success() {
+        return Err(DownloadError::Status(resp.status().as_u16()));
+    }
+    backoff.reset();
+
+    let mut parser = SseParser::default();
+    let mut stream = resp.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| DownloadError::Network(e.to_string()))?;
+        for event in parser.feed(&chunk) {
+            if tx.send(event).await.is_err() {
+                return Ok(());
+            }
+        }
+        if let Some(retry) = parser.retry.take() {
+            backoff.min = retry;
+            backoff.reset();
+        }
+    }
+    Ok(())
+}
+
+struct Backoff {
+    min: Duration,
+    max: Duration,
+    next: Duration,
+}
+
+impl Backoff {
+    fn new(min: Duration, max: Duration) -> Self {
+        Self {
+            min,
+            max,
+            next: min,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.next = self.min;
+    }
+
+    fn next_delay(&mut self) -> Duration {
+        let delay = self.next;
+        self.next = (self.next * 2).min(self.max.max(self.min));
+        delay
+    }
+}
+
+/// Incremental `text/event-stream` parser; chunks may split lines anywhere.
+#[derive(Default)]
+struct SseParser {
+    pending: Vec<u8>,
+    event: String,
+    data: Vec<String>,
+    /// Reconnect delay requested by the server's latest `retry:` field.
+    retry: Option<Duration>,
+}
+
+impl SseParser {
+    fn feed(&mut self, chunk: &[u8]) -> Vec<LiveEvent> {
+        self.pending.extend_from_slice(chunk);
+        let mut events = Vec::new();
+        while let Some(end) = self.pending.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(['\n', '\r']);
+            if line.is_empty() {
+                if let Some(event) = self.dispatch() {
+                    events.push(event);
+                }
+                continue;
+            }
+            let (field, value) = line.split_once(':').unwrap_or((line, ""));
+            let value = value.strip_prefix(' ').unwrap_or(value);
+            match field {
+                "event" => self.event = value.to_string(),
+                "data" => self.data.push(value.to_string()),
+                "retry" => {
+                    if let Ok(ms) = value.parse() {
+                        self.retry = Some(Duration::from_millis(ms));
+                    }
+                }
+                // Comments (empty field name), `id` and unknown fields.
+                _ => {}
+            }
+        }
+        events
+    }
+
+    fn dispatch(&mut self) -> Option<LiveEvent> {
+        let event = std::mem::take(&mut self.event);
+        if self.data.is_empty() {
+            return None;
+        }
+        Some(LiveEvent {
+            event: if event.is_empty() {
+                "message".to_string()
+            } else {
+                event
+            },
+            data: std::mem::take(&mut self.data).join("\n"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[test]
+    fn parser_handles_split_chunks_comments_and_multiline_data() {
+        let mut parser = SseParser::default();
+        assert!(parser.feed(b": keep-alive\n\nevent: repo").is_empty());
+        let events = parser.feed(b"-updated\r\ndata: a\ndata: b\n\ndata: plain\n\nretry: 5000\n");
+        assert_eq!(
+            events,
+            [
+                LiveEvent {
+                    event: "repo-updated".into(),
+                    data: "a\nb".into(),
+                },
+                LiveEvent {
+                    event: "message".into(),
+                    data: "plain".into(),
+                },
+            ]
+        );
+        assert_eq!(parser.retry, Some(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap_and_resets() {
+        let mut backoff = Backoff::new(Duration::from_secs(1), Duration::from_secs(5));
+        let delays: Vec<u64> = (0..5).map(|_| backoff.next_delay().as_secs()).collect();
+        assert_eq!(delays, [1, 2, 4, 5, 5]);
+        backoff.reset();
+        assert_eq!(backoff.next_delay(), Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn watch_reconnects_after_the_stream_closes() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            for n in 0..2 {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buf = [0u8; 1024];
+                let _ = socket.read(&mut buf).await.unwrap();
+                let body = format!("data: change {n}\n\n");
+                let reply = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                socket.write_all(reply.as_bytes()).await.unwrap();
+            }
+        });
+
+        let (tx, mut rx) = tokio::sync::mpsc::channel(4);
+        let watcher = tokio::spawn(watch_with_backoff(
+            Client::new(),
+            format!("http://{addr}/events"),
+            tx,
+            Duration::from_millis(10),
+            Duration::from_millis(10),
+        ));
+        for n in 0..2 {
+            let event = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(event.data, format!("change {n}"));
+        }
+        drop(rx);
+        tokio::time::timeout(Duration::from_secs(5), watcher)
+            .await
+            .expect("watch returns once the receiver is gone")
+            .unwrap();
+    }
+}
@@ -1,19 +1,266 @@
+use bytes::Bytes;
 use camino::Utf8PathBuf;
-use futures::stream::{self, StreamExt};
+use futures::stream::{self, Stream, StreamExt};
 use governor::clock::DefaultClock;
 use governor::middleware::NoOpMiddleware;
 use governor::state::{InMemoryState, NotKeyed};
 use governor::{Quota, RateLimiter};
 use reqwest::Client;
+use std::collections::HashMap;
 use std::num::NonZeroU32;
 use std::sync::Arc;
 use std::time::Duration;
 use std::time::Instant;
 use tokio::fs::File;
-use tokio::io::AsyncWriteExt;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
 use tokio::sync::mpsc::Sender;
+use tokio::sync::Semaphore;
 use tracing::warn;
 
+pub mod live;
+
+type ByteLimiter = RateLimiter<NotKeyed, InMemoryState, DefaultClock, NoOpMiddleware>;
+
+/// Tries per file before a transfer is reported as failed.
+const TRANSFER_ATTEMPTS: u32 = 3;
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+/// Longest `Retry-After` honored; a server asking for more gets retried after this anyway.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+fn byte_limiter(rate_limit_bytes: Option<u64>) -> Option<Arc<ByteLimiter>> {
+    rate_limit_bytes.and_then(|bps| {
+        NonZeroU32::new(bps as u32).map(|nz| Arc::new(RateLimiter::direct(Quota::per_second(nz))))
+    })
+}
+
+/// A bytes-per-second limit shared by every transfer of a batch. Clones share the limit, so
+/// [`Self::set`] retunes downloads that are already running.
+#[derive(Clone, Default)]
+pub struct RateLimit {
+    inner: Arc<std::sync::RwLock<LimitState>>,
+}
+
+#[derive(Default)]
+struct LimitState {
+    bytes_per_sec: Option<u64>,
+    limiter: Option<Arc<ByteLimiter>>,
+}
+
+impl RateLimit {
+    /// `None` (or 0) is unlimited.
+    pub fn new(bytes_per_sec: Option<u64>) -> Self {
+        let limit = Self::default();
+        limit.set(bytes_per_sec);
+        limit
+    }
+
+    pub fn get(&self) -> Option<u64> {
+        self.inner
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .bytes_per_sec
+    }
+
+    /// Changes the limit; chunks already waiting finish under the old one.
+    pub fn set(&self, bytes_per_sec: Option<u64>) {
+        let bytes_per_sec = bytes_per_sec.filter(|&bps| bps > 0);
+        let mut inner = self.inner.write().unwrap_or_else(|e| e.into_inner());
+        if inner.bytes_per_sec != bytes_per_sec {
+            *inner = LimitState {
+                bytes_per_sec,
+                limiter: byte_limiter(bytes_per_sec),
+            };
+        }
+    }
+
+    /// Waits until `len` more bytes fit under the current limit.
+    async fn acquire(&self, len: usize) {
+        let limiter = self
+            .inner
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .limiter
+            .clone();
+        if let (Some(l), Some(nz)) = (limiter, NonZeroU32::new(len as u32)) {
+            l.until_n_ready(nz).await.ok();
+        }
+    }
+}
+
+impl std::fmt::Debug for RateLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("RateLimit").field(&self.get()).finish()
+    }
+}
+
+/// Caps the transfers open to any one host; hosts are tracked as they are first seen.
+struct HostLimits {
+    per_host: usize,
+    hosts: std::sync::Mutex<HashMap<String, Arc<Semaphore>>>,
+}
+
+impl HostLimits {
+    fn new(per_host: usize) -> Self {
+        Self {
+            per_host: per_host.max(1),
+            hosts: Default::default(),
+        }
+    }
+
+    /// Waits for a free connection to `url`'s host; `file://` URLs are never limited.
+    async fn acquire(&self, url: &str) -> Option<tokio::sync::OwnedSemaphorePermit> {
+        let host = host_key(url)?;
+        let semaphore = self
+            .hosts
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(host)
+            .or_insert_with(|| Arc::new(Semaphore::new(self.per_host)))
+            .clone();
+        semaphore.acquire_owned().await.ok()
+    }
+}
+
+/// `host:port` of an HTTP URL; `None` for `file://` and unparseable URLs.
+fn host_key(url: &str) -> Option<String> {
+    let url = reqwest::Url::parse(url).ok()?;
+    Some(format!(
+        "{}:{}",
+        url.host_str()?,
+        url.port_or_known_default()?
+    ))
+}
+
+/// Consecutive failures against one host before [`CircuitBreaker`] gives up on it.
+pub const DEFAULT_MAX_HOST_FAILURES: usize = 5;
+
+/// Stops talking to a host after `threshold` consecutive failures (connection errors,
+/// stalls, 5xx), so a server that is down fails the sync quickly instead of every request
+/// running its full retry schedule first. Any other answer from the host resets its count.
+/// Once open, a host stays open for the life of the breaker; make one per sync.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    threshold: usize,
+    failures: std::sync::Mutex<HashMap<String, usize>>,
+}
+
+impl CircuitBreaker {
+    pub fn new(threshold: usize) -> Self {
+        Self {
+            threshold: threshold.max(1),
+            failures: Default::default(),
+        }
+    }
+
+    /// The host of `url` has failed `threshold` times in a row; `file://` URLs never trip.
+    pub fn is_open(&self, url: &str) -> bool {
+        host_key(url).is_some_and(|host| self.failures_for(&host) >= self.threshold)
+    }
+
+    /// The first host that tripped the breaker, with its failure count.
+    pub fn open_host(&self) -> Option<(String, usize)> {
+        self.failures
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .filter(|(_, &n)| n >= self.threshold)
+            .min_by(|a, b| a.0.cmp(b.0))
+            .map(|(host, &n)| (host.clone(), n))
+    }
+
+    pub fn record_failure(&self, url: &str) {
+        if let Some(host) = host_key(url) {
+            *self
+                .failures
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .entry(host)
+                .or_default() += 1;
+        }
+    }
+
+    /// The host answered; its count starts over unless the breaker is already open.
+    pub fn record_success(&self, url: &str) {
+        if let Some(host) = host_key(url) {
+            let mut failures = self.failures.lock().unwrap_or_else(|e| e.into_inner());
+            if failures.get(&host).is_some_and(|&n| n < self.threshold) {
+                failures.remove(&host);
+            }
+        }
+    }
+
+    /// Counts a finished download by its final error, once per file however many attempts
+    /// it took: host-level errors are failures, anything the host actually answered
+    /// (including 404s and bad checksums) is a success, and local IO errors are ignored.
+    fn record(&self, url: &str, error: Option<&DownloadError>) {
+        match error {
+            Some(e) if e.is_host_failure() => self.record_failure(url),
+            Some(DownloadError::Io(_) | DownloadError::HostUnavailable(_)) => {}
+            _ => self.record_success(url),
+        }
+    }
+
+    fn failures_for(&self, host: &str) -> usize {
+        self.failures
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(host)
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
+/// How long a 429/503 response asks the client to wait: delta-seconds or an HTTP date,
+/// capped at [`MAX_RETRY_AFTER`].
+fn retry_after(resp: &reqwest::Response) -> Option<Duration> {
+    let value = resp
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim();
+    let wait = match value.parse::<u64>() {
+        Ok(secs) => Duration::from_secs(secs),
+        Err(_) => httpdate::parse_http_date(value)
+            .ok()?
+            .duration_since(std::time::SystemTime::now())
+            .unwrap_or_default(),
+    };
+    Some(wait.min(MAX_RETRY_AFTER))
+}
+
+/// Read size for copies out of a `file://` mirror.
+const LOCAL_CHUNK: usize = 256 * 1024;
+
+/// The path behind a `file://` download URL, or `None` for anything fetched over HTTP.
+fn local_source(url: &str) -> Option<std::path::PathBuf> {
+    let url = reqwest::Url::parse(url).ok()?;
+    if url.scheme() != "file" {
+        return None;
+    }
+    url.to_file_path().ok()
+}
+
+/// Streams a local file in `LOCAL_CHUNK`-sized pieces, mirroring an HTTP body stream; also
+/// the body of uploads.
+fn local_chunks(src: File) -> impl Stream<Item = Result<Bytes, DownloadError>> {
+    stream::try_unfold(src, |mut src| async move {
+        let mut buf = vec![0u8; LOCAL_CHUNK];
+        let n = src
+            .read(&mut buf)
+            .await
+            .map_err(|e| DownloadError::Io(e.to_string()))?;
+        if n == 0 {
+            return Ok(None);
+        }
+        buf.truncate(n);
+        Ok(Some((Bytes::from(buf), src)))
+    })
+}
+
+/// Removes a download's `.part` file when dropped unless disarmed, so failed attempts and
+/// downloads abandoned mid-await (a cancelled batch drops its in-flight futures) leave no
+/// orphaned temp files.
 #[derive(Debug)]
 struct PartFileCleanup {
     path: Utf8PathBuf,
@@ -39,20 +286,278 @@ impl Drop for PartFileCleanup {
     }
 }
 
-pub fn default_http_client() -> Result<Client, reqwest::Error> {
-    Client::builder()
-        .connect_timeout(Duration::from_secs(10))
-        .timeout(Duration::from_secs(30 * 60))
-        .build()
+/// `User-Agent` sent unless [`NetConfig::user_agent`] overrides it.
+pub const DEFAULT_USER_AGENT: &str = concat!("fleet/", env!("CARGO_PKG_VERSION"));
+
+/// Header carrying the client version, so repository operators can tell Fleet releases apart.
+pub const VERSION_HEADER: &str = "X-Fleet-Version";
+
+/// Connect timeout used when `NetConfig::connect_timeout` is unset.
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Maximum time without receiving any bytes before a request is aborted.
+pub const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How long a download may go without receiving a byte before the attempt is abandoned
+/// and retried.
+pub const DEFAULT_STALL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Optional network settings applied on top of the default client configuration.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct NetConfig {
+    /// Proxy URL used for all requests (e.g. `http://proxy.local:3128`).
+    pub proxy: Option<String>,
+    /// Extra headers sent with every request (e.g. `Authorization`).
+    pub headers: Vec<(String, String)>,
+    /// Replaces [`DEFAULT_USER_AGENT`] and drops the [`VERSION_HEADER`], so requests no
+    /// longer reveal the client version.
+    pub user_agent: Option<String>,
+    pub connect_timeout: Option<Duration>,
+    /// Stall timeout: applies to each read, so long downloads are fine as long as bytes keep flowing.
+    pub read_timeout: Option<Duration>,
+    /// Skip all network operations; callers fall back to cached state. A client built with
+    /// this set fails every request without connecting.
+    pub offline: bool,
+}
+
+impl NetConfig {
+    /// Adds an `Authorization: Bearer <token>` header.
+    pub fn with_bearer_token(mut self, token: &str) -> Self {
+        self.headers
+            .push(("Authorization".to_string(), format!("Bearer {token}")));
+        self
+    }
+
+    /// Sends `auth` with every request, replacing any `Authorization` header already set.
+    pub fn with_repo_auth(mut self, auth: &RepoAuth) -> Self {
+        self.headers
+            .retain(|(name, _)| !name.trim().eq_ignore_ascii_case("authorization"));
+        let value = match auth {
+            RepoAuth::Basic { username, password } => {
+                use base64::Engine as _;
+                let encoded = base64::engine::general_purpose::STANDARD
+                    .encode(format!("{username}:{password}"));
+                format!("Basic {encoded}")
+            }
+            RepoAuth::Bearer(token) => format!("Bearer {token}"),
+        };
+        self.headers.push(("Authorization".to_string(), value));
+        self
+    }
+}
+
+// Hand-written so credentials in `Authorization` (or a proxy URL) never end up in logs.
+impl std::fmt::Debug for NetConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let headers: Vec<(&str, &str)> = self
+            .headers
+            .iter()
+            .map(|(name, value)| {
+                if is_sensitive_header(name) {
+                    (name.as_str(), "<redacted>")
+                } else {
+                    (name.as_str(), value.as_str())
+                }
+            })
+            .collect();
+        f.debug_struct("NetConfig")
+            .field("proxy", &self.proxy.as_ref().map(|_| "<set>"))
+            .field("headers", &headers)
+            .field("user_agent", &self.user_agent)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("read_timeout", &self.read_timeout)
+            .field("offline", &self.offline)
+            .finish()
+    }
+}
+
+fn is_sensitive_header(name: &str) -> bool {
+    let name = name.trim();
+    name.eq_ignore_ascii_case("authorization") || name.eq_ignore_ascii_case("proxy-authorization")
+}
+
+/// Credentials for a private repository, sent with the repo.json, mod.srf and file requests.
+#[derive(Clone, PartialEq, Eq)]
+pub enum RepoAuth {
+    Basic { username: String, password: String },
+    Bearer(String),
+}
+
+impl std::fmt::Debug for RepoAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RepoAuth::Basic { username, .. } => f
+                .debug_struct("Basic")
+                .field("username", username)
+                .field("password", &"<redacted>")
+                .finish(),
+            RepoAuth::Bearer(_) => f.debug_tuple("Bearer").field(&"<redacted>").finish(),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum HttpClientError {
+    #[error("HTTP client error: {0}")]
+    Client(#[from] reqwest::Error),
+    #[error("Invalid proxy URL '{url}': {source}")]
+    Proxy { url: String, source: reqwest::Error },
+    #[error("Invalid header '{0}'")]
+    Header(String),
+}
+
+/// Proxy every request of an offline client is sent through; never resolved.
+const OFFLINE_PROXY: &str = "http://offline.invalid";
+
+/// Resolves no name, so an offline client cannot open a connection.
+struct OfflineResolver;
+
+impl reqwest::dns::Resolve for OfflineResolver {
+    fn resolve(&self, _name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        Box::pin(async { Err("offline mode is on; no network requests are made".into()) })
+    }
+}
+
+pub fn default_http_client(config: Option<&NetConfig>) -> Result<Client, HttpClientError> {
+    let read_timeout = config
+        .and_then(|c| c.read_timeout)
+        .unwrap_or(DEFAULT_READ_TIMEOUT);
+    build_http_client(config, Some(read_timeout))
+}
+
+/// Like [`default_http_client`], but without a read timeout, for long-lived streams such as
+/// [`live::watch`] that stay silent between events.
+pub fn streaming_http_client(config: Option<&NetConfig>) -> Result<Client, HttpClientError> {
+    build_http_client(config, None)
+}
+
+fn build_http_client(
+    config: Option<&NetConfig>,
+    read_timeout: Option<Duration>,
+) -> Result<Client, HttpClientError> {
+    let connect_timeout = config
+        .and_then(|c| c.connect_timeout)
+        .unwrap_or(DEFAULT_CONNECT_TIMEOUT);
+
+    // No total timeout: a large PBO on a slow or rate-limited link may legitimately take
+    // longer than any fixed budget. The read timeout catches dead connections instead.
+    // Advertise gzip/deflate/brotli so servers can compress repo.json and srf metadata;
+    // replies are decoded transparently and uncompressed ones are unaffected.
+    let mut builder = Client::builder()
+        .connect_timeout(connect_timeout)
+        .gzip(true)
+        .deflate(true)
+        .brotli(true);
+    if let Some(read_timeout) = read_timeout {
+        builder = builder.read_timeout(read_timeout);
+    }
+
+    let custom_agent = config
+        .and_then(|c| c.user_agent.as_deref())
+        .map(str::trim)
+        .filter(|u| !u.is_empty());
+    let mut headers = reqwest::header::HeaderMap::new();
+    match custom_agent {
+        Some(ua) => builder = builder.user_agent(ua),
+        None => {
+            builder = builder.user_agent(DEFAULT_USER_AGENT);
+            headers.insert(
+                VERSION_HEADER,
+                reqwest::header::HeaderValue::from_static(env!("CARGO_PKG_VERSION")),
+            );
+        }
+    }
+
+    if let Some(config) = config.filter(|c| c.offline) {
+        // Every request goes to a proxy that only the offline resolver is asked about, so
+        // URLs with an IP address fail as well instead of connecting.
+        let proxy =
+            reqwest::Proxy::all(OFFLINE_PROXY).map_err(|source| HttpClientError::Proxy {
+                url: OFFLINE_PROXY.to_string(),
+                source,
+            })?;
+        builder = builder.proxy(proxy).dns_resolver(Arc::new(OfflineResolver));
+    } else if let Some(config) = config {
+        if let Some(url) = config.proxy.as_deref().filter(|u| !u.trim().is_empty()) {
+            let proxy =
+                reqwest::Proxy::all(url.trim()).map_err(|source| HttpClientError::Proxy {
+                    url: url.to_string(),
+                    source,
+                })?;
+            builder = builder.proxy(proxy);
+        }
+
+        for (name, value) in &config.headers {
+            let header_name = reqwest::header::HeaderName::from_bytes(name.trim().as_bytes())
+                .map_err(|_| HttpClientError::Header(name.clone()))?;
+            let mut header_value = reqwest::header::HeaderValue::from_str(value.trim())
+                .map_err(|_| HttpClientError::Header(name.clone()))?;
+            header_value.set_sensitive(is_sensitive_header(name));
+            headers.insert(header_name, header_value);
+        }
+    }
+
+    Ok(builder.default_headers(headers).build()?)
 }
 
 #[derive(Debug, Clone)]
 pub struct DownloadRequest {
     pub id: u64,
+    /// Mod and path the file belongs to, echoed in [`DownloadEvent::Started`] for progress.
+    pub mod_name: String,
+    pub rel_path: String,
     pub url: String,
     pub target_path: Utf8PathBuf,
     pub expected_size: u64,
     pub expected_checksum: Option<String>,
+    /// Manifest parts covering the file; a segmented download fetches runs of them as
+    /// Range requests and checks each part's checksum. Empty downloads in one stream.
+    pub parts: Vec<fleet_core::FilePart>,
+}
+
+/// Smallest file split into segments unless configured otherwise.
+pub const DEFAULT_SEGMENT_MIN_SIZE: u64 = 256 * 1024 * 1024;
+
+/// Fetch large files as several parallel HTTP Range requests instead of one stream.
+/// Finished segments are reused within one download only; an interrupted file starts over
+/// on the next run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SegmentedDownloads {
+    /// Files smaller than this are downloaded in one stream.
+    pub min_size: u64,
+    /// Ranges requested at once for one file.
+    pub segments: usize,
+}
+
+/// Why a single download ultimately failed (after retries).
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum DownloadError {
+    #[error("HTTP status {0}")]
+    Status(u16),
+    #[error("network error: {0}")]
+    Network(String),
+    #[error("checksum mismatch (expected {expected}, got {actual})")]
+    ChecksumMismatch { expected: String, actual: String },
+    #[error("size mismatch (expected {expected} bytes, got {actual})")]
+    SizeMismatch { expected: u64, actual: u64 },
+    #[error("stalled: no data received for {0:?}")]
+    Stalled(Duration),
+    #[error("IO error: {0}")]
+    Io(String),
+    /// Not attempted: the host had already failed too often (see [`CircuitBreaker`]).
+    #[error("skipped: {0} is unavailable")]
+    HostUnavailable(String),
+}
+
+impl DownloadError {
+    /// The host could not be reached or could not serve the request at all.
+    pub fn is_host_failure(&self) -> bool {
+        match self {
+            DownloadError::Network(_) | DownloadError::Stalled(_) => true,
+            DownloadError::Status(code) => (500..600).contains(code),
+            _ => false,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -60,19 +565,48 @@ pub struct DownloadResult {
     pub id: u64,
     pub success: bool,
     pub bytes_downloaded: u64,
+    pub error: Option<DownloadError>,
 }
 
 #[derive(Debug)]
 pub enum DownloadEvent {
-    Started { id: u64, total_bytes: u64 },
-    Progress { id: u64, bytes_delta: u64 },
-    Completed { id: u64, success: bool },
+    Started {
+        id: u64,
+        mod_name: String,
+        rel_path: String,
+        total_bytes: u64,
+        /// Copied from disk (a `file://` URL) rather than transferred over the network.
+        local: bool,
+    },
+    Progress {
+        id: u64,
+        bytes_delta: u64,
+    },
+    /// Every byte is on disk and the checksum is being computed; `Completed` follows.
+    Verifying {
+        id: u64,
+    },
+    /// Bytes already reported through `Progress` that were thrown away, e.g. by a failed
+    /// attempt; they are downloaded again.
+    Discarded {
+        id: u64,
+        bytes: u64,
+    },
+    Completed {
+        id: u64,
+        success: bool,
+    },
 }
 
 pub struct Downloader {
     client: Client,
     concurrency: usize,
-    rate_limit_bytes: Option<u64>,
+    rate_limit: RateLimit,
+    temp_dir: Option<Utf8PathBuf>,
+    stall_timeout: Option<Duration>,
+    host_limits: Option<Arc<HostLimits>>,
+    segmented: Option<SegmentedDownloads>,
+    breaker: Option<Arc<CircuitBreaker>>,
 }
 
 impl Downloader {
@@ -80,179 +614,238 @@ impl Downloader {
         Self {
             client,
             concurrency,
-            rate_limit_bytes,
+            rate_limit: RateLimit::new(rate_limit_bytes),
+            temp_dir: None,
+            stall_timeout: None,
+            host_limits: None,
+            segmented: None,
+            breaker: None,
         }
     }
 
+    /// Stage `.part` files in `dir` and move them into place once verified, instead of
+    /// writing them next to their targets. `None` keeps the in-place default.
+    pub fn with_temp_dir(mut self, dir: Option<Utf8PathBuf>) -> Self {
+        self.temp_dir = dir;
+        self
+    }
+
+    /// Share `limit` instead of the fixed limit given to [`Self::new`], so the caller can
+    /// change it while the batch runs.
+    pub fn with_rate_limit(mut self, limit: RateLimit) -> Self {
+        self.rate_limit = limit;
+        self
+    }
+
+    /// Abandon an attempt (and retry) once a download receives nothing for `timeout`, even
+    /// though the connection is still open. `None` waits as long as the client's read timeout.
+    pub fn with_stall_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.stall_timeout = timeout;
+        self
+    }
+
+    /// Keep at most `limit` transfers open to any single host, for hosts that refuse
+    /// connections beyond a per-IP cap. `None` only applies the overall concurrency.
+    pub fn with_per_host_limit(mut self, limit: Option<usize>) -> Self {
+        self.host_limits = limit.map(|n| Arc::new(HostLimits::new(n)));
+        self
+    }
+
+    /// Split files of at least `config.min_size` with known parts into parallel Range
+    /// requests when the server accepts them. `None` downloads every file in one stream.
+    pub fn with_segmented_downloads(mut self, config: Option<SegmentedDownloads>) -> Self {
+        self.segmented = config.filter(|c| c.segments > 1);
+        self
+    }
+
+    /// Skip downloads (and their retries) from hosts `breaker` has given up on, reporting
+    /// them as [`DownloadError::HostUnavailable`]. `None` always tries every file.
+    pub fn with_circuit_breaker(mut self, breaker: Option<Arc<CircuitBreaker>>) -> Self {
+        self.breaker = breaker;
+        self
+    }
+
     /// Generic batch download. Does NOT handle deletes, renames, or domain logic.
     pub async fn download_batch(
         &self,
         items: Vec<DownloadRequest>,
         progress_tx: Option<Sender<DownloadEvent>>,
     ) -> Vec<DownloadResult> {
-        let limiter = self.rate_limit_bytes.and_then(|bps| {
-            NonZeroU32::new(bps as u32)
-                .map(|nz| Arc::new(RateLimiter::direct(Quota::per_second(nz))))
-        });
+        self.download_stream(items, progress_tx).collect().await
+    }
+
+    /// Like [`Self::download_batch`], yielding each result as its download finishes.
+    /// Dropping the stream abandons in-flight downloads and removes their `.part` files.
+    pub fn download_stream(
+        &self,
+        items: Vec<DownloadRequest>,
+        progress_tx: Option<Sender<DownloadEvent>>,
+    ) -> impl Stream<Item = DownloadResult> + '_ {
+        let limiter = self.rate_limit.clone();
         // FIX: Use buffer_unordered to drive concurrency without deadlock
         stream::iter(items)
-            .map(|item| {
+            .map(move |item| {
                 let client = self.client.clone();
                 let tx = progress_tx.clone();
                 let lim = limiter.clone();
+                let temp_dir = self.temp_dir.clone();
+                let stall = self.stall_timeout;
+                let hosts = self.host_limits.clone();
+                let segmented = self.segmented;
+                let breaker = self.breaker.clone();
 
-                async move { Self::download_single(client, item, tx, lim).await }
+                async move {
+                    Self::download_single(
+                        client, item, temp_dir, stall, hosts, segmented, breaker, tx, lim,
+                    )
+                    .await
+                }
             })
             .buffer_unordered(self.concurrency)
-            .collect()
-            .await
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn download_single(
         client: Client,
         req: DownloadRequest,
+        temp_dir: Option<Utf8PathBuf>,
+        stall: Option<Duration>,
+        hosts: Option<Arc<HostLimits>>,
+        segmented: Option<SegmentedDownloads>,
+        breaker: Option<Arc<CircuitBreaker>>,
         tx: Option<Sender<DownloadEvent>>,
-        lim: Option<Arc<RateLimiter<NotKeyed, InMemoryState, DefaultClock, NoOpMiddleware>>>,
+        lim: RateLimit,
     ) -> DownloadResult {
         if let Some(ref t) = tx {
             let _ = t
                 .send(DownloadEvent::Started {
                     id: req.id,
+                    mod_name: req.mod_name.clone(),
+                    rel_path: req.rel_path.clone(),
                     total_bytes: req.expected_size,
+                    local: local_source(&req.url).is_some(),
                 })
                 .await;
         }
 
-        let tmp_path = req.target_path.with_extension("part");
+        let tmp_path = match &temp_dir {
+            Some(dir) => {
+                let _ = tokio::fs::create_dir_all(dir.as_std_path()).await;
+                dir.join(format!(
+                    "fleet-{}-{}-{}.part",
+                    std::process::id(),
+                    req.id,
+                    req.target_path.file_name().unwrap_or("download")
+                ))
+            }
+            None => req.target_path.with_extension("part"),
+        };
         let mut tmp_cleanup = PartFileCleanup::new(tmp_path.clone());
 
         if let Some(parent) = req.target_path.parent() {
             let _ = tokio::fs::create_dir_all(parent.as_std_path()).await;
         }
 
-        let mut success = false;
         let mut total_written = 0;
+        let mut error = None;
+        let mut wait = None;
 
-        for _attempt in 0..3 {
-            if let Ok(resp) = client.get(&req.url).send().await {
-                if resp.status().is_success() {
-                    if let Ok(mut file) = File::create(tmp_path.as_std_path()).await {
-                        let mut stream = resp.bytes_stream();
-                        let mut write_err = false;
-
-                        let mut accumulated = 0u64;
-                        let mut last_emit = Instant::now();
-
-                        while let Some(chunk_res) = stream.next().await {
-                            match chunk_res {
-                                Ok(chunk) => {
-                                    if let Some(l) = &lim {
-                                        if let Some(nz) = NonZeroU32::new(chunk.len() as u32) {
-                                            l.until_n_ready(nz).await.ok();
-                                        }
-                                    }
-                                    if file.write_all(&chunk).await.is_ok() {
-                                        let len = chunk.len() as u64;
-                                        total_written += len;
-                                        accumulated += len;
-
-                                        if accumulated > 1_000_000
-                                            || last_emit.elapsed().as_millis() > 100
-                                        {
-                                            if let Some(ref t) = tx {
-                                                let _ = t
-                                                    .send(DownloadEvent::Progress {
-                                                        id: req.id,
-                                                        bytes_delta: accumulated,
-                                                    })
-                                                    .await;
-                                            }
-                                            accumulated = 0;
-                                            last_emit = Instant::now();
-                                        }
-                                    } else {
-                                        write_err = true;
-                                        break;
-                                    }
-                                }
-                                Err(_) => {
-                                    write_err = true;
-                                    break;
-                                }
-                            }
-                        }
-
-                        if accumulated > 0 {
-                            if let Some(ref t) = tx {
-                                let _ = t
-                                    .send(DownloadEvent::Progress {
-                                        id: req.id,
-                                        bytes_delta: accumulated,
-                                    })
-                                    .await;
-                            }
-                        }
+        let tripped = || breaker.as_ref().is_some_and(|b| b.is_open(&req.url));
+        let segments = match segmented {
+            Some(config)
+                if req.expected_size >= config.min_size
+                    && local_source(&req.url).is_none()
+                    && !tripped() =>
+            {
+                plan_segments(&req.parts, req.expected_size, config.segments)
+            }
+            _ => None,
+        };
+        let segmented_result = match segments {
+            Some(segments) => {
+                Self::download_segmented(
+                    &client,
+                    &req,
+                    &tmp_path,
+                    &segments,
+                    stall,
+                    hosts.as_deref(),
+                    &tx,
+                    &lim,
+                    &mut total_written,
+                )
+                .await
+            }
+            None => None,
+        };
 
-                        if !write_err {
-                            let _ = file.flush().await;
-
-                            // Verification: if an expected checksum is provided, compute it
-                            // using `fleet-hashing` before committing the file to the final path.
-                            let mut verified = true;
-                            if let Some(expected) = &req.expected_checksum {
-                                let tmp_path_clone = tmp_path.clone();
-                                let target_filename = req
-                                    .target_path
-                                    .file_name()
-                                    .map(|s| s.to_string())
-                                    .unwrap_or_default();
-
-                                let check_res = tokio::task::spawn_blocking(move || {
-                                    let logical = camino::Utf8Path::new(&target_filename);
-                                    crate::hashing::compute_file_checksum(&tmp_path_clone, logical)
-                                        .ok()
-                                })
-                                .await;
-
-                                match check_res {
-                                    Ok(Some(actual)) => {
-                                        if !actual.eq_ignore_ascii_case(expected) {
-                                            warn!(
-                                                "Checksum mismatch for {}: expected {}, got {}",
-                                                req.url, expected, actual
-                                            );
-                                            verified = false;
-                                        }
-                                    }
-                                    _ => {
-                                        warn!("Failed to compute checksum for {}", req.url);
-                                        verified = false;
-                                    }
-                                }
-                            }
+        // Single stream, also when the server turned out not to support ranges or the
+        // segments failed; their bytes are downloaded again.
+        let mut attempts = TRANSFER_ATTEMPTS;
+        match segmented_result {
+            Some(Ok(())) => {
+                tmp_cleanup.disarm();
+                attempts = 0;
+            }
+            Some(Err(e)) => {
+                warn!(
+                    "Segmented download of {} failed, retrying as one stream: {e}",
+                    req.url
+                );
+                discard_progress(&tx, req.id, std::mem::take(&mut total_written)).await;
+                error = Some(e);
+            }
+            None => {}
+        }
 
-                            if verified
-                                && tokio::fs::rename(
-                                    tmp_path.as_std_path(),
-                                    req.target_path.as_std_path(),
-                                )
-                                .await
-                                .is_ok()
-                            {
-                                success = true;
-                                tmp_cleanup.disarm();
-                                break;
-                            }
-                        }
+        for attempt in 0..attempts {
+            if tripped() {
+                // A file whose segments already failed keeps that error.
+                error.get_or_insert_with(|| {
+                    DownloadError::HostUnavailable(host_key(&req.url).unwrap_or(req.url.clone()))
+                });
+                break;
+            }
+            if attempt > 0 {
+                tokio::time::sleep(wait.take().unwrap_or(RETRY_DELAY)).await;
+            }
+            // Held for this attempt only, so a host asking us to back off frees its slot.
+            let _permit = match &hosts {
+                Some(hosts) => hosts.acquire(&req.url).await,
+                None => None,
+            };
+            let outcome = Self::attempt_download(
+                &client,
+                &req,
+                &tmp_path,
+                stall,
+                &tx,
+                &lim,
+                &mut total_written,
+                &mut wait,
+            )
+            .await;
+            match outcome {
+                Ok(()) => {
+                    error = None;
+                    tmp_cleanup.disarm();
+                    break;
+                }
+                Err(e) => {
+                    if let DownloadError::Stalled(_) = e {
+                        warn!("Download stalled for {}, retrying", req.url);
                     }
+                    discard_progress(&tx, req.id, std::mem::take(&mut total_written)).await;
+                    error = Some(e);
                 }
             }
-            tokio::time::sleep(std::time::Duration::from_millis(500)).await;
         }
 
-        if !success {
-            let _ = tokio::fs::remove_file(&tmp_path).await;
+        if let Some(breaker) = &breaker {
+            breaker.record(&req.url, error.as_ref());
         }
+        let success = error.is_none();
+        drop(tmp_cleanup);
 
         if let Some(ref t) = tx {
             let _ = t
@@ -267,6 +860,1169 @@ impl Downloader {
             id: req.id,
             success,
             bytes_downloaded: total_written,
+            error,
+        }
+    }
+
+    /// Single GET into the `.part` file, verified and renamed onto the target on success.
+    /// `file://` URLs are copied from the local mirror through the same path. A 429/503
+    /// with `Retry-After` leaves the requested wait in `retry_after`.
+    #[allow(clippy::too_many_arguments)]
+    async fn attempt_download(
+        client: &Client,
+        req: &DownloadRequest,
+        tmp_path: &Utf8PathBuf,
+        stall: Option<Duration>,
+        tx: &Option<Sender<DownloadEvent>>,
+        lim: &RateLimit,
+        total_written: &mut u64,
+        retry_after: &mut Option<Duration>,
+    ) -> Result<(), DownloadError> {
+        let mut stream = match local_source(&req.url) {
+            Some(path) => {
+                let src = File::open(&path).await.map_err(|e| match e.kind() {
+                    std::io::ErrorKind::NotFound => DownloadError::Status(404),
+                    _ => DownloadError::Io(e.to_string()),
+                })?;
+                local_chunks(src).boxed().left_stream()
+            }
+            None => {
+                let resp = within(stall, client.get(&req.url).send())
+                    .await?
+                    .map_err(|e| DownloadError::Network(e.to_string()))?;
+                if !resp.status().is_success() {
+                    let status = resp.status();
+                    if status == reqwest::StatusCode::TOO_MANY_REQUESTS
+                        || status == reqwest::StatusCode::SERVICE_UNAVAILABLE
+                    {
+                        *retry_after = self::retry_after(&resp);
+                    }
+                    return Err(DownloadError::Status(status.as_u16()));
+                }
+                // A declared length that disagrees with the manifest can't produce the file.
+                if let Some(declared) = resp.content_length() {
+                    if declared != req.expected_size {
+                        return Err(DownloadError::SizeMismatch {
+                            expected: req.expected_size,
+                            actual: declared,
+                        });
+                    }
+                }
+                resp.bytes_stream()
+                    .map(|res| {
+                        res.map_err(|e| {
+                            if e.is_timeout() {
+                                warn!("Download stalled for {}, retrying", req.url);
+                            }
+                            DownloadError::Network(e.to_string())
+                        })
+                    })
+                    .right_stream()
+            }
+        };
+
+        let mut file = File::create(tmp_path.as_std_path())
+            .await
+            .map_err(|e| DownloadError::Io(e.to_string()))?;
+
+        let mut accumulated = 0u64;
+        let mut last_emit = Instant::now();
+        let mut stream_err = None;
+        let written_before = *total_written;
+
+        loop {
+            let chunk_res = match within(stall, stream.next()).await {
+                Ok(Some(chunk_res)) => chunk_res,
+                Ok(None) => break,
+                Err(e) => Err(e),
+            };
+            let chunk = match chunk_res {
+                Ok(chunk) => chunk,
+                Err(e) => {
+                    stream_err = Some(e);
+                    break;
+                }
+            };
+            lim.acquire(chunk.len()).await;
+            if let Err(e) = file.write_all(&chunk).await {
+                stream_err = Some(DownloadError::Io(e.to_string()));
+                break;
+            }
+            let len = chunk.len() as u64;
+            *total_written += len;
+            accumulated += len;
+
+            if accumulated > 1_000_000 || last_emit.elapsed().as_millis() > 100 {
+                if let Some(t) = tx {
+                    let _ = t
+                        .send(DownloadEvent::Progress {
+                            id: req.id,
+                            bytes_delta: accumulated,
+                        })
+                        .await;
+                }
+                accumulated = 0;
+                last_emit = Instant::now();
+            }
+        }
+
+        if accumulated > 0 {
+            if let Some(t) = tx {
+                let _ = t
+                    .send(DownloadEvent::Progress {
+                        id: req.id,
+                        bytes_delta: accumulated,
+                    })
+                    .await;
+            }
+        }
+
+        if let Some(e) = stream_err {
+            return Err(e);
+        }
+        file.flush()
+            .await
+            .map_err(|e| DownloadError::Io(e.to_string()))?;
+        drop(file);
+
+        // Verification: a short or long body is rejected on its size alone, before paying
+        // for the checksum.
+        let written = *total_written - written_before;
+        if written != req.expected_size {
+            return Err(DownloadError::SizeMismatch {
+                expected: req.expected_size,
+                actual: written,
+            });
+        }
+        Self::finish_download(req, tmp_path, tx).await
+    }
+
+    /// If an expected checksum is provided, compute it using `fleet-hashing` before
+    /// committing the complete `.part` file to the final path.
+    async fn finish_download(
+        req: &DownloadRequest,
+        tmp_path: &Utf8PathBuf,
+        tx: &Option<Sender<DownloadEvent>>,
+    ) -> Result<(), DownloadError> {
+        if let Some(expected) = &req.expected_checksum {
+            let tmp_path_clone = tmp_path.clone();
+            let target_filename = req
+                .target_path
+                .file_name()
+                .map(|s| s.to_string())
+                .unwrap_or_default();
+
+            if let Some(t) = tx {
+                let _ = t.send(DownloadEvent::Verifying { id: req.id }).await;
+            }
+            let actual = tokio::task::spawn_blocking(move || {
+                let logical = camino::Utf8Path::new(&target_filename);
+                crate::hashing::compute_file_checksum(&tmp_path_clone, logical)
+            })
+            .await
+            .map_err(|e| DownloadError::Io(e.to_string()))?
+            .map_err(|e| {
+                warn!("Failed to compute checksum for {}", req.url);
+                DownloadError::Io(e.to_string())
+            })?;
+
+            if !actual.eq_ignore_ascii_case(expected) {
+                warn!(
+                    "Checksum mismatch for {}: expected {}, got {}",
+                    req.url, expected, actual
+                );
+                return Err(DownloadError::ChecksumMismatch {
+                    expected: expected.clone(),
+                    actual,
+                });
+            }
+        }
+
+        // A staging directory may be on another filesystem; `robust_rename` copies across.
+        crate::fs::robust_rename(tmp_path.as_std_path(), req.target_path.as_std_path())
+            .await
+            .map_err(|e| DownloadError::Io(e.to_string()))
+    }
+
+    /// Fetches `segments` (runs of `req.parts`) as parallel Range requests into a
+    /// preallocated `.part` file. A failed segment is retried on its own while finished
+    /// ones are kept. `None` when the server does not advertise byte ranges, leaving the
+    /// single-stream download to the caller.
+    #[allow(clippy::too_many_arguments)]
+    async fn download_segmented(
+        client: &Client,
+        req: &DownloadRequest,
+        tmp_path: &Utf8PathBuf,
+        segments: &[std::ops::Range<usize>],
+        stall: Option<Duration>,
+        hosts: Option<&HostLimits>,
+        tx: &Option<Sender<DownloadEvent>>,
+        lim: &RateLimit,
+        total_written: &mut u64,
+    ) -> Option<Result<(), DownloadError>> {
+        let head = {
+            let _permit = match hosts {
+                Some(hosts) => hosts.acquire(&req.url).await,
+                None => None,
+            };
+            within(stall, client.head(&req.url).send())
+                .await
+                .ok()?
+                .ok()?
+        };
+        let ranges = head.status().is_success()
+            && head
+                .headers()
+                .get(reqwest::header::ACCEPT_RANGES)
+                .and_then(|v| v.to_str().ok())
+                .is_some_and(|v| v.trim().eq_ignore_ascii_case("bytes"));
+        if !ranges {
+            return None;
+        }
+
+        let preallocated = async {
+            let file = File::create(tmp_path.as_std_path()).await?;
+            file.set_len(req.expected_size).await
+        };
+        if let Err(e) = preallocated.await {
+            return Some(Err(DownloadError::Io(e.to_string())));
+        }
+
+        let mut pending = segments.to_vec();
+        let mut error = None;
+        for attempt in 0..TRANSFER_ATTEMPTS {
+            if attempt > 0 {
+                tokio::time::sleep(RETRY_DELAY).await;
+            }
+            let results = futures::future::join_all(pending.iter().map(|segment| async {
+                let _permit = match hosts {
+                    Some(hosts) => hosts.acquire(&req.url).await,
+                    None => None,
+                };
+                let parts = &req.parts[segment.clone()];
+                Self::fetch_segment(client, req, tmp_path, parts, stall, tx, lim).await
+            }))
+            .await;
+            let mut failed = Vec::new();
+            for (segment, (written, result)) in pending.into_iter().zip(results) {
+                match result {
+                    Ok(()) => *total_written += written,
+                    Err(e) => {
+                        warn!("Segment of {} failed: {e}", req.url);
+                        discard_progress(tx, req.id, written).await;
+                        error = Some(e);
+                        failed.push(segment);
+                    }
+                }
+            }
+            pending = failed;
+            // A success status other than 206 means the Range header was ignored; retrying
+            // would fetch the whole file again per segment.
+            let ignored_range = matches!(error, Some(DownloadError::Status(status)) if (200..300).contains(&status));
+            if pending.is_empty() || ignored_range {
+                break;
+            }
+        }
+        if !pending.is_empty() {
+            return Some(Err(
+                error.unwrap_or(DownloadError::Io("segment failed".into()))
+            ));
         }
+        Some(Self::finish_download(req, tmp_path, tx).await)
+    }
+
+    /// One Range request covering `parts`, written at their offset and checked part by part
+    /// as the bytes arrive. Returns the bytes written alongside the outcome.
+    async fn fetch_segment(
+        client: &Client,
+        req: &DownloadRequest,
+        tmp_path: &Utf8PathBuf,
+        parts: &[fleet_core::FilePart],
+        stall: Option<Duration>,
+        tx: &Option<Sender<DownloadEvent>>,
+        lim: &RateLimit,
+    ) -> (u64, Result<(), DownloadError>) {
+        let mut written = 0u64;
+        let result = async {
+            let (Some(first), Some(last)) = (parts.first(), parts.last()) else {
+                return Ok(());
+            };
+            let (start, end) = (first.start, last.start + last.length);
+            let resp = within(
+                stall,
+                client
+                    .get(&req.url)
+                    .header(reqwest::header::RANGE, format!("bytes={start}-{}", end - 1))
+                    .send(),
+            )
+            .await?
+            .map_err(|e| DownloadError::Network(e.to_string()))?;
+            if resp.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+                return Err(DownloadError::Status(resp.status().as_u16()));
+            }
+
+            let mut file = tokio::fs::OpenOptions::new()
+                .write(true)
+                .open(tmp_path.as_std_path())
+                .await
+                .map_err(|e| DownloadError::Io(e.to_string()))?;
+            file.seek(std::io::SeekFrom::Start(start))
+                .await
+                .map_err(|e| DownloadError::Io(e.to_string()))?;
+
+            let mut verifier = PartVerifier::new(parts);
+            let mut stream = resp.bytes_stream();
+            while let Some(chunk) = within(stall, stream.next()).await? {
+                let chunk = chunk.map_err(|e| DownloadError::Network(e.to_string()))?;
+                lim.acquire(chunk.len()).await;
+                verifier.feed(&chunk)?;
+                file.write_all(&chunk)
+                    .await
+                    .map_err(|e| DownloadError::Io(e.to_string()))?;
+                written += chunk.len() as u64;
+                if let Some(t) = tx {
+                    let _ = t
+                        .send(DownloadEvent::Progress {
+                            id: req.id,
+                            bytes_delta: chunk.len() as u64,
+                        })
+                        .await;
+                }
+            }
+            file.flush()
+                .await
+                .map_err(|e| DownloadError::Io(e.to_string()))?;
+            verifier.finish()
+        }
+        .await;
+        (written, result)
+    }
+}
+
+/// Takes back `bytes` reported as progress for download `id` whose data was thrown away.
+async fn discard_progress(tx: &Option<Sender<DownloadEvent>>, id: u64, bytes: u64) {
+    if bytes == 0 {
+        return;
+    }
+    if let Some(t) = tx {
+        let _ = t.send(DownloadEvent::Discarded { id, bytes }).await;
+    }
+}
+
+/// Groups `parts` into at most `segments` contiguous runs of similar size. `None` when the
+/// parts don't cover the file exactly from start to end or there is only one run.
+fn plan_segments(
+    parts: &[fleet_core::FilePart],
+    size: u64,
+    segments: usize,
+) -> Option<Vec<std::ops::Range<usize>>> {
+    let mut pos = 0;
+    for part in parts {
+        if part.start != pos {
+            return None;
+        }
+        pos += part.length;
+    }
+    if pos != size {
+        return None;
+    }
+
+    let target = size.div_ceil(segments.max(1) as u64).max(1);
+    let mut runs = Vec::new();
+    let (mut first, mut bytes) = (0, 0);
+    for (i, part) in parts.iter().enumerate() {
+        bytes += part.length;
+        if bytes >= target {
+            runs.push(first..i + 1);
+            (first, bytes) = (i + 1, 0);
+        }
+    }
+    if first < parts.len() {
+        runs.push(first..parts.len());
+    }
+    (runs.len() > 1).then_some(runs)
+}
+
+/// Hashes a segment's bytes as they stream in and checks each part's MD5 once it is complete.
+struct PartVerifier<'a> {
+    parts: &'a [fleet_core::FilePart],
+    current: usize,
+    filled: u64,
+    received: u64,
+    hasher: md5::Context,
+}
+
+impl<'a> PartVerifier<'a> {
+    fn new(parts: &'a [fleet_core::FilePart]) -> Self {
+        Self {
+            parts,
+            current: 0,
+            filled: 0,
+            received: 0,
+            hasher: md5::Context::new(),
+        }
+    }
+
+    fn expected(&self) -> u64 {
+        self.parts.iter().map(|p| p.length).sum()
+    }
+
+    fn feed(&mut self, mut data: &[u8]) -> Result<(), DownloadError> {
+        self.received += data.len() as u64;
+        loop {
+            self.close_complete()?;
+            if data.is_empty() {
+                return Ok(());
+            }
+            let Some(part) = self.parts.get(self.current) else {
+                return Err(DownloadError::SizeMismatch {
+                    expected: self.expected(),
+                    actual: self.received,
+                });
+            };
+            let take = ((part.length - self.filled) as usize).min(data.len());
+            self.hasher.consume(&data[..take]);
+            self.filled += take as u64;
+            data = &data[take..];
+        }
+    }
+
+    fn close_complete(&mut self) -> Result<(), DownloadError> {
+        while let Some(part) = self.parts.get(self.current) {
+            if self.filled < part.length {
+                break;
+            }
+            let hasher = std::mem::replace(&mut self.hasher, md5::Context::new());
+            let actual = format!("{:X}", hasher.finalize());
+            if !part.checksum.is_empty() && !actual.eq_ignore_ascii_case(&part.checksum) {
+                return Err(DownloadError::ChecksumMismatch {
+                    expected: part.checksum.clone(),
+                    actual,
+                });
+            }
+            self.current += 1;
+            self.filled = 0;
+        }
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<(), DownloadError> {
+        self.close_complete()?;
+        if self.current < self.parts.len() {
+            return Err(DownloadError::SizeMismatch {
+                expected: self.expected(),
+                actual: self.received,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Awaits `fut`, failing with [`DownloadError::Stalled`] if it takes longer than `stall`.
+async fn within<T>(
+    stall: Option<Duration>,
+    fut: impl std::future::Future<Output = T>,
+) -> Result<T, DownloadError> {
+    match stall {
+        Some(limit) => tokio::time::timeout(limit, fut)
+            .await
+            .map_err(|_| DownloadError::Stalled(limit)),
+        None => Ok(fut.await),
+    }
+}
+
+/// A local file to send with `PUT` (WebDAV, S3 presigned/compatible endpoints, ...).
+#[derive(Debug, Clone)]
+pub struct UploadRequest {
+    pub url: String,
+    pub source_path: Utf8PathBuf,
+}
+
+/// Uploads files with the same rate limiting and retries as [`Downloader`]; failures use
+/// [`DownloadError`] as well, since they are the same transfer problems.
+pub struct Uploader {
+    client: Client,
+    limiter: RateLimit,
+}
+
+impl Uploader {
+    pub fn new(client: Client, rate_limit_bytes: Option<u64>) -> Self {
+        Self {
+            client,
+            limiter: RateLimit::new(rate_limit_bytes),
+        }
+    }
+
+    pub async fn upload(&self, req: &UploadRequest) -> Result<(), DownloadError> {
+        let mut error = None;
+        for attempt in 0..TRANSFER_ATTEMPTS {
+            if attempt > 0 {
+                tokio::time::sleep(RETRY_DELAY).await;
+            }
+            match self.attempt_upload(req).await {
+                Ok(()) => return Ok(()),
+                Err(e) => {
+                    warn!("Upload of {} failed: {e}", req.url);
+                    error = Some(e);
+                }
+            }
+        }
+        Err(error.unwrap_or(DownloadError::Network("upload not attempted".into())))
+    }
+
+    /// `DELETE`s `url` with the same retries as uploads; a `404` counts as already deleted.
+    pub async fn delete(&self, url: &str) -> Result<(), DownloadError> {
+        let mut error = None;
+        for attempt in 0..TRANSFER_ATTEMPTS {
+            if attempt > 0 {
+                tokio::time::sleep(RETRY_DELAY).await;
+            }
+            let result = match self.client.delete(url).send().await {
+                Ok(resp) if resp.status().is_success() => return Ok(()),
+                Ok(resp) if resp.status() == reqwest::StatusCode::NOT_FOUND => return Ok(()),
+                Ok(resp) => DownloadError::Status(resp.status().as_u16()),
+                Err(e) => DownloadError::Network(e.to_string()),
+            };
+            warn!("Delete of {url} failed: {result}");
+            error = Some(result);
+        }
+        Err(error.unwrap_or(DownloadError::Network("delete not attempted".into())))
+    }
+
+    async fn attempt_upload(&self, req: &UploadRequest) -> Result<(), DownloadError> {
+        let src = File::open(req.source_path.as_std_path())
+            .await
+            .map_err(|e| DownloadError::Io(e.to_string()))?;
+        let len = src
+            .metadata()
+            .await
+            .map_err(|e| DownloadError::Io(e.to_string()))?
+            .len();
+        let limiter = self.limiter.clone();
+        let body = local_chunks(src).then(move |chunk| {
+            let limiter = limiter.clone();
+            async move {
+                if let Ok(chunk) = &chunk {
+                    limiter.acquire(chunk.len()).await;
+                }
+                chunk
+            }
+        });
+
+        let resp = self
+            .client
+            .put(&req.url)
+            .header(reqwest::header::CONTENT_LENGTH, len)
+            .body(reqwest::Body::wrap_stream(body))
+            .send()
+            .await
+            .map_err(|e| DownloadError::Network(e.to_string()))?;
+        if !resp.status().is_success() {
+            return Err(DownloadError::Status(resp.status().as_u16()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn circuit_breaker_counts_consecutive_failures_per_host() {
+        let breaker = CircuitBreaker::new(2);
+        let (a, b) = ("http://a.example/x", "http://b.example:8080/y");
+
+        breaker.record_failure(a);
+        breaker.record_success(a);
+        breaker.record_failure(a);
+        breaker.record_failure(b);
+        assert!(!breaker.is_open(a));
+
+        breaker.record_failure("http://a.example/other");
+        assert!(breaker.is_open(a));
+        assert!(!breaker.is_open(b));
+        // An open host stays open.
+        breaker.record_success(a);
+        assert_eq!(breaker.open_host(), Some(("a.example:80".to_string(), 2)));
+
+        breaker.record_failure("file:///mirror/x");
+        breaker.record_failure("file:///mirror/x");
+        assert!(!breaker.is_open("file:///mirror/x"));
+    }
+
+    #[test]
+    fn client_builds_with_proxy_and_headers() {
+        let config = NetConfig {
+            proxy: Some("http://127.0.0.1:3128".into()),
+            headers: vec![("X-Repo-Key".into(), "abc".into())],
+            user_agent: Some("fleet-test".into()),
+            ..Default::default()
+        }
+        .with_bearer_token("secret");
+
+        assert!(config
+            .headers
+            .iter()
+            .any(|(k, v)| k == "Authorization" && v == "Bearer secret"));
+        assert!(default_http_client(Some(&config)).is_ok());
+    }
+
+    #[test]
+    fn repo_auth_replaces_token_and_is_redacted_in_debug() {
+        let config = NetConfig::default()
+            .with_bearer_token("global")
+            .with_repo_auth(&RepoAuth::Basic {
+                username: "user".into(),
+                password: "pass".into(),
+            });
+
+        let auth: Vec<_> = config
+            .headers
+            .iter()
+            .filter(|(k, _)| k == "Authorization")
+            .collect();
+        assert_eq!(auth.len(), 1);
+        assert_eq!(auth[0].1, "Basic dXNlcjpwYXNz");
+        assert!(default_http_client(Some(&config)).is_ok());
+
+        let debug = format!("{config:?}");
+        assert!(!debug.contains("dXNlcjpwYXNz"), "got: {debug}");
+        let auth_debug = format!("{:?}", RepoAuth::Bearer("secret".into()));
+        assert!(!auth_debug.contains("secret"), "got: {auth_debug}");
+    }
+
+    #[test]
+    fn invalid_header_is_rejected() {
+        let config = NetConfig {
+            headers: vec![("Bad Header".into(), "x".into())],
+            ..Default::default()
+        };
+        assert!(matches!(
+            default_http_client(Some(&config)),
+            Err(HttpClientError::Header(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn stalled_download_fails_instead_of_hanging() {
+        use tokio::io::AsyncReadExt;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((mut sock, _)) = listener.accept().await {
+                let mut buf = [0u8; 1024];
+                let _ = sock.read(&mut buf).await;
+                let _ = sock
+                    .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 100\r\n\r\npartial")
+                    .await;
+                // Keep the connection open without sending the rest of the body.
+                held.push(sock);
+            }
+        });
+
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let target = Utf8PathBuf::from_path_buf(dir.join("file.bin")).unwrap();
+        let config = NetConfig {
+            read_timeout: Some(Duration::from_millis(200)),
+            ..Default::default()
+        };
+        let client = default_http_client(Some(&config)).unwrap();
+        let downloader = Downloader::new(client, 1, None);
+
+        let results = tokio::time::timeout(
+            Duration::from_secs(20),
+            downloader.download_batch(
+                vec![DownloadRequest {
+                    id: 1,
+                    mod_name: "@stall".into(),
+                    rel_path: "file.bin".into(),
+                    url: format!("http://{addr}/file.bin"),
+                    target_path: target.clone(),
+                    expected_size: 100,
+                    expected_checksum: None,
+                    parts: Vec::new(),
+                }],
+                None,
+            ),
+        )
+        .await
+        .expect("download should not hang on a stalled connection");
+
+        assert!(!results[0].success);
+        assert!(!target.exists());
+    }
+
+    #[tokio::test]
+    async fn stall_timeout_abandons_a_silent_download_before_the_read_timeout() {
+        use tokio::io::AsyncReadExt;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((mut sock, _)) = listener.accept().await {
+                let mut buf = [0u8; 1024];
+                let _ = sock.read(&mut buf).await;
+                let _ = sock
+                    .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 100\r\n\r\npartial")
+                    .await;
+                held.push(sock);
+            }
+        });
+
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let target = Utf8PathBuf::from_path_buf(dir.join("file.bin")).unwrap();
+        // The read timeout alone would keep this attempt open for a minute.
+        let client = default_http_client(None).unwrap();
+        let downloader =
+            Downloader::new(client, 1, None).with_stall_timeout(Some(Duration::from_millis(200)));
+
+        let results = tokio::time::timeout(
+            Duration::from_secs(20),
+            downloader.download_batch(
+                vec![DownloadRequest {
+                    id: 1,
+                    mod_name: "@stall".into(),
+                    rel_path: "file.bin".into(),
+                    url: format!("http://{addr}/file.bin"),
+                    target_path: target.clone(),
+                    expected_size: 100,
+                    expected_checksum: None,
+                    parts: Vec::new(),
+                }],
+                None,
+            ),
+        )
+        .await
+        .expect("stall timeout should end the download");
+
+        assert_eq!(
+            results[0].error,
+            Some(DownloadError::Stalled(Duration::from_millis(200)))
+        );
+        assert!(!target.exists());
+        assert!(!target.with_extension("part").exists());
+    }
+
+    #[tokio::test]
+    async fn short_bodies_fail_on_size_before_the_checksum() {
+        use tokio::io::AsyncReadExt;
+
+        async fn download(response: &'static [u8], name: &str) -> DownloadResult {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            tokio::spawn(async move {
+                while let Ok((mut sock, _)) = listener.accept().await {
+                    let mut buf = [0u8; 1024];
+                    let _ = sock.read(&mut buf).await;
+                    let _ = sock.write_all(response).await;
+                }
+            });
+            let temp = tempfile::tempdir().unwrap();
+            let dir = temp.path();
+            let target = Utf8PathBuf::from_path_buf(dir.join("file.bin")).unwrap();
+            let downloader = Downloader::new(default_http_client(None).unwrap(), 1, None);
+            let mut results = downloader
+                .download_batch(
+                    vec![DownloadRequest {
+                        id: 1,
+                        mod_name: "@short".into(),
+                        rel_path: "file.bin".into(),
+                        url: format!("http://{addr}/file.bin"),
+                        target_path: target.clone(),
+                        expected_size: 5,
+                        expected_checksum: Some("F872A18EB88181EB00816510E762FEE6".into()),
+                        parts: Vec::new(),
+                    }],
+                    None,
+                )
+                .await;
+            assert!(!target.exists());
+            results.remove(0)
+        }
+
+        let declared = download(
+            b"HTTP/1.1 200 OK\r\nContent-Length: 3\r\n\r\nhel",
+            "short-declared",
+        )
+        .await;
+        assert_eq!(
+            declared.error,
+            Some(DownloadError::SizeMismatch {
+                expected: 5,
+                actual: 3
+            })
+        );
+
+        // No Content-Length: the body simply ends when the server closes the connection.
+        let undeclared = download(
+            b"HTTP/1.1 200 OK\r\nConnection: close\r\n\r\nhel",
+            "short-undeclared",
+        )
+        .await;
+        assert_eq!(
+            undeclared.error,
+            Some(DownloadError::SizeMismatch {
+                expected: 5,
+                actual: 3
+            })
+        );
+    }
+
+    fn hello_request(id: u64, url: String, target_path: Utf8PathBuf) -> DownloadRequest {
+        DownloadRequest {
+            id,
+            mod_name: "@m".into(),
+            rel_path: "file.bin".into(),
+            url,
+            target_path,
+            expected_size: 5,
+            expected_checksum: None,
+            parts: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn retry_after_is_waited_out_before_retrying() {
+        use tokio::io::AsyncReadExt;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut busy = true;
+            while let Ok((mut sock, _)) = listener.accept().await {
+                let mut buf = [0u8; 1024];
+                let _ = sock.read(&mut buf).await;
+                let response: &[u8] = if std::mem::take(&mut busy) {
+                    b"HTTP/1.1 503 Service Unavailable\r\nRetry-After: 2\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                } else {
+                    b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\nConnection: close\r\n\r\nhello"
+                };
+                let _ = sock.write_all(response).await;
+            }
+        });
+
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let target = Utf8PathBuf::from_path_buf(dir.join("file.bin")).unwrap();
+        let downloader = Downloader::new(default_http_client(None).unwrap(), 1, None);
+
+        let started = Instant::now();
+        let results = downloader
+            .download_batch(
+                vec![hello_request(
+                    1,
+                    format!("http://{addr}/file.bin"),
+                    target.clone(),
+                )],
+                None,
+            )
+            .await;
+
+        assert!(results[0].success, "{:?}", results[0].error);
+        assert!(started.elapsed() >= Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn per_host_limit_caps_open_transfers() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use tokio::io::AsyncReadExt;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let active = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        {
+            let (active, peak) = (active.clone(), peak.clone());
+            tokio::spawn(async move {
+                while let Ok((mut sock, _)) = listener.accept().await {
+                    let (active, peak) = (active.clone(), peak.clone());
+                    tokio::spawn(async move {
+                        let mut buf = [0u8; 1024];
+                        let _ = sock.read(&mut buf).await;
+                        let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+                        peak.fetch_max(now, Ordering::SeqCst);
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                        active.fetch_sub(1, Ordering::SeqCst);
+                        let _ = sock
+                            .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\nConnection: close\r\n\r\nhello")
+                            .await;
+                    });
+                }
+            });
+        }
+
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let items = (0..4)
+            .map(|i| {
+                let target = Utf8PathBuf::from_path_buf(dir.join(format!("{i}.bin"))).unwrap();
+                hello_request(i, format!("http://{addr}/{i}.bin"), target)
+            })
+            .collect();
+        let downloader = Downloader::new(default_http_client(None).unwrap(), 4, None)
+            .with_per_host_limit(Some(1));
+
+        let results = downloader.download_batch(items, None).await;
+
+        assert!(results.iter().all(|r| r.success));
+        assert_eq!(peak.load(Ordering::SeqCst), 1);
+    }
+
+    const SEGMENTED_BODY: &[u8] = b"0123456789abcdefghijklmn";
+
+    /// Serves [`SEGMENTED_BODY`], advertising ranges on HEAD when `advertise` is set and
+    /// answering Range requests when `ranges` is. The first request for bytes 12.. gets a
+    /// corrupted body. Returns the requested ranges.
+    async fn range_server(
+        advertise: bool,
+        ranges: bool,
+    ) -> (String, Arc<std::sync::Mutex<Vec<String>>>) {
+        use tokio::io::AsyncReadExt;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let log = seen.clone();
+        tokio::spawn(async move {
+            let mut corrupt = true;
+            while let Ok((mut sock, _)) = listener.accept().await {
+                let mut buf = [0u8; 2048];
+                let n = sock.read(&mut buf).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&buf[..n]).to_ascii_lowercase();
+                let accept = if advertise {
+                    "Accept-Ranges: bytes\r\n"
+                } else {
+                    ""
+                };
+                let range = request
+                    .lines()
+                    .find_map(|l| l.strip_prefix("range: bytes="))
+                    .and_then(|r| r.trim().split_once('-'))
+                    .map(|(a, b)| (a.parse::<usize>().unwrap(), b.parse::<usize>().unwrap()));
+                let response = match (request.starts_with("head"), range) {
+                    (true, _) => format!(
+                        "HTTP/1.1 200 OK\r\n{accept}Content-Length: {}\r\nConnection: close\r\n\r\n",
+                        SEGMENTED_BODY.len()
+                    )
+                    .into_bytes(),
+                    (false, Some((start, end))) if ranges => {
+                        log.lock().unwrap().push(format!("{start}-{end}"));
+                        let mut body = SEGMENTED_BODY[start..=end].to_vec();
+                        if start == 12 && std::mem::take(&mut corrupt) {
+                            body[0] ^= 1;
+                        }
+                        let mut head = format!(
+                            "HTTP/1.1 206 Partial Content\r\nContent-Range: bytes {start}-{end}/{}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                            SEGMENTED_BODY.len(),
+                            body.len()
+                        )
+                        .into_bytes();
+                        head.extend(body);
+                        head
+                    }
+                    _ => {
+                        log.lock().unwrap().push("full".into());
+                        let mut head = format!(
+                            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                            SEGMENTED_BODY.len()
+                        )
+                        .into_bytes();
+                        head.extend_from_slice(SEGMENTED_BODY);
+                        head
+                    }
+                };
+                let _ = sock.write_all(&response).await;
+            }
+        });
+        (format!("http://{addr}/big.bin"), seen)
+    }
+
+    fn segmented_request(url: String, target_path: Utf8PathBuf) -> DownloadRequest {
+        let parts = SEGMENTED_BODY
+            .chunks(6)
+            .enumerate()
+            .map(|(i, chunk)| fleet_core::FilePart {
+                path: format!("big.bin_{i}"),
+                length: chunk.len() as u64,
+                start: i as u64 * 6,
+                checksum: format!("{:X}", md5::compute(chunk)),
+            })
+            .collect();
+        DownloadRequest {
+            id: 1,
+            mod_name: "@m".into(),
+            rel_path: "big.bin".into(),
+            url,
+            target_path,
+            expected_size: SEGMENTED_BODY.len() as u64,
+            expected_checksum: None,
+            parts,
+        }
+    }
+
+    fn segmented_downloader() -> Downloader {
+        Downloader::new(default_http_client(None).unwrap(), 1, None).with_segmented_downloads(Some(
+            SegmentedDownloads {
+                min_size: 1,
+                segments: 2,
+            },
+        ))
+    }
+
+    #[tokio::test]
+    async fn segmented_downloads_retry_only_the_segment_that_failed_its_part_check() {
+        let (url, seen) = range_server(true, true).await;
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let target = Utf8PathBuf::from_path_buf(dir.join("big.bin")).unwrap();
+
+        let results = segmented_downloader()
+            .download_batch(vec![segmented_request(url, target.clone())], None)
+            .await;
+
+        assert!(results[0].success, "{:?}", results[0].error);
+        assert_eq!(std::fs::read(&target).unwrap(), SEGMENTED_BODY);
+        let mut seen = seen.lock().unwrap().clone();
+        seen.sort();
+        assert_eq!(seen, vec!["0-11", "12-23", "12-23"]);
+    }
+
+    #[tokio::test]
+    async fn segmented_downloads_fall_back_to_one_stream_without_range_support() {
+        let (url, seen) = range_server(false, false).await;
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let target = Utf8PathBuf::from_path_buf(dir.join("big.bin")).unwrap();
+
+        let results = segmented_downloader()
+            .download_batch(vec![segmented_request(url, target.clone())], None)
+            .await;
+
+        assert!(results[0].success, "{:?}", results[0].error);
+        assert_eq!(std::fs::read(&target).unwrap(), SEGMENTED_BODY);
+        assert_eq!(*seen.lock().unwrap(), vec!["full".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn segmented_downloads_fall_back_when_ranges_are_advertised_but_ignored() {
+        let (url, seen) = range_server(true, false).await;
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let target = Utf8PathBuf::from_path_buf(dir.join("big.bin")).unwrap();
+        let (tx, mut rx) = tokio::sync::mpsc::channel(64);
+
+        let results = segmented_downloader()
+            .download_batch(vec![segmented_request(url, target.clone())], Some(tx))
+            .await;
+
+        assert!(results[0].success, "{:?}", results[0].error);
+        assert_eq!(std::fs::read(&target).unwrap(), SEGMENTED_BODY);
+        // Both segments get the whole file once, then a single stream fetches it again.
+        assert_eq!(seen.lock().unwrap().len(), 3);
+        let mut reported = 0i64;
+        while let Ok(ev) = rx.try_recv() {
+            match ev {
+                DownloadEvent::Progress { bytes_delta, .. } => reported += bytes_delta as i64,
+                DownloadEvent::Discarded { bytes, .. } => reported -= bytes as i64,
+                _ => {}
+            }
+        }
+        assert_eq!(reported, SEGMENTED_BODY.len() as i64);
+    }
+
+    #[tokio::test]
+    async fn requests_identify_fleet_unless_the_user_agent_is_overridden() {
+        use tokio::io::AsyncReadExt;
+
+        async fn sent_headers(config: NetConfig) -> String {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            let server = tokio::spawn(async move {
+                let (mut sock, _) = listener.accept().await.unwrap();
+                let mut buf = [0u8; 4096];
+                let n = sock.read(&mut buf).await.unwrap();
+                let _ = sock
+                    .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n")
+                    .await;
+                String::from_utf8_lossy(&buf[..n]).to_ascii_lowercase()
+            });
+            let client = default_http_client(Some(&config)).unwrap();
+            client.get(format!("http://{addr}/")).send().await.unwrap();
+            server.await.unwrap()
+        }
+
+        let sent = sent_headers(NetConfig::default()).await;
+        assert!(sent.contains(&format!(
+            "user-agent: fleet/{}\r\n",
+            env!("CARGO_PKG_VERSION")
+        )));
+        assert!(sent.contains(&format!(
+            "x-fleet-version: {}\r\n",
+            env!("CARGO_PKG_VERSION")
+        )));
+
+        let sent = sent_headers(NetConfig {
+            user_agent: Some(" private ".into()),
+            ..Default::default()
+        })
+        .await;
+        assert!(sent.contains("user-agent: private\r\n"), "{sent}");
+        assert!(!sent.contains("x-fleet-version"), "{sent}");
+    }
+
+    #[tokio::test]
+    async fn rate_limit_changes_reach_every_clone() {
+        let limit = RateLimit::new(Some(1000));
+        let shared = limit.clone();
+        assert_eq!(shared.get(), Some(1000));
+
+        // At 1 KB/s a second kilobyte would wait a full second.
+        shared.acquire(1000).await;
+        limit.set(Some(1_000_000));
+        tokio::time::timeout(Duration::from_millis(500), shared.acquire(1000))
+            .await
+            .expect("raising the limit applies to transfers already holding the handle");
+
+        limit.set(Some(0));
+        assert_eq!(shared.get(), None, "0 is unlimited");
+    }
+
+    #[tokio::test]
+    async fn offline_client_makes_no_requests() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let config = NetConfig {
+            proxy: Some(format!("http://{addr}")),
+            offline: true,
+            ..Default::default()
+        };
+        let client = default_http_client(Some(&config)).unwrap();
+
+        let err = client
+            .get(format!("http://{addr}/repo.json"))
+            .send()
+            .await
+            .unwrap_err();
+        assert!(format!("{err:?}").contains("offline mode"), "{err:?}");
+        let accepted = tokio::time::timeout(Duration::from_millis(100), listener.accept()).await;
+        assert!(accepted.is_err(), "nothing may connect");
+    }
+
+    #[test]
+    fn invalid_proxy_is_rejected() {
+        let config = NetConfig {
+            proxy: Some("not a url".into()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            default_http_client(Some(&config)),
+            Err(HttpClientError::Proxy { .. })
+        ));
     }
 }
@@ -0,0 +1,132 @@
+use camino::{Utf8Path, Utf8PathBuf};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+/// Authority names are short; anything longer means the file is not a key or signature.
+const MAX_AUTHORITY_LEN: usize = 1024;
+
+/// A `.bisign` whose authority has no matching `.bikey` in the mod.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownKeySignature {
+    /// Path of the `.bisign`, relative to the mod directory.
+    pub bisign: String,
+    pub authority: String,
+}
+
+/// Result of [`check_mod_signatures`]. Paths are relative to the mod directory and use `/`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SignatureReport {
+    /// PBOs with no `<name>.pbo.<authority>.bisign` next to them.
+    pub unsigned: Vec<String>,
+    /// Signatures made with a key the mod does not ship. Only filled when keys are checked.
+    pub unknown_key: Vec<UnknownKeySignature>,
+}
+
+impl SignatureReport {
+    pub fn is_clean(&self) -> bool {
+        self.unsigned.is_empty() && self.unknown_key.is_empty()
+    }
+}
+
+/// Lists the PBOs in `mod_dir` that servers with signature verification would reject
+/// because they have no `.bisign`.
+///
+/// With `check_keys`, each `.bisign` is also matched by authority name against the
+/// `.bikey` files found anywhere in the mod. This does not verify the RSA signature itself.
+pub fn check_mod_signatures(
+    mod_dir: &Utf8Path,
+    check_keys: bool,
+) -> std::io::Result<SignatureReport> {
+    let mut pbos = Vec::new();
+    let mut bisigns = Vec::new();
+    let mut bikeys = Vec::new();
+    collect_files(mod_dir, &mut pbos, &mut bisigns, &mut bikeys)?;
+
+    let signature_paths: HashSet<String> =
+        bisigns.iter().map(|p| p.as_str().to_lowercase()).collect();
+
+    let mut report = SignatureReport::default();
+    for pbo in &pbos {
+        let prefix = format!("{}.", pbo.as_str().to_lowercase());
+        let signed = signature_paths.iter().any(|s| s.starts_with(&prefix));
+        if !signed {
+            report.unsigned.push(relative(mod_dir, pbo));
+        }
+    }
+
+    if check_keys {
+        let mut authorities = HashSet::new();
+        for key in &bikeys {
+            authorities.insert(read_authority(key)?);
+        }
+        for bisign in &bisigns {
+            let authority = read_authority(bisign)?;
+            if !authorities.contains(&authority) {
+                report.unknown_key.push(UnknownKeySignature {
+                    bisign: relative(mod_dir, bisign),
+                    authority,
+                });
+            }
+        }
+    }
+
+    report.unsigned.sort();
+    report.unknown_key.sort_by(|a, b| a.bisign.cmp(&b.bisign));
+    Ok(report)
+}
+
+/// Reads the authority name that starts every `.bikey` and `.bisign` file.
+pub fn read_authority(path: &Utf8Path) -> std::io::Result<String> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut bytes = Vec::new();
+    reader.read_until(0, &mut bytes)?;
+    if bytes.pop() != Some(0) || bytes.len() > MAX_AUTHORITY_LEN {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("{path} does not start with an authority name"),
+        ));
+    }
+    String::from_utf8(bytes).map_err(|_| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("{path} has a non-UTF-8 authority name"),
+        )
+    })
+}
+
+fn collect_files(
+    dir: &Utf8Path,
+    pbos: &mut Vec<Utf8PathBuf>,
+    bisigns: &mut Vec<Utf8PathBuf>,
+    bikeys: &mut Vec<Utf8PathBuf>,
+) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let Ok(path) = Utf8PathBuf::from_path_buf(entry.path()) else {
+            continue;
+        };
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            collect_files(&path, pbos, bisigns, bikeys)?;
+            continue;
+        }
+        if !file_type.is_file() {
+            continue;
+        }
+        match path.extension().map(str::to_lowercase).as_deref() {
+            Some("pbo") => pbos.push(path),
+            Some("bisign") => bisigns.push(path),
+            Some("bikey") => bikeys.push(path),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn relative(mod_dir: &Utf8Path, path: &Utf8Path) -> String {
+    path.strip_prefix(mod_dir)
+        .unwrap_or(path)
+        .as_str()
+        .replace('\\', "/")
+}
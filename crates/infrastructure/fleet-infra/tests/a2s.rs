@@ -0,0 +1,55 @@
+use fleet_infra::a2s::{query_info, A2sError, ServerInfo};
+use std::time::Duration;
+use tokio::net::UdpSocket;
+
+fn info_reply() -> Vec<u8> {
+    let mut reply = vec![0xFF, 0xFF, 0xFF, 0xFF, 0x49, 17];
+    for s in ["Main Server", "Altis", "Arma3", "Arma 3"] {
+        reply.extend_from_slice(s.as_bytes());
+        reply.push(0);
+    }
+    reply.extend_from_slice(&[0, 0, 12, 64, 0, b'd', b'w', 0, 1]);
+    reply
+}
+
+#[tokio::test]
+async fn query_answers_challenge_and_reads_player_counts() {
+    let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let port = server.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        let mut buf = [0u8; 1400];
+        let (_, peer) = server.recv_from(&mut buf).await.unwrap();
+        server
+            .send_to(&[0xFF, 0xFF, 0xFF, 0xFF, 0x41, 1, 2, 3, 4], peer)
+            .await
+            .unwrap();
+        let (len, peer) = server.recv_from(&mut buf).await.unwrap();
+        assert_eq!(&buf[len - 4..len], &[1, 2, 3, 4], "challenge echoed back");
+        server.send_to(&info_reply(), peer).await.unwrap();
+    });
+
+    let info = query_info("127.0.0.1", port, Duration::from_secs(2))
+        .await
+        .unwrap();
+    assert_eq!(
+        info,
+        ServerInfo {
+            name: "Main Server".into(),
+            map: "Altis".into(),
+            players: 12,
+            max_players: 64,
+        }
+    );
+}
+
+#[tokio::test]
+async fn silent_server_times_out() {
+    let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let port = server.local_addr().unwrap().port();
+
+    let err = query_info("127.0.0.1", port, Duration::from_millis(100))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, A2sError::Timeout(_)), "{err}");
+    drop(server);
+}
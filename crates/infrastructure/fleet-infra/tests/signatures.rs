@@ -0,0 +1,24 @@
+use camino::Utf8PathBuf;
+use fleet_infra::signatures::check_mod_signatures;
+
+fn test_mod(name: &str) -> Utf8PathBuf {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR not set");
+    let path = std::path::PathBuf::from(manifest_dir)
+        .join("../../../test_files")
+        .join(name)
+        .canonicalize()
+        .expect("failed to canonicalize test mod path");
+    Utf8PathBuf::from_path_buf(path).expect("invalid utf8 in test mod path")
+}
+
+#[test]
+fn signed_pbo_with_shipped_key_is_clean() {
+    let report = check_mod_signatures(&test_mod("@ace_compat_cup_vehicles"), true).unwrap();
+    assert!(report.is_clean(), "unexpected report: {report:?}");
+}
+
+#[test]
+fn pbo_without_bisign_is_reported() {
+    let report = check_mod_signatures(&test_mod("@ace"), false).unwrap();
+    assert_eq!(report.unsigned, ["addons/ace_advanced_ballistics.pbo"]);
+}
@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use camino::Utf8Path;
 use serde::{Deserialize, Serialize};
 
-pub const FLEET_REDB_FILENAME: &str = "fleet.redb";
+pub use fleet_core::path_utils::FLEET_REDB_FILENAME;
 pub const CURRENT_SCHEMA: u32 = 1;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -98,6 +98,13 @@ pub trait FleetDataStore: Send + Sync {
         rel_path: &str,
     ) -> Result<(), crate::StorageError>;
 
+    fn scan_cache_delete_files(
+        &self,
+        root: &Utf8Path,
+        mod_name: &str,
+        rel_paths: &[String],
+    ) -> Result<(), crate::StorageError>;
+
     fn scan_cache_delete_mod(
         &self,
         root: &Utf8Path,
@@ -112,6 +119,10 @@ pub trait FleetDataStore: Send + Sync {
         new_rel_path: &str,
     ) -> Result<(), crate::StorageError>;
 
+    /// Drop every scan-cache row while keeping the baseline and meta tables intact.
+    /// Returns the number of entries removed.
+    fn clear_scan_cache(&self, root: &Utf8Path) -> Result<u64, crate::StorageError>;
+
     fn commit_repair_snapshot(
         &self,
         root: &Utf8Path,
@@ -119,6 +130,17 @@ pub trait FleetDataStore: Send + Sync {
         summary: &[LocalManifestSummary],
     ) -> Result<(), crate::StorageError>;
 
+    /// Record one fully downloaded mod while a sync is still running: replaces that mod's
+    /// baseline and summary entries and upserts its scan-cache rows in one transaction.
+    /// The rest of the baseline is left as it was; the sync's final snapshot supersedes it.
+    fn commit_mod_progress(
+        &self,
+        root: &Utf8Path,
+        remote_mod: &fleet_core::Mod,
+        summary: &LocalManifestSummary,
+        cache_updates: &[CacheUpsert],
+    ) -> Result<(), crate::StorageError>;
+
     fn commit_sync_snapshot(
         &self,
         root: &Utf8Path,
@@ -128,4 +150,13 @@ pub trait FleetDataStore: Send + Sync {
         cache_deletes: &[CacheDeleteRecord],
         cache_renames: &[CacheRenameRecord],
     ) -> Result<(), crate::StorageError>;
+
+    /// Re-read the baseline in a fresh read transaction and check it matches `manifest` and
+    /// `summary` and still decodes. Fails with [`crate::StorageError::BaselineVerifyFailed`].
+    fn verify_baseline(
+        &self,
+        root: &Utf8Path,
+        manifest: &fleet_core::Manifest,
+        summary: &[LocalManifestSummary],
+    ) -> Result<(), crate::StorageError>;
 }
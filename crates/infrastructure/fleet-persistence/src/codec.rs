@@ -5,8 +5,10 @@ pub fn encode_manifest(manifest: &fleet_core::Manifest) -> Result<Vec<u8>, Stora
     Ok(serde_json::to_vec(manifest)?)
 }
 
+/// Decodes a stored manifest and migrates it to the current format version.
 pub fn decode_manifest(bytes: &[u8]) -> Result<fleet_core::Manifest, StorageError> {
-    Ok(serde_json::from_slice(bytes)?)
+    let manifest: fleet_core::Manifest = serde_json::from_slice(bytes)?;
+    manifest.migrate().map_err(StorageError::ManifestVersion)
 }
 
 pub fn encode_summary(summary: &[LocalManifestSummary]) -> Result<Vec<u8>, StorageError> {
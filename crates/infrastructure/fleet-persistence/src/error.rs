@@ -4,8 +4,15 @@ pub enum StorageError {
     Missing,
     #[error("fleet.redb schema is invalid or corrupt")]
     Corrupt,
+    /// Scan cache entries for a mod could not be read; the baseline is unaffected and the
+    /// entries can be dropped and rebuilt by rehashing.
+    #[error("fleet.redb scan cache for {0} is corrupt")]
+    CacheCorrupt(String),
     #[error("fleet.redb is from a newer Fleet (schema_version={found}, supported={supported})")]
     NewerSchema { found: u32, supported: u32 },
+    /// The baseline manifest has a version this Fleet cannot read.
+    #[error("fleet.redb baseline: {0}")]
+    ManifestVersion(fleet_core::ManifestVersionError),
     #[error("fleet.redb is busy (locked)")]
     DatabaseAlreadyOpen,
     #[error("io error: {0}")]
@@ -26,12 +33,16 @@ pub enum StorageError {
     RedbCommit(Box<redb::CommitError>),
     #[error("invalid path: {0}")]
     InvalidPath(String),
+    /// The baseline read back after a commit did not match what was written.
+    #[error("fleet.redb baseline failed read-back check: {0}")]
+    BaselineVerifyFailed(String),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum StorageErrorKind {
     Missing,
     Corrupt,
+    CacheCorrupt,
     NewerSchema,
     Busy,
     InvalidPath,
@@ -44,8 +55,17 @@ impl StorageError {
     pub fn kind(&self) -> StorageErrorKind {
         match self {
             StorageError::Missing => StorageErrorKind::Missing,
-            StorageError::Corrupt => StorageErrorKind::Corrupt,
+            StorageError::Corrupt | StorageError::BaselineVerifyFailed(_) => {
+                StorageErrorKind::Corrupt
+            }
+            StorageError::CacheCorrupt(_) => StorageErrorKind::CacheCorrupt,
             StorageError::NewerSchema { .. } => StorageErrorKind::NewerSchema,
+            StorageError::ManifestVersion(fleet_core::ManifestVersionError::Unsupported {
+                ..
+            }) => StorageErrorKind::NewerSchema,
+            StorageError::ManifestVersion(fleet_core::ManifestVersionError::Invalid(_)) => {
+                StorageErrorKind::Corrupt
+            }
             StorageError::DatabaseAlreadyOpen => StorageErrorKind::Busy,
             StorageError::Io(_) => StorageErrorKind::Io,
             StorageError::Serde(_) => StorageErrorKind::Codec,
@@ -1,4 +1,4 @@
-use camino::Utf8Path;
+use camino::{Utf8Path, Utf8PathBuf};
 use chrono::Utc;
 use std::sync::atomic::{AtomicU64, Ordering};
 
@@ -19,3 +19,62 @@ pub fn quarantine_corrupt_file(path: &Utf8Path) -> std::io::Result<()> {
     std::fs::rename(path, &new_path)?;
     Ok(())
 }
+
+/// Files left in `root` by [`quarantine_corrupt_file`] for `db_name`, sorted by name (and
+/// therefore by quarantine time).
+pub fn quarantined_files(root: &Utf8Path, db_name: &str) -> std::io::Result<Vec<Utf8PathBuf>> {
+    let prefix = format!("{db_name}.corrupt.");
+    let entries = match std::fs::read_dir(root) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut files = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        let Ok(name) = entry.file_name().into_string() else {
+            continue;
+        };
+        if name.starts_with(&prefix) && entry.file_type()?.is_file() {
+            files.push(root.join(name));
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Pre-redb JSON files kept at the root of a mods folder.
+pub const LEGACY_ROOT_FILES: [&str; 2] =
+    [".fleet-local-manifest.json", ".fleet-local-summary.json"];
+/// Pre-redb per-mod scan cache, kept inside each `@mod` folder.
+pub const LEGACY_MOD_CACHE: &str = ".fleet-cache.json";
+
+/// Legacy JSON artifacts left in `root` by older versions: the root manifest and summary plus
+/// every `@mod/.fleet-cache.json`, sorted by path.
+pub fn legacy_artifacts(root: &Utf8Path) -> std::io::Result<Vec<Utf8PathBuf>> {
+    let entries = match std::fs::read_dir(root) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut files: Vec<Utf8PathBuf> = LEGACY_ROOT_FILES
+        .iter()
+        .map(|name| root.join(name))
+        .filter(|path| path.is_file())
+        .collect();
+    for entry in entries {
+        let entry = entry?;
+        let Ok(name) = entry.file_name().into_string() else {
+            continue;
+        };
+        if !name.starts_with('@') || !entry.file_type()?.is_dir() {
+            continue;
+        }
+        let cache = root.join(name).join(LEGACY_MOD_CACHE);
+        if cache.is_file() {
+            files.push(cache);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
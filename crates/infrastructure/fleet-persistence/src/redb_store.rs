@@ -3,7 +3,7 @@ use std::sync::{Arc, Mutex, OnceLock};
 
 use camino::{Utf8Path, Utf8PathBuf};
 use chrono::Utc;
-use redb::{Database, ReadableTable, TableDefinition};
+use redb::{Database, ReadableTable, ReadableTableMetadata, TableDefinition};
 
 use crate::api::{
     CacheDeleteRecord, CacheRenameRecord, CacheUpsert, CacheUpsertRecord, DbState,
@@ -14,7 +14,10 @@ use crate::codec::{
     decode_cache_entry, decode_manifest, decode_summary, encode_cache_entry, encode_manifest,
     encode_summary,
 };
-use crate::maintenance::quarantine_corrupt_file;
+use crate::maintenance::{
+    legacy_artifacts, quarantine_corrupt_file, quarantined_files, LEGACY_MOD_CACHE,
+    LEGACY_ROOT_FILES,
+};
 use crate::paths::normalize_rel_path;
 use crate::{FleetDataStore, StorageError};
 
@@ -64,6 +67,71 @@ impl RedbFleetDataStore {
         root.join(FLEET_REDB_FILENAME)
     }
 
+    /// Like [`FleetDataStore::validate`], but never writes: a database without its tables is
+    /// reported rather than initialised, and a corrupt one is left where it is.
+    pub fn probe(&self, root: &Utf8Path) -> Result<DbState, StorageError> {
+        let path = Self::path_for_root(root);
+        if !path.exists() {
+            return Ok(DbState::Missing);
+        }
+        let cached = Self::db_cache()
+            .lock()
+            .expect("db cache lock poisoned")
+            .get(&path)
+            .cloned();
+        let db = match cached {
+            Some(db) => db,
+            None => match Database::open(path.as_std_path()) {
+                Ok(db) => Arc::new(db),
+                Err(redb::DatabaseError::DatabaseAlreadyOpen) => return Ok(DbState::Busy),
+                Err(e) if Self::is_corrupt_open_error(&e) => return Ok(DbState::Corrupt),
+                Err(e) => return Err(e.into()),
+            },
+        };
+        match Self::check_schema(&db) {
+            Ok(()) => Ok(DbState::Valid),
+            Err(StorageError::NewerSchema { found, supported }) => {
+                Ok(DbState::NewerSchema { found, supported })
+            }
+            Err(StorageError::Corrupt) => Ok(DbState::Corrupt),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Corrupt databases moved aside in `root` (`fleet.redb.corrupt.*`), oldest first.
+    /// Unreadable directories are reported as having none.
+    pub fn list_quarantined(root: &Utf8Path) -> Vec<Utf8PathBuf> {
+        quarantined_files(root, FLEET_REDB_FILENAME).unwrap_or_else(|e| {
+            tracing::warn!("failed to list quarantined databases in {root}: {e}");
+            Vec::new()
+        })
+    }
+
+    /// Deletes every quarantined database in `root` and returns how many were removed.
+    pub fn clear_quarantined(root: &Utf8Path) -> Result<usize, StorageError> {
+        let files = quarantined_files(root, FLEET_REDB_FILENAME)?;
+        for file in &files {
+            std::fs::remove_file(file)?;
+        }
+        Ok(files.len())
+    }
+
+    /// Legacy JSON artifacts (`.fleet-local-*.json`, `@mod/.fleet-cache.json`) still in `root`.
+    pub fn list_legacy_artifacts(root: &Utf8Path) -> Result<Vec<Utf8PathBuf>, StorageError> {
+        Ok(legacy_artifacts(root)?)
+    }
+
+    /// Deletes every legacy JSON artifact in `root`; returns the file count and bytes freed.
+    pub fn remove_legacy_artifacts(root: &Utf8Path) -> Result<(usize, u64), StorageError> {
+        let files = legacy_artifacts(root)?;
+        let mut bytes = 0;
+        for file in &files {
+            bytes += std::fs::metadata(file)?.len();
+            std::fs::remove_file(file)?;
+        }
+        Ok((files.len(), bytes))
+    }
+
     fn open_or_create(&self, root: &Utf8Path) -> Result<Arc<Database>, StorageError> {
         let path = Self::path_for_root(root);
         if let Some(parent) = path.parent() {
@@ -167,10 +235,22 @@ impl RedbFleetDataStore {
         let _ = write_tx.open_table(BASELINE)?;
         let _ = write_tx.open_table(SCAN_CACHE)?;
         write_tx.commit()?;
+        Self::check_schema(db)
+    }
 
-        // Validate schema version.
+    /// Validates the format marker and schema version in a read transaction.
+    fn check_schema(db: &Database) -> Result<(), StorageError> {
         let read_tx = db.begin_read()?;
-        let meta = read_tx.open_table(META)?;
+        let meta = match read_tx.open_table(META) {
+            Ok(meta) => meta,
+            Err(redb::TableError::TableDoesNotExist(_)) => return Err(StorageError::Corrupt),
+            Err(e) => return Err(e.into()),
+        };
+        if meta.get(META_FORMAT_KEY)?.map(|g| g.value().to_string())
+            != Some(META_FORMAT_VALUE.to_string())
+        {
+            return Err(StorageError::Corrupt);
+        }
         let schema_version = meta
             .get(META_SCHEMA_VERSION)?
             .and_then(|g| g.value().parse::<u32>().ok())
@@ -223,37 +303,19 @@ impl RedbFleetDataStore {
         root: &Utf8Path,
         touched_mods: Option<&std::collections::HashSet<String>>,
     ) {
-        let _ = std::fs::remove_file(root.join(".fleet-local-manifest.json").as_std_path());
-        let _ = std::fs::remove_file(root.join(".fleet-local-summary.json").as_std_path());
-
-        let remove_mod_cache = |mod_name: &str| {
-            let _ =
-                std::fs::remove_file(root.join(mod_name).join(".fleet-cache.json").as_std_path());
-        };
-
         if let Some(mods) = touched_mods {
+            for name in LEGACY_ROOT_FILES {
+                let _ = std::fs::remove_file(root.join(name).as_std_path());
+            }
             for mod_name in mods {
-                remove_mod_cache(mod_name);
+                let _ =
+                    std::fs::remove_file(root.join(mod_name).join(LEGACY_MOD_CACHE).as_std_path());
             }
             return;
         }
 
-        if let Ok(entries) = std::fs::read_dir(root.as_std_path()) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if !path.is_dir() {
-                    continue;
-                }
-                let Ok(utf) = Utf8PathBuf::from_path_buf(path) else {
-                    continue;
-                };
-                let Some(name) = utf.file_name() else {
-                    continue;
-                };
-                if name.starts_with('@') {
-                    remove_mod_cache(name);
-                }
-            }
+        for file in legacy_artifacts(root).unwrap_or_default() {
+            let _ = std::fs::remove_file(file.as_std_path());
         }
     }
 }
@@ -308,7 +370,9 @@ impl FleetDataStore for RedbFleetDataStore {
         let guard = baseline
             .get(BASELINE_MANIFEST)?
             .ok_or(StorageError::Missing)?;
-        decode_manifest(guard.value())
+        // An unreadable baseline cannot be rebuilt locally; callers must repair. One from a
+        // newer Fleet is reported as such instead.
+        decode_manifest(guard.value()).map_err(unless_newer_manifest)
     }
 
     fn load_baseline_summary(
@@ -321,7 +385,7 @@ impl FleetDataStore for RedbFleetDataStore {
         let guard = baseline
             .get(BASELINE_SUMMARY)?
             .ok_or(StorageError::Missing)?;
-        decode_summary(guard.value())
+        decode_summary(guard.value()).map_err(|_| StorageError::Corrupt)
     }
 
     fn scan_cache_load_mod(
@@ -342,15 +406,19 @@ impl FleetDataStore for RedbFleetDataStore {
         let (start, end) = CacheKey::range_for_mod(mod_name)?;
         let read_tx = db.begin_read()?;
         let cache = read_tx.open_table(SCAN_CACHE)?;
+        let corrupt = || StorageError::CacheCorrupt(mod_name.to_string());
 
         let mut out = HashMap::new();
-        for row in cache.range(start.as_slice()..end.as_slice())? {
-            let (k, v) = row?;
+        for row in cache
+            .range(start.as_slice()..end.as_slice())
+            .map_err(|_| corrupt())?
+        {
+            let (k, v) = row.map_err(|_| corrupt())?;
             let key = k.value();
             let Some(rel) = CacheKey::rel_path_from_prefixed_key(start.as_slice(), key) else {
                 continue;
             };
-            let entry = decode_cache_entry(v.value())?;
+            let entry = decode_cache_entry(v.value()).map_err(|_| corrupt())?;
             out.insert(rel.to_string(), entry);
         }
         Ok(out)
@@ -401,6 +469,31 @@ impl FleetDataStore for RedbFleetDataStore {
         Ok(())
     }
 
+    fn scan_cache_delete_files(
+        &self,
+        root: &Utf8Path,
+        mod_name: &str,
+        rel_paths: &[String],
+    ) -> Result<(), StorageError> {
+        CacheKey::validate_mod_name(mod_name)?;
+        let path = Self::path_for_root(root);
+        if !path.exists() {
+            return Ok(());
+        }
+        let db = self.open_or_create(root)?;
+        let write_tx = db.begin_write()?;
+        {
+            let mut table = write_tx.open_table(SCAN_CACHE)?;
+            for rel_path in rel_paths {
+                let rel = normalize_rel_path(rel_path)?;
+                let key = CacheKey::new(mod_name, &rel).to_bytes();
+                let _ = table.remove(key.as_slice())?;
+            }
+        }
+        write_tx.commit()?;
+        Ok(())
+    }
+
     fn scan_cache_delete_mod(&self, root: &Utf8Path, mod_name: &str) -> Result<(), StorageError> {
         CacheKey::validate_mod_name(mod_name)?;
         let path = Self::path_for_root(root);
@@ -455,6 +548,19 @@ impl FleetDataStore for RedbFleetDataStore {
         Ok(())
     }
 
+    fn clear_scan_cache(&self, root: &Utf8Path) -> Result<u64, StorageError> {
+        let db = self.open_existing(root)?;
+        let write_tx = db.begin_write()?;
+        let removed = {
+            let mut table = write_tx.open_table(SCAN_CACHE)?;
+            let removed = table.len()?;
+            table.retain(|_, _| false)?;
+            removed
+        };
+        write_tx.commit()?;
+        Ok(removed)
+    }
+
     fn commit_repair_snapshot(
         &self,
         root: &Utf8Path,
@@ -482,6 +588,59 @@ impl FleetDataStore for RedbFleetDataStore {
         Ok(())
     }
 
+    fn commit_mod_progress(
+        &self,
+        root: &Utf8Path,
+        remote_mod: &fleet_core::Mod,
+        summary: &LocalManifestSummary,
+        cache_updates: &[CacheUpsert],
+    ) -> Result<(), StorageError> {
+        CacheKey::validate_mod_name(&remote_mod.name)?;
+        let db = self.open_or_create(root)?;
+        let normalized =
+            Self::normalize_manifest(&fleet_core::Manifest::new(vec![remote_mod.clone()]))?;
+        let mut summary = Self::normalize_summary(std::slice::from_ref(summary))?;
+
+        let write_tx = db.begin_write()?;
+        {
+            let mut baseline = write_tx.open_table(BASELINE)?;
+            // A baseline that exists but can't be decoded must be repaired, not patched.
+            let mut manifest = match baseline.get(BASELINE_MANIFEST)? {
+                Some(guard) => decode_manifest(guard.value()).map_err(unless_newer_manifest)?,
+                None => fleet_core::Manifest::new(Vec::new()),
+            };
+            let mut summaries = match baseline.get(BASELINE_SUMMARY)? {
+                Some(guard) => decode_summary(guard.value()).map_err(|_| StorageError::Corrupt)?,
+                None => Vec::new(),
+            };
+
+            let same_mod = |name: &str| name.eq_ignore_ascii_case(&remote_mod.name);
+            manifest.mods.retain(|m| !same_mod(&m.name));
+            manifest.mods.extend(normalized.mods);
+            summaries.retain(|s| !same_mod(&s.mod_name));
+            summaries.append(&mut summary);
+
+            let manifest_bytes = encode_manifest(&manifest)?;
+            let summary_bytes = encode_summary(&summaries)?;
+            baseline.insert(BASELINE_MANIFEST, manifest_bytes.as_slice())?;
+            baseline.insert(BASELINE_SUMMARY, summary_bytes.as_slice())?;
+
+            let mut cache = write_tx.open_table(SCAN_CACHE)?;
+            for up in cache_updates {
+                let rel = normalize_rel_path(&up.rel_path)?;
+                let key = CacheKey::new(&remote_mod.name, &rel).to_bytes();
+                let value = encode_cache_entry(&crate::api::FileCacheEntry {
+                    mtime: up.mtime,
+                    size: up.size,
+                    checksum: up.checksum.clone(),
+                })?;
+                cache.insert(key.as_slice(), value.as_slice())?;
+            }
+        }
+        write_tx.commit()?;
+        Ok(())
+    }
+
     fn commit_sync_snapshot(
         &self,
         root: &Utf8Path,
@@ -567,4 +726,51 @@ impl FleetDataStore for RedbFleetDataStore {
         Self::cleanup_legacy_artifacts(root, Some(&touched_mods));
         Ok(())
     }
+
+    fn verify_baseline(
+        &self,
+        root: &Utf8Path,
+        manifest: &fleet_core::Manifest,
+        summary: &[LocalManifestSummary],
+    ) -> Result<(), StorageError> {
+        let manifest_bytes = encode_manifest(&Self::normalize_manifest(manifest)?)?;
+        let summary_bytes = encode_summary(&Self::normalize_summary(summary)?)?;
+
+        let db = self.open_existing(root)?;
+        let read_tx = db.begin_read()?;
+        let baseline = read_tx.open_table(BASELINE)?;
+        for (key, expected) in [
+            (BASELINE_MANIFEST, manifest_bytes.as_slice()),
+            (BASELINE_SUMMARY, summary_bytes.as_slice()),
+        ] {
+            let failure = match baseline.get(key)? {
+                None => Some("missing after commit"),
+                Some(guard) if guard.value() != expected => Some("differs from what was written"),
+                Some(guard) => {
+                    let decoded = if key == BASELINE_MANIFEST {
+                        decode_manifest(guard.value()).map(|_| ())
+                    } else {
+                        decode_summary(guard.value()).map(|_| ())
+                    };
+                    decoded.err().map(|_| "does not decode")
+                }
+            };
+            if let Some(reason) = failure {
+                tracing::error!(key, reason, "baseline read-back check failed");
+                return Err(StorageError::BaselineVerifyFailed(format!(
+                    "{key} {reason}"
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Maps a baseline decode failure to [`StorageError::Corrupt`], except a manifest written by
+/// a newer Fleet, which must not be treated as damage and overwritten.
+fn unless_newer_manifest(e: StorageError) -> StorageError {
+    match e {
+        StorageError::ManifestVersion(fleet_core::ManifestVersionError::Unsupported { .. }) => e,
+        _ => StorageError::Corrupt,
+    }
 }
@@ -0,0 +1,48 @@
+use camino::Utf8PathBuf;
+use fleet_persistence::{
+    FleetDataStore, LocalManifestSummary, RedbFleetDataStore, StorageError, StorageErrorKind,
+};
+
+#[test]
+fn sync_snapshot_commit_passes_read_back_check() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let manifest = fleet_core::Manifest {
+        version: "1".into(),
+        mods: Vec::new(),
+    };
+    let summary = vec![LocalManifestSummary {
+        mod_name: "@a".into(),
+        files: Vec::new(),
+    }];
+
+    let store = RedbFleetDataStore;
+    store
+        .commit_sync_snapshot(&root, &manifest, &summary, &[], &[], &[])
+        .unwrap();
+
+    store.verify_baseline(&root, &manifest, &summary).unwrap();
+    assert_eq!(store.load_baseline_manifest(&root).unwrap().version, "1.0");
+    assert_eq!(store.load_baseline_summary(&root).unwrap(), summary);
+}
+
+#[test]
+fn read_back_that_differs_is_reported_as_corruption() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let manifest = fleet_core::Manifest::new(Vec::new());
+    let store = RedbFleetDataStore;
+    store
+        .commit_sync_snapshot(&root, &manifest, &[], &[], &[], &[])
+        .unwrap();
+
+    let summary = vec![LocalManifestSummary {
+        mod_name: "@a".into(),
+        files: Vec::new(),
+    }];
+    let err = store
+        .verify_baseline(&root, &manifest, &summary)
+        .unwrap_err();
+    assert!(matches!(err, StorageError::BaselineVerifyFailed(_)));
+    assert_eq!(err.kind(), StorageErrorKind::Corrupt);
+}
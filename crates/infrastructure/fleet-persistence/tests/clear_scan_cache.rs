@@ -0,0 +1,73 @@
+use camino::Utf8PathBuf;
+use fleet_persistence::{CacheUpsert, FleetDataStore, RedbFleetDataStore, StorageErrorKind};
+
+#[test]
+fn clear_scan_cache_removes_entries_but_keeps_baseline() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+
+    let store = RedbFleetDataStore;
+    store
+        .commit_repair_snapshot(
+            &root,
+            &fleet_core::Manifest {
+                version: "1.0".into(),
+                mods: vec![],
+            },
+            &[],
+        )
+        .unwrap();
+
+    store
+        .scan_cache_upsert_batch(
+            &root,
+            "@a",
+            &[
+                CacheUpsert {
+                    rel_path: "a1.txt".into(),
+                    mtime: 1,
+                    size: 1,
+                    checksum: "a1".into(),
+                },
+                CacheUpsert {
+                    rel_path: "a2.txt".into(),
+                    mtime: 2,
+                    size: 2,
+                    checksum: "a2".into(),
+                },
+            ],
+        )
+        .unwrap();
+    store
+        .scan_cache_upsert_batch(
+            &root,
+            "@b",
+            &[CacheUpsert {
+                rel_path: "b.txt".into(),
+                mtime: 3,
+                size: 3,
+                checksum: "b".into(),
+            }],
+        )
+        .unwrap();
+
+    assert_eq!(store.clear_scan_cache(&root).unwrap(), 3);
+    assert!(store.scan_cache_load_mod(&root, "@a").unwrap().is_empty());
+    assert!(store.scan_cache_load_mod(&root, "@b").unwrap().is_empty());
+
+    let manifest = store.load_baseline_manifest(&root).unwrap();
+    assert_eq!(manifest.version, "1.0");
+    assert!(store.load_baseline_summary(&root).unwrap().is_empty());
+
+    assert_eq!(store.clear_scan_cache(&root).unwrap(), 0);
+}
+
+#[test]
+fn clear_scan_cache_reports_missing_database() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+
+    let err = RedbFleetDataStore.clear_scan_cache(&root).unwrap_err();
+    assert_eq!(err.kind(), StorageErrorKind::Missing);
+    assert!(!root.join("fleet.redb").exists());
+}
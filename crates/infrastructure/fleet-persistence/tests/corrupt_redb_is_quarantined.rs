@@ -37,3 +37,27 @@ fn corrupt_redb_is_quarantined_and_repair_can_recreate() {
     let manifest = store.load_baseline_manifest(&root).unwrap();
     assert!(manifest.mods.is_empty());
 }
+
+#[test]
+fn quarantined_databases_can_be_listed_and_cleared() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let store = RedbFleetDataStore;
+    assert!(RedbFleetDataStore::list_quarantined(&root).is_empty());
+
+    for _ in 0..2 {
+        std::fs::write(root.join("fleet.redb"), b"definitely-not-a-redb-database").unwrap();
+        assert_eq!(store.validate(&root).unwrap(), DbState::Corrupt);
+    }
+    std::fs::write(root.join("unrelated.txt"), b"keep me").unwrap();
+
+    let quarantined = RedbFleetDataStore::list_quarantined(&root);
+    assert_eq!(quarantined.len(), 2);
+    assert!(quarantined
+        .iter()
+        .all(|p| p.file_name().unwrap().starts_with("fleet.redb.corrupt.")));
+
+    assert_eq!(RedbFleetDataStore::clear_quarantined(&root).unwrap(), 2);
+    assert!(RedbFleetDataStore::list_quarantined(&root).is_empty());
+    assert!(root.join("unrelated.txt").exists());
+}
@@ -0,0 +1,39 @@
+use camino::Utf8PathBuf;
+use fleet_persistence::RedbFleetDataStore;
+
+#[test]
+fn legacy_artifacts_are_listed_and_removed() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+
+    std::fs::write(root.join(".fleet-local-manifest.json"), b"{}").unwrap();
+    std::fs::create_dir_all(root.join("@a")).unwrap();
+    std::fs::write(root.join("@a/.fleet-cache.json"), b"{\"files\":[]}").unwrap();
+    std::fs::write(root.join("@a/data.pbo"), b"keep").unwrap();
+    // Only `@mod` folders carry a legacy cache.
+    std::fs::create_dir_all(root.join("other")).unwrap();
+    std::fs::write(root.join("other/.fleet-cache.json"), b"{}").unwrap();
+
+    let listed = RedbFleetDataStore::list_legacy_artifacts(&root).unwrap();
+    assert_eq!(
+        listed,
+        vec![
+            root.join(".fleet-local-manifest.json"),
+            root.join("@a/.fleet-cache.json"),
+        ]
+    );
+
+    let (removed, bytes) = RedbFleetDataStore::remove_legacy_artifacts(&root).unwrap();
+    assert_eq!(removed, 2);
+    assert_eq!(bytes, 2 + 12);
+    assert!(RedbFleetDataStore::list_legacy_artifacts(&root)
+        .unwrap()
+        .is_empty());
+    assert!(root.join("@a/data.pbo").exists());
+    assert!(root.join("other/.fleet-cache.json").exists());
+
+    assert_eq!(
+        RedbFleetDataStore::remove_legacy_artifacts(&root).unwrap(),
+        (0, 0)
+    );
+}
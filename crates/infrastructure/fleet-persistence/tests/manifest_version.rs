@@ -0,0 +1,60 @@
+use camino::Utf8PathBuf;
+use fleet_persistence::{
+    FleetDataStore, LocalManifestSummary, RedbFleetDataStore, StorageErrorKind,
+};
+use redb::TableDefinition;
+
+const BASELINE: TableDefinition<&str, &[u8]> = TableDefinition::new("baseline");
+
+#[test]
+fn a_baseline_from_a_newer_major_is_refused_not_treated_as_corrupt() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let store = RedbFleetDataStore;
+    // Written straight to the table, as a newer Fleet would have.
+    let db = redb::Database::create(root.join("fleet.redb").as_std_path()).unwrap();
+    let write_tx = db.begin_write().unwrap();
+    {
+        let mut baseline = write_tx.open_table(BASELINE).unwrap();
+        baseline
+            .insert("manifest", br#"{"Version":"2.0","Mods":[]}"#.as_slice())
+            .unwrap();
+    }
+    write_tx.commit().unwrap();
+    drop(db);
+
+    let err = store.load_baseline_manifest(&root).unwrap_err();
+    assert_eq!(err.kind(), StorageErrorKind::NewerSchema);
+    assert!(err.to_string().contains("2.0"), "got: {err}");
+
+    let summary = LocalManifestSummary {
+        mod_name: "@a".into(),
+        files: Vec::new(),
+    };
+    let remote_mod = fleet_core::Mod {
+        name: "@a".into(),
+        checksum: "A".into(),
+        files: Vec::new(),
+    };
+    let err = store
+        .commit_mod_progress(&root, &remote_mod, &summary, &[])
+        .unwrap_err();
+    assert_eq!(err.kind(), StorageErrorKind::NewerSchema);
+}
+
+#[test]
+fn an_unversioned_baseline_loads_as_the_current_version() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let store = RedbFleetDataStore;
+    let manifest = fleet_core::Manifest {
+        version: String::new(),
+        mods: Vec::new(),
+    };
+    store
+        .commit_sync_snapshot(&root, &manifest, &[], &[], &[], &[])
+        .unwrap();
+
+    let manifest = store.load_baseline_manifest(&root).unwrap();
+    assert_eq!(manifest.version, fleet_core::MANIFEST_VERSION);
+}
@@ -46,3 +46,17 @@ fn validate_reports_newer_schema_without_quarantine() {
 
     assert!(db_path.exists(), "newer schema should not be quarantined");
 }
+
+#[test]
+fn probe_reports_a_corrupt_database_without_moving_it() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = Utf8PathBuf::from_path_buf(dir.path().to_path_buf()).unwrap();
+    let db_path = root.join("fleet.redb");
+    // A redb file with no Fleet metadata in it.
+    drop(redb::Database::create(db_path.as_std_path()).unwrap());
+    let before = std::fs::read(&db_path).unwrap();
+
+    assert_eq!(RedbFleetDataStore.probe(&root).unwrap(), DbState::Corrupt);
+    assert_eq!(std::fs::read(&db_path).unwrap(), before);
+    assert!(RedbFleetDataStore::list_quarantined(&root).is_empty());
+}
@@ -1,19 +1,36 @@
-use crate::{CliScanStrategy, CliSyncMode};
+use crate::{CliDownloadOrder, CliOutputFormat, CliScanStrategy, CliSyncMode, NetArgs};
 use anyhow::{Context, Result};
-use camino::Utf8PathBuf;
+use camino::{Utf8Path, Utf8PathBuf};
+use fleet_app_core::viewmodel::{mod_tree, ModNode};
+use fleet_core::diff::{diff_report, DiffReport, FileStatus};
 use fleet_core::formats::RepositoryExternal;
 use fleet_core::repo::Repository;
-use fleet_persistence::{DbState, FleetDataStore, RedbFleetDataStore};
-use fleet_pipeline::sync::{SyncMode, SyncOptions, SyncRequest};
-use fleet_scanner::{ScanStats, Scanner};
+use fleet_core::DeleteThreshold;
+use fleet_infra::{HashOptions, NetConfig};
+use fleet_persistence::{DbState, FleetDataStore, RedbFleetDataStore, StorageErrorKind};
+use fleet_pipeline::publish::PublishReport;
+use fleet_pipeline::sync::plan_file::SavedPlan;
+use fleet_pipeline::sync::{SyncError, SyncMode, SyncOptions, SyncRequest};
+use fleet_scanner::{ScanOptions, ScanStats, Scanner};
 use humansize::{format_size, DECIMAL};
-use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use indicatif::{HumanBytes, MultiProgress, ProgressBar, ProgressStyle};
+use std::collections::HashMap;
 use std::time::Duration;
 
+/// Where `scan` writes its result.
+pub enum ScanOutput {
+    /// The combined manifest JSON, to a file or stdout.
+    Manifest(Option<Utf8PathBuf>),
+    /// `<mod>.srf` for every mod and a `repo.json` in this directory.
+    Srf(Utf8PathBuf),
+}
+
 pub async fn cmd_scan(
     path: Utf8PathBuf,
-    output: Option<Utf8PathBuf>,
+    output: ScanOutput,
     strategy: CliScanStrategy,
+    chunk_size: u64,
+    scan_threads: Option<usize>,
 ) -> anyhow::Result<()> {
     println!(":: Scanning directory: {}", path);
 
@@ -43,58 +60,223 @@ pub async fn cmd_scan(
 
     let root = path.clone();
     let manifest = tokio::task::spawn_blocking(move || {
-        Scanner::scan_directory(root.as_path(), strategy, Some(cb), None, None)
+        Scanner::scan_directory_with_options(
+            root.as_path(),
+            strategy,
+            ScanOptions {
+                hash: HashOptions { chunk_size },
+                threads: scan_threads,
+                ..ScanOptions::default()
+            },
+            Some(cb),
+            None,
+            None,
+        )
     })
     .await??;
 
     pb.finish_with_message("Scan complete.");
 
-    let json = serde_json::to_string_pretty(&manifest)?;
-    if let Some(out) = output {
-        std::fs::write(&out, json)?;
-        println!(":: Saved manifest to {}", out);
-    } else {
-        println!("{}", json);
+    match output {
+        ScanOutput::Manifest(output) => {
+            let json = serde_json::to_string_pretty(&manifest)?;
+            if let Some(out) = output {
+                std::fs::write(&out, json)?;
+                println!(":: Saved manifest to {}", out);
+            } else {
+                println!("{}", json);
+            }
+        }
+        ScanOutput::Srf(dir) => {
+            let repo_name = path.file_name().unwrap_or("repository");
+            write_srf_files(&dir, repo_name, &manifest)?;
+            println!(
+                ":: Saved {} mod.srf file(s) and repo.json to {}",
+                manifest.mods.len(),
+                dir
+            );
+        }
     }
 
     Ok(())
 }
 
+/// Writes `<mod>.srf` for every mod in `manifest` and a `repo.json` listing them into `dir`.
+fn write_srf_files(
+    dir: &camino::Utf8Path,
+    repo_name: &str,
+    manifest: &fleet_core::Manifest,
+) -> anyhow::Result<()> {
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {dir}"))?;
+    let mut mods = manifest.mods.clone();
+    mods.sort_by_key(|m| m.name.to_lowercase());
+    for m in &mods {
+        let path = dir.join(format!("{}.srf", m.name));
+        std::fs::write(&path, serde_json::to_vec_pretty(m)?)
+            .with_context(|| format!("Failed to write {path}"))?;
+    }
+    let repo = fleet_pipeline::publish::repository_json(repo_name, &mods);
+    let path = dir.join("repo.json");
+    std::fs::write(&path, serde_json::to_vec_pretty(&repo)?)
+        .with_context(|| format!("Failed to write {path}"))?;
+    Ok(())
+}
+
+/// Where `publish --upload` pushes the repository.
+pub struct UploadTarget {
+    pub url: String,
+    /// Upload every file instead of only what differs from the remote.
+    pub full: bool,
+    pub rate_limit_bytes: Option<u64>,
+    pub net: NetConfig,
+}
+
+/// Hash every mod under `path` and write the repository metadata next to them, ready to be
+/// served over HTTP or read as a `file://` repository. With `upload`, the remote is then
+/// brought in line: files that differ from what it serves are pushed and stale ones deleted.
+pub async fn cmd_publish(
+    path: Utf8PathBuf,
+    name: String,
+    scan_threads: Option<usize>,
+    upload: Option<UploadTarget>,
+) -> anyhow::Result<PublishReport> {
+    println!(":: Publishing {} as '{}'", path, name);
+
+    let root = path.clone();
+    let manifest = tokio::task::spawn_blocking(move || {
+        // Always a full hash: a stale cache entry would publish a wrong checksum.
+        Scanner::scan_directory_with_options(
+            root.as_path(),
+            fleet_scanner::ScanStrategy::ForceRehash,
+            ScanOptions {
+                threads: scan_threads,
+                ..Default::default()
+            },
+            None,
+            None,
+            None,
+        )
+    })
+    .await??;
+
+    let report = fleet_pipeline::publish::write_repository(&path, &name, &manifest)?;
+    for mod_name in &report.rewritten {
+        println!("   updated    {mod_name}");
+    }
+    for mod_name in &report.unchanged {
+        println!("   unchanged  {mod_name}");
+    }
+    let files: usize = manifest.mods.iter().map(|m| m.files.len()).sum();
+    println!(
+        ":: Published {} mods ({} updated, {} unchanged, {} files, checksum {})",
+        report.repo.required_mods.len(),
+        report.rewritten.len(),
+        report.unchanged.len(),
+        files,
+        report.repo.checksum
+    );
+
+    if let Some(target) = upload {
+        let client = fleet_infra::net::default_http_client(Some(&target.net))?;
+        let remote = fleet_pipeline::publish::fetch_published(client.clone(), &target.url).await?;
+        let mut plan = fleet_pipeline::publish::plan_upload(&manifest, &remote);
+        if target.full {
+            plan.puts = fleet_pipeline::publish::repository_paths(&manifest);
+        }
+        println!(
+            ":: Uploading {} file(s) to {}, deleting {}",
+            plan.puts.len(),
+            target.url,
+            plan.deletes.len()
+        );
+        let publisher = fleet_pipeline::publish::HttpPutPublisher::new(
+            client,
+            &target.url,
+            target.rate_limit_bytes,
+        )?;
+        fleet_pipeline::publish::upload_changes(&path, &plan, &publisher).await?;
+        println!(":: Upload complete.");
+    }
+    Ok(report)
+}
+
+/// First repository is the primary; the rest are merged into it.
+fn split_repos(repos: Vec<String>) -> Result<(String, Vec<String>)> {
+    let mut repos = repos.into_iter();
+    let primary = repos
+        .next()
+        .context("At least one repository URL is required")?;
+    Ok((primary, repos.collect()))
+}
+
 pub async fn cmd_check(
-    repo: String,
+    repos: Vec<String>,
     local_path: Utf8PathBuf,
     mode: CliSyncMode,
+    net: &NetConfig,
 ) -> anyhow::Result<fleet_core::SyncPlan> {
     println!(":: Analyzing state...");
-    println!("   Repo:  {}", repo);
+    println!("   Repo:  {}", repos.join(", "));
     println!("   Local: {}", local_path);
 
-    let client = fleet_infra::net::default_http_client().context("Failed to build HTTP client")?;
+    let client =
+        fleet_infra::net::default_http_client(Some(net)).context("Failed to build HTTP client")?;
     let engine = fleet_pipeline::default_engine(client);
 
+    let (repo_url, extra_repo_urls) = split_repos(repos)?;
     let req = SyncRequest {
-        repo_url: repo,
+        repo_url,
+        extra_repo_urls,
         local_root: local_path,
         mode: mode.into(),
         options: SyncOptions::default(),
         profile_id: None,
     };
 
-    let plan = engine.plan(&req).await?;
+    let fetch = engine.fetch_remote_state(&req).await?;
+    let local = engine.scan_local_state(&req, None, None).await?;
+    let plan = engine.compute_plan(&fetch, &local, &req)?;
 
     println!("\n:: Analysis Result");
     println!("   Pending Downloads: {}", plan.downloads.len());
     println!("   Pending Deletes:   {}", plan.deletes.len());
     println!("   Verified Files:    {}", plan.checks.len());
 
+    if !fetch.case_collisions.is_empty() {
+        println!(
+            "\n:: Case Collisions ({}): paths that are the same file on Windows/macOS",
+            fetch.case_collisions.len()
+        );
+        for c in &fetch.case_collisions {
+            println!(
+                "   {}: keeping {}, ignoring {}",
+                c.mod_name,
+                c.kept,
+                c.dropped.join(", ")
+            );
+        }
+    }
+
+    if !fetch.unreachable_mods.is_empty() {
+        println!(
+            "\n:: Unreachable Mods ({}): not planned",
+            fetch.unreachable_mods.len()
+        );
+        for m in &fetch.unreachable_mods {
+            println!("   {}: {}", m.mod_name, m.reason);
+        }
+    }
+
     Ok(plan)
 }
 
-pub async fn cmd_check_for_updates(repo: String, local_path: Utf8PathBuf) -> anyhow::Result<()> {
-    println!(":: Checking for updates...");
-    println!("   Repo:  {}", repo);
-    println!("   Local: {}", local_path);
-
+/// The request `check-for-updates` plans with: a fast check against a valid database,
+/// otherwise a verifying scan.
+fn update_check_request(
+    repos: Vec<String>,
+    local_path: Utf8PathBuf,
+    net: &NetConfig,
+) -> anyhow::Result<SyncRequest> {
     let store = RedbFleetDataStore;
     let mode = match store.validate(&local_path)? {
         DbState::Valid => SyncMode::FastCheck,
@@ -107,23 +289,67 @@ pub async fn cmd_check_for_updates(repo: String, local_path: Utf8PathBuf) -> any
         ),
     };
 
-    let client = fleet_infra::net::default_http_client().context("Failed to build HTTP client")?;
-    let engine = fleet_pipeline::default_engine(client);
-
-    let req = SyncRequest {
-        repo_url: repo,
+    let (repo_url, extra_repo_urls) = split_repos(repos)?;
+    Ok(SyncRequest {
+        repo_url,
+        extra_repo_urls,
         local_root: local_path,
         mode,
-        options: SyncOptions::default(),
+        options: SyncOptions {
+            offline: net.offline,
+            ..SyncOptions::default()
+        },
         profile_id: None,
-    };
+    })
+}
+
+pub async fn cmd_check_for_updates(
+    repos: Vec<String>,
+    local_path: Utf8PathBuf,
+    net: &NetConfig,
+    save_plan: Option<Utf8PathBuf>,
+    format: CliOutputFormat,
+) -> anyhow::Result<()> {
+    let text = format == CliOutputFormat::Text;
+    if text {
+        println!(":: Checking for updates...");
+        println!("   Repo:  {}", repos.join(", "));
+        println!("   Local: {}", local_path);
+    }
 
-    let plan = engine.plan(&req).await?;
+    let req = update_check_request(repos, local_path, net)?;
+    let client =
+        fleet_infra::net::default_http_client(Some(net)).context("Failed to build HTTP client")?;
+    let engine = fleet_pipeline::default_engine(client);
+
+    let (plan, report, remote) = engine.plan_with_remote(&req).await?;
+
+    if let Some(plan_path) = &save_plan {
+        engine.save_plan(&req, &plan, &remote, plan_path)?;
+        if text {
+            println!("   Plan saved to {}", plan_path);
+        }
+    }
+
+    if !text {
+        let out = serde_json::json!({ "plan": plan, "report": report });
+        println!("{}", serde_json::to_string_pretty(&out)?);
+        return Ok(());
+    }
 
     println!("\n:: Update Check Result");
     println!("   Pending Downloads: {}", plan.downloads.len());
     println!("   Pending Deletes:   {}", plan.deletes.len());
 
+    println!(
+        "   Files:             {} unchanged, {} changed, {} added, {} removed, {} renamed",
+        report.count(FileStatus::Unchanged),
+        report.count(FileStatus::Changed),
+        report.count(FileStatus::Added),
+        report.count(FileStatus::Removed),
+        report.count(FileStatus::Renamed)
+    );
+
     if plan.downloads.is_empty() && plan.deletes.is_empty() {
         println!("   Status:            Up to date");
     } else {
@@ -133,6 +359,176 @@ pub async fn cmd_check_for_updates(repo: String, local_path: Utf8PathBuf) -> any
     Ok(())
 }
 
+/// Hashes `local_path` and compares it with the repositories without writing anything, not
+/// even `fleet.redb`. Returns the plan a sync would apply.
+pub async fn cmd_audit(
+    repos: Vec<String>,
+    local_path: Utf8PathBuf,
+    net: &NetConfig,
+    format: CliOutputFormat,
+) -> anyhow::Result<fleet_core::SyncPlan> {
+    let text = format == CliOutputFormat::Text;
+    if text {
+        println!(":: Auditing (read-only)...");
+        println!("   Repo:  {}", repos.join(", "));
+        println!("   Local: {}", local_path);
+    }
+
+    let (repo_url, extra_repo_urls) = split_repos(repos)?;
+    let req = SyncRequest {
+        repo_url,
+        extra_repo_urls,
+        local_root: local_path,
+        mode: SyncMode::FullRehash,
+        options: SyncOptions {
+            offline: net.offline,
+            ..SyncOptions::default()
+        },
+        profile_id: None,
+    };
+    let client =
+        fleet_infra::net::default_http_client(Some(net)).context("Failed to build HTTP client")?;
+    let engine = fleet_pipeline::default_engine(client);
+    let (plan, report) = engine.audit(&req).await?;
+
+    if !text {
+        let out = serde_json::json!({ "plan": plan, "report": report });
+        println!("{}", serde_json::to_string_pretty(&out)?);
+        return Ok(plan);
+    }
+
+    println!("\n:: Audit Result");
+    for file in report
+        .files
+        .iter()
+        .filter(|f| f.status != FileStatus::Unchanged)
+    {
+        let label = match file.status {
+            FileStatus::Added => "missing",
+            FileStatus::Removed => "extra",
+            FileStatus::Renamed => "case differs",
+            _ => "differs",
+        };
+        println!("   {label:<13} {}/{}", file.mod_name, file.path);
+    }
+    println!(
+        "   Files:  {} unchanged, {} changed, {} missing, {} extra",
+        report.count(FileStatus::Unchanged),
+        report.count(FileStatus::Changed),
+        report.count(FileStatus::Added),
+        report.count(FileStatus::Removed)
+    );
+    if plan.downloads.is_empty() && plan.deletes.is_empty() && plan.renames.is_empty() {
+        println!("   Status: Matches the repository");
+    } else {
+        println!("   Status: Differs from the repository (run `sync` to fix)");
+    }
+
+    Ok(plan)
+}
+
+/// Compares the repositories' per-mod checksums with the last synced baseline, without
+/// scanning `local_path`.
+pub async fn cmd_quick_check(
+    repos: Vec<String>,
+    local_path: Utf8PathBuf,
+    net: &NetConfig,
+    format: CliOutputFormat,
+) -> anyhow::Result<fleet_pipeline::QuickCheck> {
+    let text = format == CliOutputFormat::Text;
+    if text {
+        println!(":: Quick check (mod checksums only)...");
+        println!("   Repo:  {}", repos.join(", "));
+        println!("   Local: {}", local_path);
+    }
+
+    let req = update_check_request(repos, local_path, net)?;
+    let client =
+        fleet_infra::net::default_http_client(Some(net)).context("Failed to build HTTP client")?;
+    let check = fleet_pipeline::default_engine(client)
+        .quick_check(&req)
+        .await?;
+
+    if !text {
+        println!("{}", serde_json::to_string_pretty(&check)?);
+        return Ok(check);
+    }
+    for (label, mods) in [
+        ("changed", &check.changed),
+        ("new", &check.added),
+        ("removed", &check.removed),
+    ] {
+        for mod_name in mods {
+            println!("   {label:<8} {mod_name}");
+        }
+    }
+    if check.is_up_to_date() {
+        println!("   Status: Up to date");
+    } else {
+        println!(
+            "   Status: {} mod(s) changed (run `check` for details or `sync`)",
+            check.mods_changed()
+        );
+    }
+    Ok(check)
+}
+
+/// Checks every saved profile for updates, one after another, and prints one line each.
+/// Returns how many profiles have updates.
+pub async fn cmd_check_all(
+    profiles: Vec<fleet_app_core::Profile>,
+    net: &NetArgs,
+) -> anyhow::Result<usize> {
+    println!(":: Checking {} profile(s) for updates...", profiles.len());
+    let width = profiles.iter().map(|p| p.name.len()).max().unwrap_or(0);
+    let (mut updates, mut failed) = (0, 0);
+    for profile in &profiles {
+        let status = async {
+            let net = net.to_net_config_for(profile)?;
+            let mut repos = vec![profile.repo_url.clone()];
+            repos.extend(profile.extra_repo_urls());
+            let req = update_check_request(repos, Utf8PathBuf::from(&profile.local_path), &net)?;
+            let client = fleet_infra::net::default_http_client(Some(&net))
+                .context("Failed to build HTTP client")?;
+            let (plan, _) = fleet_pipeline::default_engine(client)
+                .plan_with_report(&req)
+                .await?;
+            anyhow::Ok(plan)
+        }
+        .await;
+        let line = match status {
+            Ok(plan) if plan.downloads.is_empty() && plan.deletes.is_empty() => {
+                "Up to date".to_string()
+            }
+            Ok(plan) => {
+                updates += 1;
+                let bytes: u64 = plan.downloads.iter().map(|d| d.size).sum();
+                format!(
+                    "Updates available ({} download(s), {}; {} delete(s))",
+                    plan.downloads.len(),
+                    format_size(bytes, DECIMAL),
+                    plan.deletes.len()
+                )
+            }
+            Err(e) => {
+                failed += 1;
+                format!("Check failed: {e:#}")
+            }
+        };
+        println!("   {:width$}  {line}", profile.name);
+    }
+    println!(
+        "\n:: {updates} of {} profile(s) have updates{}",
+        profiles.len(),
+        if failed > 0 {
+            format!(", {failed} check(s) failed")
+        } else {
+            String::new()
+        }
+    );
+    Ok(updates)
+}
+
 pub async fn cmd_local_check(local_path: Utf8PathBuf) -> anyhow::Result<()> {
     println!(":: Local integrity check...");
     println!("   Local: {}", local_path);
@@ -151,11 +547,13 @@ pub async fn cmd_local_check(local_path: Utf8PathBuf) -> anyhow::Result<()> {
         ),
     }
 
-    let client = fleet_infra::net::default_http_client().context("Failed to build HTTP client")?;
+    let client =
+        fleet_infra::net::default_http_client(None).context("Failed to build HTTP client")?;
     let engine = fleet_pipeline::default_engine(client);
 
     let req = SyncRequest {
         repo_url: String::new(),
+        extra_repo_urls: Vec::new(),
         local_root: local_path,
         mode: SyncMode::MetadataOnly,
         options: SyncOptions::default(),
@@ -181,8 +579,11 @@ pub async fn cmd_local_check(local_path: Utf8PathBuf) -> anyhow::Result<()> {
         })
     };
 
-    let local_state = engine.scan_local_state(&req, Some(cb)).await?;
+    let local_state = engine.scan_local_state(&req, Some(cb), None).await?;
     pb.finish_with_message("Scan complete.");
+    for warning in &local_state.warnings {
+        println!("   Warning: {warning}");
+    }
 
     let plan = engine.compute_local_integrity_plan(&req, &local_state)?;
 
@@ -199,19 +600,257 @@ pub async fn cmd_local_check(local_path: Utf8PathBuf) -> anyhow::Result<()> {
     Ok(())
 }
 
-pub async fn cmd_repair(repo: String, local_path: Utf8PathBuf) -> anyhow::Result<()> {
+/// Print the baseline's mods and files, marking what the saved plan at `plan_file` would
+/// download or delete.
+pub fn cmd_tree(
+    local_path: Utf8PathBuf,
+    plan_file: Option<Utf8PathBuf>,
+    format: CliOutputFormat,
+) -> anyhow::Result<Vec<ModNode>> {
+    let store = RedbFleetDataStore;
+    let baseline = match store.validate(&local_path)? {
+        DbState::Valid => store.load_baseline_manifest(&local_path)?,
+        DbState::Missing => fleet_core::Manifest::new(Vec::new()),
+        DbState::Corrupt => anyhow::bail!("Local database is corrupt (run `repair` first)"),
+        DbState::Busy => anyhow::bail!(
+            "Local database is busy (another Fleet instance may be running). Close it and try again."
+        ),
+        DbState::NewerSchema { found, supported } => anyhow::bail!(
+            "Local database is from a newer Fleet (schema_version={found}, supported={supported}). Update Fleet and try again."
+        ),
+    };
+    let plan = match &plan_file {
+        Some(plan_path) => {
+            let data = std::fs::read_to_string(plan_path)
+                .with_context(|| format!("Failed to read plan {plan_path}"))?;
+            let saved: SavedPlan = serde_json::from_str(&data)
+                .with_context(|| format!("Failed to parse plan {plan_path}"))?;
+            if Utf8Path::new(&saved.local_root) != local_path {
+                anyhow::bail!(
+                    "Plan was computed for {}, not {}",
+                    saved.local_root,
+                    local_path
+                );
+            }
+            Some(saved.plan)
+        }
+        None => None,
+    };
+    let tree = mod_tree(&baseline, plan.as_ref());
+
+    if format == CliOutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&tree)?);
+        return Ok(tree);
+    }
+
+    println!(":: Mod tree");
+    println!("   Local: {}", local_path);
+    for node in &tree {
+        println!("\n   {} ({} files)", node.name, node.files.len());
+        for file in &node.files {
+            println!(
+                "     {:<9} {} ({})",
+                file.status.label(),
+                file.path,
+                HumanBytes(file.size)
+            );
+        }
+    }
+    if tree.is_empty() {
+        println!("   Nothing synced yet");
+    }
+
+    Ok(tree)
+}
+
+/// Compare two local mods folders without a repository, treating `dir_a` as the old side.
+/// Returns only the files that differ.
+pub async fn cmd_local_diff(
+    dir_a: Utf8PathBuf,
+    dir_b: Utf8PathBuf,
+    strategy: CliScanStrategy,
+    format: CliOutputFormat,
+) -> anyhow::Result<DiffReport> {
+    let text = format == CliOutputFormat::Text;
+    if text {
+        println!(":: Comparing local directories...");
+        println!("   A: {}", dir_a);
+        println!("   B: {}", dir_b);
+    }
+
+    let strategy = match strategy {
+        CliScanStrategy::Smart => fleet_scanner::ScanStrategy::SmartCache,
+        CliScanStrategy::Force => fleet_scanner::ScanStrategy::ForceRehash,
+    };
+    let scan = |root: Utf8PathBuf| {
+        tokio::task::spawn_blocking(move || {
+            Scanner::scan_directory(root.as_path(), strategy, None, None, None)
+                .with_context(|| format!("Failed to scan {root}"))
+        })
+    };
+    let (a, b) = tokio::try_join!(scan(dir_a), scan(dir_b))?;
+    let (a, b) = (a?, b?);
+
+    let mut report = diff_report(&b, &a);
+    report.files.retain(|f| f.status != FileStatus::Unchanged);
+
+    if !text {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(report);
+    }
+
+    println!("\n:: Local Diff Result");
+    for file in &report.files {
+        let label = match file.status {
+            FileStatus::Added => "only in B",
+            FileStatus::Removed => "only in A",
+            FileStatus::Renamed => "case differs",
+            FileStatus::Changed if file.old_size != file.new_size => "size differs",
+            _ => "checksum differs",
+        };
+        let size = |s: Option<u64>| s.map_or("-".to_string(), |s| HumanBytes(s).to_string());
+        println!(
+            "   {label:<16} {}/{} ({} -> {})",
+            file.mod_name,
+            file.path,
+            size(file.old_size),
+            size(file.new_size)
+        );
+    }
+    if report.files.is_empty() {
+        println!("   Status: Identical");
+    } else {
+        println!("   Status: {} file(s) differ", report.files.len());
+    }
+
+    Ok(report)
+}
+
+/// Report PBOs a signature-checking server would reject. Returns the number of problems.
+pub fn cmd_verify_signatures(local_path: Utf8PathBuf, check_keys: bool) -> anyhow::Result<usize> {
+    println!(":: Verifying PBO signatures...");
+    println!("   Local: {}", local_path);
+
+    let mut problems = 0;
+    for mod_dir in resolve_mods_from_dir(&local_path)? {
+        if !mod_dir.is_dir() {
+            continue;
+        }
+        let report = fleet_infra::check_mod_signatures(&mod_dir, check_keys)
+            .with_context(|| format!("Failed to check signatures in {}", mod_dir))?;
+        if report.is_clean() {
+            continue;
+        }
+
+        let mod_name = mod_dir.file_name().unwrap_or(mod_dir.as_str());
+        println!("\n   {}", mod_name);
+        for pbo in &report.unsigned {
+            println!("     unsigned:    {}", pbo);
+        }
+        for sig in &report.unknown_key {
+            println!("     unknown key: {} ({})", sig.bisign, sig.authority);
+        }
+        problems += report.unsigned.len() + report.unknown_key.len();
+    }
+
+    if problems == 0 {
+        println!("   Status: All PBOs signed");
+    }
+    Ok(problems)
+}
+
+/// Prints the total download size of a repository without comparing it to a local folder.
+pub async fn cmd_repo_info(
+    repo_url: String,
+    include_optional: bool,
+    net: &NetConfig,
+) -> anyhow::Result<fleet_pipeline::RepoSizeInfo> {
+    println!(":: Repository info");
+    println!("   Repo:  {}", repo_url);
+
+    let client =
+        fleet_infra::net::default_http_client(Some(net)).context("Failed to build HTTP client")?;
+    let engine = fleet_pipeline::default_engine(client);
+    let info = engine.repo_size(&repo_url, include_optional).await?;
+
+    let scope = if include_optional {
+        "required + optional"
+    } else {
+        "required"
+    };
+    println!("   Name:  {}", info.repo_name);
+    println!("   Mods:  {} ({scope})", info.mods);
+    println!("   Files: {}", info.files);
+    println!("   Size:  {}", format_size(info.total_bytes, DECIMAL));
+
+    Ok(info)
+}
+
+/// Mods that differ between the current baseline and the one `repair` writes.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BaselineChanges {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<String>,
+    pub unchanged: usize,
+}
+
+impl BaselineChanges {
+    fn between(old: &fleet_core::Manifest, new: &fleet_core::Manifest) -> Self {
+        let mut changes = Self::default();
+        for m in &new.mods {
+            match old.mods.iter().find(|o| o.name == m.name) {
+                None => changes.added.push(m.name.clone()),
+                Some(o) if o.checksum != m.checksum || o.files != m.files => {
+                    changes.changed.push(m.name.clone())
+                }
+                Some(_) => changes.unchanged += 1,
+            }
+        }
+        changes.removed = old
+            .mods
+            .iter()
+            .filter(|o| !new.mods.iter().any(|m| m.name == o.name))
+            .map(|o| o.name.clone())
+            .collect();
+        changes
+    }
+}
+
+fn file_count(manifest: &fleet_core::Manifest, name: &str) -> usize {
+    manifest
+        .mods
+        .iter()
+        .find(|m| m.name == name)
+        .map_or(0, |m| m.files.len())
+}
+
+pub async fn cmd_repair(
+    repos: Vec<String>,
+    local_path: Utf8PathBuf,
+    net: &NetConfig,
+    scan_threads: Option<usize>,
+    dry_run: bool,
+) -> anyhow::Result<BaselineChanges> {
     println!(":: Repairing local state...");
-    println!("   Repo:  {}", repo);
+    println!("   Repo:  {}", repos.join(", "));
     println!("   Local: {}", local_path);
 
-    let client = fleet_infra::net::default_http_client().context("Failed to build HTTP client")?;
+    let client =
+        fleet_infra::net::default_http_client(Some(net)).context("Failed to build HTTP client")?;
     let engine = fleet_pipeline::default_engine(client);
 
+    let (repo_url, extra_repo_urls) = split_repos(repos)?;
     let req = SyncRequest {
-        repo_url: repo,
+        repo_url,
+        extra_repo_urls,
         local_root: local_path,
         mode: SyncMode::SmartVerify,
-        options: SyncOptions::default(),
+        options: SyncOptions {
+            offline: net.offline,
+            scan_threads,
+            ..SyncOptions::default()
+        },
         profile_id: None,
     };
 
@@ -234,42 +873,426 @@ pub async fn cmd_repair(repo: String, local_path: Utf8PathBuf) -> anyhow::Result
         })
     };
 
-    let _ = engine.scan_local_state(&req, Some(cb)).await?;
+    let _ = engine.scan_local_state(&req, Some(cb), None).await?;
     pb.finish_with_message("Local scan complete.");
 
     println!(":: Fetching remote manifest...");
     let remote = engine.fetch_remote_state(&req).await?;
 
-    engine.persist_remote_snapshot(&req.local_root, &remote.manifest)?;
+    let old = match RedbFleetDataStore.load_baseline_manifest(&req.local_root) {
+        Ok(old) => old,
+        Err(e) if e.kind() == StorageErrorKind::Missing => {
+            println!("   No existing baseline.");
+            fleet_core::Manifest::new(Vec::new())
+        }
+        Err(e) => {
+            return Err(e).with_context(|| {
+                format!(
+                    "Existing baseline in {} is unreadable; move fleet.redb aside (if it is still there) and repair again",
+                    req.local_root
+                )
+            })
+        }
+    };
+    let new = &remote.manifest;
+    let changes = BaselineChanges::between(&old, new);
+
+    println!(":: Baseline changes");
+    for name in &changes.added {
+        println!("   + {} ({} files)", name, file_count(new, name));
+    }
+    for name in &changes.removed {
+        println!("   - {} ({} files)", name, file_count(&old, name));
+    }
+    for name in &changes.changed {
+        println!(
+            "   ~ {} ({} -> {} files)",
+            name,
+            file_count(&old, name),
+            file_count(new, name)
+        );
+    }
+    println!("   Unchanged: {} mod(s)", changes.unchanged);
+
+    if dry_run {
+        println!("   Dry run: baseline not written.");
+        return Ok(changes);
+    }
+
+    engine.persist_remote_snapshot(&req.local_root, new)?;
 
     println!(":: Repair complete.");
     println!("   Wrote `fleet.redb`");
 
-    Ok(())
+    Ok(changes)
+}
+
+/// What `import-swifty` found comparing an existing Swifty install with the repository.
+/// `@mod/path` lists are sorted.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SwiftyImport {
+    /// Files whose Fleet checksum matches the repository.
+    pub matched: usize,
+    /// Repository files absent locally.
+    pub missing: Vec<String>,
+    /// Local files whose size differs from the repository's: different content.
+    pub outdated: Vec<String>,
+    /// Local files of the right size whose checksum still differs; a sign Fleet hashes them
+    /// differently from Swifty.
+    pub hash_mismatches: Vec<String>,
+    /// Local files the repository does not list; the next sync removes them.
+    pub extra: usize,
+    pub baseline_written: bool,
+}
+
+impl SwiftyImport {
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty() && self.outdated.is_empty() && self.hash_mismatches.is_empty()
+    }
+}
+
+/// Adopts a folder downloaded by Swifty: hashes it once and, when every file matches the
+/// repository, writes the baseline like `repair` so the next check is immediately in sync.
+pub async fn cmd_import_swifty(
+    repos: Vec<String>,
+    local_path: Utf8PathBuf,
+    net: &NetConfig,
+    scan_threads: Option<usize>,
+) -> anyhow::Result<SwiftyImport> {
+    println!(":: Importing Swifty installation...");
+    println!("   Repo:  {}", repos.join(", "));
+    println!("   Local: {}", local_path);
+
+    let client =
+        fleet_infra::net::default_http_client(Some(net)).context("Failed to build HTTP client")?;
+    let engine = fleet_pipeline::default_engine(client);
+
+    let (repo_url, extra_repo_urls) = split_repos(repos)?;
+    let req = SyncRequest {
+        repo_url,
+        extra_repo_urls,
+        local_root: local_path,
+        // Hash everything: an earlier import's cache must not hide files fixed since.
+        mode: SyncMode::FullRehash,
+        options: SyncOptions {
+            scan_threads,
+            ..SyncOptions::default()
+        },
+        profile_id: None,
+    };
+
+    let pb = ProgressBar::new_spinner();
+    pb.set_style(
+        ProgressStyle::default_spinner()
+            .template("{spinner:.green} {msg}")
+            .unwrap(),
+    );
+    pb.enable_steady_tick(Duration::from_millis(100));
+
+    let cb = {
+        let pb = pb.clone();
+        Box::new(move |stats: ScanStats| {
+            pb.set_message(format!(
+                "Hashed {} files ({})",
+                stats.files_scanned,
+                format_size(stats.bytes_processed, DECIMAL)
+            ));
+        })
+    };
+
+    let local = engine.scan_local_state(&req, Some(cb), None).await?;
+    pb.finish_with_message("Local scan complete.");
+
+    println!(":: Fetching remote manifest...");
+    let remote = engine.fetch_remote_state(&req).await?;
+    let report = diff_report(&remote.manifest, &local.manifest);
+
+    let mut import = SwiftyImport::default();
+    for f in &report.files {
+        let path = format!("{}/{}", f.mod_name, f.path);
+        match f.status {
+            FileStatus::Unchanged => import.matched += 1,
+            FileStatus::Added => import.missing.push(path),
+            FileStatus::Removed => import.extra += 1,
+            FileStatus::Changed if f.old_size == f.new_size => import.hash_mismatches.push(path),
+            FileStatus::Changed | FileStatus::Renamed => import.outdated.push(path),
+        }
+    }
+    import.missing.sort();
+    import.outdated.sort();
+    import.hash_mismatches.sort();
+
+    println!(":: Import Result");
+    println!("   Matching Files:    {}", import.matched);
+    println!("   Missing Files:     {}", import.missing.len());
+    println!("   Outdated Files:    {}", import.outdated.len());
+    println!("   Extra Files:       {}", import.extra);
+    if !import.hash_mismatches.is_empty() {
+        println!(
+            "\n:: Checksum Mismatches ({}): same size as the repository, different checksum",
+            import.hash_mismatches.len()
+        );
+        for path in &import.hash_mismatches {
+            println!("   {path}");
+        }
+    }
+
+    if !import.is_complete() {
+        println!("   Baseline not written; run `sync` to download the differences.");
+        return Ok(import);
+    }
+
+    engine.persist_remote_snapshot(&req.local_root, &remote.manifest)?;
+    import.baseline_written = true;
+    println!(":: Import complete.");
+    println!("   Wrote `fleet.redb`");
+    if import.extra > 0 {
+        println!("   The next sync removes {} extra file(s).", import.extra);
+    }
+
+    Ok(import)
+}
+
+/// Runs the `doctor` self-test and prints one line per check.
+pub async fn cmd_doctor(
+    repo_url: Option<&str>,
+    local_path: Option<&Utf8Path>,
+    net: &NetConfig,
+) -> fleet_app_core::doctor::DoctorReport {
+    println!(":: Fleet doctor");
+    let report = fleet_app_core::doctor::run_doctor(repo_url, local_path, net).await;
+    for check in &report.checks {
+        println!("   [{}] {:<14} {}", check.status, check.name, check.detail);
+    }
+    if report.passed() {
+        println!("   All checks passed.");
+    }
+    report
+}
+
+pub fn cmd_db_clear_cache(local_path: Utf8PathBuf) -> anyhow::Result<u64> {
+    println!(":: Clearing scan cache...");
+    println!("   Local: {}", local_path);
+
+    let store = RedbFleetDataStore;
+    ensure_db_valid(&store, &local_path)?;
+
+    let removed = store
+        .clear_scan_cache(&local_path)
+        .context("Failed to clear scan cache")?;
+
+    println!("   Removed Entries: {}", removed);
+    println!("   Baseline manifest kept; next scan will rehash local files.");
+
+    Ok(removed)
+}
+
+/// Counts the corrupt databases quarantined in `local_path`, printing each one when `list` is
+/// set and deleting them when `clear` is. Returns the quarantined files found.
+pub fn cmd_db_quarantine(
+    local_path: Utf8PathBuf,
+    list: bool,
+    clear: bool,
+) -> anyhow::Result<Vec<Utf8PathBuf>> {
+    println!(":: Quarantined databases");
+    println!("   Local: {}", local_path);
+
+    let files = RedbFleetDataStore::list_quarantined(&local_path);
+    if files.is_empty() {
+        println!("   None found.");
+        return Ok(files);
+    }
+    println!("   Found: {}", files.len());
+    if list {
+        for file in &files {
+            let size = std::fs::metadata(file).map(|m| m.len()).unwrap_or(0);
+            println!("   {} ({})", file, HumanBytes(size));
+        }
+    }
+
+    if clear {
+        let removed = RedbFleetDataStore::clear_quarantined(&local_path)
+            .context("Failed to delete quarantined databases")?;
+        println!("   Deleted: {}", removed);
+    } else {
+        println!("   Run with --list to show them or --clear to delete them.");
+    }
+    Ok(files)
+}
+
+/// Lists legacy JSON artifacts under `local_path` and deletes them unless `dry_run`.
+/// Returns the number of files and bytes found.
+pub fn cmd_clean(local_path: Utf8PathBuf, dry_run: bool) -> anyhow::Result<(usize, u64)> {
+    println!(":: Legacy artifacts");
+    println!("   Local: {}", local_path);
+
+    let files = RedbFleetDataStore::list_legacy_artifacts(&local_path)
+        .context("Failed to scan for legacy artifacts")?;
+    if files.is_empty() {
+        println!("   None found.");
+        return Ok((0, 0));
+    }
+
+    if dry_run {
+        let mut bytes = 0;
+        for file in &files {
+            let size = std::fs::metadata(file).map(|m| m.len()).unwrap_or(0);
+            bytes += size;
+            println!("   {} ({})", file, HumanBytes(size));
+        }
+        println!(
+            "   Would remove {} file(s), {}.",
+            files.len(),
+            HumanBytes(bytes)
+        );
+        println!("   Run without --dry-run to delete them.");
+        return Ok((files.len(), bytes));
+    }
+
+    let (removed, bytes) = RedbFleetDataStore::remove_legacy_artifacts(&local_path)
+        .context("Failed to remove legacy artifacts")?;
+    println!(
+        "   Removed {} file(s), reclaimed {}.",
+        removed,
+        HumanBytes(bytes)
+    );
+    Ok((removed, bytes))
+}
+
+/// Prints the baseline manifest recorded by the last sync, optionally limited to one mod.
+/// Returns the mods that were shown.
+pub fn cmd_db_show(
+    local_path: Utf8PathBuf,
+    mod_filter: Option<String>,
+    files: bool,
+) -> anyhow::Result<Vec<fleet_core::Mod>> {
+    println!(":: Baseline manifest");
+    println!("   Local: {}", local_path);
+
+    let store = RedbFleetDataStore;
+    ensure_db_valid(&store, &local_path)?;
+
+    let manifest = store
+        .load_baseline_manifest(&local_path)
+        .context("Failed to load baseline manifest")?;
+    let mods: Vec<fleet_core::Mod> = manifest
+        .mods
+        .into_iter()
+        .filter(|m| {
+            mod_filter
+                .as_deref()
+                .is_none_or(|name| m.name.eq_ignore_ascii_case(name))
+        })
+        .collect();
+    if let Some(name) = &mod_filter {
+        if mods.is_empty() {
+            anyhow::bail!("Mod {name} is not in the baseline manifest");
+        }
+    }
+
+    for m in &mods {
+        let total: u64 = m.files.iter().map(|f| f.length).sum();
+        println!(
+            "\n   {}  {}  {} files, {}",
+            m.name,
+            m.checksum,
+            m.files.len(),
+            format_size(total, DECIMAL)
+        );
+        if files {
+            for f in &m.files {
+                println!("     {}  {:>10}  {}", f.checksum, f.length, f.path);
+            }
+        }
+    }
+    println!("\n   Mods: {}", mods.len());
+
+    Ok(mods)
 }
 
+/// Fails unless `fleet.redb` exists and can be opened by this version.
+fn ensure_db_valid(store: &RedbFleetDataStore, local_path: &Utf8PathBuf) -> anyhow::Result<()> {
+    match store.validate(local_path)? {
+        DbState::Valid => Ok(()),
+        DbState::Missing | DbState::Corrupt => {
+            anyhow::bail!("Unknown local state: missing `fleet.redb` (run `repair` first)")
+        }
+        DbState::Busy => anyhow::bail!(
+            "Local database is busy (another Fleet instance may be running). Close it and try again."
+        ),
+        DbState::NewerSchema { found, supported } => anyhow::bail!(
+            "Local database is from a newer Fleet (schema_version={found}, supported={supported}). Update Fleet and try again."
+        ),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub async fn cmd_sync(
-    repo: String,
+    repos: Vec<String>,
     path: Utf8PathBuf,
     mode: CliSyncMode,
     threads: usize,
+    max_per_host: Option<usize>,
+    order: CliDownloadOrder,
+    segmented: Option<fleet_infra::SegmentedDownloads>,
     limit_mb: Option<u64>,
     cache_dir: Option<Utf8PathBuf>,
+    temp_dir: Option<Utf8PathBuf>,
+    local_source: Option<Utf8PathBuf>,
+    skip_mods: Vec<String>,
+    exclude: Vec<String>,
+    no_delete: bool,
+    verify: bool,
+    no_verify: bool,
+    force: bool,
+    skip_unreachable: bool,
+    max_host_failures: usize,
+    pin: Option<String>,
+    net: &NetConfig,
+    plan_file: Option<Utf8PathBuf>,
 ) -> anyhow::Result<fleet_pipeline::SyncResult> {
     println!(":: Synchronizing...");
     println!("   Target: {}", path);
 
-    let client = fleet_infra::net::default_http_client().context("Failed to build HTTP client")?;
+    let client =
+        fleet_infra::net::default_http_client(Some(net)).context("Failed to build HTTP client")?;
     let engine = fleet_pipeline::default_engine(client);
 
     let options = SyncOptions {
         max_threads: threads.clamp(1, 32),
+        max_connections_per_host: max_per_host,
+        download_order: order.into(),
+        segmented_downloads: segmented,
         rate_limit_bytes: limit_mb.map(|mb| mb * 1024 * 1024),
+        rate_limit: None,
         cache_root: cache_dir,
+        skip_deletes: no_delete,
+        verify_checks: verify,
+        verify_downloads: !no_verify,
+        verify_baseline: true,
+        delete_threshold: if force {
+            None
+        } else {
+            Some(DeleteThreshold::default())
+        },
+        offline: net.offline,
+        scan_threads: None,
+        sequential_scan: false,
+        temp_dir,
+        local_source,
+        preserve_mtimes: true,
+        stall_timeout: Some(fleet_infra::net::DEFAULT_STALL_TIMEOUT),
+        skip_mods,
+        exclude,
+        skip_unreachable_mods: skip_unreachable,
+        max_host_failures: (max_host_failures > 0).then_some(max_host_failures),
+        pinned_repo_checksum: pin,
     };
 
+    let (repo_url, extra_repo_urls) = split_repos(repos)?;
     let req = SyncRequest {
-        repo_url: repo,
+        repo_url,
+        extra_repo_urls,
         local_root: path,
         mode: mode.into(),
         options,
@@ -277,7 +1300,19 @@ pub async fn cmd_sync(
     };
 
     let (tx, mut rx) = tokio::sync::mpsc::channel(100);
-    let engine_handle = tokio::spawn(async move { engine.plan_and_execute(&req, Some(tx)).await });
+    let saved_plan = match &plan_file {
+        Some(plan_path) => {
+            println!("   Plan:   {}", plan_path);
+            Some(engine.load_plan(&req, plan_path)?)
+        }
+        None => None,
+    };
+    let engine_handle = tokio::spawn(async move {
+        match saved_plan {
+            Some(saved) => engine.execute_saved_plan(&req, saved, Some(tx)).await,
+            None => engine.plan_and_execute(&req, Some(tx)).await,
+        }
+    });
 
     let m = MultiProgress::new();
     let sty_main = ProgressStyle::with_template(
@@ -294,31 +1329,91 @@ pub async fn cmd_sync(
     let mut downloaded_bytes = 0u64;
     let mut files_done = 0u64;
     let mut files_total = 0u64;
+    let mut names: HashMap<u64, String> = HashMap::new();
 
     while let Some(ev) = rx.recv().await {
         use fleet_infra::net::DownloadEvent;
         match ev {
             DownloadEvent::Started {
-                total_bytes: size, ..
+                id,
+                mod_name,
+                rel_path,
+                total_bytes: size,
+                ..
             } => {
+                names.insert(id, format!("{mod_name}/{rel_path}"));
                 total_bytes = total_bytes.saturating_add(size);
                 files_total = files_total.saturating_add(1);
                 pb_main.set_length(total_bytes);
-                pb_main.set_message(format!("Downloading {} files", files_total));
+                pb_main.set_message(format!(
+                    "Downloading {} files: {}/{}",
+                    files_total, mod_name, rel_path
+                ));
             }
             DownloadEvent::Progress { bytes_delta, .. } => {
                 downloaded_bytes = downloaded_bytes.saturating_add(bytes_delta);
                 pb_main.set_position(downloaded_bytes);
             }
-            DownloadEvent::Completed { .. } => {
+            DownloadEvent::Discarded { bytes, .. } => {
+                downloaded_bytes = downloaded_bytes.saturating_sub(bytes);
+                pb_main.set_position(downloaded_bytes);
+            }
+            DownloadEvent::Verifying { id } => {
+                if let Some(name) = names.get(&id) {
+                    pb_main.set_message(format!("Verifying {name}…"));
+                }
+            }
+            DownloadEvent::Completed { id, .. } => {
+                names.remove(&id);
                 files_done = files_done.saturating_add(1);
                 pb_main.set_message(format!("Downloading {}/{} files", files_done, files_total));
             }
         }
     }
 
-    let result = engine_handle.await??;
+    let result = match engine_handle.await? {
+        Ok(result) => result,
+        Err(SyncError::Downloads(failed)) => {
+            pb_main.abandon_with_message("Sync Failed");
+            println!(":: Failed Downloads");
+            for f in &failed {
+                println!("   {f}");
+            }
+            anyhow::bail!("{} file(s) failed to download", failed.len());
+        }
+        Err(SyncError::Verification(failed)) => {
+            pb_main.abandon_with_message("Verification Failed");
+            println!(":: Failed Verification");
+            for f in &failed {
+                println!("   {f}");
+            }
+            anyhow::bail!("{} file(s) failed verification", failed.len());
+        }
+        Err(SyncError::Destructive { deleted, existing }) => {
+            pb_main.abandon_with_message("Sync Refused");
+            anyhow::bail!(
+                "Plan would delete {deleted} of {existing} local files. \
+                 Check the repository URL and path, then re-run with --force to apply it"
+            );
+        }
+        Err(e) => return Err(e.into()),
+    };
     pb_main.finish_with_message("Sync Complete");
+    if !result.stats.changes.is_empty() {
+        println!("   Changes:           {}", result.stats.changes.summary());
+    }
+    if result.stats.files_verified > 0 {
+        println!("   Verified Files:    {}", result.stats.files_verified);
+    }
+    if result.stats.files_from_local_source > 0 {
+        println!(
+            "   Copied Locally:    {}",
+            result.stats.files_from_local_source
+        );
+    }
+    for warning in &result.warnings {
+        println!("   Warning: {warning}");
+    }
 
     Ok(result)
 }
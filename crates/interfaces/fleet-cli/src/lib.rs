@@ -1,8 +1,73 @@
 pub mod commands;
 pub mod profiles;
 
-use clap::ValueEnum;
+use clap::{Args, ValueEnum};
+use fleet_app_core::domain::Profile;
+use fleet_infra::NetConfig;
 use fleet_pipeline::sync::SyncMode;
+use std::time::Duration;
+
+/// Network flags shared by the commands that talk to a repository.
+#[derive(Args, Clone, Debug, Default)]
+pub struct NetArgs {
+    #[arg(long, env = "FLEET_PROXY", help = "Proxy URL for repository requests")]
+    pub proxy: Option<String>,
+    #[arg(
+        long,
+        env = "FLEET_TOKEN",
+        hide_env_values = true,
+        help = "Bearer token sent as Authorization header"
+    )]
+    pub token: Option<String>,
+    #[arg(
+        long,
+        env = "FLEET_USER_AGENT",
+        help = "User-Agent sent instead of fleet/<version>; also omits the X-Fleet-Version header"
+    )]
+    pub user_agent: Option<String>,
+    #[arg(long, value_name = "SECS", help = "Connection timeout in seconds")]
+    pub connect_timeout: Option<u64>,
+    #[arg(
+        long,
+        value_name = "SECS",
+        help = "Abort a request after this many seconds without data"
+    )]
+    pub read_timeout: Option<u64>,
+    #[arg(
+        long,
+        help = "Never contact the repository; use the cached manifest from the last sync"
+    )]
+    pub offline: bool,
+}
+
+impl NetArgs {
+    pub fn to_net_config(&self) -> NetConfig {
+        let config = NetConfig {
+            proxy: self.proxy.clone(),
+            user_agent: self.user_agent.clone(),
+            connect_timeout: self.connect_timeout.map(Duration::from_secs),
+            read_timeout: self.read_timeout.map(Duration::from_secs),
+            offline: self.offline,
+            ..Default::default()
+        };
+        match self.token.as_deref() {
+            Some(token) if !token.trim().is_empty() => config.with_bearer_token(token.trim()),
+            _ => config,
+        }
+    }
+
+    /// [`Self::to_net_config`], adding `profile`'s keyring credentials unless `--token` is given.
+    pub fn to_net_config_for(&self, profile: &Profile) -> anyhow::Result<NetConfig> {
+        let config = self.to_net_config();
+        if self.token.as_deref().is_some_and(|t| !t.trim().is_empty()) {
+            return Ok(config);
+        }
+        Ok(match profile.load_repo_auth()? {
+            Some(auth) => config.with_repo_auth(&auth),
+            None => config,
+        })
+    }
+}
 
 #[derive(ValueEnum, Clone, Debug, Copy)]
 pub enum CliScanStrategy {
@@ -10,6 +75,41 @@ pub enum CliScanStrategy {
     Force,
 }
 
+/// What `scan` writes.
+#[derive(ValueEnum, Clone, Debug, Copy, Default, PartialEq, Eq)]
+pub enum CliScanOutputFormat {
+    /// One combined manifest JSON.
+    #[default]
+    Manifest,
+    /// A `<mod>.srf` per mod plus a `repo.json`.
+    Srf,
+}
+
+#[derive(ValueEnum, Clone, Debug, Copy, Default, PartialEq, Eq)]
+pub enum CliOutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+#[derive(ValueEnum, Clone, Debug, Copy, Default)]
+pub enum CliDownloadOrder {
+    #[default]
+    Planned,
+    Smallest,
+    Largest,
+}
+
+impl From<CliDownloadOrder> for fleet_pipeline::DownloadOrder {
+    fn from(o: CliDownloadOrder) -> Self {
+        match o {
+            CliDownloadOrder::Planned => Self::AsPlanned,
+            CliDownloadOrder::Smallest => Self::SmallestFirst,
+            CliDownloadOrder::Largest => Self::LargestFirst,
+        }
+    }
+}
+
 #[derive(ValueEnum, Clone, Debug, Copy)]
 pub enum CliSyncMode {
     CacheOnly,
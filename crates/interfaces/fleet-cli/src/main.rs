@@ -1,11 +1,19 @@
 use camino::Utf8PathBuf;
 use clap::{Parser, Subcommand};
 use fleet_app_core::domain::{FLATPAK_STEAM_LAUNCH_TEMPLATE, STEAM_LAUNCH_TEMPLATE};
-use fleet_cli::{commands, profiles, CliScanStrategy, CliSyncMode};
+use fleet_cli::{
+    commands, profiles, CliDownloadOrder, CliOutputFormat, CliScanOutputFormat, CliScanStrategy,
+    CliSyncMode, NetArgs,
+};
 use fleet_infra::launcher::Launcher;
+use fleet_infra::NetConfig;
 use tracing::Level;
+use tracing_subscriber::fmt::format::FmtSpan;
 use tracing_subscriber::FmtSubscriber;
 
+/// `fleet audit` exit status when the folder differs from the repository; errors exit with 1.
+const AUDIT_DRIFT_EXIT_CODE: i32 = 2;
+
 #[derive(Parser)]
 #[command(author, version, about)]
 struct Cli {
@@ -24,19 +32,105 @@ enum Commands {
     },
     Scan {
         path: Utf8PathBuf,
-        #[arg(short, long)]
+        #[arg(short, long, conflicts_with = "output_dir")]
         output: Option<Utf8PathBuf>,
+        #[arg(long, value_enum, default_value_t = CliScanOutputFormat::Manifest)]
+        output_format: CliScanOutputFormat,
+        #[arg(
+            long,
+            value_name = "DIR",
+            required_if_eq("output_format", "srf"),
+            help = "Directory for the .srf files and repo.json (with --output-format srf)"
+        )]
+        output_dir: Option<Utf8PathBuf>,
         #[arg(long, value_enum, default_value_t = CliScanStrategy::Smart)]
         strategy: CliScanStrategy,
+        #[arg(
+            long,
+            default_value_t = fleet_infra::DEFAULT_CHUNK_SIZE,
+            help = "Part size in bytes for non-PBO files; changes checksums, only set to match another repo"
+        )]
+        chunk_size: u64,
+        #[arg(
+            long,
+            value_name = "N",
+            help = "Hashing threads (default: all cores but one)"
+        )]
+        scan_threads: Option<usize>,
     },
     #[command(name = "check-for-updates", alias = "check")]
     CheckForUpdates {
+        #[arg(
+            long,
+            required_unless_present = "profile",
+            help = "Repository URL; repeat to merge several repositories"
+        )]
+        repo: Vec<String>,
         #[arg(long, required_unless_present = "profile")]
-        repo: Option<String>,
+        path: Option<Utf8PathBuf>,
+        #[arg(short, long, help = "Use settings from a named profile")]
+        profile: Option<String>,
+        #[command(flatten)]
+        net: NetArgs,
+        #[arg(long, value_name = "FILE", help = "Write the computed plan as JSON")]
+        save_plan: Option<Utf8PathBuf>,
+        #[arg(
+            long,
+            conflicts_with = "save_plan",
+            help = "Only compare repo.json's mod checksums with the last sync; no disk scan"
+        )]
+        quick: bool,
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = CliOutputFormat::Text,
+            help = "json prints the plan and a file-by-file comparison"
+        )]
+        format: CliOutputFormat,
+    },
+    /// Hash every local file and compare with the repository without writing anything; exits
+    /// with status 2 when the folder differs
+    Audit {
+        #[arg(
+            long,
+            required_unless_present = "profile",
+            help = "Repository URL; repeat to merge several repositories"
+        )]
+        repo: Vec<String>,
+        #[arg(long, required_unless_present = "profile")]
+        path: Option<Utf8PathBuf>,
+        #[arg(short, long, help = "Use settings from a named profile")]
+        profile: Option<String>,
+        #[command(flatten)]
+        net: NetArgs,
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = CliOutputFormat::Text,
+            help = "json prints the plan and a file-by-file comparison"
+        )]
+        format: CliOutputFormat,
+    },
+    /// Check every saved profile for updates and list which ones need a sync
+    #[command(name = "check-all")]
+    CheckAll {
+        #[command(flatten)]
+        net: NetArgs,
+    },
+    /// List every mod and file of the last sync, with what a saved plan would do to each
+    Tree {
         #[arg(long, required_unless_present = "profile")]
         path: Option<Utf8PathBuf>,
         #[arg(short, long, help = "Use settings from a named profile")]
         profile: Option<String>,
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "Mark the files a plan saved by `check-for-updates --save-plan` changes"
+        )]
+        plan: Option<Utf8PathBuf>,
+        #[arg(long, value_enum, default_value_t = CliOutputFormat::Text)]
+        format: CliOutputFormat,
     },
     #[command(name = "local-check")]
     LocalCheck {
@@ -45,17 +139,128 @@ enum Commands {
         #[arg(short, long, help = "Use settings from a named profile")]
         profile: Option<String>,
     },
+    /// Compare two local mods folders file by file, without a repository
+    #[command(name = "local-diff")]
+    LocalDiff {
+        dir_a: Utf8PathBuf,
+        dir_b: Utf8PathBuf,
+        #[arg(long, value_enum, default_value_t = CliScanStrategy::Smart)]
+        strategy: CliScanStrategy,
+        #[arg(long, value_enum, default_value_t = CliOutputFormat::Text)]
+        format: CliOutputFormat,
+    },
+    /// Write repo.json and a mod.srf per mod so a mods folder can be served as a repository
+    Publish {
+        #[arg(long)]
+        path: Utf8PathBuf,
+        #[arg(long, help = "Repository name written to repo.json")]
+        name: String,
+        #[arg(
+            long,
+            value_name = "N",
+            help = "Hashing threads (default: all cores but one)"
+        )]
+        scan_threads: Option<usize>,
+        #[arg(
+            long,
+            value_name = "URL",
+            help = "Also sync the published files to this WebDAV/S3-compatible base URL"
+        )]
+        upload: Option<String>,
+        #[arg(
+            long,
+            requires = "upload",
+            help = "Upload every file, not just what differs from the remote"
+        )]
+        full_upload: bool,
+        #[arg(long, requires = "upload", help = "Basic auth user for the upload")]
+        upload_user: Option<String>,
+        #[arg(
+            long,
+            env = "FLEET_UPLOAD_PASSWORD",
+            hide_env_values = true,
+            help = "Basic auth password for the upload"
+        )]
+        upload_password: Option<String>,
+        #[arg(long, requires = "upload", help = "Upload speed limit in MB/s")]
+        limit_mb: Option<u64>,
+        #[command(flatten)]
+        net: NetArgs,
+    },
+    /// Show how large a repository is before adding it as a profile
+    #[command(name = "repo-info")]
+    RepoInfo {
+        url: String,
+        #[arg(long, help = "Include optional mods in the totals")]
+        optional: bool,
+        #[command(flatten)]
+        net: NetArgs,
+    },
+    /// Check a local mods folder for problems servers would reject
+    Verify {
+        #[arg(long, required_unless_present = "profile")]
+        path: Option<Utf8PathBuf>,
+        #[arg(short, long, help = "Use settings from a named profile")]
+        profile: Option<String>,
+        #[arg(long, help = "Report PBOs without a .bisign")]
+        signatures: bool,
+        #[arg(
+            long,
+            requires = "signatures",
+            help = "Also require each .bisign to match a .bikey shipped with the mod"
+        )]
+        keys: bool,
+    },
     Repair {
+        #[arg(
+            long,
+            required_unless_present = "profile",
+            help = "Repository URL; repeat to merge several repositories"
+        )]
+        repo: Vec<String>,
         #[arg(long, required_unless_present = "profile")]
-        repo: Option<String>,
+        path: Option<Utf8PathBuf>,
+        #[arg(short, long, help = "Use settings from a named profile")]
+        profile: Option<String>,
+        #[command(flatten)]
+        net: NetArgs,
+        #[arg(
+            long,
+            value_name = "N",
+            help = "Hashing threads (default: all cores but one)"
+        )]
+        scan_threads: Option<usize>,
+        #[arg(long, help = "Show how the baseline would change without writing it")]
+        dry_run: bool,
+    },
+    /// Adopt a mods folder downloaded by Swifty without downloading it again
+    ImportSwifty {
+        #[arg(
+            long,
+            required_unless_present = "profile",
+            help = "Repository URL; repeat to merge several repositories"
+        )]
+        repo: Vec<String>,
         #[arg(long, required_unless_present = "profile")]
         path: Option<Utf8PathBuf>,
         #[arg(short, long, help = "Use settings from a named profile")]
         profile: Option<String>,
+        #[command(flatten)]
+        net: NetArgs,
+        #[arg(
+            long,
+            value_name = "N",
+            help = "Hashing threads (default: all cores but one)"
+        )]
+        scan_threads: Option<usize>,
     },
     Sync {
-        #[arg(long, required_unless_present = "profile")]
-        repo: Option<String>,
+        #[arg(
+            long,
+            required_unless_present = "profile",
+            help = "Repository URL; repeat to merge several repositories"
+        )]
+        repo: Vec<String>,
         #[arg(long, required_unless_present = "profile")]
         path: Option<Utf8PathBuf>,
         #[arg(short, long, help = "Use settings from a named profile")]
@@ -64,10 +269,125 @@ enum Commands {
         mode: CliSyncMode,
         #[arg(short, long, default_value_t = 4)]
         threads: usize,
+        #[arg(
+            long,
+            value_name = "N",
+            help = "Open at most this many downloads to one host (for hosts that answer 429/503)"
+        )]
+        max_per_host: Option<usize>,
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = CliDownloadOrder::Planned,
+            help = "Download small files first (quick file count) or large ones first"
+        )]
+        order: CliDownloadOrder,
+        #[arg(
+            long,
+            value_name = "N",
+            help = "Fetch large files as N parallel range requests when the server supports it"
+        )]
+        segments: Option<usize>,
+        #[arg(
+            long,
+            value_name = "MB",
+            default_value_t = fleet_infra::net::DEFAULT_SEGMENT_MIN_SIZE / (1024 * 1024),
+            help = "Smallest file split by --segments"
+        )]
+        segment_min_mb: u64,
         #[arg(long)]
         limit_mb: Option<u64>,
         #[arg(long)]
         cache_dir: Option<Utf8PathBuf>,
+        #[arg(
+            long,
+            value_name = "DIR",
+            help = "Stage downloads here, then move them into the mods folder"
+        )]
+        temp_dir: Option<Utf8PathBuf>,
+        #[arg(
+            long,
+            value_name = "DIR",
+            help = "Copy files with the expected checksum from this install instead of downloading"
+        )]
+        local_source: Option<Utf8PathBuf>,
+        #[arg(
+            long,
+            value_name = "GLOB",
+            help = "Never delete or overwrite files matching this `@mod/path` glob; repeatable"
+        )]
+        exclude: Vec<String>,
+        #[arg(
+            long,
+            help = "Restore missing/changed files only; keep extra local files"
+        )]
+        no_delete: bool,
+        #[arg(
+            long,
+            help = "Re-hash unchanged files after downloading and fail if any differ"
+        )]
+        verify: bool,
+        #[arg(
+            long,
+            help = "Skip checksumming downloads and trust their size (trusted LAN mirrors only)"
+        )]
+        no_verify: bool,
+        #[arg(
+            long,
+            help = "Apply the plan even if it would delete most of the local files"
+        )]
+        force: bool,
+        #[arg(
+            long,
+            help = "Leave mods whose mod.srf fails to load as they are instead of failing"
+        )]
+        skip_unreachable: bool,
+        #[arg(
+            long,
+            value_name = "N",
+            default_value_t = fleet_infra::net::DEFAULT_MAX_HOST_FAILURES,
+            help = "Give up on a repository host after this many consecutive failures (0 = never)"
+        )]
+        max_host_failures: usize,
+        #[arg(
+            long,
+            value_name = "CHECKSUM",
+            help = "Refuse to sync unless repo.json still has this checksum (defaults to the profile's pin)"
+        )]
+        pin: Option<String>,
+        #[command(flatten)]
+        net: NetArgs,
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "Apply a plan saved by `check-for-updates --save-plan` instead of recomputing"
+        )]
+        plan: Option<Utf8PathBuf>,
+    },
+    /// Check that the network, the mods folder, the database and hashing work
+    Doctor {
+        #[arg(long, help = "Repository URL to check for reachability")]
+        repo: Option<String>,
+        #[arg(long, help = "Mods folder to check")]
+        path: Option<Utf8PathBuf>,
+        #[arg(short, long, help = "Use settings from a named profile")]
+        profile: Option<String>,
+        #[command(flatten)]
+        net: NetArgs,
+    },
+    /// Remove legacy JSON caches left by older versions (`.fleet-local-*.json`, `@mod/.fleet-cache.json`)
+    Clean {
+        #[arg(long, required_unless_present = "profile")]
+        path: Option<Utf8PathBuf>,
+        #[arg(short, long, help = "Use settings from a named profile")]
+        profile: Option<String>,
+        #[arg(long, help = "List the files without deleting them")]
+        dry_run: bool,
+    },
+    /// Maintain the local `fleet.redb` database
+    Db {
+        #[command(subcommand)]
+        command: DbCommands,
     },
     Launch {
         #[arg(
@@ -89,6 +409,21 @@ enum Commands {
         flatpak: bool,
         #[arg(long, help = "Custom launch template (overrides default/--flatpak)")]
         template: Option<String>,
+        #[arg(
+            long = "env",
+            value_name = "KEY=VAL",
+            value_parser = parse_env_var,
+            help = "Set an environment variable for the game process (repeatable)"
+        )]
+        env: Vec<(String, String)>,
+        #[arg(long, help = "Run the game from this directory")]
+        working_dir: Option<std::path::PathBuf>,
+        #[arg(
+            long,
+            value_delimiter = ',',
+            help = "Load these mods first, in this order (overrides the profile's order)"
+        )]
+        order: Option<Vec<String>>,
     },
 }
 
@@ -101,10 +436,73 @@ enum ProfileCommands {
         name: String,
         repo: String,
         path: Utf8PathBuf,
+        #[arg(
+            long = "extra-repo",
+            help = "Additional repository merged into this profile"
+        )]
+        extra_repos: Vec<String>,
     },
     Remove {
         name: String,
     },
+    /// Save credentials for a private repository in the OS keyring
+    Login {
+        name: String,
+        #[arg(long, help = "Username for basic auth; omit to store a bearer token")]
+        username: Option<String>,
+        #[arg(
+            long,
+            env = "FLEET_REPO_SECRET",
+            hide_env_values = true,
+            help = "Password or token (read from stdin when omitted)"
+        )]
+        secret: Option<String>,
+    },
+    /// Forget a profile's repository credentials
+    Logout {
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum DbCommands {
+    /// Drop all scan cache entries while keeping the baseline manifest
+    #[command(name = "clear-cache")]
+    ClearCache {
+        #[arg(long, required_unless_present = "profile")]
+        path: Option<Utf8PathBuf>,
+        #[arg(short, long, help = "Use settings from a named profile")]
+        profile: Option<String>,
+    },
+    /// List or delete corrupt databases that were moved aside (`fleet.redb.corrupt.*`)
+    Quarantine {
+        #[arg(long, required_unless_present = "profile")]
+        path: Option<Utf8PathBuf>,
+        #[arg(short, long, help = "Use settings from a named profile")]
+        profile: Option<String>,
+        #[arg(long, help = "Print each quarantined file and its size")]
+        list: bool,
+        #[arg(long, help = "Delete all quarantined files")]
+        clear: bool,
+    },
+    /// Print the baseline manifest recorded by the last sync
+    Show {
+        #[arg(long, required_unless_present = "profile")]
+        path: Option<Utf8PathBuf>,
+        #[arg(short, long, help = "Use settings from a named profile")]
+        profile: Option<String>,
+        #[arg(long = "mod", value_name = "NAME", help = "Only show this mod")]
+        mod_name: Option<String>,
+        #[arg(long, help = "List every file with its checksum and size")]
+        files: bool,
+    },
+}
+
+fn parse_env_var(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("expected KEY=VAL, got `{s}`")),
+    }
 }
 
 #[tokio::main]
@@ -115,13 +513,30 @@ async fn main() -> anyhow::Result<()> {
     } else {
         Level::WARN
     };
-    let subscriber = FmtSubscriber::builder().with_max_level(level).finish();
+    // Verbose runs also log each sync phase span as it closes, with its timings.
+    let span_events = if cli.verbose {
+        FmtSpan::CLOSE
+    } else {
+        FmtSpan::NONE
+    };
+    let subscriber = FmtSubscriber::builder()
+        .with_max_level(level)
+        .with_span_events(span_events)
+        .finish();
     tracing::subscriber::set_global_default(subscriber).expect("default subscriber");
 
-    let resolve_profile = |name: &str| -> anyhow::Result<(String, Utf8PathBuf)> {
+    let resolve_profile = |name: &str| -> anyhow::Result<(Vec<String>, Utf8PathBuf)> {
         let mgr = profiles::ProfileManager::new();
         let p = mgr.find(name)?;
-        Ok((p.repo_url, Utf8PathBuf::from(p.local_path)))
+        let mut repos = vec![p.repo_url.clone()];
+        repos.extend(p.extra_repo_urls());
+        Ok((repos, Utf8PathBuf::from(p.local_path)))
+    };
+    let net_config = |net: &NetArgs, profile: Option<&str>| -> anyhow::Result<NetConfig> {
+        match profile {
+            Some(name) => net.to_net_config_for(&profiles::ProfileManager::new().find(name)?),
+            None => Ok(net.to_net_config()),
+        }
     };
 
     match cli.command {
@@ -132,25 +547,94 @@ async fn main() -> anyhow::Result<()> {
                 name,
                 repo,
                 path,
-            } => profiles::handle_add(id, name, repo, path)?,
+                extra_repos,
+            } => profiles::handle_add(id, name, repo, path, extra_repos)?,
             ProfileCommands::Remove { name } => profiles::handle_remove(name)?,
+            ProfileCommands::Login {
+                name,
+                username,
+                secret,
+            } => profiles::handle_login(name, username, secret)?,
+            ProfileCommands::Logout { name } => profiles::handle_logout(name)?,
         },
         Commands::Scan {
             path,
             output,
+            output_format,
+            output_dir,
             strategy,
-        } => commands::cmd_scan(path, output, strategy).await?,
+            chunk_size,
+            scan_threads,
+        } => {
+            let output = match (output_format, output_dir) {
+                (CliScanOutputFormat::Srf, Some(dir)) => commands::ScanOutput::Srf(dir),
+                _ => commands::ScanOutput::Manifest(output),
+            };
+            commands::cmd_scan(path, output, strategy, chunk_size, scan_threads).await?
+        }
         Commands::CheckForUpdates {
             repo,
             path,
             profile,
+            net,
+            save_plan,
+            quick,
+            format,
         } => {
+            let net = net_config(&net, profile.as_deref())?;
             let (final_repo, final_path) = if let Some(p_name) = profile {
                 resolve_profile(&p_name)?
             } else {
-                (repo.unwrap(), path.unwrap())
+                (repo, path.unwrap())
+            };
+            if quick {
+                commands::cmd_quick_check(final_repo, final_path, &net, format).await?;
+            } else {
+                commands::cmd_check_for_updates(final_repo, final_path, &net, save_plan, format)
+                    .await?;
+            }
+        }
+        Commands::Audit {
+            repo,
+            path,
+            profile,
+            net,
+            format,
+        } => {
+            let net = net_config(&net, profile.as_deref())?;
+            let (final_repo, final_path) = if let Some(p_name) = profile {
+                resolve_profile(&p_name)?
+            } else {
+                (repo, path.unwrap())
+            };
+            let plan = commands::cmd_audit(final_repo, final_path, &net, format).await?;
+            let drift = plan.downloads.len() + plan.deletes.len() + plan.renames.len();
+            if drift > 0 {
+                eprintln!("{drift} difference(s) from the repository");
+                std::process::exit(AUDIT_DRIFT_EXIT_CODE);
+            }
+        }
+        Commands::CheckAll { net } => {
+            let profiles = profiles::ProfileManager::new().list()?;
+            if profiles.is_empty() {
+                println!("No profiles saved; add one with `fleet profile add`.");
+            } else {
+                commands::cmd_check_all(profiles, &net).await?;
+            }
+        }
+        Commands::Tree {
+            path,
+            profile,
+            plan,
+            format,
+        } => {
+            let final_path = if let Some(p_name) = profile {
+                let (_repo, path) = resolve_profile(&p_name)?;
+                path
+            } else {
+                path.unwrap()
             };
-            commands::cmd_check_for_updates(final_repo, final_path).await?;
+            commands::cmd_tree(final_path, plan, format)?;
         }
         Commands::LocalCheck { path, profile } => {
             let final_path = if let Some(p_name) = profile {
@@ -161,17 +645,95 @@ async fn main() -> anyhow::Result<()> {
             };
             commands::cmd_local_check(final_path).await?;
         }
+        Commands::LocalDiff {
+            dir_a,
+            dir_b,
+            strategy,
+            format,
+        } => {
+            commands::cmd_local_diff(dir_a, dir_b, strategy, format).await?;
+        }
+        Commands::Publish {
+            path,
+            name,
+            scan_threads,
+            upload,
+            full_upload,
+            upload_user,
+            upload_password,
+            limit_mb,
+            net,
+        } => {
+            let upload = upload.map(|url| {
+                let mut config = net.to_net_config();
+                if let Some(username) = upload_user {
+                    config = config.with_repo_auth(&fleet_infra::RepoAuth::Basic {
+                        username,
+                        password: upload_password.unwrap_or_default(),
+                    });
+                }
+                commands::UploadTarget {
+                    url,
+                    full: full_upload,
+                    rate_limit_bytes: limit_mb.map(|mb| mb * 1024 * 1024),
+                    net: config,
+                }
+            });
+            commands::cmd_publish(path, name, scan_threads, upload).await?;
+        }
+        Commands::RepoInfo { url, optional, net } => {
+            commands::cmd_repo_info(url, optional, &net.to_net_config()).await?;
+        }
+        Commands::Verify {
+            path,
+            profile,
+            signatures,
+            keys,
+        } => {
+            let final_path = if let Some(p_name) = profile {
+                let (_repo, path) = resolve_profile(&p_name)?;
+                path
+            } else {
+                path.unwrap()
+            };
+            if !signatures {
+                anyhow::bail!("Nothing to verify; pass --signatures");
+            }
+            let problems = commands::cmd_verify_signatures(final_path, keys)?;
+            if problems > 0 {
+                anyhow::bail!("{problems} signature problem(s) found");
+            }
+        }
         Commands::Repair {
             repo,
             path,
             profile,
+            net,
+            scan_threads,
+            dry_run,
+        } => {
+            let net = net_config(&net, profile.as_deref())?;
+            let (final_repo, final_path) = if let Some(p_name) = profile {
+                resolve_profile(&p_name)?
+            } else {
+                (repo, path.unwrap())
+            };
+            commands::cmd_repair(final_repo, final_path, &net, scan_threads, dry_run).await?;
+        }
+        Commands::ImportSwifty {
+            repo,
+            path,
+            profile,
+            net,
+            scan_threads,
         } => {
+            let net = net_config(&net, profile.as_deref())?;
             let (final_repo, final_path) = if let Some(p_name) = profile {
                 resolve_profile(&p_name)?
             } else {
-                (repo.unwrap(), path.unwrap())
+                (repo, path.unwrap())
             };
-            commands::cmd_repair(final_repo, final_path).await?;
+            commands::cmd_import_swifty(final_repo, final_path, &net, scan_threads).await?;
         }
         Commands::Sync {
             repo,
@@ -179,16 +741,135 @@ async fn main() -> anyhow::Result<()> {
             profile,
             mode,
             threads,
+            max_per_host,
+            order,
+            segments,
+            segment_min_mb,
             limit_mb,
             cache_dir,
+            temp_dir,
+            local_source,
+            mut exclude,
+            no_delete,
+            verify,
+            no_verify,
+            force,
+            skip_unreachable,
+            max_host_failures,
+            mut pin,
+            net,
+            plan,
+        } => {
+            let net = net_config(&net, profile.as_deref())?;
+            let (final_repo, final_path, skip_mods) = if let Some(p_name) = profile {
+                let (repos, path) = resolve_profile(&p_name)?;
+                let p = profiles::ProfileManager::new().find(&p_name)?;
+                exclude.extend(p.exclude_patterns());
+                pin = pin.or(p.pinned_repo_checksum);
+                (repos, path, p.unsynced_mods)
+            } else {
+                (repo, path.unwrap(), Vec::new())
+            };
+            commands::cmd_sync(
+                final_repo,
+                final_path,
+                mode,
+                threads,
+                max_per_host,
+                order,
+                segments.map(|segments| fleet_infra::SegmentedDownloads {
+                    min_size: segment_min_mb * 1024 * 1024,
+                    segments,
+                }),
+                limit_mb,
+                cache_dir,
+                temp_dir,
+                local_source,
+                skip_mods,
+                exclude,
+                no_delete,
+                verify,
+                no_verify,
+                force,
+                skip_unreachable,
+                max_host_failures,
+                pin,
+                &net,
+                plan,
+            )
+            .await?;
+        }
+        Commands::Doctor {
+            repo,
+            path,
+            profile,
+            net,
         } => {
+            let net = net_config(&net, profile.as_deref())?;
             let (final_repo, final_path) = if let Some(p_name) = profile {
-                resolve_profile(&p_name)?
+                let (repos, path) = resolve_profile(&p_name)?;
+                (repos.into_iter().next(), Some(path))
             } else {
-                (repo.unwrap(), path.unwrap())
+                (repo, path)
             };
-            commands::cmd_sync(final_repo, final_path, mode, threads, limit_mb, cache_dir).await?;
+            let report =
+                commands::cmd_doctor(final_repo.as_deref(), final_path.as_deref(), &net).await;
+            if !report.passed() {
+                anyhow::bail!("{} check(s) failed", report.failures());
+            }
         }
+        Commands::Clean {
+            path,
+            profile,
+            dry_run,
+        } => {
+            let final_path = if let Some(p_name) = profile {
+                let (_repo, path) = resolve_profile(&p_name)?;
+                path
+            } else {
+                path.unwrap()
+            };
+            commands::cmd_clean(final_path, dry_run)?;
+        }
+        Commands::Db { command } => match command {
+            DbCommands::ClearCache { path, profile } => {
+                let final_path = if let Some(p_name) = profile {
+                    let (_repo, path) = resolve_profile(&p_name)?;
+                    path
+                } else {
+                    path.unwrap()
+                };
+                commands::cmd_db_clear_cache(final_path)?;
+            }
+            DbCommands::Quarantine {
+                path,
+                profile,
+                list,
+                clear,
+            } => {
+                let final_path = if let Some(p_name) = profile {
+                    let (_repo, path) = resolve_profile(&p_name)?;
+                    path
+                } else {
+                    path.unwrap()
+                };
+                commands::cmd_db_quarantine(final_path, list, clear)?;
+            }
+            DbCommands::Show {
+                path,
+                profile,
+                mod_name,
+                files,
+            } => {
+                let final_path = if let Some(p_name) = profile {
+                    let (_repo, path) = resolve_profile(&p_name)?;
+                    path
+                } else {
+                    path.unwrap()
+                };
+                commands::cmd_db_show(final_path, mod_name, files)?;
+            }
+        },
         Commands::Launch {
             mods,
             profile,
@@ -197,6 +878,9 @@ async fn main() -> anyhow::Result<()> {
             mut args,
             flatpak,
             template,
+            env,
+            working_dir,
+            order,
         } => {
             #[cfg(not(target_os = "linux"))]
             if flatpak {
@@ -223,17 +907,26 @@ async fn main() -> anyhow::Result<()> {
                 }
             });
 
-            let launcher = Launcher::new("".to_string(), args, launch_template);
+            let launcher = Launcher::new("".to_string(), args, launch_template)
+                .with_env(env)
+                .with_working_dir(working_dir);
 
+            let mut mod_order = order.unwrap_or_default();
             let final_mods = if let Some(explicit_mods) = mods {
                 explicit_mods
             } else if let Some(p_name) = profile {
                 let mgr = profiles::ProfileManager::new();
                 let p = mgr.find(&p_name)?;
-                commands::resolve_mods_from_dir(&Utf8PathBuf::from(p.local_path))?
+                if mod_order.is_empty() {
+                    mod_order = p.mod_order.clone();
+                }
+                let mut mods = commands::resolve_mods_from_dir(&Utf8PathBuf::from(&p.local_path))?;
+                mods.retain(|m| m.file_name().is_none_or(|name| p.loads_mod(name)));
+                mods
             } else {
                 Vec::new()
             };
+            let final_mods = fleet_infra::order_mods(final_mods, &mod_order);
 
             launcher.launch(final_mods)?;
         }
@@ -1,7 +1,8 @@
 use anyhow::{anyhow, Result};
 use camino::Utf8PathBuf;
-use fleet_app_core::domain::Profile;
+use fleet_app_core::domain::{Profile, RepoAuthKind};
 use fleet_app_core::persistence::FilePersistence;
+use fleet_pipeline::sync::remote::normalize_repo_url;
 
 pub struct ProfileManager {
     persistence: FilePersistence,
@@ -32,6 +33,7 @@ impl ProfileManager {
         name: String,
         repo_url: String,
         local_path: Utf8PathBuf,
+        extra_repos: Vec<String>,
     ) -> Result<Profile> {
         let mut profiles = self.list()?;
 
@@ -47,14 +49,32 @@ impl ProfileManager {
         if profiles.iter().any(|p| p.id == id) {
             return Err(anyhow!("A profile with ID '{}' already exists", id));
         }
+        let repo_url = normalize_repo_url(&repo_url)?;
+        let extra_repos = extra_repos
+            .iter()
+            .map(|u| normalize_repo_url(u))
+            .collect::<Result<Vec<_>, _>>()?;
 
         let profile = Profile {
             id,
             name,
             repo_url,
+            extra_repos,
+            repo_auth: None,
             local_path: local_path.to_string(),
             last_synced: None,
             last_scan: None,
+            scan_history: Vec::new(),
+            throughput_history: Vec::new(),
+            launch_params_override: None,
+            mod_order: Vec::new(),
+            disabled_mods: Vec::new(),
+            unsynced_mods: Vec::new(),
+            excluded_paths: Vec::new(),
+            pinned_repo_checksum: None,
+            last_changes: None,
+            live_notify_url: None,
+            network: Default::default(),
         };
 
         profiles.push(profile.clone());
@@ -62,6 +82,43 @@ impl ProfileManager {
         Ok(profile)
     }
 
+    /// Stores repository credentials for a profile: the secret in the OS keyring, the
+    /// username (or bearer kind) in profiles.json.
+    pub fn set_repo_auth(&self, name: &str, username: Option<String>, secret: &str) -> Result<()> {
+        let mut profiles = self.list()?;
+        let profile = profiles
+            .iter_mut()
+            .find(|p| p.name.eq_ignore_ascii_case(name) || p.id == name)
+            .ok_or_else(|| anyhow!("Profile '{}' not found", name))?;
+        if secret.is_empty() {
+            return Err(anyhow!("Password or token cannot be empty"));
+        }
+        let key = fleet_infra::credentials::repo_key(&profile.id);
+        fleet_infra::credentials::store_password(&key, secret)?;
+        profile.repo_auth = Some(match username.filter(|u| !u.trim().is_empty()) {
+            Some(username) => RepoAuthKind::Basic {
+                username: username.trim().to_string(),
+            },
+            None => RepoAuthKind::Bearer,
+        });
+        self.persistence.save_profiles(&profiles)?;
+        Ok(())
+    }
+
+    /// Removes a profile's repository credentials from the keyring and profiles.json.
+    pub fn clear_repo_auth(&self, name: &str) -> Result<()> {
+        let mut profiles = self.list()?;
+        let profile = profiles
+            .iter_mut()
+            .find(|p| p.name.eq_ignore_ascii_case(name) || p.id == name)
+            .ok_or_else(|| anyhow!("Profile '{}' not found", name))?;
+        let key = fleet_infra::credentials::repo_key(&profile.id);
+        fleet_infra::credentials::delete_password(&key)?;
+        profile.repo_auth = None;
+        self.persistence.save_profiles(&profiles)?;
+        Ok(())
+    }
+
     pub fn remove(&self, name: &str) -> Result<()> {
         let mut profiles = self.list()?;
         let original_len = profiles.len();
@@ -100,9 +157,15 @@ pub fn handle_list() -> Result<()> {
     Ok(())
 }
 
-pub fn handle_add(id: String, name: String, repo: String, path: Utf8PathBuf) -> Result<()> {
+pub fn handle_add(
+    id: String,
+    name: String,
+    repo: String,
+    path: Utf8PathBuf,
+    extra_repos: Vec<String>,
+) -> Result<()> {
     let mgr = ProfileManager::new();
-    let p = mgr.add(id, name, repo, path)?;
+    let p = mgr.add(id, name, repo, path, extra_repos)?;
     println!("Profile '{}' ({}) created successfully.", p.name, p.id);
     Ok(())
 }
@@ -113,3 +176,29 @@ pub fn handle_remove(name: String) -> Result<()> {
     println!("Profile '{}' removed.", name);
     Ok(())
 }
+
+pub fn handle_login(name: String, username: Option<String>, secret: Option<String>) -> Result<()> {
+    let secret = match secret {
+        Some(secret) => secret,
+        None => {
+            eprint!("Password or token: ");
+            let mut line = String::new();
+            std::io::stdin().read_line(&mut line)?;
+            line.trim_end_matches(['\r', '\n']).to_string()
+        }
+    };
+    let mgr = ProfileManager::new();
+    mgr.set_repo_auth(&name, username, &secret)?;
+    println!(
+        "Repository credentials for '{}' saved to the keyring.",
+        name
+    );
+    Ok(())
+}
+
+pub fn handle_logout(name: String) -> Result<()> {
+    let mgr = ProfileManager::new();
+    mgr.clear_repo_auth(&name)?;
+    println!("Repository credentials for '{}' removed.", name);
+    Ok(())
+}
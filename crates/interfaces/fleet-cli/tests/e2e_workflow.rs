@@ -1,6 +1,9 @@
 use axum::{body::Body, routing::get, Router};
 use camino::Utf8PathBuf;
-use fleet_cli::{commands, CliSyncMode};
+use fleet_cli::{commands, CliDownloadOrder, CliOutputFormat, CliScanStrategy, CliSyncMode};
+use fleet_core::diff::FileStatus;
+use fleet_infra::NetConfig;
+use fleet_persistence::{FleetDataStore, RedbFleetDataStore};
 use std::net::SocketAddr;
 use std::sync::Arc;
 use tempfile::tempdir;
@@ -27,7 +30,7 @@ fn generate_repo_json(mod_name: &str) -> String {
     format!(
         r#"{{
   "repoName": "E2E_Test_Repo",
-  "checksum": "REPO_HASH",
+  "checksum": "",
   "requiredMods": [
     {{ "modName": "{mod_name}", "checkSum": "MOD_HASH", "enabled": true }}
   ],
@@ -92,13 +95,35 @@ async fn full_user_lifecycle_workflow() {
     let work_dir = tempdir().unwrap();
     let root = Utf8PathBuf::from_path_buf(work_dir.path().to_path_buf()).unwrap();
 
+    // Phase 0: size the repository before adding it
+    let info = commands::cmd_repo_info(repo_url.clone(), false, &NetConfig::default())
+        .await
+        .expect("repo-info failed");
+    assert_eq!((info.mods, info.files, info.total_bytes), (1, 1, 5));
+
     // Phase 1: fresh sync
     let res = commands::cmd_sync(
-        repo_url.clone(),
+        vec![repo_url.clone()],
         root.clone(),
         CliSyncMode::Smart,
         4,
         None,
+        CliDownloadOrder::Planned,
+        None,
+        None,
+        None,
+        None,
+        None,
+        Vec::new(),
+        Vec::new(),
+        false,
+        false,
+        false,
+        false,
+        false,
+        fleet_infra::net::DEFAULT_MAX_HOST_FAILURES,
+        None,
+        &NetConfig::default(),
         None,
     )
     .await
@@ -118,10 +143,72 @@ async fn full_user_lifecycle_workflow() {
         "`fleet.redb` must be saved"
     );
 
+    let shown =
+        commands::cmd_db_show(root.clone(), Some("@E2E_MOD".into()), true).expect("db show failed");
+    assert_eq!(shown.len(), 1, "Baseline should hold the synced mod");
+    assert_eq!(shown[0].files.len(), 1);
+    assert!(commands::cmd_db_show(root.clone(), Some("@missing".into()), false).is_err());
+    let tree = commands::cmd_tree(root.clone(), None, CliOutputFormat::Json).expect("tree failed");
+    assert_eq!(tree.len(), 1);
+    assert_eq!(tree[0].files[0].path, "data.bin");
+    assert_eq!(tree[0].files[0].status.label(), "installed");
+    let audit = commands::cmd_audit(
+        vec![repo_url.clone()],
+        root.clone(),
+        &NetConfig::default(),
+        CliOutputFormat::Text,
+    )
+    .await
+    .expect("audit failed");
+    assert!(audit.downloads.is_empty() && audit.deletes.is_empty());
+    assert!(commands::cmd_db_quarantine(root.clone(), true, false)
+        .expect("db quarantine failed")
+        .is_empty());
+
+    std::fs::write(root.join("@e2e_mod/.fleet-cache.json"), b"{}").unwrap();
+    assert_eq!(commands::cmd_clean(root.clone(), true).unwrap(), (1, 2));
+    assert!(root.join("@e2e_mod/.fleet-cache.json").exists());
+    assert_eq!(commands::cmd_clean(root.clone(), false).unwrap(), (1, 2));
+    assert!(!root.join("@e2e_mod/.fleet-cache.json").exists());
+
+    // Compare against a second install with one edited and one extra file
+    let other_dir = tempdir().unwrap();
+    let other = Utf8PathBuf::from_path_buf(other_dir.path().to_path_buf()).unwrap();
+    std::fs::create_dir_all(other.join("@e2e_mod")).unwrap();
+    std::fs::write(other.join("@e2e_mod/data.bin"), b"hello!").unwrap();
+    std::fs::write(other.join("@e2e_mod/extra.txt"), b"x").unwrap();
+    let report = commands::cmd_local_diff(
+        root.clone(),
+        other,
+        CliScanStrategy::Smart,
+        CliOutputFormat::Json,
+    )
+    .await
+    .expect("local-diff failed");
+    assert_eq!(report.count(FileStatus::Changed), 1);
+    assert_eq!(report.count(FileStatus::Added), 1);
+    assert_eq!(report.files.len(), 2);
+
+    // Quick check compares only repo.json mod checksums against the baseline
+    let quick = commands::cmd_quick_check(
+        vec![repo_url.clone()],
+        root.clone(),
+        &NetConfig::default(),
+        CliOutputFormat::Json,
+    )
+    .await
+    .expect("quick check failed");
+    assert!(quick.is_up_to_date(), "{quick:?}");
+
     // Phase 2: warm check, expect 0 ops
-    let plan = commands::cmd_check(repo_url.clone(), root.clone(), CliSyncMode::Smart)
-        .await
-        .expect("Phase 2 check failed");
+    let plan = commands::cmd_check(
+        vec![repo_url.clone()],
+        root.clone(),
+        CliSyncMode::Smart,
+        &NetConfig::default(),
+    )
+    .await
+    .expect("Phase 2 check failed");
     assert_eq!(
         plan.downloads.len(),
         0,
@@ -131,19 +218,43 @@ async fn full_user_lifecycle_workflow() {
 
     // Phase 3: cold check (delete persisted baseline), expect 0 ops
     std::fs::remove_file(root.join("fleet.redb")).unwrap();
-    let plan = commands::cmd_check(repo_url.clone(), root.clone(), CliSyncMode::Smart)
-        .await
-        .expect("Phase 3 check failed");
+    let plan = commands::cmd_check(
+        vec![repo_url.clone()],
+        root.clone(),
+        CliSyncMode::Smart,
+        &NetConfig::default(),
+    )
+    .await
+    .expect("Phase 3 check failed");
     assert_eq!(
         plan.downloads.len(),
         0,
         "Cold check should find existing files and skip download"
     );
 
+    // A dry run reports the baseline it would write without persisting it
+    let changes = commands::cmd_repair(
+        vec![repo_url.clone()],
+        root.clone(),
+        &NetConfig::default(),
+        None,
+        true,
+    )
+    .await
+    .expect("Phase 3 dry-run repair failed");
+    assert_eq!(changes.added, vec!["@e2e_mod".to_string()]);
+    assert!(RedbFleetDataStore.load_baseline_manifest(&root).is_err());
+
     // Restore baseline via repair (writes fleet.redb)
-    commands::cmd_repair(repo_url.clone(), root.clone())
-        .await
-        .expect("Phase 3 restore repair failed");
+    commands::cmd_repair(
+        vec![repo_url.clone()],
+        root.clone(),
+        &NetConfig::default(),
+        None,
+        false,
+    )
+    .await
+    .expect("Phase 3 restore repair failed");
     assert!(
         root.join("fleet.redb").exists(),
         "Repair must write `fleet.redb`"
@@ -152,13 +263,31 @@ async fn full_user_lifecycle_workflow() {
     // Phase 4: sabotage (delete mod folder)
     std::fs::remove_dir_all(root.join("@e2e_mod")).unwrap();
 
-    // Phase 5: repair sync should re-download missing file
+    // Phase 5: repair sync should re-download missing file, staged outside the mods folder
+    let staging_dir = tempdir().unwrap();
+    let staging = Utf8PathBuf::from_path_buf(staging_dir.path().join("staging")).unwrap();
     let res = commands::cmd_sync(
-        repo_url.clone(),
+        vec![repo_url.clone()],
         root.clone(),
         CliSyncMode::Smart,
         4,
         None,
+        CliDownloadOrder::Planned,
+        None,
+        None,
+        None,
+        Some(staging.clone()),
+        None,
+        Vec::new(),
+        Vec::new(),
+        false,
+        false,
+        false,
+        false,
+        false,
+        fleet_infra::net::DEFAULT_MAX_HOST_FAILURES,
+        None,
+        &NetConfig::default(),
         None,
     )
     .await
@@ -167,17 +296,145 @@ async fn full_user_lifecycle_workflow() {
         res.stats.files_planned_download, 1,
         "Repair should re-download missing file"
     );
+    assert!(
+        std::fs::read_dir(&staging).unwrap().next().is_none(),
+        "Staged downloads must be moved out of the temp dir"
+    );
     assert!(
         root.join("@e2e_mod/data.bin").exists(),
         "File must be restored"
     );
 
     // Phase 6: final check should be clean
-    let plan = commands::cmd_check(repo_url.clone(), root.clone(), CliSyncMode::Smart)
-        .await
-        .expect("Final verification failed");
+    let plan = commands::cmd_check(
+        vec![repo_url.clone()],
+        root.clone(),
+        CliSyncMode::Smart,
+        &NetConfig::default(),
+    )
+    .await
+    .expect("Final verification failed");
     assert_eq!(plan.downloads.len(), 0, "System should be clean");
     assert_eq!(plan.deletes.len(), 0, "System should be clean");
 
     server_handle.abort();
 }
+
+#[tokio::test]
+async fn scan_writes_srf_files_and_repo_json() {
+    let temp = tempdir().unwrap();
+    let root = Utf8PathBuf::from_path_buf(temp.path().join("mods")).unwrap();
+    for mod_name in ["@b", "@a"] {
+        std::fs::create_dir_all(root.join(mod_name).join("addons")).unwrap();
+        std::fs::write(root.join(mod_name).join("addons/data.bin"), mod_name).unwrap();
+    }
+    let out = Utf8PathBuf::from_path_buf(temp.path().join("out")).unwrap();
+
+    commands::cmd_scan(
+        root,
+        commands::ScanOutput::Srf(out.clone()),
+        CliScanStrategy::Force,
+        fleet_infra::DEFAULT_CHUNK_SIZE,
+        None,
+    )
+    .await
+    .expect("scan failed");
+
+    let srf = fleet_core::formats::parse_srf(&std::fs::read(out.join("@a.srf")).unwrap())
+        .expect("srf should parse");
+    assert_eq!(srf.name, "@a");
+    assert_eq!(srf.files[0].path, "addons/data.bin");
+    let repo: fleet_core::formats::RepositoryExternal =
+        serde_json::from_slice(&std::fs::read(out.join("repo.json")).unwrap()).unwrap();
+    assert_eq!(repo.repo_name, "mods");
+    let names: Vec<&str> = repo
+        .required_mods
+        .iter()
+        .map(|m| m.mod_name.as_str())
+        .collect();
+    assert_eq!(names, ["@a", "@b"]);
+    assert_eq!(repo.required_mods[0].checksum, srf.checksum);
+}
+
+#[tokio::test]
+async fn import_swifty_adopts_a_matching_install() {
+    let (addr, server_handle) = start_mock_server().await;
+    let repo_url = format!("http://{addr}");
+    let work_dir = tempdir().unwrap();
+    let root = Utf8PathBuf::from_path_buf(work_dir.path().to_path_buf()).unwrap();
+    std::fs::create_dir_all(root.join("@e2e_mod")).unwrap();
+
+    // Same size as the repository's file, different bytes.
+    std::fs::write(root.join("@e2e_mod/data.bin"), b"54321").unwrap();
+    let import = commands::cmd_import_swifty(
+        vec![repo_url.clone()],
+        root.clone(),
+        &NetConfig::default(),
+        None,
+    )
+    .await
+    .expect("import failed");
+    assert_eq!(
+        import.hash_mismatches,
+        vec!["@e2e_mod/data.bin".to_string()]
+    );
+    assert!(!import.baseline_written);
+    assert!(RedbFleetDataStore.load_baseline_manifest(&root).is_err());
+
+    std::fs::write(root.join("@e2e_mod/data.bin"), b"12345").unwrap();
+    let import = commands::cmd_import_swifty(
+        vec![repo_url.clone()],
+        root.clone(),
+        &NetConfig::default(),
+        None,
+    )
+    .await
+    .expect("import failed");
+    assert_eq!(import.matched, 1);
+    assert!(import.baseline_written);
+
+    let plan = commands::cmd_check(
+        vec![repo_url],
+        root.clone(),
+        CliSyncMode::Fast,
+        &NetConfig::default(),
+    )
+    .await
+    .expect("check after import failed");
+    assert!(plan.downloads.is_empty() && plan.deletes.is_empty());
+
+    server_handle.abort();
+}
+
+#[tokio::test]
+async fn doctor_reports_each_check() {
+    use fleet_app_core::doctor::CheckStatus;
+
+    let (addr, server_handle) = start_mock_server().await;
+    let work_dir = tempdir().unwrap();
+    let root = Utf8PathBuf::from_path_buf(work_dir.path().to_path_buf()).unwrap();
+    let net = NetConfig::default();
+
+    let report = commands::cmd_doctor(Some(&format!("http://{addr}")), Some(&root), &net).await;
+    assert!(report.passed(), "{report:?}");
+    for name in ["http client", "repository", "local path", "hashing"] {
+        assert_eq!(
+            report.get(name).unwrap().status,
+            CheckStatus::Pass,
+            "{name}"
+        );
+    }
+    // No sync has run here yet.
+    assert_eq!(report.get("database").unwrap().status, CheckStatus::Warn);
+
+    let missing = format!("http://{addr}/nowhere");
+    let report = commands::cmd_doctor(Some(&missing), None, &net).await;
+    assert_eq!(report.get("repository").unwrap().status, CheckStatus::Fail);
+    assert_eq!(
+        report.get("local path").unwrap().status,
+        CheckStatus::Skipped
+    );
+    assert!(!report.passed());
+
+    server_handle.abort();
+}
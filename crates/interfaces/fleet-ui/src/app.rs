@@ -1,5 +1,5 @@
 use crate::components::{header, sidebar};
-use crate::screens::{dashboard, editor, settings};
+use crate::screens::{dashboard, editor, settings, tree};
 use crate::updates;
 use eframe::egui;
 use egui_taffy::taffy::prelude::{auto, length, percent};
@@ -34,7 +34,20 @@ pub type DesktopFleetApp = FleetUiApp;
 
 impl eframe::App for FleetUiApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        self.core.handle_pipeline_events();
+        let events_applied = self.core.handle_pipeline_events();
+
+        // Escape or Ctrl+C cancels the running pipeline from anywhere; a second press
+        // confirms a large sync's cancel. Text fields keep both keys while focused.
+        let editing = ctx.memory(|m| m.focused().is_some());
+        let cancel_pressed = !editing
+            && ctx.input(|i| {
+                i.key_pressed(egui::Key::Escape)
+                    // egui reports Ctrl+C as a copy event rather than a key press.
+                    || i.events.iter().any(|e| matches!(e, egui::Event::Copy))
+            });
+        if cancel_pressed && self.core.is_pipeline_running() {
+            self.core.request_cancel();
+        }
 
         let mut update_state_changed = false;
         while let Ok(event) = self.update_events.try_recv() {
@@ -149,6 +162,11 @@ impl eframe::App for FleetUiApp {
                             if resp.settings_clicked {
                                 self.core.navigate(Route::Settings);
                             }
+                            if resp.check_all_clicked {
+                                if let Err(e) = self.core.check_all_profiles() {
+                                    tracing::error!("Failed to check all profiles: {e}");
+                                }
+                            }
                         });
 
                         tui.style(taffy::Style {
@@ -198,14 +216,21 @@ impl eframe::App for FleetUiApp {
                                 }
                             }
                             Route::ProfileEditor(_) => editor::draw(tui, &mut self.core),
+                            Route::ProfileTree(_) => tree::draw(tui, &mut self.core),
                             Route::Settings => settings::draw(tui, &mut self.core),
                         });
                     });
                 });
         });
 
-        if self.core.is_pipeline_running() {
+        // Redraw right away when something changed; otherwise poll at the progress cadence
+        // instead of spinning the render loop for the whole sync.
+        if events_applied {
             ctx.request_repaint();
+        } else if self.core.is_pipeline_running() {
+            ctx.request_repaint_after(fleet_app_core::orchestrator::PROGRESS_TICK);
+        } else if let Some(wait) = self.core.next_scheduled_check() {
+            ctx.request_repaint_after(wait);
         }
     }
 }
@@ -8,25 +8,33 @@ use fleet_app_core::viewmodel::DashboardState;
 
 pub struct CommandInterfaceResponse {
     pub sync: bool,
+    pub confirm_sync: bool,
     pub check_remote: bool,
     pub check_local: bool,
     pub repair: bool,
     pub launch: bool,
     pub join: bool,
     pub cancel: bool,
+    pub confirm_cancel: bool,
+    pub keep_running: bool,
     pub ack: bool,
+    pub retry_failed: bool,
 }
 
 pub fn draw<'a>(tui: impl TuiBuilderLogic<'a>, state: &DashboardState) -> CommandInterfaceResponse {
     let mut resp = CommandInterfaceResponse {
         sync: false,
+        confirm_sync: false,
         check_remote: false,
         check_local: false,
         repair: false,
         launch: false,
         join: false,
         cancel: false,
+        confirm_cancel: false,
+        keep_running: false,
         ack: false,
+        retry_failed: false,
     };
 
     tui.style(taffy::Style {
@@ -93,10 +101,15 @@ pub fn draw<'a>(tui: impl TuiBuilderLogic<'a>, state: &DashboardState) -> Comman
                 let task_lbl = match state {
                     DashboardState::Busy { task_name, .. } => task_name.clone(),
                     DashboardState::Review {
-                        changes_summary, ..
-                    } => changes_summary.clone(),
+                        changes_summary,
+                        eta,
+                        ..
+                    } => match eta {
+                        Some(eta) => format!("{changes_summary} {eta}"),
+                        None => changes_summary.clone(),
+                    },
                     DashboardState::Synced { .. } => "UP TO DATE".to_string(),
-                    DashboardState::Error { msg } => msg.clone(),
+                    DashboardState::Error { msg, .. } => msg.clone(),
                     DashboardState::Idle { .. } => "READY".to_string(),
                     DashboardState::Unknown { msg } => msg.clone(),
                 };
@@ -118,10 +131,17 @@ pub fn draw<'a>(tui: impl TuiBuilderLogic<'a>, state: &DashboardState) -> Comman
                 });
 
                 let detail_lbl = match state {
+                    DashboardState::Busy {
+                        confirm_cancel: true,
+                        ..
+                    } => Some("This sync is large; cancelling discards the download in progress."),
                     DashboardState::Busy { detail, .. } => Some(detail.as_str()),
                     DashboardState::Synced { msg, .. } => Some(msg.as_str()),
-                    DashboardState::Error { msg } => Some(msg.as_str()),
+                    DashboardState::Error { msg, .. } => Some(msg.as_str()),
                     DashboardState::Unknown { msg } => Some(msg.as_str()),
+                    DashboardState::Review {
+                        destructive: true, ..
+                    } => Some("Most local files would be deleted. Check the repository and path."),
                     _ => None,
                 };
 
@@ -199,6 +219,23 @@ pub fn draw<'a>(tui: impl TuiBuilderLogic<'a>, state: &DashboardState) -> Comman
                     ..Default::default()
                 })
                 .add(|tui| match state {
+                    DashboardState::Busy {
+                        confirm_cancel: true,
+                        ..
+                    } => {
+                        if tui
+                            .ui(|ui| cmd_button(ui, "CONFIRM CANCEL", "danger", true))
+                            .clicked()
+                        {
+                            resp.confirm_cancel = true;
+                        }
+                        if tui
+                            .ui(|ui| cmd_button(ui, "KEEP SYNCING", "outline", true))
+                            .clicked()
+                        {
+                            resp.keep_running = true;
+                        }
+                    }
                     DashboardState::Busy { can_cancel, .. } => {
                         if tui
                             .ui(|ui| cmd_button(ui, "CANCEL", "danger", *can_cancel))
@@ -207,8 +244,19 @@ pub fn draw<'a>(tui: impl TuiBuilderLogic<'a>, state: &DashboardState) -> Comman
                             resp.cancel = true;
                         }
                     }
-                    DashboardState::Review { can_launch, .. } => {
-                        if tui
+                    DashboardState::Review {
+                        can_launch,
+                        destructive,
+                        ..
+                    } => {
+                        if *destructive {
+                            if tui
+                                .ui(|ui| cmd_button(ui, "CONFIRM DELETE + SYNC", "danger", true))
+                                .clicked()
+                            {
+                                resp.confirm_sync = true;
+                            }
+                        } else if tui
                             .ui(|ui| cmd_button(ui, "SYNC", "primary", true))
                             .clicked()
                         {
@@ -253,7 +301,17 @@ pub fn draw<'a>(tui: impl TuiBuilderLogic<'a>, state: &DashboardState) -> Comman
                             resp.check_remote = true;
                         }
                     }
-                    DashboardState::Error { .. } => {
+                    DashboardState::Error { retry_files, .. } => {
+                        if *retry_files > 0 {
+                            let noun = if *retry_files == 1 { "file" } else { "files" };
+                            let label = format!("RETRY FAILED ({retry_files} {noun})");
+                            if tui
+                                .ui(|ui| cmd_button(ui, &label, "primary", true))
+                                .clicked()
+                            {
+                                resp.retry_failed = true;
+                            }
+                        }
                         if tui
                             .ui(|ui| cmd_button(ui, "ACK", "outline", true))
                             .clicked()
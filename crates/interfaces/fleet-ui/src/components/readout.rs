@@ -4,7 +4,7 @@ use eframe::egui;
 use egui_taffy::bg::simple::{TuiBackground, TuiBuilderLogicWithBackground};
 use egui_taffy::taffy::prelude::{auto, length, percent};
 use egui_taffy::{taffy, TuiBuilderLogic};
-use fleet_app_core::viewmodel::ProfileStatsVm;
+use fleet_app_core::viewmodel::{ProfileStatsVm, ScanHistoryVm};
 
 pub fn draw<'a>(tui: impl TuiBuilderLogic<'a>, stats: &Option<ProfileStatsVm>) {
     tui.style(taffy::Style {
@@ -86,3 +86,29 @@ fn draw_cell<'a>(tui: impl TuiBuilderLogic<'a>, label: &str, value: &str) {
         );
     });
 }
+
+/// Recent scans, newest first, so a sudden drop in cache hits stands out.
+pub fn draw_history<'a>(tui: impl TuiBuilderLogic<'a>, history: &[ScanHistoryVm]) {
+    if history.is_empty() {
+        return;
+    }
+    tui.style(taffy::Style {
+        flex_direction: taffy::FlexDirection::Column,
+        gap: length(2.0),
+        ..Default::default()
+    })
+    .add(|tui| {
+        tui.ui(|ui| section_label(ui, "RECENT CHECKS"));
+        for entry in history {
+            tui.label(
+                egui::RichText::new(format!(
+                    "{}  {:>8} files  cache {:>6}",
+                    entry.when, entry.file_count, entry.cache_ratio
+                ))
+                .size(10.0)
+                .color(COL_TEXT_DIM)
+                .monospace(),
+            );
+        }
+    });
+}
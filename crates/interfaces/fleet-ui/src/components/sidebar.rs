@@ -11,6 +11,7 @@ pub struct SidebarResponse {
     pub selected_id: Option<ProfileId>,
     pub add_clicked: bool,
     pub settings_clicked: bool,
+    pub check_all_clicked: bool,
 }
 
 pub fn draw<'a>(
@@ -22,6 +23,7 @@ pub fn draw<'a>(
         selected_id: None,
         add_clicked: false,
         settings_clicked: false,
+        check_all_clicked: false,
     };
 
     tui.style(taffy::Style {
@@ -57,6 +59,9 @@ pub fn draw<'a>(
             })
             .add(|tui| {
                 tui.ui(|ui| section_label(ui, "PROFILES"));
+                if let Some(summary) = &vm.update_summary {
+                    tui.label(egui::RichText::new(summary).size(10.0).color(COL_TEXT_DIM));
+                }
 
                 tui.style(taffy::Style {
                     flex_direction: taffy::FlexDirection::Column,
@@ -82,7 +87,9 @@ pub fn draw<'a>(
                         let is_selected = Some(profile.id.clone()) == selected_id;
                         let badge_col = match profile.status_label.as_str() {
                             "Ready" => COL_SUCCESS,
-                            "Update Available" => COL_ACCENT,
+                            "Update Available" | "Repo Changed" => COL_ACCENT,
+                            "Checking" => COL_SYNCING,
+                            "Check Failed" => COL_DANGER,
                             _ => COL_TEXT_DIM,
                         };
 
@@ -152,6 +159,10 @@ pub fn draw<'a>(
                                 },
                             );
 
+                        let response = match &profile.status_detail {
+                            Some(detail) => response.response.on_hover_text(detail),
+                            None => response.response,
+                        };
                         if response.clicked() {
                             resp.selected_id = Some(profile.id.clone());
                         }
@@ -173,6 +184,12 @@ pub fn draw<'a>(
                     .with_border_color(COL_BORDER)
                     .with_border_width(1.0),
                 |tui| {
+                    if tui
+                        .ui(|ui| cmd_button(ui, "CHECK ALL", "outline", vm.can_check_all))
+                        .clicked()
+                    {
+                        resp.check_all_clicked = true;
+                    }
                     if tui
                         .ui(|ui| cmd_button(ui, "SETTINGS", "outline", true))
                         .clicked()
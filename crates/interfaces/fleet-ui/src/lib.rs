@@ -1,5 +1,6 @@
 mod app;
 mod components;
+mod logging;
 mod screens;
 mod theme;
 mod updates;
@@ -7,12 +8,12 @@ mod utils;
 
 use fleet_app_core::FleetApplication;
 use std::sync::Arc;
-use tracing_subscriber::{EnvFilter, FmtSubscriber};
 
 fn setup_logging() {
-    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
-    let subscriber = FmtSubscriber::builder().with_env_filter(filter).finish();
-    let _ = tracing::subscriber::set_global_default(subscriber);
+    let log_dir = fleet_app_core::persistence::FilePersistence::new()
+        .log_dir()
+        .ok();
+    logging::setup(log_dir.as_deref());
 }
 
 fn app_icon() -> Arc<eframe::egui::IconData> {
@@ -46,6 +47,7 @@ pub fn run() -> eframe::Result<()> {
             if let Err(e) = core.load_initial_state() {
                 tracing::error!("Failed to load state: {}", e);
             }
+            logging::set_level(&core.state.settings.log_level);
 
             Ok(Box::new(app::FleetUiApp::new(core)))
         }),
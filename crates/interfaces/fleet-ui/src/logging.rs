@@ -0,0 +1,123 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, EnvFilter, Registry};
+
+const LOG_FILE_NAME: &str = "fleet.log";
+/// Rotate once the active log file grows past this size.
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+/// Number of rotated files kept next to the active one (`fleet.log.1` ..).
+const KEPT_LOG_FILES: usize = 3;
+
+static FILTER_HANDLE: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+/// Logs to the console and, when the log directory is available, to a size-rotated file.
+/// `RUST_LOG` overrides the level chosen in settings.
+pub fn setup(log_dir: Option<&Path>) {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let (filter, handle) = reload::Layer::new(filter);
+
+    let file_layer = log_dir
+        .and_then(|dir| RotatingFile::open(dir).ok())
+        .map(|file| {
+            fmt::layer()
+                .with_ansi(false)
+                .with_writer(move || file.clone())
+        });
+
+    let registry = tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer())
+        .with(file_layer);
+    if registry.try_init().is_ok() {
+        let _ = FILTER_HANDLE.set(handle);
+    }
+}
+
+/// Applies the level from settings unless `RUST_LOG` is set.
+pub fn set_level(level: &str) {
+    if std::env::var_os(EnvFilter::DEFAULT_ENV).is_some() {
+        return;
+    }
+    let Some(handle) = FILTER_HANDLE.get() else {
+        return;
+    };
+    match EnvFilter::try_new(level) {
+        Ok(filter) => {
+            let _ = handle.reload(filter);
+        }
+        Err(e) => tracing::warn!("Ignoring invalid log level {level:?}: {e}"),
+    }
+}
+
+/// Append-only log file that is shifted to `fleet.log.1` (and so on) once it exceeds
+/// [`MAX_LOG_BYTES`].
+#[derive(Clone)]
+struct RotatingFile {
+    inner: Arc<Mutex<RotatingState>>,
+}
+
+struct RotatingState {
+    dir: PathBuf,
+    file: File,
+    len: u64,
+}
+
+impl RotatingFile {
+    fn open(dir: &Path) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let (file, len) = open_active(dir)?;
+        Ok(Self {
+            inner: Arc::new(Mutex::new(RotatingState {
+                dir: dir.to_path_buf(),
+                file,
+                len,
+            })),
+        })
+    }
+}
+
+fn open_active(dir: &Path) -> io::Result<(File, u64)> {
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join(LOG_FILE_NAME))?;
+    let len = file.metadata()?.len();
+    Ok((file, len))
+}
+
+impl RotatingState {
+    fn rotate(&mut self) -> io::Result<()> {
+        let rotated = |i: usize| self.dir.join(format!("{LOG_FILE_NAME}.{i}"));
+        let _ = fs::remove_file(rotated(KEPT_LOG_FILES));
+        for i in (1..KEPT_LOG_FILES).rev() {
+            let _ = fs::rename(rotated(i), rotated(i + 1));
+        }
+        fs::rename(self.dir.join(LOG_FILE_NAME), rotated(1))?;
+        let (file, len) = open_active(&self.dir)?;
+        self.file = file;
+        self.len = len;
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut state = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        if state.len > 0 && state.len + buf.len() as u64 > MAX_LOG_BYTES {
+            // Keep logging into the current file if rotation fails (e.g. locked on Windows).
+            let _ = state.rotate();
+        }
+        let written = state.file.write(buf)?;
+        state.len += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let mut state = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        state.file.flush()
+    }
+}
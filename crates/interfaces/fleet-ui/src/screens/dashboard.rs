@@ -1,11 +1,11 @@
 use crate::components::{command, readout, visualizer};
 use crate::theme::*;
-use crate::utils::cmd_button;
+use crate::utils::{cmd_button, section_label};
 use eframe::egui;
 use egui_taffy::taffy::prelude::{length, percent};
 use egui_taffy::{taffy, TuiBuilderLogic};
-use fleet_app_core::viewmodel::ProfileDashboardVm;
-use fleet_app_core::FleetApplication;
+use fleet_app_core::viewmodel::{ProfileDashboardVm, ServerVm, VisualizerPhase};
+use fleet_app_core::{FleetApplication, Route};
 
 pub fn draw<'a>(
     tui: impl TuiBuilderLogic<'a>,
@@ -49,12 +49,37 @@ pub fn draw<'a>(
                         .color(COL_TEXT),
                 );
 
-                if tui
-                    .ui(|ui| cmd_button(ui, "EDIT", "outline", true))
-                    .clicked()
-                {
-                    app.edit_profile(vm.profile.id.clone());
-                }
+                tui.style(taffy::Style {
+                    flex_direction: taffy::FlexDirection::Row,
+                    gap: length(6.0),
+                    ..Default::default()
+                })
+                .add(|tui| {
+                    if tui
+                        .ui(|ui| {
+                            cmd_button(ui, "OPEN FOLDER", "outline", vm.local_folder_exists)
+                        })
+                        .on_hover_text("Show the mods folder in the file manager")
+                        .clicked()
+                    {
+                        if let Err(e) = app.open_local_folder(vm.profile.id.clone()) {
+                            tracing::error!("Failed to open mods folder: {e}");
+                        }
+                    }
+                    if tui
+                        .ui(|ui| cmd_button(ui, "FILES", "outline", true))
+                        .on_hover_text("List every mod and file with its sync status")
+                        .clicked()
+                    {
+                        app.navigate(Route::ProfileTree(vm.profile.id.clone()));
+                    }
+                    if tui
+                        .ui(|ui| cmd_button(ui, "EDIT", "outline", true))
+                        .clicked()
+                    {
+                        app.edit_profile(vm.profile.id.clone());
+                    }
+                });
             });
 
             tui.label(
@@ -67,6 +92,201 @@ pub fn draw<'a>(
         });
 
         readout::draw(&mut *tui, &vm.stats);
+        readout::draw_history(&mut *tui, &vm.scan_history);
+
+        if let Some(changes) = &vm.last_changes {
+            tui.label(
+                egui::RichText::new(format!("LAST SYNC: {changes}"))
+                    .size(10.0)
+                    .color(COL_TEXT_DIM),
+            );
+        }
+
+        if let Some(warmup) = &vm.cache_warmup {
+            tui.label(egui::RichText::new(warmup).size(10.0).color(COL_TEXT_DIM));
+        }
+
+        if vm.offline {
+            tui.label(
+                egui::RichText::new("Offline — showing local state only")
+                    .size(10.0)
+                    .color(COL_WARN),
+            );
+        }
+
+        if vm.quarantined_dbs > 0 {
+            tui.style(taffy::Style {
+                flex_direction: taffy::FlexDirection::Row,
+                gap: length(8.0),
+                align_items: Some(taffy::AlignItems::Center),
+                ..Default::default()
+            })
+            .add(|tui| {
+                let noun = if vm.quarantined_dbs == 1 {
+                    "copy"
+                } else {
+                    "copies"
+                };
+                tui.label(
+                    egui::RichText::new(format!(
+                        "{} corrupt database {noun} (fleet.redb.corrupt.*) in the mods folder",
+                        vm.quarantined_dbs
+                    ))
+                    .size(10.0)
+                    .color(COL_WARN),
+                );
+                if tui
+                    .ui(|ui| cmd_button(ui, "OPEN FOLDER", "outline", true))
+                    .clicked()
+                {
+                    if let Err(e) = app.open_local_folder(vm.profile.id.clone()) {
+                        tracing::error!("Failed to open mods folder: {e}");
+                    }
+                }
+                let confirm_id = egui::Id::new(("delete_quarantined", vm.profile.id.as_str()));
+                let confirming = tui
+                    .egui_ctx()
+                    .data_mut(|d| *d.get_temp_mut_or_default::<bool>(confirm_id));
+                if !confirming {
+                    if tui
+                        .ui(|ui| cmd_button(ui, "DELETE", "outline", true))
+                        .clicked()
+                    {
+                        tui.egui_ctx().data_mut(|d| d.insert_temp(confirm_id, true));
+                    }
+                    return;
+                }
+                if tui
+                    .ui(|ui| cmd_button(ui, "CONFIRM DELETE", "danger", true))
+                    .clicked()
+                {
+                    if let Err(e) = app.clear_quarantined_dbs(vm.profile.id.clone()) {
+                        tracing::error!("Failed to delete quarantined databases: {e}");
+                    }
+                    tui.egui_ctx().data_mut(|d| d.remove::<bool>(confirm_id));
+                }
+                if tui
+                    .ui(|ui| cmd_button(ui, "KEEP", "outline", true))
+                    .clicked()
+                {
+                    tui.egui_ctx().data_mut(|d| d.remove::<bool>(confirm_id));
+                }
+            });
+        }
+
+        if vm.pin_status.is_some() || vm.can_pin {
+            tui.style(taffy::Style {
+                flex_direction: taffy::FlexDirection::Row,
+                gap: length(8.0),
+                align_items: Some(taffy::AlignItems::Center),
+                ..Default::default()
+            })
+            .add(|tui| {
+                let profile_id = vm.profile.id.clone();
+                if let Some(status) = &vm.pin_status {
+                    tui.label(egui::RichText::new(status).size(10.0).color(COL_WARN));
+                    if tui
+                        .ui(|ui| cmd_button(ui, "UNPIN", "outline", true))
+                        .clicked()
+                    {
+                        if let Err(e) = app.unpin_repo_version(profile_id) {
+                            tracing::error!("Failed to unpin repository version: {e}");
+                        }
+                    }
+                } else if tui
+                    .ui(|ui| cmd_button(ui, "PIN VERSION", "outline", true))
+                    .clicked()
+                {
+                    if let Err(e) = app.pin_repo_version(profile_id) {
+                        tracing::error!("Failed to pin repository version: {e}");
+                    }
+                }
+            });
+        }
+
+        if !vm.verify_mismatches.is_empty() {
+            tui.label(
+                egui::RichText::new(format!(
+                    "WARNING: {} downloaded file(s) failed verification; run Repair",
+                    vm.verify_mismatches.len()
+                ))
+                .size(10.0)
+                .color(COL_WARN),
+            );
+        }
+        for warning in vm
+            .storage_warnings
+            .iter()
+            .chain(vm.verify_mismatches.iter().take(5))
+            .chain(&vm.signature_warnings)
+        {
+            tui.label(
+                egui::RichText::new(format!("WARNING: {warning}"))
+                    .size(10.0)
+                    .color(COL_WARN),
+            );
+        }
+
+        // With several repo.json servers, JOIN connects to the one picked here. Each server's
+        // live status comes from a periodic A2S query.
+        if !vm.servers.is_empty() {
+            app.refresh_server_status(vm.profile.id.clone());
+            let answered = vm.servers.iter().all(|s| s.status.is_some());
+            tui.egui_ctx().request_repaint_after(if answered {
+                fleet_app_core::app::SERVER_STATUS_REFRESH
+            } else {
+                std::time::Duration::from_millis(500)
+            });
+        }
+        let server_id = egui::Id::new(("join_server", vm.profile.id.as_str()));
+        let mut server_index = tui
+            .egui_ctx()
+            .data_mut(|d| *d.get_temp_mut_or_default::<usize>(server_id))
+            .min(vm.servers.len().saturating_sub(1));
+        if !vm.servers.is_empty() {
+            tui.style(taffy::Style {
+                flex_direction: taffy::FlexDirection::Row,
+                gap: length(6.0),
+                align_items: Some(taffy::AlignItems::Center),
+                ..Default::default()
+            })
+            .add(|tui| {
+                tui.label("Join server:");
+                let label = |s: &ServerVm| format!("{} — {}", s.name, s.address);
+                if vm.servers.len() > 1 {
+                    tui.ui(|ui| {
+                        egui::ComboBox::from_id_salt(("join_server_combo", vm.profile.id.as_str()))
+                            .selected_text(label(&vm.servers[server_index]))
+                            .show_ui(ui, |ui| {
+                                for (i, server) in vm.servers.iter().enumerate() {
+                                    let text = match &server.status {
+                                        Some(status) => format!("{} ({status})", label(server)),
+                                        None => label(server),
+                                    };
+                                    ui.selectable_value(&mut server_index, i, text);
+                                }
+                            });
+                    });
+                } else {
+                    tui.label(label(&vm.servers[0]));
+                }
+                let server = &vm.servers[server_index];
+                let mut info = vec![server.status.as_deref().unwrap_or("querying…")];
+                if server.battle_eye {
+                    info.push("BattlEye");
+                }
+                if server.has_password {
+                    info.push("password");
+                }
+                tui.label(
+                    egui::RichText::new(info.join(" · "))
+                        .size(10.0)
+                        .color(COL_TEXT_DIM),
+                );
+            });
+        }
+        tui.egui_ctx()
+            .data_mut(|d| d.insert_temp(server_id, server_index));
 
         let cmd_resp = command::draw(&mut *tui, &vm.state);
         if cmd_resp.check_local {
@@ -89,23 +309,109 @@ pub fn draw<'a>(
                 tracing::error!("Failed to start sync: {e}");
             }
         }
+        if cmd_resp.confirm_sync {
+            if let Err(e) = app.execute_confirmed_sync(vm.profile.id.clone()) {
+                tracing::error!("Failed to start sync: {e}");
+            }
+        }
         if cmd_resp.launch {
             if let Err(e) = app.launch_profile(vm.profile.id.clone()) {
                 tracing::error!("Failed to launch profile: {e}");
             }
         }
         if cmd_resp.join {
-            if let Err(e) = app.join_profile(vm.profile.id.clone()) {
+            if let Err(e) = app.join_profile_server(vm.profile.id.clone(), server_index) {
                 tracing::error!("Failed to join profile: {e}");
             }
         }
         if cmd_resp.cancel {
+            app.request_cancel();
+        }
+        if cmd_resp.confirm_cancel {
             app.cancel_pipeline();
         }
+        if cmd_resp.keep_running {
+            app.dismiss_cancel();
+        }
         if cmd_resp.ack {
             app.acknowledge_pipeline_completion();
         }
+        if cmd_resp.retry_failed {
+            if let Err(e) = app.retry_failed_downloads(vm.profile.id.clone()) {
+                tracing::error!("Failed to retry failed downloads: {e}");
+            }
+        }
 
         visualizer::Visualizer::draw(&mut *tui, &vm.state, &vm.visualizer);
+
+        if vm.visualizer.phase == VisualizerPhase::Executing {
+            if let Some(transfer) = &vm.visualizer.transfer {
+                if transfer.per_mod.len() > 1 {
+                    tui.ui(|ui| section_label(ui, "MODS"));
+                    for m in &transfer.per_mod {
+                        let ratio = if m.total_bytes > 0 {
+                            m.downloaded_bytes as f32 / m.total_bytes as f32
+                        } else {
+                            0.0
+                        };
+                        tui.ui_add(egui::ProgressBar::new(ratio.clamp(0.0, 1.0)).text(format!(
+                            "{}  {}/{}",
+                            m.mod_name, m.downloaded_files, m.total_files
+                        )));
+                    }
+                }
+            }
+        }
+
+        if !vm.mods.is_empty() {
+            let mut set_loaded: Option<(String, bool)> = None;
+            let mut set_synced: Option<(String, bool)> = None;
+            tui.ui(|ui| {
+                egui::CollapsingHeader::new(
+                    egui::RichText::new("MOD TOGGLES")
+                        .size(10.0)
+                        .color(COL_TEXT_DIM)
+                        .family(egui::FontFamily::Monospace)
+                        .strong(),
+                )
+                .id_salt(("mod_toggles", &vm.profile.id))
+                .show(ui, |ui| {
+                    ui.label(
+                        egui::RichText::new(
+                            "LOAD passes the mod to the game; SYNC lets checks download, verify and delete it.",
+                        )
+                        .size(10.0)
+                        .color(COL_TEXT_DIM),
+                    );
+                    egui::Grid::new(("mod_toggle_grid", &vm.profile.id))
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for m in &vm.mods {
+                                let (mut loaded, mut synced) = (m.loaded, m.synced);
+                                ui.label(
+                                    egui::RichText::new(&m.name).font(egui::FontId::monospace(12.0)),
+                                );
+                                if ui.checkbox(&mut loaded, "LOAD").changed() {
+                                    set_loaded = Some((m.name.clone(), loaded));
+                                }
+                                if ui.checkbox(&mut synced, "SYNC").changed() {
+                                    set_synced = Some((m.name.clone(), synced));
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+            });
+            if let Some((name, loaded)) = set_loaded {
+                if let Err(e) = app.set_mod_loaded(vm.profile.id.clone(), &name, loaded) {
+                    tracing::error!("Failed to update mod toggles: {e}");
+                }
+            }
+            if let Some((name, synced)) = set_synced {
+                if let Err(e) = app.set_mod_synced(vm.profile.id.clone(), &name, synced) {
+                    tracing::error!("Failed to update mod toggles: {e}");
+                }
+            }
+        }
     });
 }
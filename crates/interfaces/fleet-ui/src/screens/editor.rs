@@ -4,7 +4,9 @@ use crate::utils::cmd_button;
 use eframe::egui;
 use egui_taffy::taffy::prelude::{length, percent};
 use egui_taffy::{taffy, TuiBuilderLogic};
-use fleet_app_core::{viewmodel::profile_editor_vm, FleetApplication};
+use fleet_app_core::{
+    app::default_mod_order, domain::RepoAuthKind, viewmodel::profile_editor_vm, FleetApplication,
+};
 
 pub fn draw<'a>(tui: impl TuiBuilderLogic<'a>, app: &mut FleetApplication) {
     let Some(vm) = profile_editor_vm(&*app) else {
@@ -26,6 +28,7 @@ pub fn draw<'a>(tui: impl TuiBuilderLogic<'a>, app: &mut FleetApplication) {
         // Section header
         tui.ui(|ui| crate::utils::section_label(ui, "PROFILE EDITOR"));
 
+        let global = app.state.settings.clone();
         if let Some(draft) = app.state.editor_draft.as_mut() {
             // ID
             tui.style(taffy::Style {
@@ -48,6 +51,28 @@ pub fn draw<'a>(tui: impl TuiBuilderLogic<'a>, app: &mut FleetApplication) {
             text_field(&mut *tui, "NAME", &mut draft.name, "Profile Name");
             text_field(&mut *tui, "REPOSITORY", &mut draft.repo_url, "git@...");
 
+            // Extra repositories: one URL per line, merged into this profile.
+            tui.style(taffy::Style {
+                flex_direction: taffy::FlexDirection::Column,
+                gap: length(4.0),
+                ..Default::default()
+            })
+            .add(|tui| {
+                tui.ui(|ui| crate::utils::section_label(ui, "EXTRA REPOSITORIES"));
+                let mut extra = draft.extra_repos.join("\n");
+                let resp = tui.ui_add(
+                    egui::TextEdit::multiline(&mut extra)
+                        .hint_text("One URL per line (optional)")
+                        .desired_rows(2)
+                        .desired_width(f32::INFINITY)
+                        .font(egui::FontId::monospace(12.0)),
+                );
+                if resp.changed() {
+                    // Keep blank lines while editing; they are dropped on save.
+                    draft.extra_repos = extra.split('\n').map(str::to_string).collect();
+                }
+            });
+
             // PATH row with browse button placed beneath for clarity
             tui.style(taffy::Style {
                 flex_direction: taffy::FlexDirection::Column,
@@ -98,6 +123,294 @@ pub fn draw<'a>(tui: impl TuiBuilderLogic<'a>, app: &mut FleetApplication) {
                     }
                 });
             });
+
+            // Advanced: per-profile launch parameters replacing the global ones.
+            tui.style(taffy::Style {
+                flex_direction: taffy::FlexDirection::Column,
+                gap: length(4.0),
+                ..Default::default()
+            })
+            .add(|tui| {
+                tui.ui(|ui| crate::utils::section_label(ui, "ADVANCED: LAUNCH PARAMETERS"));
+                let mut params = draft.launch_params_override.clone().unwrap_or_default();
+                let resp = tui.ui_add(
+                    egui::TextEdit::singleline(&mut params)
+                        .hint_text("Empty uses the global launch parameters")
+                        .desired_width(f32::INFINITY)
+                        .font(egui::FontId::monospace(12.0)),
+                );
+                if resp.changed() {
+                    draft.launch_params_override = (!params.is_empty()).then_some(params);
+                }
+            });
+
+            // Advanced: threads and speed limit replacing the global ones.
+            tui.style(taffy::Style {
+                flex_direction: taffy::FlexDirection::Column,
+                gap: length(4.0),
+                ..Default::default()
+            })
+            .add(|tui| {
+                tui.ui(|ui| crate::utils::section_label(ui, "ADVANCED: NETWORK OVERRIDES"));
+                let net = &mut draft.network;
+                tui.style(taffy::Style {
+                    flex_direction: taffy::FlexDirection::Row,
+                    gap: length(6.0),
+                    align_items: Some(taffy::AlignItems::Center),
+                    ..Default::default()
+                })
+                .add(|tui| {
+                    let mut on = net.max_threads.is_some();
+                    if tui.ui_add(egui::Checkbox::new(&mut on, "Threads")).changed() {
+                        net.max_threads = on.then_some(global.max_threads);
+                    }
+                    if let Some(threads) = net.max_threads.as_mut() {
+                        tui.ui_add(egui::DragValue::new(threads).range(1..=32));
+                    }
+                });
+                tui.style(taffy::Style {
+                    flex_direction: taffy::FlexDirection::Row,
+                    gap: length(6.0),
+                    align_items: Some(taffy::AlignItems::Center),
+                    ..Default::default()
+                })
+                .add(|tui| {
+                    let mut on = net.speed_limit_enabled.is_some();
+                    if tui.ui_add(egui::Checkbox::new(&mut on, "Speed limit")).changed() {
+                        net.speed_limit_enabled = on.then_some(global.speed_limit_enabled);
+                        net.max_speed_bytes = on.then_some(global.max_speed_bytes);
+                    }
+                    if let Some(enabled) = net.speed_limit_enabled.as_mut() {
+                        tui.ui_add(egui::Checkbox::new(enabled, "Enabled"));
+                        if *enabled {
+                            let bytes = net.max_speed_bytes.get_or_insert(global.max_speed_bytes);
+                            tui.label("Bytes/s:");
+                            tui.ui_add(egui::DragValue::new(bytes).speed(1024.0));
+                        }
+                    }
+                });
+                tui.label(
+                    egui::RichText::new("Unchecked settings use the global values")
+                        .size(10.0)
+                        .color(crate::theme::COL_TEXT_DIM),
+                );
+            });
+
+            // Advanced: repository push notifications that trigger a check.
+            tui.style(taffy::Style {
+                flex_direction: taffy::FlexDirection::Column,
+                gap: length(4.0),
+                ..Default::default()
+            })
+            .add(|tui| {
+                tui.ui(|ui| crate::utils::section_label(ui, "ADVANCED: LIVE UPDATES"));
+                let mut url = draft.live_notify_url.clone().unwrap_or_default();
+                let resp = tui.ui_add(
+                    egui::TextEdit::singleline(&mut url)
+                        .hint_text("SSE endpoint, e.g. https://repo.example.com/events (empty = check manually)")
+                        .desired_width(f32::INFINITY)
+                        .font(egui::FontId::monospace(12.0)),
+                );
+                if resp.changed() {
+                    draft.live_notify_url = (!url.is_empty()).then_some(url);
+                }
+            });
+
+            // Advanced: files sync must leave alone, e.g. customized configs.
+            tui.style(taffy::Style {
+                flex_direction: taffy::FlexDirection::Column,
+                gap: length(4.0),
+                ..Default::default()
+            })
+            .add(|tui| {
+                tui.ui(|ui| crate::utils::section_label(ui, "ADVANCED: EXCLUDED FILES"));
+                let mut patterns = draft.excluded_paths.join("\n");
+                let resp = tui.ui_add(
+                    egui::TextEdit::multiline(&mut patterns)
+                        .hint_text("One glob per line, e.g. @mod/userconfig/**")
+                        .desired_rows(2)
+                        .desired_width(f32::INFINITY)
+                        .font(egui::FontId::monospace(12.0)),
+                );
+                if resp.changed() {
+                    // Keep blank lines while editing; they are dropped when syncing.
+                    draft.excluded_paths = patterns.split('\n').map(str::to_string).collect();
+                }
+            });
+
+            // Advanced: explicit mod load order, reordered by dragging.
+            tui.style(taffy::Style {
+                flex_direction: taffy::FlexDirection::Column,
+                gap: length(4.0),
+                ..Default::default()
+            })
+            .add(|tui| {
+                tui.ui(|ui| crate::utils::section_label(ui, "ADVANCED: MOD LOAD ORDER"));
+                if draft.mod_order.is_empty() {
+                    tui.label(
+                        egui::RichText::new("Default order (repo.json, then alphabetical)")
+                            .size(10.0)
+                            .color(crate::theme::COL_TEXT_DIM),
+                    );
+                } else {
+                    tui.ui(|ui| draw_mod_order(ui, &mut draft.mod_order));
+                }
+                tui.style(taffy::Style {
+                    flex_direction: taffy::FlexDirection::Row,
+                    gap: length(8.0),
+                    ..Default::default()
+                })
+                .add(|tui| {
+                    if tui
+                        .ui(|ui| cmd_button(ui, "LOAD FROM FOLDER", "outline", true))
+                        .clicked()
+                    {
+                        let mut order = draft.mod_order.clone();
+                        for name in default_mod_order(draft.local_path.trim()) {
+                            if !order.iter().any(|o| o.eq_ignore_ascii_case(&name)) {
+                                order.push(name);
+                            }
+                        }
+                        draft.mod_order = order;
+                    }
+                    if tui
+                        .ui(|ui| cmd_button(ui, "RESET", "outline", !draft.mod_order.is_empty()))
+                        .clicked()
+                    {
+                        draft.mod_order.clear();
+                    }
+                });
+            });
+        }
+
+        // Join server password, kept in the OS keyring rather than profiles.json.
+        if !vm.is_new {
+            let profile_id = vm.draft.id.clone();
+            let has_password = app.has_server_password(profile_id.clone());
+            tui.style(taffy::Style {
+                flex_direction: taffy::FlexDirection::Column,
+                gap: length(4.0),
+                ..Default::default()
+            })
+            .add(|tui| {
+                tui.ui(|ui| crate::utils::section_label(ui, "ADVANCED: SERVER PASSWORD"));
+                let input_id = egui::Id::new(("server_password", profile_id.as_str()));
+                let mut password = tui
+                    .egui_ctx()
+                    .data_mut(|d| d.get_temp_mut_or_default::<String>(input_id).clone());
+                tui.ui_add(
+                    egui::TextEdit::singleline(&mut password)
+                        .password(true)
+                        .hint_text(if has_password {
+                            "Saved in keyring"
+                        } else {
+                            "Uses the repo.json password"
+                        })
+                        .desired_width(f32::INFINITY),
+                );
+                let mut save = false;
+                let mut clear = false;
+                tui.style(taffy::Style {
+                    flex_direction: taffy::FlexDirection::Row,
+                    gap: length(8.0),
+                    ..Default::default()
+                })
+                .add(|tui| {
+                    save = tui
+                        .ui(|ui| cmd_button(ui, "SAVE TO KEYRING", "outline", !password.is_empty()))
+                        .clicked();
+                    clear = tui
+                        .ui(|ui| cmd_button(ui, "FORGET", "outline", has_password))
+                        .clicked();
+                });
+                let result = if save {
+                    app.set_server_password(profile_id.clone(), &password)
+                        .map(|_| password.clear())
+                } else if clear {
+                    app.clear_server_password(profile_id.clone())
+                } else {
+                    Ok(())
+                };
+                if let Err(e) = result {
+                    tracing::error!("Failed to update server password: {e}");
+                }
+                tui.egui_ctx()
+                    .data_mut(|d| d.insert_temp(input_id, password));
+            });
+        }
+
+        // Repository login for private repos; the password or token stays in the OS keyring.
+        {
+            let profile_id = vm.draft.id.clone();
+            let saved = vm.draft.repo_auth.clone();
+            tui.style(taffy::Style {
+                flex_direction: taffy::FlexDirection::Column,
+                gap: length(4.0),
+                ..Default::default()
+            })
+            .add(|tui| {
+                tui.ui(|ui| crate::utils::section_label(ui, "ADVANCED: REPOSITORY LOGIN"));
+                let status = match &saved {
+                    None => "None (public repository)".to_string(),
+                    Some(RepoAuthKind::Basic { username }) => {
+                        format!("Username {username}, password in keyring")
+                    }
+                    Some(RepoAuthKind::Bearer) => "Bearer token in keyring".to_string(),
+                };
+                tui.label(
+                    egui::RichText::new(status)
+                        .size(10.0)
+                        .color(crate::theme::COL_TEXT_DIM),
+                );
+                let input_id = egui::Id::new(("repo_login", profile_id.as_str()));
+                let (mut username, mut secret) = tui.egui_ctx().data_mut(|d| {
+                    d.get_temp_mut_or_default::<(String, String)>(input_id)
+                        .clone()
+                });
+                tui.ui_add(
+                    egui::TextEdit::singleline(&mut username)
+                        .hint_text("Username (empty for a bearer token)")
+                        .desired_width(f32::INFINITY),
+                );
+                tui.ui_add(
+                    egui::TextEdit::singleline(&mut secret)
+                        .password(true)
+                        .hint_text("Password or token")
+                        .desired_width(f32::INFINITY),
+                );
+                let mut save = false;
+                let mut clear = false;
+                tui.style(taffy::Style {
+                    flex_direction: taffy::FlexDirection::Row,
+                    gap: length(8.0),
+                    ..Default::default()
+                })
+                .add(|tui| {
+                    let can_save = !secret.is_empty() && !profile_id.trim().is_empty();
+                    save = tui
+                        .ui(|ui| cmd_button(ui, "SAVE TO KEYRING", "outline", can_save))
+                        .clicked();
+                    clear = tui
+                        .ui(|ui| cmd_button(ui, "FORGET", "outline", saved.is_some()))
+                        .clicked();
+                });
+                let result = if save {
+                    app.set_repo_credentials(profile_id.clone(), &username, &secret)
+                        .map(|_| {
+                            username.clear();
+                            secret.clear();
+                        })
+                } else if clear {
+                    app.clear_repo_credentials(profile_id.clone())
+                } else {
+                    Ok(())
+                };
+                if let Err(e) = result {
+                    tracing::error!("Failed to update repository credentials: {e}");
+                }
+                tui.egui_ctx()
+                    .data_mut(|d| d.insert_temp(input_id, (username, secret)));
+            });
         }
 
         // Errors stacked beneath fields
@@ -158,3 +471,35 @@ pub fn draw<'a>(tui: impl TuiBuilderLogic<'a>, app: &mut FleetApplication) {
         });
     });
 }
+
+/// Draggable list of mod names; dropping a row onto another moves it to that position.
+fn draw_mod_order(ui: &mut egui::Ui, order: &mut Vec<String>) {
+    let mut moved: Option<(usize, usize)> = None;
+    ui.vertical(|ui| {
+        for (i, name) in order.iter().enumerate() {
+            let resp = ui
+                .dnd_drag_source(egui::Id::new(("mod_order", i)), i, |ui| {
+                    ui.label(
+                        egui::RichText::new(format!("{:>2}. {name}", i + 1))
+                            .font(egui::FontId::monospace(12.0)),
+                    );
+                })
+                .response;
+            if resp.dnd_hover_payload::<usize>().is_some() {
+                let rect = resp.rect;
+                ui.painter().hline(
+                    rect.x_range(),
+                    rect.top(),
+                    egui::Stroke::new(1.0, crate::theme::COL_ACCENT),
+                );
+            }
+            if let Some(from) = resp.dnd_release_payload::<usize>() {
+                moved = Some((*from, i));
+            }
+        }
+    });
+    if let Some((from, to)) = moved {
+        let name = order.remove(from);
+        order.insert(to, name);
+    }
+}
@@ -1,3 +1,4 @@
 pub mod dashboard;
 pub mod editor;
 pub mod settings;
+pub mod tree;
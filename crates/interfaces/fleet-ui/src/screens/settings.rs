@@ -3,7 +3,10 @@ use eframe::egui;
 use egui_taffy::taffy::prelude::{auto, length, percent};
 use egui_taffy::{taffy, TuiBuilderLogic};
 use fleet_app_core::{
-    domain::{FlatpakSteamAvailability, FLATPAK_STEAM_LAUNCH_TEMPLATE, STEAM_LAUNCH_TEMPLATE},
+    domain::{
+        DownloadOrder, FlatpakSteamAvailability, FLATPAK_STEAM_LAUNCH_TEMPLATE,
+        STEAM_LAUNCH_TEMPLATE,
+    },
     viewmodel::settings_vm,
     FleetApplication, Route,
 };
@@ -12,6 +15,7 @@ pub fn draw<'a>(tui: impl TuiBuilderLogic<'a>, app: &mut FleetApplication) {
     let vm = settings_vm(&app.state);
     let mut save_settings: Option<fleet_app_core::AppSettings> = None;
     let mut cancel_clicked = false;
+    let mut open_logs_clicked = false;
 
     tui.style(taffy::Style {
         flex_direction: taffy::FlexDirection::Column,
@@ -45,6 +49,11 @@ pub fn draw<'a>(tui: impl TuiBuilderLogic<'a>, app: &mut FleetApplication) {
             tui.ui_add(egui::DragValue::new(&mut settings.max_threads).range(1..=32));
         });
 
+        tui.ui_add(egui::Checkbox::new(
+            &mut settings.offline,
+            "Offline mode (skip the repository; check and launch from local state)",
+        ));
+
         tui.ui_add(egui::Checkbox::new(
             &mut settings.speed_limit_enabled,
             "Enable Speed Limit",
@@ -63,6 +72,253 @@ pub fn draw<'a>(tui: impl TuiBuilderLogic<'a>, app: &mut FleetApplication) {
             });
         }
 
+        tui.label("Speed schedule (local time; outside these windows the limit above applies):");
+        let mut remove_window: Option<usize> = None;
+        for (i, window) in settings.bandwidth_schedule.iter_mut().enumerate() {
+            tui.style(taffy::Style {
+                flex_direction: taffy::FlexDirection::Row,
+                gap: length(6.0),
+                align_items: Some(taffy::AlignItems::Center),
+                ..Default::default()
+            })
+            .add(|tui| {
+                tui.ui_add(
+                    egui::TextEdit::singleline(&mut window.start)
+                        .hint_text("08:00")
+                        .desired_width(50.0),
+                );
+                tui.label("to");
+                tui.ui_add(
+                    egui::TextEdit::singleline(&mut window.end)
+                        .hint_text("23:00")
+                        .desired_width(50.0),
+                );
+                tui.label("Bytes/s (0 = unlimited):");
+                tui.ui_add(egui::DragValue::new(&mut window.max_speed_bytes).speed(1024.0));
+                if tui
+                    .ui(|ui| cmd_button(ui, "REMOVE", "outline", true))
+                    .clicked()
+                {
+                    remove_window = Some(i);
+                }
+            });
+        }
+        if let Some(i) = remove_window {
+            settings.bandwidth_schedule.remove(i);
+        }
+        if tui
+            .ui(|ui| cmd_button(ui, "ADD WINDOW", "outline", true))
+            .clicked()
+        {
+            let max_speed_bytes = settings.max_speed_bytes;
+            settings
+                .bandwidth_schedule
+                .push(fleet_app_core::domain::BandwidthWindow {
+                    start: "08:00".into(),
+                    end: "23:00".into(),
+                    max_speed_bytes,
+                });
+        }
+
+        tui.style(taffy::Style {
+            flex_direction: taffy::FlexDirection::Row,
+            gap: length(6.0),
+            align_items: Some(taffy::AlignItems::Center),
+            ..Default::default()
+        })
+        .add(|tui| {
+            tui.label("Connect timeout (s):");
+            tui.ui_add(egui::DragValue::new(&mut settings.connect_timeout_secs).range(1..=300));
+            tui.label("Read timeout (s):");
+            tui.ui_add(egui::DragValue::new(&mut settings.read_timeout_secs).range(1..=600));
+        });
+
+        tui.style(taffy::Style {
+            flex_direction: taffy::FlexDirection::Row,
+            gap: length(6.0),
+            align_items: Some(taffy::AlignItems::Center),
+            ..Default::default()
+        })
+        .add(|tui| {
+            tui.label("Download stall timeout (s, 0 = off):");
+            tui.ui_add(egui::DragValue::new(&mut settings.stall_timeout_secs).range(0..=600));
+            tui.label("Connections per host (0 = no cap):");
+            tui.ui_add(egui::DragValue::new(&mut settings.max_connections_per_host).range(0..=32));
+            tui.label("Give up on a host after failures (0 = never):");
+            tui.ui_add(egui::DragValue::new(&mut settings.max_host_failures).range(0..=100));
+            tui.label("Download order:");
+            tui.ui(|ui| {
+                let label = |order| match order {
+                    DownloadOrder::AsPlanned => "As planned",
+                    DownloadOrder::SmallestFirst => "Smallest first",
+                    DownloadOrder::LargestFirst => "Largest first",
+                };
+                egui::ComboBox::from_id_salt("download_order")
+                    .selected_text(label(settings.download_order))
+                    .show_ui(ui, |ui| {
+                        for order in [
+                            DownloadOrder::AsPlanned,
+                            DownloadOrder::SmallestFirst,
+                            DownloadOrder::LargestFirst,
+                        ] {
+                            ui.selectable_value(&mut settings.download_order, order, label(order));
+                        }
+                    });
+            });
+        });
+
+        tui.style(taffy::Style {
+            flex_direction: taffy::FlexDirection::Row,
+            gap: length(6.0),
+            align_items: Some(taffy::AlignItems::Center),
+            ..Default::default()
+        })
+        .add(|tui| {
+            tui.label("Segments per large file (0 = one stream):");
+            tui.ui_add(egui::DragValue::new(&mut settings.download_segments).range(0..=16));
+            tui.label("from MB:");
+            tui.ui_add(egui::DragValue::new(&mut settings.segment_min_size_mb).range(1..=65536));
+        });
+
+        tui.label("Proxy:");
+        tui.ui_add(
+            egui::TextEdit::singleline(&mut settings.proxy).hint_text("http://proxy:8080"),
+        );
+
+        tui.label("Auth token:");
+        tui.ui_add(egui::TextEdit::singleline(&mut settings.auth_token).password(true));
+
+        tui.label("User agent (blank = fleet/<version>):");
+        tui.ui_add(egui::TextEdit::singleline(&mut settings.user_agent));
+
+        tui.ui(|ui| section_label(ui, "SYNC"));
+
+        tui.ui_add(egui::Checkbox::new(
+            &mut settings.preserve_unmanaged,
+            "Keep extra files (never delete content not in the repository)",
+        ));
+
+        tui.style(taffy::Style {
+            flex_direction: taffy::FlexDirection::Row,
+            gap: length(6.0),
+            align_items: Some(taffy::AlignItems::Center),
+            ..Default::default()
+        })
+        .add(|tui| {
+            tui.label("Scan threads:");
+            let sequential = settings.sequential_scan;
+            tui.ui(|ui| {
+                ui.add_enabled(
+                    !sequential,
+                    egui::DragValue::new(&mut settings.scan_threads).range(0..=64),
+                )
+            });
+            tui.label("(0 = all cores but one)");
+        });
+
+        tui.ui_add(egui::Checkbox::new(
+            &mut settings.sequential_scan,
+            "Sequential scan (one file at a time in path order; faster on HDDs and network drives)",
+        ));
+
+        tui.style(taffy::Style {
+            flex_direction: taffy::FlexDirection::Row,
+            gap: length(6.0),
+            align_items: Some(taffy::AlignItems::Center),
+            ..Default::default()
+        })
+        .add(|tui| {
+            tui.label("Confirm cancel above (MB):");
+            tui.ui_add(egui::DragValue::new(&mut settings.cancel_confirm_mb).speed(64.0));
+            tui.label("(0 = cancel instantly)");
+        });
+
+        tui.label("Download staging folder:");
+        tui.ui_add(
+            egui::TextEdit::singleline(&mut settings.download_temp_dir)
+                .hint_text("empty = next to each file"),
+        );
+
+        tui.label("Copy matching files from another install first:");
+        tui.ui_add(
+            egui::TextEdit::singleline(&mut settings.local_source_dir)
+                .hint_text("empty = always download"),
+        );
+
+        tui.ui_add(egui::Checkbox::new(
+            &mut settings.verify_downloads,
+            "Checksum files as they download (turn off only for a trusted LAN mirror)",
+        ));
+
+        tui.ui_add(egui::Checkbox::new(
+            &mut settings.verify_baseline,
+            "Read the local database back after each sync to catch failed writes",
+        ));
+
+        tui.ui_add(egui::Checkbox::new(
+            &mut settings.preserve_mtimes,
+            "Keep the repository's file modification times when it publishes them",
+        ));
+
+        tui.ui_add(egui::Checkbox::new(
+            &mut settings.skip_unreachable_mods,
+            "Leave mods the server fails to provide as they are instead of failing the check",
+        ));
+
+        tui.style(taffy::Style {
+            flex_direction: taffy::FlexDirection::Row,
+            gap: length(6.0),
+            align_items: Some(taffy::AlignItems::Center),
+            ..Default::default()
+        })
+        .add(|tui| {
+            tui.label("Automatic recheck quiet period (s):");
+            tui.ui_add(egui::DragValue::new(&mut settings.recheck_quiet_period_secs).range(0..=3600));
+            tui.label("(manual checks run immediately)");
+        });
+
+        tui.ui_add(egui::Checkbox::new(
+            &mut settings.verify_after_sync,
+            "Re-hash downloaded files after each sync and warn about mismatches",
+        ));
+
+        tui.ui_add(egui::Checkbox::new(
+            &mut settings.auto_launch_after_sync,
+            "Launch the game automatically after a successful sync",
+        ));
+
+        tui.ui_add(egui::Checkbox::new(
+            &mut settings.warm_cache_on_start,
+            "Warm the scan cache in the background on start",
+        ));
+
+        tui.ui(|ui| section_label(ui, "LOGGING"));
+
+        tui.style(taffy::Style {
+            flex_direction: taffy::FlexDirection::Row,
+            gap: length(6.0),
+            align_items: Some(taffy::AlignItems::Center),
+            ..Default::default()
+        })
+        .add(|tui| {
+            tui.label("Log level:");
+            tui.ui(|ui| {
+                egui::ComboBox::from_id_salt("log_level")
+                    .selected_text(settings.log_level.clone())
+                    .show_ui(ui, |ui| {
+                        for level in ["error", "warn", "info", "debug", "trace"] {
+                            ui.selectable_value(&mut settings.log_level, level.to_string(), level);
+                        }
+                    });
+            });
+            if tui
+                .ui(|ui| cmd_button(ui, "OPEN LOG FOLDER", "outline", true))
+                .clicked()
+            {
+                open_logs_clicked = true;
+            }
+        });
+
         tui.ui(|ui| section_label(ui, "LAUNCHER"));
 
         tui.style(taffy::Style {
@@ -129,6 +385,47 @@ pub fn draw<'a>(tui: impl TuiBuilderLogic<'a>, app: &mut FleetApplication) {
         tui.label("Args:");
         tui.ui_add(egui::TextEdit::singleline(&mut settings.launch_params));
 
+        tui.label("Working directory:");
+        tui.ui_add(
+            egui::TextEdit::singleline(&mut settings.launch_working_dir)
+                .hint_text("derived from the launch template"),
+        );
+
+        tui.label("Environment:");
+        let mut remove_env: Option<usize> = None;
+        for (i, (key, value)) in settings.launch_env.iter_mut().enumerate() {
+            tui.style(taffy::Style {
+                flex_direction: taffy::FlexDirection::Row,
+                gap: length(6.0),
+                align_items: Some(taffy::AlignItems::Center),
+                ..Default::default()
+            })
+            .add(|tui| {
+                tui.ui_add(
+                    egui::TextEdit::singleline(key)
+                        .hint_text("NAME")
+                        .desired_width(160.0),
+                );
+                tui.label("=");
+                tui.ui_add(egui::TextEdit::singleline(value).hint_text("value"));
+                if tui
+                    .ui(|ui| cmd_button(ui, "REMOVE", "outline", true))
+                    .clicked()
+                {
+                    remove_env = Some(i);
+                }
+            });
+        }
+        if let Some(i) = remove_env {
+            settings.launch_env.remove(i);
+        }
+        if tui
+            .ui(|ui| cmd_button(ui, "ADD VARIABLE", "outline", true))
+            .clicked()
+        {
+            settings.launch_env.push((String::new(), String::new()));
+        }
+
         tui.style(taffy::Style {
             flex_direction: taffy::FlexDirection::Row,
             gap: length(8.0),
@@ -165,11 +462,19 @@ pub fn draw<'a>(tui: impl TuiBuilderLogic<'a>, app: &mut FleetApplication) {
             tui.ui(|ui| ui.colored_label(egui::Color32::LIGHT_RED, err));
         }
 
+        if open_logs_clicked {
+            if let Err(e) = app.open_log_folder() {
+                tracing::error!("Failed to open log folder: {e}");
+            }
+        }
+
         if cancel_clicked {
             app.state.settings_draft = None;
             app.navigate(Route::ProfileHub);
         } else if let Some(s) = save_settings.take() {
+            let log_level = s.log_level.clone();
             if app.update_settings(s).is_ok() {
+                crate::logging::set_level(&log_level);
                 app.state.settings_draft = None;
                 app.navigate(Route::ProfileHub);
             }
@@ -0,0 +1,114 @@
+use crate::theme::*;
+use crate::utils::{cmd_button, section_label};
+use eframe::egui;
+use egui_taffy::taffy::prelude::{length, percent};
+use egui_taffy::{taffy, TuiBuilderLogic};
+use fleet_app_core::viewmodel::FileSyncStatus;
+use fleet_app_core::{FleetApplication, Route};
+
+fn status_color(status: FileSyncStatus) -> egui::Color32 {
+    match status {
+        FileSyncStatus::Installed => COL_TEXT_DIM,
+        FileSyncStatus::Outdated => COL_WARN,
+        FileSyncStatus::Missing => COL_ACCENT,
+        FileSyncStatus::Extra => COL_DANGER,
+    }
+}
+
+/// "512 B", "12.3 KB", "4.5 MB"; files are often far below the dashboard's MB granularity.
+fn format_bytes(bytes: u64) -> String {
+    const KB: f64 = 1024.0;
+    let b = bytes as f64;
+    if b < KB {
+        format!("{bytes} B")
+    } else if b < KB * KB {
+        format!("{:.1} KB", b / KB)
+    } else if b < KB * KB * KB {
+        format!("{:.1} MB", b / KB / KB)
+    } else {
+        format!("{:.2} GB", b / KB / KB / KB)
+    }
+}
+
+pub fn draw<'a>(tui: impl TuiBuilderLogic<'a>, app: &mut FleetApplication) {
+    let Route::ProfileTree(profile_id) = app.state.route.clone() else {
+        return;
+    };
+    let tree = app
+        .state
+        .profile_tree
+        .as_ref()
+        .filter(|t| t.profile_id == profile_id)
+        .and_then(|t| t.mods.clone());
+
+    tui.style(taffy::Style {
+        flex_direction: taffy::FlexDirection::Column,
+        gap: length(8.0),
+        size: percent(1.),
+        overflow: taffy::Point {
+            x: taffy::Overflow::Hidden,
+            y: taffy::Overflow::Scroll,
+        },
+        ..Default::default()
+    })
+    .add(|tui| {
+        tui.style(taffy::Style {
+            flex_direction: taffy::FlexDirection::Row,
+            justify_content: Some(taffy::JustifyContent::SpaceBetween),
+            align_items: Some(taffy::AlignItems::Center),
+            ..Default::default()
+        })
+        .add(|tui| {
+            tui.ui(|ui| section_label(ui, "FILES"));
+            if tui
+                .ui(|ui| cmd_button(ui, "BACK", "outline", true))
+                .clicked()
+            {
+                app.navigate(Route::ProfileDashboard(profile_id.clone()));
+            }
+        });
+
+        let Some(tree) = tree else {
+            tui.colored_label(COL_ERROR, "The profile's database could not be read");
+            return;
+        };
+        if tree.is_empty() {
+            tui.colored_label(COL_TEXT_DIM, "Nothing synced yet");
+            return;
+        }
+
+        tui.ui(|ui| {
+            for node in tree.iter() {
+                let changed = node
+                    .files
+                    .iter()
+                    .filter(|f| f.status != FileSyncStatus::Installed)
+                    .count();
+                let mut title = format!("{} ({} files)", node.name, node.files.len());
+                if changed > 0 {
+                    title.push_str(&format!(", {changed} pending"));
+                }
+                egui::CollapsingHeader::new(egui::RichText::new(title).size(11.0).color(COL_TEXT))
+                    .id_salt(&node.name)
+                    .show(ui, |ui| {
+                        for file in &node.files {
+                            ui.horizontal(|ui| {
+                                ui.label(
+                                    egui::RichText::new(format!("{:<9}", file.status.label()))
+                                        .monospace()
+                                        .size(10.0)
+                                        .color(status_color(file.status)),
+                                );
+                                ui.label(egui::RichText::new(&file.path).monospace().size(10.0));
+                                ui.label(
+                                    egui::RichText::new(format_bytes(file.size))
+                                        .size(10.0)
+                                        .color(COL_TEXT_DIM),
+                                );
+                            });
+                        }
+                    });
+            }
+        });
+    });
+}